@@ -1139,6 +1139,20 @@ class ApiGateway {
     }
   }
 
+  /**
+   * Cancels queued and running queries of the SQL API request, it's called when
+   * the SQL API client cancels the query.
+   */
+  public async sqlApiCancel(context: RequestContext) {
+    const orchestratorApi = await this.getAdapterApi(context);
+    const cancelled = await orchestratorApi.cancelQueriesByRequestId(context.requestId);
+
+    this.log({
+      type: 'SQL API Query Cancelled',
+      cancelled,
+    }, context);
+  }
+
   public async cancelPreAggregationsFromQueue(
     { query, context, res }: { query: any, context: RequestContext, res: ResponseResultFn }
   ) {
@@ -2532,6 +2546,7 @@ class ApiGateway {
         ...(!context.appName ? undefined : { appName: context.appName }),
         ...(!context.protocol ? undefined : { protocol: context.protocol }),
         ...(!context.apiType ? undefined : { apiType: context.apiType }),
+        ...(!context.queryTag ? undefined : { queryTag: context.queryTag }),
       })
     });
   }
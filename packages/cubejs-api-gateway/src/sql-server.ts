@@ -3,6 +3,7 @@ import {
   registerInterface,
   shutdownInterface,
   execSql,
+  notify,
   SqlInterfaceInstance,
   Request as NativeRequest,
   LoadRequestMeta,
@@ -62,6 +63,15 @@ export class SQLServer {
     await execSql(this.sqlInterfaceInstance!, sqlQuery, stream, securityContext);
   }
 
+  /**
+   * Sends NOTIFY to SQL API sessions which are listening the channel, it's no-op until the server is initialized
+   */
+  public async notify(channel: string, payload: string): Promise<void> {
+    if (this.sqlInterfaceInstance) {
+      await notify(this.sqlInterfaceInstance, channel, payload);
+    }
+  }
+
   protected buildCheckSqlAuth(options: SQLServerOptions): CheckSQLAuthFn {
     return (options.checkSqlAuth && this.wrapCheckSqlAuthFn(options.checkSqlAuth))
       || this.createDefaultCheckSqlAuthFn(options);
@@ -283,7 +293,12 @@ export class SQLServer {
           }
         });
       },
-      canSwitchUserForSession: async ({ session, user }) => canSwitchUserForSession(session, user)
+      canSwitchUserForSession: async ({ session, user }) => canSwitchUserForSession(session, user),
+      sqlApiCancel: async ({ request, session }) => {
+        const context = await contextByRequest(request, session);
+
+        await this.apiGateway.sqlApiCancel(context);
+      },
     });
   }
 
@@ -23,6 +23,7 @@ interface RequestContext {
   appName?: string,
   protocol?: string,
   apiType?: string,
+  queryTag?: string,
 }
 
 /**
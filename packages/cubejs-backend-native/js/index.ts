@@ -19,6 +19,8 @@ export interface BaseMeta {
 export interface LoadRequestMeta extends BaseMeta {
   // Security Context switching
   changeUser?: string,
+  // Tag from cube.query_tag session variable or /* tag: ... */ comment
+  queryTag?: string,
 }
 
 export interface Request<Meta> {
@@ -68,6 +70,12 @@ export interface SqlApiLoadPayload {
   streaming: boolean,
 }
 
+export interface SqlApiCancelPayload {
+  request: Request<LoadRequestMeta>,
+  session: SessionContext,
+  queryKey: any,
+}
+
 export interface LogLoadEventPayload {
   request: Request<LoadRequestMeta>,
   session: SessionContext,
@@ -97,6 +105,7 @@ export type SQLInterfaceOptions = {
   logLoadEvent: (payload: LogLoadEventPayload) => unknown | Promise<unknown>,
   sqlGenerators: (paramsJson: string) => unknown | Promise<unknown>,
   canSwitchUserForSession: (payload: CanSwitchUserPayload) => unknown | Promise<unknown>,
+  sqlApiCancel?: (payload: SqlApiCancelPayload) => unknown | Promise<unknown>,
   // gateway options
   gatewayPort?: number,
 };
@@ -339,6 +348,7 @@ export const registerInterface = async (options: SQLInterfaceOptions): Promise<S
     sqlGenerators: wrapRawNativeFunctionWithChannelCallback(options.sqlGenerators),
     logLoadEvent: wrapRawNativeFunctionWithChannelCallback(options.logLoadEvent),
     canSwitchUserForSession: wrapRawNativeFunctionWithChannelCallback(options.canSwitchUserForSession),
+    sqlApiCancel: options.sqlApiCancel ? wrapRawNativeFunctionWithChannelCallback(options.sqlApiCancel) : undefined,
   });
 };
 
@@ -350,6 +360,12 @@ export const shutdownInterface = async (instance: SqlInterfaceInstance, shutdown
   await native.shutdownInterface(instance, shutdownMode);
 };
 
+export const notify = async (instance: SqlInterfaceInstance, channel: string, payload: string): Promise<void> => {
+  const native = loadNative();
+
+  await native.notify(instance, channel, payload);
+};
+
 export const execSql = async (instance: SqlInterfaceInstance, sqlQuery: string, stream: any, securityContext?: any): Promise<void> => {
   const native = loadNative();
 
@@ -63,6 +63,10 @@ impl AuthContext for NativeAuthContext {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn security_context(&self) -> Option<&serde_json::Value> {
+        self.security_context.as_ref()
+    }
 }
 
 #[async_trait]
@@ -2,6 +2,9 @@ use cubesql::compile::DatabaseProtocol;
 use cubesql::compile::{convert_sql_to_cube_query, get_df_batches};
 use cubesql::config::processing_loop::ShutdownMode;
 use cubesql::config::ConfigObj;
+use cubesql::sql::notification_service::{
+    Notification, NotificationService, NOTIFICATION_PAYLOAD_MAX_LENGTH,
+};
 use cubesql::sql::SessionManager;
 use cubesql::transport::TransportService;
 use futures::StreamExt;
@@ -70,6 +73,17 @@ fn register_interface<C: NodeConfiguration>(mut cx: FunctionContext) -> JsResult
     let transport_can_switch_user_for_session = options
         .get::<JsFunction, _, _>(&mut cx, "canSwitchUserForSession")?
         .root(&mut cx);
+    // Optional, older hosts don't provide it
+    let transport_sql_api_cancel = options.get_value(&mut cx, "sqlApiCancel")?;
+    let transport_sql_api_cancel = if transport_sql_api_cancel.is_a::<JsFunction, _>(&mut cx) {
+        Some(
+            transport_sql_api_cancel
+                .downcast_or_throw::<JsFunction, _>(&mut cx)?
+                .root(&mut cx),
+        )
+    } else {
+        None
+    };
 
     let pg_port_handle = options.get_value(&mut cx, "pgPort")?;
     let pg_port = if pg_port_handle.is_a::<JsNumber, _>(&mut cx) {
@@ -101,6 +115,7 @@ fn register_interface<C: NodeConfiguration>(mut cx: FunctionContext) -> JsResult
         transport_log_load_event,
         transport_sql_generator,
         transport_can_switch_user_for_session,
+        transport_sql_api_cancel,
     );
     let auth_service = NodeBridgeAuthService::new(cx.channel(), check_auth);
 
@@ -176,6 +191,45 @@ fn shutdown_interface(mut cx: FunctionContext) -> JsResult<JsPromise> {
     Ok(promise)
 }
 
+/// Sends NOTIFY to all SQL API sessions which are listening the channel, it's used to deliver
+/// events from Cube, for example when a pre-aggregation is built
+fn notify(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let interface = cx.argument::<JsBox<SQLInterface>>(0)?;
+    let channel_name = cx.argument::<JsString>(1)?.value(&mut cx);
+    let payload = cx.argument::<JsString>(2)?.value(&mut cx);
+
+    if payload.len() >= NOTIFICATION_PAYLOAD_MAX_LENGTH {
+        return cx.throw_range_error::<&str, Handle<JsPromise>>("Payload string too long");
+    }
+
+    let (deferred, promise) = cx.promise();
+    let channel = cx.channel();
+
+    let services = interface.services.clone();
+    let runtime = tokio_runtime_node(&mut cx)?;
+
+    runtime.spawn(async move {
+        let notifications = services
+            .injector()
+            .get_service_typed::<dyn NotificationService>()
+            .await;
+
+        // process_id is 0, because notification is not sent by any session
+        let result = notifications.notify(Notification {
+            process_id: 0,
+            channel: channel_name,
+            payload,
+        });
+
+        deferred.settle_with(&channel, move |mut cx| match result {
+            Ok(_) => Ok(cx.undefined()),
+            Err(err) => cx.throw_error(err.to_string()),
+        });
+    });
+
+    Ok(promise)
+}
+
 const CHUNK_DELIM: &str = "\n";
 
 async fn handle_sql_query(
@@ -509,6 +563,7 @@ pub fn register_module_exports<C: NodeConfiguration + 'static>(
     cx.export_function("registerInterface", register_interface::<C>)?;
     cx.export_function("shutdownInterface", shutdown_interface)?;
     cx.export_function("execSql", exec_sql)?;
+    cx.export_function("notify", notify)?;
     cx.export_function("isFallbackBuild", is_fallback_build)?;
     cx.export_function("__js_to_clrepr_to_js", debug_js_to_clrepr_to_js)?;
 
@@ -39,6 +39,7 @@ pub struct NodeBridgeTransport {
     log_load_event: Arc<Root<JsFunction>>,
     sql_generators: Arc<Root<JsFunction>>,
     can_switch_user_for_session: Arc<Root<JsFunction>>,
+    sql_api_cancel: Option<Arc<Root<JsFunction>>>,
 }
 
 impl NodeBridgeTransport {
@@ -50,6 +51,7 @@ impl NodeBridgeTransport {
         log_load_event: Root<JsFunction>,
         sql_generators: Root<JsFunction>,
         can_switch_user_for_session: Root<JsFunction>,
+        sql_api_cancel: Option<Root<JsFunction>>,
     ) -> Self {
         Self {
             channel: Arc::new(channel),
@@ -59,6 +61,7 @@ impl NodeBridgeTransport {
             log_load_event: Arc::new(log_load_event),
             sql_generators: Arc::new(sql_generators),
             can_switch_user_for_session: Arc::new(can_switch_user_for_session),
+            sql_api_cancel: sql_api_cancel.map(Arc::new),
         }
     }
 }
@@ -93,6 +96,14 @@ struct LoadRequest {
     query_key: Option<serde_json::Value>,
 }
 
+#[derive(Debug, Serialize)]
+struct CancelRequest {
+    request: TransportRequest,
+    session: SessionContext,
+    #[serde(rename = "queryKey", skip_serializing_if = "Option::is_none")]
+    query_key: Option<serde_json::Value>,
+}
+
 #[derive(Debug, Serialize)]
 struct LogEvent {
     request: TransportRequest,
@@ -548,6 +559,47 @@ impl TransportService for NodeBridgeTransport {
         }
     }
 
+    async fn cancel(
+        &self,
+        span_id: Arc<SpanId>,
+        ctx: AuthContextRef,
+        meta_fields: LoadRequestMeta,
+    ) -> Result<(), CubeError> {
+        let Some(sql_api_cancel) = &self.sql_api_cancel else {
+            trace!("[transport] Cancel is not supported by the host, skipping");
+            return Ok(());
+        };
+
+        let native_auth = ctx
+            .as_any()
+            .downcast_ref::<NativeAuthContext>()
+            .expect("Unable to cast AuthContext to NativeAuthContext");
+
+        // Load requests use "{span_id}-span-{seq}" ids, API side cancels all of them by the prefix
+        call_raw_js_with_channel_as_callback(
+            self.channel.clone(),
+            sql_api_cancel.clone(),
+            CancelRequest {
+                request: TransportRequest {
+                    id: format!("{}-span", span_id.span_id),
+                    meta: Some(meta_fields),
+                },
+                session: SessionContext {
+                    user: native_auth.user.clone(),
+                    superuser: native_auth.superuser,
+                    security_context: native_auth.security_context.clone(),
+                },
+                query_key: Some(span_id.query_key.clone()),
+            },
+            Box::new(|cx, v| match NodeObjSerializer::serialize(&v, cx) {
+                Ok(res) => Ok(res),
+                Err(e) => cx.throw_error(format!("Can't serialize to node obj: {}", e)),
+            }),
+            Box::new(move |_, _| Ok(())),
+        )
+        .await
+    }
+
     async fn can_switch_user_for_session(
         &self,
         ctx: AuthContextRef,
@@ -1,5 +1,11 @@
 import { CancelablePromise, MaybeCancelablePromise } from '@cubejs-backend/shared';
 
+export type CacheChannelMessage = {
+  // Pass the cursor of the last received message to the next subscribe call to not lose messages
+  cursor: number;
+  message: string;
+};
+
 export interface CacheDriverInterface {
   get<R = any>(key: string): Promise<R | null>;
   set(key: string, value: unknown, expiration: number): Promise<{
@@ -16,4 +22,9 @@ export interface CacheDriverInterface {
     expiration: number,
     freeAfter: boolean
   ): CancelablePromise<boolean>;
+  // Pub/sub is shared between all Cube instances which use the same cache driver
+  publish(channel: string, message: string): Promise<void>;
+  // Returns messages published after the cursor, waits up to timeout ms for them.
+  // Without cursor only messages published after the call are returned.
+  subscribe(channel: string, timeout: number, after?: number): Promise<CacheChannelMessage[]>;
 }
@@ -95,6 +95,10 @@ export interface QueueDriverConnectionInterface {
   release(): void;
   //
   getQueriesToCancel(): Promise<QueryKeysTuple[]>
+  // Requests waiting for the query are shared between all API instances, returns id of the waiter
+  addWaitingRequestId(hash: QueryKeyHash, requestId: string): Promise<string>;
+  removeWaitingRequestId(hash: QueryKeyHash, waiterId: string): Promise<void>;
+  getWaitingRequestIds(hash: QueryKeyHash): Promise<string[]>;
   // @deprecated
   getActiveAndToProcess(): Promise<GetActiveAndToProcessResponse>;
 }
@@ -1,5 +1,5 @@
 import { createCancelablePromise, MaybeCancelablePromise } from '@cubejs-backend/shared';
-import { CacheDriverInterface } from '@cubejs-backend/base-driver';
+import { CacheChannelMessage, CacheDriverInterface } from '@cubejs-backend/base-driver';
 
 import { CubeStoreDriver } from './CubeStoreDriver';
 
@@ -93,6 +93,22 @@ export class CubeStoreCacheDriver implements CacheDriverInterface {
     return rows.map((row) => row.key);
   }
 
+  public async publish(channel: string, message: string): Promise<void> {
+    await (await this.getConnection()).query('CACHE PUBLISH ? ?', [channel, message]);
+  }
+
+  public async subscribe(channel: string, timeout: number, after?: number): Promise<CacheChannelMessage[]> {
+    const connection = await this.getConnection();
+    const rows = after !== undefined
+      ? await connection.query('CACHE SUBSCRIBE TIMEOUT ? AFTER ? ?', [timeout, after, channel])
+      : await connection.query('CACHE SUBSCRIBE TIMEOUT ? ?', [timeout, channel]);
+
+    return rows.map((row) => ({
+      cursor: parseInt(row.cursor, 10),
+      message: row.message,
+    }));
+  }
+
   public async cleanup(): Promise<void> {
     //
   }
@@ -329,6 +329,34 @@ class CubestoreQueueDriverConnection implements QueueDriverConnectionInterface {
       queueId || this.prefixKey(hash),
     ]);
   }
+
+  protected waitingRequestsPrefix(hash: QueryKeyHash): string {
+    return `${this.options.redisQueuePrefix}:WAITING:${hash}:`;
+  }
+
+  public async addWaitingRequestId(hash: QueryKeyHash, requestId: string): Promise<string> {
+    // Waiters of a crashed instance expire as orphaned queries do
+    const waiterId = `${this.waitingRequestsPrefix(hash)}${crypto.randomBytes(16).toString('hex')}:${requestId}`;
+    await this.driver.query('CACHE SET TTL ? ? ?', [this.options.orphanedTimeout, waiterId, '1']);
+
+    return waiterId;
+  }
+
+  public async removeWaitingRequestId(_hash: QueryKeyHash, waiterId: string): Promise<void> {
+    await this.driver.query('CACHE REMOVE ?', [
+      waiterId
+    ]);
+  }
+
+  public async getWaitingRequestIds(hash: QueryKeyHash): Promise<string[]> {
+    const prefix = this.waitingRequestsPrefix(hash);
+    const rows = await this.driver.query<{ key: string }>('CACHE KEYS ?', [
+      prefix
+    ]);
+
+    // Keys are prefix + 32 hex chars of the waiter + ':' + request id
+    return rows.map((row) => row.key.substring(prefix.length + 33));
+  }
 }
 
 export class CubeStoreQueueDriver implements QueueDriverInterface {
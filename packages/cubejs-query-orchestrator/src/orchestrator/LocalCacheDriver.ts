@@ -1,13 +1,26 @@
+import { EventEmitter } from 'events';
 import { createCancelablePromise, MaybeCancelablePromise } from '@cubejs-backend/shared';
-import { CacheDriverInterface } from '@cubejs-backend/base-driver';
+import { CacheChannelMessage, CacheDriverInterface } from '@cubejs-backend/base-driver';
 
 interface ItemBucket {
   value: any,
   exp: number,
 }
 
+interface ChannelMessageBucket extends CacheChannelMessage {
+  exp: number,
+}
+
 const store: Record<string, ItemBucket> = {};
 
+// Published messages are kept for subscribers which poll with a cursor
+const CHANNEL_MESSAGE_EXPIRATION = 60 * 1000;
+
+const channels: Record<string, ChannelMessageBucket[]> = {};
+const channelEvents = new EventEmitter();
+channelEvents.setMaxListeners(0);
+let lastMessageCursor = 0;
+
 export class LocalCacheDriver implements CacheDriverInterface {
   protected readonly store: Record<string, any>;
 
@@ -43,6 +56,53 @@ export class LocalCacheDriver implements CacheDriverInterface {
       .filter(k => k.indexOf(prefix) === 0 && this.store[k].exp > new Date().getTime());
   }
 
+  public async publish(channel: string, message: string): Promise<void> {
+    const now = new Date().getTime();
+
+    lastMessageCursor += 1;
+    channels[channel] = (channels[channel] || []).filter(m => m.exp > now);
+    channels[channel].push({
+      cursor: lastMessageCursor,
+      message,
+      exp: now + CHANNEL_MESSAGE_EXPIRATION,
+    });
+
+    channelEvents.emit(channel);
+  }
+
+  public async subscribe(channel: string, timeout: number, after?: number): Promise<CacheChannelMessage[]> {
+    const since = after !== undefined ? after : lastMessageCursor;
+    const messagesAfter = () => (channels[channel] || [])
+      .filter(m => m.cursor > since && m.exp > new Date().getTime())
+      .map(({ cursor, message }) => ({ cursor, message }));
+
+    const messages = messagesAfter();
+    if (messages.length) {
+      return messages;
+    }
+
+    await new Promise<void>((resolve) => {
+      let timer: NodeJS.Timeout | null = null;
+      const onMessage = () => {
+        if (timer) {
+          clearTimeout(timer);
+        }
+
+        resolve();
+      };
+      timer = setTimeout(() => {
+        channelEvents.removeListener(channel, onMessage);
+        resolve();
+      }, timeout);
+      // Waiting subscriber doesn't keep the process alive
+      timer.unref();
+
+      channelEvents.once(channel, onMessage);
+    });
+
+    return messagesAfter();
+  }
+
   public async cleanup(): Promise<void> {
     // Nothing to do
   }
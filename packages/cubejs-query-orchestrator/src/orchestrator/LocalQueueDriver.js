@@ -1,3 +1,4 @@
+import crypto from 'crypto';
 import R from 'ramda';
 import { QueueDriverInterface, QueueDriverConnectionInterface } from '@cubejs-backend/base-driver';
 import { BaseQueueDriver } from './BaseQueueDriver';
@@ -21,6 +22,7 @@ export class LocalQueueDriverConnection {
     this.heartBeat = driver.heartBeat;
     this.processingCounter = driver.processingCounter;
     this.processingLocks = driver.processingLocks;
+    this.waitingRequests = driver.waitingRequests;
     this.getQueueEventsBus = options.getQueueEventsBus;
   }
 
@@ -339,6 +341,44 @@ export class LocalQueueDriverConnection {
   release() {
   }
 
+  /**
+   * @param {QueryKeyHash} queryKeyHash
+   * @param {string} requestId
+   * @returns {Promise<string>} waiter id
+   */
+  async addWaitingRequestId(queryKeyHash, requestId) {
+    const waiters = this.waitingRequests[queryKeyHash] || {};
+    const waiterId = crypto.randomBytes(16).toString('hex');
+    waiters[waiterId] = requestId;
+    this.waitingRequests[queryKeyHash] = waiters;
+
+    return waiterId;
+  }
+
+  /**
+   * @param {QueryKeyHash} queryKeyHash
+   * @param {string} waiterId
+   */
+  async removeWaitingRequestId(queryKeyHash, waiterId) {
+    const waiters = this.waitingRequests[queryKeyHash];
+    if (!waiters) {
+      return;
+    }
+
+    delete waiters[waiterId];
+    if (!Object.keys(waiters).length) {
+      delete this.waitingRequests[queryKeyHash];
+    }
+  }
+
+  /**
+   * @param {QueryKeyHash} queryKeyHash
+   * @returns {Promise<string[]>}
+   */
+  async getWaitingRequestIds(queryKeyHash) {
+    return Object.values(this.waitingRequests[queryKeyHash] || {});
+  }
+
   /**
    * Returns cache key to the specified by the queryKey query and the
    * specified by the suffix query state.
@@ -378,6 +418,7 @@ const active = {};
 const heartBeat = {};
 const processingCounters = {};
 const processingLocks = {};
+const waitingRequests = {};
 
 /**
  * @implements {QueueDriverInterface}
@@ -395,6 +436,7 @@ export class LocalQueueDriver extends BaseQueueDriver {
     heartBeat[options.redisQueuePrefix] = heartBeat[options.redisQueuePrefix] || {};
     processingCounters[options.redisQueuePrefix] = processingCounters[options.redisQueuePrefix] || {};
     processingLocks[options.redisQueuePrefix] = processingLocks[options.redisQueuePrefix] || {};
+    waitingRequests[options.redisQueuePrefix] = waitingRequests[options.redisQueuePrefix] || {};
     this.results = results[options.redisQueuePrefix];
     this.resultPromises = resultPromises[options.redisQueuePrefix];
     this.queryDef = queryDef[options.redisQueuePrefix];
@@ -404,6 +446,7 @@ export class LocalQueueDriver extends BaseQueueDriver {
     this.heartBeat = heartBeat[options.redisQueuePrefix];
     this.processingCounter = processingCounters[options.redisQueuePrefix];
     this.processingLocks = processingLocks[options.redisQueuePrefix];
+    this.waitingRequests = waitingRequests[options.redisQueuePrefix];
   }
 
   createConnection() {
@@ -8,7 +8,7 @@ import {
   FROM_PARTITION_RANGE,
   getEnv,
   inDbTimeZone,
-  MAX_SOURCE_ROW_LIMIT, MaybeCancelablePromise, reformatInIsoLocal,
+  MAX_SOURCE_ROW_LIMIT, MaybeCancelablePromise, pausePromise, reformatInIsoLocal,
   timeSeries,
   TO_PARTITION_RANGE,
   utcToLocalTimeZone,
@@ -914,12 +914,16 @@ export class PreAggregationLoader {
       }
     }
     return cancelCombinator(
-      saveCancelFn => refreshStrategy.bind(this)(
-        client,
-        newVersionEntry,
-        saveCancelFn,
-        invalidationKeys
-      )
+      async saveCancelFn => {
+        const result = await refreshStrategy.bind(this)(
+          client,
+          newVersionEntry,
+          saveCancelFn,
+          invalidationKeys
+        );
+        await this.preAggregations.notifyPreAggregationBuilt(this.preAggregation, this.targetTableName(newVersionEntry));
+        return result;
+      }
     );
   }
 
@@ -1986,6 +1990,16 @@ type PreAggregationsOptions = {
   skipExternalCacheAndQueue?: boolean;
 };
 
+export type PreAggregationBuiltEvent = {
+  preAggregationId: string;
+  dataSource: string;
+  tableName: string;
+  external: boolean;
+};
+
+// How long a subscriber waits for build events in one request to the cache driver, ms
+const PRE_AGGREGATION_BUILT_SUBSCRIBE_TIMEOUT = 30 * 1000;
+
 type PreAggregationQueryBody = QueryBody & {
   preAggregationsLoadCacheByDataSource?: {
     [key: string]: PreAggregationLoadCache,
@@ -2015,6 +2029,8 @@ export class PreAggregations {
 
   private readonly touchCache: LRUCache<string, true>;
 
+  private readonly preAggregationBuiltSubscriptions: Set<() => void> = new Set();
+
   public constructor(
     private readonly redisPrefix: string,
     private readonly driverFactory: DriverFactoryByDataSource,
@@ -2049,6 +2065,10 @@ export class PreAggregations {
     return this.queryCache.getKey('SQL_PRE_AGGREGATIONS_TABLES_TOUCH', tableName);
   }
 
+  protected preAggregationBuiltChannel() {
+    return this.queryCache.getKey('SQL_PRE_AGGREGATIONS_BUILT', '');
+  }
+
   protected refreshEndReachedKey() {
     // TODO add dataSource?
     return this.queryCache.getKey('SQL_PRE_AGGREGATIONS_REFRESH_END_REACHED', '');
@@ -2353,6 +2373,81 @@ export class PreAggregations {
     };
   }
 
+  /**
+   * Publishes the build event to all Cube instances which share the cache driver,
+   * pre-aggregations are usually built by a refresh worker without the API.
+   */
+  public async notifyPreAggregationBuilt(preAggregation: PreAggregationDescription, tableName: string) {
+    const event: PreAggregationBuiltEvent = {
+      preAggregationId: preAggregation.preAggregationId,
+      dataSource: preAggregation.dataSource || 'default',
+      tableName,
+      external: !!preAggregation.external,
+    };
+
+    try {
+      await this.queryCache.getCacheDriver().publish(this.preAggregationBuiltChannel(), JSON.stringify(event));
+    } catch (e: any) {
+      this.logger('Pre-aggregation built notification failed', {
+        preAggregationId: preAggregation.preAggregationId,
+        error: e.stack || e.toString(),
+      });
+    }
+  }
+
+  /**
+   * Calls `callback` for every pre-aggregation built by any Cube instance which shares the cache driver.
+   * Returns a function which stops the subscription.
+   */
+  public onPreAggregationBuilt(callback: (event: PreAggregationBuiltEvent) => Promise<void> | void): () => void {
+    let active = true;
+    const unsubscribe = () => {
+      active = false;
+      this.preAggregationBuiltSubscriptions.delete(unsubscribe);
+    };
+    this.preAggregationBuiltSubscriptions.add(unsubscribe);
+
+    const poll = async () => {
+      // Messages are kept by the cache driver for a while, reading them from the start doesn't
+      // lose events published between polls, builds finished right before the subscription are delivered too
+      let cursor = 0;
+
+      while (active) {
+        try {
+          const messages = await this.queryCache.getCacheDriver().subscribe(
+            this.preAggregationBuiltChannel(),
+            PRE_AGGREGATION_BUILT_SUBSCRIBE_TIMEOUT,
+            cursor
+          );
+
+          for (const { cursor: messageCursor, message } of messages) {
+            cursor = messageCursor;
+
+            if (active) {
+              await callback(JSON.parse(message));
+            }
+          }
+        } catch (e: any) {
+          if (active) {
+            this.logger('Pre-aggregation built subscription failed', {
+              error: e.stack || e.toString(),
+            });
+            await pausePromise(1000);
+          }
+        }
+      }
+    };
+    poll();
+
+    return unsubscribe;
+  }
+
+  public cleanup() {
+    for (const unsubscribe of this.preAggregationBuiltSubscriptions) {
+      unsubscribe();
+    }
+  }
+
   public async getQueue(dataSource: string = 'default') {
     if (!this.queue[dataSource]) {
       const queueOptions = await this.options.queueOptions(dataSource);
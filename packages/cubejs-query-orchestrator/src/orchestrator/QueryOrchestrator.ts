@@ -367,6 +367,8 @@ export class QueryOrchestrator {
   }
 
   public async cleanup() {
+    this.preAggregations.cleanup();
+
     return this.queryCache.cleanup();
   }
 
@@ -448,6 +450,18 @@ export class QueryOrchestrator {
     return this.preAggregations.cancelQueriesFromQueue(queryKeys, dataSource);
   }
 
+  /**
+   * Cancels queries of all data sources which were added by the request.
+   */
+  public async cancelQueriesByRequestId(requestIdPrefix: string): Promise<number> {
+    const cancelled = await Promise.all(
+      Object.values(this.queryCache.getQueues())
+        .map(queue => queue.cancelQueriesByRequestId(requestIdPrefix))
+    );
+
+    return cancelled.reduce((total, count) => total + count, 0);
+  }
+
   public async subscribeQueueEvents(id, callback) {
     return this.getQueueEventsBus().subscribe(id, callback);
   }
@@ -225,9 +225,17 @@ export class QueryQueue {
       return this.parseResult(result);
     }
 
+    const queryKeyHash = this.redisHash(queryKey);
     const queueConnection = await this.queueDriver.createConnection();
     let waitingContext;
+    let waiterId;
     try {
+      // Queue entries are deduplicated by query key, so several requests, possibly of
+      // other API instances, can wait for the same entry.
+      if (options.requestId) {
+        waiterId = await queueConnection.addWaitingRequestId(queryKeyHash, options.requestId);
+      }
+
       if (priority == null) {
         priority = 0;
       }
@@ -244,8 +252,6 @@ export class QueryQueue {
         return this.parseResult(result);
       }
 
-      const queryKeyHash = this.redisHash(queryKey);
-
       if (query.forceBuild) {
         const jobExists = await queueConnection.getQueryDef(queryKeyHash, null);
         if (jobExists) return null;
@@ -370,6 +376,9 @@ export class QueryQueue {
       }
       throw error;
     } finally {
+      if (waiterId) {
+        await queueConnection.removeWaitingRequestId(queryKeyHash, waiterId);
+      }
       this.queueDriver.release(queueConnection);
     }
   }
@@ -526,6 +535,40 @@ export class QueryQueue {
     }
   }
 
+  /**
+   * Cancel queries which were added by the request. One SQL API query can issue
+   * multiple load requests with `${requestId}-span-${n}` ids, so ids starting with
+   * the request id followed by `-` match too. Queries which other requests are still
+   * waiting for are left in the queue.
+   *
+   * @param {string} requestIdPrefix
+   * @returns {Promise<number>} number of cancelled queries
+   */
+  async cancelQueriesByRequestId(requestIdPrefix) {
+    const isRequestMatched = (requestId) => requestId === requestIdPrefix ||
+      requestId.startsWith(`${requestIdPrefix}-`);
+
+    const { active, toProcess } = await this.getQueries();
+    const candidates = [...active, ...toProcess]
+      .filter(query => query.requestId && isRequestMatched(query.requestId));
+
+    const queueConnection = await this.queueDriver.createConnection();
+    let queries;
+    try {
+      const waitedByOtherRequest = await Promise.all(candidates.map(async (query) => {
+        const requestIds = await queueConnection.getWaitingRequestIds(query.queryKey);
+        return requestIds.some(requestId => !isRequestMatched(requestId));
+      }));
+      queries = candidates.filter((_, i) => !waitedByOtherRequest[i]);
+    } finally {
+      this.queueDriver.release(queueConnection);
+    }
+
+    await Promise.all(queries.map(query => this.cancelQuery(query.queryKey, null)));
+
+    return queries.length;
+  }
+
   /**
    * Reconciliation logic: cancel stalled and orphaned queries from the queue
    * and pick some planned to be processed queries to process.
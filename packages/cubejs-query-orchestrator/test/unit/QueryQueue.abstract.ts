@@ -129,6 +129,69 @@ export const QueryQueueTest = (name: string, options: QueryQueueTestOptions = {}
       expect(results).toStrictEqual(['20', '20']);
     });
 
+    test('cancel by request id', async () => {
+      const query = ['select * from cancel by request id'];
+      const result = queue
+        .executeInQueue('delay', query, { delay: 400, result: '3' }, 0, { requestId: 'request-1-span-1' })
+        .catch(e => e);
+      await pausePromise(100);
+
+      expect(await queue.cancelQueriesByRequestId('request-1')).toBe(1);
+      await result;
+    });
+
+    test('cancel by request id keeps queries waited by other requests', async () => {
+      const query = ['select * from cancel by request id shared'];
+      const results = Promise.all([
+        queue.executeInQueue('delay', query, { delay: 400, result: '4' }, 0, { requestId: 'request-2-span-1' }),
+        queue.executeInQueue('delay', query, { delay: 400, result: '4' }, 0, { requestId: 'request-3-span-1' }),
+      ]);
+      await pausePromise(100);
+
+      expect(await queue.cancelQueriesByRequestId('request-2')).toBe(0);
+      expect(await results).toStrictEqual(['40', '40']);
+    });
+
+    test('cancel by request id matches the whole id', async () => {
+      const query = ['select * from cancel by request id exact'];
+      const result = queue.executeInQueue('delay', query, { delay: 400, result: '5' }, 0, { requestId: 'request-10-span-1' });
+      await pausePromise(100);
+
+      expect(await queue.cancelQueriesByRequestId('request-1')).toBe(0);
+      expect(await result).toBe('50');
+    });
+
+    test('cancel by request id keeps queries waited by other API instances', async () => {
+      // Another instance shares the queue driver state, but not the process
+      const otherQueue = new QueryQueue(`${tenantPrefix}#test_query_queue`, {
+        queryHandlers: {
+          delay: async (query) => delayFn(query.result, query.delay),
+        },
+        sendProcessMessageFn: async (queryKeyHashed, queueId) => {
+          processMessagePromises.push(queue.processQuery.bind(queue)(queryKeyHashed, queueId));
+        },
+        sendCancelMessageFn: async (query) => {
+          processCancelPromises.push(queue.processCancel.bind(queue)(query));
+        },
+        continueWaitTimeout: 1,
+        executionTimeout: 2,
+        orphanedTimeout: 2,
+        concurrency: 1,
+        ...options,
+        logger,
+      });
+
+      const query = ['select * from cancel by request id other instance'];
+      const results = Promise.all([
+        queue.executeInQueue('delay', query, { delay: 400, result: '6' }, 0, { requestId: 'request-4-span-1' }),
+        otherQueue.executeInQueue('delay', query, { delay: 400, result: '6' }, 0, { requestId: 'request-5-span-1' }),
+      ]);
+      await pausePromise(100);
+
+      expect(await queue.cancelQueriesByRequestId('request-4')).toBe(0);
+      expect(await results).toStrictEqual(['60', '60']);
+    });
+
     test('priority', async () => {
       const result = await Promise.all([
         queue.executeInQueue('delay', '11', { delay: 600, result: '1' }, 1),
@@ -8,6 +8,7 @@ import {
   DriverType,
   QueryOrchestratorOptions,
   QueryBody,
+  PreAggregationBuiltEvent,
 } from '@cubejs-backend/query-orchestrator';
 
 import { DatabaseType, RequestContext } from './types';
@@ -297,6 +298,10 @@ export class OrchestratorApi {
     return this.orchestrator.cancelPreAggregationQueriesFromQueue(queryKeys, dataSource);
   }
 
+  public async cancelQueriesByRequestId(requestIdPrefix: string) {
+    return this.orchestrator.cancelQueriesByRequestId(requestIdPrefix);
+  }
+
   public async subscribeQueueEvents(id, callback) {
     return this.orchestrator.subscribeQueueEvents(id, callback);
   }
@@ -308,4 +313,12 @@ export class OrchestratorApi {
   public async updateRefreshEndReached() {
     return this.orchestrator.updateRefreshEndReached();
   }
+
+  /**
+   * Subscribes to pre-aggregations built by any Cube instance which shares the cache driver,
+   * returns a function which stops the subscription.
+   */
+  public onPreAggregationBuilt(callback: (event: PreAggregationBuiltEvent) => Promise<void> | void) {
+    return this.orchestrator.getPreAggregations().onPreAggregationBuilt(callback);
+  }
 }
@@ -26,7 +26,7 @@ import {
 
 import type { Application as ExpressApplication } from 'express';
 
-import { BaseDriver, DriverFactoryByDataSource } from '@cubejs-backend/query-orchestrator';
+import { BaseDriver, DriverFactoryByDataSource, PreAggregationBuiltEvent } from '@cubejs-backend/query-orchestrator';
 import { RefreshScheduler, ScheduledRefreshOptions } from './RefreshScheduler';
 import { OrchestratorApi, OrchestratorApiOptions } from './OrchestratorApi';
 import { CompilerApi } from './CompilerApi';
@@ -70,6 +70,8 @@ import {
 
 const { version } = require('../../../package.json');
 
+const PRE_AGGREGATIONS_NOTIFY_CHANNEL = 'cube_pre_aggregations';
+
 function wrapToFnIfNeeded<T, R>(possibleFn: T | ((a: R) => T)): (a: R) => T {
   if (typeof possibleFn === 'function') {
     return <any>possibleFn;
@@ -163,6 +165,9 @@ export class CubejsServerCore {
 
   protected apiGatewayInstance: ApiGateway | null = null;
 
+  // Pre-aggregation build events are relayed to SQL API sessions only by instances which run the SQL API
+  protected preAggregationsNotificationsEnabled: boolean = false;
+
   // eslint-disable-next-line @typescript-eslint/no-unused-vars
   public readonly event: (name: string, props?: object) => Promise<void>;
 
@@ -450,7 +455,61 @@ export class CubejsServerCore {
 
   public initSQLServer() {
     const apiGateway = this.apiGateway();
-    return apiGateway.getSQLServer();
+    const sqlServer = apiGateway.getSQLServer();
+
+    this.startPreAggregationsNotifications();
+
+    return sqlServer;
+  }
+
+  /**
+   * Pre-aggregations are usually built by a refresh worker without the SQL API, so build events
+   * are received over pub/sub of the cache driver by every orchestrator of this instance.
+   * Orchestrators of scheduled refresh contexts are created right away, others subscribe on creation.
+   */
+  protected startPreAggregationsNotifications() {
+    if (this.preAggregationsNotificationsEnabled) {
+      return;
+    }
+
+    this.preAggregationsNotificationsEnabled = true;
+
+    (async () => {
+      const contexts = await this.options.scheduledRefreshContexts();
+
+      await Promise.all(contexts.map((ctx) => {
+        const context = this.migrateBackgroundContext(ctx);
+
+        return this.getOrchestratorApi({
+          authInfo: null,
+          ...context,
+          securityContext: context?.securityContext || {},
+          requestId: 'pre-aggregations-notifications',
+        });
+      }));
+    })().catch((e) => {
+      this.logger('Pre-aggregation built subscription failed', {
+        error: e.stack || e.toString(),
+      });
+    });
+  }
+
+  /**
+   * Delivers pre-aggregation build events to SQL API sessions listening the `cube_pre_aggregations` channel
+   */
+  protected async notifyPreAggregationBuilt(event: PreAggregationBuiltEvent) {
+    if (!this.apiGatewayInstance) {
+      return;
+    }
+
+    try {
+      await this.apiGatewayInstance.getSQLServer()
+        .notify(PRE_AGGREGATIONS_NOTIFY_CHANNEL, JSON.stringify(event));
+    } catch (e: any) {
+      this.logger('Pre-aggregation built notification failed', {
+        error: e.stack || e.toString(),
+      });
+    }
   }
 
   protected apiGateway(): ApiGateway {
@@ -683,6 +742,10 @@ export class CubejsServerCore {
 
     this.orchestratorStorage.set(orchestratorId, orchestratorApi);
 
+    if (this.preAggregationsNotificationsEnabled) {
+      orchestratorApi.onPreAggregationBuilt((event) => this.notifyPreAggregationBuilt(event));
+    }
+
     return orchestratorApi;
   }
 
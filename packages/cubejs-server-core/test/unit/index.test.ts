@@ -1,6 +1,7 @@
 /* eslint-disable @typescript-eslint/no-empty-function */
 
-import { SchemaFileRepository, withTimeout } from '@cubejs-backend/shared';
+import { pausePromise, SchemaFileRepository, withTimeout } from '@cubejs-backend/shared';
+import type { ApiGateway } from '@cubejs-backend/api-gateway';
 
 import {
   CreateOptions,
@@ -913,4 +914,62 @@ describe('index.test', () => {
     await cubejsServerCore.beforeShutdown();
     await cubejsServerCore.shutdown();
   });
+
+  test('pre-aggregation built by refresh worker is delivered to SQL API sessions', async () => {
+    let delivered: (args: [string, string]) => void = () => {
+      //
+    };
+    const notification = new Promise<[string, string]>((resolve) => {
+      delivered = resolve;
+    });
+
+    // API instance runs the SQL API, NOTIFY is captured instead of sending it to sessions
+    class ApiServerCore extends CubejsServerCoreOpen {
+      protected createApiGatewayInstance(): ApiGateway {
+        return <any>{
+          getSQLServer: () => ({
+            notify: async (channel: string, payload: string) => delivered([channel, payload]),
+          }),
+          release: () => {
+            //
+          },
+        };
+      }
+    }
+
+    const options: CreateOptions = {
+      dbType: 'mysql',
+      apiSecret: 'secret',
+      cacheAndQueueDriver: 'memory',
+      scheduledRefreshTimer: false,
+    };
+    const apiServerCore = new ApiServerCore(options);
+    // Refresh worker doesn't run the SQL API, the memory driver is shared as Cube Store would be
+    const refreshWorkerCore = new CubejsServerCoreOpen(options);
+
+    apiServerCore.initSQLServer();
+    // Let the API instance subscribe for orchestrators of scheduled refresh contexts
+    await pausePromise(100);
+
+    const orchestratorApi = await refreshWorkerCore.getOrchestratorApi(<any>{
+      securityContext: {},
+      requestId: 'refresh-worker',
+    });
+    await orchestratorApi.getQueryOrchestrator().getPreAggregations().notifyPreAggregationBuilt(
+      <any>{ preAggregationId: 'Orders.main', dataSource: 'default', external: true },
+      'prod_pre_aggregations.orders_main20230101'
+    );
+
+    const [channel, payload] = await notification;
+    expect(channel).toEqual('cube_pre_aggregations');
+    expect(JSON.parse(payload)).toEqual({
+      preAggregationId: 'Orders.main',
+      dataSource: 'default',
+      tableName: 'prod_pre_aggregations.orders_main20230101',
+      external: true,
+    });
+
+    await apiServerCore.shutdown();
+    await refreshWorkerCore.shutdown();
+  });
 });
@@ -0,0 +1,736 @@
+use std::{
+    fmt,
+    io::{Read, Write},
+    time::{Duration, Instant},
+};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use log::{debug, warn};
+use reqwest::{header, Method, StatusCode};
+use serde::{de::DeserializeOwned, Serialize};
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use super::{configuration::Configuration, Error};
+use crate::apis::ResponseContent;
+
+/// struct for typed errors of [`CubeClient`] methods
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CubeClientV1Error {
+    Status4XX(crate::models::V1Error),
+    Status5XX(crate::models::V1Error),
+    UnknownValue(serde_json::Value),
+}
+
+#[derive(Debug)]
+pub enum CubeClientError {
+    /// Request was cancelled by the cancellation token of the client
+    Cancelled,
+    /// Query was still in progress (`Continue wait`) when the timeout elapsed
+    ContinueWaitTimeout {
+        request_id: String,
+    },
+    Api(Error<CubeClientV1Error>),
+}
+
+impl fmt::Display for CubeClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CubeClientError::Cancelled => write!(f, "request was cancelled"),
+            CubeClientError::ContinueWaitTimeout { request_id } => write!(
+                f,
+                "query was not completed in time (continue wait), requestId: {}",
+                request_id
+            ),
+            CubeClientError::Api(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for CubeClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CubeClientError::Api(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error<CubeClientV1Error>> for CubeClientError {
+    fn from(e: Error<CubeClientV1Error>) -> Self {
+        CubeClientError::Api(e)
+    }
+}
+
+impl From<serde_json::Error> for CubeClientError {
+    fn from(e: serde_json::Error) -> Self {
+        CubeClientError::Api(Error::from(e))
+    }
+}
+
+impl From<std::io::Error> for CubeClientError {
+    fn from(e: std::io::Error) -> Self {
+        CubeClientError::Api(Error::from(e))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Retries of transient errors: timeouts, connection errors, 502, 503 and 504 responses
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Overall time to wait for a query which responds with `Continue wait`, unlimited if `None`
+    pub continue_wait_timeout: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            continue_wait_timeout: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(1 << attempt.min(16))
+            .min(self.max_backoff)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CubeClientOptions {
+    pub retry: RetryPolicy,
+    /// Compress request bodies and ask for compressed responses
+    pub gzip: bool,
+}
+
+/// Client on top of [`Configuration`] which handles `Continue wait` polling and retries
+/// of transient errors, so callers receive only the final result.
+#[derive(Debug, Clone)]
+pub struct CubeClient {
+    configuration: Configuration,
+    options: CubeClientOptions,
+    cancellation: CancellationToken,
+}
+
+impl CubeClient {
+    pub fn new(configuration: Configuration, options: CubeClientOptions) -> Self {
+        Self {
+            configuration,
+            options,
+            cancellation: CancellationToken::new(),
+        }
+    }
+
+    /// In-flight and following requests fail with [`CubeClientError::Cancelled`] once `token` is cancelled.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    pub async fn load(
+        &self,
+        request: &crate::models::V1LoadRequest,
+    ) -> Result<crate::models::V1LoadResponse, CubeClientError> {
+        self.request(Method::POST, "/v1/load", &[], Some(request), true)
+            .await
+    }
+
+    pub async fn meta(
+        &self,
+        extended: bool,
+    ) -> Result<crate::models::V1MetaResponse, CubeClientError> {
+        self.request::<(), _>(
+            Method::GET,
+            "/v1/meta",
+            &[("extended", extended.to_string())],
+            None,
+            false,
+        )
+        .await
+    }
+
+    pub async fn sql(
+        &self,
+        request: &crate::models::V1LoadRequest,
+    ) -> Result<crate::models::V1SqlResponse, CubeClientError> {
+        self.request(Method::POST, "/v1/sql", &[], Some(request), false)
+            .await
+    }
+
+    pub async fn dry_run(
+        &self,
+        request: &crate::models::V1LoadRequest,
+    ) -> Result<crate::models::V1DryRunResponse, CubeClientError> {
+        self.request(Method::POST, "/v1/dry-run", &[], Some(request), false)
+            .await
+    }
+
+    /// Starts building of the selected pre-aggregations, returns tokens of the started jobs.
+    pub async fn post_pre_aggregations_jobs(
+        &self,
+        selector: crate::models::V1PreAggregationsSelector,
+    ) -> Result<Vec<String>, CubeClientError> {
+        let mut request = crate::models::V1PreAggregationsJobsRequest::new("post".to_string());
+        request.selector = Some(Box::new(selector));
+
+        self.request(
+            Method::POST,
+            "/v1/pre-aggregations/jobs",
+            &[],
+            Some(&request),
+            false,
+        )
+        .await
+    }
+
+    pub async fn get_pre_aggregations_jobs(
+        &self,
+        tokens: Vec<String>,
+    ) -> Result<Vec<crate::models::V1PreAggregationJobStatus>, CubeClientError> {
+        let mut request = crate::models::V1PreAggregationsJobsRequest::new("get".to_string());
+        request.tokens = Some(tokens);
+
+        self.request(
+            Method::POST,
+            "/v1/pre-aggregations/jobs",
+            &[],
+            Some(&request),
+            false,
+        )
+        .await
+    }
+
+    /// HTTP transport of subscriptions returns the current result of the query, the same as `load`.
+    pub async fn subscribe(
+        &self,
+        query: &crate::models::V1LoadRequestQuery,
+    ) -> Result<crate::models::V1LoadResponse, CubeClientError> {
+        let query = serde_json::to_string(query)?;
+
+        self.request::<(), _>(
+            Method::GET,
+            "/v1/subscribe",
+            &[("query", query)],
+            None,
+            true,
+        )
+        .await
+    }
+
+    async fn request<B: Serialize, R: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<&B>,
+        continue_wait: bool,
+    ) -> Result<R, CubeClientError> {
+        let retry = &self.options.retry;
+        let body = body.map(|body| self.encode_body(body)).transpose()?;
+
+        let request_id = Uuid::new_v4().to_string();
+        let mut span_counter: u32 = 1;
+        let mut retries: u32 = 0;
+        let mut continue_wait_attempts: u32 = 0;
+        let continue_wait_deadline = retry
+            .continue_wait_timeout
+            .map(|timeout| Instant::now() + timeout);
+
+        loop {
+            let mut req_builder = self.configuration.client.request(
+                method.clone(),
+                format!("{}{}", self.configuration.base_path, path),
+            );
+            if !query.is_empty() {
+                req_builder = req_builder.query(query);
+            }
+            if let Some(ref user_agent) = self.configuration.user_agent {
+                req_builder = req_builder.header(header::USER_AGENT, user_agent.clone());
+            }
+            if let Some(ref token) = self.configuration.bearer_access_token {
+                req_builder = req_builder.bearer_auth(token.to_owned());
+            }
+            if self.options.gzip {
+                req_builder = req_builder.header(header::ACCEPT_ENCODING, "gzip");
+            }
+            if let Some(ref body) = body {
+                req_builder = req_builder
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(body.clone());
+                if self.options.gzip {
+                    req_builder = req_builder.header(header::CONTENT_ENCODING, "gzip");
+                }
+            }
+            req_builder = req_builder.header(
+                "x-request-id",
+                format!("{}-span-{}", request_id, span_counter),
+            );
+            span_counter += 1;
+
+            let req = req_builder.build().map_err(Error::from)?;
+            let response = tokio::select! {
+                _ = self.cancellation.cancelled() => return Err(CubeClientError::Cancelled),
+                response = self.execute(req) => response,
+            };
+
+            let (status, content) = match response {
+                Ok(response) => response,
+                Err(Error::Reqwest(e))
+                    if (e.is_timeout() || e.is_connect()) && retries < retry.max_retries =>
+                {
+                    warn!(
+                        "[client] {} - retrying request ({}) requestId: {}",
+                        path, e, request_id
+                    );
+                    self.sleep(retry.backoff(retries)).await?;
+                    retries += 1;
+
+                    continue;
+                }
+                Err(e) => return Err(CubeClientError::Api(e)),
+            };
+
+            if matches!(
+                status,
+                StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            ) && retries < retry.max_retries
+            {
+                warn!(
+                    "[client] {} - retrying request ({}) requestId: {}",
+                    path, status, request_id
+                );
+                self.sleep(retry.backoff(retries)).await?;
+                retries += 1;
+
+                continue;
+            }
+
+            if status.is_client_error() || status.is_server_error() {
+                let entity: Option<CubeClientV1Error> = serde_json::from_str(&content).ok();
+
+                return Err(CubeClientError::Api(Error::ResponseError(
+                    ResponseContent {
+                        status,
+                        content,
+                        entity,
+                    },
+                )));
+            }
+
+            if continue_wait {
+                if let Ok(res) = serde_json::from_str::<crate::models::V1LoadContinueWait>(&content)
+                {
+                    if res.error.eq_ignore_ascii_case("continue wait") {
+                        let backoff = retry.backoff(continue_wait_attempts);
+                        if let Some(deadline) = continue_wait_deadline {
+                            if Instant::now() + backoff >= deadline {
+                                return Err(CubeClientError::ContinueWaitTimeout { request_id });
+                            }
+                        }
+
+                        debug!(
+                            "[client] {} - retrying request (continue wait) requestId: {}, span: {}",
+                            path, request_id, span_counter
+                        );
+                        self.sleep(backoff).await?;
+                        continue_wait_attempts += 1;
+
+                        continue;
+                    }
+                }
+            }
+
+            return serde_json::from_str(&content).map_err(CubeClientError::from);
+        }
+    }
+
+    async fn execute(
+        &self,
+        req: reqwest::Request,
+    ) -> Result<(StatusCode, String), Error<CubeClientV1Error>> {
+        let resp = self.configuration.client.execute(req).await?;
+
+        let status = resp.status();
+        let gzipped = resp
+            .headers()
+            .get(header::CONTENT_ENCODING)
+            .map(|encoding| encoding.as_bytes().eq_ignore_ascii_case(b"gzip"))
+            .unwrap_or(false);
+        let bytes = resp.bytes().await?;
+
+        let content = if gzipped {
+            let mut content = String::new();
+            GzDecoder::new(bytes.as_ref()).read_to_string(&mut content)?;
+            content
+        } else {
+            String::from_utf8_lossy(&bytes).into_owned()
+        };
+
+        Ok((status, content))
+    }
+
+    fn encode_body<B: Serialize>(&self, body: &B) -> Result<Vec<u8>, CubeClientError> {
+        let json = serde_json::to_vec(body)?;
+        if !self.options.gzip {
+            return Ok(json);
+        }
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&json)?;
+
+        Ok(encoder.finish()?)
+    }
+
+    async fn sleep(&self, duration: Duration) -> Result<(), CubeClientError> {
+        tokio::select! {
+            _ = self.cancellation.cancelled() => Err(CubeClientError::Cancelled),
+            _ = tokio::time::sleep(duration) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Client;
+    use reqwest_middleware::ClientBuilder;
+    use serde_json::json;
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+    use wiremock::{
+        matchers::{body_json, header, method, path, query_param},
+        Mock, MockServer, Respond, ResponseTemplate,
+    };
+
+    use super::*;
+
+    const LOAD_RESPONSE: &str = r#"{
+        "queryType": "regularQuery",
+        "results": [
+            {
+                "annotation": {
+                    "measures": {},
+                    "dimensions": {},
+                    "segments": {},
+                    "timeDimensions": {}
+                },
+                "data": []
+            }
+        ]
+    }"#;
+
+    /// Responds with `pending` until it was called `pending_count` times, then with `last`
+    struct SequenceResponder {
+        calls: Arc<AtomicU32>,
+        pending_count: u32,
+        pending: ResponseTemplate,
+        last: ResponseTemplate,
+    }
+
+    impl SequenceResponder {
+        fn new(pending_count: u32, pending: ResponseTemplate, last: ResponseTemplate) -> Self {
+            Self {
+                calls: Arc::new(AtomicU32::new(0)),
+                pending_count,
+                pending,
+                last,
+            }
+        }
+    }
+
+    impl Respond for SequenceResponder {
+        fn respond(&self, _request: &wiremock::Request) -> ResponseTemplate {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.pending_count {
+                self.pending.clone()
+            } else {
+                self.last.clone()
+            }
+        }
+    }
+
+    fn test_client(server: &MockServer, gzip: bool) -> CubeClient {
+        let reqwest_client = Client::builder().build().unwrap();
+        let client = ClientBuilder::new(reqwest_client).build();
+
+        let mut configuration = Configuration::new(client);
+        configuration.base_path = server.uri();
+
+        CubeClient::new(
+            configuration,
+            CubeClientOptions {
+                retry: RetryPolicy {
+                    max_retries: 2,
+                    initial_backoff: Duration::from_millis(10),
+                    max_backoff: Duration::from_millis(50),
+                    continue_wait_timeout: None,
+                },
+                gzip,
+            },
+        )
+    }
+
+    fn continue_wait() -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_string(r#"{"error":"Continue wait"}"#)
+    }
+
+    #[tokio::test]
+    async fn test_load_continue_wait() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/load"))
+            .respond_with(SequenceResponder::new(
+                3,
+                continue_wait(),
+                ResponseTemplate::new(200).set_body_string(LOAD_RESPONSE),
+            ))
+            .expect(4)
+            .mount(&server)
+            .await;
+
+        let response = test_client(&server, false)
+            .load(&crate::models::V1LoadRequest::new())
+            .await
+            .unwrap();
+        assert_eq!(response.results.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_load_continue_wait_timeout() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/load"))
+            .respond_with(continue_wait())
+            .mount(&server)
+            .await;
+
+        let mut client = test_client(&server, false);
+        client.options.retry.continue_wait_timeout = Some(Duration::from_millis(100));
+
+        match client.load(&crate::models::V1LoadRequest::new()).await {
+            Err(CubeClientError::ContinueWaitTimeout { .. }) => {}
+            other => panic!("must be a continue wait timeout, {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_unavailable() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/load"))
+            .respond_with(SequenceResponder::new(
+                2,
+                ResponseTemplate::new(503),
+                ResponseTemplate::new(200).set_body_string(LOAD_RESPONSE),
+            ))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        test_client(&server, false)
+            .load(&crate::models::V1LoadRequest::new())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_bad_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/load"))
+            .respond_with(
+                ResponseTemplate::new(400).set_body_string(r#"{"error":"Unknown member"}"#),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        match test_client(&server, false)
+            .load(&crate::models::V1LoadRequest::new())
+            .await
+        {
+            Err(CubeClientError::Api(Error::ResponseError(ResponseContent {
+                status,
+                entity: Some(CubeClientV1Error::Status4XX(error)),
+                ..
+            }))) => {
+                assert_eq!(status, StatusCode::BAD_REQUEST);
+                assert_eq!(error.error, "Unknown member");
+            }
+            other => panic!("must be a bad request error, {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancellation() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/load"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_delay(Duration::from_secs(10))
+                    .set_body_string(LOAD_RESPONSE),
+            )
+            .mount(&server)
+            .await;
+
+        let token = CancellationToken::new();
+        let client = test_client(&server, false).with_cancellation(token.clone());
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            token.cancel();
+        });
+
+        match client.load(&crate::models::V1LoadRequest::new()).await {
+            Err(CubeClientError::Cancelled) => {}
+            other => panic!("must be cancelled, {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gzip() {
+        struct GzipResponder;
+
+        impl Respond for GzipResponder {
+            fn respond(&self, request: &wiremock::Request) -> ResponseTemplate {
+                let mut body = String::new();
+                GzDecoder::new(request.body.as_slice())
+                    .read_to_string(&mut body)
+                    .unwrap();
+                let request: serde_json::Value = serde_json::from_str(&body).unwrap();
+                assert_eq!(request["query"]["measures"], json!(["Orders.count"]));
+
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(LOAD_RESPONSE.as_bytes()).unwrap();
+                ResponseTemplate::new(200)
+                    .insert_header("Content-Encoding", "gzip")
+                    .set_body_bytes(encoder.finish().unwrap())
+            }
+        }
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/load"))
+            .and(header("Accept-Encoding", "gzip"))
+            .and(header("Content-Encoding", "gzip"))
+            .respond_with(GzipResponder)
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut request = crate::models::V1LoadRequest::new();
+        request.query = Some(crate::models::V1LoadRequestQuery {
+            measures: Some(vec!["Orders.count".to_string()]),
+            ..Default::default()
+        });
+        test_client(&server, true).load(&request).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sql_and_dry_run() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/sql"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "sql": {
+                    "sql": ["SELECT count(*) FROM orders WHERE status = ?", ["new"]],
+                    "external": false,
+                    "aliasNameToMember": { "orders__count": "Orders.count" }
+                }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/dry-run"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "queryType": "regularQuery",
+                "normalizedQueries": [{ "measures": ["Orders.count"] }],
+                "queryOrder": [],
+                "transformedQueries": [],
+                "pivotQuery": { "measures": ["Orders.count"] }
+            })))
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server, false);
+        let request = crate::models::V1LoadRequest::new();
+
+        let sql = client.sql(&request).await.unwrap();
+        assert_eq!(sql.sql.sql[1], json!(["new"]));
+        assert_eq!(sql.sql.external, Some(false));
+
+        let dry_run = client.dry_run(&request).await.unwrap();
+        assert_eq!(dry_run.query_type, "regularQuery");
+        assert_eq!(dry_run.normalized_queries.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_pre_aggregations_jobs() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/pre-aggregations/jobs"))
+            .and(body_json(json!({
+                "action": "post",
+                "selector": { "timezones": ["UTC"] }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(["token1"])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/pre-aggregations/jobs"))
+            .and(body_json(json!({ "action": "get", "tokens": ["token1"] })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "token": "token1", "status": "processing", "table": "orders_main" }
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server, false);
+        let tokens = client
+            .post_pre_aggregations_jobs(crate::models::V1PreAggregationsSelector::new(vec![
+                "UTC".to_string()
+            ]))
+            .await
+            .unwrap();
+        assert_eq!(tokens, vec!["token1".to_string()]);
+
+        let statuses = client.get_pre_aggregations_jobs(tokens).await.unwrap();
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].status, "processing");
+        assert_eq!(statuses[0].table.as_deref(), Some("orders_main"));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/subscribe"))
+            .and(query_param("query", r#"{"measures":["Orders.count"]}"#))
+            .respond_with(SequenceResponder::new(
+                1,
+                continue_wait(),
+                ResponseTemplate::new(200).set_body_string(LOAD_RESPONSE),
+            ))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let query = crate::models::V1LoadRequestQuery {
+            measures: Some(vec!["Orders.count".to_string()]),
+            ..Default::default()
+        };
+        test_client(&server, false).subscribe(&query).await.unwrap();
+    }
+}
@@ -66,5 +66,6 @@ pub fn urlencode<T: AsRef<str>>(s: T) -> String {
     ::url::form_urlencoded::byte_serialize(s.as_ref().as_bytes()).collect()
 }
 
+pub mod client;
 pub mod configuration;
 pub mod default_api;
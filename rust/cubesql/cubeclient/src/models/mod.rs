@@ -14,6 +14,8 @@ pub mod v1_cube_meta_segment;
 pub use self::v1_cube_meta_segment::V1CubeMetaSegment;
 pub mod v1_cube_meta_type;
 pub use self::v1_cube_meta_type::V1CubeMetaType;
+pub mod v1_dry_run_response;
+pub use self::v1_dry_run_response::V1DryRunResponse;
 pub mod v1_error;
 pub use self::v1_error::V1Error;
 pub mod v1_load_request;
@@ -40,5 +42,17 @@ pub mod v1_load_result_annotation;
 pub use self::v1_load_result_annotation::V1LoadResultAnnotation;
 pub mod v1_meta_response;
 pub use self::v1_meta_response::V1MetaResponse;
+pub mod v1_pre_aggregation_job_status;
+pub use self::v1_pre_aggregation_job_status::V1PreAggregationJobStatus;
+pub mod v1_pre_aggregations_jobs_request;
+pub use self::v1_pre_aggregations_jobs_request::V1PreAggregationsJobsRequest;
+pub mod v1_pre_aggregations_selector;
+pub use self::v1_pre_aggregations_selector::V1PreAggregationsSelector;
+pub mod v1_pre_aggregations_selector_context;
+pub use self::v1_pre_aggregations_selector_context::V1PreAggregationsSelectorContext;
+pub mod v1_sql_query;
+pub use self::v1_sql_query::V1SqlQuery;
+pub mod v1_sql_response;
+pub use self::v1_sql_response::V1SqlResponse;
 pub mod v1_load_continue_wait;
 pub use self::v1_load_continue_wait::V1LoadContinueWait;
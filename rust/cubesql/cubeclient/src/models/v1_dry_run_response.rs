@@ -0,0 +1,35 @@
+/*
+ * Cube.js
+ *
+ * Cube.js Swagger Schema
+ *
+ * The version of the OpenAPI document: 1.0.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct V1DryRunResponse {
+    #[serde(rename = "queryType")]
+    pub query_type: String,
+    #[serde(rename = "normalizedQueries")]
+    pub normalized_queries: Vec<serde_json::Value>,
+    #[serde(rename = "queryOrder", skip_serializing_if = "Option::is_none")]
+    pub query_order: Option<Vec<serde_json::Value>>,
+    #[serde(rename = "transformedQueries", skip_serializing_if = "Option::is_none")]
+    pub transformed_queries: Option<Vec<serde_json::Value>>,
+    #[serde(rename = "pivotQuery", skip_serializing_if = "Option::is_none")]
+    pub pivot_query: Option<serde_json::Value>,
+}
+
+impl V1DryRunResponse {
+    pub fn new(query_type: String, normalized_queries: Vec<serde_json::Value>) -> V1DryRunResponse {
+        V1DryRunResponse {
+            query_type,
+            normalized_queries,
+            query_order: None,
+            transformed_queries: None,
+            pivot_query: None,
+        }
+    }
+}
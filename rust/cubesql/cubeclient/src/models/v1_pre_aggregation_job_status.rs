@@ -0,0 +1,32 @@
+/*
+ * Cube.js
+ *
+ * Cube.js Swagger Schema
+ *
+ * The version of the OpenAPI document: 1.0.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct V1PreAggregationJobStatus {
+    #[serde(rename = "token")]
+    pub token: String,
+    #[serde(rename = "status")]
+    pub status: String,
+    #[serde(rename = "table", skip_serializing_if = "Option::is_none")]
+    pub table: Option<String>,
+    #[serde(rename = "selector", skip_serializing_if = "Option::is_none")]
+    pub selector: Option<Box<crate::models::V1PreAggregationsSelector>>,
+}
+
+impl V1PreAggregationJobStatus {
+    pub fn new(token: String, status: String) -> V1PreAggregationJobStatus {
+        V1PreAggregationJobStatus {
+            token,
+            status,
+            table: None,
+            selector: None,
+        }
+    }
+}
@@ -0,0 +1,32 @@
+/*
+ * Cube.js
+ *
+ * Cube.js Swagger Schema
+ *
+ * The version of the OpenAPI document: 1.0.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct V1PreAggregationsJobsRequest {
+    #[serde(rename = "action")]
+    pub action: String,
+    #[serde(rename = "selector", skip_serializing_if = "Option::is_none")]
+    pub selector: Option<Box<crate::models::V1PreAggregationsSelector>>,
+    #[serde(rename = "tokens", skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<Vec<String>>,
+    #[serde(rename = "resType", skip_serializing_if = "Option::is_none")]
+    pub res_type: Option<String>,
+}
+
+impl V1PreAggregationsJobsRequest {
+    pub fn new(action: String) -> V1PreAggregationsJobsRequest {
+        V1PreAggregationsJobsRequest {
+            action,
+            selector: None,
+            tokens: None,
+            res_type: None,
+        }
+    }
+}
@@ -0,0 +1,35 @@
+/*
+ * Cube.js
+ *
+ * Cube.js Swagger Schema
+ *
+ * The version of the OpenAPI document: 1.0.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct V1PreAggregationsSelector {
+    #[serde(rename = "contexts", skip_serializing_if = "Option::is_none")]
+    pub contexts: Option<Vec<crate::models::V1PreAggregationsSelectorContext>>,
+    #[serde(rename = "timezones")]
+    pub timezones: Vec<String>,
+    #[serde(rename = "dataSources", skip_serializing_if = "Option::is_none")]
+    pub data_sources: Option<Vec<String>>,
+    #[serde(rename = "cubes", skip_serializing_if = "Option::is_none")]
+    pub cubes: Option<Vec<String>>,
+    #[serde(rename = "preAggregations", skip_serializing_if = "Option::is_none")]
+    pub pre_aggregations: Option<Vec<String>>,
+}
+
+impl V1PreAggregationsSelector {
+    pub fn new(timezones: Vec<String>) -> V1PreAggregationsSelector {
+        V1PreAggregationsSelector {
+            contexts: None,
+            timezones,
+            data_sources: None,
+            cubes: None,
+            pre_aggregations: None,
+        }
+    }
+}
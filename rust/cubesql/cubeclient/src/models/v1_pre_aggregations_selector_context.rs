@@ -0,0 +1,21 @@
+/*
+ * Cube.js
+ *
+ * Cube.js Swagger Schema
+ *
+ * The version of the OpenAPI document: 1.0.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct V1PreAggregationsSelectorContext {
+    #[serde(rename = "securityContext")]
+    pub security_context: serde_json::Value,
+}
+
+impl V1PreAggregationsSelectorContext {
+    pub fn new(security_context: serde_json::Value) -> V1PreAggregationsSelectorContext {
+        V1PreAggregationsSelectorContext { security_context }
+    }
+}
@@ -0,0 +1,35 @@
+/*
+ * Cube.js
+ *
+ * Cube.js Swagger Schema
+ *
+ * The version of the OpenAPI document: 1.0.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct V1SqlQuery {
+    #[serde(rename = "sql")]
+    pub sql: Vec<serde_json::Value>,
+    #[serde(rename = "dataSource", skip_serializing_if = "Option::is_none")]
+    pub data_source: Option<String>,
+    #[serde(rename = "external", skip_serializing_if = "Option::is_none")]
+    pub external: Option<bool>,
+    #[serde(rename = "preAggregations", skip_serializing_if = "Option::is_none")]
+    pub pre_aggregations: Option<Vec<serde_json::Value>>,
+    #[serde(rename = "aliasNameToMember", skip_serializing_if = "Option::is_none")]
+    pub alias_name_to_member: Option<::std::collections::HashMap<String, String>>,
+}
+
+impl V1SqlQuery {
+    pub fn new(sql: Vec<serde_json::Value>) -> V1SqlQuery {
+        V1SqlQuery {
+            sql,
+            data_source: None,
+            external: None,
+            pre_aggregations: None,
+            alias_name_to_member: None,
+        }
+    }
+}
@@ -0,0 +1,21 @@
+/*
+ * Cube.js
+ *
+ * Cube.js Swagger Schema
+ *
+ * The version of the OpenAPI document: 1.0.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct V1SqlResponse {
+    #[serde(rename = "sql")]
+    pub sql: Box<crate::models::V1SqlQuery>,
+}
+
+impl V1SqlResponse {
+    pub fn new(sql: crate::models::V1SqlQuery) -> V1SqlResponse {
+        V1SqlResponse { sql: Box::new(sql) }
+    }
+}
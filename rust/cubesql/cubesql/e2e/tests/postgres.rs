@@ -3,7 +3,7 @@ use std::{env, time::Duration};
 use async_trait::async_trait;
 use comfy_table::{Cell as TableCell, Table};
 use cubesql::config::Config;
-use futures::{pin_mut, TryStreamExt};
+use futures::{channel::mpsc, pin_mut, stream, FutureExt, StreamExt, TryStreamExt};
 use portpicker::{pick_unused_port, Port};
 use rust_decimal::prelude::*;
 use tokio::time::sleep;
@@ -14,7 +14,7 @@ use datafusion::assert_contains;
 use pg_interval::Interval;
 use pg_srv::{PgType, PgTypeId};
 use tokio::join;
-use tokio_postgres::{error::SqlState, Client, NoTls, Row, SimpleQueryMessage};
+use tokio_postgres::{error::SqlState, AsyncMessage, Client, NoTls, Row, SimpleQueryMessage};
 
 use super::basic::{AsyncTestConstructorResult, AsyncTestSuite, RunResult};
 
@@ -337,6 +337,46 @@ impl PostgresIntegrationTestSuite {
         Ok(())
     }
 
+    async fn test_statement_timeout(&self) -> RunResult<()> {
+        let client = PostgresIntegrationTestSuite::create_client(
+            format!("host=127.0.0.1 port={} user=test password=test", self.port)
+                .parse()
+                .unwrap(),
+        )
+        .await;
+
+        client.simple_query("SET statement_timeout = '1s'").await?;
+
+        // testing_blocking tables will never finish. It's a special testing table
+        match client
+            .simple_query("SELECT * FROM information_schema.testing_blocking")
+            .await
+        {
+            Err(ref e) if e.code() == Some(&SqlState::QUERY_CANCELED) => {}
+            res => panic!(
+                "unexpected return, simple query must time out, actual: {:?}",
+                res
+            ),
+        };
+
+        match client
+            .query("SELECT * FROM information_schema.testing_blocking", &[])
+            .await
+        {
+            Err(ref e) if e.code() == Some(&SqlState::QUERY_CANCELED) => {}
+            res => panic!(
+                "unexpected return, prepared query must time out, actual: {:?}",
+                res
+            ),
+        };
+
+        // The connection is still usable and the server default applies after RESET
+        client.simple_query("RESET statement_timeout").await?;
+        client.simple_query("SELECT 1").await?;
+
+        Ok(())
+    }
+
     async fn test_snapshot_execute_query(
         &self,
         query: String,
@@ -845,6 +885,42 @@ impl PostgresIntegrationTestSuite {
         Ok(())
     }
 
+    async fn test_simple_query_execute(&self) -> RunResult<()> {
+        self.test_simple_query(
+            "PREPARE execute_query AS SELECT $1 AS value".to_string(),
+            |_| {},
+        )
+        .await?;
+
+        for value in ["first", "second"] {
+            self.test_simple_query(format!("EXECUTE execute_query('{}')", value), |messages| {
+                if let SimpleQueryMessage::Row(row) = &messages[0] {
+                    assert_eq!(row.get(0), Some(value));
+                } else {
+                    panic!("Must be Row command, 0")
+                }
+            })
+            .await?;
+        }
+
+        self.test_simple_query("DEALLOCATE execute_query".to_string(), |_| {})
+            .await?;
+
+        match self
+            .client
+            .simple_query("EXECUTE execute_query('deallocated')")
+            .await
+        {
+            Err(ref e) if e.code() == Some(&SqlState::INVALID_SQL_STATEMENT_NAME) => {}
+            res => panic!(
+                "unexpected return, deallocated statement must be unknown, actual: {:?}",
+                res
+            ),
+        }
+
+        Ok(())
+    }
+
     async fn test_simple_query_discard_all(&self) -> RunResult<()> {
         self.test_simple_query("PREPARE simple_query AS SELECT 1".to_string(), |_| {})
             .await?;
@@ -875,6 +951,47 @@ impl PostgresIntegrationTestSuite {
         Ok(())
     }
 
+    async fn test_listen_notify(&self) -> RunResult<()> {
+        let (listener, mut connection) = tokio_postgres::connect(
+            &format!("host=127.0.0.1 port={} user=test password=test", self.port),
+            NoTls,
+        )
+        .await?;
+
+        // Connection must be polled manually to receive asynchronous messages
+        let (tx, mut rx) = mpsc::unbounded();
+        let messages = stream::poll_fn(move |cx| connection.poll_message(cx))
+            .map_err(|e| panic!("connection error: {}", e));
+        tokio::spawn(messages.forward(tx).map(|r| r.unwrap()));
+
+        listener.batch_execute("LISTEN test_channel").await?;
+
+        self.client
+            .batch_execute("NOTIFY another_channel, 'skipped'")
+            .await?;
+        self.client
+            .batch_execute("NOTIFY test_channel, 'payload'")
+            .await?;
+        // Extended query protocol
+        self.client
+            .execute("NOTIFY test_channel, 'extended'", &[])
+            .await?;
+
+        for expected_payload in ["payload", "extended"] {
+            match rx.next().await {
+                Some(AsyncMessage::Notification(n)) => {
+                    assert_eq!(n.channel(), "test_channel");
+                    assert_eq!(n.payload(), expected_payload);
+                }
+                other => panic!("unexpected message: {:?}", other),
+            }
+        }
+
+        listener.batch_execute("UNLISTEN *").await?;
+
+        Ok(())
+    }
+
     async fn test_database_change(&self) -> RunResult<()> {
         self.test_simple_query("SELECT current_database()".to_string(), |messages| {
             assert_eq!(messages.len(), 2);
@@ -1170,6 +1287,7 @@ impl AsyncTestSuite for PostgresIntegrationTestSuite {
     async fn run(&mut self) -> RunResult<()> {
         self.test_cancel_simple_query().await?;
         self.test_cancel_execute_prepared().await?;
+        self.test_statement_timeout().await?;
         self.test_prepare().await?;
         self.test_extended_error().await?;
         self.test_prepare_empty_query().await?;
@@ -1190,9 +1308,11 @@ impl AsyncTestSuite for PostgresIntegrationTestSuite {
         .await?;
         self.test_simple_query_deallocate_specific().await?;
         self.test_simple_query_deallocate_all().await?;
+        self.test_simple_query_execute().await?;
         self.test_df_panic_handle().await?;
         self.test_simple_query_discard_all().await?;
         self.test_database_change().await?;
+        self.test_listen_notify().await?;
         self.test_temp_tables().await?;
 
         // PostgreSQL doesn't support unsigned integers in the protocol, it's a constraint only
@@ -13,7 +13,7 @@ use datafusion::{
 
 use crate::{
     compile::DatabaseProtocolDetails,
-    sql::{ColumnType, SessionManager, SessionState},
+    sql::{temp_tables::TempTableManager, ColumnType, SessionManager, SessionState},
     transport::{CubeMeta, MetaContext, V1CubeMetaExt},
     CubeError,
 };
@@ -52,6 +52,15 @@ impl CubeContext {
             .table_name_by_table_provider(table_provider)
     }
 
+    /// Tables shared between sessions of the current user with the same security context, None
+    /// when they are disabled
+    pub fn shared_tables(&self) -> Option<Arc<TempTableManager>> {
+        let user = self.session_state.user()?;
+
+        self.sessions
+            .shared_tables(&user, self.session_state.security_context_hash())
+    }
+
     pub fn get_function<T>(&self, name: &str, udfs: &HashMap<String, Arc<T>>) -> Option<Arc<T>> {
         if name.starts_with("pg_catalog.") {
             return udfs.get(&format!("{}", &name[11..name.len()])).cloned();
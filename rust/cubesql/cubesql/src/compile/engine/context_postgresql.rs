@@ -193,22 +193,23 @@ impl DatabaseProtocol {
             datafusion::catalog::TableReference::Bare { table } => {
                 let table_lower = table.to_ascii_lowercase();
                 let schema = if context.session_state.temp_tables().has(&table_lower) {
-                    "pg_temp_3"
+                    context.session_state.temp_schema()
                 } else if table.starts_with("pg_") {
-                    "pg_catalog"
+                    "pg_catalog".to_string()
                 } else {
-                    "public"
+                    "public".to_string()
                 };
                 (
                     context.session_state.database().unwrap_or("db".to_string()),
-                    schema.to_string(),
+                    schema,
                     table_lower,
                 )
             }
         };
 
         match schema.as_str() {
-            "pg_temp_3" => {
+            // pg_temp is an alias for the temporary schema of the session
+            schema if schema == "pg_temp" || schema == context.session_state.temp_schema() => {
                 if let Some(temp_table) = context.session_state.temp_tables().get(&table) {
                     return Some(Arc::new(TempTableProvider::new(table, temp_table)));
                 }
@@ -224,6 +225,13 @@ impl DatabaseProtocol {
                     // TODO .clone()
                 };
 
+                if let Some(shared_table) = context
+                    .shared_tables()
+                    .and_then(|shared_tables| shared_tables.get(&table))
+                {
+                    return Some(Arc::new(TempTableProvider::new(table, shared_table)));
+                }
+
                 // TODO: Move to pg_catalog, support SEARCH PATH.
                 // Redshift
                 match table.as_str() {
@@ -263,6 +271,12 @@ impl DatabaseProtocol {
                     return Some(Arc::new(PostgresSchemaTableProvider::new(
                         &context.session_state.database().unwrap_or("db".to_string()),
                         &context.meta.cubes,
+                        &context.session_state.temp_schema(),
+                        &context.session_state.temp_tables().names(),
+                        &context
+                            .shared_tables()
+                            .map(|shared_tables| shared_tables.names())
+                            .unwrap_or_default(),
                     )))
                 }
                 "character_sets" => {
@@ -323,6 +337,12 @@ impl DatabaseProtocol {
                     return Some(Arc::new(PgCatalogTableProvider::new(
                         &context.session_state.user().unwrap_or("test".to_string()),
                         &context.meta.cubes,
+                        &context.session_state.temp_schema(),
+                        &context.session_state.temp_tables().names(),
+                        &context
+                            .shared_tables()
+                            .map(|shared_tables| shared_tables.names())
+                            .unwrap_or_default(),
                     )))
                 }
                 "pg_type" => {
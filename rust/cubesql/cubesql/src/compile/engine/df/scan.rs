@@ -632,6 +632,13 @@ impl ExecutionPlan for CubeScanExecutionPlan {
             self.span_id.clone(),
         );
 
+        let mut cancel_guard = CubeScanCancelGuard::new(
+            self.span_id.clone(),
+            self.auth_context.clone(),
+            self.transport.clone(),
+            meta.clone(),
+        );
+
         if stream_mode {
             let result = self
                 .transport
@@ -645,8 +652,11 @@ impl ExecutionPlan for CubeScanExecutionPlan {
                     self.member_fields.clone(),
                 )
                 .await;
-            let stream = result.map_err(|err| DataFusionError::Execution(err.to_string()))?;
-            let main_stream = CubeScanMemoryStream::new(stream);
+            let stream = result.map_err(|err| {
+                cancel_guard.disarm();
+                DataFusionError::Execution(err.to_string())
+            })?;
+            let main_stream = CubeScanMemoryStream::new(stream, cancel_guard);
 
             return Ok(Box::pin(CubeScanStreamRouter::new(
                 Some(main_stream),
@@ -666,7 +676,9 @@ impl ExecutionPlan for CubeScanExecutionPlan {
             self.options.clone(),
             self.wrapped_sql.clone(),
         )
-        .await?;
+        .await;
+        cancel_guard.disarm();
+        let response = response?;
 
         // For now execute method executes only one query at a time, so we
         // take the first result
@@ -750,22 +762,83 @@ impl CubeScanOneShotStream {
     }
 }
 
+/// Cancels load request on the transport side when the scan is dropped before the request
+/// is completed, for example when the client sends CancelRequest.
+struct CubeScanCancelGuard {
+    span_id: Option<Arc<SpanId>>,
+    auth_context: AuthContextRef,
+    transport: Arc<dyn TransportService>,
+    meta: LoadRequestMeta,
+}
+
+impl CubeScanCancelGuard {
+    pub fn new(
+        span_id: Option<Arc<SpanId>>,
+        auth_context: AuthContextRef,
+        transport: Arc<dyn TransportService>,
+        meta: LoadRequestMeta,
+    ) -> Self {
+        Self {
+            span_id,
+            auth_context,
+            transport,
+            meta,
+        }
+    }
+
+    /// Request is completed, there is nothing to cancel
+    pub fn disarm(&mut self) {
+        self.span_id = None;
+    }
+}
+
+impl Drop for CubeScanCancelGuard {
+    fn drop(&mut self) {
+        let Some(span_id) = self.span_id.take() else {
+            return;
+        };
+        // Drop can be called outside of the runtime, for example on shutdown
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+
+        let transport = self.transport.clone();
+        let auth_context = self.auth_context.clone();
+        let meta = self.meta.clone();
+        handle.spawn(async move {
+            if let Err(err) = transport.cancel(span_id, auth_context, meta).await {
+                warn!("Unable to cancel load request: {}", err);
+            }
+        });
+    }
+}
+
 struct CubeScanMemoryStream {
     receiver: CubeStreamReceiver,
+    cancel_guard: CubeScanCancelGuard,
 }
 
 impl CubeScanMemoryStream {
-    pub fn new(receiver: CubeStreamReceiver) -> Self {
-        Self { receiver }
+    pub fn new(receiver: CubeStreamReceiver, cancel_guard: CubeScanCancelGuard) -> Self {
+        Self {
+            receiver,
+            cancel_guard,
+        }
     }
 
     fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<ArrowResult<RecordBatch>>> {
-        self.receiver.poll_recv(cx).map(|res| match res {
+        let next = self.receiver.poll_recv(cx).map(|res| match res {
             Some(Some(Ok(chunk))) => Some(Ok(chunk)),
             Some(Some(Err(err))) => Some(Err(ArrowError::ComputeError(err.to_string()))),
             Some(None) => None,
             None => None,
-        })
+        });
+        // Stream is finished or failed, dropping it after that must not cancel anything
+        if let Poll::Ready(None | Some(Err(_))) = &next {
+            self.cancel_guard.disarm();
+        }
+
+        next
     }
 }
 
@@ -1449,6 +1522,15 @@ mod tests {
                 panic!("It's a fake transport");
             }
 
+            async fn cancel(
+                &self,
+                _span_id: Arc<SpanId>,
+                _ctx: AuthContextRef,
+                _meta_fields: LoadRequestMeta,
+            ) -> Result<(), CubeError> {
+                panic!("It's a fake transport");
+            }
+
             async fn can_switch_user_for_session(
                 &self,
                 _ctx: AuthContextRef,
@@ -1609,4 +1691,148 @@ mod tests {
             .unwrap()
         )
     }
+
+    #[tokio::test]
+    async fn test_df_cube_scan_cancel_in_flight_load() {
+        #[derive(Debug)]
+        struct PendingTransport {
+            cancelled: tokio::sync::mpsc::UnboundedSender<String>,
+        }
+
+        #[async_trait]
+        impl TransportService for PendingTransport {
+            async fn meta(&self, _ctx: AuthContextRef) -> Result<Arc<MetaContext>, CubeError> {
+                panic!("It's a fake transport");
+            }
+
+            async fn sql(
+                &self,
+                _span_id: Option<Arc<SpanId>>,
+                _query: V1LoadRequestQuery,
+                _ctx: AuthContextRef,
+                _meta_fields: LoadRequestMeta,
+                _member_to_alias: Option<HashMap<String, String>>,
+                _expression_params: Option<Vec<Option<String>>>,
+            ) -> Result<SqlResponse, CubeError> {
+                panic!("It's a fake transport");
+            }
+
+            // Request is never completed, like a long query in the queue
+            async fn load(
+                &self,
+                _span_id: Option<Arc<SpanId>>,
+                _query: V1LoadRequestQuery,
+                _sql_query: Option<SqlQuery>,
+                _ctx: AuthContextRef,
+                _meta_fields: LoadRequestMeta,
+                _schema: SchemaRef,
+                _member_fields: Vec<MemberField>,
+            ) -> Result<Vec<RecordBatch>, CubeError> {
+                futures::future::pending().await
+            }
+
+            async fn load_stream(
+                &self,
+                _span_id: Option<Arc<SpanId>>,
+                _query: V1LoadRequestQuery,
+                _sql_query: Option<SqlQuery>,
+                _ctx: AuthContextRef,
+                _meta_fields: LoadRequestMeta,
+                _schema: SchemaRef,
+                _member_fields: Vec<MemberField>,
+            ) -> Result<CubeStreamReceiver, CubeError> {
+                panic!("It's a fake transport");
+            }
+
+            async fn cancel(
+                &self,
+                span_id: Arc<SpanId>,
+                _ctx: AuthContextRef,
+                _meta_fields: LoadRequestMeta,
+            ) -> Result<(), CubeError> {
+                self.cancelled
+                    .send(span_id.span_id.clone())
+                    .map_err(|err| CubeError::internal(err.to_string()))
+            }
+
+            async fn can_switch_user_for_session(
+                &self,
+                _ctx: AuthContextRef,
+                _to_user: String,
+            ) -> Result<bool, CubeError> {
+                panic!("It's a fake transport");
+            }
+
+            async fn log_load_state(
+                &self,
+                _span_id: Option<Arc<SpanId>>,
+                _ctx: AuthContextRef,
+                _meta_fields: LoadRequestMeta,
+                _event: String,
+                _properties: serde_json::Value,
+            ) -> Result<(), CubeError> {
+                Ok(())
+            }
+        }
+
+        let (cancelled_tx, mut cancelled_rx) = tokio::sync::mpsc::unbounded_channel();
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "KibanaSampleDataEcommerce.count",
+            DataType::Utf8,
+            false,
+        )]));
+
+        let scan_node = CubeScanExecutionPlan {
+            schema: schema.clone(),
+            member_fields: vec![MemberField::Member(
+                "KibanaSampleDataEcommerce.count".to_string(),
+            )],
+            request: V1LoadRequestQuery {
+                measures: Some(vec!["KibanaSampleDataEcommerce.count".to_string()]),
+                ..Default::default()
+            },
+            wrapped_sql: None,
+            auth_context: Arc::new(HttpAuthContext {
+                access_token: "access_token".to_string(),
+                base_path: "base_path".to_string(),
+            }),
+            options: CubeScanOptions {
+                change_user: None,
+                max_records: None,
+            },
+            transport: Arc::new(PendingTransport {
+                cancelled: cancelled_tx,
+            }),
+            meta: get_test_load_meta(DatabaseProtocol::PostgreSQL),
+            span_id: Some(Arc::new(SpanId::new(
+                "test-span".to_string(),
+                serde_json::json!({}),
+            ))),
+            config_obj: crate::config::Config::test().config_obj(),
+        };
+
+        let runtime = Arc::new(
+            RuntimeEnv::new(RuntimeConfig::new()).expect("Unable to create RuntimeEnv for testing"),
+        );
+        let task = Arc::new(TaskContext::new(
+            "test".to_string(),
+            "session".to_string(),
+            HashMap::new(),
+            HashMap::new(),
+            HashMap::new(),
+            runtime,
+        ));
+
+        // Query is cancelled by the client while load is still in flight
+        let result = tokio::time::timeout(
+            std::time::Duration::from_millis(100),
+            scan_node.execute(0, task),
+        )
+        .await;
+        assert!(result.is_err(), "load must be in flight");
+
+        let cancelled =
+            tokio::time::timeout(std::time::Duration::from_secs(5), cancelled_rx.recv()).await;
+        assert_eq!(cancelled.unwrap(), Some("test-span".to_string()));
+    }
 }
@@ -38,6 +38,7 @@ struct PgStatActivityBuilder {
     query_id: Int64Builder,
     query: StringBuilder,
     backend_type: StringBuilder,
+    query_tag: StringBuilder,
 }
 
 impl PgStatActivityBuilder {
@@ -65,6 +66,7 @@ impl PgStatActivityBuilder {
             query_id: Int64Builder::new(capacity),
             query: StringBuilder::new(capacity),
             backend_type: StringBuilder::new(capacity),
+            query_tag: StringBuilder::new(capacity),
         }
     }
 
@@ -89,8 +91,10 @@ impl PgStatActivityBuilder {
         self.xact_start.append_null().unwrap();
         self.query_start.append_null().unwrap();
         self.state_change.append_null().unwrap();
-        self.wait_event_type.append_null().unwrap();
-        self.wait_event.append_null().unwrap();
+        self.wait_event_type
+            .append_option(session.wait_event_type)
+            .unwrap();
+        self.wait_event.append_option(session.wait_event).unwrap();
         self.state.append_null().unwrap();
         self.backend_xid.append_null().unwrap();
         self.backend_xmin.append_null().unwrap();
@@ -98,6 +102,7 @@ impl PgStatActivityBuilder {
         self.query.append_option(session.query).unwrap();
 
         self.backend_type.append_value(&"client backend").unwrap();
+        self.query_tag.append_option(session.query_tag).unwrap();
     }
 
     fn finish(mut self) -> Vec<Arc<dyn Array>> {
@@ -124,6 +129,7 @@ impl PgStatActivityBuilder {
             Arc::new(self.query_id.finish()),
             Arc::new(self.query.finish()),
             Arc::new(self.backend_type.finish()),
+            Arc::new(self.query_tag.finish()),
         ];
 
         columns
@@ -196,6 +202,8 @@ impl TableProvider for PgCatalogStatActivityProvider {
             Field::new("query_id", DataType::Int64, true),
             Field::new("query", DataType::Utf8, true),
             Field::new("backend_type", DataType::Utf8, false),
+            // Cube extension, tag of the load from `cube.query_tag` or a `/* tag: ... */` comment
+            Field::new("query_tag", DataType::Utf8, true),
         ]))
     }
 
@@ -80,13 +80,27 @@ pub struct PgCatalogTableProvider {
 }
 
 impl PgCatalogTableProvider {
-    pub fn new(current_user: &str, cubes: &Vec<V1CubeMeta>) -> Self {
+    pub fn new(
+        current_user: &str,
+        cubes: &Vec<V1CubeMeta>,
+        temp_schema: &str,
+        temp_tables: &Vec<String>,
+        shared_tables: &Vec<String>,
+    ) -> Self {
         let mut builder = PgCatalogTablesBuilder::new();
 
         for cube in cubes {
             builder.add_table("public", cube.name.clone(), current_user);
         }
 
+        for table in temp_tables {
+            builder.add_table(temp_schema, table, current_user);
+        }
+
+        for table in shared_tables {
+            builder.add_table("public", table, current_user);
+        }
+
         Self {
             data: Arc::new(builder.finish()),
         }
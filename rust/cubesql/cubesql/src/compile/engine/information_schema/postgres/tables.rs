@@ -119,7 +119,13 @@ pub struct InfoSchemaTableProvider {
 }
 
 impl InfoSchemaTableProvider {
-    pub fn new(db_name: &str, cubes: &Vec<V1CubeMeta>) -> Self {
+    pub fn new(
+        db_name: &str,
+        cubes: &Vec<V1CubeMeta>,
+        temp_schema: &str,
+        temp_tables: &Vec<String>,
+        shared_tables: &Vec<String>,
+    ) -> Self {
         let mut builder = InformationSchemaTablesBuilder::new();
         // information_schema
         builder.add_table(db_name, "information_schema", "tables", "VIEW");
@@ -130,6 +136,14 @@ impl InfoSchemaTableProvider {
             builder.add_table(db_name, "public", &cube.name, "BASE TABLE");
         }
 
+        for table in temp_tables {
+            builder.add_table(db_name, temp_schema, table, "LOCAL TEMPORARY");
+        }
+
+        for table in shared_tables {
+            builder.add_table(db_name, "public", table, "BASE TABLE");
+        }
+
         Self {
             data: Arc::new(builder.finish()),
         }
@@ -40,6 +40,7 @@ mod tests {
             rewrite::rewriter::Rewriter,
             test::{get_sixteen_char_member_cube, get_string_cube_meta},
         },
+        sql::extended::PreparedStatement,
         CubeError,
     };
     use chrono::Datelike;
@@ -49,6 +50,7 @@ mod tests {
     };
     use datafusion::{arrow::datatypes::DataType, physical_plan::displayable};
     use itertools::Itertools;
+    use pg_srv::{protocol::ParameterDescription, PgTypeId};
     use pretty_assertions::assert_eq;
     use regex::Regex;
     use serde_json::json;
@@ -5171,6 +5173,109 @@ ORDER BY
             .0
         );
 
+        insta::assert_snapshot!(
+            "pg_set_statement_timeout_show",
+            execute_queries_with_flags(
+                vec![
+                    "SET statement_timeout = '30s'".to_string(),
+                    "SHOW statement_timeout".to_string()
+                ],
+                DatabaseProtocol::PostgreSQL
+            )
+            .await?
+            .0
+        );
+
+        let default = execute_queries_with_flags(
+            vec!["SHOW statement_timeout".to_string()],
+            DatabaseProtocol::PostgreSQL,
+        )
+        .await?
+        .0;
+        for reset in [
+            "SET statement_timeout TO DEFAULT",
+            "RESET statement_timeout",
+        ] {
+            assert_eq!(
+                execute_queries_with_flags(
+                    vec![
+                        "SET statement_timeout = '30s'".to_string(),
+                        reset.to_string(),
+                        "SHOW statement_timeout".to_string()
+                    ],
+                    DatabaseProtocol::PostgreSQL
+                )
+                .await?
+                .0,
+                default,
+                "{}",
+                reset
+            );
+        }
+
+        insta::assert_snapshot!(
+            "pg_set_statement_timeout_invalid",
+            execute_queries_with_flags(
+                vec!["SET statement_timeout = '30 years'".to_string()],
+                DatabaseProtocol::PostgreSQL
+            )
+            .await
+            .err()
+            .unwrap()
+            .to_string()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_semantic_layer_show() -> Result<(), CubeError> {
+        insta::assert_snapshot!(
+            "semantic_layer_show_cubes",
+            execute_query("SHOW CUBES".to_string(), DatabaseProtocol::PostgreSQL).await?
+        );
+
+        insta::assert_snapshot!(
+            "semantic_layer_show_segments_from",
+            execute_query(
+                "SHOW SEGMENTS FROM KibanaSampleDataEcommerce".to_string(),
+                DatabaseProtocol::PostgreSQL
+            )
+            .await?
+        );
+
+        insta::assert_snapshot!(
+            "semantic_layer_describe_cube",
+            execute_query(
+                "DESCRIBE KibanaSampleDataEcommerce".to_string(),
+                DatabaseProtocol::PostgreSQL
+            )
+            .await?
+        );
+
+        insta::assert_snapshot!(
+            "semantic_layer_show_measures_unknown_cube",
+            execute_query(
+                "SHOW MEASURES FROM UnknownCube".to_string(),
+                DatabaseProtocol::PostgreSQL
+            )
+            .await
+            .err()
+            .unwrap()
+            .to_string()
+        );
+
+        // Without FROM members of all cubes are listed
+        for query in ["SHOW measures", "SHOW DIMENSIONS", "SHOW Segments"] {
+            let result = execute_query(query.to_string(), DatabaseProtocol::PostgreSQL).await?;
+            assert!(
+                result.contains("KibanaSampleDataEcommerce") && !result.contains("setting"),
+                "{}: {}",
+                query,
+                result
+            );
+        }
+
         Ok(())
     }
 
@@ -5243,6 +5348,172 @@ ORDER BY
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_explain_cube() -> Result<(), CubeError> {
+        let meta = get_test_tenant_ctx();
+        let session = get_test_session(DatabaseProtocol::PostgreSQL, meta.clone()).await;
+
+        let plan = convert_sql_to_cube_query(
+            &"EXPLAIN (FORMAT JSON, CUBE) SELECT COUNT(*) FROM KibanaSampleDataEcommerce"
+                .to_string(),
+            meta.clone(),
+            session.clone(),
+        )
+        .await?;
+        let explain = match plan {
+            QueryPlan::MetaTabular(_, frame) => match frame.get_rows()[0].values()[0].clone() {
+                crate::sql::dataframe::TableValue::String(explain) => {
+                    serde_json::from_str::<serde_json::Value>(&explain)?
+                }
+                other => panic!("unexpected value: {:?}", other),
+            },
+            other => panic!("unexpected plan: {:?}", other),
+        };
+        assert_eq!(
+            explain["cubeScans"][0]["request"]["measures"],
+            json!(["KibanaSampleDataEcommerce.count"])
+        );
+        assert_eq!(explain["rewrite"]["cacheHit"], false);
+        assert!(!explain["rewrite"]["stages"].as_array().unwrap().is_empty());
+
+        // Text format prints the same details line by line
+        let plan = convert_sql_to_cube_query(
+            &"EXPLAIN (CUBE) SELECT COUNT(*) FROM KibanaSampleDataEcommerce".to_string(),
+            meta.clone(),
+            session.clone(),
+        )
+        .await?;
+        match plan {
+            QueryPlan::MetaTabular(_, frame) => {
+                let lines = frame
+                    .get_rows()
+                    .iter()
+                    .map(|row| row.values()[0].to_string())
+                    .collect::<Vec<_>>();
+                assert_eq!(lines[0], "Plan:");
+                assert!(lines.iter().any(|line| line == "CubeScan #1:"));
+                assert!(lines.iter().any(|line| line.starts_with("Rewrite: ")));
+            }
+            other => panic!("unexpected plan: {:?}", other),
+        };
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rewrite_budget_fallback() -> Result<(), CubeError> {
+        init_testing_logger();
+
+        // Tiny e-graph budget stops rewrites before the first iteration
+        let config = |fallback: bool| {
+            Arc::new(crate::config::ConfigObjImpl {
+                rewrite_budget_max_nodes: 1,
+                rewrite_budget_fallback: fallback,
+                ..crate::config::ConfigObjImpl::default()
+            })
+        };
+
+        let context = TestContext::with_config(DatabaseProtocol::PostgreSQL, config(false)).await;
+        let err = context
+            .execute_query("SELECT 1 + 1 AS n")
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("node limit reached"), "{}", err);
+
+        // Plan without cubes doesn't need rewrites to be executed
+        let context = TestContext::with_config(DatabaseProtocol::PostgreSQL, config(true)).await;
+        assert_eq!(
+            context.execute_query("SELECT 1 + 1 AS n").await?,
+            "+---+\n| n |\n+---+\n| 2 |\n+---+"
+        );
+
+        // Table scans of cubes must be replaced by CubeScan
+        let err = context
+            .execute_query("SELECT COUNT(*) FROM KibanaSampleDataEcommerce")
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("not executable"), "{}", err);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_persisted_rewrite_cache() -> Result<(), CubeError> {
+        init_testing_logger();
+
+        let cache_dir =
+            std::env::temp_dir().join(format!("cubesql-rewrite-cache-{}", uuid::Uuid::new_v4()));
+        let config_obj = Arc::new(crate::config::ConfigObjImpl {
+            enable_rewrite_cache: true,
+            enable_parameterized_rewrite_cache: true,
+            persisted_rewrite_cache_dir: Some(cache_dir.to_string_lossy().to_string()),
+            ..crate::config::ConfigObjImpl::default()
+        });
+        let meta = get_test_tenant_ctx();
+        let kibana_cubes = meta
+            .cubes
+            .iter()
+            .filter(|cube| cube.name == "KibanaSampleDataEcommerce")
+            .cloned()
+            .collect::<Vec<_>>();
+
+        // Every session gets its own compiler cache and a new compiler id, as if the server was
+        // restarted and compiled the data model again. The last one has another data model.
+        for (cubes, expected_cache_hit) in [
+            (meta.cubes.clone(), false),
+            (meta.cubes.clone(), true),
+            (kibana_cubes, false),
+        ] {
+            let meta = Arc::new(crate::transport::MetaContext::new(
+                cubes,
+                meta.cube_to_data_source.clone(),
+                meta.data_source_to_sql_generator.clone(),
+                uuid::Uuid::new_v4(),
+            ));
+            let session = crate::compile::test::get_test_session_with_config(
+                DatabaseProtocol::PostgreSQL,
+                config_obj.clone(),
+                meta.clone(),
+            )
+            .await;
+            let plan = convert_sql_to_cube_query(
+                &"EXPLAIN (FORMAT JSON, CUBE) SELECT COUNT(*) FROM KibanaSampleDataEcommerce WHERE customer_gender = 'female'"
+                    .to_string(),
+                meta.clone(),
+                session,
+            )
+            .await?;
+            let explain = match plan {
+                QueryPlan::MetaTabular(_, frame) => match frame.get_rows()[0].values()[0].clone() {
+                    crate::sql::dataframe::TableValue::String(explain) => {
+                        serde_json::from_str::<serde_json::Value>(&explain)?
+                    }
+                    other => panic!("unexpected value: {:?}", other),
+                },
+                other => panic!("unexpected plan: {:?}", other),
+            };
+            assert_eq!(
+                explain["rewrite"]["parameterizedCacheHit"],
+                expected_cache_hit
+            );
+            assert_eq!(
+                explain["cubeScans"][0]["request"]["measures"],
+                json!(["KibanaSampleDataEcommerce.count"])
+            );
+            assert_eq!(
+                explain["cubeScans"][0]["request"]["filters"][0]["values"],
+                json!(["female"])
+            );
+        }
+        assert_eq!(std::fs::read_dir(&cache_dir)?.count(), 2);
+
+        std::fs::remove_dir_all(&cache_dir)?;
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_information_schema_tables_postgres() -> Result<(), CubeError> {
         insta::assert_snapshot!(
@@ -6045,6 +6316,157 @@ ORDER BY
         assert!(select_into_query.is_ok());
     }
 
+    #[tokio::test]
+    async fn shared_table_named_like_cube() {
+        let meta = get_test_tenant_ctx();
+        let session = crate::compile::test::get_test_session_with_config(
+            DatabaseProtocol::PostgreSQL,
+            Arc::new(crate::config::ConfigObjImpl {
+                shared_tables_ttl: 60,
+                ..crate::config::ConfigObjImpl::default()
+            }),
+            meta.clone(),
+        )
+        .await;
+
+        let create_query = convert_sql_to_cube_query(
+            &"CREATE TABLE kibanasampledataecommerce AS SELECT 1 AS col".to_string(),
+            meta.clone(),
+            session,
+        )
+        .await;
+        match create_query {
+            Err(CompilationError::User(msg, _)) => assert_eq!(
+                msg,
+                "relation \"kibanasampledataecommerce\" already exists as a cube"
+            ),
+            _ => panic!("CREATE TABLE named like a cube should throw CompilationError::User"),
+        };
+    }
+
+    #[tokio::test]
+    async fn test_listen_notify_plan() -> Result<(), CubeError> {
+        let meta = get_test_tenant_ctx();
+        let session = get_test_session(DatabaseProtocol::PostgreSQL, meta.clone()).await;
+        let mut receiver = session.server.notifications.subscribe();
+
+        let plan = |query: &str| {
+            let stmt = parser::parse_notification_statement(query)
+                .unwrap()
+                .expect("notification statement");
+            convert_notification_to_cube_query(&stmt, meta.clone(), session.clone())
+        };
+
+        match plan("LISTEN test_channel")? {
+            QueryPlan::MetaOk(_, CommandCompletion::Listen) => {}
+            other => panic!("unexpected plan: {:?}", other),
+        };
+        assert!(session.state.is_listening("test_channel"));
+
+        match plan("NOTIFY test_channel, 'payload'")? {
+            QueryPlan::MetaOk(_, CommandCompletion::Notify) => {}
+            other => panic!("unexpected plan: {:?}", other),
+        };
+        let notification = receiver.recv().await?;
+        assert_eq!(notification.process_id, session.state.connection_id);
+        assert_eq!(notification.channel, "test_channel");
+        assert_eq!(notification.payload, "payload");
+
+        match plan("UNLISTEN *")? {
+            QueryPlan::MetaOk(_, CommandCompletion::Unlisten) => {}
+            other => panic!("unexpected plan: {:?}", other),
+        };
+        assert!(!session.state.has_listen_channels());
+
+        let too_long = format!("NOTIFY test_channel, '{}'", "a".repeat(8000));
+        assert!(plan(&too_long).is_err());
+
+        // Inside of the transaction NOTIFY is queued until COMMIT, duplicates are collapsed
+        assert!(session.state.begin_transaction());
+        plan("NOTIFY test_channel, 'in_transaction'")?;
+        plan("NOTIFY test_channel, 'in_transaction'")?;
+        assert!(receiver.try_recv().is_err());
+
+        session.state.end_transaction();
+        let pending = session.state.take_pending_notifications();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].payload, "in_transaction");
+        assert!(session.state.take_pending_notifications().is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_execute_prepared_statement() -> Result<(), CubeError> {
+        init_testing_logger();
+
+        let meta = get_test_tenant_ctx();
+        let session = get_test_session(DatabaseProtocol::PostgreSQL, meta.clone()).await;
+
+        let query = parser::parse_sql_to_statement(
+            &"SELECT customer_gender FROM KibanaSampleDataEcommerce WHERE customer_gender = $1 GROUP BY 1"
+                .to_string(),
+            DatabaseProtocol::PostgreSQL,
+            &mut None,
+        )?;
+        session.state.statements.write().await.insert(
+            "test_stmt".to_string(),
+            PreparedStatement::Query {
+                from_sql: true,
+                created: chrono::offset::Utc::now(),
+                query,
+                parameters: ParameterDescription::new(vec![PgTypeId::TEXT]),
+                description: None,
+                span_id: None,
+            },
+        );
+
+        for gender in ["female", "male"] {
+            let logical_plan = convert_sql_to_cube_query(
+                &format!("EXECUTE test_stmt('{}')", gender),
+                meta.clone(),
+                session.clone(),
+            )
+            .await?
+            .as_logical_plan();
+
+            assert_eq!(
+                logical_plan.find_cube_scan().request.filters,
+                Some(vec![V1LoadRequestQueryFilterItem {
+                    member: Some("KibanaSampleDataEcommerce.customer_gender".to_string()),
+                    operator: Some("equals".to_string()),
+                    values: Some(vec![gender.to_string()]),
+                    or: None,
+                    and: None,
+                }])
+            );
+        }
+
+        let err = convert_sql_to_cube_query(
+            &"EXECUTE test_stmt('female', 'male')".to_string(),
+            meta.clone(),
+            session.clone(),
+        )
+        .await
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("wrong number of parameters for prepared statement"));
+
+        let err = convert_sql_to_cube_query(
+            &"EXECUTE unknown_stmt(1)".to_string(),
+            meta.clone(),
+            session.clone(),
+        )
+        .await
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains(r#"prepared statement "unknown_stmt" does not exist"#));
+
+        Ok(())
+    }
+
     // This tests asserts that our DF fork contains support for IS TRUE|FALSE
     #[tokio::test]
     async fn df_is_boolean() -> Result<(), CubeError> {
@@ -1,10 +1,11 @@
-use std::{collections::HashMap, sync::LazyLock};
+use std::{collections::HashMap, fmt, sync::LazyLock};
 
 use regex::Regex;
 use sqlparser::{
-    ast::Statement,
+    ast::{escape_single_quote_string, Statement},
     dialect::{Dialect, PostgreSqlDialect},
     parser::Parser,
+    tokenizer::{Token, Tokenizer},
 };
 
 use super::{qtrace::Qtrace, CompilationError, DatabaseProtocol};
@@ -236,6 +237,32 @@ pub fn parse_sql_to_statements(
             .to_string()
     };
 
+    // Our fork of sqlparser doesn't support RESET, it's the same as SET ... TO DEFAULT
+    let query = {
+        static RESET_RE: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r#"(?i)^\s*reset\s+(?P<name>[\w."]+)\s*(?P<end>;?\s*)$"#).unwrap()
+        });
+        match RESET_RE.captures(&query) {
+            Some(c)
+                if matches!(protocol, DatabaseProtocol::PostgreSQL)
+                    && !c["name"].eq_ignore_ascii_case("all") =>
+            {
+                format!("SET {} TO DEFAULT{}", &c["name"], &c["end"])
+            }
+            _ => query,
+        }
+    };
+
+    // Our fork of sqlparser doesn't support qualified names of variables in SET
+    let query = {
+        static SET_QUERY_TAG_RE: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r#"(?i)^(?P<set>\s*set\s+(?:session\s+)?)cube\.query_tag\b"#).unwrap()
+        });
+        SET_QUERY_TAG_RE
+            .replace(&query, "${set}\"cube.query_tag\"")
+            .to_string()
+    };
+
     if let Some(qtrace) = qtrace {
         qtrace.set_replaced_query(&query)
     }
@@ -280,6 +307,282 @@ pub fn parse_sql_to_statement(
     }
 }
 
+/// Returns the tag from a `/* tag: ... */` comment of the query.
+/// Comments are dropped by the parser, that's why it's taken from the query text
+pub fn parse_query_tag_comment(query: &str) -> Option<String> {
+    static QUERY_TAG_COMMENT_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r#"/\*\s*(?i:tag):\s*(?P<tag>.*?)\s*\*/"#).unwrap());
+
+    QUERY_TAG_COMMENT_RE
+        .captures(query)
+        .map(|captures| captures["tag"].to_string())
+        .filter(|tag| !tag.is_empty())
+}
+
+/// LISTEN, UNLISTEN and NOTIFY statements from PostgreSQL.
+/// Our fork of sqlparser doesn't support them, that's why they are parsed separately
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotificationStatement {
+    Listen {
+        channel: String,
+    },
+    /// None means UNLISTEN *
+    Unlisten {
+        channel: Option<String>,
+    },
+    Notify {
+        channel: String,
+        payload: Option<String>,
+    },
+}
+
+impl fmt::Display for NotificationStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotificationStatement::Listen { channel } => write!(f, "LISTEN {}", channel),
+            NotificationStatement::Unlisten { channel: None } => write!(f, "UNLISTEN *"),
+            NotificationStatement::Unlisten {
+                channel: Some(channel),
+            } => write!(f, "UNLISTEN {}", channel),
+            NotificationStatement::Notify {
+                channel,
+                payload: None,
+            } => write!(f, "NOTIFY {}", channel),
+            NotificationStatement::Notify {
+                channel,
+                payload: Some(payload),
+            } => write!(
+                f,
+                "NOTIFY {}, '{}'",
+                channel,
+                escape_single_quote_string(payload)
+            ),
+        }
+    }
+}
+
+/// Returns None if query is not a LISTEN/UNLISTEN/NOTIFY statement,
+/// then it should be parsed by parse_sql_to_statements
+pub fn parse_notification_statement(
+    query: &str,
+) -> CompilationResult<Option<NotificationStatement>> {
+    let tokens = match Tokenizer::new(&PostgreSqlDialect {}, query).tokenize() {
+        Ok(tokens) => tokens,
+        // Error will be reported by the main parser
+        Err(_) => return Ok(None),
+    };
+    let mut tokens = tokens
+        .into_iter()
+        .filter(|t| !matches!(t, Token::Whitespace(_) | Token::EOF))
+        .collect::<Vec<_>>();
+
+    let command = match tokens.first() {
+        Some(Token::Word(w)) if w.quote_style.is_none() => w.value.to_uppercase(),
+        _ => return Ok(None),
+    };
+    if !matches!(command.as_str(), "LISTEN" | "UNLISTEN" | "NOTIFY") {
+        return Ok(None);
+    }
+
+    if let Some(Token::SemiColon) = tokens.last() {
+        tokens.pop();
+    }
+
+    let syntax_error = |token: Option<&Token>| {
+        let near = match token {
+            Some(token) => token.to_string(),
+            None => "end of input".to_string(),
+        };
+
+        CompilationError::user(format!(
+            "Unable to parse: syntax error at or near \"{}\"",
+            near
+        ))
+        .with_meta(Some(HashMap::from([(
+            "query".to_string(),
+            query.to_string(),
+        )])))
+    };
+
+    let channel = match tokens.get(1) {
+        Some(Token::Word(w)) => Some(match w.quote_style {
+            Some(_) => w.value.clone(),
+            None => w.value.to_lowercase(),
+        }),
+        Some(Token::Mul) if command == "UNLISTEN" => None,
+        token => return Err(syntax_error(token)),
+    };
+
+    let (stmt, rest) = match (command.as_str(), channel) {
+        ("LISTEN", Some(channel)) => (NotificationStatement::Listen { channel }, 2),
+        ("UNLISTEN", channel) => (NotificationStatement::Unlisten { channel }, 2),
+        ("NOTIFY", Some(channel)) => match (tokens.get(2), tokens.get(3)) {
+            (None, _) => (
+                NotificationStatement::Notify {
+                    channel,
+                    payload: None,
+                },
+                2,
+            ),
+            (Some(Token::Comma), Some(Token::SingleQuotedString(payload))) => (
+                NotificationStatement::Notify {
+                    channel,
+                    payload: Some(payload.clone()),
+                },
+                4,
+            ),
+            (Some(Token::Comma), token) => return Err(syntax_error(token)),
+            (token, _) => return Err(syntax_error(token)),
+        },
+        _ => return Err(syntax_error(tokens.get(1))),
+    };
+
+    if let Some(token) = tokens.get(rest) {
+        return Err(syntax_error(Some(token)));
+    }
+
+    Ok(Some(stmt))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExplainFormat {
+    Text,
+    Json,
+}
+
+/// Options of EXPLAIN (option [, ...]) statement.
+/// Our fork of sqlparser doesn't support the parenthesized form, that's why it's parsed separately
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExplainOptions {
+    pub analyze: bool,
+    pub verbose: bool,
+    pub format: ExplainFormat,
+    /// Show Cube load requests, SQL of wrapped scans and rewrite statistics
+    pub cube: bool,
+}
+
+impl Default for ExplainOptions {
+    fn default() -> Self {
+        Self {
+            analyze: false,
+            verbose: false,
+            format: ExplainFormat::Text,
+            cube: false,
+        }
+    }
+}
+
+/// Returns None if query is not an EXPLAIN statement with options,
+/// then it should be parsed by parse_sql_to_statements
+pub fn parse_explain_statement(
+    query: &str,
+    protocol: DatabaseProtocol,
+    qtrace: &mut Option<Qtrace>,
+) -> CompilationResult<Option<(ExplainOptions, Statement)>> {
+    let tokens = match Tokenizer::new(&PostgreSqlDialect {}, query).tokenize() {
+        Ok(tokens) => tokens,
+        // Error will be reported by the main parser
+        Err(_) => return Ok(None),
+    };
+
+    let mut tokens = tokens.iter();
+    // Byte length of the consumed part of the query, tokens before the closing parenthesis
+    // are words, numbers and punctuation which are displayed as in the query
+    let mut consumed = 0;
+    let mut next_token = || {
+        for token in tokens.by_ref() {
+            consumed += token.to_string().len();
+            if !matches!(token, Token::Whitespace(_)) {
+                return Some(token);
+            }
+        }
+
+        None
+    };
+
+    match next_token() {
+        Some(Token::Word(w))
+            if w.quote_style.is_none() && w.value.eq_ignore_ascii_case("explain") => {}
+        _ => return Ok(None),
+    }
+    match next_token() {
+        Some(Token::LParen) => {}
+        _ => return Ok(None),
+    }
+
+    let syntax_error = |message: String| {
+        CompilationError::user(message).with_meta(Some(HashMap::from([(
+            "query".to_string(),
+            query.to_string(),
+        )])))
+    };
+    let near = |token: Option<&Token>| match token {
+        Some(token) => format!("Unable to parse: syntax error at or near \"{}\"", token),
+        None => "Unable to parse: syntax error at end of input".to_string(),
+    };
+
+    let mut options = ExplainOptions::default();
+    loop {
+        let name = match next_token() {
+            Some(Token::Word(w)) => w.value.to_lowercase(),
+            token => return Err(syntax_error(near(token))),
+        };
+        let (value, separator) = match next_token() {
+            Some(Token::Word(w)) => (Some(w.value.to_lowercase()), next_token()),
+            Some(Token::Number(n, _)) => (Some(n.clone()), next_token()),
+            token => (None, token),
+        };
+
+        let flag = || match value.as_deref() {
+            None | Some("true") | Some("on") | Some("1") => Ok(true),
+            Some("false") | Some("off") | Some("0") => Ok(false),
+            Some(value) => Err(syntax_error(format!(
+                r#"{} requires a Boolean value, got: "{}""#,
+                name.to_uppercase(),
+                value
+            ))),
+        };
+        match name.as_str() {
+            "analyze" => options.analyze = flag()?,
+            "verbose" => options.verbose = flag()?,
+            "cube" => options.cube = flag()?,
+            "format" => {
+                options.format = match value.as_deref() {
+                    Some("text") => ExplainFormat::Text,
+                    Some("json") => ExplainFormat::Json,
+                    Some(value) => {
+                        return Err(syntax_error(format!(
+                            r#"unrecognized value for EXPLAIN option "format": "{}""#,
+                            value
+                        )))
+                    }
+                    None => return Err(syntax_error(near(separator))),
+                }
+            }
+            // PostgreSQL options which don't change the output of Cube plans
+            "costs" | "buffers" | "timing" | "summary" | "settings" | "wal" => {
+                flag()?;
+            }
+            _ => {
+                return Err(syntax_error(format!(
+                    r#"unrecognized EXPLAIN option "{}""#,
+                    name
+                )))
+            }
+        }
+
+        match separator {
+            Some(Token::Comma) => continue,
+            Some(Token::RParen) => break,
+            token => return Err(syntax_error(near(token))),
+        }
+    }
+
+    let statement = parse_sql_to_statement(&query[consumed..].to_string(), protocol, qtrace)?;
+
+    Ok(Some((options, statement)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -387,4 +690,132 @@ mod tests {
             Err(err) => panic!("{}", err),
         }
     }
+
+    #[test]
+    fn test_notification_statements() {
+        assert_eq!(
+            parse_notification_statement("LISTEN MyChannel").unwrap(),
+            Some(NotificationStatement::Listen {
+                channel: "mychannel".to_string()
+            })
+        );
+        assert_eq!(
+            parse_notification_statement("listen \"MyChannel\";").unwrap(),
+            Some(NotificationStatement::Listen {
+                channel: "MyChannel".to_string()
+            })
+        );
+        assert_eq!(
+            parse_notification_statement("UNLISTEN *").unwrap(),
+            Some(NotificationStatement::Unlisten { channel: None })
+        );
+        assert_eq!(
+            parse_notification_statement("UNLISTEN ch").unwrap(),
+            Some(NotificationStatement::Unlisten {
+                channel: Some("ch".to_string())
+            })
+        );
+        assert_eq!(
+            parse_notification_statement("NOTIFY ch").unwrap(),
+            Some(NotificationStatement::Notify {
+                channel: "ch".to_string(),
+                payload: None,
+            })
+        );
+        assert_eq!(
+            parse_notification_statement("NOTIFY ch, 'it''s done';").unwrap(),
+            Some(NotificationStatement::Notify {
+                channel: "ch".to_string(),
+                payload: Some("it's done".to_string()),
+            })
+        );
+        assert_eq!(parse_notification_statement("SELECT 1").unwrap(), None);
+    }
+
+    #[test]
+    fn test_explain_statements() {
+        let (options, statement) = parse_explain_statement(
+            "EXPLAIN (FORMAT JSON, CUBE) SELECT 'it''s' AS s",
+            DatabaseProtocol::PostgreSQL,
+            &mut None,
+        )
+        .unwrap()
+        .expect("explain statement");
+        assert_eq!(
+            options,
+            ExplainOptions {
+                analyze: false,
+                verbose: false,
+                format: ExplainFormat::Json,
+                cube: true,
+            }
+        );
+        assert_eq!(statement.to_string(), "SELECT 'it''s' AS s");
+
+        let (options, _) = parse_explain_statement(
+            "explain (verbose on, costs off, cube false) select 1",
+            DatabaseProtocol::PostgreSQL,
+            &mut None,
+        )
+        .unwrap()
+        .expect("explain statement");
+        assert!(options.verbose);
+        assert!(!options.cube);
+
+        for query in ["EXPLAIN SELECT 1", "SELECT 1", "EXPLAINX (CUBE) SELECT 1"] {
+            assert_eq!(
+                parse_explain_statement(query, DatabaseProtocol::PostgreSQL, &mut None).unwrap(),
+                None
+            );
+        }
+
+        for (query, error) in [
+            ("EXPLAIN (FORMAT XML) SELECT 1", "unrecognized value"),
+            ("EXPLAIN (PLAN) SELECT 1", "unrecognized EXPLAIN option"),
+            ("EXPLAIN (CUBE maybe) SELECT 1", "requires a Boolean value"),
+            (
+                "EXPLAIN (CUBE, FORMAT JSON SELECT 1",
+                "syntax error at or near",
+            ),
+        ] {
+            match parse_explain_statement(query, DatabaseProtocol::PostgreSQL, &mut None) {
+                Ok(r) => panic!("Query ({}) should throw an error, actual: {:?}", query, r),
+                Err(err) => assert!(err.to_string().contains(error), "{}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn test_query_tag_comment() {
+        for (query, expected) in [
+            ("/* tag: dashboard-42 */ SELECT 1", Some("dashboard-42")),
+            ("SELECT 1 /*TAG:nightly job*/", Some("nightly job")),
+            ("SELECT 1 /* tag: */", None),
+            ("SELECT 1 /* dashboard-42 */", None),
+        ] {
+            assert_eq!(
+                parse_query_tag_comment(query).as_deref(),
+                expected,
+                "{}",
+                query
+            );
+        }
+    }
+
+    #[test]
+    fn test_notification_statements_errors() {
+        for query in [
+            "LISTEN",
+            "LISTEN *",
+            "LISTEN a b",
+            "NOTIFY ch,",
+            "NOTIFY ch, 1",
+            "NOTIFY ch 'payload'",
+        ] {
+            match parse_notification_statement(query) {
+                Ok(r) => panic!("Query ({}) should throw an error, actual: {:?}", query, r),
+                Err(err) => assert!(err.to_string().contains("syntax error at or near")),
+            }
+        }
+    }
 }
@@ -37,6 +37,9 @@ pub enum CommandCompletion {
     DeallocateAll,
     Discard(String),
     DropTable,
+    Listen,
+    Unlisten,
+    Notify,
 }
 
 pub enum QueryPlan {
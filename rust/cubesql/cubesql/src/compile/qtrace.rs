@@ -25,7 +25,7 @@ impl Qtrace {
     // Version of the qtrace schema, (major, minor).
     // The major component should be bumped whenever backwards incompatible changes are introduced.
     fn version() -> (u64, u64) {
-        (1, 0)
+        (1, 1)
     }
 
     pub fn new(original_query: &str) -> Option<Self> {
@@ -90,6 +90,10 @@ impl Qtrace {
         self.statement(|stmt| stmt.set_best_graph(nodes));
     }
 
+    pub fn add_rewrite_budget_exhausted(&mut self, budget: QtraceRewriteBudget) {
+        self.statement(|stmt| stmt.rewrite_budget_exhausted.push(budget));
+    }
+
     pub fn set_best_plan_and_cube_scans(&mut self, plan: &LogicalPlan) {
         self.statement(|stmt| stmt.set_best_plan_and_cube_scans(plan));
     }
@@ -152,6 +156,7 @@ pub struct QtraceStatement {
     best_plan: Option<String>,
     cube_scans: Option<Vec<V1LoadRequestQuery>>,
     error_message: Option<String>,
+    rewrite_budget_exhausted: Vec<QtraceRewriteBudget>,
 }
 
 impl QtraceStatement {
@@ -167,6 +172,7 @@ impl QtraceStatement {
             best_plan: None,
             cube_scans: None,
             error_message: None,
+            rewrite_budget_exhausted: vec![],
         }
     }
 
@@ -244,6 +250,34 @@ impl QtraceEgraphIteration {
     }
 }
 
+/// Rewrite stage which ran out of the rewrite budget, plan is extracted from the unfinished e-graph
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QtraceRewriteBudget {
+    stage: String,
+    reason: String,
+    largest_eclasses: Vec<QtraceEclass>,
+}
+
+impl QtraceRewriteBudget {
+    const LARGEST_ECLASSES: usize = 10;
+
+    pub fn make(stage: &str, reason: &str, egraph: &CubeEGraph) -> Self {
+        let mut eclasses = egraph.classes().collect::<Vec<_>>();
+        eclasses.sort_by_key(|eclass| std::cmp::Reverse(eclass.nodes.len()));
+
+        Self {
+            stage: stage.to_string(),
+            reason: reason.to_string(),
+            largest_eclasses: eclasses
+                .into_iter()
+                .take(Self::LARGEST_ECLASSES)
+                .map(|eclass| QtraceEclass::make(eclass))
+                .collect(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QtraceEclass {
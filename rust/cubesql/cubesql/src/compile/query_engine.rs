@@ -24,6 +24,7 @@ use crate::{
     },
     config::ConfigObj,
     sql::{
+        audit_log::plan_audit_details,
         compiler_cache::{CompilerCache, CompilerCacheEntry},
         statement::SensitiveDataSanitizer,
         SessionManager, SessionState,
@@ -96,6 +97,7 @@ pub trait QueryEngine {
         state: Arc<SessionState>,
     ) -> CompilationResult<(QueryPlan, Self::PlanMetadataType)> {
         let cache_entry = self.get_cache_entry(state.clone()).await?;
+        state.reset_planning_stats();
 
         let planning_start = SystemTime::now();
         if let Some(span_id) = span_id.as_ref() {
@@ -309,6 +311,10 @@ pub trait QueryEngine {
         if let Some(qtrace) = qtrace {
             qtrace.set_best_plan_and_cube_scans(&rewrite_plan);
         }
+        if let Some(span_id) = &span_id {
+            let (pushed_down, load_requests) = plan_audit_details(&rewrite_plan);
+            span_id.set_audit_plan(pushed_down, load_requests);
+        }
 
         Ok((QueryPlan::DataFusionSelect(rewrite_plan, ctx), metadata))
     }
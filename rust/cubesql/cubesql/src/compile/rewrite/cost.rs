@@ -351,6 +351,12 @@ impl CubePlanCostAndState {
 }
 
 impl CubePlanCost {
+    /// Plan with replacers or member errors left can't be converted to an executable plan,
+    /// it's possible only when rewrites were stopped before completion
+    pub fn is_executable(&self) -> bool {
+        self.replacers == 0 && self.member_errors == 0
+    }
+
     pub fn add_child(&self, other: &Self) -> Self {
         Self {
             replacers: self.replacers + other.replacers,
@@ -59,6 +59,12 @@ macro_rules! variant_field_struct {
                     write!(f, "{}", self.0)
                 }
             }
+
+            impl [<$variant $var_field:camel>] {
+                pub fn to_persisted(&self) -> Option<String> {
+                    Some(format!("{}:{}", std::stringify!([<$variant $var_field:camel>]), self.0))
+                }
+            }
         }
     };
 
@@ -83,6 +89,12 @@ macro_rules! variant_field_struct {
                     write!(f, "{:?}", self.0)
                 }
             }
+
+            impl [<$variant $var_field:camel>] {
+                pub fn to_persisted(&self) -> Option<String> {
+                    Some(format!("{}:{}", std::stringify!([<$variant $var_field:camel>]), self.0))
+                }
+            }
         }
     };
 
@@ -107,6 +119,12 @@ macro_rules! variant_field_struct {
                     write!(f, "{:?}", self.0)
                 }
             }
+
+            impl [<$variant $var_field:camel>] {
+                pub fn to_persisted(&self) -> Option<String> {
+                    Some(format!("{}:{}", std::stringify!([<$variant $var_field:camel>]), self.0))
+                }
+            }
         }
     };
 
@@ -135,6 +153,16 @@ macro_rules! variant_field_struct {
                     write!(f, "{:?}", self.0)
                 }
             }
+
+            impl [<$variant $var_field:camel>] {
+                pub fn to_persisted(&self) -> Option<String> {
+                    let value = match self.0 {
+                        Some(value) => value.to_string(),
+                        None => "None".to_string(),
+                    };
+                    Some(format!("{}:{}", std::stringify!([<$variant $var_field:camel>]), value))
+                }
+            }
         }
     };
 
@@ -163,6 +191,16 @@ macro_rules! variant_field_struct {
                     write!(f, "{:?}", self.0)
                 }
             }
+
+            impl [<$variant $var_field:camel>] {
+                pub fn to_persisted(&self) -> Option<String> {
+                    let value = match &self.0 {
+                        Some(value) => value.join(","),
+                        None => "None".to_string(),
+                    };
+                    Some(format!("{}:{}", std::stringify!([<$variant $var_field:camel>]), value))
+                }
+            }
         }
     };
 
@@ -191,6 +229,16 @@ macro_rules! variant_field_struct {
                     write!(f, "{:?}", self.0)
                 }
             }
+
+            impl [<$variant $var_field:camel>] {
+                pub fn to_persisted(&self) -> Option<String> {
+                    let value = match &self.0 {
+                        Some(value) => value.as_str(),
+                        None => "None",
+                    };
+                    Some(format!("{}:{}", std::stringify!([<$variant $var_field:camel>]), value))
+                }
+            }
         }
     };
 
@@ -201,8 +249,15 @@ macro_rules! variant_field_struct {
 
             impl FromStr for [<$variant $var_field:camel>] {
                 type Err = $crate::compile::rewrite::language::LanguageParseError;
-                fn from_str(_s: &str) -> Result<Self, Self::Err> {
-                    Err(Self::Err::NotSupported)
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    const PREFIX: &'static str = concat!(std::stringify!([<$variant $var_field:camel>]), ":");
+                    let json = s
+                        .strip_prefix(PREFIX)
+                        .ok_or(Self::Err::ShouldStartWith(PREFIX))?;
+
+                    let (relation, name) = serde_json::from_str::<(Option<String>, String)>(json)
+                        .map_err(|_| Self::Err::ShouldMatch("Column"))?;
+                    Ok([<$variant $var_field:camel>](Column { relation, name }))
                 }
             }
 
@@ -211,6 +266,13 @@ macro_rules! variant_field_struct {
                     write!(f, "{}", self.0)
                 }
             }
+
+            impl [<$variant $var_field:camel>] {
+                pub fn to_persisted(&self) -> Option<String> {
+                    let json = serde_json::to_string(&(&self.0.relation, &self.0.name)).ok()?;
+                    Some(format!("{}:{}", std::stringify!([<$variant $var_field:camel>]), json))
+                }
+            }
         }
     };
 
@@ -221,8 +283,20 @@ macro_rules! variant_field_struct {
 
             impl FromStr for [<$variant $var_field:camel>] {
                 type Err = $crate::compile::rewrite::language::LanguageParseError;
-                fn from_str(_s: &str) -> Result<Self, Self::Err> {
-                    Err(Self::Err::NotSupported)
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    const PREFIX: &'static str = concat!(std::stringify!([<$variant $var_field:camel>]), ":");
+                    let json = s
+                        .strip_prefix(PREFIX)
+                        .ok_or(Self::Err::ShouldStartWith(PREFIX))?;
+
+                    let columns = serde_json::from_str::<Vec<(Option<String>, String)>>(json)
+                        .map_err(|_| Self::Err::ShouldMatch("Vec<Column>"))?;
+                    Ok([<$variant $var_field:camel>](
+                        columns
+                            .into_iter()
+                            .map(|(relation, name)| Column { relation, name })
+                            .collect(),
+                    ))
                 }
             }
 
@@ -231,6 +305,18 @@ macro_rules! variant_field_struct {
                     write!(f, "{:?}", self.0)
                 }
             }
+
+            impl [<$variant $var_field:camel>] {
+                pub fn to_persisted(&self) -> Option<String> {
+                    let columns = self
+                        .0
+                        .iter()
+                        .map(|column| (&column.relation, &column.name))
+                        .collect::<Vec<_>>();
+                    let json = serde_json::to_string(&columns).ok()?;
+                    Some(format!("{}:{}", std::stringify!([<$variant $var_field:camel>]), json))
+                }
+            }
         }
     };
 
@@ -241,8 +327,20 @@ macro_rules! variant_field_struct {
 
             impl FromStr for [<$variant $var_field:camel>] {
                 type Err = $crate::compile::rewrite::language::LanguageParseError;
-                fn from_str(_s: &str) -> Result<Self, Self::Err> {
-                    Err(Self::Err::NotSupported)
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    const PREFIX: &'static str = concat!(std::stringify!([<$variant $var_field:camel>]), ":");
+                    let json = s
+                        .strip_prefix(PREFIX)
+                        .ok_or(Self::Err::ShouldStartWith(PREFIX))?;
+
+                    let columns = serde_json::from_str::<Vec<(Option<String>, String)>>(json)
+                        .map_err(|_| Self::Err::ShouldMatch("Vec<Column>"))?;
+                    Ok([<$variant $var_field:camel>](
+                        columns
+                            .into_iter()
+                            .map(|(relation, name)| Column { relation, name })
+                            .collect(),
+                    ))
                 }
             }
 
@@ -251,6 +349,18 @@ macro_rules! variant_field_struct {
                     write!(f, "{:?}", self.0)
                 }
             }
+
+            impl [<$variant $var_field:camel>] {
+                pub fn to_persisted(&self) -> Option<String> {
+                    let columns = self
+                        .0
+                        .iter()
+                        .map(|column| (&column.relation, &column.name))
+                        .collect::<Vec<_>>();
+                    let json = serde_json::to_string(&columns).ok()?;
+                    Some(format!("{}:{}", std::stringify!([<$variant $var_field:camel>]), json))
+                }
+            }
         }
     };
 
@@ -261,8 +371,12 @@ macro_rules! variant_field_struct {
 
             impl FromStr for [<$variant $var_field:camel>] {
                 type Err = $crate::compile::rewrite::language::LanguageParseError;
-                fn from_str(_s: &str) -> Result<Self, Self::Err> {
-                    Err(Self::Err::NotSupported)
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    const PREFIX: &'static str = concat!(std::stringify!([<$variant $var_field:camel>]), ":");
+                    if let Some(suffix) = s.strip_prefix(PREFIX) {
+                        return Ok([<$variant $var_field:camel>](suffix.to_string()));
+                    }
+                    Err(Self::Err::ShouldStartWith(PREFIX))
                 }
             }
 
@@ -271,6 +385,12 @@ macro_rules! variant_field_struct {
                     write!(f, "{}", self.0)
                 }
             }
+
+            impl [<$variant $var_field:camel>] {
+                pub fn to_persisted(&self) -> Option<String> {
+                    Some(format!("{}:{}", std::stringify!([<$variant $var_field:camel>]), self.0))
+                }
+            }
         }
     };
 
@@ -281,8 +401,12 @@ macro_rules! variant_field_struct {
 
             impl FromStr for [<$variant $var_field:camel>] {
                 type Err = $crate::compile::rewrite::language::LanguageParseError;
-                fn from_str(_s: &str) -> Result<Self, Self::Err> {
-                    Err(Self::Err::NotSupported)
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    const PREFIX: &'static str = concat!(std::stringify!([<$variant $var_field:camel>]), ":");
+                    if let Some(suffix) = s.strip_prefix(PREFIX) {
+                        return Ok([<$variant $var_field:camel>](suffix.to_string()));
+                    }
+                    Err(Self::Err::ShouldStartWith(PREFIX))
                 }
             }
 
@@ -291,6 +415,12 @@ macro_rules! variant_field_struct {
                     write!(f, "{}", self.0)
                 }
             }
+
+            impl [<$variant $var_field:camel>] {
+                pub fn to_persisted(&self) -> Option<String> {
+                    Some(format!("{}:{}", std::stringify!([<$variant $var_field:camel>]), self.0))
+                }
+            }
         }
     };
 
@@ -315,6 +445,12 @@ macro_rules! variant_field_struct {
                     write!(f, "{}", self.0)
                 }
             }
+
+            impl [<$variant $var_field:camel>] {
+                pub fn to_persisted(&self) -> Option<String> {
+                    Some(format!("{}:{}", std::stringify!([<$variant $var_field:camel>]), self.0))
+                }
+            }
         }
     };
 
@@ -578,6 +714,12 @@ macro_rules! variant_field_struct {
             }
 
             impl core::cmp::Eq for [<$variant $var_field:camel>] {}
+
+            impl [<$variant $var_field:camel>] {
+                pub fn to_persisted(&self) -> Option<String> {
+                    Some(format!("{}:{}", std::stringify!([<$variant $var_field:camel>]), self))
+                }
+            }
         }
     };
 
@@ -613,6 +755,12 @@ macro_rules! variant_field_struct {
                     write!(f, "{}", self.0)
                 }
             }
+
+            impl [<$variant $var_field:camel>] {
+                pub fn to_persisted(&self) -> Option<String> {
+                    Some(format!("{}:{}", std::stringify!([<$variant $var_field:camel>]), self.0))
+                }
+            }
         }
     };
 
@@ -652,6 +800,19 @@ macro_rules! variant_field_struct {
                 }
             }
 
+            impl [<$variant $var_field:camel>] {
+                pub fn to_persisted(&self) -> Option<String> {
+                    let value = match &self.0 {
+                        ScalarValue::Utf8(Some(value)) => format!("s:{}", value),
+                        ScalarValue::Boolean(Some(value)) => format!("b:{}", value),
+                        ScalarValue::Int64(Some(value)) => format!("i:{}", value),
+                        ScalarValue::Float64(Some(value)) => format!("f:{}", value),
+                        _ => return None,
+                    };
+                    Some(format!("{}:{}", std::stringify!([<$variant $var_field:camel>]), value))
+                }
+            }
+
             impl core::cmp::Ord for [<$variant $var_field:camel>] {
                 fn cmp(&self, other: &Self) -> core::cmp::Ordering {
                     self.partial_cmp(&other).unwrap()
@@ -685,6 +846,81 @@ macro_rules! variant_field_struct {
         }
     };
 
+    ($variant:ident, $var_field:ident, Vec<String>) => {
+        $crate::variant_field_struct!(@json_struct $variant, $var_field, Vec<String>);
+    };
+
+    ($variant:ident, $var_field:ident, Vec<(String, String)>) => {
+        $crate::variant_field_struct!(@json_struct $variant, $var_field, Vec<(String, String)>);
+    };
+
+    ($variant:ident, $var_field:ident, Vec<((String, String), String)>) => {
+        $crate::variant_field_struct!(@json_struct $variant, $var_field, Vec<((String, String), String)>);
+    };
+
+    ($variant:ident, $var_field:ident, Option<Vec<usize>>) => {
+        $crate::variant_field_struct!(@json_struct $variant, $var_field, Option<Vec<usize>>);
+    };
+
+    ($variant:ident, $var_field:ident, Option<char>) => {
+        $crate::variant_field_struct!(@json_struct $variant, $var_field, Option<char>);
+    };
+
+    // Same as the generic field below, but it can be persisted as JSON
+    (@json_struct $variant:ident, $var_field:ident, $var_field_type:ty) => {
+        paste::item! {
+            #[derive(Debug, PartialOrd, Clone)]
+            pub struct [<$variant $var_field:camel>]($var_field_type);
+
+            impl FromStr for [<$variant $var_field:camel>] {
+                type Err = $crate::compile::rewrite::language::LanguageParseError;
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    const PREFIX: &'static str = concat!(std::stringify!([<$variant $var_field:camel>]), ":");
+                    let json = s
+                        .strip_prefix(PREFIX)
+                        .ok_or(Self::Err::ShouldStartWith(PREFIX))?;
+
+                    serde_json::from_str::<$var_field_type>(json)
+                        .map(|value| [<$variant $var_field:camel>](value))
+                        .map_err(|_| Self::Err::ShouldMatch(std::stringify!($var_field_type)))
+                }
+            }
+
+            impl std::fmt::Display for [<$variant $var_field:camel>] {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    write!(f, "{:?}", self)
+                }
+            }
+
+            impl [<$variant $var_field:camel>] {
+                pub fn to_persisted(&self) -> Option<String> {
+                    let json = serde_json::to_string(&self.0).ok()?;
+                    Some(format!("{}:{}", std::stringify!([<$variant $var_field:camel>]), json))
+                }
+            }
+
+            impl core::cmp::Ord for [<$variant $var_field:camel>] {
+                fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+                    self.partial_cmp(&other).unwrap()
+                }
+            }
+
+            impl core::hash::Hash for [<$variant $var_field:camel>] {
+                fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                    self.0.hash(state);
+                }
+            }
+
+            impl core::cmp::PartialEq for [<$variant $var_field:camel>] {
+                fn eq(&self, other: &[<$variant $var_field:camel>]) -> bool {
+                    self.0 == other.0
+                }
+            }
+
+            impl core::cmp::Eq for [<$variant $var_field:camel>] {}
+        }
+    };
+
     ($variant:ident, $var_field:ident, $var_field_type:ty) => {
         paste::item! {
             #[derive(Debug, PartialOrd, Clone)]
@@ -703,6 +939,12 @@ macro_rules! variant_field_struct {
                 }
             }
 
+            impl [<$variant $var_field:camel>] {
+                pub fn to_persisted(&self) -> Option<String> {
+                    None
+                }
+            }
+
             impl core::cmp::Ord for [<$variant $var_field:camel>] {
                 fn cmp(&self, other: &Self) -> core::cmp::Ordering {
                     self.partial_cmp(&other).unwrap()
@@ -730,7 +972,7 @@ macro_rules! variant_field_struct {
 macro_rules! __plan_to_language {
     (@define_language $(#[$meta:meta])* $vis:vis enum $name:ident {} ->
      $decl:tt {$($matches:tt)*} $children:tt $children_mut:tt
-     $display:tt {$($from_op:tt)*} {$($type_decl:tt)*}
+     $display:tt $persisted:tt {$($from_op:tt)*} {$($type_decl:tt)*}
     ) => { paste::item! {
         $(#[$meta])*
         #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
@@ -760,6 +1002,13 @@ macro_rules! __plan_to_language {
             }
         }
 
+        impl $name {
+            /// Op which can be parsed back with `FromOp`, `None` if data of the node can't be persisted
+            pub fn to_persisted_op(&self) -> Option<String> {
+                match self $persisted
+            }
+        }
+
         impl egg::FromOp for $name {
             type Error = egg::FromOpError;
 
@@ -778,7 +1027,7 @@ macro_rules! __plan_to_language {
          $($variants:tt)*
      } ->
      { $($decl:tt)* } { $($matches:tt)* } { $($children:tt)* } { $($children_mut:tt)* }
-     { $($display:tt)* } { $($from_op:tt)* } { $($type_decl:tt)* }
+     { $($display:tt)* } { $($persisted:tt)* } { $($from_op:tt)* } { $($type_decl:tt)* }
     ) => {
         $crate::__plan_to_language!(
             @define_language
@@ -789,6 +1038,7 @@ macro_rules! __plan_to_language {
             { $($children)*      $name::$variant(ids) => egg::LanguageChildren::as_slice(ids), }
             { $($children_mut)*  $name::$variant(ids) => egg::LanguageChildren::as_mut_slice(ids), }
             { $($display)*       ($name::$variant(..), f) => f.write_str(std::stringify!($variant)), }
+            { $($persisted)*     $name::$variant(..) => Some(std::stringify!($variant).to_string()), }
             { $($from_op)*       (op, children) if op == std::stringify!($variant) && <$ids as egg::LanguageChildren>::can_be_length(children.len()) => {
                   let children = <$ids as egg::LanguageChildren>::from_vec(children);
                   Ok($name::$variant(children))
@@ -804,7 +1054,7 @@ macro_rules! __plan_to_language {
          $($variants:tt)*
      } ->
      { $($decl:tt)* } { $($matches:tt)* } { $($children:tt)* } { $($children_mut:tt)* }
-     { $($display:tt)* } { $($from_op:tt)* } { $($type_decl:tt)* }
+     { $($display:tt)* } { $($persisted:tt)* } { $($from_op:tt)* } { $($type_decl:tt)* }
     ) => {
         $crate::__plan_to_language!(
             @define_language
@@ -815,6 +1065,7 @@ macro_rules! __plan_to_language {
             { $($children)*      $name::[<$variant $var_field:camel>](ids) => egg::LanguageChildren::as_slice(ids), }
             { $($children_mut)*  $name::[<$variant $var_field:camel>](ids) => egg::LanguageChildren::as_mut_slice(ids), }
             { $($display)*       ($name::[<$variant $var_field:camel>](..), f) => f.write_str(std::stringify!([<$variant $var_field:camel>])), }
+            { $($persisted)*     $name::[<$variant $var_field:camel>](..) => Some(std::stringify!([<$variant $var_field:camel>]).to_string()), }
             { $($from_op)*       (op, children) if op == std::stringify!([<$variant $var_field:camel>]) && <$ids as egg::LanguageChildren>::can_be_length(children.len()) => {
                   let children = <$ids as egg::LanguageChildren>::from_vec(children);
                   Ok($name::[<$variant $var_field:camel>](children))
@@ -830,7 +1081,7 @@ macro_rules! __plan_to_language {
          $($variants:tt)*
      } ->
      { $($decl:tt)* } { $($matches:tt)* } { $($children:tt)* } { $($children_mut:tt)* }
-     { $($display:tt)* } { $($from_op:tt)* } { $($type_decl:tt)* }
+     { $($display:tt)* } { $($persisted:tt)* } { $($from_op:tt)* } { $($type_decl:tt)* }
     ) => {
         $crate::__plan_to_language!(
             @define_language $(#[$meta])* $vis enum $name
@@ -840,6 +1091,7 @@ macro_rules! __plan_to_language {
             { $($children)*      $name::[<$variant $var_field:camel>](_data) => &[], }
             { $($children_mut)*  $name::[<$variant $var_field:camel>](_data) => &mut [], }
             { $($display)*       ($name::[<$variant $var_field:camel>](data), f) => ::std::fmt::Display::fmt(data, f), }
+            { $($persisted)*     $name::[<$variant $var_field:camel>](data) => data.to_persisted(), }
             { $($from_op)*       (op, children) if op.parse::<[<$variant $var_field:camel>]>().is_ok() && children.is_empty() => Ok($name::[<$variant $var_field:camel>](op.parse().unwrap())), }
             {
                 $($type_decl)*
@@ -860,7 +1112,7 @@ macro_rules! __plan_to_language {
             @define_language
             $(#[$meta])*
             $vis enum $name $decl
-            -> {} {} {} {} {} {} {}
+            -> {} {} {} {} {} {} {} {}
         }
     };
 
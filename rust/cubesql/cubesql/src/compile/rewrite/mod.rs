@@ -2,6 +2,7 @@ pub mod analysis;
 pub mod converter;
 mod cost;
 pub mod language;
+pub mod persisted;
 pub mod rewriter;
 pub mod rules;
 
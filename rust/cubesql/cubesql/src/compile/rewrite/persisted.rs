@@ -0,0 +1,126 @@
+use crate::{
+    compile::rewrite::{rewriter::CubeEGraph, LogicalPlanLanguage},
+    CubeError,
+};
+use egg::{FromOp, Id, Language};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Rewritten e-graph in a form which can be stored outside of the process.
+/// Nodes are stored as ops which are parsed back with `FromOp`, so graphs with nodes
+/// which don't survive this round trip (e.g. most of the literals) can't be persisted.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PersistedEGraph {
+    // Class of the input graph -> class of the persisted graph
+    input_classes: Vec<(usize, usize)>,
+    classes: Vec<PersistedEClass>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedEClass {
+    id: usize,
+    nodes: Vec<PersistedENode>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedENode {
+    op: String,
+    children: Vec<usize>,
+}
+
+impl PersistedEGraph {
+    /// `input_ids` are classes of the graph which was rewritten into `egraph`.
+    pub fn from_egraph(egraph: &CubeEGraph, input_ids: &[Id]) -> Option<Self> {
+        let mut classes = Vec::with_capacity(egraph.number_of_classes());
+        for class in egraph.classes() {
+            let mut nodes = Vec::with_capacity(class.nodes.len());
+            for node in &class.nodes {
+                let op = node.to_persisted_op()?;
+                // Not every data type is printed losslessly, so check the node can be restored as is
+                match LogicalPlanLanguage::from_op(&op, node.children().to_vec()) {
+                    Ok(restored) if &restored == node => {}
+                    _ => return None,
+                }
+
+                nodes.push(PersistedENode {
+                    op,
+                    children: node
+                        .children()
+                        .iter()
+                        .map(|id| usize::from(egraph.find(*id)))
+                        .collect(),
+                });
+            }
+
+            classes.push(PersistedEClass {
+                id: usize::from(class.id),
+                nodes,
+            });
+        }
+
+        let input_classes = input_ids
+            .iter()
+            .map(|id| (usize::from(*id), usize::from(egraph.find(*id))))
+            .collect();
+
+        Some(Self {
+            input_classes,
+            classes,
+        })
+    }
+
+    /// Adds persisted nodes to the input graph, so ids of the input graph stay valid.
+    pub fn restore(&self, mut egraph: CubeEGraph) -> Result<CubeEGraph, CubeError> {
+        let mut class_ids = HashMap::<usize, Id>::new();
+        for (input_id, persisted_id) in &self.input_classes {
+            let input_id = Id::from(*input_id);
+            if let Some(id) = class_ids.get(persisted_id) {
+                egraph.union(*id, input_id);
+            } else {
+                class_ids.insert(*persisted_id, input_id);
+            }
+        }
+
+        // Node can be added only after all of its children, it takes a few passes for cycles
+        let mut pending = self
+            .classes
+            .iter()
+            .flat_map(|class| class.nodes.iter().map(move |node| (class.id, node)))
+            .collect::<Vec<_>>();
+        while !pending.is_empty() {
+            let mut rest = Vec::new();
+            for (class_id, node) in pending.iter() {
+                let Some(children) = node
+                    .children
+                    .iter()
+                    .map(|child| class_ids.get(child).cloned())
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    rest.push((*class_id, *node));
+                    continue;
+                };
+
+                let enode = LogicalPlanLanguage::from_op(&node.op, children).map_err(|e| {
+                    CubeError::internal(format!("Can't restore persisted e-graph node: {}", e))
+                })?;
+                let id = egraph.add(enode);
+                if let Some(existing_id) = class_ids.get(class_id) {
+                    egraph.union(*existing_id, id);
+                } else {
+                    class_ids.insert(*class_id, id);
+                }
+            }
+
+            if rest.len() == pending.len() {
+                return Err(CubeError::internal(
+                    "Can't restore persisted e-graph: some nodes are unreachable".to_string(),
+                ));
+            }
+            pending = rest;
+        }
+
+        egraph.rebuild();
+
+        Ok(egraph)
+    }
+}
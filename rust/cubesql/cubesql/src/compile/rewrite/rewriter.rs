@@ -1,6 +1,7 @@
 use crate::{
     compile::{
-        qtrace::{Qtrace, QtraceEclass, QtraceEgraphIteration},
+        engine::CubeTableProvider,
+        qtrace::{Qtrace, QtraceEclass, QtraceEgraphIteration, QtraceRewriteBudget},
         rewrite::{
             analysis::LogicalPlanAnalysis,
             converter::LanguageToLogicalPlanConverter,
@@ -20,7 +21,9 @@ use crate::{
     CubeError,
 };
 use datafusion::{
-    logical_plan::LogicalPlan, physical_plan::planner::DefaultPhysicalPlanner, scalar::ScalarValue,
+    logical_plan::{LogicalPlan, PlanVisitor},
+    physical_plan::planner::DefaultPhysicalPlanner,
+    scalar::ScalarValue,
 };
 use egg::{EGraph, Extractor, Id, IterationData, Language, Rewrite, Runner, StopReason};
 use serde::{Deserialize, Serialize};
@@ -41,6 +44,31 @@ pub struct Rewriter {
 
 pub type CubeRunner = Runner<LogicalPlanLanguage, LogicalPlanAnalysis, IterInfo>;
 
+/// Statistics of a single rewrite run, reported by EXPLAIN (CUBE)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RewriteStats {
+    pub stage: String,
+    pub iterations: usize,
+    pub egraph_nodes: usize,
+    pub egraph_classes: usize,
+    pub duration_ms: u64,
+}
+
+impl RewriteStats {
+    fn make(runner: &CubeRunner, stage: &str) -> Self {
+        let duration: f64 = runner.iterations.iter().map(|i| i.total_time).sum();
+
+        Self {
+            stage: stage.to_string(),
+            iterations: runner.iterations.len(),
+            egraph_nodes: runner.egraph.total_size(),
+            egraph_classes: runner.egraph.number_of_classes(),
+            duration_ms: (duration * 1000.0) as u64,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 struct DebugENodeId(String);
 
@@ -200,26 +228,38 @@ impl Rewriter {
     }
 
     pub fn rewrite_runner(cube_context: Arc<CubeContext>, egraph: CubeEGraph) -> CubeRunner {
+        // TODO move config to injector
+        let mut node_limit = env::var("CUBESQL_REWRITE_MAX_NODES")
+            .map(|v| v.parse::<usize>().unwrap())
+            .unwrap_or(10000);
+        let mut time_limit = Duration::from_secs(
+            env::var("CUBESQL_REWRITE_TIMEOUT")
+                .map(|v| v.parse::<u64>().unwrap())
+                .unwrap_or(30),
+        );
+
+        // Budget is shared by all rewrite stages of the query
+        let config_obj = cube_context.sessions.server.config_obj.clone();
+        if config_obj.rewrite_budget_max_nodes() > 0 {
+            node_limit = node_limit.min(config_obj.rewrite_budget_max_nodes());
+        }
+        if config_obj.rewrite_budget_timeout() > 0 {
+            let remaining = Duration::from_secs(config_obj.rewrite_budget_timeout())
+                .saturating_sub(cube_context.session_state.planning_elapsed());
+            time_limit = time_limit.min(remaining);
+        }
+
         let runner = CubeRunner::new(LogicalPlanAnalysis::new(
             cube_context,
             Arc::new(DefaultPhysicalPlanner::default()),
         ))
-        // TODO move config to injector
         .with_iter_limit(
             env::var("CUBESQL_REWRITE_MAX_ITERATIONS")
                 .map(|v| v.parse::<usize>().unwrap())
                 .unwrap_or(300),
         )
-        .with_node_limit(
-            env::var("CUBESQL_REWRITE_MAX_NODES")
-                .map(|v| v.parse::<usize>().unwrap())
-                .unwrap_or(10000),
-        )
-        .with_time_limit(Duration::from_secs(
-            env::var("CUBESQL_REWRITE_TIMEOUT")
-                .map(|v| v.parse::<u64>().unwrap())
-                .unwrap_or(30),
-        ))
+        .with_node_limit(node_limit)
+        .with_time_limit(time_limit)
         .with_scheduler(IncrementalScheduler::default());
 
         let runner = if IterInfo::egraph_debug_enabled() {
@@ -263,16 +303,20 @@ impl Rewriter {
             .rewrite_rules(cache_entry, false)
             .await?;
 
-        let (plan, qtrace_egraph_iterations) = tokio::task::spawn_blocking(move || {
-            let (runner, qtrace_egraph_iterations) =
-                Self::run_rewrites(&cube_context, egraph, rules, "intermediate")?;
+        let (plan, qtrace_egraph_iterations, qtrace_budget) =
+            tokio::task::spawn_blocking(move || {
+                let (runner, qtrace_egraph_iterations, qtrace_budget) =
+                    Self::run_rewrites(&cube_context, egraph, rules, "intermediate")?;
 
-            Ok::<_, CubeError>((runner.egraph, qtrace_egraph_iterations))
-        })
-        .await??;
+                Ok::<_, CubeError>((runner.egraph, qtrace_egraph_iterations, qtrace_budget))
+            })
+            .await??;
 
         if let Some(qtrace) = qtrace {
             qtrace.set_egraph_iterations(qtrace_egraph_iterations);
+            if let Some(qtrace_budget) = qtrace_budget {
+                qtrace.add_rewrite_budget_exhausted(qtrace_budget);
+            }
         }
 
         Ok(plan)
@@ -344,12 +388,12 @@ impl Rewriter {
             .rewrite_rules(cache_entry, true)
             .await?;
 
-        let (plan, qtrace_egraph_iterations, qtrace_best_graph) =
+        let (plan, qtrace_egraph_iterations, qtrace_best_graph, qtrace_budget) =
             tokio::task::spawn_blocking(move || {
-                let (runner, qtrace_egraph_iterations) =
+                let (runner, qtrace_egraph_iterations, qtrace_budget) =
                     Self::run_rewrites(&cube_context, egraph, rules, "final")?;
 
-                let best = if top_down_extractor {
+                let (best_cost, best) = if top_down_extractor {
                     let mut extractor = TopDownExtractor::new(
                         &runner.egraph,
                         BestCubePlan::new(cube_context.meta.clone()),
@@ -359,7 +403,7 @@ impl Rewriter {
                         return Err(CubeError::internal("Unable to find best plan".to_string()));
                     };
                     log::debug!("Best cost: {:#?}", best_cost);
-                    best
+                    (best_cost, best)
                 } else {
                     let extractor = Extractor::new(
                         &runner.egraph,
@@ -367,8 +411,16 @@ impl Rewriter {
                     );
                     let (best_cost, best) = extractor.find_best(root);
                     log::debug!("Best cost: {:#?}", best_cost);
-                    best
+                    (best_cost.cost, best)
                 };
+                if let Some(reason) = cube_context.session_state.planning_stats().rewrite_budget_exhausted {
+                    if !best_cost.is_executable() {
+                        return Err(CubeError::user(format!(
+                            "Can't find rewrite due to {}: the best plan found so far is not executable",
+                            reason
+                        )));
+                    }
+                }
                 let qtrace_best_graph = if Qtrace::is_enabled() {
                     best.as_ref().iter().cloned().collect()
                 } else {
@@ -386,6 +438,7 @@ impl Rewriter {
                     converter.to_logical_plan(new_root),
                     qtrace_egraph_iterations,
                     qtrace_best_graph,
+                    qtrace_budget,
                 ))
             })
             .await??;
@@ -393,22 +446,52 @@ impl Rewriter {
         if let Some(qtrace) = qtrace {
             qtrace.set_egraph_iterations(qtrace_egraph_iterations);
             qtrace.set_best_graph(&qtrace_best_graph);
+            if let Some(qtrace_budget) = qtrace_budget {
+                qtrace.add_rewrite_budget_exhausted(qtrace_budget);
+            }
         }
 
-        plan
+        let plan = plan?;
+        if let Some(reason) = self
+            .cube_context
+            .session_state
+            .planning_stats()
+            .rewrite_budget_exhausted
+        {
+            if has_unrewritten_cube_table_scans(&plan)? {
+                return Err(CubeError::user(format!(
+                    "Can't find rewrite due to {}: the best plan found so far is not executable",
+                    reason
+                )));
+            }
+        }
+
+        Ok(plan)
     }
 
+    /// When the rewrite budget runs out and fallback is enabled,
+    /// the unfinished e-graph is returned to extract the best plan found so far
     fn run_rewrites(
         cube_context: &Arc<CubeContext>,
         egraph: CubeEGraph,
         rules: Arc<Vec<CubeRewrite>>,
         stage: &str,
-    ) -> Result<(CubeRunner, Vec<QtraceEgraphIteration>), CubeError> {
+    ) -> Result<
+        (
+            CubeRunner,
+            Vec<QtraceEgraphIteration>,
+            Option<QtraceRewriteBudget>,
+        ),
+        CubeError,
+    > {
         let runner = Self::rewrite_runner(cube_context.clone(), egraph);
         let mut runner = runner.run(rules.iter());
         if !IterInfo::egraph_debug_enabled() {
             log::debug!("Iterations: {:?}", runner.iterations);
         }
+        cube_context
+            .session_state
+            .add_rewrite_stats(RewriteStats::make(&runner, stage));
         let stop_reason = &runner.iterations[runner.iterations.len() - 1].stop_reason;
         let stop_reason = match stop_reason {
             None => Some("timeout reached".to_string()),
@@ -427,11 +510,35 @@ impl Rewriter {
             LogicalPlanAnalysis::store_egraph_debug_state(&mut runner.egraph);
             write_debug_states(&runner, stage)?;
         }
+        let mut qtrace_budget = None;
         if let Some(stop_reason) = stop_reason {
-            return Err(CubeError::user(format!(
-                "Can't find rewrite due to {}",
+            if !cube_context
+                .sessions
+                .server
+                .config_obj
+                .rewrite_budget_fallback()
+            {
+                return Err(CubeError::user(format!(
+                    "Can't find rewrite due to {}",
+                    stop_reason
+                )));
+            }
+
+            log::warn!(
+                "Rewrite budget is exhausted on {} stage due to {}, the best plan found so far will be used",
+                stage,
                 stop_reason
-            )));
+            );
+            if Qtrace::is_enabled() {
+                qtrace_budget = Some(QtraceRewriteBudget::make(
+                    stage,
+                    &stop_reason,
+                    &runner.egraph,
+                ));
+            }
+            cube_context
+                .session_state
+                .set_rewrite_budget_exhausted(format!("{} on {} stage", stop_reason, stage));
         }
         let qtrace_egraph_iterations = if Qtrace::is_enabled() {
             runner
@@ -452,7 +559,7 @@ impl Rewriter {
         } else {
             vec![]
         };
-        Ok((runner, qtrace_egraph_iterations))
+        Ok((runner, qtrace_egraph_iterations, qtrace_budget))
     }
 
     pub fn sql_push_down_enabled() -> bool {
@@ -522,6 +629,38 @@ impl Rewriter {
     }
 }
 
+/// Table scans of cubes are replaced by CubeScan during rewrites and can't be executed as is
+fn has_unrewritten_cube_table_scans(plan: &LogicalPlan) -> Result<bool, CubeError> {
+    struct CubeTableScanVisitor {
+        found: bool,
+    }
+
+    impl PlanVisitor for CubeTableScanVisitor {
+        type Error = CubeError;
+
+        fn pre_visit(&mut self, plan: &LogicalPlan) -> Result<bool, Self::Error> {
+            if let LogicalPlan::TableScan(scan) = plan {
+                if scan
+                    .source
+                    .as_any()
+                    .downcast_ref::<CubeTableProvider>()
+                    .is_some()
+                {
+                    self.found = true;
+                    return Ok(false);
+                }
+            }
+
+            Ok(true)
+        }
+    }
+
+    let mut visitor = CubeTableScanVisitor { found: false };
+    plan.accept(&mut visitor)?;
+
+    Ok(visitor.found)
+}
+
 pub trait RewriteRules {
     fn rewrite_rules(&self) -> Vec<CubeRewrite>;
 }
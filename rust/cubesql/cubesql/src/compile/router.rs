@@ -7,25 +7,39 @@ use std::{collections::HashMap, sync::Arc};
 
 use crate::{
     compile::{
+        engine::df::{scan::CubeScanNode, wrapper::CubeScanWrappedSqlNode},
         error::{CompilationError, CompilationResult},
-        parser::parse_sql_to_statement,
+        parser::{
+            parse_explain_statement, parse_sql_to_statement, ExplainFormat, ExplainOptions,
+            NotificationStatement,
+        },
         DatabaseVariable, DatabaseVariablesToUpdate,
     },
     sql::{
+        database_variables::{
+            postgres::session_vars::{parse_timeout_ms, QUERY_TAG_VARIABLE, TIMEOUT_VARIABLES},
+            postgres_default_session_variables,
+        },
         dataframe,
+        extended::PreparedStatement,
+        notification_service::{Notification, NOTIFICATION_PAYLOAD_MAX_LENGTH},
         statement::{
-            ApproximateCountDistinctVisitor, CastReplacer, DateTokenNormalizeReplacer,
-            RedshiftDatePartReplacer, SensitiveDataSanitizer, ToTimestampReplacer,
-            UdfWildcardArgReplacer,
+            execute_arguments_to_bind_values, ApproximateCountDistinctVisitor, CastReplacer,
+            DateTokenNormalizeReplacer, RedshiftDatePartReplacer, SensitiveDataSanitizer,
+            ToTimestampReplacer, UdfWildcardArgReplacer,
         },
+        temp_tables::TempTableManager,
         ColumnFlags, ColumnType, Session, SessionManager, SessionState,
     },
-    transport::{MetaContext, SpanId},
+    transport::{
+        CubeMeta, CubeMetaType, MetaContext, SpanId, V1CubeMetaDimensionExt, V1CubeMetaMeasureExt,
+        V1CubeMetaSegmentExt,
+    },
 };
 use datafusion::{
     logical_plan::{
         plan::{Analyze, Explain, ToStringifiedPlan},
-        LogicalPlan, PlanType, ToDFSchema,
+        LogicalPlan, PlanType, PlanVisitor, ToDFSchema,
     },
     scalar::ScalarValue,
 };
@@ -77,7 +91,20 @@ impl QueryRouter {
                 statement,
                 verbose,
                 ..
-            } => self.explain_to_plan(statement, verbose, analyze).await,
+            } => {
+                let options = ExplainOptions {
+                    analyze,
+                    verbose,
+                    ..ExplainOptions::default()
+                };
+                self.explain_to_plan(statement, options).await
+            }
+            ast::Statement::Execute { name, parameters }
+                if self.state.protocol == DatabaseProtocol::PostgreSQL =>
+            {
+                self.execute_to_plan(&name, &parameters, qtrace, span_id)
+                    .await
+            }
             other => self.plan_query(&other, qtrace, span_id).await,
         }
     }
@@ -106,9 +133,15 @@ impl QueryRouter {
             (ast::Statement::SetVariable { key_values }, _) => {
                 self.set_variable_to_plan(&key_values).await
             }
+            (ast::Statement::ShowVariable { variable }, _) if is_semantic_layer_show(variable) => {
+                self.show_semantic_layer_to_plan(variable)
+            }
             (ast::Statement::ShowVariable { variable }, _) => {
                 self.show_variable_to_plan(variable, span_id.clone()).await
             }
+            (ast::Statement::ExplainTable { table_name, .. }, _) => {
+                self.describe_cube_to_plan(table_name)
+            }
             (ast::Statement::StartTransaction { .. }, DatabaseProtocol::PostgreSQL) => {
                 // TODO: Real support
                 Ok(QueryPlan::MetaOk(
@@ -154,11 +187,10 @@ impl QueryRouter {
             ) if columns.is_empty()
                 && constraints.is_empty()
                 && table_properties.is_empty()
-                && with_options.is_empty()
-                && *temporary =>
+                && with_options.is_empty() =>
             {
                 let stmt = ast::Statement::Query(query.clone());
-                self.create_table_to_plan(name, &stmt, qtrace, span_id.clone())
+                self.create_table_to_plan(name, *temporary, &stmt, qtrace, span_id.clone())
                     .await
             }
             (
@@ -190,6 +222,117 @@ impl QueryRouter {
         }
     }
 
+    /// LISTEN/UNLISTEN only changes the state of session, delivery of notifications is done by shim
+    pub fn notification_to_plan(
+        &self,
+        stmt: &NotificationStatement,
+    ) -> CompilationResult<QueryPlan> {
+        let completion = match stmt {
+            NotificationStatement::Listen { channel } => {
+                self.state.listen(channel.clone());
+
+                CommandCompletion::Listen
+            }
+            NotificationStatement::Unlisten { channel } => {
+                self.state.unlisten(channel.as_deref());
+
+                CommandCompletion::Unlisten
+            }
+            NotificationStatement::Notify { channel, payload } => {
+                let payload = payload.clone().unwrap_or_default();
+                if payload.len() >= NOTIFICATION_PAYLOAD_MAX_LENGTH {
+                    return Err(CompilationError::user(
+                        "payload string too long".to_string(),
+                    ));
+                }
+
+                let notification = Notification {
+                    process_id: self.state.connection_id,
+                    channel: channel.clone(),
+                    payload,
+                };
+                // Inside of the transaction NOTIFY is delivered only after COMMIT
+                if self.state.is_in_transaction() {
+                    self.state.queue_notification(notification);
+                } else {
+                    self.session_manager
+                        .server
+                        .notifications
+                        .notify(notification)
+                        .map_err(|err| CompilationError::internal(err.to_string()))?;
+                }
+
+                CommandCompletion::Notify
+            }
+        };
+
+        Ok(QueryPlan::MetaOk(StatusFlags::empty(), completion))
+    }
+
+    /// EXECUTE binds arguments to the prepared statement of the session and plans it as a regular query,
+    /// rewrites are reused between executions by parameterized rewrite cache
+    async fn execute_to_plan(
+        &self,
+        name: &ast::Ident,
+        parameters: &Vec<ast::Expr>,
+        qtrace: &mut Option<Qtrace>,
+        span_id: Option<Arc<SpanId>>,
+    ) -> CompilationResult<QueryPlan> {
+        let values = execute_arguments_to_bind_values(parameters)
+            .map_err(|err| CompilationError::user(err.to_string()))?;
+
+        let statements_guard = self.state.statements.read().await;
+        let prepared_statement = statements_guard.get(&name.value).ok_or_else(|| {
+            CompilationError::user(format!(
+                r#"prepared statement "{}" does not exist"#,
+                name.value
+            ))
+        })?;
+
+        let stmt = match prepared_statement {
+            PreparedStatement::Query { parameters, .. } => {
+                if parameters.parameters.len() != values.len() {
+                    return Err(CompilationError::user(format!(
+                        r#"wrong number of parameters for prepared statement "{}", expected {}, got {}"#,
+                        name.value,
+                        parameters.parameters.len(),
+                        values.len()
+                    )));
+                }
+
+                prepared_statement
+                    .bind(values)
+                    .map_err(|err| CompilationError::internal(err.to_string()))?
+            }
+            PreparedStatement::Notification { statement, .. } => {
+                let statement = statement.clone();
+                drop(statements_guard);
+
+                return self.notification_to_plan(&statement);
+            }
+            PreparedStatement::Empty { .. } => {
+                return Err(CompilationError::unsupported(format!(
+                    r#"EXECUTE is not supported for empty prepared statement "{}""#,
+                    name.value
+                )))
+            }
+            PreparedStatement::Error { .. } => {
+                return Err(CompilationError::user(format!(
+                    r#"prepared statement "{}" was not planned successfully"#,
+                    name.value
+                )))
+            }
+        };
+        drop(statements_guard);
+
+        let stmt = rewrite_statement(stmt);
+        if let Some(qtrace) = qtrace {
+            qtrace.set_visitor_replaced_statement(&stmt);
+        }
+
+        self.plan_query(&stmt, qtrace, span_id).await
+    }
+
     async fn show_variable_to_plan(
         &self,
         variable: &Vec<ast::Ident>,
@@ -198,6 +341,7 @@ impl QueryRouter {
         let full_variable = variable.iter().map(|v| v.value.to_lowercase()).join("_");
         let full_variable = match full_variable.as_str() {
             "transaction_isolation_level" => "transaction_isolation",
+            "cube_query_tag" => QUERY_TAG_VARIABLE,
             x => x,
         };
 
@@ -225,12 +369,226 @@ impl QueryRouter {
             .await
     }
 
+    /// SHOW CUBES | MEASURES [FROM cube] | DIMENSIONS [FROM cube] | SEGMENTS [FROM cube]
+    fn show_semantic_layer_to_plan(&self, variable: &[ast::Ident]) -> CompilationResult<QueryPlan> {
+        let object = variable[0].value.to_lowercase();
+        let cubes = match &variable[1..] {
+            [] => self.meta.cubes.iter().collect::<Vec<_>>(),
+            [_, cube] => vec![self.find_cube_for_meta(&cube.value)?],
+            _ => {
+                return Err(CompilationError::user(format!(
+                    "Unsupported syntax: SHOW {}",
+                    variable.iter().map(|v| v.value.clone()).join(" ")
+                )))
+            }
+        };
+
+        let frame = match object.as_str() {
+            "cubes" => dataframe::DataFrame::new(
+                meta_columns(&[
+                    ("name", ColumnType::String),
+                    ("title", ColumnType::String),
+                    ("description", ColumnType::String),
+                    ("is_view", ColumnType::Boolean),
+                    ("folders", ColumnType::String),
+                    ("meta", ColumnType::String),
+                ]),
+                cubes
+                    .into_iter()
+                    .map(|cube| {
+                        let folders = cube.folders.as_ref().map(|folders| {
+                            folders.iter().map(|folder| folder.name.clone()).join(", ")
+                        });
+
+                        dataframe::Row::new(vec![
+                            dataframe::TableValue::String(cube.name.clone()),
+                            optional_value(cube.title.clone()),
+                            optional_value(cube.description.clone()),
+                            dataframe::TableValue::Boolean(cube.r#type == CubeMetaType::View),
+                            optional_value(folders),
+                            optional_value(cube.meta.as_ref().map(|meta| meta.to_string())),
+                        ])
+                    })
+                    .collect(),
+            ),
+            "measures" => dataframe::DataFrame::new(
+                meta_columns(&[
+                    ("cube", ColumnType::String),
+                    ("name", ColumnType::String),
+                    ("title", ColumnType::String),
+                    ("description", ColumnType::String),
+                    ("type", ColumnType::String),
+                    ("agg_type", ColumnType::String),
+                    ("folder", ColumnType::String),
+                    ("meta", ColumnType::String),
+                ]),
+                cubes
+                    .into_iter()
+                    .flat_map(|cube| {
+                        cube.measures.iter().map(move |measure| {
+                            dataframe::Row::new(vec![
+                                dataframe::TableValue::String(cube.name.clone()),
+                                dataframe::TableValue::String(measure.get_real_name()),
+                                optional_value(measure.title.clone()),
+                                optional_value(measure.description.clone()),
+                                dataframe::TableValue::String(measure.r#type.clone()),
+                                optional_value(measure.agg_type.clone()),
+                                optional_value(member_folder(cube, &measure.name)),
+                                optional_value(measure.meta.as_ref().map(|meta| meta.to_string())),
+                            ])
+                        })
+                    })
+                    .collect(),
+            ),
+            "dimensions" => dataframe::DataFrame::new(
+                meta_columns(&[
+                    ("cube", ColumnType::String),
+                    ("name", ColumnType::String),
+                    ("title", ColumnType::String),
+                    ("description", ColumnType::String),
+                    ("type", ColumnType::String),
+                    ("folder", ColumnType::String),
+                    ("meta", ColumnType::String),
+                ]),
+                cubes
+                    .into_iter()
+                    .flat_map(|cube| {
+                        cube.dimensions.iter().map(move |dimension| {
+                            dataframe::Row::new(vec![
+                                dataframe::TableValue::String(cube.name.clone()),
+                                dataframe::TableValue::String(dimension.get_real_name()),
+                                optional_value(dimension.title.clone()),
+                                optional_value(dimension.description.clone()),
+                                dataframe::TableValue::String(dimension.r#type.clone()),
+                                optional_value(member_folder(cube, &dimension.name)),
+                                optional_value(
+                                    dimension.meta.as_ref().map(|meta| meta.to_string()),
+                                ),
+                            ])
+                        })
+                    })
+                    .collect(),
+            ),
+            _ => dataframe::DataFrame::new(
+                meta_columns(&[
+                    ("cube", ColumnType::String),
+                    ("name", ColumnType::String),
+                    ("title", ColumnType::String),
+                    ("description", ColumnType::String),
+                    ("folder", ColumnType::String),
+                    ("meta", ColumnType::String),
+                ]),
+                cubes
+                    .into_iter()
+                    .flat_map(|cube| {
+                        cube.segments.iter().map(move |segment| {
+                            dataframe::Row::new(vec![
+                                dataframe::TableValue::String(cube.name.clone()),
+                                dataframe::TableValue::String(segment.get_real_name()),
+                                dataframe::TableValue::String(segment.title.clone()),
+                                optional_value(segment.description.clone()),
+                                optional_value(member_folder(cube, &segment.name)),
+                                optional_value(segment.meta.as_ref().map(|meta| meta.to_string())),
+                            ])
+                        })
+                    })
+                    .collect(),
+            ),
+        };
+
+        Ok(QueryPlan::MetaTabular(
+            StatusFlags::empty(),
+            Box::new(frame),
+        ))
+    }
+
+    /// DESCRIBE cube lists all members of the cube or view
+    fn describe_cube_to_plan(&self, table_name: &ast::ObjectName) -> CompilationResult<QueryPlan> {
+        let cube_name = match table_name.0.last() {
+            Some(ident) => &ident.value,
+            None => return Err(CompilationError::user("Cube name is missing".to_string())),
+        };
+        let cube = self.find_cube_for_meta(cube_name)?;
+
+        let mut rows = Vec::new();
+        for measure in cube.measures.iter() {
+            rows.push(dataframe::Row::new(vec![
+                dataframe::TableValue::String(measure.get_real_name()),
+                dataframe::TableValue::String("measure".to_string()),
+                dataframe::TableValue::String(measure.r#type.clone()),
+                optional_value(measure.agg_type.clone()),
+                optional_value(measure.title.clone()),
+                optional_value(measure.description.clone()),
+                optional_value(member_folder(cube, &measure.name)),
+            ]));
+        }
+        for dimension in cube.dimensions.iter() {
+            rows.push(dataframe::Row::new(vec![
+                dataframe::TableValue::String(dimension.get_real_name()),
+                dataframe::TableValue::String("dimension".to_string()),
+                dataframe::TableValue::String(dimension.r#type.clone()),
+                dataframe::TableValue::Null,
+                optional_value(dimension.title.clone()),
+                optional_value(dimension.description.clone()),
+                optional_value(member_folder(cube, &dimension.name)),
+            ]));
+        }
+        for segment in cube.segments.iter() {
+            rows.push(dataframe::Row::new(vec![
+                dataframe::TableValue::String(segment.get_real_name()),
+                dataframe::TableValue::String("segment".to_string()),
+                dataframe::TableValue::String("boolean".to_string()),
+                dataframe::TableValue::Null,
+                dataframe::TableValue::String(segment.title.clone()),
+                optional_value(segment.description.clone()),
+                optional_value(member_folder(cube, &segment.name)),
+            ]));
+        }
+
+        Ok(QueryPlan::MetaTabular(
+            StatusFlags::empty(),
+            Box::new(dataframe::DataFrame::new(
+                meta_columns(&[
+                    ("name", ColumnType::String),
+                    ("member_type", ColumnType::String),
+                    ("type", ColumnType::String),
+                    ("agg_type", ColumnType::String),
+                    ("title", ColumnType::String),
+                    ("description", ColumnType::String),
+                    ("folder", ColumnType::String),
+                ]),
+                rows,
+            )),
+        ))
+    }
+
+    fn find_cube_for_meta(&self, name: &str) -> CompilationResult<&CubeMeta> {
+        self.meta
+            .cubes
+            .iter()
+            .find(|cube| cube.name == name)
+            .or_else(|| {
+                self.meta
+                    .cubes
+                    .iter()
+                    .find(|cube| cube.name.eq_ignore_ascii_case(name))
+            })
+            .ok_or_else(|| CompilationError::user(format!(r#"cube "{}" does not exist"#, name)))
+    }
+
     async fn explain_to_plan(
         &self,
         statement: Box<ast::Statement>,
-        verbose: bool,
-        analyze: bool,
+        options: ExplainOptions,
     ) -> Result<QueryPlan, CompilationError> {
+        if options.cube || options.format == ExplainFormat::Json {
+            return self.explain_cube_to_plan(statement, options).await;
+        }
+
+        let ExplainOptions {
+            analyze, verbose, ..
+        } = options;
+
         // TODO span_id ?
         let plan = self.plan_query(&statement, &mut None, None).await?;
 
@@ -283,6 +641,78 @@ impl QueryRouter {
         }
     }
 
+    /// EXPLAIN (FORMAT JSON | TEXT, CUBE) shows what Cube does with the query:
+    /// load requests of CubeScan nodes, SQL of wrapped scans and rewrite statistics
+    async fn explain_cube_to_plan(
+        &self,
+        statement: Box<ast::Statement>,
+        options: ExplainOptions,
+    ) -> Result<QueryPlan, CompilationError> {
+        if options.analyze {
+            return Err(CompilationError::unsupported(
+                "EXPLAIN ANALYZE is not supported with FORMAT JSON or CUBE options".to_string(),
+            ));
+        }
+
+        let plan = self.plan_query(&statement, &mut None, None).await?;
+        let logical_plan = match &plan {
+            QueryPlan::MetaOk(_, _) | QueryPlan::MetaTabular(_, _) => None,
+            QueryPlan::DataFusionSelect(plan, _) | QueryPlan::CreateTempTable(plan, _, _, _) => {
+                Some(plan)
+            }
+        };
+
+        let mut explain = serde_json::Map::new();
+        explain.insert(
+            "plan".to_string(),
+            match logical_plan {
+                Some(plan) => serde_json::Value::String(format!("{}", plan.display_indent())),
+                None => serde_json::Value::Null,
+            },
+        );
+        if options.cube {
+            let cube_scans = match logical_plan {
+                Some(plan) => explain_cube_scans(plan)?,
+                None => vec![],
+            };
+            let stats = self.state.planning_stats();
+
+            explain.insert(
+                "cubeScans".to_string(),
+                serde_json::Value::Array(cube_scans),
+            );
+            explain.insert(
+                "rewrite".to_string(),
+                serde_json::json!({
+                    "cacheHit": stats.rewrite_cache_hit,
+                    "parameterizedCacheHit": stats.parameterized_rewrite_cache_hit,
+                    "budgetExhausted": stats.rewrite_budget_exhausted,
+                    "stages": stats.rewrites,
+                }),
+            );
+        }
+        let explain = serde_json::Value::Object(explain);
+
+        let rows = match options.format {
+            ExplainFormat::Json => vec![serde_json::to_string_pretty(&explain)?],
+            ExplainFormat::Text => explain_to_text_lines(&explain),
+        };
+
+        Ok(QueryPlan::MetaTabular(
+            StatusFlags::empty(),
+            Box::new(dataframe::DataFrame::new(
+                vec![dataframe::Column::new(
+                    "QUERY PLAN".to_string(),
+                    ColumnType::String,
+                    ColumnFlags::empty(),
+                )],
+                rows.into_iter()
+                    .map(|row| dataframe::Row::new(vec![dataframe::TableValue::String(row)]))
+                    .collect(),
+            )),
+        ))
+    }
+
     fn set_role_to_plan(
         &self,
         role_name: &Option<ast::Ident>,
@@ -313,6 +743,20 @@ impl QueryRouter {
         match self.state.protocol {
             DatabaseProtocol::PostgreSQL => {
                 for key_value in key_values.iter() {
+                    // `SET name TO DEFAULT` and `RESET name` restore the default value, which
+                    // is NULL for timeouts, so the server-wide default applies again
+                    if let ast::Expr::Identifier(ident) = &key_value.value[0] {
+                        if ident.quote_style.is_none()
+                            && ident.value.eq_ignore_ascii_case("default")
+                        {
+                            let key = key_value.key.value.to_lowercase();
+                            if let Some(default) = postgres_default_session_variables().get(&key) {
+                                session_columns_to_update.push(default.clone());
+                                continue;
+                            }
+                        }
+                    }
+
                     let value: String = match &key_value.value[0] {
                         ast::Expr::Identifier(ident) => ident.value.to_string(),
                         ast::Expr::Value(val) => match val {
@@ -338,8 +782,18 @@ impl QueryRouter {
                         }
                     };
 
+                    let key = key_value.key.value.to_lowercase();
+                    if TIMEOUT_VARIABLES.contains(&key.as_str())
+                        && parse_timeout_ms(&value).is_none()
+                    {
+                        return Err(CompilationError::user(format!(
+                            r#"invalid value for parameter "{}": "{}""#,
+                            key, value
+                        )));
+                    }
+
                     session_columns_to_update.push(DatabaseVariable::system(
-                        key_value.key.value.to_lowercase(),
+                        key,
                         ScalarValue::Utf8(Some(value.clone())),
                         None,
                     ));
@@ -480,18 +934,31 @@ impl QueryRouter {
         Ok(QueryPlan::MetaOk(flags, CommandCompletion::Set))
     }
 
+    /// Tables of the current user which are visible to all of their sessions with the same
+    /// security context
+    fn shared_tables(&self) -> Option<Arc<TempTableManager>> {
+        let user = self.state.user()?;
+
+        self.session_manager
+            .shared_tables(&user, self.state.security_context_hash())
+    }
+
     async fn create_table_to_plan(
         &self,
         name: &ast::ObjectName,
+        temporary: bool,
         stmt: &ast::Statement,
         qtrace: &mut Option<Qtrace>,
         span_id: Option<Arc<SpanId>>,
     ) -> Result<QueryPlan, CompilationError> {
-        let plan = self.select_to_plan(stmt, qtrace, span_id).await?;
-        let QueryPlan::DataFusionSelect(plan, ctx) = plan else {
-            return Err(CompilationError::internal(
-                "unable to build DataFusion plan from Query".to_string(),
-            ));
+        let temp_tables = if temporary {
+            self.state.temp_tables()
+        } else {
+            self.shared_tables().ok_or_else(|| {
+                CompilationError::unsupported(
+                    "only TEMPORARY TABLE is supported for CREATE TABLE AS".to_string(),
+                )
+            })?
         };
 
         let ast::ObjectName(ident_parts) = name;
@@ -501,11 +968,33 @@ impl QueryRouter {
             ));
         };
 
+        // Shared tables are resolved in the public schema after cubes, so they can't be named
+        // like a cube
+        if !temporary
+            && self
+                .meta
+                .cubes
+                .iter()
+                .any(|c| c.name.eq_ignore_ascii_case(&table_name.value))
+        {
+            return Err(CompilationError::user(format!(
+                "relation \"{}\" already exists as a cube",
+                table_name.value
+            )));
+        }
+
+        let plan = self.select_to_plan(stmt, qtrace, span_id).await?;
+        let QueryPlan::DataFusionSelect(plan, ctx) = plan else {
+            return Err(CompilationError::internal(
+                "unable to build DataFusion plan from Query".to_string(),
+            ));
+        };
+
         Ok(QueryPlan::CreateTempTable(
             plan,
             ctx,
             table_name.value.to_string(),
-            self.state.temp_tables(),
+            temp_tables,
         ))
     }
 
@@ -531,7 +1020,7 @@ impl QueryRouter {
             ));
         }
         let new_stmt = ast::Statement::Query(Box::new(new_query));
-        self.create_table_to_plan(&into.name, &new_stmt, qtrace, span_id)
+        self.create_table_to_plan(&into.name, true, &new_stmt, qtrace, span_id)
             .await
     }
 
@@ -551,7 +1040,13 @@ impl QueryRouter {
             ));
         };
         let table_name_lower = table_name.value.to_ascii_lowercase();
-        let temp_tables = self.state.temp_tables();
+        // Temporary tables shadow shared tables with the same name
+        let temp_tables = match self.shared_tables() {
+            Some(shared_tables) if !self.state.temp_tables().has(&table_name_lower) => {
+                shared_tables
+            }
+            _ => self.state.temp_tables(),
+        };
         tokio::task::spawn_blocking(move || temp_tables.remove(&table_name_lower))
             .await
             .map_err(|err| CompilationError::internal(err.to_string()))?
@@ -608,6 +1103,147 @@ impl QueryRouter {
     }
 }
 
+/// Load requests of CubeScan nodes, wrapped scans also include the generated SQL
+fn explain_cube_scans(plan: &LogicalPlan) -> CompilationResult<Vec<serde_json::Value>> {
+    struct ExplainVisitor {
+        cube_scans: Vec<serde_json::Value>,
+    }
+
+    impl PlanVisitor for ExplainVisitor {
+        type Error = CompilationError;
+
+        fn pre_visit(&mut self, plan: &LogicalPlan) -> Result<bool, Self::Error> {
+            if let LogicalPlan::Extension(ext) = plan {
+                if let Some(scan_node) = ext.node.as_any().downcast_ref::<CubeScanNode>() {
+                    self.cube_scans.push(serde_json::json!({
+                        "request": scan_node.request,
+                    }));
+                } else if let Some(wrapped_node) =
+                    ext.node.as_any().downcast_ref::<CubeScanWrappedSqlNode>()
+                {
+                    self.cube_scans.push(serde_json::json!({
+                        "request": wrapped_node.request,
+                        "sql": wrapped_node.wrapped_sql.sql,
+                        "values": wrapped_node.wrapped_sql.values,
+                    }));
+
+                    return Ok(false);
+                }
+            }
+
+            Ok(true)
+        }
+    }
+
+    let mut visitor = ExplainVisitor { cube_scans: vec![] };
+    plan.accept(&mut visitor)?;
+
+    Ok(visitor.cube_scans)
+}
+
+fn explain_to_text_lines(explain: &serde_json::Value) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(plan) = explain["plan"].as_str() {
+        lines.push("Plan:".to_string());
+        lines.extend(plan.lines().map(|line| format!("  {}", line)));
+    }
+    if let Some(cube_scans) = explain["cubeScans"].as_array() {
+        for (i, cube_scan) in cube_scans.iter().enumerate() {
+            lines.push(format!("CubeScan #{}:", i + 1));
+            lines.push(format!("  Request: {}", cube_scan["request"]));
+            if let Some(sql) = cube_scan["sql"].as_str() {
+                lines.push("  SQL:".to_string());
+                lines.extend(sql.lines().map(|line| format!("    {}", line)));
+            }
+        }
+    }
+    if explain["rewrite"].is_object() {
+        let rewrite = &explain["rewrite"];
+        lines.push(format!(
+            "Rewrite: cache hit: {}, parameterized cache hit: {}",
+            rewrite["cacheHit"], rewrite["parameterizedCacheHit"]
+        ));
+        if let Some(reason) = rewrite["budgetExhausted"].as_str() {
+            lines.push(format!("  Budget exhausted: {}", reason));
+        }
+        for stage in rewrite["stages"].as_array().into_iter().flatten() {
+            lines.push(format!(
+                "  Stage {}: {} iterations, {} e-nodes, {} e-classes, {} ms",
+                stage["stage"].as_str().unwrap_or_default(),
+                stage["iterations"],
+                stage["egraphNodes"],
+                stage["egraphClasses"],
+                stage["durationMs"]
+            ));
+        }
+    }
+
+    lines
+}
+
+/// `SHOW CUBES|MEASURES|DIMENSIONS|SEGMENTS` and `SHOW MEASURES|DIMENSIONS|SEGMENTS FROM|IN cube`,
+/// none of these names clash with a Postgres setting. Quoted names are always variables.
+fn is_semantic_layer_show(variable: &[ast::Ident]) -> bool {
+    let is_keyword = |ident: &ast::Ident, name: &str| {
+        ident.quote_style.is_none() && ident.value.eq_ignore_ascii_case(name)
+    };
+
+    match variable {
+        [object] => ["cubes", "measures", "dimensions", "segments"]
+            .iter()
+            .any(|name| is_keyword(object, name)),
+        [object, from, _] => {
+            ["measures", "dimensions", "segments"]
+                .iter()
+                .any(|name| is_keyword(object, name))
+                && (is_keyword(from, "from") || is_keyword(from, "in"))
+        }
+        _ => false,
+    }
+}
+
+fn meta_columns(columns: &[(&str, ColumnType)]) -> Vec<dataframe::Column> {
+    columns
+        .iter()
+        .map(|(name, column_type)| {
+            dataframe::Column::new(name.to_string(), column_type.clone(), ColumnFlags::empty())
+        })
+        .collect()
+}
+
+fn optional_value(value: Option<String>) -> dataframe::TableValue {
+    match value {
+        Some(value) => dataframe::TableValue::String(value),
+        None => dataframe::TableValue::Null,
+    }
+}
+
+/// Folders of the cube which contain the member, members are listed either by full or short name
+fn member_folder(cube: &CubeMeta, member_name: &str) -> Option<String> {
+    let short_name = member_name
+        .split_once('.')
+        .map(|(_, name)| name)
+        .unwrap_or(member_name);
+    let folders = cube
+        .folders
+        .as_ref()?
+        .iter()
+        .filter(|folder| {
+            folder
+                .members
+                .iter()
+                .any(|member| member == member_name || member == short_name)
+        })
+        .map(|folder| folder.name.clone())
+        .collect::<Vec<_>>();
+
+    if folders.is_empty() {
+        None
+    } else {
+        Some(folders.join(", "))
+    }
+}
+
 pub fn rewrite_statement(stmt: ast::Statement) -> ast::Statement {
     let stmt = CastReplacer::new().replace(stmt);
     let stmt = ToTimestampReplacer::new().replace(stmt);
@@ -636,11 +1272,38 @@ pub async fn convert_statement_to_cube_query(
     planner.plan(stmt, qtrace, span_id).await
 }
 
+pub fn convert_notification_to_cube_query(
+    stmt: &NotificationStatement,
+    meta: Arc<MetaContext>,
+    session: Arc<Session>,
+) -> CompilationResult<QueryPlan> {
+    let planner = QueryRouter::new(session.state.clone(), meta, session.session_manager.clone());
+    planner.notification_to_plan(stmt)
+}
+
+pub async fn convert_explain_to_cube_query(
+    options: ExplainOptions,
+    stmt: ast::Statement,
+    meta: Arc<MetaContext>,
+    session: Arc<Session>,
+) -> CompilationResult<QueryPlan> {
+    let stmt = rewrite_statement(stmt);
+
+    let planner = QueryRouter::new(session.state.clone(), meta, session.session_manager.clone());
+    planner.explain_to_plan(Box::new(stmt), options).await
+}
+
 pub async fn convert_sql_to_cube_query(
     query: &String,
     meta: Arc<MetaContext>,
     session: Arc<Session>,
 ) -> CompilationResult<QueryPlan> {
+    if let Some((options, stmt)) =
+        parse_explain_statement(query, session.state.protocol.clone(), &mut None)?
+    {
+        return convert_explain_to_cube_query(options, stmt, meta, session).await;
+    }
+
     let stmt = parse_sql_to_statement(&query, session.state.protocol.clone(), &mut None)?;
     convert_statement_to_cube_query(stmt, meta, session, &mut None, None).await
 }
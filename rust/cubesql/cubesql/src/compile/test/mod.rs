@@ -8,7 +8,8 @@ use crate::{
     },
     config::{ConfigObj, ConfigObjImpl},
     sql::{
-        compiler_cache::CompilerCacheImpl, dataframe::batches_to_dataframe,
+        audit_log::AuditLogSinkNoopImpl, compiler_cache::CompilerCacheImpl,
+        dataframe::batches_to_dataframe, notification_service::NotificationServiceDefaultImpl,
         pg_auth_service::PostgresAuthServiceDefaultImpl, AuthContextRef, AuthenticateResponse,
         HttpAuthContext, ServerManager, Session, SessionManager, SqlAuthService,
     },
@@ -38,6 +39,8 @@ pub mod test_filters;
 #[cfg(test)]
 pub mod test_introspection;
 #[cfg(test)]
+pub mod test_query_tag;
+#[cfg(test)]
 pub mod test_udfs;
 #[cfg(test)]
 pub mod test_user_change;
@@ -676,6 +679,10 @@ async fn get_test_session_with_config_and_transport(
         get_test_auth(),
         test_transport.clone(),
         Arc::new(PostgresAuthServiceDefaultImpl::new()),
+        Arc::new(NotificationServiceDefaultImpl::new(
+            config_obj.notification_queue_capacity(),
+        )),
+        Arc::new(AuditLogSinkNoopImpl::new()),
         Arc::new(CompilerCacheImpl::new(config_obj.clone(), test_transport)),
         None,
         config_obj,
@@ -855,6 +862,15 @@ impl TransportService for TestConnectionTransport {
         panic!("It's a fake transport");
     }
 
+    async fn cancel(
+        &self,
+        _span_id: Arc<SpanId>,
+        _ctx: AuthContextRef,
+        _meta_fields: LoadRequestMeta,
+    ) -> Result<(), CubeError> {
+        Ok(())
+    }
+
     async fn can_switch_user_for_session(
         &self,
         _ctx: AuthContextRef,
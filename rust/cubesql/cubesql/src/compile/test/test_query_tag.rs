@@ -0,0 +1,79 @@
+//! Tests that check query tags are attached to load requests
+
+use pretty_assertions::assert_eq;
+
+use crate::compile::{
+    parser::parse_query_tag_comment,
+    test::{init_testing_logger, TestContext},
+    DatabaseProtocol,
+};
+
+const LOAD_QUERY: &str = "SELECT COUNT(*) FROM KibanaSampleDataEcommerce";
+
+#[tokio::test]
+async fn test_query_tag_session_variable() {
+    init_testing_logger();
+
+    let context = TestContext::new(DatabaseProtocol::PostgreSQL).await;
+
+    context
+        .execute_query("SET cube.query_tag = 'dashboard-42'")
+        .await
+        .unwrap();
+    assert!(context
+        .execute_query("SHOW cube.query_tag")
+        .await
+        .unwrap()
+        .contains("dashboard-42"));
+    assert!(context
+        .execute_query("SELECT query_tag FROM pg_catalog.pg_stat_activity")
+        .await
+        .unwrap()
+        .contains("dashboard-42"));
+
+    context
+        .execute_query(LOAD_QUERY)
+        .await
+        .expect_err("Test transport does not have a mock for this query");
+
+    let load_calls = context.load_calls().await;
+    assert_eq!(load_calls.len(), 1);
+    assert_eq!(
+        load_calls[0].meta.query_tag(),
+        Some("dashboard-42".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_query_tag_comment_overrides_variable() {
+    init_testing_logger();
+
+    let context = TestContext::new(DatabaseProtocol::PostgreSQL).await;
+
+    context
+        .execute_query("SET cube.query_tag = 'dashboard-42'")
+        .await
+        .unwrap();
+
+    // Shim sets the tag of every statement from its SQL
+    let query = format!("/* tag: nightly-job */ {}", LOAD_QUERY);
+    context
+        .session
+        .state
+        .set_statement_query_tag(parse_query_tag_comment(&query));
+    context.execute_query(query).await.unwrap_err();
+
+    context.session.state.set_statement_query_tag(None);
+    context.execute_query(LOAD_QUERY).await.unwrap_err();
+
+    let load_calls = context.load_calls().await;
+    assert_eq!(load_calls.len(), 2);
+    assert_eq!(
+        load_calls[0].meta.query_tag(),
+        Some("nightly-job".to_string())
+    );
+    assert_eq!(
+        load_calls[1].meta.query_tag(),
+        Some("dashboard-42".to_string())
+    );
+}
@@ -7,6 +7,11 @@ use crate::{
         processing_loop::{ProcessingLoop, ShutdownMode},
     },
     sql::{
+        audit_log::{
+            AuditLogCallback, AuditLogSink, AuditLogSinkNoopImpl, CallbackAuditLogSink,
+            JsonlFileAuditLogSink,
+        },
+        notification_service::{NotificationService, NotificationServiceDefaultImpl},
         pg_auth_service::{PostgresAuthService, PostgresAuthServiceDefaultImpl},
         PostgresServer, ServerManager, SessionManager, SqlAuthDefaultImpl, SqlAuthService,
     },
@@ -83,6 +88,7 @@ impl CubeServices {
 pub struct Config {
     config_obj: Arc<ConfigObjImpl>,
     injector: Arc<Injector>,
+    audit_log_callback: Option<AuditLogCallback>,
 }
 
 pub trait ConfigObj: DIService + Debug {
@@ -114,9 +120,53 @@ pub trait ConfigObj: DIService + Debug {
 
     fn max_sessions(&self) -> usize;
 
+    fn max_connections_per_user(&self) -> usize;
+
+    fn max_concurrent_queries_per_user(&self) -> usize;
+
+    fn query_queue_timeout(&self) -> u64;
+
     fn no_implicit_order(&self) -> bool;
 
     fn top_down_extractor(&self) -> bool;
+
+    fn notification_queue_capacity(&self) -> usize;
+
+    fn temp_table_session_mem(&self) -> usize;
+
+    fn temp_table_total_mem(&self) -> usize;
+
+    fn temp_table_spill_dir(&self) -> &Option<String>;
+
+    fn temp_table_spill_max_size(&self) -> usize;
+
+    fn shared_tables_ttl(&self) -> u64;
+
+    fn statement_timeout(&self) -> u64;
+
+    fn statement_timeout_max(&self) -> u64;
+
+    fn idle_in_transaction_session_timeout(&self) -> u64;
+
+    fn idle_in_transaction_session_timeout_max(&self) -> u64;
+
+    fn idle_session_timeout(&self) -> u64;
+
+    fn idle_session_timeout_max(&self) -> u64;
+
+    fn audit_log_path(&self) -> &Option<String>;
+
+    fn audit_log_redact_literals(&self) -> bool;
+
+    fn rewrite_budget_timeout(&self) -> u64;
+
+    fn rewrite_budget_max_nodes(&self) -> usize;
+
+    fn rewrite_budget_fallback(&self) -> bool;
+
+    fn persisted_rewrite_cache_dir(&self) -> &Option<String>;
+
+    fn persisted_rewrite_cache_size(&self) -> usize;
 }
 
 #[derive(Debug, Clone)]
@@ -136,8 +186,41 @@ pub struct ConfigObjImpl {
     pub stream_mode: bool,
     pub non_streaming_query_max_row_limit: i32,
     pub max_sessions: usize,
+    // 0 means no limit
+    pub max_connections_per_user: usize,
+    // 0 means no limit, otherwise data queries above the limit wait in a queue
+    pub max_concurrent_queries_per_user: usize,
+    // How long a data query waits in the queue in seconds, 0 means without timeout
+    pub query_queue_timeout: u64,
     pub no_implicit_order: bool,
     pub top_down_extractor: bool,
+    pub notification_queue_capacity: usize,
+    pub temp_table_session_mem: usize,
+    pub temp_table_total_mem: usize,
+    pub temp_table_spill_dir: Option<String>,
+    // Total size of spilled temporary tables in MiB
+    pub temp_table_spill_max_size: usize,
+    pub shared_tables_ttl: u64,
+    // Server-wide session timeout defaults and hard maximums in seconds, 0 means disabled
+    pub statement_timeout: u64,
+    pub statement_timeout_max: u64,
+    pub idle_in_transaction_session_timeout: u64,
+    pub idle_in_transaction_session_timeout_max: u64,
+    pub idle_session_timeout: u64,
+    pub idle_session_timeout_max: u64,
+    // JSONL file for the audit log of SQL API statements
+    pub audit_log_path: Option<String>,
+    pub audit_log_redact_literals: bool,
+    // Wall-clock budget in seconds for all rewrite stages of a query, 0 means disabled
+    pub rewrite_budget_timeout: u64,
+    // Maximum number of e-graph nodes of a query rewrite, 0 means disabled
+    pub rewrite_budget_max_nodes: usize,
+    // Use the best plan found so far when the rewrite runs out of budget
+    pub rewrite_budget_fallback: bool,
+    // Directory to persist parameterized rewrites across restarts, disabled if not set
+    pub persisted_rewrite_cache_dir: Option<String>,
+    // Size limit of the persisted rewrite cache in MiB
+    pub persisted_rewrite_cache_size: usize,
 }
 
 impl ConfigObjImpl {
@@ -174,8 +257,39 @@ impl ConfigObjImpl {
             stream_mode: env_parse("CUBESQL_STREAM_MODE", false),
             non_streaming_query_max_row_limit: env_parse("CUBEJS_DB_QUERY_LIMIT", 50000),
             max_sessions: env_parse("CUBEJS_MAX_SESSIONS", 1024),
+            max_connections_per_user: env_parse("CUBESQL_MAX_CONNECTIONS_PER_USER", 0),
+            max_concurrent_queries_per_user: env_parse(
+                "CUBESQL_MAX_CONCURRENT_QUERIES_PER_USER",
+                0,
+            ),
+            query_queue_timeout: env_parse("CUBESQL_QUERY_QUEUE_TIMEOUT", 60),
             no_implicit_order: env_parse("CUBESQL_SQL_NO_IMPLICIT_ORDER", true),
             top_down_extractor: env_parse("CUBESQL_TOP_DOWN_EXTRACTOR", true),
+            notification_queue_capacity: env_parse("CUBESQL_NOTIFICATION_QUEUE_CAPACITY", 1024),
+            temp_table_session_mem: env_parse("CUBESQL_TEMP_TABLE_SESSION_MEM", 10),
+            temp_table_total_mem: env_parse("CUBESQL_TEMP_TABLE_TOTAL_MEM", 100),
+            temp_table_spill_dir: env::var("CUBESQL_TEMP_TABLE_SPILL_DIR").ok(),
+            temp_table_spill_max_size: env_parse("CUBESQL_TEMP_TABLE_SPILL_MAX_SIZE", 1024),
+            shared_tables_ttl: env_parse("CUBESQL_SHARED_TABLES_TTL", 0),
+            statement_timeout: env_parse("CUBESQL_STATEMENT_TIMEOUT", 0),
+            statement_timeout_max: env_parse("CUBESQL_STATEMENT_TIMEOUT_MAX", 0),
+            idle_in_transaction_session_timeout: env_parse(
+                "CUBESQL_IDLE_IN_TRANSACTION_SESSION_TIMEOUT",
+                0,
+            ),
+            idle_in_transaction_session_timeout_max: env_parse(
+                "CUBESQL_IDLE_IN_TRANSACTION_SESSION_TIMEOUT_MAX",
+                0,
+            ),
+            idle_session_timeout: env_parse("CUBESQL_IDLE_SESSION_TIMEOUT", 0),
+            idle_session_timeout_max: env_parse("CUBESQL_IDLE_SESSION_TIMEOUT_MAX", 0),
+            audit_log_path: env::var("CUBESQL_AUDIT_LOG_PATH").ok(),
+            audit_log_redact_literals: env_parse("CUBESQL_AUDIT_LOG_REDACT_LITERALS", false),
+            rewrite_budget_timeout: env_parse("CUBESQL_REWRITE_BUDGET_TIMEOUT", 0),
+            rewrite_budget_max_nodes: env_parse("CUBESQL_REWRITE_BUDGET_MAX_NODES", 0),
+            rewrite_budget_fallback: env_parse("CUBESQL_REWRITE_BUDGET_FALLBACK", false),
+            persisted_rewrite_cache_dir: env::var("CUBESQL_PERSISTED_REWRITE_CACHE_DIR").ok(),
+            persisted_rewrite_cache_size: env_parse("CUBESQL_PERSISTED_REWRITE_CACHE_SIZE", 256),
         }
     }
 }
@@ -243,9 +357,97 @@ impl ConfigObj for ConfigObjImpl {
         self.max_sessions
     }
 
+    fn max_connections_per_user(&self) -> usize {
+        self.max_connections_per_user
+    }
+
+    fn max_concurrent_queries_per_user(&self) -> usize {
+        self.max_concurrent_queries_per_user
+    }
+
+    fn query_queue_timeout(&self) -> u64 {
+        self.query_queue_timeout
+    }
+
     fn top_down_extractor(&self) -> bool {
         self.top_down_extractor
     }
+
+    fn notification_queue_capacity(&self) -> usize {
+        self.notification_queue_capacity
+    }
+
+    fn temp_table_session_mem(&self) -> usize {
+        self.temp_table_session_mem
+    }
+
+    fn temp_table_total_mem(&self) -> usize {
+        self.temp_table_total_mem
+    }
+
+    fn temp_table_spill_dir(&self) -> &Option<String> {
+        &self.temp_table_spill_dir
+    }
+
+    fn temp_table_spill_max_size(&self) -> usize {
+        self.temp_table_spill_max_size
+    }
+
+    fn shared_tables_ttl(&self) -> u64 {
+        self.shared_tables_ttl
+    }
+
+    fn statement_timeout(&self) -> u64 {
+        self.statement_timeout
+    }
+
+    fn statement_timeout_max(&self) -> u64 {
+        self.statement_timeout_max
+    }
+
+    fn idle_in_transaction_session_timeout(&self) -> u64 {
+        self.idle_in_transaction_session_timeout
+    }
+
+    fn idle_in_transaction_session_timeout_max(&self) -> u64 {
+        self.idle_in_transaction_session_timeout_max
+    }
+
+    fn idle_session_timeout(&self) -> u64 {
+        self.idle_session_timeout
+    }
+
+    fn idle_session_timeout_max(&self) -> u64 {
+        self.idle_session_timeout_max
+    }
+
+    fn audit_log_path(&self) -> &Option<String> {
+        &self.audit_log_path
+    }
+
+    fn audit_log_redact_literals(&self) -> bool {
+        self.audit_log_redact_literals
+    }
+
+    fn rewrite_budget_timeout(&self) -> u64 {
+        self.rewrite_budget_timeout
+    }
+
+    fn rewrite_budget_max_nodes(&self) -> usize {
+        self.rewrite_budget_max_nodes
+    }
+
+    fn rewrite_budget_fallback(&self) -> bool {
+        self.rewrite_budget_fallback
+    }
+
+    fn persisted_rewrite_cache_dir(&self) -> &Option<String> {
+        &self.persisted_rewrite_cache_dir
+    }
+
+    fn persisted_rewrite_cache_size(&self) -> usize {
+        self.persisted_rewrite_cache_size
+    }
 }
 
 impl Config {
@@ -253,6 +455,7 @@ impl Config {
         Config {
             injector: Injector::new(),
             config_obj: Arc::new(ConfigObjImpl::default()),
+            audit_log_callback: None,
         }
     }
 
@@ -277,9 +480,32 @@ impl Config {
                 stream_mode: false,
                 non_streaming_query_max_row_limit: 50000,
                 max_sessions: 1024,
+                max_connections_per_user: 0,
+                max_concurrent_queries_per_user: 0,
+                query_queue_timeout: 60,
                 no_implicit_order: true,
                 top_down_extractor: true,
+                notification_queue_capacity: 1024,
+                temp_table_session_mem: 10,
+                temp_table_total_mem: 100,
+                temp_table_spill_dir: None,
+                temp_table_spill_max_size: 1024,
+                shared_tables_ttl: 0,
+                statement_timeout: 0,
+                statement_timeout_max: 0,
+                idle_in_transaction_session_timeout: 0,
+                idle_in_transaction_session_timeout_max: 0,
+                idle_session_timeout: 0,
+                idle_session_timeout_max: 0,
+                audit_log_path: None,
+                audit_log_redact_literals: false,
+                rewrite_budget_timeout: 0,
+                rewrite_budget_max_nodes: 0,
+                rewrite_budget_fallback: false,
+                persisted_rewrite_cache_dir: None,
+                persisted_rewrite_cache_size: 256,
             }),
+            audit_log_callback: None,
         }
     }
 
@@ -291,6 +517,15 @@ impl Config {
         Self {
             injector: self.injector.clone(),
             config_obj: Arc::new(update_config(new_config)),
+            audit_log_callback: self.audit_log_callback.clone(),
+        }
+    }
+
+    /// Sends audit log records to the callback instead of the file from `CUBESQL_AUDIT_LOG_PATH`
+    pub fn with_audit_log_callback(self, callback: AuditLogCallback) -> Config {
+        Self {
+            audit_log_callback: Some(callback),
+            ..self
         }
     }
 
@@ -320,6 +555,36 @@ impl Config {
             })
             .await;
 
+        self.injector
+            .register_typed::<dyn NotificationService, _, _, _>(|i| async move {
+                let config = i.get_service_typed::<dyn ConfigObj>().await;
+                Arc::new(NotificationServiceDefaultImpl::new(
+                    config.notification_queue_capacity(),
+                ))
+            })
+            .await;
+
+        let audit_log_callback = self.audit_log_callback.clone();
+        self.injector
+            .register_typed::<dyn AuditLogSink, _, _, _>(|i| async move {
+                if let Some(callback) = audit_log_callback {
+                    return Arc::new(CallbackAuditLogSink::new(callback)) as Arc<dyn AuditLogSink>;
+                }
+
+                let config = i.get_service_typed::<dyn ConfigObj>().await;
+                match config.audit_log_path() {
+                    Some(path) => match JsonlFileAuditLogSink::new(path) {
+                        Ok(sink) => Arc::new(sink) as Arc<dyn AuditLogSink>,
+                        Err(err) => {
+                            error!("Audit log is disabled: {}", err);
+                            Arc::new(AuditLogSinkNoopImpl::new())
+                        }
+                    },
+                    None => Arc::new(AuditLogSinkNoopImpl::new()),
+                }
+            })
+            .await;
+
         self.injector
             .register_typed::<dyn CompilerCache, _, _, _>(|i| async move {
                 let config = i.get_service_typed::<dyn ConfigObj>().await;
@@ -338,6 +603,8 @@ impl Config {
                     i.get_service_typed().await,
                     i.get_service_typed().await,
                     i.get_service_typed().await,
+                    i.get_service_typed().await,
+                    i.get_service_typed().await,
                     config.nonce().clone(),
                     config.clone(),
                 ))
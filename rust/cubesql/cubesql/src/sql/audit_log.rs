@@ -0,0 +1,422 @@
+use std::{
+    fmt::{Debug, Formatter},
+    fs::OpenOptions,
+    io::{BufWriter, Write},
+    sync::Arc,
+    thread::JoinHandle,
+};
+
+use async_trait::async_trait;
+use datafusion::logical_plan::{LogicalPlan, PlanVisitor};
+use serde::Serialize;
+use sqlparser::{dialect::PostgreSqlDialect, parser::Parser};
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+use crate::{
+    compile::{
+        engine::df::{scan::CubeScanNode, wrapper::CubeScanWrappedSqlNode},
+        DatabaseProtocolDetails,
+    },
+    sql::{statement::LiteralRedactor, SessionState},
+    transport::SpanId,
+    CubeError,
+};
+
+/// One record per SQL API statement
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogRecord {
+    pub timestamp: String,
+    pub span_id: String,
+    pub connection_id: u32,
+    pub protocol: String,
+    pub user: Option<String>,
+    pub security_context_hash: Option<String>,
+    pub application_name: Option<String>,
+    pub query_tag: Option<String>,
+    pub sql: String,
+    pub pushed_down: bool,
+    pub load_requests: Vec<serde_json::Value>,
+    pub rows: u64,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+}
+
+impl AuditLogRecord {
+    pub fn new(
+        state: &SessionState,
+        span_id: &SpanId,
+        error: Option<String>,
+        redact_literals: bool,
+    ) -> Self {
+        let audit = span_id.audit();
+        let sql = span_id
+            .query_key
+            .get("sql")
+            .and_then(|sql| sql.as_str())
+            .unwrap_or_default();
+        let (sql, load_requests) = if redact_literals {
+            let mut load_requests = audit.load_requests;
+            load_requests.iter_mut().for_each(redact_load_request);
+
+            (redact_sql(sql), load_requests)
+        } else {
+            (sql.to_string(), audit.load_requests)
+        };
+
+        let application_name = state
+            .get_variable("application_name")
+            .and_then(|v| match v.value {
+                datafusion::scalar::ScalarValue::Utf8(name) => name,
+                _ => None,
+            });
+
+        Self {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            span_id: span_id.span_id.clone(),
+            connection_id: state.connection_id,
+            protocol: state.protocol.get_name().to_string(),
+            user: state.user(),
+            security_context_hash: state.security_context_hash(),
+            application_name,
+            query_tag: state.query_tag(),
+            sql,
+            pushed_down: audit.pushed_down,
+            load_requests,
+            rows: audit.rows,
+            duration_ms: span_id.duration(),
+            error,
+        }
+    }
+}
+
+#[async_trait]
+pub trait AuditLogSink: Send + Sync + Debug {
+    async fn log(&self, record: AuditLogRecord) -> Result<(), CubeError>;
+
+    fn is_enabled(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug)]
+pub struct AuditLogSinkNoopImpl {}
+
+impl AuditLogSinkNoopImpl {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait]
+impl AuditLogSink for AuditLogSinkNoopImpl {
+    async fn log(&self, _record: AuditLogRecord) -> Result<(), CubeError> {
+        Ok(())
+    }
+
+    fn is_enabled(&self) -> bool {
+        false
+    }
+}
+
+crate::di_service!(AuditLogSinkNoopImpl, [AuditLogSink]);
+
+/// Max number of records waiting for the writer, logging waits when the writer falls behind
+const JSONL_AUDIT_LOG_QUEUE_SIZE: usize = 4096;
+
+/// Appends records as JSON lines to the file. Writes happen on a dedicated thread, so the
+/// connection waits for disk I/O only when the queue is full. Records are never dropped.
+#[derive(Debug)]
+pub struct JsonlFileAuditLogSink {
+    sender: Option<Sender<String>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl JsonlFileAuditLogSink {
+    pub fn new(path: &str) -> Result<Self, CubeError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| {
+                CubeError::internal(format!("Unable to open audit log '{}': {}", path, e))
+            })?;
+
+        let (sender, receiver) = channel(JSONL_AUDIT_LOG_QUEUE_SIZE);
+        let writer = std::thread::Builder::new()
+            .name("audit-log-writer".to_string())
+            .spawn(move || Self::write_loop(BufWriter::new(file), receiver))?;
+
+        Ok(Self {
+            sender: Some(sender),
+            writer: Some(writer),
+        })
+    }
+
+    fn write_loop(mut file: impl Write, mut receiver: Receiver<String>) {
+        // Ends when the sink is dropped
+        while let Some(line) = receiver.blocking_recv() {
+            let mut result = file.write_all(line.as_bytes());
+            // Write everything which is already queued before flushing
+            while result.is_ok() {
+                match receiver.try_recv() {
+                    Ok(line) => result = file.write_all(line.as_bytes()),
+                    Err(_) => break,
+                }
+            }
+
+            if let Err(err) = result.and_then(|_| file.flush()) {
+                log::error!("Unable to write audit log: {}", err);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl AuditLogSink for JsonlFileAuditLogSink {
+    async fn log(&self, record: AuditLogRecord) -> Result<(), CubeError> {
+        let mut line = serde_json::to_string(&record)?;
+        line.push('\n');
+
+        let sender = self
+            .sender
+            .as_ref()
+            .ok_or_else(|| CubeError::internal("Audit log is closed".to_string()))?;
+        sender.send(line).await.map_err(|_| {
+            CubeError::internal("Audit log writer is stopped, record was not written".to_string())
+        })
+    }
+}
+
+impl Drop for JsonlFileAuditLogSink {
+    fn drop(&mut self) {
+        // Closing the channel lets the writer flush queued records and exit
+        self.sender.take();
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+crate::di_service!(JsonlFileAuditLogSink, [AuditLogSink]);
+
+pub type AuditLogCallback = Arc<dyn Fn(AuditLogRecord) -> Result<(), CubeError> + Send + Sync>;
+
+/// Passes records to the callback, for example to send them to an external system
+pub struct CallbackAuditLogSink {
+    callback: AuditLogCallback,
+}
+
+impl CallbackAuditLogSink {
+    pub fn new(callback: AuditLogCallback) -> Self {
+        Self { callback }
+    }
+}
+
+impl Debug for CallbackAuditLogSink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CallbackAuditLogSink")
+    }
+}
+
+#[async_trait]
+impl AuditLogSink for CallbackAuditLogSink {
+    async fn log(&self, record: AuditLogRecord) -> Result<(), CubeError> {
+        (self.callback)(record)
+    }
+}
+
+crate::di_service!(CallbackAuditLogSink, [AuditLogSink]);
+
+/// Returns whether the plan was pushed down to SQL and the Cube load requests of the plan
+pub fn plan_audit_details(plan: &LogicalPlan) -> (bool, Vec<serde_json::Value>) {
+    struct AuditVisitor {
+        pushed_down: bool,
+        load_requests: Vec<serde_json::Value>,
+    }
+
+    impl PlanVisitor for AuditVisitor {
+        type Error = CubeError;
+
+        fn pre_visit(&mut self, plan: &LogicalPlan) -> Result<bool, Self::Error> {
+            if let LogicalPlan::Extension(ext) = plan {
+                if let Some(scan_node) = ext.node.as_any().downcast_ref::<CubeScanNode>() {
+                    self.load_requests
+                        .push(serde_json::to_value(&scan_node.request)?);
+                } else if let Some(wrapped_node) =
+                    ext.node.as_any().downcast_ref::<CubeScanWrappedSqlNode>()
+                {
+                    self.pushed_down = true;
+                    self.load_requests
+                        .push(serde_json::to_value(&wrapped_node.request)?);
+
+                    return Ok(false);
+                }
+            }
+
+            Ok(true)
+        }
+    }
+
+    let mut visitor = AuditVisitor {
+        pushed_down: false,
+        load_requests: Vec::new(),
+    };
+    if let Err(err) = plan.accept(&mut visitor) {
+        log::error!("Unable to collect audit details of the plan: {}", err);
+    }
+
+    (visitor.pushed_down, visitor.load_requests)
+}
+
+/// Replaces literal values in the SQL, unparsable SQL is hidden completely
+pub fn redact_sql(sql: &str) -> String {
+    match Parser::parse_sql(&PostgreSqlDialect {}, sql) {
+        Ok(statements) => statements
+            .into_iter()
+            .map(|stmt| LiteralRedactor::new().replace(stmt).to_string())
+            .collect::<Vec<_>>()
+            .join("; "),
+        Err(_) => "[REDACTED]".to_string(),
+    }
+}
+
+/// Replaces filter values of the Cube load request
+pub fn redact_load_request(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if key == "values" || key == "dateRange" {
+                    redact_values(value);
+                } else {
+                    redact_load_request(value);
+                }
+            }
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(redact_load_request),
+        _ => (),
+    }
+}
+
+fn redact_values(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Array(values) => values.iter_mut().for_each(redact_values),
+        serde_json::Value::Null => (),
+        _ => *value = serde_json::Value::String("?".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_redact_sql() {
+        assert_eq!(
+            redact_sql("SELECT * FROM orders WHERE status = 'new' AND amount > 10"),
+            "SELECT * FROM orders WHERE status = ? AND amount > ?"
+        );
+        assert_eq!(redact_sql("SELEC 'secret"), "[REDACTED]");
+    }
+
+    #[test]
+    fn test_redact_load_request() {
+        let mut request = serde_json::json!({
+            "measures": ["Orders.count"],
+            "filters": [
+                { "member": "Orders.status", "operator": "equals", "values": ["new", null] },
+                { "or": [{ "member": "Orders.amount", "operator": "gt", "values": ["10"] }] }
+            ],
+            "timeDimensions": [
+                { "dimension": "Orders.createdAt", "dateRange": ["2024-01-01", "2024-02-01"] }
+            ]
+        });
+        redact_load_request(&mut request);
+
+        assert_eq!(
+            request,
+            serde_json::json!({
+                "measures": ["Orders.count"],
+                "filters": [
+                    { "member": "Orders.status", "operator": "equals", "values": ["?", null] },
+                    { "or": [{ "member": "Orders.amount", "operator": "gt", "values": ["?"] }] }
+                ],
+                "timeDimensions": [
+                    { "dimension": "Orders.createdAt", "dateRange": ["?", "?"] }
+                ]
+            })
+        );
+    }
+
+    fn test_record(connection_id: u32) -> AuditLogRecord {
+        AuditLogRecord {
+            timestamp: "2024-01-01T00:00:00+00:00".to_string(),
+            span_id: "span".to_string(),
+            connection_id,
+            protocol: "postgres".to_string(),
+            user: Some("ovr".to_string()),
+            security_context_hash: None,
+            application_name: None,
+            query_tag: None,
+            sql: "SELECT 1".to_string(),
+            pushed_down: false,
+            load_requests: vec![],
+            rows: 1,
+            duration_ms: 5,
+            error: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_jsonl_file_audit_log_sink() -> Result<(), CubeError> {
+        let path =
+            std::env::temp_dir().join(format!("cubesql-audit-{}.jsonl", uuid::Uuid::new_v4()));
+        let sink = JsonlFileAuditLogSink::new(&path.to_string_lossy())?;
+
+        // More records than the queue can hold, logging waits for the writer instead of dropping
+        for connection_id in 1..=(JSONL_AUDIT_LOG_QUEUE_SIZE as u32 * 2) {
+            sink.log(test_record(connection_id)).await?;
+        }
+        // Waits for the writer to flush queued records
+        drop(sink);
+
+        let content = std::fs::read_to_string(&path)?;
+        let lines = content.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), JSONL_AUDIT_LOG_QUEUE_SIZE * 2);
+        let record: serde_json::Value = serde_json::from_str(lines[1])?;
+        assert_eq!(record["connectionId"], 2);
+        assert_eq!(record["sql"], "SELECT 1");
+
+        std::fs::remove_file(&path)?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_callback_audit_log_sink() -> Result<(), CubeError> {
+        let records = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let config = Config::test().with_audit_log_callback({
+            let records = records.clone();
+            Arc::new(move |record| {
+                records.lock().unwrap().push(record);
+                Ok(())
+            })
+        });
+        config.configure_injector().await;
+
+        let sink = config
+            .injector()
+            .get_service_typed::<dyn AuditLogSink>()
+            .await;
+        assert!(sink.is_enabled());
+        sink.log(test_record(1)).await?;
+
+        let records = records.lock().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].connection_id, 1);
+        assert_eq!(records[0].sql, "SELECT 1");
+
+        Ok(())
+    }
+}
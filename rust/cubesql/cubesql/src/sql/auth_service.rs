@@ -8,6 +8,11 @@ use crate::CubeError;
 // Any type will allow us to split (with downcast) auth context into HTTP (standalone) or Native
 pub trait AuthContext: Debug + Send + Sync {
     fn as_any(&self) -> &dyn Any;
+
+    // Used to identify the security context in the audit log without storing it
+    fn security_context(&self) -> Option<&serde_json::Value> {
+        None
+    }
 }
 
 pub type AuthContextRef = Arc<dyn AuthContext>;
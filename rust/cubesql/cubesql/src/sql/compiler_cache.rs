@@ -2,11 +2,14 @@ use crate::{
     compile::{
         engine::CubeContext,
         qtrace::Qtrace,
-        rewrite::rewriter::{CubeEGraph, CubeRewrite, Rewriter},
+        rewrite::{
+            persisted::PersistedEGraph,
+            rewriter::{CubeEGraph, CubeRewrite, Rewriter},
+        },
         DatabaseProtocol,
     },
     config::ConfigObj,
-    sql::AuthContextRef,
+    sql::{persisted_rewrite_cache::PersistedRewriteCache, AuthContextRef},
     transport::{MetaContext, TransportService},
     utils::egraph_hash,
     CubeError, MutexAsync, RWLockAsync,
@@ -14,7 +17,7 @@ use crate::{
 use async_trait::async_trait;
 use datafusion::scalar::ScalarValue;
 use lru::LruCache;
-use std::{collections::HashMap, fmt::Debug, num::NonZeroUsize, sync::Arc};
+use std::{collections::HashMap, fmt::Debug, num::NonZeroUsize, path::Path, sync::Arc};
 use uuid::Uuid;
 
 #[async_trait]
@@ -59,10 +62,14 @@ pub struct CompilerCacheImpl {
     config_obj: Arc<dyn ConfigObj>,
     transport: Arc<dyn TransportService>,
     compiler_id_to_entry: MutexAsync<LruCache<(Uuid, DatabaseProtocol), Arc<CompilerCacheEntry>>>,
+    persisted_cache: Option<PersistedRewriteCache>,
 }
 
 pub struct CompilerCacheEntry {
     meta_context: Arc<MetaContext>,
+    protocol: DatabaseProtocol,
+    // Set only if the persisted rewrite cache is enabled
+    schema_version: Option<String>,
     rewrite_rules: RWLockAsync<HashMap<bool, Arc<Vec<CubeRewrite>>>>,
     parameterized_cache: MutexAsync<LruCache<[u8; 32], CubeEGraph>>,
     queries_cache: MutexAsync<LruCache<[u8; 32], CubeEGraph>>,
@@ -123,15 +130,36 @@ impl CompilerCache for CompilerCacheImpl {
         let cache_entry_clone = Arc::clone(&cache_entry);
         let mut rewrites_cache_lock = cache_entry.parameterized_cache.lock().await;
         if let Some(rewrite_entry) = rewrites_cache_lock.get(&graph_key) {
-            Ok(rewrite_entry.clone())
-        } else {
-            let mut rewriter = Rewriter::new(parameterized_graph, cube_context);
-            let rewrite_entry = rewriter
-                .run_rewrite_to_completion(cache_entry_clone, qtrace)
-                .await?;
+            cube_context.session_state.set_rewrite_cache_hit(true);
+            return Ok(rewrite_entry.clone());
+        }
+
+        if let Some(rewrite_entry) = self
+            .get_persisted_rewrite(&cache_entry, &graph_key, &parameterized_graph)
+            .await
+        {
+            cube_context.session_state.set_rewrite_cache_hit(true);
+            rewrites_cache_lock.put(graph_key, rewrite_entry.clone());
+            return Ok(rewrite_entry);
+        }
+
+        let input_ids = parameterized_graph
+            .classes()
+            .map(|class| class.id)
+            .collect::<Vec<_>>();
+        let mut rewriter = Rewriter::new(parameterized_graph, cube_context.clone());
+        let rewrite_entry = rewriter
+            .run_rewrite_to_completion(cache_entry_clone, qtrace)
+            .await?;
+        // Unfinished rewrites shouldn't be reused by other queries
+        if !cube_context.session_state.is_rewrite_budget_exhausted() {
             rewrites_cache_lock.put(graph_key, rewrite_entry.clone());
-            Ok(rewrite_entry)
+            drop(rewrites_cache_lock);
+
+            self.put_persisted_rewrite(&cache_entry, &graph_key, &rewrite_entry, &input_ids)
+                .await;
         }
+        Ok(rewrite_entry)
     }
 
     async fn rewrite(
@@ -155,6 +183,7 @@ impl CompilerCache for CompilerCacheImpl {
         let cache_entry_clone = Arc::clone(&cache_entry);
         let mut rewrites_cache_lock = cache_entry.queries_cache.lock().await;
         if let Some(plan) = rewrites_cache_lock.get(&graph_key) {
+            cube_context.session_state.set_rewrite_cache_hit(false);
             Ok(plan.clone())
         } else {
             let graph = if self.config_obj.enable_parameterized_rewrite_cache() {
@@ -168,12 +197,15 @@ impl CompilerCache for CompilerCacheImpl {
             } else {
                 input_plan
             };
-            let mut rewriter = Rewriter::new(graph, cube_context);
+            let mut rewriter = Rewriter::new(graph, cube_context.clone());
             rewriter.add_param_values(param_values)?;
             let final_plan = rewriter
                 .run_rewrite_to_completion(cache_entry_clone, qtrace)
                 .await?;
-            rewrites_cache_lock.put(graph_key, final_plan.clone());
+            // Unfinished rewrites shouldn't be reused by other queries
+            if !cube_context.session_state.is_rewrite_budget_exhausted() {
+                rewrites_cache_lock.put(graph_key, final_plan.clone());
+            }
             Ok(final_plan)
         }
     }
@@ -203,6 +235,11 @@ impl CompilerCache for CompilerCacheImpl {
                 .unwrap_or_else(|| {
                     let cache_entry = Arc::new(CompilerCacheEntry {
                         meta_context: meta_context.clone(),
+                        protocol: protocol.clone(),
+                        schema_version: self
+                            .persisted_cache
+                            .as_ref()
+                            .map(|_| PersistedRewriteCache::schema_version(&meta_context)),
                         rewrite_rules: RWLockAsync::new(HashMap::new()),
                         parameterized_cache: MutexAsync::new(LruCache::new(
                             NonZeroUsize::new(self.config_obj.query_cache_size()).unwrap(),
@@ -225,12 +262,82 @@ impl CompilerCache for CompilerCacheImpl {
 impl CompilerCacheImpl {
     pub fn new(config_obj: Arc<dyn ConfigObj>, transport: Arc<dyn TransportService>) -> Self {
         let compiler_cache_size = config_obj.compiler_cache_size();
+        let persisted_cache = config_obj
+            .persisted_rewrite_cache_dir()
+            .as_ref()
+            .and_then(|dir| {
+                match PersistedRewriteCache::new(
+                    Path::new(dir),
+                    config_obj.persisted_rewrite_cache_size(),
+                ) {
+                    Ok(cache) => Some(cache),
+                    Err(err) => {
+                        log::error!("Persisted rewrite cache is disabled: {}", err);
+                        None
+                    }
+                }
+            });
         CompilerCacheImpl {
             config_obj,
             transport,
             compiler_id_to_entry: MutexAsync::new(LruCache::new(
                 NonZeroUsize::new(compiler_cache_size).unwrap(),
             )),
+            persisted_cache,
+        }
+    }
+
+    async fn get_persisted_rewrite(
+        &self,
+        cache_entry: &CompilerCacheEntry,
+        graph_key: &[u8; 32],
+        input_graph: &CubeEGraph,
+    ) -> Option<CubeEGraph> {
+        let (Some(persisted_cache), Some(schema_version)) =
+            (&self.persisted_cache, &cache_entry.schema_version)
+        else {
+            return None;
+        };
+
+        let persisted_graph = persisted_cache
+            .get(&cache_entry.protocol, graph_key, schema_version)
+            .await?;
+        match persisted_graph.restore(input_graph.clone()) {
+            Ok(graph) => Some(graph),
+            Err(err) => {
+                log::warn!("Unable to restore persisted rewrite: {}", err);
+                None
+            }
+        }
+    }
+
+    async fn put_persisted_rewrite(
+        &self,
+        cache_entry: &CompilerCacheEntry,
+        graph_key: &[u8; 32],
+        rewrite_entry: &CubeEGraph,
+        input_ids: &[egg::Id],
+    ) {
+        let (Some(persisted_cache), Some(schema_version)) =
+            (&self.persisted_cache, &cache_entry.schema_version)
+        else {
+            return;
+        };
+
+        // Graphs with data which can't be persisted are kept in memory only
+        let Some(persisted_graph) = PersistedEGraph::from_egraph(rewrite_entry, input_ids) else {
+            return;
+        };
+        if let Err(err) = persisted_cache
+            .put(
+                &cache_entry.protocol,
+                graph_key,
+                schema_version,
+                persisted_graph,
+            )
+            .await
+        {
+            log::warn!("Unable to persist rewrite: {}", err);
         }
     }
 }
@@ -1,5 +1,5 @@
 use datafusion::scalar::ScalarValue;
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use crate::compile::{DatabaseVariable, DatabaseVariables};
 
@@ -105,5 +105,118 @@ pub fn defaults() -> DatabaseVariables {
         ),
     );
 
+    variables.insert(
+        QUERY_TAG_VARIABLE.to_string(),
+        DatabaseVariable::system(
+            QUERY_TAG_VARIABLE.to_string(),
+            ScalarValue::Utf8(None),
+            None,
+        ),
+    );
+
+    // NULL means that the server-wide default from the config is used
+    for name in TIMEOUT_VARIABLES {
+        variables.insert(
+            name.to_string(),
+            DatabaseVariable::system(name.to_string(), ScalarValue::Utf8(None), None),
+        );
+    }
+
     variables
 }
+
+/// Tag which is attached to every load of the session, see `SessionState::query_tag`
+pub const QUERY_TAG_VARIABLE: &str = "cube.query_tag";
+
+pub const TIMEOUT_VARIABLES: [&str; 3] = [
+    "statement_timeout",
+    "idle_in_transaction_session_timeout",
+    "idle_session_timeout",
+];
+
+/// Parses a PostgreSQL time setting, like `30s` or `500`, into milliseconds.
+/// A value without a unit is treated as milliseconds.
+pub fn parse_timeout_ms(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let unit_pos = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let number = value[..unit_pos].parse::<f64>().ok()?;
+    let multiplier = match value[unit_pos..].trim() {
+        "us" => 0.001,
+        "" | "ms" => 1.0,
+        "s" => 1000.0,
+        "min" => 60.0 * 1000.0,
+        "h" => 60.0 * 60.0 * 1000.0,
+        "d" => 24.0 * 60.0 * 60.0 * 1000.0,
+        _ => return None,
+    };
+
+    let ms = (number * multiplier).round();
+    if ms.is_finite() && ms <= i32::MAX as f64 {
+        Some(ms as u64)
+    } else {
+        None
+    }
+}
+
+/// Resolves the effective timeout for a session: an unset value falls back to the server
+/// default, and the server maximum (if any) caps the value, including disabled (0) ones.
+pub fn resolve_timeout(
+    session_ms: Option<u64>,
+    default_secs: u64,
+    max_secs: u64,
+) -> Option<Duration> {
+    let max_ms = max_secs * 1000;
+    let ms = match session_ms.unwrap_or(default_secs * 1000) {
+        ms if max_ms > 0 && (ms == 0 || ms > max_ms) => max_ms,
+        ms => ms,
+    };
+
+    if ms == 0 {
+        None
+    } else {
+        Some(Duration::from_millis(ms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timeout_ms() {
+        assert_eq!(parse_timeout_ms("0"), Some(0));
+        assert_eq!(parse_timeout_ms("1500"), Some(1500));
+        assert_eq!(parse_timeout_ms("250ms"), Some(250));
+        assert_eq!(parse_timeout_ms("30s"), Some(30_000));
+        assert_eq!(parse_timeout_ms("1.5 s"), Some(1500));
+        assert_eq!(parse_timeout_ms("2min"), Some(120_000));
+        assert_eq!(parse_timeout_ms("1h"), Some(3_600_000));
+        assert_eq!(parse_timeout_ms("1d"), Some(86_400_000));
+        assert_eq!(parse_timeout_ms("10 years"), None);
+        assert_eq!(parse_timeout_ms("abc"), None);
+        assert_eq!(parse_timeout_ms("-1s"), None);
+        assert_eq!(parse_timeout_ms("1000d"), None);
+    }
+
+    #[test]
+    fn test_resolve_timeout() {
+        assert_eq!(resolve_timeout(None, 0, 0), None);
+        assert_eq!(resolve_timeout(None, 30, 0), Some(Duration::from_secs(30)));
+        assert_eq!(
+            resolve_timeout(Some(500), 30, 0),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(resolve_timeout(Some(0), 30, 0), None);
+        assert_eq!(
+            resolve_timeout(Some(0), 30, 60),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            resolve_timeout(Some(120_000), 30, 60),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(resolve_timeout(None, 0, 60), Some(Duration::from_secs(60)));
+    }
+}
@@ -10,8 +10,8 @@ use chrono_tz::Tz;
 use comfy_table::{Cell, Table};
 use datafusion::arrow::{
     array::{
-        Array, ArrayRef, BooleanArray, Date32Array, Date64Array, DecimalArray, Float16Array,
-        Float32Array, Float64Array, Int16Array, Int32Array, Int64Array, Int8Array,
+        Array, ArrayRef, BinaryArray, BooleanArray, Date32Array, Date64Array, DecimalArray,
+        Float16Array, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array, Int8Array,
         IntervalDayTimeArray, IntervalMonthDayNanoArray, IntervalYearMonthArray, LargeStringArray,
         ListArray, StringArray, TimestampMicrosecondArray, TimestampMillisecondArray,
         TimestampNanosecondArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
@@ -103,6 +103,18 @@ pub enum TableValue {
     Date(NaiveDate),
     Timestamp(TimestampValue),
     Interval(IntervalValue),
+    Binary(Vec<u8>),
+}
+
+/// bytea in the hex format, which is the default output format of Postgres
+fn format_binary(v: &[u8]) -> String {
+    let mut res = String::with_capacity(2 + v.len() * 2);
+    res.push_str("\\x");
+    for byte in v {
+        res.push_str(&format!("{:02x}", byte));
+    }
+
+    res
 }
 
 impl Serialize for TableValue {
@@ -124,6 +136,7 @@ impl Serialize for TableValue {
             TableValue::Interval(ref val) => serializer.serialize_str(val.to_string().as_str()),
             TableValue::Date(ref val) => serializer.serialize_str(val.to_string().as_str()),
             TableValue::List(ref val) => serializer.serialize_str(val.to_string().as_str()),
+            TableValue::Binary(ref val) => serializer.serialize_str(&format_binary(val)),
         }
     }
 }
@@ -144,6 +157,7 @@ impl ToString for TableValue {
             TableValue::Decimal128(v) => v.to_string(),
             TableValue::Interval(v) => v.to_string(),
             TableValue::List(v) => v.to_string(),
+            TableValue::Binary(v) => format_binary(v),
         }
     }
 }
@@ -467,6 +481,9 @@ pub fn batches_to_dataframe(
                 DataType::Float64 => {
                     convert_array!(array, num_rows, rows, Float64Array, Float64, f64)
                 }
+                DataType::Binary => {
+                    convert_array!(array, num_rows, rows, BinaryArray, Binary, (Vec<u8>))
+                }
                 DataType::Utf8 => {
                     let a = array.as_any().downcast_ref::<StringArray>().unwrap();
                     for i in 0..num_rows {
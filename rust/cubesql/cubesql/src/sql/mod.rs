@@ -1,7 +1,9 @@
+pub mod audit_log;
 pub(crate) mod auth_service;
 pub mod compiler_cache;
 pub(crate) mod database_variables;
 pub mod dataframe;
+pub(crate) mod persisted_rewrite_cache;
 pub(crate) mod postgres;
 pub(crate) mod server_manager;
 pub(crate) mod session;
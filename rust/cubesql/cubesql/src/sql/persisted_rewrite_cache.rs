@@ -0,0 +1,226 @@
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    compile::{rewrite::persisted::PersistedEGraph, DatabaseProtocol, DatabaseProtocolDetails},
+    transport::MetaContext,
+    CubeError, MutexAsync,
+};
+
+/// Parameterized rewrites stored on the disk, so warm-up of the rewrite cache survives restarts.
+/// Every entry is a separate file, least recently used entries are evicted when the total size
+/// of the directory goes above the limit.
+#[derive(Debug)]
+pub struct PersistedRewriteCache {
+    dir: PathBuf,
+    max_size: u64,
+    state: MutexAsync<PersistedRewriteCacheState>,
+}
+
+#[derive(Debug)]
+struct PersistedRewriteCacheState {
+    // File name -> file size
+    entries: LruCache<String, u64>,
+    size: u64,
+}
+
+impl PersistedRewriteCacheState {
+    fn evict(&mut self, max_size: u64) -> Vec<String> {
+        let mut removed = Vec::new();
+        while self.size > max_size {
+            let Some((file_name, size)) = self.entries.pop_lru() else {
+                break;
+            };
+            self.size -= size;
+            removed.push(file_name);
+        }
+
+        removed
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedRewriteEntry {
+    schema_version: String,
+    graph: PersistedEGraph,
+}
+
+impl PersistedRewriteCache {
+    const EXTENSION: &'static str = "egraph";
+
+    /// `max_size` is in MiB
+    pub fn new(dir: &Path, max_size: usize) -> Result<Self, CubeError> {
+        std::fs::create_dir_all(dir)?;
+
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some(Self::EXTENSION) => {}
+                // Leftover of an interrupted write
+                Some("tmp") => {
+                    let _ = std::fs::remove_file(&path);
+                    continue;
+                }
+                _ => continue,
+            }
+
+            let metadata = entry.metadata()?;
+            let accessed = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            files.push((
+                entry.file_name().to_string_lossy().to_string(),
+                metadata.len(),
+                accessed,
+            ));
+        }
+        // Oldest files are the first to evict
+        files.sort_by_key(|(_, _, accessed)| *accessed);
+
+        let mut state = PersistedRewriteCacheState {
+            entries: LruCache::unbounded(),
+            size: 0,
+        };
+        for (file_name, size, _) in files {
+            state.size += size;
+            state.entries.put(file_name, size);
+        }
+
+        let max_size = max_size as u64 * 1024 * 1024;
+        let removed = state.evict(max_size);
+        let cache = Self {
+            dir: dir.to_path_buf(),
+            max_size,
+            state: MutexAsync::new(state),
+        };
+        cache.remove_files(removed);
+
+        Ok(cache)
+    }
+
+    /// Changes of the data model or of the rewrite rules between versions make persisted rewrites stale.
+    pub fn schema_version(meta_context: &MetaContext) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+        for cube in &meta_context.cubes {
+            hasher.update(serde_json::to_vec(cube).unwrap_or_default());
+        }
+
+        hex_string(&hasher.finalize())
+    }
+
+    pub async fn get(
+        &self,
+        protocol: &DatabaseProtocol,
+        graph_key: &[u8; 32],
+        schema_version: &str,
+    ) -> Option<PersistedEGraph> {
+        let file_name = Self::file_name(protocol, graph_key, schema_version);
+        {
+            let mut state = self.state.lock().await;
+            state.entries.get(&file_name)?;
+        }
+
+        let entry = tokio::fs::read(self.dir.join(&file_name))
+            .await
+            .ok()
+            .and_then(|data| bincode::deserialize::<PersistedRewriteEntry>(&data).ok());
+        match entry {
+            Some(entry) if entry.schema_version == schema_version => Some(entry.graph),
+            _ => {
+                // Unreadable or written for another schema version
+                self.remove(&file_name).await;
+                None
+            }
+        }
+    }
+
+    pub async fn put(
+        &self,
+        protocol: &DatabaseProtocol,
+        graph_key: &[u8; 32],
+        schema_version: &str,
+        graph: PersistedEGraph,
+    ) -> Result<(), CubeError> {
+        let file_name = Self::file_name(protocol, graph_key, schema_version);
+        let data = bincode::serialize(&PersistedRewriteEntry {
+            schema_version: schema_version.to_string(),
+            graph,
+        })
+        .map_err(|e| CubeError::internal(e.to_string()))?;
+        let size = data.len() as u64;
+        if size > self.max_size {
+            return Ok(());
+        }
+
+        // Write to a temporary file first, so readers never see partially written entries
+        let tmp_path = self.dir.join(format!("{}.tmp", file_name));
+        tokio::fs::write(&tmp_path, data).await?;
+        tokio::fs::rename(&tmp_path, self.dir.join(&file_name)).await?;
+
+        let removed = {
+            let mut state = self.state.lock().await;
+            if let Some(previous_size) = state.entries.put(file_name, size) {
+                state.size -= previous_size;
+            }
+            state.size += size;
+            state.evict(self.max_size)
+        };
+        self.remove_files(removed);
+
+        Ok(())
+    }
+
+    async fn remove(&self, file_name: &str) {
+        let removed = {
+            let mut state = self.state.lock().await;
+            match state.entries.pop(file_name) {
+                Some(size) => {
+                    state.size -= size;
+                    true
+                }
+                None => false,
+            }
+        };
+        if removed {
+            self.remove_files(vec![file_name.to_string()]);
+        }
+    }
+
+    fn remove_files(&self, file_names: Vec<String>) {
+        for file_name in file_names {
+            if let Err(e) = std::fs::remove_file(self.dir.join(&file_name)) {
+                log::warn!(
+                    "Unable to remove persisted rewrite cache entry {}: {}",
+                    file_name,
+                    e
+                );
+            }
+        }
+    }
+
+    /// Compiler id is not a part of the key, because it changes on every compilation of the same
+    /// data model, including restarts. The schema version is used to tell data models apart.
+    fn file_name(
+        protocol: &DatabaseProtocol,
+        graph_key: &[u8; 32],
+        schema_version: &str,
+    ) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(schema_version.as_bytes());
+        hasher.update(protocol.get_name().as_bytes());
+        hasher.update(graph_key);
+
+        format!("{}.{}", hex_string(&hasher.finalize()), Self::EXTENSION)
+    }
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -1,5 +1,5 @@
 use crate::{
-    compile::QueryPlan,
+    compile::{parser::NotificationStatement, QueryPlan},
     sql::{
         dataframe::{batches_to_dataframe, DataFrame, TableValue},
         statement::PostgresStatementParamsBinder,
@@ -58,6 +58,14 @@ pub enum PreparedStatement {
         description: Option<protocol::RowDescription>,
         span_id: Option<Arc<SpanId>>,
     },
+    /// LISTEN/UNLISTEN/NOTIFY, which cannot be represented as ast::Statement
+    Notification {
+        /// Prepared statement can be declared from SQL or protocol (Parser)
+        from_sql: bool,
+        created: DateTime<Utc>,
+        statement: NotificationStatement,
+        span_id: Option<Arc<SpanId>>,
+    },
     Error {
         /// Prepared statement can be declared from SQL or protocol (Parser)
         from_sql: bool,
@@ -72,6 +80,7 @@ impl PreparedStatement {
         match self {
             PreparedStatement::Empty { created, .. } => created,
             PreparedStatement::Query { created, .. } => created,
+            PreparedStatement::Notification { created, .. } => created,
             PreparedStatement::Error { created, .. } => created,
         }
     }
@@ -81,6 +90,7 @@ impl PreparedStatement {
         match self {
             PreparedStatement::Empty { .. } => "".to_string(),
             PreparedStatement::Query { query, .. } => query.to_string(),
+            PreparedStatement::Notification { statement, .. } => statement.to_string(),
             PreparedStatement::Error { sql, .. } => sql.clone(),
         }
     }
@@ -89,6 +99,7 @@ impl PreparedStatement {
         match self {
             PreparedStatement::Empty { from_sql, .. } => from_sql.clone(),
             PreparedStatement::Query { from_sql, .. } => from_sql.clone(),
+            PreparedStatement::Notification { from_sql, .. } => from_sql.clone(),
             PreparedStatement::Error { from_sql, .. } => from_sql.clone(),
         }
     }
@@ -97,6 +108,7 @@ impl PreparedStatement {
         match self {
             PreparedStatement::Empty { .. } => None,
             PreparedStatement::Query { parameters, .. } => Some(&parameters.parameters),
+            PreparedStatement::Notification { .. } => None,
             PreparedStatement::Error { .. } => None,
         }
     }
@@ -114,6 +126,11 @@ impl PreparedStatement {
 
                 Ok(statement)
             }
+            PreparedStatement::Notification { .. } => Err(CubeError::internal(
+                "It's not possible to bind notification prepared statements (it's a bug)"
+                    .to_string(),
+            )
+            .into()),
             PreparedStatement::Error { .. } => Err(CubeError::internal(
                 "It's not possible to bind errored prepared statements (it's a bug)".to_string(),
             )
@@ -125,6 +142,7 @@ impl PreparedStatement {
         match self {
             PreparedStatement::Empty { span_id, .. } => span_id.clone(),
             PreparedStatement::Query { span_id, .. } => span_id.clone(),
+            PreparedStatement::Notification { span_id, .. } => span_id.clone(),
             PreparedStatement::Error { span_id, .. } => span_id.clone(),
         }
     }
@@ -298,6 +316,17 @@ impl Portal {
         }
     }
 
+    /// Data queries are executed by DataFusion and load data via the transport
+    pub fn is_data_query(&self) -> bool {
+        match &self.state {
+            Some(PortalState::Prepared(state)) => matches!(
+                state.plan,
+                QueryPlan::DataFusionSelect(_, _) | QueryPlan::CreateTempTable(_, _, _, _)
+            ),
+            _ => false,
+        }
+    }
+
     pub fn get_format(&self) -> protocol::Format {
         self.format.clone()
     }
@@ -374,6 +403,7 @@ impl Portal {
                     TableValue::Date(v) => writer.write_value(v)?,
                     TableValue::Decimal128(v) => writer.write_value(v)?,
                     TableValue::Interval(v) => writer.write_value(v)?,
+                    TableValue::Binary(v) => writer.write_value(v)?,
                 };
             }
 
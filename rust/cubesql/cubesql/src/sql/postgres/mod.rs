@@ -1,4 +1,5 @@
 pub(crate) mod extended;
+pub mod notification_service;
 pub mod pg_auth_service;
 pub(crate) mod pg_type;
 pub(crate) mod service;
@@ -0,0 +1,84 @@
+use std::fmt::Debug;
+
+use tokio::sync::broadcast;
+
+use crate::CubeError;
+
+/// Max length of the payload, the same limit as in PostgreSQL
+pub const NOTIFICATION_PAYLOAD_MAX_LENGTH: usize = 8000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    /// Connection id of the session which sent NOTIFY
+    pub process_id: u32,
+    pub channel: String,
+    pub payload: String,
+}
+
+/// Delivers NOTIFY messages to all sessions which are waiting for them with LISTEN.
+/// Default implementation works inside one process, It's possible to replace it
+/// with an implementation which shares notifications between multiple instances.
+pub trait NotificationService: Send + Sync + Debug {
+    fn subscribe(&self) -> broadcast::Receiver<Notification>;
+
+    fn notify(&self, notification: Notification) -> Result<(), CubeError>;
+}
+
+#[derive(Debug)]
+pub struct NotificationServiceDefaultImpl {
+    sender: broadcast::Sender<Notification>,
+}
+
+impl NotificationServiceDefaultImpl {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+
+        Self { sender }
+    }
+}
+
+impl NotificationService for NotificationServiceDefaultImpl {
+    fn subscribe(&self) -> broadcast::Receiver<Notification> {
+        self.sender.subscribe()
+    }
+
+    fn notify(&self, notification: Notification) -> Result<(), CubeError> {
+        // It returns error when there are no subscribers, It's ok
+        let _ = self.sender.send(notification);
+
+        Ok(())
+    }
+}
+
+crate::di_service!(NotificationServiceDefaultImpl, [NotificationService]);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_notify_subscribers() -> Result<(), CubeError> {
+        let service = NotificationServiceDefaultImpl::new(16);
+        // Nobody listens yet
+        service.notify(Notification {
+            process_id: 1,
+            channel: "lost".to_string(),
+            payload: "".to_string(),
+        })?;
+
+        let mut first = service.subscribe();
+        let mut second = service.subscribe();
+
+        let notification = Notification {
+            process_id: 1,
+            channel: "ch".to_string(),
+            payload: "payload".to_string(),
+        };
+        service.notify(notification.clone())?;
+
+        assert_eq!(first.recv().await?, notification);
+        assert_eq!(second.recv().await?, notification);
+
+        Ok(())
+    }
+}
@@ -4,15 +4,20 @@ use pg_srv::{protocol, PgTypeId, ProtocolError};
 pub fn df_type_to_pg_tid(dt: &DataType) -> Result<PgTypeId, ProtocolError> {
     match dt {
         DataType::Boolean => Ok(PgTypeId::BOOL),
+        // PG doesnt support i8, casting to i16
+        DataType::Int8 => Ok(PgTypeId::INT2),
+        DataType::UInt8 => Ok(PgTypeId::INT2),
         DataType::Int16 => Ok(PgTypeId::INT2),
         DataType::UInt16 => Ok(PgTypeId::INT2),
         DataType::Int32 => Ok(PgTypeId::INT4),
         DataType::UInt32 => Ok(PgTypeId::INT4),
         DataType::Int64 => Ok(PgTypeId::INT8),
         DataType::UInt64 => Ok(PgTypeId::INT8),
+        DataType::Float16 => Ok(PgTypeId::FLOAT4),
         DataType::Float32 => Ok(PgTypeId::FLOAT4),
         DataType::Float64 => Ok(PgTypeId::FLOAT8),
         DataType::Decimal(_, _) => Ok(PgTypeId::NUMERIC),
+        DataType::Binary => Ok(PgTypeId::BYTEA),
         DataType::Utf8 | DataType::LargeUtf8 => Ok(PgTypeId::TEXT),
         DataType::Date32 | DataType::Date64 => Ok(PgTypeId::DATE),
         DataType::Interval(_) => Ok(PgTypeId::INTERVAL),
@@ -34,8 +39,15 @@ pub fn df_type_to_pg_tid(dt: &DataType) -> Result<PgTypeId, ProtocolError> {
             DataType::Float16 => Ok(PgTypeId::ARRAYFLOAT4),
             DataType::Float32 => Ok(PgTypeId::ARRAYFLOAT4),
             DataType::Float64 => Ok(PgTypeId::ARRAYFLOAT8),
+            DataType::Decimal(_, _) => Ok(PgTypeId::ARRAYNUMERIC),
             DataType::Binary => Ok(PgTypeId::ARRAYBYTEA),
-            DataType::Utf8 => Ok(PgTypeId::ARRAYTEXT),
+            DataType::Utf8 | DataType::LargeUtf8 => Ok(PgTypeId::ARRAYTEXT),
+            DataType::Date32 | DataType::Date64 => Ok(PgTypeId::ARRAYDATE),
+            DataType::Interval(_) => Ok(PgTypeId::ARRAYINTERVAL),
+            DataType::Timestamp(_, tz) => match tz {
+                None => Ok(PgTypeId::ARRAYTIMESTAMP),
+                Some(_) => Ok(PgTypeId::ARRAYTIMESTAMPTZ),
+            },
             dt => Err(protocol::ErrorResponse::error(
                 protocol::ErrorCode::FeatureNotSupported,
                 format!("Unsupported data type in List for pg-wire: {:?}", dt),
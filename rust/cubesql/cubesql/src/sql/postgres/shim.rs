@@ -1,18 +1,31 @@
 use std::{
-    backtrace::Backtrace, collections::HashMap, io::ErrorKind, pin::Pin, sync::Arc,
-    time::SystemTime,
+    backtrace::Backtrace,
+    collections::HashMap,
+    io::ErrorKind,
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, SystemTime},
 };
 
-use super::{extended::PreparedStatement, pg_auth_service::AuthenticationStatus};
+use super::{
+    extended::PreparedStatement, notification_service::Notification,
+    pg_auth_service::AuthenticationStatus,
+};
 use crate::{
     compile::{
+        convert_explain_to_cube_query, convert_notification_to_cube_query,
         convert_statement_to_cube_query,
-        parser::{parse_sql_to_statement, parse_sql_to_statements},
+        parser::{
+            parse_explain_statement, parse_notification_statement, parse_query_tag_comment,
+            parse_sql_to_statement, parse_sql_to_statements,
+        },
         qtrace::Qtrace,
         CommandCompletion, CompilationError, DatabaseProtocol, QueryPlan, StatusFlags,
     },
     sql::{
+        audit_log::AuditLogRecord,
         compiler_cache::CompilerCacheEntry,
+        database_variables::postgres::session_vars::{parse_timeout_ms, resolve_timeout},
         df_type_to_pg_tid,
         extended::{Cursor, Portal, PortalBatch, PortalFrom},
         statement::{PostgresStatementParamsFinder, StatementPlaceholderReplacer},
@@ -22,8 +35,9 @@ use crate::{
     transport::{MetaContext, SpanId},
     CubeError,
 };
+use datafusion::scalar::ScalarValue;
 use futures::{pin_mut, FutureExt, StreamExt};
-use log::{debug, error, trace};
+use log::{debug, error, trace, warn};
 use pg_srv::{
     buffer,
     protocol::{
@@ -33,7 +47,12 @@ use pg_srv::{
     PgType, PgTypeId, ProtocolError,
 };
 use sqlparser::ast::{self, CloseCursor, FetchDirection, Query, SetExpr, Statement, Value};
-use tokio::{io::AsyncWriteExt, net::TcpStream};
+use tokio::{
+    io::AsyncWriteExt,
+    net::TcpStream,
+    sync::{broadcast, OwnedSemaphorePermit},
+    time::{sleep_until, Instant},
+};
 use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
@@ -324,6 +343,78 @@ impl AsyncPostgresShim {
         )
     }
 
+    fn session_timeout(&self, name: &str, default_secs: u64, max_secs: u64) -> Option<Duration> {
+        let session_ms = match self.session.state.get_variable(name).map(|v| v.value) {
+            Some(ScalarValue::Utf8(Some(value))) => parse_timeout_ms(&value),
+            _ => None,
+        };
+
+        resolve_timeout(session_ms, default_secs, max_secs)
+    }
+
+    fn statement_deadline(&self) -> Option<Instant> {
+        let config = &self.session.server.config_obj;
+        self.session_timeout(
+            "statement_timeout",
+            config.statement_timeout(),
+            config.statement_timeout_max(),
+        )
+        .map(|timeout| Instant::now() + timeout)
+    }
+
+    /// Returns the moment when an idle connection should be terminated and the error to send.
+    fn idle_deadline(&self) -> Option<(Instant, ErrorResponse)> {
+        let config = &self.session.server.config_obj;
+        let (timeout, error) = if self.session.state.is_in_transaction() {
+            (
+                self.session_timeout(
+                    "idle_in_transaction_session_timeout",
+                    config.idle_in_transaction_session_timeout(),
+                    config.idle_in_transaction_session_timeout_max(),
+                )?,
+                ErrorResponse::idle_in_transaction_session_timeout(),
+            )
+        } else {
+            (
+                self.session_timeout(
+                    "idle_session_timeout",
+                    config.idle_session_timeout(),
+                    config.idle_session_timeout_max(),
+                )?,
+                ErrorResponse::idle_session_timeout(),
+            )
+        };
+
+        Some((Instant::now() + timeout, error))
+    }
+
+    async fn write_idle_timeout_fatal_message(
+        &mut self,
+        error: Option<ErrorResponse>,
+    ) -> Result<(), ConnectionError> {
+        if let Some(error) = error {
+            self.write(error).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Data queries of the same user are limited by the number of concurrent queries
+    async fn acquire_query_slot(
+        session: &Session,
+        portal: &Portal,
+    ) -> Result<Option<OwnedSemaphorePermit>, ConnectionError> {
+        if !portal.is_data_query() {
+            return Ok(None);
+        }
+
+        session
+            .session_manager
+            .acquire_query_slot(session)
+            .await
+            .map_err(|err| ErrorResponse::error(ErrorCode::TooManyConnections, err.message).into())
+    }
+
     pub async fn run(&mut self) -> Result<(), ConnectionError> {
         let (initial_parameters, auth_method) = match self.process_initial_message().await? {
             StartupState::Success(parameters, auth_method) => (parameters, auth_method),
@@ -353,28 +444,76 @@ impl AsyncPostgresShim {
         // Clone here to avoid conflicting borrows of self in the tokio::select!.
         let semifast_shutdown_interruptor = self.semifast_shutdown_interruptor.clone();
 
+        // Subscription to notifications exists only while session is listening for any channel
+        let mut notifications: Option<broadcast::Receiver<Notification>> = None;
+
         loop {
             let mut doing_extended_query_message = false;
             let semifast_shutdownable = self.is_semifast_shutdownable();
+            // Idle timeouts are armed only on a message boundary, i.e. not in the middle of
+            // an extended query that is being discarded until Sync
+            let (idle_deadline, mut idle_error) = match self.idle_deadline() {
+                Some((deadline, error)) if tracked_error.is_none() => (Some(deadline), Some(error)),
+                _ => (None, None),
+            };
+
+            if !self.session.state.has_listen_channels() {
+                notifications = None;
+            } else if notifications.is_none() {
+                notifications = Some(self.session.server.notifications.subscribe());
+            }
+
+            if let Some(receiver) = notifications.as_mut() {
+                // read_message is not cancellation safe, that's why notifications are delivered
+                // only while we are waiting for the beginning of the next message
+                loop {
+                    tokio::select! {
+                        true = async { semifast_shutdownable && { semifast_shutdown_interruptor.cancelled().await; true } } => {
+                            return Self::flush_and_write_admin_shutdown_fatal_message(self).await;
+                        }
+                        _ = sleep_until(idle_deadline.unwrap_or_else(Instant::now)), if idle_deadline.is_some() => {
+                            return self.write_idle_timeout_fatal_message(idle_error.take()).await;
+                        }
+                        readable = self.socket.readable() => {
+                            readable?;
+                            break;
+                        }
+                        // PostgreSQL doesn't deliver notifications inside a transaction
+                        notification = receiver.recv(), if !self.session.state.is_in_transaction() => {
+                            self.write_notification(notification).await?;
+                        }
+                    }
+                }
+            }
 
             let message: protocol::FrontendMessage = tokio::select! {
                 true = async { semifast_shutdownable && { semifast_shutdown_interruptor.cancelled().await; true } } => {
                     return Self::flush_and_write_admin_shutdown_fatal_message(self).await;
                 }
+                // read_message is not cancellation safe, but the connection is closed right after
+                _ = sleep_until(idle_deadline.unwrap_or_else(Instant::now)), if idle_deadline.is_some() => {
+                    return self.write_idle_timeout_fatal_message(idle_error.take()).await;
+                }
                 message_result = buffer::read_message(&mut self.socket, Arc::clone(&message_tag_parser)) => message_result?
             };
 
             let result = match message {
                 protocol::FrontendMessage::Query(body) => {
                     let span_id = Self::new_span_id(body.query.clone());
+                    self.set_statement_query_tag(span_id.as_ref());
                     let mut qtrace = Qtrace::new(&body.query);
                     if let Some(qtrace) = &qtrace {
                         debug!("Assigned query UUID: {}", qtrace.uuid())
                     }
                     let result = self
                         .process_query(body.query, &mut qtrace, span_id.clone())
-                        .await
-                        .map_err(|e| e.with_span_id(span_id));
+                        .await;
+                    self.write_audit_log(
+                        span_id.as_ref(),
+                        result.as_ref().err().map(|e| e.to_string()),
+                    )
+                    .await;
+                    let result = result.map_err(|e| e.with_span_id(span_id));
                     if let Some(qtrace) = &qtrace {
                         qtrace.save_json()
                     }
@@ -390,6 +529,7 @@ impl AsyncPostgresShim {
                     doing_extended_query_message = true;
                     let mut qtrace = Qtrace::new(&body.query);
                     let span_id = Self::new_span_id(body.query.clone());
+                    self.set_statement_query_tag(span_id.as_ref());
                     if let Some(qtrace) = &qtrace {
                         debug!("Assigned query UUID: {}", qtrace.uuid())
                     }
@@ -411,10 +551,13 @@ impl AsyncPostgresShim {
                             )
                             .await?;
                     }
-                    let result = self
-                        .parse(body, &mut qtrace, span_id.clone())
-                        .await
-                        .map_err(|e| e.with_span_id(span_id));
+                    let result = self.parse(body, &mut qtrace, span_id.clone()).await;
+                    if let Err(err) = &result {
+                        // The statement is never executed, so the failure is audited here
+                        self.write_audit_log(span_id.as_ref(), Some(err.to_string()))
+                            .await;
+                    }
+                    let result = result.map_err(|e| e.with_span_id(span_id));
                     if let Err(err) = &result {
                         if let Some(qtrace) = &mut qtrace {
                             qtrace.set_query_error_message(&err.to_string())
@@ -442,7 +585,10 @@ impl AsyncPostgresShim {
                         .portals
                         .get(&body.portal)
                         .and_then(|portal| portal.span_id());
-                    if tracked_error.is_some() {
+                    self.set_statement_query_tag(span_id.as_ref());
+                    if let Some(err) = &tracked_error {
+                        self.write_audit_log(span_id.as_ref(), Some(err.to_string()))
+                            .await;
                         if let Some(auth_context) = self.session.state.auth_context() {
                             if let Some(span_id) = span_id {
                                 // If there was an error, always show the query
@@ -469,6 +615,11 @@ impl AsyncPostgresShim {
                         .execute(body)
                         .await
                         .map_err(|e| e.with_span_id(span_id.clone()));
+                    self.write_audit_log(
+                        span_id.as_ref(),
+                        result.as_ref().err().map(|e| e.to_string()),
+                    )
+                    .await;
                     if let Some(auth_context) = self.session.state.auth_context() {
                         if let Some(span_id) = span_id {
                             // Always indicate whether this is a data query
@@ -555,6 +706,34 @@ impl AsyncPostgresShim {
         }
     }
 
+    /// Writes the record of the finished statement to the audit log
+    async fn write_audit_log(&self, span_id: Option<&Arc<SpanId>>, error: Option<String>) {
+        let audit_log = &self.session.server.audit_log;
+        let span_id = match span_id {
+            Some(span_id) if audit_log.is_enabled() => span_id,
+            _ => return,
+        };
+
+        let record = AuditLogRecord::new(
+            &self.session.state,
+            span_id,
+            error,
+            self.session.server.config_obj.audit_log_redact_literals(),
+        );
+        if let Err(err) = audit_log.log(record).await {
+            error!("Unable to write audit log record: {}", err);
+        }
+    }
+
+    /// Tag comment belongs to the statement, so it's taken from the SQL of the span
+    fn set_statement_query_tag(&self, span_id: Option<&Arc<SpanId>>) {
+        let tag = span_id
+            .and_then(|span_id| span_id.query_key.get("sql"))
+            .and_then(|sql| sql.as_str())
+            .and_then(parse_query_tag_comment);
+        self.session.state.set_statement_query_tag(tag);
+    }
+
     fn new_span_id(sql: String) -> Option<Arc<SpanId>> {
         Some(Arc::new(SpanId::new(
             Uuid::new_v4().to_string(),
@@ -668,6 +847,37 @@ impl AsyncPostgresShim {
         Ok(())
     }
 
+    pub async fn write_notification(
+        &mut self,
+        notification: Result<Notification, broadcast::error::RecvError>,
+    ) -> Result<(), ConnectionError> {
+        match notification {
+            Ok(notification) => {
+                if self.session.state.is_listening(&notification.channel) {
+                    self.write(protocol::NotificationResponse::new(
+                        notification.process_id,
+                        notification.channel,
+                        notification.payload,
+                    ))
+                    .await?;
+                }
+
+                Ok(())
+            }
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                warn!(
+                    "Connection {} is too slow to receive notifications, {} notifications were skipped",
+                    self.session.state.connection_id, skipped
+                );
+
+                Ok(())
+            }
+            Err(broadcast::error::RecvError::Closed) => {
+                Err(CubeError::internal("Notification channel was closed".to_string()).into())
+            }
+        }
+    }
+
     pub async fn write_admin_shutdown_fatal_message(&mut self) -> Result<(), ConnectionError> {
         buffer::write_message(
             &mut bytes::BytesMut::new(),
@@ -806,7 +1016,25 @@ impl AsyncPostgresShim {
                     .map(|v| v.clone())
                     .unwrap_or("db".to_string());
                 self.session.state.set_database(Some(database));
-                self.session.state.set_user(Some(user));
+                if let Err(err) = self
+                    .session
+                    .session_manager
+                    .admit_user(&self.session, user)
+                    .await
+                {
+                    let error_response = protocol::ErrorResponse::fatal(
+                        protocol::ErrorCode::TooManyConnections,
+                        err.message,
+                    );
+                    buffer::write_message(
+                        &mut self.partial_write_buf,
+                        &mut self.socket,
+                        error_response,
+                    )
+                    .await?;
+
+                    return Ok(false);
+                }
                 self.session.state.set_auth_context(Some(auth_context));
 
                 self.write(protocol::Authentication::new(AuthenticationRequest::Ok))
@@ -904,7 +1132,7 @@ impl AsyncPostgresShim {
                 return Ok(());
             }
             Some(statement) => match statement {
-                PreparedStatement::Empty { .. } => {
+                PreparedStatement::Empty { .. } | PreparedStatement::Notification { .. } => {
                     self.write(protocol::ParameterDescription::new(vec![]))
                         .await?;
                     self.write(protocol::NoData::new()).await
@@ -965,14 +1193,34 @@ impl AsyncPostgresShim {
             if portal.is_empty() {
                 self.write(protocol::EmptyQueryResponse::new()).await?;
             } else {
+                let statement_deadline = self.statement_deadline();
                 let cancel = self
                     .session
                     .state
                     .begin_query(format!("portal #{}", execute.portal));
 
+                let query_slot = tokio::select! {
+                    _ = cancel.cancelled() => {
+                        self.session.state.end_query();
+
+                        return Err(protocol::ErrorResponse::query_canceled().into());
+                    },
+                    query_slot = Self::acquire_query_slot(&self.session, portal) => match query_slot {
+                        Ok(query_slot) => query_slot,
+                        Err(err) => {
+                            self.session.state.end_query();
+
+                            return Err(err);
+                        }
+                    },
+                };
+
+                let span_id = portal.span_id();
                 let mut portal = Pin::new(portal);
                 let stream = portal.execute(execute.max_rows as usize);
                 pin_mut!(stream);
+                // Keep the slot until the batch of the portal is executed
+                let _query_slot = query_slot;
 
                 loop {
                     tokio::select! {
@@ -981,6 +1229,12 @@ impl AsyncPostgresShim {
 
                             return Err(protocol::ErrorResponse::query_canceled().into());
                         },
+                        _ = sleep_until(statement_deadline.unwrap_or_else(Instant::now)), if statement_deadline.is_some() => {
+                            cancel.cancel();
+                            self.session.state.end_query();
+
+                            return Err(protocol::ErrorResponse::statement_timeout().into());
+                        },
                         chunk = stream.next() => {
                             let chunk = match chunk {
                                 Some(chunk) => match chunk {
@@ -1000,7 +1254,12 @@ impl AsyncPostgresShim {
                             }
 
                             match chunk {
-                                PortalBatch::Rows(writer) if writer.has_data() => buffer::write_direct(&mut self.partial_write_buf, &mut self.socket, writer).await?,
+                                PortalBatch::Rows(writer) if writer.has_data() => {
+                                    if let Some(span_id) = &span_id {
+                                        span_id.add_returned_rows(writer.num_rows());
+                                    }
+                                    buffer::write_direct(&mut self.partial_write_buf, &mut self.socket, writer).await?
+                                },
                                 PortalBatch::Completion(completion) => {
                                     self.session.state.end_query();
 
@@ -1082,6 +1341,19 @@ impl AsyncPostgresShim {
 
                 Portal::new(plan, format, PortalFrom::Extended, span_id)
             }
+            PreparedStatement::Notification { statement, .. } => {
+                let statement = statement.clone();
+                drop(statements_guard);
+
+                let cache_entry = self.get_cache_entry().await?;
+                let meta = self.session.server.compiler_cache.meta(cache_entry).await?;
+
+                // There is no real planning for notifications, It's applied on Bind
+                let plan =
+                    convert_notification_to_cube_query(&statement, meta, self.session.clone())?;
+
+                Portal::new(plan, format, PortalFrom::Extended, span_id)
+            }
             PreparedStatement::Error { .. } => {
                 drop(statements_guard);
 
@@ -1111,19 +1383,53 @@ impl AsyncPostgresShim {
                     span_id: span_id.clone(),
                 },
             );
+        } else if let Some(statement) = parse_notification_statement(&parse.query)? {
+            let mut statements_guard = self.session.state.statements.write().await;
+            statements_guard.insert(
+                parse.name,
+                PreparedStatement::Notification {
+                    from_sql: false,
+                    created: chrono::offset::Utc::now(),
+                    statement,
+                    span_id: span_id.clone(),
+                },
+            );
+        } else if parse_explain_statement(&parse.query, DatabaseProtocol::PostgreSQL, qtrace)?
+            .is_some()
+        {
+            self.prepare_statement(
+                parse.name,
+                Err(parse.query.to_string()),
+                &parse.param_types,
+                false,
+                qtrace,
+                span_id.clone(),
+            )
+            .await?;
+            Err(CompilationError::unsupported(
+                "EXPLAIN with options is supported only by the simple query protocol".to_string(),
+            ))?;
         } else {
             match parse_sql_to_statement(&parse.query, DatabaseProtocol::PostgreSQL, qtrace) {
                 Ok(query) => {
                     if let Some(qtrace) = qtrace {
                         qtrace.push_statement(&query);
                     }
-                    self.prepare_statement(parse.name, Ok(query), false, qtrace, span_id.clone())
-                        .await?;
+                    self.prepare_statement(
+                        parse.name,
+                        Ok(query),
+                        &parse.param_types,
+                        false,
+                        qtrace,
+                        span_id.clone(),
+                    )
+                    .await?;
                 }
                 Err(err) => {
                     self.prepare_statement(
                         parse.name,
                         Err(parse.query.to_string()),
+                        &parse.param_types,
                         false,
                         qtrace,
                         span_id.clone(),
@@ -1143,6 +1449,7 @@ impl AsyncPostgresShim {
         &mut self,
         name: String,
         query: Result<Statement, String>,
+        param_types: &[u32],
         from_sql: bool,
         qtrace: &mut Option<Qtrace>,
         span_id: Option<Arc<SpanId>>,
@@ -1174,7 +1481,15 @@ impl AsyncPostgresShim {
                 let parameters: Vec<PgTypeId> = stmt_finder
                     .find(&query)?
                     .into_iter()
-                    .map(|param| param.coltype.to_pg_tid())
+                    .enumerate()
+                    .map(|(idx, param)| {
+                        // Types specified by the client in Parse take precedence, 0 is unspecified
+                        param_types
+                            .get(idx)
+                            .filter(|oid| **oid != 0)
+                            .and_then(|oid| PgTypeId::from_oid(*oid))
+                            .unwrap_or_else(|| param.coltype.to_pg_tid())
+                    })
                     .collect();
 
                 let cache_entry = self.get_cache_entry().await?;
@@ -1247,8 +1562,20 @@ impl AsyncPostgresShim {
         result
     }
 
-    pub fn end_transaction(&mut self) -> Result<bool, ConnectionError> {
+    pub fn end_transaction(&mut self, commit: bool) -> Result<bool, ConnectionError> {
         if let Some(_) = self.session.state.end_transaction() {
+            // NOTIFY of the transaction is delivered only when it's committed
+            let notifications = self.session.state.take_pending_notifications();
+            if commit {
+                for notification in notifications {
+                    self.session
+                        .server
+                        .notifications
+                        .notify(notification)
+                        .map_err(|err| ConnectionError::Cube(err, None))?;
+                }
+            }
+
             // Portals + Cursors which we want to remove
             let mut to_remove = Vec::new();
 
@@ -1278,6 +1605,7 @@ impl AsyncPostgresShim {
         qtrace: &mut Option<Qtrace>,
         span_id: Option<Arc<SpanId>>,
     ) -> Result<(), ConnectionError> {
+        let statement_deadline = self.statement_deadline();
         let cancel = self.session.state.begin_query(stmt.to_string());
 
         tokio::select! {
@@ -1293,6 +1621,17 @@ impl AsyncPostgresShim {
 
                 Ok(())
             },
+            _ = sleep_until(statement_deadline.unwrap_or_else(Instant::now)), if statement_deadline.is_some() => {
+                cancel.cancel();
+                self.session.state.end_query();
+
+                if let Some(qtrace) = qtrace {
+                    qtrace.set_statement_error_message("Execution cancelled by statement timeout");
+                }
+
+                // Unlike a user cancel, the timeout aborts the rest of the query string
+                Err(protocol::ErrorResponse::statement_timeout().into())
+            },
             res = self.process_simple_query(stmt, meta, cancel.clone(), qtrace, span_id) => {
                 self.session.state.end_query();
 
@@ -1336,7 +1675,7 @@ impl AsyncPostgresShim {
                 .await?;
             }
             Statement::Rollback { .. } => {
-                if !self.end_transaction()? {
+                if !self.end_transaction(false)? {
                     // PostgreSQL returns command completion anyway
                     self.write(protocol::NoticeResponse::warning(
                         ErrorCode::NoActiveSqlTransaction,
@@ -1355,7 +1694,7 @@ impl AsyncPostgresShim {
                 .await?;
             }
             Statement::Commit { .. } => {
-                if !self.end_transaction()? {
+                if !self.end_transaction(true)? {
                     // PostgreSQL returns command completion anyway
                     self.write(protocol::NoticeResponse::warning(
                         ErrorCode::NoActiveSqlTransaction,
@@ -1593,6 +1932,9 @@ impl AsyncPostgresShim {
             }
             Statement::Discard { object_type } => {
                 self.session.state.clear_extended().await;
+                if object_type.to_string().eq_ignore_ascii_case("all") {
+                    self.session.state.unlisten(None);
+                }
                 self.portals = HashMap::new();
                 self.cursors = HashMap::new();
 
@@ -1703,8 +2045,15 @@ impl AsyncPostgresShim {
                     _ => *statement,
                 };
 
-                self.prepare_statement(name.value, Ok(statement), true, qtrace, span_id.clone())
-                    .await?;
+                self.prepare_statement(
+                    name.value,
+                    Ok(statement),
+                    &[],
+                    true,
+                    qtrace,
+                    span_id.clone(),
+                )
+                .await?;
 
                 let plan = QueryPlan::MetaOk(StatusFlags::empty(), CommandCompletion::Prepare);
 
@@ -1743,6 +2092,12 @@ impl AsyncPostgresShim {
         max_rows: usize,
         cancel: CancellationToken,
     ) -> Result<(), ConnectionError> {
+        let _query_slot = tokio::select! {
+            _ = cancel.cancelled() => return Ok(()),
+            query_slot = Self::acquire_query_slot(&self.session, portal) => query_slot?,
+        };
+
+        let span_id = portal.span_id();
         let mut portal = Pin::new(portal);
         let stream = portal.execute(max_rows);
         pin_mut!(stream);
@@ -1766,6 +2121,9 @@ impl AsyncPostgresShim {
                             _ => self.write(description).await?,
                         },
                         PortalBatch::Rows(writer) => {
+                            if let Some(span_id) = &span_id {
+                                span_id.add_returned_rows(writer.num_rows());
+                            }
                             if writer.has_data() {
                                 buffer::write_direct(&mut self.partial_write_buf, &mut self.socket, writer).await?
                             }
@@ -1792,6 +2150,35 @@ impl AsyncPostgresShim {
         let cache_entry = self.get_cache_entry().await?;
         let meta = self.session.server.compiler_cache.meta(cache_entry).await?;
 
+        if let Some(statement) = parse_notification_statement(query)? {
+            let plan = convert_notification_to_cube_query(&statement, meta, self.session.clone())?;
+
+            return self
+                .write_portal(
+                    &mut Portal::new(plan, Format::Text, PortalFrom::Simple, span_id),
+                    0,
+                    CancellationToken::new(),
+                )
+                .await;
+        }
+
+        // EXPLAIN with options is supported only by the simple query protocol
+        if let Some((options, statement)) =
+            parse_explain_statement(query, DatabaseProtocol::PostgreSQL, qtrace)?
+        {
+            let plan =
+                convert_explain_to_cube_query(options, statement, meta, self.session.clone())
+                    .await?;
+
+            return self
+                .write_portal(
+                    &mut Portal::new(plan, Format::Text, PortalFrom::Simple, span_id),
+                    0,
+                    CancellationToken::new(),
+                )
+                .await;
+        }
+
         let statements =
             parse_sql_to_statements(&query.to_string(), DatabaseProtocol::PostgreSQL, qtrace)?;
 
@@ -13,15 +13,19 @@ use chrono::{
 };
 use datafusion::arrow::{
     array::{
-        Array, BooleanArray, Float16Array, Float32Array, Float64Array, Int16Array, Int32Array,
-        Int64Array, Int8Array, StringArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+        Array, BinaryArray, BooleanArray, Date32Array, Date64Array, DecimalArray, Float16Array,
+        Float32Array, Float64Array, Int16Array, Int32Array, Int64Array, Int8Array,
+        IntervalDayTimeArray, IntervalMonthDayNanoArray, IntervalYearMonthArray, LargeStringArray,
+        StringArray, TimestampMicrosecondArray, TimestampMillisecondArray,
+        TimestampNanosecondArray, TimestampSecondArray, UInt16Array, UInt32Array, UInt64Array,
+        UInt8Array,
     },
-    datatypes::DataType,
+    datatypes::{DataType, Field, IntervalUnit, TimeUnit},
 };
 use pg_srv::{
-    protocol,
+    format_text_array, protocol,
     protocol::{ErrorCode, ErrorResponse, Format, Serialize},
-    PgTypeId, ProtocolError, ToProtocolValue,
+    IntervalValue, PgTypeId, ProtocolError, ToProtocolValue,
 };
 use postgres_types::{ToSql, Type};
 use std::{convert::TryFrom, io, io::Error};
@@ -126,129 +130,223 @@ impl ToProtocolValue for Decimal128Value {
     }
 }
 
-impl ToProtocolValue for ListValue {
-    fn to_text(&self, buf: &mut BytesMut) -> Result<(), ProtocolError> {
-        let mut values: Vec<String> = Vec::with_capacity(self.v.len());
-
-        macro_rules! write_native_array_to_buffer {
-            ($ARRAY:expr, $BUFF:expr, $ARRAY_TYPE: ident) => {{
-                let arr = $ARRAY.as_any().downcast_ref::<$ARRAY_TYPE>().unwrap();
+fn interval_value(months: i32, days: i32, nanos: i64) -> IntervalValue {
+    let usecs = nanos / 1_000;
+
+    IntervalValue::new(
+        months,
+        days,
+        (usecs / 3_600_000_000) as i32,
+        (usecs / 60_000_000 % 60) as i32,
+        (usecs / 1_000_000 % 60) as i32,
+        (usecs % 1_000_000) as i32,
+    )
+}
 
-                for i in 0..$ARRAY.len() {
-                    if self.v.is_null(i) {
-                        $BUFF.push("null".to_string());
-                    } else {
-                        $BUFF.push(arr.value(i).to_string());
+// array_out - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/arrayfuncs.c#L1012
+// array_send - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/arrayfuncs.c#L1548
+fn write_list_values<T: ToProtocolValue>(
+    buf: &mut BytesMut,
+    format: Format,
+    element_oid: u32,
+    values: Vec<Option<T>>,
+) -> Result<(), ProtocolError> {
+    match format {
+        Format::Text => {
+            let mut elements = Vec::with_capacity(values.len());
+            for value in values.iter() {
+                elements.push(match value {
+                    Some(value) => {
+                        let mut element = BytesMut::new();
+                        value.to_text(&mut element)?;
+                        // skip length
+                        Some(String::from_utf8_lossy(&element[4..]).to_string())
                     }
-                }
-            }};
+                    None => None,
+                });
+            }
+
+            format_text_array(elements.iter().map(|e| e.as_deref())).to_text(buf)
         }
+        // Example for ARRAY[1,2,3]::int8[]
+        // 0000   00 00 00 01 00 00 00 00 00 00 00 14 00 00 00 03   ................
+        // 0010   00 00 00 01 00 00 00 08 00 00 00 00 00 00 00 01   ................
+        // 0020   00 00 00 08 00 00 00 00 00 00 00 02 00 00 00 08   ................
+        // 0030   00 00 00 00 00 00 00 03                           ........
+        //
+        // Example for ARRAY['test1', 'test2']
+        // 0000   00 00 00 01 00 00 00 00 00 00 00 19 00 00 00 02   ................
+        // 0010   00 00 00 01 00 00 00 05 74 65 73 74 31 00 00 00   ........test1...
+        // 0020   05 74 65 73 74 32                                 .test2
+        Format::Binary => {
+            let mut column_data = BytesMut::with_capacity(3 * 5 + values.len() * 2);
+            if values.is_empty() {
+                // Empty array has no dimensions
+                column_data.put_i32(0);
+                column_data.put_i32(0);
+                column_data.put_u32(element_oid);
+            } else {
+                // row1 from the comment
+                // dimensions
+                column_data.put_i32(1);
+                // has_nulls
+                column_data.put_i32(values.iter().any(|v| v.is_none()) as i32);
+                column_data.put_u32(element_oid);
+                column_data.put_u32(values.len() as u32);
+
+                // row2 from the comment
+                column_data.put_u32(1);
+            }
 
-        match self.v.data_type() {
-            DataType::Float16 => write_native_array_to_buffer!(self.v, values, Float16Array),
-            DataType::Float32 => write_native_array_to_buffer!(self.v, values, Float32Array),
-            DataType::Float64 => write_native_array_to_buffer!(self.v, values, Float64Array),
-            // PG doesnt support i8, casting to i16
-            DataType::Int8 => write_native_array_to_buffer!(self.v, values, Int8Array),
-            DataType::Int16 => write_native_array_to_buffer!(self.v, values, Int16Array),
-            DataType::Int32 => write_native_array_to_buffer!(self.v, values, Int32Array),
-            DataType::Int64 => write_native_array_to_buffer!(self.v, values, Int64Array),
-            // PG doesnt support i8, casting to i16
-            DataType::UInt8 => write_native_array_to_buffer!(self.v, values, UInt8Array),
-            DataType::UInt16 => write_native_array_to_buffer!(self.v, values, UInt16Array),
-            DataType::UInt32 => write_native_array_to_buffer!(self.v, values, UInt32Array),
-            DataType::UInt64 => write_native_array_to_buffer!(self.v, values, UInt64Array),
-            DataType::Boolean => write_native_array_to_buffer!(self.v, values, BooleanArray),
-            DataType::Utf8 => write_native_array_to_buffer!(self.v, values, StringArray),
-            dt => {
-                return Err(protocol::ErrorResponse::error(
-                    protocol::ErrorCode::InternalError,
-                    format!("Unsupported type for list serializing: {}", dt),
-                )
-                .into());
+            for value in values {
+                value.to_binary(&mut column_data)?;
             }
-        };
 
-        ("{".to_string() + &values.join(",") + "}").to_text(buf)
+            buf.put_i32(column_data.len() as i32);
+            buf.extend_from_slice(&column_data[..]);
+
+            Ok(())
+        }
     }
+}
 
-    // Example for ARRAY[1,2,3]::int8[]
-    // 0000   00 00 00 01 00 00 00 00 00 00 00 14 00 00 00 03   ................
-    // 0010   00 00 00 01 00 00 00 08 00 00 00 00 00 00 00 01   ................
-    // 0020   00 00 00 08 00 00 00 00 00 00 00 02 00 00 00 08   ................
-    // 0030   00 00 00 00 00 00 00 03                           ........
-    //
-    // Example for ARRAY['test1', 'test2']
-    // 0000   00 00 00 01 00 00 00 00 00 00 00 19 00 00 00 02   ................
-    // 0010   00 00 00 01 00 00 00 05 74 65 73 74 31 00 00 00   ........test1...
-    // 0020   05 74 65 73 74 32                                 .test2
-    fn to_binary(&self, buf: &mut BytesMut) -> Result<(), ProtocolError> {
-        let mut column_data = BytesMut::with_capacity(3 * 5 + self.v.len() * 2);
-        // row1 from the comment
-        // dimensions
-        column_data.put_i32(1);
-        // has_nulls
-        column_data.put_i32((self.v.null_count() > 0) as i32);
-        column_data.put_u32(df_type_to_pg_tid(self.v.data_type())? as u32);
-        column_data.put_u32(self.v.len() as u32);
-
-        // row2 from the comment
-        column_data.put_u32(1);
-
-        macro_rules! write_native_array_as_binary {
-            ($ARRAY:expr, $ARRAY_TYPE: ident, $NATIVE: tt) => {{
-                let arr = $ARRAY.as_any().downcast_ref::<$ARRAY_TYPE>().unwrap();
-
-                for i in 0..$ARRAY.len() {
-                    if $ARRAY.is_null(i) {
-                        let n: Option<$NATIVE> = None;
-                        n.to_binary(&mut column_data)?
-                    } else {
-                        (arr.value(i) as $NATIVE).to_binary(&mut column_data)?
-                    }
-                }
+impl ListValue {
+    fn write(&self, buf: &mut BytesMut, format: Format) -> Result<(), ProtocolError> {
+        // Elements are written with the type which is advertised for the whole array
+        let list_type = DataType::List(Box::new(Field::new(
+            "item",
+            self.v.data_type().clone(),
+            true,
+        )));
+        let element_oid = df_type_to_pg_tid(&list_type)?.to_type().typelem;
+
+        macro_rules! write_list {
+            ($ARRAY_TYPE: ident, |$ARR: ident, $I: ident| $CONVERT: expr) => {{
+                let $ARR = self.v.as_any().downcast_ref::<$ARRAY_TYPE>().unwrap();
+                let values = (0..$ARR.len())
+                    .map(|$I| {
+                        if $ARR.is_null($I) {
+                            Ok(None)
+                        } else {
+                            $CONVERT.map(Some)
+                        }
+                    })
+                    .collect::<Result<Vec<_>, ProtocolError>>()?;
+
+                write_list_values(buf, format, element_oid, values)
             }};
         }
 
+        let invalid_date = || {
+            ProtocolError::from(ErrorResponse::error(
+                ErrorCode::InternalError,
+                "Unable to convert list element to date".to_string(),
+            ))
+        };
+
         match self.v.data_type() {
-            // DataType::Float16 => write_native_array_as_binary!(self, Float16Array, f32),
-            DataType::Float32 => write_native_array_as_binary!(self.v, Float32Array, f32),
-            DataType::Float64 => write_native_array_as_binary!(self.v, Float64Array, f64),
+            DataType::Float16 => write_list!(Float16Array, |a, i| Ok(a.value(i).to_f32())),
+            DataType::Float32 => write_list!(Float32Array, |a, i| Ok(a.value(i))),
+            DataType::Float64 => write_list!(Float64Array, |a, i| Ok(a.value(i))),
             // PG doesnt support i8, casting to i16
-            DataType::Int8 => write_native_array_as_binary!(self.v, Int8Array, i16),
-            DataType::Int16 => write_native_array_as_binary!(self.v, Int16Array, i16),
-            DataType::Int32 => write_native_array_as_binary!(self.v, Int32Array, i32),
-            DataType::Int64 => write_native_array_as_binary!(self.v, Int64Array, i64),
-            // PG doesnt support i8, casting to i16
-            DataType::UInt8 => write_native_array_as_binary!(self.v, UInt8Array, i16),
-            DataType::UInt16 => write_native_array_as_binary!(self.v, UInt16Array, i16),
-            DataType::UInt32 => write_native_array_as_binary!(self.v, UInt32Array, i32),
-            DataType::UInt64 => write_native_array_as_binary!(self.v, UInt64Array, i64),
-            DataType::Boolean => write_native_array_as_binary!(self.v, BooleanArray, bool),
-            DataType::Utf8 => {
-                let arr = self.v.as_any().downcast_ref::<StringArray>().unwrap();
-
-                for i in 0..self.v.len() {
-                    if self.v.is_null(i) {
-                        "null".to_string().to_binary(&mut column_data)?
-                    } else {
-                        arr.value(i).to_string().to_binary(&mut column_data)?
-                    }
-                }
+            DataType::Int8 => write_list!(Int8Array, |a, i| Ok(a.value(i) as i16)),
+            DataType::Int16 => write_list!(Int16Array, |a, i| Ok(a.value(i))),
+            DataType::Int32 => write_list!(Int32Array, |a, i| Ok(a.value(i))),
+            DataType::Int64 => write_list!(Int64Array, |a, i| Ok(a.value(i))),
+            // PG doesnt support unsigned types, casting to signed
+            DataType::UInt8 => write_list!(UInt8Array, |a, i| Ok(a.value(i) as i16)),
+            DataType::UInt16 => write_list!(UInt16Array, |a, i| Ok(a.value(i) as i16)),
+            DataType::UInt32 => write_list!(UInt32Array, |a, i| Ok(a.value(i) as i32)),
+            DataType::UInt64 => write_list!(UInt64Array, |a, i| Ok(a.value(i) as i64)),
+            DataType::Boolean => write_list!(BooleanArray, |a, i| Ok(a.value(i))),
+            DataType::Utf8 => write_list!(StringArray, |a, i| Ok(a.value(i).to_string())),
+            DataType::LargeUtf8 => {
+                write_list!(LargeStringArray, |a, i| Ok(a.value(i).to_string()))
             }
-            dt => {
-                return Err(protocol::ErrorResponse::error(
-                    protocol::ErrorCode::InternalError,
-                    format!("Unsupported type for list serializing: {}", dt),
-                )
-                .into())
+            DataType::Binary => write_list!(BinaryArray, |a, i| Ok(a.value(i).to_vec())),
+            DataType::Date32 => {
+                write_list!(Date32Array, |a, i| a
+                    .value_as_date(i)
+                    .ok_or_else(invalid_date))
             }
-        };
+            DataType::Date64 => {
+                write_list!(Date64Array, |a, i| a
+                    .value_as_date(i)
+                    .ok_or_else(invalid_date))
+            }
+            DataType::Timestamp(TimeUnit::Second, tz) => {
+                write_list!(TimestampSecondArray, |a, i| Ok(TimestampValue::new(
+                    a.value(i) * 1_000_000_000,
+                    tz.clone()
+                )))
+            }
+            DataType::Timestamp(TimeUnit::Millisecond, tz) => {
+                write_list!(TimestampMillisecondArray, |a, i| Ok(TimestampValue::new(
+                    a.value(i) * 1_000_000,
+                    tz.clone()
+                )))
+            }
+            DataType::Timestamp(TimeUnit::Microsecond, tz) => {
+                write_list!(TimestampMicrosecondArray, |a, i| Ok(TimestampValue::new(
+                    a.value(i) * 1_000,
+                    tz.clone()
+                )))
+            }
+            DataType::Timestamp(TimeUnit::Nanosecond, tz) => {
+                write_list!(TimestampNanosecondArray, |a, i| Ok(TimestampValue::new(
+                    a.value(i),
+                    tz.clone()
+                )))
+            }
+            DataType::Decimal(_, s) => {
+                write_list!(DecimalArray, |a, i| Ok(Decimal128Value::new(
+                    a.value(i),
+                    *s
+                )))
+            }
+            DataType::Interval(IntervalUnit::YearMonth) => {
+                write_list!(IntervalYearMonthArray, |a, i| Ok(interval_value(
+                    a.value(i),
+                    0,
+                    0
+                )))
+            }
+            DataType::Interval(IntervalUnit::DayTime) => {
+                write_list!(IntervalDayTimeArray, |a, i| {
+                    let value = a.value(i);
+                    Ok(interval_value(
+                        0,
+                        (value >> 32) as i32,
+                        value as i32 as i64 * 1_000_000,
+                    ))
+                })
+            }
+            DataType::Interval(IntervalUnit::MonthDayNano) => {
+                write_list!(IntervalMonthDayNanoArray, |a, i| {
+                    let value = a.value(i);
+                    Ok(interval_value(
+                        (value >> 96) as i32,
+                        (value >> 64) as i32,
+                        value as i64,
+                    ))
+                })
+            }
+            dt => Err(protocol::ErrorResponse::error(
+                protocol::ErrorCode::InternalError,
+                format!("Unsupported type for list serializing: {}", dt),
+            )
+            .into()),
+        }
+    }
+}
 
-        buf.put_i32(column_data.len() as i32);
-        buf.extend_from_slice(&column_data[..]);
+impl ToProtocolValue for ListValue {
+    fn to_text(&self, buf: &mut BytesMut) -> Result<(), ProtocolError> {
+        self.write(buf, Format::Text)
+    }
 
-        Ok(())
+    fn to_binary(&self, buf: &mut BytesMut) -> Result<(), ProtocolError> {
+        self.write(buf, Format::Binary)
     }
 }
 
@@ -329,8 +427,10 @@ mod tests {
         writer::{BatchWriter, ToProtocolValue},
     };
     use bytes::BytesMut;
-    use datafusion::arrow::array::{ArrayRef, Int64Builder};
-    use pg_srv::{buffer, protocol::Format};
+    use datafusion::arrow::array::{
+        ArrayRef, BooleanArray, Date32Array, Int32Array, Int64Builder, Int8Array, StringArray,
+    };
+    use pg_srv::{buffer, protocol::Format, FromProtocolValue};
     use std::{io::Cursor, sync::Arc};
 
     fn assert_text_encode<T: ToProtocolValue>(value: T, expected: &[u8]) {
@@ -503,4 +603,78 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_list_encoders() -> Result<(), ConnectionError> {
+        let strings = || {
+            ListValue::new(
+                Arc::new(StringArray::from(vec![Some("a b"), None, Some("c")])) as ArrayRef,
+            )
+        };
+
+        // SELECT ARRAY['a b', NULL, 'c']
+        assert_text_encode(strings(), b"\0\0\0\x0e{\"a b\",NULL,c}");
+        assert_bind_encode(
+            strings(),
+            &[
+                0, 0, 0, 36, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 25, 0, 0, 0, 3, 0, 0, 0, 1, 0, 0, 0,
+                3, 97, 32, 98, 255, 255, 255, 255, 0, 0, 0, 1, 99,
+            ],
+        );
+
+        // SELECT ARRAY[1, -1]::int2[]
+        assert_bind_encode(
+            ListValue::new(Arc::new(Int8Array::from(vec![Some(1), Some(-1)])) as ArrayRef),
+            &[
+                0, 0, 0, 32, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 21, 0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0,
+                2, 0, 1, 0, 0, 0, 2, 255, 255,
+            ],
+        );
+
+        // SELECT ARRAY['2022-01-01'::date]
+        let dates = || ListValue::new(Arc::new(Date32Array::from(vec![Some(18993)])) as ArrayRef);
+        assert_text_encode(dates(), b"\0\0\0\x0c{2022-01-01}");
+        assert_bind_encode(
+            dates(),
+            &[
+                0, 0, 0, 28, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 4, 58, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0,
+                4, 0, 0, 31, 100,
+            ],
+        );
+
+        // SELECT ARRAY[]::int4[]
+        assert_bind_encode(
+            ListValue::new(Arc::new(Int32Array::from(Vec::<Option<i32>>::new())) as ArrayRef),
+            &[0, 0, 0, 12, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 23],
+        );
+
+        assert_text_encode(
+            ListValue::new(Arc::new(BooleanArray::from(vec![Some(true), Some(false)])) as ArrayRef),
+            b"\0\0\0\x05{t,f}",
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_round_trip() -> Result<(), ConnectionError> {
+        let values = vec![Some(1), None, Some(3)];
+        let mut buf = BytesMut::new();
+        ListValue::new(Arc::new(Int32Array::from(values.clone())) as ArrayRef)
+            .to_binary(&mut buf)?;
+        assert_eq!(Vec::<Option<i32>>::from_binary(&buf[4..])?, values);
+
+        let values = vec![
+            Some("a b".to_string()),
+            None,
+            Some("NULL".to_string()),
+            Some("{\"}".to_string()),
+        ];
+        let mut buf = BytesMut::new();
+        ListValue::new(Arc::new(StringArray::from(values.clone())) as ArrayRef)
+            .to_text(&mut buf)?;
+        assert_eq!(Vec::<Option<String>>::from_text(&buf[4..])?, values);
+
+        Ok(())
+    }
 }
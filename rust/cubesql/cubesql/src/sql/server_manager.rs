@@ -2,8 +2,10 @@ use crate::{
     compile::{DatabaseProtocol, DatabaseVariables, DatabaseVariablesToUpdate},
     config::ConfigObj,
     sql::{
+        audit_log::AuditLogSink,
         compiler_cache::CompilerCache,
         database_variables::{mysql_default_global_variables, postgres_default_global_variables},
+        notification_service::NotificationService,
         pg_auth_service::PostgresAuthService,
         SqlAuthService,
     },
@@ -39,6 +41,8 @@ pub struct ServerManager {
     pub auth: Arc<dyn SqlAuthService>,
     pub transport: Arc<dyn TransportService>,
     pub pg_auth: Arc<dyn PostgresAuthService>,
+    pub notifications: Arc<dyn NotificationService>,
+    pub audit_log: Arc<dyn AuditLogSink>,
     // Non references
     pub configuration: ServerConfiguration,
     pub nonce: Option<Vec<u8>>,
@@ -55,6 +59,8 @@ impl ServerManager {
         auth: Arc<dyn SqlAuthService>,
         transport: Arc<dyn TransportService>,
         pg_auth: Arc<dyn PostgresAuthService>,
+        notifications: Arc<dyn NotificationService>,
+        audit_log: Arc<dyn AuditLogSink>,
         compiler_cache: Arc<dyn CompilerCache>,
         nonce: Option<Vec<u8>>,
         config_obj: Arc<dyn ConfigObj>,
@@ -63,6 +69,8 @@ impl ServerManager {
             auth,
             transport,
             pg_auth,
+            notifications,
+            audit_log,
             compiler_cache,
             nonce,
             config_obj,
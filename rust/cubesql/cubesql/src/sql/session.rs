@@ -1,22 +1,30 @@
 use datafusion::scalar::ScalarValue;
 use log::trace;
 use rand::Rng;
+use sha2::{Digest, Sha256};
 use std::{
-    collections::HashMap,
-    sync::{Arc, LazyLock, RwLock as RwLockSync, Weak},
-    time::{Duration, SystemTime},
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, LazyLock, RwLock as RwLockSync, Weak,
+    },
+    time::{Duration, Instant, SystemTime},
 };
 use tokio_util::sync::CancellationToken;
 
 use super::{server_manager::ServerManager, session_manager::SessionManager, AuthContextRef};
 use crate::{
     compile::{
-        DatabaseProtocol, DatabaseProtocolDetails, DatabaseVariable, DatabaseVariables,
-        DatabaseVariablesToUpdate,
+        rewrite::rewriter::RewriteStats, DatabaseProtocol, DatabaseProtocolDetails,
+        DatabaseVariable, DatabaseVariables, DatabaseVariablesToUpdate,
     },
     sql::{
-        database_variables::{mysql_default_session_variables, postgres_default_session_variables},
+        database_variables::{
+            mysql_default_session_variables, postgres::session_vars::QUERY_TAG_VARIABLE,
+            postgres_default_session_variables,
+        },
         extended::PreparedStatement,
+        notification_service::Notification,
         temp_tables::TempTableManager,
     },
     transport::LoadRequestMeta,
@@ -47,6 +55,19 @@ pub enum TransactionState {
     Active(u64),
 }
 
+/// Statistics of the last planned query, reported by EXPLAIN (CUBE)
+#[derive(Debug, Clone, Default)]
+pub struct PlanningStats {
+    // Rewritten plan was taken from the compiler cache
+    pub rewrite_cache_hit: bool,
+    // Rewrite of the parameterized plan was taken from the compiler cache
+    pub parameterized_rewrite_cache_hit: bool,
+    pub rewrites: Vec<RewriteStats>,
+    // Reason why the rewrite budget ran out, the best plan found so far was used
+    pub rewrite_budget_exhausted: Option<String>,
+    pub started: Option<Instant>,
+}
+
 #[derive(Debug)]
 pub enum QueryState {
     None,
@@ -85,10 +106,20 @@ pub struct SessionState {
 
     transaction: RwLockSync<TransactionState>,
     query: RwLockSync<QueryState>,
+    // Tag from a `/* tag: ... */` comment of the current statement
+    statement_query_tag: RwLockSync<Option<String>>,
+    // Query is waiting for a free slot of the per-user concurrency limit
+    query_queued: AtomicBool,
+    planning_stats: RwLockSync<PlanningStats>,
 
     // Extended Query
     pub statements: RWLockAsync<HashMap<String, PreparedStatement>>,
 
+    // Channels subscribed by LISTEN
+    listen_channels: RwLockSync<HashSet<String>>,
+    // NOTIFY messages of the current transaction, they are sent on COMMIT
+    pending_notifications: RwLockSync<Vec<Notification>>,
+
     auth_context_expiration: Duration,
 }
 
@@ -118,7 +149,12 @@ impl SessionState {
             auth_context: RwLockSync::new((auth_context, SystemTime::now())),
             transaction: RwLockSync::new(TransactionState::None),
             query: RwLockSync::new(QueryState::None),
+            statement_query_tag: RwLockSync::new(None),
+            query_queued: AtomicBool::new(false),
+            planning_stats: RwLockSync::new(PlanningStats::default()),
             statements: RWLockAsync::new(HashMap::new()),
+            listen_channels: RwLockSync::new(HashSet::new()),
+            pending_notifications: RwLockSync::new(Vec::new()),
             auth_context_expiration,
         }
     }
@@ -195,6 +231,40 @@ impl SessionState {
         }
     }
 
+    pub fn set_statement_query_tag(&self, tag: Option<String>) {
+        let mut guard = self
+            .statement_query_tag
+            .write()
+            .expect("failed to unlock statement_query_tag for set_statement_query_tag");
+        *guard = tag;
+    }
+
+    /// Tag of the current statement comment takes precedence over the `cube.query_tag` variable
+    pub fn query_tag(&self) -> Option<String> {
+        let guard = self
+            .statement_query_tag
+            .read()
+            .expect("failed to unlock statement_query_tag for query_tag");
+        if let Some(tag) = &*guard {
+            return Some(tag.clone());
+        }
+
+        self.get_variable(QUERY_TAG_VARIABLE)
+            .and_then(|v| match v.value {
+                ScalarValue::Utf8(tag) => tag,
+                _ => None,
+            })
+            .filter(|tag| !tag.is_empty())
+    }
+
+    pub fn is_query_queued(&self) -> bool {
+        self.query_queued.load(Ordering::SeqCst)
+    }
+
+    pub fn set_query_queued(&self, queued: bool) {
+        self.query_queued.store(queued, Ordering::SeqCst);
+    }
+
     pub fn end_query(&self) {
         let mut guard = self
             .query
@@ -207,6 +277,8 @@ impl SessionState {
             }
             QueryState::None => {}
         }
+
+        self.set_query_queued(false);
     }
 
     pub fn begin_query(&self, query: String) -> CancellationToken {
@@ -255,6 +327,130 @@ impl SessionState {
         *statements_guard = HashMap::new();
     }
 
+    pub fn planning_stats(&self) -> PlanningStats {
+        let guard = self
+            .planning_stats
+            .read()
+            .expect("failed to unlock planning_stats for planning_stats");
+        guard.clone()
+    }
+
+    pub fn reset_planning_stats(&self) {
+        let mut guard = self
+            .planning_stats
+            .write()
+            .expect("failed to unlock planning_stats for reset_planning_stats");
+        *guard = PlanningStats {
+            started: Some(Instant::now()),
+            ..PlanningStats::default()
+        };
+    }
+
+    pub fn planning_elapsed(&self) -> Duration {
+        let guard = self
+            .planning_stats
+            .read()
+            .expect("failed to unlock planning_stats for planning_elapsed");
+        guard
+            .started
+            .map(|started| started.elapsed())
+            .unwrap_or_default()
+    }
+
+    pub fn set_rewrite_budget_exhausted(&self, reason: String) {
+        let mut guard = self
+            .planning_stats
+            .write()
+            .expect("failed to unlock planning_stats for set_rewrite_budget_exhausted");
+        guard.rewrite_budget_exhausted = Some(reason);
+    }
+
+    pub fn is_rewrite_budget_exhausted(&self) -> bool {
+        let guard = self
+            .planning_stats
+            .read()
+            .expect("failed to unlock planning_stats for is_rewrite_budget_exhausted");
+        guard.rewrite_budget_exhausted.is_some()
+    }
+
+    pub fn add_rewrite_stats(&self, stats: RewriteStats) {
+        let mut guard = self
+            .planning_stats
+            .write()
+            .expect("failed to unlock planning_stats for add_rewrite_stats");
+        guard.rewrites.push(stats);
+    }
+
+    pub fn set_rewrite_cache_hit(&self, parameterized: bool) {
+        let mut guard = self
+            .planning_stats
+            .write()
+            .expect("failed to unlock planning_stats for set_rewrite_cache_hit");
+        if parameterized {
+            guard.parameterized_rewrite_cache_hit = true;
+        } else {
+            guard.rewrite_cache_hit = true;
+        }
+    }
+
+    /// Returns false if session is already listening this channel
+    pub fn listen(&self, channel: String) -> bool {
+        let mut guard = self
+            .listen_channels
+            .write()
+            .expect("failed to unlock listen_channels for listen");
+        guard.insert(channel)
+    }
+
+    /// None means all channels
+    pub fn unlisten(&self, channel: Option<&str>) {
+        let mut guard = self
+            .listen_channels
+            .write()
+            .expect("failed to unlock listen_channels for unlisten");
+        match channel {
+            Some(channel) => {
+                guard.remove(channel);
+            }
+            None => guard.clear(),
+        }
+    }
+
+    pub fn is_listening(&self, channel: &str) -> bool {
+        let guard = self
+            .listen_channels
+            .read()
+            .expect("failed to unlock listen_channels for is_listening");
+        guard.contains(channel)
+    }
+
+    pub fn has_listen_channels(&self) -> bool {
+        let guard = self
+            .listen_channels
+            .read()
+            .expect("failed to unlock listen_channels for has_listen_channels");
+        !guard.is_empty()
+    }
+
+    /// Postpones NOTIFY until the end of the transaction, duplicates are sent only once as in PostgreSQL
+    pub fn queue_notification(&self, notification: Notification) {
+        let mut guard = self
+            .pending_notifications
+            .write()
+            .expect("failed to unlock pending_notifications for queue_notification");
+        if !guard.contains(&notification) {
+            guard.push(notification);
+        }
+    }
+
+    pub fn take_pending_notifications(&self) -> Vec<Notification> {
+        let mut guard = self
+            .pending_notifications
+            .write()
+            .expect("failed to unlock pending_notifications for take_pending_notifications");
+        std::mem::take(&mut *guard)
+    }
+
     pub fn user(&self) -> Option<String> {
         let guard = self
             .properties
@@ -306,6 +502,18 @@ impl SessionState {
         guard.0.clone()
     }
 
+    /// Identifies the security context of the session without exposing it
+    pub fn security_context_hash(&self) -> Option<String> {
+        self.auth_context()
+            .and_then(|ctx| ctx.security_context().cloned())
+            .map(|security_context| {
+                format!(
+                    "{:x}",
+                    Sha256::digest(security_context.to_string().as_bytes())
+                )
+            })
+    }
+
     pub fn set_auth_context(&self, auth_context: Option<AuthContextRef>) {
         let mut guard = self
             .auth_context
@@ -377,6 +585,11 @@ impl SessionState {
         Arc::clone(&self.temp_tables)
     }
 
+    /// Schema of the session temporary tables, unique per connection like in Postgres
+    pub fn temp_schema(&self) -> String {
+        format!("pg_temp_{}", self.connection_id)
+    }
+
     pub fn get_load_request_meta(&self) -> LoadRequestMeta {
         let application_name = if let Some(var) = self.get_variable("application_name") {
             Some(var.value.to_string())
@@ -384,11 +597,14 @@ impl SessionState {
             None
         };
 
-        LoadRequestMeta::new(
+        let mut meta = LoadRequestMeta::new(
             self.protocol.get_name().to_string(),
             "sql".to_string(),
             application_name,
-        )
+        );
+        meta.set_query_tag(self.query_tag());
+
+        meta
     }
 }
 
@@ -433,9 +649,12 @@ pub struct SessionStatActivity {
     pub usesysid: Option<u32>,
     pub usename: Option<String>,
     pub application_name: Option<String>,
+    pub query_tag: Option<String>,
     pub client_addr: String,
     pub client_hostname: Option<String>,
     pub client_port: u16,
+    pub wait_event_type: Option<String>,
+    pub wait_event: Option<String>,
     pub query: Option<String>,
 }
 
@@ -452,6 +671,15 @@ impl From<&Session> for SessionStatActivity {
             None
         };
 
+        let (wait_event_type, wait_event) = if session.state.is_query_queued() {
+            (
+                Some("Extension".to_string()),
+                Some("QueryQueue".to_string()),
+            )
+        } else {
+            (None, None)
+        };
+
         Self {
             oid: session.state.connection_id,
             datname: session.state.database(),
@@ -460,9 +688,12 @@ impl From<&Session> for SessionStatActivity {
             usesysid: None,
             usename: session.state.user(),
             application_name,
+            query_tag: session.state.query_tag(),
             client_addr: session.state.client_ip.clone(),
             client_hostname: None,
             client_port: session.state.client_port.clone(),
+            wait_event_type,
+            wait_event,
             query,
         }
     }
@@ -1,4 +1,4 @@
-use crate::{CubeError, RWLockAsync};
+use crate::{CubeError, RWLockAsync, RWLockSync};
 use std::{
     collections::HashMap,
     sync::{
@@ -7,10 +7,12 @@ use std::{
     },
     time::Duration,
 };
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
 use super::{
     server_manager::ServerManager,
     session::{Session, SessionState},
+    temp_tables::TempTableManager,
 };
 use crate::{compile::DatabaseProtocol, sql::session::SessionExtraId};
 
@@ -20,12 +22,22 @@ struct SessionManagerInner {
     uid_to_session: HashMap<SessionExtraId, Arc<Session>>,
 }
 
+/// User and hash of the security context, sessions of the same user with different security
+/// contexts can't see tables of each other
+type SharedTablesKey = (String, Option<String>);
+
 #[derive(Debug)]
 pub struct SessionManager {
     // Sessions
     last_id: AtomicU32,
     sessions: RWLockAsync<SessionManagerInner>,
     pub temp_table_size: AtomicUsize,
+    // Tables which are shared between sessions of the same user and security context
+    shared_tables: RWLockSync<HashMap<SharedTablesKey, Arc<TempTableManager>>>,
+    // Size of temporary tables spilled to the disk
+    pub temp_table_spill_size: Arc<AtomicUsize>,
+    // Slots for concurrent data queries per user
+    query_slots: RWLockSync<HashMap<String, Arc<Semaphore>>>,
     // Backref
     pub server: Arc<ServerManager>,
 }
@@ -41,6 +53,9 @@ impl SessionManager {
                 uid_to_session: HashMap::new(),
             }),
             temp_table_size: AtomicUsize::new(0),
+            shared_tables: RWLockSync::new(HashMap::new()),
+            temp_table_spill_size: Arc::new(AtomicUsize::new(0)),
+            query_slots: RWLockSync::new(HashMap::new()),
             server,
         }
     }
@@ -94,6 +109,93 @@ impl SessionManager {
         Ok(session_ref)
     }
 
+    /// Assigns the authenticated user to the session, unless the user has reached the connection limit
+    pub async fn admit_user(&self, session: &Session, user: String) -> Result<(), CubeError> {
+        // Write lock serializes admissions, so concurrent connections can't exceed the limit
+        let guard = self.sessions.write().await;
+
+        let limit = self.server.config_obj.max_connections_per_user();
+        if limit > 0 {
+            let user_sessions = guard
+                .sessions
+                .values()
+                .filter(|s| {
+                    s.state.connection_id != session.state.connection_id
+                        && s.state.user().as_ref() == Some(&user)
+                })
+                .count();
+
+            if user_sessions >= limit {
+                return Err(CubeError::user(format!(
+                    "Too many connections for user '{}', limit reached: {}",
+                    user, limit
+                )));
+            }
+        }
+
+        session.state.set_user(Some(user));
+
+        Ok(())
+    }
+
+    /// Waits for a free slot to run a data query of the user, None when there is no limit
+    pub async fn acquire_query_slot(
+        &self,
+        session: &Session,
+    ) -> Result<Option<OwnedSemaphorePermit>, CubeError> {
+        let limit = self.server.config_obj.max_concurrent_queries_per_user();
+        let user = match session.state.user() {
+            Some(user) if limit > 0 => user,
+            _ => return Ok(None),
+        };
+
+        let semaphore = {
+            let mut guard = self
+                .query_slots
+                .write()
+                .expect("failed to unlock query slots for writing");
+            Self::prune_query_slots(&mut guard);
+
+            guard
+                .entry(user.clone())
+                .or_insert_with(|| Arc::new(Semaphore::new(limit)))
+                .clone()
+        };
+
+        if let Ok(permit) = semaphore.clone().try_acquire_owned() {
+            return Ok(Some(permit));
+        }
+
+        session.state.set_query_queued(true);
+        let queue_timeout = self.server.config_obj.query_queue_timeout();
+        let permit = if queue_timeout > 0 {
+            tokio::time::timeout(
+                Duration::from_secs(queue_timeout),
+                semaphore.acquire_owned(),
+            )
+            .await
+            .ok()
+        } else {
+            Some(semaphore.acquire_owned().await)
+        };
+        session.state.set_query_queued(false);
+
+        match permit {
+            Some(Ok(permit)) => Ok(Some(permit)),
+            Some(Err(err)) => Err(CubeError::internal(err.to_string())),
+            None => Err(CubeError::user(format!(
+                "Too many concurrent queries for user '{}', limit reached: {}, query was waiting in the queue for {} seconds",
+                user, limit, queue_timeout
+            ))),
+        }
+    }
+
+    /// Removes slots of users who don't have running or queued queries. Every permit and waiter
+    /// holds a reference to the semaphore, so an idle semaphore is referenced only by the map.
+    fn prune_query_slots(query_slots: &mut HashMap<String, Arc<Semaphore>>) {
+        query_slots.retain(|_, semaphore| Arc::strong_count(semaphore) > 1);
+    }
+
     pub async fn map_sessions<T: for<'a> From<&'a Session>>(self: &Arc<Self>) -> Vec<T> {
         let guard = self.sessions.read().await;
 
@@ -115,6 +217,54 @@ impl SessionManager {
         guard.uid_to_session.get(&extra_id).map(|s| s.clone())
     }
 
+    /// Returns shared tables of the user with the security context, None when shared tables are
+    /// disabled
+    pub fn shared_tables(
+        self: &Arc<Self>,
+        user: &str,
+        security_context_hash: Option<String>,
+    ) -> Option<Arc<TempTableManager>> {
+        let ttl = self.server.config_obj.shared_tables_ttl();
+        if ttl == 0 {
+            return None;
+        }
+
+        let key = (user.to_string(), security_context_hash);
+        if let Some(shared_tables) = self
+            .shared_tables
+            .read()
+            .expect("failed to unlock shared tables for reading")
+            .get(&key)
+        {
+            return Some(shared_tables.clone());
+        }
+
+        let mut guard = self
+            .shared_tables
+            .write()
+            .expect("failed to unlock shared tables for writing");
+        Self::prune_shared_tables(&mut guard);
+
+        Some(
+            guard
+                .entry(key)
+                .or_insert_with(|| {
+                    Arc::new(TempTableManager::with_ttl(
+                        Arc::downgrade(self),
+                        Duration::from_secs(ttl),
+                    ))
+                })
+                .clone(),
+        )
+    }
+
+    /// Removes shared tables of users who don't have tables left. Expired tables of idle users are
+    /// released here, even if the user never comes back.
+    fn prune_shared_tables(shared_tables: &mut HashMap<SharedTablesKey, Arc<TempTableManager>>) {
+        shared_tables
+            .retain(|_, user_tables| Arc::strong_count(user_tables) > 1 || !user_tables.is_empty());
+    }
+
     pub async fn drop_session(&self, connection_id: u32) {
         let mut guard = self.sessions.write().await;
 
@@ -128,5 +278,214 @@ impl SessionManager {
                 Ordering::SeqCst,
             );
         }
+        drop(guard);
+
+        Self::prune_shared_tables(
+            &mut self
+                .shared_tables
+                .write()
+                .expect("failed to unlock shared tables for writing"),
+        );
+        Self::prune_query_slots(
+            &mut self
+                .query_slots
+                .write()
+                .expect("failed to unlock query slots for writing"),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        compile::test::{get_test_session_with_config, get_test_tenant_ctx},
+        config::ConfigObjImpl,
+        sql::temp_tables::TempTable,
+    };
+    use datafusion::logical_plan::DFSchema;
+
+    #[tokio::test]
+    async fn test_admission_limits() -> Result<(), CubeError> {
+        let config_obj = ConfigObjImpl {
+            max_connections_per_user: 2,
+            max_concurrent_queries_per_user: 1,
+            query_queue_timeout: 1,
+            ..ConfigObjImpl::default()
+        };
+        // Test session is created with the "ovr" user
+        let session = get_test_session_with_config(
+            DatabaseProtocol::PostgreSQL,
+            Arc::new(config_obj),
+            get_test_tenant_ctx(),
+        )
+        .await;
+        let session_manager = session.session_manager.clone();
+
+        let second = session_manager
+            .create_session(
+                DatabaseProtocol::PostgreSQL,
+                "127.0.0.1".to_string(),
+                1235,
+                None,
+            )
+            .await?;
+        session_manager
+            .admit_user(&second, "ovr".to_string())
+            .await?;
+
+        let third = session_manager
+            .create_session(
+                DatabaseProtocol::PostgreSQL,
+                "127.0.0.1".to_string(),
+                1236,
+                None,
+            )
+            .await?;
+        assert!(session_manager
+            .admit_user(&third, "ovr".to_string())
+            .await
+            .is_err());
+        session_manager
+            .admit_user(&third, "other".to_string())
+            .await?;
+
+        let slot = session_manager.acquire_query_slot(&session).await?;
+        assert!(slot.is_some());
+        // The second query of the same user waits in the queue and times out
+        assert!(session_manager.acquire_query_slot(&second).await.is_err());
+        assert!(!second.state.is_query_queued());
+        // Other users are not affected
+        assert!(session_manager.acquire_query_slot(&third).await?.is_some());
+
+        drop(slot);
+        assert!(session_manager.acquire_query_slot(&second).await?.is_some());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_query_slots_pruning() -> Result<(), CubeError> {
+        let config_obj = ConfigObjImpl {
+            max_concurrent_queries_per_user: 1,
+            ..ConfigObjImpl::default()
+        };
+        // Test session is created with the "ovr" user
+        let session = get_test_session_with_config(
+            DatabaseProtocol::PostgreSQL,
+            Arc::new(config_obj),
+            get_test_tenant_ctx(),
+        )
+        .await;
+        let session_manager = session.session_manager.clone();
+
+        let other = session_manager
+            .create_session(
+                DatabaseProtocol::PostgreSQL,
+                "127.0.0.1".to_string(),
+                1235,
+                None,
+            )
+            .await?;
+        session_manager
+            .admit_user(&other, "other".to_string())
+            .await?;
+
+        // Slots are kept while the user has a running query
+        let slot = session_manager.acquire_query_slot(&session).await?;
+        drop(session_manager.acquire_query_slot(&other).await?);
+        assert!(session_manager
+            .query_slots
+            .read()
+            .unwrap()
+            .contains_key("ovr"));
+
+        drop(slot);
+        session_manager
+            .drop_session(other.state.connection_id)
+            .await;
+        assert!(session_manager.query_slots.read().unwrap().is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shared_tables_pruning() -> Result<(), CubeError> {
+        let config_obj = ConfigObjImpl {
+            shared_tables_ttl: 1,
+            ..ConfigObjImpl::default()
+        };
+        let session = get_test_session_with_config(
+            DatabaseProtocol::PostgreSQL,
+            Arc::new(config_obj),
+            get_test_tenant_ctx(),
+        )
+        .await;
+        let session_manager = session.session_manager.clone();
+
+        let shared_tables = session_manager.shared_tables("ovr", None).unwrap();
+        shared_tables.save(
+            "shared",
+            TempTable::new(Arc::new(DFSchema::empty()), vec![]),
+        )?;
+        drop(shared_tables);
+
+        // Tables are kept until they expire
+        session_manager
+            .drop_session(session.state.connection_id)
+            .await;
+        assert!(session_manager
+            .shared_tables
+            .read()
+            .unwrap()
+            .contains_key(&("ovr".to_string(), None)));
+
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+
+        session_manager.shared_tables("other", None).unwrap();
+        let shared_tables = session_manager.shared_tables.read().unwrap();
+        assert!(!shared_tables.contains_key(&("ovr".to_string(), None)));
+        assert!(shared_tables.contains_key(&("other".to_string(), None)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shared_tables_by_security_context() -> Result<(), CubeError> {
+        let config_obj = ConfigObjImpl {
+            shared_tables_ttl: 60,
+            ..ConfigObjImpl::default()
+        };
+        let session = get_test_session_with_config(
+            DatabaseProtocol::PostgreSQL,
+            Arc::new(config_obj),
+            get_test_tenant_ctx(),
+        )
+        .await;
+        let session_manager = session.session_manager.clone();
+
+        let shared_tables = session_manager
+            .shared_tables("ovr", Some("tenant-a".to_string()))
+            .unwrap();
+        shared_tables.save(
+            "shared",
+            TempTable::new(Arc::new(DFSchema::empty()), vec![]),
+        )?;
+
+        // Same user with another security context doesn't see the table
+        assert!(session_manager
+            .shared_tables("ovr", Some("tenant-b".to_string()))
+            .unwrap()
+            .names()
+            .is_empty());
+        assert_eq!(
+            session_manager
+                .shared_tables("ovr", Some("tenant-a".to_string()))
+                .unwrap()
+                .names(),
+            vec!["shared".to_string()]
+        );
+
+        Ok(())
     }
 }
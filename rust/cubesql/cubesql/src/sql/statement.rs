@@ -610,6 +610,9 @@ impl<'ast> Visitor<'ast, ConnectionError> for PostgresStatementParamsBinder {
                     BindValue::Float64(v) => {
                         *value = ast::Value::Number(v.to_string(), *v < 0_f64);
                     }
+                    BindValue::Numeric(v) => {
+                        *value = ast::Value::Number(v.clone(), false);
+                    }
                     BindValue::Null => {
                         *value = ast::Value::Null;
                     }
@@ -622,6 +625,56 @@ impl<'ast> Visitor<'ast, ConnectionError> for PostgresStatementParamsBinder {
     }
 }
 
+/// Converts arguments of SQL EXECUTE statement to values for PostgresStatementParamsBinder
+pub fn execute_arguments_to_bind_values(
+    arguments: &Vec<Expr>,
+) -> Result<Vec<BindValue>, ConnectionError> {
+    fn to_bind_value(expr: &Expr, negative: bool) -> Result<BindValue, ConnectionError> {
+        match expr {
+            Expr::Value(Value::Number(n, _)) => {
+                let n = if negative {
+                    format!("-{}", n)
+                } else {
+                    n.clone()
+                };
+
+                if let Ok(v) = n.parse::<i64>() {
+                    Ok(BindValue::Int64(v))
+                } else if let Ok(v) = n.parse::<f64>() {
+                    Ok(BindValue::Float64(v))
+                } else {
+                    Ok(BindValue::String(n))
+                }
+            }
+            Expr::Value(Value::SingleQuotedString(v)) if !negative => {
+                Ok(BindValue::String(v.clone()))
+            }
+            Expr::Value(Value::Boolean(v)) if !negative => Ok(BindValue::Bool(*v)),
+            Expr::Value(Value::Null) => Ok(BindValue::Null),
+            Expr::UnaryOp {
+                op: ast::UnaryOperator::Minus,
+                expr,
+            } => to_bind_value(expr, !negative),
+            Expr::UnaryOp {
+                op: ast::UnaryOperator::Plus,
+                expr,
+            } => to_bind_value(expr, negative),
+            // Types are resolved by prepared statement
+            Expr::Nested(expr) | Expr::Cast { expr, .. } => to_bind_value(expr, negative),
+            _ => Err(ErrorResponse::error(
+                ErrorCode::FeatureNotSupported,
+                format!("Unsupported argument for EXECUTE statement: {}", expr),
+            )
+            .into()),
+        }
+    }
+
+    arguments
+        .iter()
+        .map(|arg| to_bind_value(arg, false))
+        .collect()
+}
+
 #[derive(Debug)]
 pub struct StatementPlaceholderReplacer {}
 
@@ -1104,6 +1157,44 @@ impl<'ast> Visitor<'ast, ConnectionError> for SensitiveDataSanitizer {
     }
 }
 
+/// Replaces all string and numeric literals with placeholders, used for the audit log
+#[derive(Debug)]
+pub struct LiteralRedactor {}
+
+impl LiteralRedactor {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn replace(mut self, stmt: ast::Statement) -> ast::Statement {
+        let mut result = stmt;
+
+        self.visit_statement(&mut result).unwrap();
+
+        result
+    }
+}
+
+impl<'ast> Visitor<'ast, ConnectionError> for LiteralRedactor {
+    fn visit_value(
+        &mut self,
+        val: &mut ast::Value,
+        _pt: PlaceholderType,
+    ) -> Result<(), ConnectionError> {
+        match val {
+            ast::Value::SingleQuotedString(_)
+            | ast::Value::DoubleQuotedString(_)
+            | ast::Value::NationalStringLiteral(_)
+            | ast::Value::Number(_, _) => {
+                *val = ast::Value::Placeholder("?".to_string());
+            }
+            _ => (),
+        };
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1191,6 +1282,37 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_execute_arguments_to_bind_values() -> Result<(), ConnectionError> {
+        let stmt = Parser::parse_sql(
+            &PostgreSqlDialect {},
+            "EXECUTE test_stmt(1, -2, 3.5, 'str', true, NULL, '2022-01-01'::date)",
+        )
+        .unwrap()
+        .pop()
+        .expect("must contain at least one statement");
+
+        let arguments = match stmt {
+            ast::Statement::Execute { parameters, .. } => parameters,
+            other => panic!("EXECUTE statement was expected, actual: {}", other),
+        };
+
+        assert_eq!(
+            execute_arguments_to_bind_values(&arguments)?,
+            vec![
+                BindValue::Int64(1),
+                BindValue::Int64(-2),
+                BindValue::Float64(3.5),
+                BindValue::String("str".to_string()),
+                BindValue::Bool(true),
+                BindValue::Null,
+                BindValue::String("2022-01-01".to_string()),
+            ]
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_pg_binder() -> Result<(), ConnectionError> {
         run_pg_binder(
@@ -1211,6 +1333,12 @@ mod tests {
             vec![BindValue::String("test1".to_string()), BindValue::Null],
         )?;
 
+        run_pg_binder(
+            "SELECT $1 AS n",
+            "SELECT 12345678901234567890.123 AS n",
+            vec![BindValue::Numeric("12345678901234567890.123".to_string())],
+        )?;
+
         // binary op
         run_pg_binder(
             r#"
@@ -1406,4 +1534,22 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_literal_redactor() -> Result<(), CubeError> {
+        let stmt = Parser::parse_sql(
+            &PostgreSqlDialect {},
+            "SELECT * FROM testdata WHERE email = 'a@b.com' AND age > 18 LIMIT 5",
+        )
+        .unwrap()
+        .pop()
+        .expect("must contain at least one statement");
+
+        assert_eq!(
+            LiteralRedactor::new().replace(stmt).to_string(),
+            "SELECT * FROM testdata WHERE email = ? AND age > ? LIMIT ?"
+        );
+
+        Ok(())
+    }
 }
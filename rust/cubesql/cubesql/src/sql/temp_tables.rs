@@ -1,33 +1,49 @@
 use std::{
     any::Any,
     collections::HashMap,
-    env,
+    fmt,
+    fs::File,
+    path::{Path, PathBuf},
+    pin::Pin,
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc, Weak,
     },
+    task::{Context, Poll},
+    time::{Duration, SystemTime},
 };
 
 use async_trait::async_trait;
 use datafusion::{
     arrow::{
         datatypes::{Schema, SchemaRef},
+        error::Result as ArrowResult,
+        ipc::{reader::FileReader, writer::FileWriter},
         record_batch::RecordBatch,
     },
     datasource::TableProvider,
     error::DataFusionError,
+    execution::context::TaskContext,
     logical_plan::{DFSchema, DFSchemaRef, Expr},
-    physical_plan::{memory::MemoryExec, ExecutionPlan},
+    physical_plan::{
+        expressions::PhysicalSortExpr, memory::MemoryExec, DisplayFormatType, ExecutionPlan,
+        Partitioning, RecordBatchStream, SendableRecordBatchStream, Statistics,
+    },
 };
+use futures::Stream;
 
 use crate::{CubeError, RWLockSync};
 
 use super::SessionManager;
 
+/// Temporary tables of the session or tables which are shared between sessions of the same user.
+/// Tables are stored in memory, but they can be spilled to the disk when memory quota is reached.
 #[derive(Debug)]
 pub struct TempTableManager {
     temp_tables: RWLockSync<HashMap<String, Arc<TempTable>>>,
     cached_size: AtomicUsize,
+    // Shared tables are removed after TTL
+    ttl: Option<Duration>,
     // Backref
     session_manager: Weak<SessionManager>,
 }
@@ -37,11 +53,21 @@ impl TempTableManager {
         Self {
             temp_tables: RWLockSync::new(HashMap::new()),
             cached_size: AtomicUsize::new(0),
+            ttl: None,
             session_manager,
         }
     }
 
+    pub fn with_ttl(session_manager: Weak<SessionManager>, ttl: Duration) -> Self {
+        Self {
+            ttl: Some(ttl),
+            ..Self::new(session_manager)
+        }
+    }
+
     pub fn get(&self, name: &str) -> Option<Arc<TempTable>> {
+        self.remove_expired();
+
         self.temp_tables
             .read()
             .expect("failed to unlock temp tables for reading")
@@ -50,25 +76,40 @@ impl TempTableManager {
     }
 
     pub fn has(&self, name: &str) -> bool {
+        self.remove_expired();
+
         self.temp_tables
             .read()
             .expect("failed to unlock temp tables for reading")
             .contains_key(name)
     }
 
+    pub fn names(&self) -> Vec<String> {
+        self.remove_expired();
+
+        let mut names = self
+            .temp_tables
+            .read()
+            .expect("failed to unlock temp tables for reading")
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        names.sort();
+
+        names
+    }
+
     pub fn save(&self, name: &str, temp_table: TempTable) -> Result<(), CubeError> {
         let session_manager = self
             .session_manager
             .upgrade()
             .ok_or_else(|| CubeError::internal("session manager is unavailable".to_string()))?;
 
-        let size_session_limit = env::var("CUBESQL_TEMP_TABLE_SESSION_MEM")
-            .map(|v| v.parse::<usize>().unwrap())
-            .unwrap_or(10); // in MiB
+        let config_obj = &session_manager.server.config_obj;
+        let size_session_limit = config_obj.temp_table_session_mem(); // in MiB
+        let size_total_limit = config_obj.temp_table_total_mem(); // in MiB
 
-        let size_total_limit = env::var("CUBESQL_TEMP_TABLE_TOTAL_MEM")
-            .map(|v| v.parse::<usize>().unwrap())
-            .unwrap_or(100); // in MiB
+        self.remove_expired();
 
         let mut guard = self
             .temp_tables
@@ -82,38 +123,91 @@ impl TempTableManager {
             )));
         }
 
-        self.cached_size
-            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |current_size| {
-                if current_size + temp_table.size > size_session_limit * 1024 * 1024 {
-                    return None;
-                }
-                session_manager
-                    .temp_table_size
-                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |current_size| {
-                        if current_size + temp_table.size > size_total_limit * 1024 * 1024 {
-                            return None;
-                        }
-                        Some(current_size + temp_table.size)
-                    })
-                    .ok()?;
-                Some(current_size + temp_table.size)
-            })
-            .map_err(|_| {
-                CubeError::user(format!(
+        let reserved =
+            self.cached_size
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |current_size| {
+                    if current_size + temp_table.size > size_session_limit * 1024 * 1024 {
+                        return None;
+                    }
+                    session_manager
+                        .temp_table_size
+                        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |current_size| {
+                            if current_size + temp_table.size > size_total_limit * 1024 * 1024 {
+                                return None;
+                            }
+                            Some(current_size + temp_table.size)
+                        })
+                        .ok()?;
+                    Some(current_size + temp_table.size)
+                });
+
+        let temp_table = match (reserved, config_obj.temp_table_spill_dir()) {
+            (Ok(_), _) => temp_table,
+            (Err(_), Some(spill_dir)) => temp_table.spill(
+                Path::new(spill_dir),
+                session_manager.temp_table_spill_size.clone(),
+                config_obj.temp_table_spill_max_size(),
+            )?,
+            (Err(_), None) => {
+                return Err(CubeError::user(format!(
                     "temporary table memory limit reached ({} MiB session, {} MiB total)",
                     size_session_limit, size_total_limit,
-                ))
-            })?;
+                )))
+            }
+        };
+
+        let temp_table = match self.ttl {
+            Some(ttl) => TempTable {
+                expires_at: Some(SystemTime::now() + ttl),
+                ..temp_table
+            },
+            None => temp_table,
+        };
 
         guard.insert(name.to_string(), Arc::new(temp_table));
         Ok(())
     }
 
+    fn remove_expired(&self) {
+        if self.ttl.is_none() {
+            return;
+        }
+
+        let now = SystemTime::now();
+        let expired = {
+            let mut guard = self
+                .temp_tables
+                .write()
+                .expect("failed to unlock temp tables for writing");
+
+            let names = guard
+                .iter()
+                .filter(|(_, temp_table)| temp_table.is_expired(now))
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>();
+
+            names
+                .iter()
+                .filter_map(|name| guard.remove(name))
+                .collect::<Vec<_>>()
+        };
+
+        for temp_table in expired {
+            self.release_size(temp_table.size);
+        }
+    }
+
+    fn release_size(&self, size: usize) {
+        self.cached_size.fetch_sub(size, Ordering::SeqCst);
+        if let Some(session_manager) = self.session_manager.upgrade() {
+            session_manager
+                .temp_table_size
+                .fetch_sub(size, Ordering::SeqCst);
+        }
+    }
+
     pub fn remove(&self, name: &str) -> Result<(), CubeError> {
-        let session_manager = self
-            .session_manager
-            .upgrade()
-            .ok_or_else(|| CubeError::internal("session manager is unavailable".to_string()))?;
+        self.remove_expired();
 
         let Some(temp_table) = ({
             let mut guard = self
@@ -129,11 +223,7 @@ impl TempTableManager {
             )));
         };
 
-        self.cached_size
-            .fetch_sub(temp_table.size, Ordering::SeqCst);
-        session_manager
-            .temp_table_size
-            .fetch_sub(temp_table.size, Ordering::SeqCst);
+        self.release_size(temp_table.size);
 
         Ok(())
     }
@@ -141,13 +231,53 @@ impl TempTableManager {
     pub fn physical_size(&self) -> usize {
         self.cached_size.load(Ordering::SeqCst)
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.remove_expired();
+
+        self.temp_tables
+            .read()
+            .expect("failed to unlock temp tables for reading")
+            .is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
+enum TempTableData {
+    Memory(Vec<Vec<RecordBatch>>),
+    // Arrow IPC file on the local disk
+    Disk(Arc<TempTableSpillFile>),
+}
+
+/// Spilled file is removed when the table is dropped
+#[derive(Debug)]
+struct TempTableSpillFile {
+    path: PathBuf,
+    // File size reserved in the total size of spilled tables
+    size: usize,
+    spill_size: Arc<AtomicUsize>,
+}
+
+impl Drop for TempTableSpillFile {
+    fn drop(&mut self) {
+        if let Err(err) = std::fs::remove_file(&self.path) {
+            log::error!(
+                "Unable to remove spilled temporary table file {}: {}",
+                self.path.display(),
+                err
+            );
+        }
+        self.spill_size.fetch_sub(self.size, Ordering::SeqCst);
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct TempTable {
     schema: SchemaRef,
-    record_batch: Vec<Vec<RecordBatch>>,
+    data: TempTableData,
+    // Memory size, It's zero for spilled tables
     size: usize,
+    expires_at: Option<SystemTime>,
 }
 
 impl TempTable {
@@ -170,12 +300,245 @@ impl TempTable {
             .sum();
         Self {
             schema: arrow_schema,
-            record_batch,
+            data: TempTableData::Memory(record_batch),
             size,
+            expires_at: None,
+        }
+    }
+
+    pub fn is_spilled(&self) -> bool {
+        matches!(self.data, TempTableData::Disk(_))
+    }
+
+    fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires_at
+            .map(|expires_at| expires_at <= now)
+            .unwrap_or(false)
+    }
+
+    /// Writes table to Arrow IPC file inside spill_dir. Total size of spilled files is limited by
+    /// spill_max_size (in MiB), spill_size tracks it.
+    fn spill(
+        self,
+        spill_dir: &Path,
+        spill_size: Arc<AtomicUsize>,
+        spill_max_size: usize,
+    ) -> Result<Self, CubeError> {
+        let TempTableData::Memory(record_batch) = self.data else {
+            return Ok(self);
+        };
+
+        std::fs::create_dir_all(spill_dir)?;
+        let path = spill_dir.join(format!("{}.arrow", uuid::Uuid::new_v4()));
+        let mut spill_file = TempTableSpillFile {
+            path,
+            size: 0,
+            spill_size,
+        };
+
+        let mut writer = FileWriter::try_new(File::create(&spill_file.path)?, &self.schema)?;
+        for batch in record_batch.iter().flatten() {
+            writer.write(batch)?;
+        }
+        writer.finish()?;
+
+        // File is removed on error
+        let size = std::fs::metadata(&spill_file.path)?.len() as usize;
+        spill_file
+            .spill_size
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |current_size| {
+                if current_size + size > spill_max_size * 1024 * 1024 {
+                    return None;
+                }
+                Some(current_size + size)
+            })
+            .map_err(|_| {
+                CubeError::user(format!(
+                    "temporary table spill limit reached ({} MiB)",
+                    spill_max_size
+                ))
+            })?;
+        spill_file.size = size;
+        let spill_file = Arc::new(spill_file);
+
+        Ok(Self {
+            schema: self.schema,
+            data: TempTableData::Disk(spill_file),
+            size: 0,
+            expires_at: self.expires_at,
+        })
+    }
+
+    fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+    ) -> Result<Arc<dyn ExecutionPlan>, DataFusionError> {
+        match &self.data {
+            TempTableData::Memory(record_batch) => Ok(Arc::new(MemoryExec::try_new(
+                record_batch,
+                self.schema.clone(),
+                projection.clone(),
+            )?)),
+            TempTableData::Disk(spill_file) => Ok(Arc::new(TempTableSpillExec::new(
+                spill_file.clone(),
+                &self.schema,
+                projection.clone(),
+            ))),
+        }
+    }
+}
+
+/// Reads spilled table from the disk batch by batch, instead of loading the whole file
+#[derive(Debug)]
+struct TempTableSpillExec {
+    // Keeps the file while it's read, even if the table is dropped
+    spill_file: Arc<TempTableSpillFile>,
+    schema: SchemaRef,
+    projection: Option<Vec<usize>>,
+}
+
+impl TempTableSpillExec {
+    fn new(
+        spill_file: Arc<TempTableSpillFile>,
+        schema: &SchemaRef,
+        projection: Option<Vec<usize>>,
+    ) -> Self {
+        let schema = match &projection {
+            Some(projection) => Arc::new(Schema::new_with_metadata(
+                projection
+                    .iter()
+                    .map(|i| schema.field(*i).clone())
+                    .collect(),
+                schema.metadata().clone(),
+            )),
+            None => schema.clone(),
+        };
+
+        Self {
+            spill_file,
+            schema,
+            projection,
+        }
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for TempTableSpillExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>, DataFusionError> {
+        Err(DataFusionError::Internal(format!(
+            "Children cannot be replaced in {:?}",
+            self
+        )))
+    }
+
+    async fn execute(
+        &self,
+        _partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream, DataFusionError> {
+        let (sender, receiver) = tokio::sync::mpsc::channel(2);
+        let spill_file = self.spill_file.clone();
+        let schema = self.schema.clone();
+        let projection = self.projection.clone();
+
+        // Arrow IPC reader is blocking, batches are sent as they are read
+        tokio::task::spawn_blocking(move || {
+            let read = || -> ArrowResult<()> {
+                let reader = FileReader::try_new(File::open(&spill_file.path)?)?;
+                for batch in reader {
+                    let batch = match &projection {
+                        Some(projection) => RecordBatch::try_new(
+                            schema.clone(),
+                            projection
+                                .iter()
+                                .map(|i| batch.column(*i).clone())
+                                .collect(),
+                        ),
+                        None => batch,
+                    };
+                    if sender.blocking_send(batch).is_err() {
+                        // Stream was dropped
+                        return Ok(());
+                    }
+                }
+                Ok(())
+            };
+            let result = read();
+            // File must be released before the stream is finished
+            drop(spill_file);
+            if let Err(err) = result {
+                let _ = sender.blocking_send(Err(err));
+            }
+        });
+
+        Ok(Box::pin(TempTableSpillStream {
+            schema: self.schema.clone(),
+            receiver,
+        }))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default => write!(
+                f,
+                "TempTableSpillExec: file={}",
+                self.spill_file.path.display()
+            ),
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics {
+            num_rows: None,
+            total_byte_size: None,
+            column_statistics: None,
+            is_exact: false,
         }
     }
 }
 
+struct TempTableSpillStream {
+    schema: SchemaRef,
+    receiver: tokio::sync::mpsc::Receiver<ArrowResult<RecordBatch>>,
+}
+
+impl Stream for TempTableSpillStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl RecordBatchStream for TempTableSpillStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
 fn df_schema_to_arrow_schema(df_schema: &DFSchema) -> SchemaRef {
     let arrow_schema = Schema::new_with_metadata(
         df_schema
@@ -220,10 +583,168 @@ impl TableProvider for TempTableProvider {
         _filters: &[Expr],
         _limit: Option<usize>,
     ) -> Result<Arc<dyn ExecutionPlan>, DataFusionError> {
-        Ok(Arc::new(MemoryExec::try_new(
-            &self.temp_table.record_batch,
-            self.schema(),
-            projection.clone(),
-        )?))
+        self.temp_table.scan(projection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::{
+        arrow::{
+            array::{Array, Int64Array},
+            datatypes::{DataType, Field},
+        },
+        execution::runtime_env::{RuntimeConfig, RuntimeEnv},
+        logical_plan::DFSchema,
+        physical_plan::common,
+    };
+
+    use super::*;
+    use crate::{
+        compile::{
+            test::{get_test_session_with_config, get_test_tenant_ctx},
+            DatabaseProtocol,
+        },
+        config::ConfigObjImpl,
+    };
+
+    fn get_test_temp_table() -> TempTable {
+        let schema = Arc::new(Schema::new(vec![Field::new("i", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int64Array::from(vec![1, 2, 3]))],
+        )
+        .unwrap();
+
+        TempTable::new(
+            Arc::new(DFSchema::try_from(schema.as_ref().clone()).unwrap()),
+            vec![vec![batch]],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_temp_table_spill_to_disk() -> Result<(), CubeError> {
+        let spill_dir =
+            std::env::temp_dir().join(format!("cubesql-spill-{}", uuid::Uuid::new_v4()));
+        let config_obj = ConfigObjImpl {
+            temp_table_session_mem: 0,
+            temp_table_spill_dir: Some(spill_dir.to_string_lossy().to_string()),
+            ..ConfigObjImpl::default()
+        };
+        let session = get_test_session_with_config(
+            DatabaseProtocol::PostgreSQL,
+            Arc::new(config_obj),
+            get_test_tenant_ctx(),
+        )
+        .await;
+
+        let temp_tables = session.state.temp_tables();
+        temp_tables.save("spilled", get_test_temp_table())?;
+
+        let temp_table = temp_tables.get("spilled").unwrap();
+        assert!(temp_table.is_spilled());
+        assert_eq!(temp_tables.physical_size(), 0);
+        let spill_size = session.session_manager.temp_table_spill_size.clone();
+        assert!(spill_size.load(Ordering::SeqCst) > 0);
+
+        let runtime = Arc::new(
+            RuntimeEnv::new(RuntimeConfig::new()).expect("Unable to create RuntimeEnv for testing"),
+        );
+        let task = Arc::new(TaskContext::new(
+            "test".to_string(),
+            "session".to_string(),
+            HashMap::new(),
+            HashMap::new(),
+            HashMap::new(),
+            runtime,
+        ));
+        let plan = TempTableProvider::new("spilled".to_string(), temp_table.clone())
+            .scan(&Some(vec![0]), &[], None)
+            .await?;
+        drop(temp_table);
+        // Table is dropped while it's read, file is removed after the scan
+        temp_tables.remove("spilled")?;
+
+        let batches = common::collect(plan.execute(0, task).await?).await?;
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].num_columns(), 1);
+        assert_eq!(batches[0].column(0).len(), 3);
+
+        drop(plan);
+        assert_eq!(std::fs::read_dir(&spill_dir)?.count(), 0);
+        assert_eq!(spill_size.load(Ordering::SeqCst), 0);
+
+        std::fs::remove_dir_all(&spill_dir)?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_temp_table_spill_limit() -> Result<(), CubeError> {
+        let spill_dir =
+            std::env::temp_dir().join(format!("cubesql-spill-{}", uuid::Uuid::new_v4()));
+        let config_obj = ConfigObjImpl {
+            temp_table_session_mem: 0,
+            temp_table_spill_dir: Some(spill_dir.to_string_lossy().to_string()),
+            temp_table_spill_max_size: 0,
+            ..ConfigObjImpl::default()
+        };
+        let session = get_test_session_with_config(
+            DatabaseProtocol::PostgreSQL,
+            Arc::new(config_obj),
+            get_test_tenant_ctx(),
+        )
+        .await;
+
+        let temp_tables = session.state.temp_tables();
+        let err = temp_tables
+            .save("spilled", get_test_temp_table())
+            .unwrap_err();
+        assert_eq!(err.message, "temporary table spill limit reached (0 MiB)");
+        assert!(!temp_tables.has("spilled"));
+        assert_eq!(std::fs::read_dir(&spill_dir)?.count(), 0);
+        assert_eq!(
+            session
+                .session_manager
+                .temp_table_spill_size
+                .load(Ordering::SeqCst),
+            0
+        );
+
+        std::fs::remove_dir_all(&spill_dir)?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shared_tables_ttl() -> Result<(), CubeError> {
+        let session = get_test_session_with_config(
+            DatabaseProtocol::PostgreSQL,
+            Arc::new(ConfigObjImpl::default()),
+            get_test_tenant_ctx(),
+        )
+        .await;
+
+        let shared_tables = TempTableManager::with_ttl(
+            Arc::downgrade(&session.session_manager),
+            Duration::from_millis(100),
+        );
+        shared_tables.save("shared", get_test_temp_table())?;
+        assert_eq!(shared_tables.names(), vec!["shared".to_string()]);
+        assert!(shared_tables.physical_size() > 0);
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        assert!(!shared_tables.has("shared"));
+        assert_eq!(shared_tables.physical_size(), 0);
+        assert_eq!(
+            session
+                .session_manager
+                .temp_table_size
+                .load(Ordering::SeqCst),
+            0
+        );
+
+        Ok(())
     }
 }
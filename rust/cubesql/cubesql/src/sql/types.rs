@@ -40,13 +40,20 @@ impl ColumnType {
             ColumnType::List(field) => match field.data_type() {
                 DataType::Binary => PgTypeId::ARRAYBYTEA,
                 DataType::Boolean => PgTypeId::ARRAYBOOL,
-                DataType::Utf8 => PgTypeId::ARRAYTEXT,
-                DataType::Int16 => PgTypeId::ARRAYINT2,
+                DataType::Utf8 | DataType::LargeUtf8 => PgTypeId::ARRAYTEXT,
+                DataType::Int8 | DataType::Int16 => PgTypeId::ARRAYINT2,
                 DataType::Int32 => PgTypeId::ARRAYINT4,
                 DataType::Int64 => PgTypeId::ARRAYINT8,
-                DataType::UInt16 => PgTypeId::ARRAYINT2,
+                DataType::UInt8 | DataType::UInt16 => PgTypeId::ARRAYINT2,
                 DataType::UInt32 => PgTypeId::ARRAYINT4,
                 DataType::UInt64 => PgTypeId::ARRAYINT8,
+                DataType::Float16 | DataType::Float32 => PgTypeId::ARRAYFLOAT4,
+                DataType::Float64 => PgTypeId::ARRAYFLOAT8,
+                DataType::Decimal(_, _) => PgTypeId::ARRAYNUMERIC,
+                DataType::Date32 | DataType::Date64 => PgTypeId::ARRAYDATE,
+                DataType::Interval(_) => PgTypeId::ARRAYINTERVAL,
+                DataType::Timestamp(_, None) => PgTypeId::ARRAYTIMESTAMP,
+                DataType::Timestamp(_, Some(_)) => PgTypeId::ARRAYTIMESTAMPTZ,
                 dt => unimplemented!("Unsupported data type for List: {}", dt),
             },
         }
@@ -123,6 +130,9 @@ impl CommandCompletion {
                 CommandComplete::Plain("DEALLOCATE ALL".to_string())
             }
             CommandCompletion::Discard(tp) => CommandComplete::Plain(format!("DISCARD {}", tp)),
+            CommandCompletion::Listen => CommandComplete::Plain("LISTEN".to_string()),
+            CommandCompletion::Unlisten => CommandComplete::Plain("UNLISTEN".to_string()),
+            CommandCompletion::Notify => CommandComplete::Plain("NOTIFY".to_string()),
             // ROWS COUNT
             CommandCompletion::Select(rows) => CommandComplete::Select(rows),
             CommandCompletion::DropTable => CommandComplete::Plain("DROP TABLE".to_string()),
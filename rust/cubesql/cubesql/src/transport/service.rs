@@ -16,7 +16,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     fmt::Debug,
-    sync::Arc,
+    sync::{Arc, Mutex},
     time::{Duration, SystemTime},
 };
 use tokio::{
@@ -48,6 +48,9 @@ pub struct LoadRequestMeta {
     // Optional fields
     #[serde(rename = "changeUser", skip_serializing_if = "Option::is_none")]
     change_user: Option<String>,
+    // Attribution of the load, from the `cube.query_tag` session variable or a `/* tag: ... */` comment
+    #[serde(rename = "queryTag", skip_serializing_if = "Option::is_none")]
+    query_tag: Option<String>,
 }
 
 impl LoadRequestMeta {
@@ -58,6 +61,7 @@ impl LoadRequestMeta {
             api_type,
             app_name,
             change_user: None,
+            query_tag: None,
         }
     }
 
@@ -68,6 +72,14 @@ impl LoadRequestMeta {
     pub fn set_change_user(&mut self, change_user: Option<String>) {
         self.change_user = change_user;
     }
+
+    pub fn query_tag(&self) -> Option<String> {
+        self.query_tag.clone()
+    }
+
+    pub fn set_query_tag(&mut self, query_tag: Option<String>) {
+        self.query_tag = query_tag;
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -75,12 +87,21 @@ pub struct SqlResponse {
     pub sql: SqlQuery,
 }
 
+/// Details of the query for the audit log, collected during planning and execution
+#[derive(Debug, Clone, Default)]
+pub struct SpanAudit {
+    pub pushed_down: bool,
+    pub load_requests: Vec<serde_json::Value>,
+    pub rows: u64,
+}
+
 #[derive(Debug)]
 pub struct SpanId {
     pub span_id: String,
     pub query_key: serde_json::Value,
     span_start: SystemTime,
     is_data_query: RWLockAsync<bool>,
+    audit: Mutex<SpanAudit>,
 }
 
 impl SpanId {
@@ -90,6 +111,7 @@ impl SpanId {
             query_key,
             span_start: SystemTime::now(),
             is_data_query: tokio::sync::RwLock::new(false),
+            audit: Mutex::new(SpanAudit::default()),
         }
     }
 
@@ -109,6 +131,24 @@ impl SpanId {
             .unwrap_or_else(|_| Duration::from_secs(0))
             .as_millis() as u64
     }
+
+    pub fn set_audit_plan(&self, pushed_down: bool, load_requests: Vec<serde_json::Value>) {
+        let mut guard = self.audit.lock().expect("failed to unlock span audit");
+        guard.pushed_down = pushed_down;
+        guard.load_requests = load_requests;
+    }
+
+    pub fn add_returned_rows(&self, rows: u32) {
+        let mut guard = self.audit.lock().expect("failed to unlock span audit");
+        guard.rows += rows as u64;
+    }
+
+    pub fn audit(&self) -> SpanAudit {
+        self.audit
+            .lock()
+            .expect("failed to unlock span audit")
+            .clone()
+    }
 }
 
 #[async_trait]
@@ -155,6 +195,14 @@ pub trait TransportService: Send + Sync + Debug {
         member_fields: Vec<MemberField>,
     ) -> Result<CubeStreamReceiver, CubeError>;
 
+    // Cancel load requests which are still in flight for the span, it's called when query was cancelled by the client
+    async fn cancel(
+        &self,
+        span_id: Arc<SpanId>,
+        ctx: AuthContextRef,
+        meta_fields: LoadRequestMeta,
+    ) -> Result<(), CubeError>;
+
     async fn can_switch_user_for_session(
         &self,
         ctx: AuthContextRef,
@@ -314,6 +362,16 @@ impl TransportService for HttpTransport {
         panic!("Does not work for standalone mode yet");
     }
 
+    async fn cancel(
+        &self,
+        _span_id: Arc<SpanId>,
+        _ctx: AuthContextRef,
+        _meta_fields: LoadRequestMeta,
+    ) -> Result<(), CubeError> {
+        // HTTP request is aborted when load future is dropped, nothing to cancel
+        Ok(())
+    }
+
     async fn can_switch_user_for_session(
         &self,
         _ctx: AuthContextRef,
@@ -1,11 +1,18 @@
 //! Decoding values from the Protocol representation
 
+#[cfg(feature = "with-chrono")]
+use crate::encoding::{format_pg_time, pg_base_date_epoch};
 use crate::{
     protocol::{ErrorCode, ErrorResponse, Format},
-    ProtocolError,
+    IntervalValue, NumericValue, PgTypeId, ProtocolError,
+};
+#[cfg(feature = "with-chrono")]
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use std::{
+    backtrace::Backtrace,
+    convert::{TryFrom, TryInto},
+    net::{Ipv4Addr, Ipv6Addr},
 };
-use byteorder::{BigEndian, ByteOrder};
-use std::backtrace::Backtrace;
 
 /// This trait explains how to decode values from the protocol
 /// It's used in the Bind message
@@ -32,82 +39,1095 @@ pub trait FromProtocolValue {
         Self: Sized;
 }
 
+fn decoding_error(message: String) -> ProtocolError {
+    ProtocolError::ErrorResponse {
+        source: ErrorResponse::error(ErrorCode::ProtocolViolation, message),
+        backtrace: Backtrace::capture(),
+    }
+}
+
+fn text_value(raw: &[u8]) -> Result<&str, ProtocolError> {
+    std::str::from_utf8(raw).map_err(|err| decoding_error(err.to_string()))
+}
+
+fn fixed_value<const N: usize>(raw: &[u8], type_name: &str) -> Result<[u8; N], ProtocolError> {
+    raw.try_into().map_err(|_| {
+        decoding_error(format!(
+            "Unable to decode {} from binary, expected {} bytes, actual: {}",
+            type_name,
+            N,
+            raw.len()
+        ))
+    })
+}
+
+/// Reader for binary values which consist of multiple fields (arrays, numeric, ranges, etc)
+struct BinaryReader<'a> {
+    raw: &'a [u8],
+    type_name: &'static str,
+}
+
+impl<'a> BinaryReader<'a> {
+    fn new(raw: &'a [u8], type_name: &'static str) -> Self {
+        Self { raw, type_name }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ProtocolError> {
+        if self.raw.len() < n {
+            return Err(decoding_error(format!(
+                "Unable to decode {} from binary, unexpected end of value",
+                self.type_name
+            )));
+        }
+
+        let (head, tail) = self.raw.split_at(n);
+        self.raw = tail;
+
+        Ok(head)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, ProtocolError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_i16(&mut self) -> Result<i16, ProtocolError> {
+        Ok(i16::from_be_bytes(fixed_value(
+            self.take(2)?,
+            self.type_name,
+        )?))
+    }
+
+    fn read_u16(&mut self) -> Result<u16, ProtocolError> {
+        Ok(u16::from_be_bytes(fixed_value(
+            self.take(2)?,
+            self.type_name,
+        )?))
+    }
+
+    fn read_i32(&mut self) -> Result<i32, ProtocolError> {
+        Ok(i32::from_be_bytes(fixed_value(
+            self.take(4)?,
+            self.type_name,
+        )?))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, ProtocolError> {
+        Ok(u32::from_be_bytes(fixed_value(
+            self.take(4)?,
+            self.type_name,
+        )?))
+    }
+
+    /// Length-prefixed value, negative length is used for NULL
+    fn read_value(&mut self) -> Result<Option<&'a [u8]>, ProtocolError> {
+        let len = self.read_i32()?;
+        if len < 0 {
+            Ok(None)
+        } else {
+            self.take(len as usize).map(Some)
+        }
+    }
+
+    fn remaining(&self) -> usize {
+        self.raw.len()
+    }
+
+    fn finish(&self) -> Result<(), ProtocolError> {
+        if self.raw.is_empty() {
+            Ok(())
+        } else {
+            Err(decoding_error(format!(
+                "Unable to decode {} from binary, unexpected {} trailing bytes",
+                self.type_name,
+                self.raw.len()
+            )))
+        }
+    }
+}
+
 impl FromProtocolValue for String {
     fn from_text(raw: &[u8]) -> Result<Self, ProtocolError> {
-        std::str::from_utf8(raw)
-            .map(|s| s.to_string())
-            .map_err(|err| ProtocolError::ErrorResponse {
-                source: ErrorResponse::error(ErrorCode::ProtocolViolation, err.to_string()),
-                backtrace: Backtrace::capture(),
+        text_value(raw).map(|s| s.to_string())
+    }
+
+    fn from_binary(raw: &[u8]) -> Result<Self, ProtocolError> {
+        text_value(raw).map(|s| s.to_string())
+    }
+}
+
+macro_rules! impl_primitive {
+    ($type: ident) => {
+        impl FromProtocolValue for $type {
+            fn from_text(raw: &[u8]) -> Result<Self, ProtocolError> {
+                text_value(raw)?.trim().parse::<$type>().map_err(|err| {
+                    decoding_error(format!(
+                        "Unable to decode {} from text: {}",
+                        stringify!($type),
+                        err
+                    ))
+                })
+            }
+
+            fn from_binary(raw: &[u8]) -> Result<Self, ProtocolError> {
+                Ok($type::from_be_bytes(fixed_value(raw, stringify!($type))?))
+            }
+        }
+    };
+}
+
+impl_primitive!(i16);
+impl_primitive!(i32);
+impl_primitive!(i64);
+impl_primitive!(u32);
+
+macro_rules! impl_float {
+    ($type: ident) => {
+        impl FromProtocolValue for $type {
+            // float8in - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/float.c#L364
+            fn from_text(raw: &[u8]) -> Result<Self, ProtocolError> {
+                let as_str = text_value(raw)?.trim();
+                match as_str.to_lowercase().as_str() {
+                    "nan" => Ok($type::NAN),
+                    "infinity" | "+infinity" | "inf" | "+inf" => Ok($type::INFINITY),
+                    "-infinity" | "-inf" => Ok($type::NEG_INFINITY),
+                    _ => as_str.parse::<$type>().map_err(|err| {
+                        decoding_error(format!(
+                            "Unable to decode {} from text: {}",
+                            stringify!($type),
+                            err
+                        ))
+                    }),
+                }
+            }
+
+            fn from_binary(raw: &[u8]) -> Result<Self, ProtocolError> {
+                Ok($type::from_be_bytes(fixed_value(raw, stringify!($type))?))
+            }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
+
+impl FromProtocolValue for bool {
+    // boolin - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/bool.c#L127
+    fn from_text(raw: &[u8]) -> Result<Self, ProtocolError> {
+        let as_str = text_value(raw)?.trim();
+        match as_str.to_lowercase().as_str() {
+            "t" | "true" | "y" | "yes" | "on" | "1" => Ok(true),
+            "f" | "false" | "n" | "no" | "off" | "0" => Ok(false),
+            _ => Err(decoding_error(format!(
+                "Unable to decode bool from text, actual: {}",
+                as_str
+            ))),
+        }
+    }
+
+    fn from_binary(raw: &[u8]) -> Result<Self, ProtocolError> {
+        match fixed_value::<1>(raw, "bool")?[0] {
+            1 => Ok(true),
+            0 => Ok(false),
+            other => Err(decoding_error(format!(
+                "Unable to decode bool from binary, actual: {}",
+                other
+            ))),
+        }
+    }
+}
+
+fn hex_digit(c: u8) -> Option<u8> {
+    (c as char).to_digit(16).map(|d| d as u8)
+}
+
+impl FromProtocolValue for Vec<u8> {
+    // byteain - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/varlena.c#L277
+    fn from_text(raw: &[u8]) -> Result<Self, ProtocolError> {
+        if let Some(hex) = raw.strip_prefix(b"\\x") {
+            let digits = hex
+                .iter()
+                .filter(|c| !c.is_ascii_whitespace())
+                .map(|c| hex_digit(*c))
+                .collect::<Option<Vec<_>>>()
+                .filter(|digits| digits.len() % 2 == 0)
+                .ok_or_else(|| {
+                    decoding_error("Unable to decode bytea from text, invalid hex".to_string())
+                })?;
+
+            return Ok(digits
+                .chunks(2)
+                .map(|pair| pair[0] << 4 | pair[1])
+                .collect());
+        }
+
+        // Escape format, where only backslash and octal escapes are special
+        let mut result = Vec::with_capacity(raw.len());
+        let mut i = 0;
+        while i < raw.len() {
+            if raw[i] != b'\\' {
+                result.push(raw[i]);
+                i += 1;
+            } else if raw.get(i + 1) == Some(&b'\\') {
+                result.push(b'\\');
+                i += 2;
+            } else {
+                let octal = raw
+                    .get(i + 1..i + 4)
+                    .and_then(|octal| std::str::from_utf8(octal).ok())
+                    .and_then(|octal| u8::from_str_radix(octal, 8).ok())
+                    .ok_or_else(|| {
+                        decoding_error(
+                            "Unable to decode bytea from text, invalid escape".to_string(),
+                        )
+                    })?;
+                result.push(octal);
+                i += 4;
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn from_binary(raw: &[u8]) -> Result<Self, ProtocolError> {
+        Ok(raw.to_vec())
+    }
+}
+
+impl FromProtocolValue for NumericValue {
+    // numeric_in - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/numeric.c#L617
+    fn from_text(raw: &[u8]) -> Result<Self, ProtocolError> {
+        let as_str = text_value(raw)?.trim();
+        match as_str.to_lowercase().as_str() {
+            "nan" => return Ok(NumericValue::new("NaN".to_string())),
+            "infinity" | "+infinity" | "inf" | "+inf" => {
+                return Ok(NumericValue::new("Infinity".to_string()))
+            }
+            "-infinity" | "-inf" => return Ok(NumericValue::new("-Infinity".to_string())),
+            _ => {}
+        };
+
+        let unsigned = as_str.trim_start_matches(['-', '+']);
+        let (int_part, frac_part) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        let is_valid = as_str.len() - unsigned.len() <= 1
+            && !(int_part.is_empty() && frac_part.is_empty())
+            && int_part
+                .chars()
+                .chain(frac_part.chars())
+                .all(|c| c.is_ascii_digit());
+        if !is_valid {
+            return Err(decoding_error(format!(
+                "Unable to decode numeric from text, actual: {}",
+                as_str
+            )));
+        }
+
+        Ok(NumericValue::new(as_str.to_string()))
+    }
+
+    // numeric_recv - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/numeric.c#L1032
+    fn from_binary(raw: &[u8]) -> Result<Self, ProtocolError> {
+        let mut reader = BinaryReader::new(raw, "numeric");
+        let ndigits = reader.read_i16()?;
+        let weight = reader.read_i16()? as i32;
+        let sign = reader.read_u16()?;
+        let dscale = reader.read_u16()? as usize;
+        let digits = (0..ndigits)
+            .map(|_| match reader.read_i16()? {
+                digit @ 0..=9999 => Ok(digit),
+                digit => Err(decoding_error(format!(
+                    "Unable to decode numeric from binary, invalid digit: {}",
+                    digit
+                ))),
             })
+            .collect::<Result<Vec<_>, _>>()?;
+        reader.finish()?;
+
+        let mut result = match sign {
+            NumericValue::SIGN_POS => String::new(),
+            NumericValue::SIGN_NEG => "-".to_string(),
+            NumericValue::SIGN_NAN => return Ok(NumericValue::new("NaN".to_string())),
+            NumericValue::SIGN_PINF => return Ok(NumericValue::new("Infinity".to_string())),
+            NumericValue::SIGN_NINF => return Ok(NumericValue::new("-Infinity".to_string())),
+            other => {
+                return Err(decoding_error(format!(
+                    "Unable to decode numeric from binary, invalid sign: {:#x}",
+                    other
+                )))
+            }
+        };
+
+        let digit = |idx: i32| -> i16 {
+            if idx >= 0 && (idx as usize) < digits.len() {
+                digits[idx as usize]
+            } else {
+                0
+            }
+        };
+
+        if weight < 0 {
+            result.push('0');
+        } else {
+            result.push_str(&digit(0).to_string());
+            for idx in 1..=weight {
+                result.push_str(&format!("{:04}", digit(idx)));
+            }
+        }
+
+        if dscale > 0 {
+            let mut frac_part = String::with_capacity(dscale + 4);
+            let mut idx = weight + 1;
+            while frac_part.len() < dscale {
+                frac_part.push_str(&format!("{:04}", digit(idx)));
+                idx += 1;
+            }
+            frac_part.truncate(dscale);
+
+            result.push('.');
+            result.push_str(&frac_part);
+        }
+
+        Ok(NumericValue::new(result))
+    }
+}
+
+#[cfg(feature = "with-chrono")]
+impl FromProtocolValue for NaiveDate {
+    // date_in - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/date.c#L111
+    fn from_text(raw: &[u8]) -> Result<Self, ProtocolError> {
+        let as_str = text_value(raw)?.trim();
+        NaiveDate::parse_from_str(as_str, "%Y-%m-%d").map_err(|err| {
+            decoding_error(format!(
+                "Unable to decode date from text, actual: {}, error: {}",
+                as_str, err
+            ))
+        })
     }
 
+    // date_recv - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/date.c#L201
     fn from_binary(raw: &[u8]) -> Result<Self, ProtocolError> {
-        std::str::from_utf8(raw)
-            .map(|s| s.to_string())
-            .map_err(|err| ProtocolError::ErrorResponse {
-                source: ErrorResponse::error(ErrorCode::ProtocolViolation, err.to_string()),
-                backtrace: Backtrace::capture(),
+        let days = i32::from_binary(raw)?;
+        pg_base_date_epoch()
+            .date()
+            .checked_add_signed(Duration::days(days as i64))
+            .ok_or_else(|| {
+                decoding_error(format!(
+                    "Unable to decode date from binary, out of range: {}",
+                    days
+                ))
             })
     }
 }
 
-impl FromProtocolValue for i64 {
+#[cfg(feature = "with-chrono")]
+impl FromProtocolValue for NaiveTime {
+    // time_in - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/date.c#L1345
     fn from_text(raw: &[u8]) -> Result<Self, ProtocolError> {
-        let as_str = std::str::from_utf8(raw).map_err(|err| ProtocolError::ErrorResponse {
-            source: ErrorResponse::error(ErrorCode::ProtocolViolation, err.to_string()),
-            backtrace: Backtrace::capture(),
-        })?;
-
-        as_str
-            .parse::<i64>()
-            .map_err(|err| ProtocolError::ErrorResponse {
-                source: ErrorResponse::error(ErrorCode::ProtocolViolation, err.to_string()),
-                backtrace: Backtrace::capture(),
+        let as_str = text_value(raw)?.trim();
+        NaiveTime::parse_from_str(as_str, "%H:%M:%S%.f")
+            .or_else(|_| NaiveTime::parse_from_str(as_str, "%H:%M"))
+            .map_err(|err| {
+                decoding_error(format!(
+                    "Unable to decode time from text, actual: {}, error: {}",
+                    as_str, err
+                ))
             })
     }
 
+    // time_recv - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/date.c#L1424
     fn from_binary(raw: &[u8]) -> Result<Self, ProtocolError> {
-        Ok(BigEndian::read_i64(raw))
+        let usecs = i64::from_binary(raw)?;
+        if !(0..86_400_000_000).contains(&usecs) {
+            return Err(decoding_error(format!(
+                "Unable to decode time from binary, out of range: {}",
+                usecs
+            )));
+        }
+
+        Ok(NaiveTime::from_num_seconds_from_midnight_opt(
+            (usecs / 1_000_000) as u32,
+            (usecs % 1_000_000) as u32 * 1_000,
+        )
+        .unwrap())
     }
 }
 
-impl FromProtocolValue for bool {
+#[cfg(feature = "with-chrono")]
+impl FromProtocolValue for NaiveDateTime {
+    // timestamp_in - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/timestamp.c#L144
     fn from_text(raw: &[u8]) -> Result<Self, ProtocolError> {
-        match raw[0] {
-            b't' => Ok(true),
-            b'f' => Ok(false),
-            other => Err(ProtocolError::ErrorResponse {
-                source: ErrorResponse::error(
-                    ErrorCode::ProtocolViolation,
-                    format!("Unable to decode bool from text, actual: {}", other),
-                ),
-                backtrace: Backtrace::capture(),
-            }),
+        let as_str = text_value(raw)?.trim();
+        NaiveDateTime::parse_from_str(as_str, "%Y-%m-%d %H:%M:%S%.f")
+            .or_else(|_| NaiveDateTime::parse_from_str(as_str, "%Y-%m-%dT%H:%M:%S%.f"))
+            .or_else(|_| {
+                NaiveDate::parse_from_str(as_str, "%Y-%m-%d")
+                    .map(|date| date.and_hms_opt(0, 0, 0).unwrap())
+            })
+            .map_err(|err| {
+                decoding_error(format!(
+                    "Unable to decode timestamp from text, actual: {}, error: {}",
+                    as_str, err
+                ))
+            })
+    }
+
+    // timestamp_recv - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/timestamp.c#L248
+    fn from_binary(raw: &[u8]) -> Result<Self, ProtocolError> {
+        let usecs = i64::from_binary(raw)?;
+        pg_base_date_epoch()
+            .checked_add_signed(Duration::microseconds(usecs))
+            .ok_or_else(|| {
+                decoding_error(format!(
+                    "Unable to decode timestamp from binary, out of range: {}",
+                    usecs
+                ))
+            })
+    }
+}
+
+#[cfg(feature = "with-chrono")]
+impl FromProtocolValue for DateTime<Utc> {
+    // timestamptz_in - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/timestamp.c#L405
+    fn from_text(raw: &[u8]) -> Result<Self, ProtocolError> {
+        let as_str = text_value(raw)?.trim();
+        match DateTime::parse_from_str(as_str, "%Y-%m-%d %H:%M:%S%.f%#z")
+            .or_else(|_| DateTime::parse_from_str(as_str, "%Y-%m-%dT%H:%M:%S%.f%#z"))
+        {
+            Ok(value) => Ok(value.with_timezone(&Utc)),
+            // Without time zone, the value is in UTC, because it's the only supported TimeZone
+            Err(_) => NaiveDateTime::from_text(raw).map(|value| Utc.from_utc_datetime(&value)),
         }
     }
 
+    // timestamptz_recv - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/timestamp.c#L797
     fn from_binary(raw: &[u8]) -> Result<Self, ProtocolError> {
-        match raw[0] {
-            1 => Ok(true),
-            0 => Ok(false),
-            other => Err(ProtocolError::ErrorResponse {
-                source: ErrorResponse::error(
-                    ErrorCode::ProtocolViolation,
-                    format!("Unable to decode bool from binary, actual: {}", other),
+        NaiveDateTime::from_binary(raw).map(|value| Utc.from_utc_datetime(&value))
+    }
+}
+
+impl FromProtocolValue for IntervalValue {
+    // interval_in - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/timestamp.c#L880
+    // Only postgres and postgres_verbose styles are supported, without `ago`
+    fn from_text(raw: &[u8]) -> Result<Self, ProtocolError> {
+        let as_str = text_value(raw)?.trim();
+        let invalid = || {
+            decoding_error(format!(
+                "Unable to decode interval from text, actual: {}",
+                as_str
+            ))
+        };
+
+        // Fields are accumulated with overflow checks, large values are rejected as Postgres does
+        let add = |field: &mut i32, value: i64| -> Result<(), ProtocolError> {
+            *field = i32::try_from(value)
+                .ok()
+                .and_then(|value| field.checked_add(value))
+                .ok_or_else(|| {
+                    decoding_error(format!(
+                        "Unable to decode interval from text, out of range: {}",
+                        as_str
+                    ))
+                })?;
+
+            Ok(())
+        };
+
+        let mut result = IntervalValue::default();
+        let mut tokens = as_str
+            .split_whitespace()
+            .filter(|token| *token != "@")
+            .map(|token| token.trim_end_matches(','));
+        while let Some(token) = tokens.next() {
+            if token.contains(':') {
+                let (negative, time) = match token.strip_prefix('-') {
+                    Some(time) => (true, time),
+                    None => (false, token.trim_start_matches('+')),
+                };
+                let mut parts = time.split(':');
+                let hours = parts.next().and_then(|p| p.parse::<i32>().ok());
+                let mins = parts.next().and_then(|p| p.parse::<i32>().ok());
+                let secs = parts.next().unwrap_or("0").parse::<f64>().ok();
+                let (hours, mins, secs) = match (hours, mins, secs, parts.next()) {
+                    (Some(hours), Some(mins), Some(secs), None) => (hours, mins, secs),
+                    _ => return Err(invalid()),
+                };
+
+                let sign: i64 = if negative { -1 } else { 1 };
+                let usecs = (secs * 1_000_000_f64).round() as i64;
+                add(&mut result.hours, sign * hours as i64)?;
+                add(&mut result.mins, sign * mins as i64)?;
+                add(&mut result.secs, sign * (usecs / 1_000_000))?;
+                add(&mut result.usecs, sign * (usecs % 1_000_000))?;
+
+                continue;
+            }
+
+            let number = token.parse::<f64>().map_err(|_| invalid())?;
+            let unit = tokens.next().ok_or_else(invalid)?.to_lowercase();
+            let whole = || {
+                if number.fract() == 0_f64 {
+                    // Saturates, out of range values are rejected by add
+                    Ok(number as i64)
+                } else {
+                    Err(invalid())
+                }
+            };
+            match unit.as_str() {
+                "year" | "years" | "y" => add(&mut result.months, whole()?.saturating_mul(12))?,
+                "mon" | "mons" | "month" | "months" => add(&mut result.months, whole()?)?,
+                "week" | "weeks" | "w" => add(&mut result.days, whole()?.saturating_mul(7))?,
+                "day" | "days" | "d" => add(&mut result.days, whole()?)?,
+                "hour" | "hours" | "h" => add(&mut result.hours, whole()?)?,
+                "min" | "mins" | "minute" | "minutes" | "m" => add(&mut result.mins, whole()?)?,
+                "sec" | "secs" | "second" | "seconds" | "s" => {
+                    let usecs = (number * 1_000_000_f64).round() as i64;
+                    add(&mut result.secs, usecs / 1_000_000)?;
+                    add(&mut result.usecs, usecs % 1_000_000)?;
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        Ok(result)
+    }
+
+    // interval_recv - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/timestamp.c#L980
+    fn from_binary(raw: &[u8]) -> Result<Self, ProtocolError> {
+        let mut reader = BinaryReader::new(raw, "interval");
+        let usecs = i64::from_binary(reader.take(8)?)?;
+        let days = reader.read_i32()?;
+        let months = reader.read_i32()?;
+        reader.finish()?;
+
+        Ok(IntervalValue::new(
+            months,
+            days,
+            (usecs / 3_600_000_000) as i32,
+            (usecs % 3_600_000_000 / 60_000_000) as i32,
+            (usecs % 60_000_000 / 1_000_000) as i32,
+            (usecs % 1_000_000) as i32,
+        ))
+    }
+}
+
+/// OID of elements and raw values of elements
+type BinaryArray<'a> = (u32, Vec<Option<&'a [u8]>>);
+
+/// Binary array (array_send) split into elements.
+/// Only one-dimensional arrays are supported.
+fn decode_binary_array(raw: &[u8]) -> Result<BinaryArray<'_>, ProtocolError> {
+    let mut reader = BinaryReader::new(raw, "array");
+    let ndim = reader.read_i32()?;
+    // has_null flag is not trusted, NULLs are detected by the length of elements
+    reader.read_i32()?;
+    let element_oid = reader.read_u32()?;
+
+    let len = match ndim {
+        0 => 0,
+        1 => {
+            let len = reader.read_i32()?;
+            // Lower bound doesn't affect values
+            reader.read_i32()?;
+            len.max(0) as usize
+        }
+        _ => {
+            return Err(ErrorResponse::error(
+                ErrorCode::FeatureNotSupported,
+                format!(
+                    "Multidimensional arrays are not supported, actual: {}",
+                    ndim
                 ),
-                backtrace: Backtrace::capture(),
-            }),
+            )
+            .into())
+        }
+    };
+
+    // Length comes from the client, every element takes at least 4 bytes
+    let mut elements = Vec::with_capacity(len.min(reader.remaining() / 4));
+    for _ in 0..len {
+        elements.push(reader.read_value()?);
+    }
+    reader.finish()?;
+
+    Ok((element_oid, elements))
+}
+
+/// Text array (array_out) split into unquoted elements.
+/// Only one-dimensional arrays are supported.
+fn decode_text_array(raw: &str) -> Result<Vec<Option<String>>, ProtocolError> {
+    let invalid = || decoding_error(format!("Unable to decode array from text, actual: {}", raw));
+
+    let mut value = raw.trim();
+    // Optional dimensions decoration, e.g. [0:1]={1,2}
+    if value.starts_with('[') {
+        value = value.split_once('=').ok_or_else(invalid)?.1.trim_start();
+    }
+
+    let value = value
+        .strip_prefix('{')
+        .and_then(|value| value.strip_suffix('}'))
+        .ok_or_else(invalid)?;
+
+    let mut elements = Vec::new();
+    let mut chars = value.chars().peekable();
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+
+        match chars.peek() {
+            None if elements.is_empty() => break,
+            None => return Err(invalid()),
+            Some('{') => {
+                return Err(ErrorResponse::error(
+                    ErrorCode::FeatureNotSupported,
+                    "Multidimensional arrays are not supported".to_string(),
+                )
+                .into())
+            }
+            Some('"') => {
+                chars.next();
+                let mut element = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => element.push(chars.next().ok_or_else(invalid)?),
+                        Some(c) => element.push(c),
+                        None => return Err(invalid()),
+                    }
+                }
+                elements.push(Some(element));
+            }
+            Some(_) => {
+                let mut element = String::new();
+                while let Some(c) = chars.peek() {
+                    match c {
+                        ',' => break,
+                        '\\' => {
+                            chars.next();
+                            element.push(chars.next().ok_or_else(invalid)?);
+                        }
+                        c => {
+                            element.push(*c);
+                            chars.next();
+                        }
+                    }
+                }
+
+                let element = element.trim_end();
+                if element.is_empty() {
+                    return Err(invalid());
+                }
+
+                if element.eq_ignore_ascii_case("NULL") {
+                    elements.push(None);
+                } else {
+                    elements.push(Some(element.to_string()));
+                }
+            }
+        }
+
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+
+        match chars.next() {
+            None => break,
+            Some(',') => continue,
+            Some(_) => return Err(invalid()),
+        }
+    }
+
+    Ok(elements)
+}
+
+/// One-dimensional array, elements are decoded with the same format as the whole value
+impl<T: FromProtocolValue> FromProtocolValue for Vec<Option<T>> {
+    // array_in - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/arrayfuncs.c#L173
+    fn from_text(raw: &[u8]) -> Result<Self, ProtocolError> {
+        decode_text_array(text_value(raw)?)?
+            .into_iter()
+            .map(|element| match element {
+                Some(element) => T::from_text(element.as_bytes()).map(Some),
+                None => Ok(None),
+            })
+            .collect()
+    }
+
+    // array_recv - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/arrayfuncs.c#L1270
+    fn from_binary(raw: &[u8]) -> Result<Self, ProtocolError> {
+        let (_, elements) = decode_binary_array(raw)?;
+
+        elements
+            .into_iter()
+            .map(|element| match element {
+                Some(element) => T::from_binary(element).map(Some),
+                None => Ok(None),
+            })
+            .collect()
+    }
+}
+
+/// Quotes an element of array or record for the text representation, when it's required
+fn quote_text_element(value: &str, force: bool, delimiters: &[char]) -> String {
+    let needs_quotes = force
+        || value.is_empty()
+        || value.eq_ignore_ascii_case("NULL")
+        || value
+            .chars()
+            .any(|c| c == '"' || c == '\\' || c.is_whitespace() || delimiters.contains(&c));
+    if !needs_quotes {
+        return value.to_string();
+    }
+
+    let mut result = String::with_capacity(value.len() + 2);
+    result.push('"');
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            result.push('\\');
         }
+        result.push(c);
+    }
+    result.push('"');
+
+    result
+}
+
+/// Formats elements as one-dimensional array in the text representation (array_out)
+pub fn format_text_array<'a>(elements: impl IntoIterator<Item = Option<&'a str>>) -> String {
+    let elements = elements
+        .into_iter()
+        .map(|element| match element {
+            Some(element) => quote_text_element(element, false, &['{', '}', ',']),
+            None => "NULL".to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    format!("{{{}}}", elements.join(","))
+}
+
+fn format_pg_float(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() && value > 0_f64 {
+        "Infinity".to_string()
+    } else if value.is_infinite() {
+        "-Infinity".to_string()
+    } else {
+        value.to_string()
     }
 }
 
+fn range_subtype(tid: PgTypeId) -> Option<PgTypeId> {
+    match tid {
+        PgTypeId::INT4RANGE | PgTypeId::INT4MULTIRANGE => Some(PgTypeId::INT4),
+        PgTypeId::INT8RANGE | PgTypeId::INT8MULTIRANGE => Some(PgTypeId::INT8),
+        PgTypeId::NUMRANGE | PgTypeId::NUMMULTIRANGE => Some(PgTypeId::NUMERIC),
+        PgTypeId::TSRANGE | PgTypeId::TSMULTIRANGE => Some(PgTypeId::TIMESTAMP),
+        PgTypeId::TSTZRANGE => Some(PgTypeId::TIMESTAMPTZ),
+        PgTypeId::DATERANGE | PgTypeId::DATEMULTIRANGE => Some(PgTypeId::DATE),
+        _ => None,
+    }
+}
+
+// range_send - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/rangetypes.c#L247
+fn range_binary_to_text(
+    subtype: PgTypeId,
+    raw: &[u8],
+    depth: usize,
+) -> Result<String, ProtocolError> {
+    // RANGE_EMPTY, RANGE_LB_INC, RANGE_UB_INC, RANGE_LB_INF, RANGE_UB_INF
+    const EMPTY: u8 = 0x01;
+    const LB_INC: u8 = 0x02;
+    const UB_INC: u8 = 0x04;
+    const LB_INF: u8 = 0x08;
+    const UB_INF: u8 = 0x10;
+
+    let mut reader = BinaryReader::new(raw, "range");
+    let flags = reader.read_u8()?;
+    if flags & EMPTY != 0 {
+        reader.finish()?;
+        return Ok("empty".to_string());
+    }
+
+    let mut bound = |infinite: bool| -> Result<String, ProtocolError> {
+        if infinite {
+            return Ok(String::new());
+        }
+
+        match reader.read_value()? {
+            Some(value) => Ok(quote_text_element(
+                &binary_to_text_nested(subtype, value, depth + 1)?,
+                false,
+                &['(', ')', '[', ']', ','],
+            )),
+            None => Err(decoding_error(
+                "Unable to decode range from binary, bound is NULL".to_string(),
+            )),
+        }
+    };
+    let lower = bound(flags & LB_INF != 0)?;
+    let upper = bound(flags & UB_INF != 0)?;
+    reader.finish()?;
+
+    Ok(format!(
+        "{}{},{}{}",
+        if flags & LB_INC != 0 { '[' } else { '(' },
+        lower,
+        upper,
+        if flags & UB_INC != 0 { ']' } else { ')' },
+    ))
+}
+
+// record_send - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/rowtypes.c#L641
+fn record_binary_to_text(raw: &[u8], depth: usize) -> Result<String, ProtocolError> {
+    let mut reader = BinaryReader::new(raw, "record");
+    let columns = reader.read_i32()?;
+
+    // Every column takes at least 8 bytes (oid and length)
+    let mut values = Vec::with_capacity((columns.max(0) as usize).min(reader.remaining() / 8));
+    for _ in 0..columns {
+        let oid = reader.read_u32()?;
+        let value = reader.read_value()?;
+        values.push(match value {
+            Some(value) => {
+                let tid = PgTypeId::from_oid(oid).ok_or_else(|| {
+                    decoding_error(format!(
+                        "Unable to decode record from binary, unknown type: {}",
+                        oid
+                    ))
+                })?;
+
+                quote_text_element(
+                    &binary_to_text_nested(tid, value, depth + 1)?,
+                    false,
+                    &['(', ')', ','],
+                )
+            }
+            // NULL is represented as nothing between delimiters
+            None => String::new(),
+        });
+    }
+    reader.finish()?;
+
+    Ok(format!("({})", values.join(",")))
+}
+
+// inet_send - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/network.c#L277
+fn inet_binary_to_text(raw: &[u8]) -> Result<String, ProtocolError> {
+    // PGSQL_AF_INET, PGSQL_AF_INET6
+    const AF_INET: u8 = 2;
+    const AF_INET6: u8 = 3;
+
+    let mut reader = BinaryReader::new(raw, "inet");
+    let family = reader.read_u8()?;
+    let bits = reader.read_u8()?;
+    let is_cidr = reader.read_u8()? != 0;
+    let len = reader.read_u8()? as usize;
+    let address = reader.take(len)?;
+    reader.finish()?;
+
+    let (address, max_bits) = match family {
+        AF_INET => (
+            Ipv4Addr::from(fixed_value::<4>(address, "inet")?).to_string(),
+            32,
+        ),
+        AF_INET6 => (
+            Ipv6Addr::from(fixed_value::<16>(address, "inet")?).to_string(),
+            128,
+        ),
+        other => {
+            return Err(decoding_error(format!(
+                "Unable to decode inet from binary, invalid address family: {}",
+                other
+            )))
+        }
+    };
+
+    if is_cidr || bits != max_bits {
+        Ok(format!("{}/{}", address, bits))
+    } else {
+        Ok(address)
+    }
+}
+
+/// Max nesting of composite values (arrays, records, ranges) in the binary format
+const MAX_BINARY_NESTING_DEPTH: usize = 8;
+
+/// Converts value in the binary format to the text representation which Postgres uses for
+/// the same type. It allows to handle parameters of any type in the same way as text parameters.
+pub fn binary_to_text(tid: PgTypeId, raw: &[u8]) -> Result<String, ProtocolError> {
+    binary_to_text_nested(tid, raw, 0)
+}
+
+fn binary_to_text_nested(tid: PgTypeId, raw: &[u8], depth: usize) -> Result<String, ProtocolError> {
+    if depth > MAX_BINARY_NESTING_DEPTH {
+        return Err(decoding_error(format!(
+            "Unable to decode value from binary, nesting is deeper than {}",
+            MAX_BINARY_NESTING_DEPTH
+        )));
+    }
+
+    let ty = tid.to_type();
+
+    Ok(match tid {
+        PgTypeId::BOOL => (if bool::from_binary(raw)? { "t" } else { "f" }).to_string(),
+        PgTypeId::INT2 => i16::from_binary(raw)?.to_string(),
+        PgTypeId::INT4 => i32::from_binary(raw)?.to_string(),
+        PgTypeId::INT8 => i64::from_binary(raw)?.to_string(),
+        PgTypeId::OID => u32::from_binary(raw)?.to_string(),
+        PgTypeId::FLOAT4 => format_pg_float(f32::from_binary(raw)? as f64),
+        PgTypeId::FLOAT8 => format_pg_float(f64::from_binary(raw)?),
+        PgTypeId::NUMERIC => NumericValue::from_binary(raw)?.to_string(),
+        // cash_send, lc_monetary is always C, which uses 2 fractional digits
+        PgTypeId::MONEY => {
+            let cents = i64::from_binary(raw)?;
+            format!(
+                "{}{}.{:02}",
+                if cents < 0 { "-" } else { "" },
+                (cents / 100).abs(),
+                (cents % 100).abs()
+            )
+        }
+        PgTypeId::UNSPECIFIED
+        | PgTypeId::TEXT
+        | PgTypeId::NAME
+        | PgTypeId::BPCHAR
+        | PgTypeId::VARCHAR
+        | PgTypeId::CHARACTERDATA
+        | PgTypeId::SQLIDENTIFIER
+        | PgTypeId::ANYENUM => String::from_binary(raw)?,
+        PgTypeId::BYTEA => {
+            let mut result = String::with_capacity(2 + raw.len() * 2);
+            result.push_str("\\x");
+            for byte in raw {
+                result.push_str(&format!("{:02x}", byte));
+            }
+
+            result
+        }
+        PgTypeId::INTERVAL => IntervalValue::from_binary(raw)?.to_string(),
+        #[cfg(feature = "with-chrono")]
+        PgTypeId::DATE => NaiveDate::from_binary(raw)?.to_string(),
+        #[cfg(feature = "with-chrono")]
+        PgTypeId::TIME => format_pg_time(&NaiveTime::from_binary(raw)?),
+        // timetz_send - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/date.c#L2210
+        #[cfg(feature = "with-chrono")]
+        PgTypeId::TIMETZ => {
+            let mut reader = BinaryReader::new(raw, "timetz");
+            let time = NaiveTime::from_binary(reader.take(8)?)?;
+            // Offset is stored in seconds west of UTC
+            let offset = reader.read_i32()?.checked_neg().ok_or_else(|| {
+                decoding_error("Unable to decode timetz from binary, invalid offset".to_string())
+            })?;
+            reader.finish()?;
+
+            let mut result = format!(
+                "{}{}{:02}",
+                format_pg_time(&time),
+                if offset < 0 { '-' } else { '+' },
+                offset.abs() / 3600
+            );
+            if offset.abs() % 3600 != 0 {
+                result.push_str(&format!(":{:02}", offset.abs() % 3600 / 60));
+            }
+
+            result
+        }
+        #[cfg(feature = "with-chrono")]
+        PgTypeId::TIMESTAMP => {
+            let value = NaiveDateTime::from_binary(raw)?;
+            format!("{} {}", value.date(), format_pg_time(&value))
+        }
+        #[cfg(feature = "with-chrono")]
+        PgTypeId::TIMESTAMPTZ => {
+            let value = NaiveDateTime::from_binary(raw)?;
+            format!("{} {}+00", value.date(), format_pg_time(&value))
+        }
+        PgTypeId::INET => inet_binary_to_text(raw)?,
+        // tidsend
+        PgTypeId::TID => {
+            let mut reader = BinaryReader::new(raw, "tid");
+            let block = reader.read_u32()?;
+            let offset = reader.read_u16()?;
+            reader.finish()?;
+
+            format!("({},{})", block, offset)
+        }
+        // pg_lsn_send
+        PgTypeId::PGLSN => {
+            let lsn = u64::from_be_bytes(fixed_value(raw, "pg_lsn")?);
+            format!("{:X}/{:X}", lsn >> 32, lsn & 0xFFFFFFFF)
+        }
+        PgTypeId::RECORD | PgTypeId::PGCLASS | PgTypeId::PGNAMESPACE | PgTypeId::PGCONSTRAINT => {
+            record_binary_to_text(raw, depth)?
+        }
+        PgTypeId::INT4MULTIRANGE
+        | PgTypeId::INT8MULTIRANGE
+        | PgTypeId::NUMMULTIRANGE
+        | PgTypeId::TSMULTIRANGE
+        | PgTypeId::DATEMULTIRANGE => {
+            // multirange_send - i32 count and length-prefixed ranges
+            let subtype = range_subtype(tid).unwrap();
+            let mut reader = BinaryReader::new(raw, "multirange");
+            let count = reader.read_i32()?;
+            // Every range takes at least 5 bytes (length and flags)
+            let mut ranges =
+                Vec::with_capacity((count.max(0) as usize).min(reader.remaining() / 5));
+            for _ in 0..count {
+                let len = reader.read_i32()?.max(0) as usize;
+                ranges.push(range_binary_to_text(subtype, reader.take(len)?, depth)?);
+            }
+            reader.finish()?;
+
+            format!("{{{}}}", ranges.join(","))
+        }
+        _ if range_subtype(tid).is_some() => {
+            range_binary_to_text(range_subtype(tid).unwrap(), raw, depth)?
+        }
+        // Arrays of any type (including anyarray), the element type is taken from the value itself
+        _ if ty.typcategory == "A" || matches!(tid, PgTypeId::ANYARRAY) => {
+            let (element_oid, elements) = decode_binary_array(raw)?;
+            let element_tid = PgTypeId::from_oid(element_oid)
+                .or_else(|| PgTypeId::from_oid(ty.typelem))
+                .ok_or_else(|| {
+                    decoding_error(format!(
+                        "Unable to decode array from binary, unknown element type: {}",
+                        element_oid
+                    ))
+                })?;
+
+            let elements = elements
+                .into_iter()
+                .map(|element| {
+                    element
+                        .map(|e| binary_to_text_nested(element_tid, e, depth + 1))
+                        .transpose()
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            format_text_array(elements.iter().map(|e| e.as_deref()))
+        }
+        _ => {
+            return Err(ErrorResponse::error(
+                ErrorCode::FeatureNotSupported,
+                format!(
+                    r#"Type "{}" is not supported for decoding from binary format"#,
+                    ty.typname
+                ),
+            )
+            .into())
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use crate::*;
 
     use crate::protocol::Format;
     use bytes::BytesMut;
+    #[cfg(feature = "with-chrono")]
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
 
     fn assert_test_decode<T: ToProtocolValue + FromProtocolValue + std::cmp::PartialEq>(
         value: T,
@@ -125,6 +1145,34 @@ mod tests {
         Ok(())
     }
 
+    /// Decodes reference bytes (captured from PostgreSQL) and encodes the value back
+    fn assert_binary_fixture<T: ToProtocolValue + FromProtocolValue + std::cmp::PartialEq>(
+        fixture: &str,
+        expected: T,
+    ) -> Result<(), ProtocolError> {
+        let raw = hex::decode(fixture).unwrap();
+        assert_eq!(T::from_binary(&raw)?, expected);
+
+        let mut buf = BytesMut::new();
+        expected.to_binary(&mut buf)?;
+        assert_eq!(hex::encode(&buf.as_ref()[4..]), fixture);
+
+        Ok(())
+    }
+
+    fn assert_binary_to_text(
+        tid: PgTypeId,
+        fixture: &str,
+        expected: &str,
+    ) -> Result<(), ProtocolError> {
+        assert_eq!(
+            binary_to_text(tid, &hex::decode(fixture).unwrap())?,
+            expected.to_string()
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_text_decoders() -> Result<(), ProtocolError> {
         assert_test_decode("test".to_string(), Format::Text)?;
@@ -132,6 +1180,100 @@ mod tests {
         assert_test_decode(false, Format::Text)?;
         assert_test_decode(1_i64, Format::Text)?;
         assert_test_decode(100_i64, Format::Text)?;
+        assert_test_decode(-5_i16, Format::Text)?;
+        assert_test_decode(123_i32, Format::Text)?;
+        assert_test_decode(1.5_f32, Format::Text)?;
+        assert_test_decode(-2.25_f64, Format::Text)?;
+        assert_test_decode(vec![0_u8, 1, 254, 255], Format::Text)?;
+        assert_test_decode(NumericValue::new("-12.340".to_string()), Format::Text)?;
+        assert_test_decode(IntervalValue::new(14, 3, 4, 5, 6, 7), Format::Text)?;
+        assert_test_decode(IntervalValue::new(0, 0, 0, 0, -1, -23), Format::Text)?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "with-chrono")]
+    #[test]
+    fn test_text_decoders_chrono() -> Result<(), ProtocolError> {
+        let timestamp = NaiveDate::from_ymd_opt(2022, 1, 1)
+            .unwrap()
+            .and_hms_micro_opt(12, 34, 56, 789000)
+            .unwrap();
+
+        assert_test_decode(timestamp.date(), Format::Text)?;
+        assert_test_decode(timestamp.time(), Format::Text)?;
+        assert_test_decode(timestamp, Format::Text)?;
+        assert_test_decode(Utc.from_utc_datetime(&timestamp), Format::Text)?;
+
+        assert_eq!(
+            DateTime::<Utc>::from_text(b"2022-01-01 15:34:56.789+03")?,
+            Utc.from_utc_datetime(&timestamp)
+        );
+        assert_eq!(
+            NaiveDateTime::from_text(b"2022-01-01T12:34:56.789")?,
+            timestamp
+        );
+        assert_eq!(
+            NaiveTime::from_text(b"12:34")?,
+            NaiveTime::from_hms_opt(12, 34, 0).unwrap()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_text_decoders_postgres_input() -> Result<(), ProtocolError> {
+        assert!(bool::from_text(b"yes")?);
+        assert!(!bool::from_text(b"OFF")?);
+        assert!(bool::from_text(b"").is_err());
+        assert!(f64::from_text(b"-Infinity")?.is_infinite());
+        assert!(f32::from_text(b"NaN")?.is_nan());
+        assert_eq!(Vec::<u8>::from_text(b"a\\\\b\\001")?, vec![97, 92, 98, 1]);
+        assert_eq!(
+            NumericValue::from_text(b"nan")?,
+            NumericValue::new("NaN".to_string())
+        );
+        assert!(NumericValue::from_text(b"1.2.3").is_err());
+        assert!(NumericValue::from_text(b"--1").is_err());
+        assert_eq!(
+            IntervalValue::from_text(b"1 year 2 mons 3 days 04:05:06.5")?,
+            IntervalValue::new(14, 3, 4, 5, 6, 500000)
+        );
+        assert_eq!(
+            IntervalValue::from_text(b"-01:02:03")?,
+            IntervalValue::new(0, 0, -1, -2, -3, 0)
+        );
+        assert!(IntervalValue::from_text(b"1 fortnight").is_err());
+        assert!(IntervalValue::from_text(b"1000000000 years").is_err());
+        assert!(IntervalValue::from_text(b"2147483647 days 1 day").is_err());
+        assert!(IntervalValue::from_text(b"99999999999:00").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_text_array_decoders() -> Result<(), ProtocolError> {
+        assert_eq!(
+            Vec::<Option<i32>>::from_text(b"{1,NULL, 3}")?,
+            vec![Some(1), None, Some(3)]
+        );
+        assert_eq!(
+            Vec::<Option<String>>::from_text(br#"{"a b","c\"d",NULL,"NULL",e\,f}"#)?,
+            vec![
+                Some("a b".to_string()),
+                Some("c\"d".to_string()),
+                None,
+                Some("NULL".to_string()),
+                Some("e,f".to_string())
+            ]
+        );
+        assert_eq!(Vec::<Option<i64>>::from_text(b"{}")?, vec![]);
+        assert_eq!(
+            Vec::<Option<i64>>::from_text(b"[0:1]={1,2}")?,
+            vec![Some(1), Some(2)]
+        );
+        assert!(Vec::<Option<i64>>::from_text(b"{1,}").is_err());
+        assert!(Vec::<Option<i64>>::from_text(b"{{1},{2}}").is_err());
 
         Ok(())
     }
@@ -143,6 +1285,258 @@ mod tests {
         assert_test_decode(false, Format::Binary)?;
         assert_test_decode(1_i64, Format::Binary)?;
         assert_test_decode(100_i64, Format::Binary)?;
+        assert_test_decode(-5_i16, Format::Binary)?;
+        assert_test_decode(123_i32, Format::Binary)?;
+        assert_test_decode(1.5_f32, Format::Binary)?;
+        assert_test_decode(-2.25_f64, Format::Binary)?;
+        assert_test_decode(vec![0_u8, 1, 254, 255], Format::Binary)?;
+        assert_test_decode(NumericValue::new("0".to_string()), Format::Binary)?;
+        assert_test_decode(NumericValue::new("10000".to_string()), Format::Binary)?;
+        assert_test_decode(
+            NumericValue::new("-123456789.000000001".to_string()),
+            Format::Binary,
+        )?;
+        assert_test_decode(IntervalValue::new(14, 3, 4, 5, 6, 7), Format::Binary)?;
+
+        // Wrong length must not panic
+        assert!(i64::from_binary(&[0, 1]).is_err());
+        assert!(bool::from_binary(&[]).is_err());
+        assert!(NumericValue::from_binary(&[0, 1, 0]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_fixtures() -> Result<(), ProtocolError> {
+        // SELECT 12.340::numeric
+        assert_binary_fixture(
+            "0002000000000003000c0d48",
+            NumericValue::new("12.340".to_string()),
+        )?;
+        // SELECT -0.00001::numeric
+        assert_binary_fixture(
+            "0001fffe4000000503e8",
+            NumericValue::new("-0.00001".to_string()),
+        )?;
+        // SELECT 'NaN'::numeric
+        assert_binary_fixture("00000000c0000000", NumericValue::new("NaN".to_string()))?;
+        // SELECT '1 year 2 mons 3 days 04:05:06.000007'::interval
+        assert_binary_fixture(
+            "000000036c8bc087000000030000000e",
+            IntervalValue::new(14, 3, 4, 5, 6, 7),
+        )?;
+        // SELECT '\x01ff'::bytea
+        assert_binary_fixture("01ff", vec![1_u8, 255])?;
+        // SELECT 4294967295::oid
+        assert_binary_fixture("ffffffff", u32::MAX)?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "with-chrono")]
+    #[test]
+    fn test_binary_fixtures_chrono() -> Result<(), ProtocolError> {
+        let timestamp = NaiveDate::from_ymd_opt(2022, 1, 1)
+            .unwrap()
+            .and_hms_micro_opt(12, 34, 56, 789000)
+            .unwrap();
+
+        // SELECT '2022-01-01'::date
+        assert_binary_fixture("00001f64", timestamp.date())?;
+        // SELECT '12:34:56.5'::time
+        assert_binary_fixture(
+            "0000000a8be1bd20",
+            NaiveTime::from_hms_micro_opt(12, 34, 56, 500000).unwrap(),
+        )?;
+        // SELECT '2022-01-01 12:34:56.789'::timestamp
+        assert_binary_fixture("0002778348a7a608", timestamp)?;
+        // SELECT '2022-01-01 12:34:56.789+00'::timestamptz
+        assert_binary_fixture("0002778348a7a608", Utc.from_utc_datetime(&timestamp))?;
+
+        // Negative and too large time values
+        assert!(NaiveTime::from_binary(&hex::decode("ffffffffffffffff").unwrap()).is_err());
+        assert!(NaiveTime::from_binary(&hex::decode("8000000000000000").unwrap()).is_err());
+        assert!(NaiveTime::from_binary(&hex::decode("000000141dd76000").unwrap()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_array_decoders() -> Result<(), ProtocolError> {
+        // SELECT ARRAY[1, NULL, 3]::int4[]
+        let int4_array = hex::decode(concat!(
+            "00000001",
+            "00000001",
+            "00000017",
+            "00000003",
+            "00000001", // header
+            "0000000400000001",
+            "ffffffff",
+            "0000000400000003", // elements
+        ))
+        .unwrap();
+        assert_eq!(
+            Vec::<Option<i32>>::from_binary(&int4_array)?,
+            vec![Some(1), None, Some(3)]
+        );
+        // Truncated value
+        assert!(Vec::<Option<i32>>::from_binary(&int4_array[0..30]).is_err());
+
+        // SELECT '{}'::int8[]
+        let empty_array = hex::decode("000000000000000000000014").unwrap();
+        assert_eq!(Vec::<Option<i64>>::from_binary(&empty_array)?, vec![]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_malicious_values() -> Result<(), ProtocolError> {
+        // Counts close to i32::MAX must not be trusted for allocations
+        let huge_array = hex::decode("00000001000000000000001700007fff00000001").unwrap();
+        assert!(Vec::<Option<i32>>::from_binary(&huge_array).is_err());
+        assert!(binary_to_text(PgTypeId::ARRAYINT4, &huge_array).is_err());
+        assert!(binary_to_text(PgTypeId::RECORD, &hex::decode("7fffffff").unwrap()).is_err());
+        assert!(
+            binary_to_text(PgTypeId::INT4MULTIRANGE, &hex::decode("7fffffff").unwrap()).is_err()
+        );
+
+        // Nested records and arrays are limited by depth
+        fn nested_record(depth: usize) -> Vec<u8> {
+            let mut value = hex::decode("00000000").unwrap();
+            for _ in 0..depth {
+                let mut record = hex::decode("00000001000008c9").unwrap();
+                record.extend_from_slice(&(value.len() as i32).to_be_bytes());
+                record.extend_from_slice(&value);
+                value = record;
+            }
+
+            value
+        }
+        assert_eq!(
+            binary_to_text(PgTypeId::RECORD, &nested_record(2))?,
+            r#"("(\"()\")")"#
+        );
+        assert!(binary_to_text(PgTypeId::RECORD, &nested_record(100)).is_err());
+
+        fn nested_array(depth: usize) -> Vec<u8> {
+            // SELECT '{}'::int4[]
+            let mut value = hex::decode("000000000000000000000017").unwrap();
+            for _ in 0..depth {
+                // Array of int4[]
+                let mut array = hex::decode("0000000100000000000003ef0000000100000001").unwrap();
+                array.extend_from_slice(&(value.len() as i32).to_be_bytes());
+                array.extend_from_slice(&value);
+                value = array;
+            }
+
+            value
+        }
+        assert!(binary_to_text(PgTypeId::ARRAYINT4, &nested_array(1)).is_ok());
+        assert!(binary_to_text(PgTypeId::ARRAYINT4, &nested_array(100)).is_err());
+
+        // Out of range values are rejected without overflows
+        assert!(binary_to_text(
+            PgTypeId::TIMETZ,
+            &hex::decode("000000000000000080000000").unwrap()
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_to_text() -> Result<(), ProtocolError> {
+        assert_binary_to_text(PgTypeId::BOOL, "01", "t")?;
+        assert_binary_to_text(PgTypeId::INT2, "fffb", "-5")?;
+        assert_binary_to_text(PgTypeId::INT4, "0000007b", "123")?;
+        assert_binary_to_text(PgTypeId::FLOAT8, "7ff0000000000000", "Infinity")?;
+        assert_binary_to_text(PgTypeId::NUMERIC, "0002000000000003000c0d48", "12.340")?;
+        assert_binary_to_text(PgTypeId::MONEY, "fffffffffffffb2e", "-12.34")?;
+        assert_binary_to_text(PgTypeId::VARCHAR, "74657374", "test")?;
+        assert_binary_to_text(PgTypeId::BYTEA, "01ff", "\\x01ff")?;
+        assert_binary_to_text(
+            PgTypeId::INTERVAL,
+            "000000036c8bc087000000030000000e",
+            "1 years 2 mons 3 days 4 hours 5 mins 6.000007 secs",
+        )?;
+        // SELECT '192.168.0.1/24'::inet, '::1'::inet
+        assert_binary_to_text(PgTypeId::INET, "02180004c0a80001", "192.168.0.1/24")?;
+        assert_binary_to_text(
+            PgTypeId::INET,
+            "0380001000000000000000000000000000000001",
+            "::1",
+        )?;
+        assert_binary_to_text(PgTypeId::TID, "0000000a0003", "(10,3)")?;
+        assert_binary_to_text(PgTypeId::PGLSN, "000000160000ab12", "16/AB12")?;
+        // SELECT int4range(1, 10), 'empty'::int4range, '[1,)'::int8range
+        assert_binary_to_text(
+            PgTypeId::INT4RANGE,
+            "020000000400000001000000040000000a",
+            "[1,10)",
+        )?;
+        assert_binary_to_text(PgTypeId::INT4RANGE, "01", "empty")?;
+        assert_binary_to_text(PgTypeId::INT8RANGE, "12000000080000000000000001", "[1,)")?;
+        // SELECT '{[1,10)}'::int4multirange
+        assert_binary_to_text(
+            PgTypeId::INT4MULTIRANGE,
+            "0000000100000011020000000400000001000000040000000a",
+            "{[1,10)}",
+        )?;
+        // SELECT ROW(1, 'a b')
+        assert_binary_to_text(
+            PgTypeId::RECORD,
+            "000000020000001700000004000000010000001900000003612062",
+            "(1,\"a b\")",
+        )?;
+        // SELECT ARRAY[1, NULL, 3]::int4[], ARRAY['a b', 'c', NULL, '']::text[]
+        assert_binary_to_text(
+            PgTypeId::ARRAYINT4,
+            concat!(
+                "000000010000000100000017000000030000000100000004",
+                "00000001ffffffff0000000400000003"
+            ),
+            "{1,NULL,3}",
+        )?;
+        assert_binary_to_text(
+            PgTypeId::ARRAYTEXT,
+            concat!(
+                "000000010000000100000019000000040000000100000003",
+                "6120620000000163ffffffff00000000"
+            ),
+            r#"{"a b",c,NULL,""}"#,
+        )?;
+        assert!(binary_to_text(PgTypeId::ANYELEMENT, &[0]).is_err());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "with-chrono")]
+    #[test]
+    fn test_binary_to_text_chrono() -> Result<(), ProtocolError> {
+        assert_binary_to_text(PgTypeId::DATE, "00001f64", "2022-01-01")?;
+        assert_binary_to_text(PgTypeId::TIME, "0000000a8be1bd20", "12:34:56.5")?;
+        assert_binary_to_text(
+            PgTypeId::TIMESTAMP,
+            "0002778348a7a608",
+            "2022-01-01 12:34:56.789",
+        )?;
+        assert_binary_to_text(
+            PgTypeId::TIMESTAMPTZ,
+            "0002778348a7a608",
+            "2022-01-01 12:34:56.789+00",
+        )?;
+        // SELECT '12:34:56.5+03'::timetz
+        assert_binary_to_text(
+            PgTypeId::TIMETZ,
+            "0000000a8be1bd20ffffd5d0",
+            "12:34:56.5+03",
+        )?;
+        // SELECT ARRAY['2022-01-01'::date]
+        assert_binary_to_text(
+            PgTypeId::ARRAYDATE,
+            "00000001000000000000043a00000001000000010000000400001f64",
+            "{2022-01-01}",
+        )?;
 
         Ok(())
     }
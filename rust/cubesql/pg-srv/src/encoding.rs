@@ -3,7 +3,7 @@
 use crate::{protocol::Format, ProtocolError};
 use bytes::{BufMut, BytesMut};
 #[cfg(feature = "with-chrono")]
-use chrono::{NaiveDate, NaiveDateTime};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
 use std::{
     fmt::{Display, Formatter},
     io::{Error, ErrorKind},
@@ -110,9 +110,44 @@ impl_primitive!(i64);
 impl_primitive!(f32);
 impl_primitive!(f64);
 
+/// Postgres doesn't have unsigned types, it's used for OID
+impl ToProtocolValue for u32 {
+    fn to_text(&self, buf: &mut BytesMut) -> Result<(), ProtocolError> {
+        self.to_string().to_text(buf)
+    }
+
+    fn to_binary(&self, buf: &mut BytesMut) -> Result<(), ProtocolError> {
+        buf.put_i32(4);
+        buf.put_u32(*self);
+
+        Ok(())
+    }
+}
+
+impl ToProtocolValue for Vec<u8> {
+    // byteaout - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/varlena.c#L372
+    fn to_text(&self, buf: &mut BytesMut) -> Result<(), ProtocolError> {
+        let mut hex = String::with_capacity(2 + self.len() * 2);
+        hex.push_str("\\x");
+        for byte in self {
+            hex.push_str(&format!("{:02x}", byte));
+        }
+
+        hex.to_text(buf)
+    }
+
+    // bytearecv - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/varlena.c#L458
+    fn to_binary(&self, buf: &mut BytesMut) -> Result<(), ProtocolError> {
+        buf.put_i32(self.len() as i32);
+        buf.extend_from_slice(self);
+
+        Ok(())
+    }
+}
+
 // POSTGRES_EPOCH_JDATE
 #[cfg(feature = "with-chrono")]
-fn pg_base_date_epoch() -> NaiveDateTime {
+pub(crate) fn pg_base_date_epoch() -> NaiveDateTime {
     NaiveDate::from_ymd_opt(2000, 1, 1)
         .unwrap()
         .and_hms_opt(0, 0, 0)
@@ -149,7 +184,189 @@ impl ToProtocolValue for NaiveDate {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+/// Seconds with fractional part as Postgres prints them, trailing zeros of microseconds are omitted
+#[cfg(feature = "with-chrono")]
+pub(crate) fn format_pg_time<T: Timelike>(value: &T) -> String {
+    let mut res = format!(
+        "{:02}:{:02}:{:02}",
+        value.hour(),
+        value.minute(),
+        value.second()
+    );
+
+    let usecs = value.nanosecond() / 1_000;
+    if usecs != 0 {
+        res.push_str(format!(".{:06}", usecs).trim_end_matches('0'));
+    }
+
+    res
+}
+
+#[cfg(feature = "with-chrono")]
+impl ToProtocolValue for NaiveTime {
+    // time_out - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/date.c#L1393
+    fn to_text(&self, buf: &mut BytesMut) -> Result<(), ProtocolError> {
+        format_pg_time(self).to_text(buf)
+    }
+
+    // time_send - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/date.c#L1447
+    fn to_binary(&self, buf: &mut BytesMut) -> Result<(), ProtocolError> {
+        let usecs = self.num_seconds_from_midnight() as i64 * 1_000_000
+            + (self.nanosecond() / 1_000) as i64;
+
+        buf.put_i32(8);
+        buf.put_i64(usecs);
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "with-chrono")]
+impl ToProtocolValue for NaiveDateTime {
+    // timestamp_out - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/timestamp.c#L232
+    fn to_text(&self, buf: &mut BytesMut) -> Result<(), ProtocolError> {
+        format!("{} {}", self.date(), format_pg_time(self)).to_text(buf)
+    }
+
+    // timestamp_send - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/timestamp.c#L279
+    fn to_binary(&self, buf: &mut BytesMut) -> Result<(), ProtocolError> {
+        let usecs = self
+            .signed_duration_since(pg_base_date_epoch())
+            .num_microseconds()
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::Other,
+                    format!(
+                        "value too large to store in the binary format (i64), actual: {}",
+                        self
+                    ),
+                )
+            })?;
+
+        buf.put_i32(8);
+        buf.put_i64(usecs);
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "with-chrono")]
+impl ToProtocolValue for DateTime<Utc> {
+    // timestamptz_out - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/timestamp.c#L774
+    fn to_text(&self, buf: &mut BytesMut) -> Result<(), ProtocolError> {
+        let value = self.naive_utc();
+
+        format!("{} {}+00", value.date(), format_pg_time(&value)).to_text(buf)
+    }
+
+    // timestamptz_send - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/timestamp.c#L820
+    fn to_binary(&self, buf: &mut BytesMut) -> Result<(), ProtocolError> {
+        self.naive_utc().to_binary(buf)
+    }
+}
+
+/// Arbitrary precision NUMERIC, the value is kept in the text form, e.g. `-12.340`, `NaN` or `Infinity`
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumericValue {
+    value: String,
+}
+
+impl NumericValue {
+    // NUMERIC_POS, NUMERIC_NEG, NUMERIC_NAN, NUMERIC_PINF, NUMERIC_NINF
+    pub(crate) const SIGN_POS: u16 = 0x0000;
+    pub(crate) const SIGN_NEG: u16 = 0x4000;
+    pub(crate) const SIGN_NAN: u16 = 0xC000;
+    pub(crate) const SIGN_PINF: u16 = 0xD000;
+    pub(crate) const SIGN_NINF: u16 = 0xF000;
+
+    /// Value must be validated, use `FromProtocolValue::from_text` to parse untrusted input
+    pub fn new(value: String) -> Self {
+        Self { value }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+
+    /// Splits the value into base-10000 digits, as it's stored by Postgres.
+    /// Returns digits, weight of the first digit, sign and display scale.
+    fn to_base_10000(&self) -> (Vec<i16>, i16, u16, u16) {
+        let (sign, value) = match self.value.as_str() {
+            "NaN" => return (vec![], 0, Self::SIGN_NAN, 0),
+            "Infinity" | "+Infinity" => return (vec![], 0, Self::SIGN_PINF, 0),
+            "-Infinity" => return (vec![], 0, Self::SIGN_NINF, 0),
+            value => match value.strip_prefix('-') {
+                Some(value) => (Self::SIGN_NEG, value),
+                None => (Self::SIGN_POS, value.trim_start_matches('+')),
+            },
+        };
+
+        let (int_part, frac_part) = value.split_once('.').unwrap_or((value, ""));
+        let int_part = int_part.trim_start_matches('0');
+
+        // Groups of 4 decimal digits are aligned by the decimal point
+        let int_padding = (4 - int_part.len() % 4) % 4;
+        let frac_padding = (4 - frac_part.len() % 4) % 4;
+        let decimal_digits = "0"
+            .repeat(int_padding)
+            .chars()
+            .chain(int_part.chars())
+            .chain(frac_part.chars())
+            .chain("0".repeat(frac_padding).chars())
+            .map(|c| c.to_digit(10).unwrap_or(0) as i16)
+            .collect::<Vec<_>>();
+
+        let mut digits = decimal_digits
+            .chunks(4)
+            .map(|chunk| chunk.iter().fold(0_i16, |acc, d| acc * 10 + d))
+            .collect::<Vec<_>>();
+        let mut weight = ((int_padding + int_part.len()) / 4) as i16 - 1;
+
+        let leading_zeros = digits.iter().take_while(|d| **d == 0).count();
+        digits.drain(..leading_zeros);
+        weight -= leading_zeros as i16;
+
+        let trailing_zeros = digits.iter().rev().take_while(|d| **d == 0).count();
+        digits.truncate(digits.len() - trailing_zeros);
+
+        if digits.is_empty() {
+            return (digits, 0, Self::SIGN_POS, frac_part.len() as u16);
+        }
+
+        (digits, weight, sign, frac_part.len() as u16)
+    }
+}
+
+impl Display for NumericValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.value)
+    }
+}
+
+impl ToProtocolValue for NumericValue {
+    // numeric_out - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/numeric.c#L743
+    fn to_text(&self, buf: &mut BytesMut) -> Result<(), ProtocolError> {
+        self.value.to_text(buf)
+    }
+
+    // numeric_send - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/numeric.c#L1113
+    fn to_binary(&self, buf: &mut BytesMut) -> Result<(), ProtocolError> {
+        let (digits, weight, sign, dscale) = self.to_base_10000();
+
+        buf.put_i32(8 + 2 * digits.len() as i32);
+        buf.put_i16(digits.len() as i16);
+        buf.put_i16(weight);
+        buf.put_u16(sign);
+        buf.put_u16(dscale);
+        for digit in digits {
+            buf.put_i16(digit);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct IntervalValue {
     pub months: i32,
     pub days: i32,
@@ -293,6 +510,8 @@ impl ToProtocolValue for IntervalValue {
 mod tests {
     use crate::*;
     use bytes::BytesMut;
+    #[cfg(feature = "with-chrono")]
+    use chrono::TimeZone;
 
     fn assert_text_encode<T: ToProtocolValue>(value: T, expected: &[u8]) {
         let mut buf = BytesMut::new();
@@ -306,6 +525,11 @@ mod tests {
         assert_text_encode(true, &[0, 0, 0, 1, 116]);
         assert_text_encode(false, &[0, 0, 0, 1, 102]);
         assert_text_encode("str".to_string(), &[0, 0, 0, 3, 115, 116, 114]);
+        assert_text_encode(vec![1_u8, 255], b"\0\0\0\x06\\x01ff");
+        assert_text_encode(
+            NumericValue::new("-1.50".to_string()),
+            &[0, 0, 0, 5, 45, 49, 46, 53, 48],
+        );
 
         Ok(())
     }
@@ -321,6 +545,30 @@ mod tests {
     fn test_binary_encoders() -> Result<(), ProtocolError> {
         assert_bind_encode(true, &[0, 0, 0, 1, 1]);
         assert_bind_encode(false, &[0, 0, 0, 1, 0]);
+        assert_bind_encode(vec![1_u8, 255], &[0, 0, 0, 2, 1, 255]);
+        // SELECT -1.50::numeric
+        assert_bind_encode(
+            NumericValue::new("-1.50".to_string()),
+            &[0, 0, 0, 12, 0, 2, 0, 0, 64, 0, 0, 2, 0, 1, 19, 136],
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "with-chrono")]
+    #[test]
+    fn test_chrono_text_encoders() -> Result<(), ProtocolError> {
+        let timestamp = chrono::NaiveDate::from_ymd_opt(2022, 1, 1)
+            .unwrap()
+            .and_hms_micro_opt(12, 34, 56, 500000)
+            .unwrap();
+
+        assert_text_encode(timestamp.time(), b"\0\0\0\x0a12:34:56.5");
+        assert_text_encode(timestamp, b"\0\0\0\x152022-01-01 12:34:56.5");
+        assert_text_encode(
+            chrono::Utc.from_utc_datetime(&timestamp),
+            b"\0\0\0\x182022-01-01 12:34:56.5+00",
+        );
 
         Ok(())
     }
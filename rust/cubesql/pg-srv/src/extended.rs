@@ -5,6 +5,8 @@ pub enum BindValue {
     String(String),
     Int64(i64),
     Float64(f64),
+    /// NUMERIC in the text representation, to keep the precision
+    Numeric(String),
     Bool(bool),
     Null,
 }
@@ -16,7 +16,10 @@ use async_trait::async_trait;
 use bytes::BufMut;
 use tokio::io::AsyncReadExt;
 
-use crate::{buffer, BindValue, FromProtocolValue, PgType, PgTypeId, ProtocolError};
+use crate::{
+    binary_to_text, buffer, BindValue, FromProtocolValue, NumericValue, PgType, PgTypeId,
+    ProtocolError,
+};
 
 const DEFAULT_CAPACITY: usize = 64;
 
@@ -220,6 +223,30 @@ impl ErrorResponse {
         }
     }
 
+    pub fn statement_timeout() -> Self {
+        Self {
+            severity: ErrorSeverity::Error,
+            code: ErrorCode::QueryCanceled,
+            message: "canceling statement due to statement timeout".to_string(),
+        }
+    }
+
+    pub fn idle_in_transaction_session_timeout() -> Self {
+        Self {
+            severity: ErrorSeverity::Fatal,
+            code: ErrorCode::IdleInTransactionSessionTimeout,
+            message: "terminating connection due to idle-in-transaction timeout".to_string(),
+        }
+    }
+
+    pub fn idle_session_timeout() -> Self {
+        Self {
+            severity: ErrorSeverity::Fatal,
+            code: ErrorCode::IdleSessionTimeout,
+            message: "terminating connection due to idle-session timeout".to_string(),
+        }
+    }
+
     pub fn admin_shutdown() -> Self {
         Self {
             severity: ErrorSeverity::Fatal,
@@ -381,6 +408,37 @@ impl Serialize for ParameterStatus {
     }
 }
 
+/// (B) Asynchronous notification for the channel which was subscribed by LISTEN command.
+#[derive(Debug, PartialEq, Clone)]
+pub struct NotificationResponse {
+    /// The process ID of the notifying backend process.
+    process_id: u32,
+    channel: String,
+    payload: String,
+}
+
+impl NotificationResponse {
+    pub fn new(process_id: u32, channel: String, payload: String) -> Self {
+        Self {
+            process_id,
+            channel,
+            payload,
+        }
+    }
+}
+
+impl Serialize for NotificationResponse {
+    const CODE: u8 = b'A';
+
+    fn serialize(&self) -> Option<Vec<u8>> {
+        let mut buffer = Vec::with_capacity(DEFAULT_CAPACITY);
+        buffer.put_u32(self.process_id);
+        buffer::write_string(&mut buffer, &self.channel);
+        buffer::write_string(&mut buffer, &self.payload);
+        Some(buffer)
+    }
+}
+
 /// (B) Success reply for Bind command.
 pub struct BindComplete {}
 
@@ -774,25 +832,39 @@ impl Bind {
             values.push(match raw_value {
                 None => BindValue::Null,
                 Some(raw_value) => match param_tid {
-                    PgTypeId::TEXT => {
-                        BindValue::String(String::from_protocol(raw_value, param_format)?)
-                    }
                     PgTypeId::BOOL => {
                         BindValue::Bool(bool::from_protocol(raw_value, param_format)?)
                     }
+                    PgTypeId::INT2 => {
+                        BindValue::Int64(i16::from_protocol(raw_value, param_format)? as i64)
+                    }
+                    PgTypeId::INT4 => {
+                        BindValue::Int64(i32::from_protocol(raw_value, param_format)? as i64)
+                    }
                     PgTypeId::INT8 => {
                         BindValue::Int64(i64::from_protocol(raw_value, param_format)?)
                     }
-                    _ => {
-                        return Err(ErrorResponse::error(
-                            ErrorCode::FeatureNotSupported,
-                            format!(
-                                r#"Type "{:?}" is not supported for parameters decoding"#,
-                                param_tid
-                            ),
-                        )
-                        .into())
+                    PgTypeId::OID => {
+                        BindValue::Int64(u32::from_protocol(raw_value, param_format)? as i64)
                     }
+                    PgTypeId::FLOAT4 => BindValue::Float64(match param_format {
+                        Format::Text => f64::from_text(raw_value)?,
+                        // Through the text, to get 1.1 instead of 1.100000023841858
+                        Format::Binary => {
+                            f64::from_text(f32::from_binary(raw_value)?.to_string().as_bytes())?
+                        }
+                    }),
+                    PgTypeId::FLOAT8 => {
+                        BindValue::Float64(f64::from_protocol(raw_value, param_format)?)
+                    }
+                    PgTypeId::NUMERIC => BindValue::Numeric(
+                        NumericValue::from_protocol(raw_value, param_format)?.to_string(),
+                    ),
+                    // Other types are passed in the text representation, like literals
+                    _ => BindValue::String(match param_format {
+                        Format::Text => String::from_text(raw_value)?,
+                        Format::Binary => binary_to_text(*param_tid, raw_value)?,
+                    }),
                 },
             })
         }
@@ -961,6 +1033,7 @@ pub enum ErrorCode {
     // Class 25 — Invalid Transaction State
     ActiveSqlTransaction,
     NoActiveSqlTransaction,
+    IdleInTransactionSessionTimeout,
     // 26
     InvalidSqlStatement,
     // 34
@@ -976,6 +1049,7 @@ pub enum ErrorCode {
     // Class 57 - Operator Intervention
     QueryCanceled,
     AdminShutdown,
+    IdleSessionTimeout,
     // XX - Internal Error
     InternalError,
 }
@@ -990,6 +1064,7 @@ impl Display for ErrorCode {
             Self::DataException => "22000",
             Self::ActiveSqlTransaction => "25001",
             Self::NoActiveSqlTransaction => "25P01",
+            Self::IdleInTransactionSessionTimeout => "25P03",
             Self::InvalidSqlStatement => "26000",
             Self::InvalidCursorName => "34000",
             Self::DuplicateCursor => "42P03",
@@ -999,6 +1074,7 @@ impl Display for ErrorCode {
             Self::ObjectNotInPrerequisiteState => "55000",
             Self::QueryCanceled => "57014",
             Self::AdminShutdown => "57P01",
+            Self::IdleSessionTimeout => "57P05",
             Self::InternalError => "XX000",
         };
         write!(f, "{}", string)
@@ -1279,6 +1355,51 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_bind_to_bind_values_binary() -> Result<(), ProtocolError> {
+        let bind = Bind {
+            portal: "".to_string(),
+            statement: "s0".to_string(),
+            parameter_formats: vec![Format::Binary],
+            parameter_values: vec![
+                Some(hex::decode("0000007b").unwrap()),
+                Some(hex::decode("3fc00000").unwrap()),
+                Some(hex::decode("0002000000000003000c0d48").unwrap()),
+                Some(hex::decode("00001f64").unwrap()),
+                Some(
+                    hex::decode(concat!(
+                        "000000010000000100000017000000030000000100000004",
+                        "00000001ffffffff0000000400000003"
+                    ))
+                    .unwrap(),
+                ),
+                None,
+            ],
+            result_formats: vec![],
+        };
+
+        assert_eq!(
+            bind.to_bind_values(&ParameterDescription::new(vec![
+                PgTypeId::INT4,
+                PgTypeId::FLOAT4,
+                PgTypeId::NUMERIC,
+                PgTypeId::DATE,
+                PgTypeId::ARRAYINT4,
+                PgTypeId::TIMESTAMPTZ,
+            ]))?,
+            vec![
+                BindValue::Int64(123),
+                BindValue::Float64(1.5),
+                BindValue::Numeric("12.340".to_string()),
+                BindValue::String("2022-01-01".to_string()),
+                BindValue::String("{1,NULL,3}".to_string()),
+                BindValue::Null,
+            ]
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_frontend_message_parse_describe() -> Result<(), ProtocolError> {
         let buffer = parse_hex_dump(
@@ -1389,6 +1510,25 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_backend_message_write_notification_response() -> Result<(), ProtocolError> {
+        let mut cursor = Cursor::new(vec![]);
+
+        buffer::write_message(
+            &mut bytes::BytesMut::new(),
+            &mut cursor,
+            NotificationResponse::new(7, "ch".to_string(), "ok".to_string()),
+        )
+        .await?;
+
+        assert_eq!(
+            cursor.get_ref()[0..],
+            vec![65, 0, 0, 0, 14, 0, 0, 0, 7, 99, 104, 0, 111, 107, 0]
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_frontend_message_write_row_description() -> Result<(), ProtocolError> {
         let mut cursor = Cursor::new(vec![]);
@@ -66,6 +66,7 @@ pub fn sql_tests() -> Vec<(&'static str, TestFn)> {
         t("column_escaping", column_escaping),
         t("information_schema", information_schema),
         t("system_query_cache", system_query_cache),
+        t("system_queries", system_queries),
         t("metastore_rocksdb_tables", metastore_rocksdb_tables),
         t("cachestore_rocksdb_tables", cachestore_rocksdb_tables),
         t("case_column_escaping", case_column_escaping),
@@ -252,6 +253,9 @@ pub fn sql_tests() -> Vec<(&'static str, TestFn)> {
         t("cache_compaction", cache_compaction),
         t("cache_set_nx", cache_set_nx),
         t("cache_prefix_keys", cache_prefix_keys),
+        t("cache_hash", cache_hash),
+        t("cache_sorted_set", cache_sorted_set),
+        t("cache_publish_subscribe", cache_publish_subscribe),
         t("queue_list_v1", queue_list_v1),
         t("queue_full_workflow_v1", queue_full_workflow_v1),
         t("queue_full_workflow_v2", queue_full_workflow_v2),
@@ -269,6 +273,12 @@ pub fn sql_tests() -> Vec<(&'static str, TestFn)> {
             queue_multiple_result_blocking,
         ),
         t("queue_custom_orphaned", queue_custom_orphaned),
+        t("queue_delayed_retrieve", queue_delayed_retrieve),
+        t(
+            "queue_max_attempts_dead_letter",
+            queue_max_attempts_dead_letter,
+        ),
+        t("queue_dead_letter_add_again", queue_dead_letter_add_again),
         t("limit_pushdown_group", limit_pushdown_group),
         t("limit_pushdown_group_order", limit_pushdown_group_order),
         t(
@@ -1414,6 +1424,20 @@ async fn system_query_cache(service: Box<dyn SqlClient>) {
         .unwrap();
 }
 
+async fn system_queries(service: Box<dyn SqlClient>) {
+    // Meta queries are not registered, so there are no running queries here.
+    let r = service
+        .exec_query(
+            "SELECT id, sql, started_at, node, memory_usage, rows_scanned FROM system.queries",
+        )
+        .await
+        .unwrap();
+    assert_eq!(r.get_rows().len(), 0);
+
+    // Unknown query.
+    service.exec_query("KILL QUERY 100500").await.unwrap_err();
+}
+
 async fn metastore_rocksdb_tables(service: Box<dyn SqlClient>) {
     service
         .exec_query("SELECT * FROM metastore.rocksdb_properties")
@@ -7055,6 +7079,224 @@ async fn cache_incr(service: Box<dyn SqlClient>) {
     );
 }
 
+async fn cache_hash(service: Box<dyn SqlClient>) {
+    let r = service
+        .exec_query(r#"CACHE HSET "tenant:1" "plan" 'pro'"#)
+        .await
+        .unwrap();
+    assert_eq!(
+        r.get_rows(),
+        &vec![Row::new(vec![TableValue::Boolean(true)])]
+    );
+
+    let r = service
+        .exec_query(r#"CACHE HSET "tenant:1" "plan" 'enterprise'"#)
+        .await
+        .unwrap();
+    assert_eq!(
+        r.get_rows(),
+        &vec![Row::new(vec![TableValue::Boolean(false)])]
+    );
+
+    let r = service
+        .exec_query(r#"CACHE HGET "tenant:1" "plan""#)
+        .await
+        .unwrap();
+    assert_eq!(
+        r.get_rows(),
+        &vec![Row::new(vec![TableValue::String("enterprise".to_string())])]
+    );
+
+    let r = service
+        .exec_query(r#"CACHE HGET "tenant:1" "unknown""#)
+        .await
+        .unwrap();
+    assert_eq!(r.get_rows(), &vec![Row::new(vec![TableValue::Null])]);
+
+    service
+        .exec_query(r#"CACHE HINCRBY "tenant:1" "requests" 5"#)
+        .await
+        .unwrap();
+    let r = service
+        .exec_query(r#"CACHE HINCRBY "tenant:1" "requests" -2"#)
+        .await
+        .unwrap();
+    assert_eq!(
+        r.get_rows(),
+        &vec![Row::new(vec![TableValue::String("3".to_string())])]
+    );
+
+    // Hash operations are not allowed for plain values
+    service
+        .exec_query(r#"CACHE SET "tenant:2" 'value'"#)
+        .await
+        .unwrap();
+    service
+        .exec_query(r#"CACHE HGET "tenant:2" "plan""#)
+        .await
+        .unwrap_err();
+
+    // TTL expires the field together with the hash key
+    service
+        .exec_query(r#"CACHE HSET TTL 1 "tenant:3" "plan" 'pro'"#)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::new(3, 0)).await;
+    service
+        .exec_query("SYS CACHESTORE COMPACTION;")
+        .await
+        .unwrap();
+    let r = service
+        .exec_query(r#"CACHE HGET "tenant:3" "plan""#)
+        .await
+        .unwrap();
+    assert_eq!(r.get_rows(), &vec![Row::new(vec![TableValue::Null])]);
+}
+
+async fn cache_sorted_set(service: Box<dyn SqlClient>) {
+    for (score, member) in [(10, "a"), (20, "b"), (30, "c"), (40, "d")] {
+        let r = service
+            .exec_query(&format!(r#"CACHE ZADD "window:1" {} '{}'"#, score, member))
+            .await
+            .unwrap();
+        assert_eq!(
+            r.get_rows(),
+            &vec![Row::new(vec![TableValue::Boolean(true)])]
+        );
+    }
+
+    let r = service
+        .exec_query(r#"CACHE ZADD "window:1" 5 'd'"#)
+        .await
+        .unwrap();
+    assert_eq!(
+        r.get_rows(),
+        &vec![Row::new(vec![TableValue::Boolean(false)])]
+    );
+
+    let r = service
+        .exec_query(r#"CACHE ZRANGEBYSCORE "window:1" -inf 20 LIMIT 2"#)
+        .await
+        .unwrap();
+    assert_eq!(
+        r.get_columns(),
+        &vec![
+            Column::new("member".to_string(), ColumnType::String, 0),
+            Column::new("score".to_string(), ColumnType::Float, 1),
+        ]
+    );
+    assert_eq!(
+        r.get_rows(),
+        &vec![
+            Row::new(vec![
+                TableValue::String("d".to_string()),
+                TableValue::Float(5.0.into())
+            ]),
+            Row::new(vec![
+                TableValue::String("a".to_string()),
+                TableValue::Float(10.0.into())
+            ]),
+        ]
+    );
+
+    let r = service
+        .exec_query(r#"CACHE ZREMRANGEBYSCORE "window:1" -inf 15"#)
+        .await
+        .unwrap();
+    assert_eq!(r.get_rows(), &vec![Row::new(vec![TableValue::Int(2)])]);
+
+    let r = service
+        .exec_query(r#"CACHE ZRANGEBYSCORE "window:1" -inf +inf"#)
+        .await
+        .unwrap();
+    assert_eq!(
+        r.get_rows(),
+        &vec![
+            Row::new(vec![
+                TableValue::String("b".to_string()),
+                TableValue::Float(20.0.into())
+            ]),
+            Row::new(vec![
+                TableValue::String("c".to_string()),
+                TableValue::Float(30.0.into())
+            ]),
+        ]
+    );
+}
+
+async fn cache_publish_subscribe(service: Box<dyn SqlClient>) {
+    // Nobody receives messages without subscription
+    service
+        .exec_query(r#"CACHE PUBLISH "events" 'lost'"#)
+        .await
+        .unwrap();
+
+    let service = Arc::new(service);
+
+    let service_to_move = service.clone();
+    let subscribe = async move {
+        service_to_move
+            .exec_query(r#"CACHE SUBSCRIBE TIMEOUT 5000 "events""#)
+            .await
+            .unwrap()
+    };
+
+    let service_to_move = service.clone();
+    let publish = async move {
+        tokio::time::sleep(Duration::from_millis(1000)).await;
+
+        service_to_move
+            .exec_query(r#"CACHE PUBLISH "other" 'skipped'"#)
+            .await
+            .unwrap();
+        service_to_move
+            .exec_query(r#"CACHE PUBLISH "events" 'message1'"#)
+            .await
+            .unwrap();
+    };
+
+    let (subscribe_res, _) = join!(subscribe, publish);
+    assert_eq!(subscribe_res.get_rows().len(), 1);
+    let values = subscribe_res.get_rows()[0].values();
+    assert_eq!(values[0], TableValue::String("events".to_string()));
+    assert_eq!(values[1], TableValue::String("message1".to_string()));
+    let cursor = match &values[2] {
+        TableValue::Int(cursor) => *cursor,
+        other => panic!("unexpected cursor: {:?}", other),
+    };
+
+    let r = service
+        .exec_query(r#"CACHE SUBSCRIBE TIMEOUT 100 "events""#)
+        .await
+        .unwrap();
+    assert_eq!(r.get_rows().len(), 0);
+
+    // Messages published between polls are received with the cursor of the last message
+    for message in ["message2", "message3"] {
+        service
+            .exec_query(&format!(r#"CACHE PUBLISH "events" '{}'"#, message))
+            .await
+            .unwrap();
+    }
+    let r = service
+        .exec_query(&format!(
+            r#"CACHE SUBSCRIBE TIMEOUT 100 AFTER {} "events""#,
+            cursor
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        r.get_rows()
+            .iter()
+            .map(|row| row.values()[1].clone())
+            .collect::<Vec<_>>(),
+        vec![
+            TableValue::String("message2".to_string()),
+            TableValue::String("message3".to_string()),
+        ]
+    );
+}
+
 async fn cache_set_get_rm(service: Box<dyn SqlClient>) {
     service
         .exec_query("CACHE SET 'key_to_rm' 'myvalue';")
@@ -9973,6 +10215,170 @@ async fn queue_custom_orphaned(service: Box<dyn SqlClient>) {
     );
 }
 
+async fn queue_delayed_retrieve(service: Box<dyn SqlClient>) {
+    service
+        .exec_query(r#"QUEUE ADD PRIORITY 1 DELAY 2 "STANDALONE#queue:1" "payload1";"#)
+        .await
+        .unwrap();
+
+    {
+        let retrieve_response = service
+            .exec_query(r#"QUEUE RETRIEVE EXTENDED CONCURRENCY 1 "STANDALONE#queue:1""#)
+            .await
+            .unwrap();
+        assert_queue_retrieve_columns(&retrieve_response);
+        assert_eq!(
+            retrieve_response.get_rows(),
+            &vec![Row::new(vec![
+                TableValue::Null,
+                TableValue::Null,
+                TableValue::Int(1),
+                TableValue::Null,
+                TableValue::Null,
+            ]),]
+        );
+    }
+
+    tokio::time::sleep(Duration::new(3, 0)).await;
+
+    {
+        let retrieve_response = service
+            .exec_query(r#"QUEUE RETRIEVE CONCURRENCY 1 "STANDALONE#queue:1""#)
+            .await
+            .unwrap();
+        assert_queue_retrieve_columns(&retrieve_response);
+        assert_eq!(
+            retrieve_response.get_rows(),
+            &vec![Row::new(vec![
+                TableValue::String("payload1".to_string()),
+                TableValue::Null,
+                TableValue::Int(0),
+                TableValue::String("1".to_string()),
+                TableValue::String("1".to_string()),
+            ]),]
+        );
+    }
+}
+
+async fn queue_max_attempts_dead_letter(service: Box<dyn SqlClient>) {
+    service
+        .exec_query(r#"QUEUE ADD PRIORITY 1 MAX_ATTEMPTS 1 "STANDALONE#queue:1" "payload1";"#)
+        .await
+        .unwrap();
+
+    service
+        .exec_query(r#"QUEUE RETRIEVE CONCURRENCY 1 "STANDALONE#queue:1""#)
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::new(2, 0)).await;
+
+    // Stalled item with exhausted attempts is moved to the dead-letter list instead of cancel
+    let res = service
+        .exec_query(r#"QUEUE STALLED 1000 "STANDALONE#queue""#)
+        .await
+        .unwrap();
+    assert_eq!(res.get_rows().len(), 0);
+
+    let res = service
+        .exec_query(r#"QUEUE LIST "STANDALONE#queue""#)
+        .await
+        .unwrap();
+    assert_eq!(res.get_rows().len(), 0);
+
+    let res = service
+        .exec_query(r#"QUEUE DLQ LIST "STANDALONE#queue""#)
+        .await
+        .unwrap();
+    assert_eq!(
+        res.get_rows(),
+        &vec![Row::new(vec![
+            TableValue::String("1".to_string()),
+            TableValue::String("1".to_string()),
+            TableValue::String("dead_letter".to_string()),
+            TableValue::Null,
+        ]),]
+    );
+
+    let res = service
+        .exec_query(r#"QUEUE DLQ REQUEUE "STANDALONE#queue:1""#)
+        .await
+        .unwrap();
+    assert_eq!(
+        res.get_rows(),
+        &vec![Row::new(vec![TableValue::Boolean(true)])]
+    );
+
+    let res = service
+        .exec_query(r#"QUEUE DLQ LIST "STANDALONE#queue""#)
+        .await
+        .unwrap();
+    assert_eq!(res.get_rows().len(), 0);
+
+    let res = service
+        .exec_query(r#"QUEUE RETRIEVE CONCURRENCY 1 "STANDALONE#queue:1""#)
+        .await
+        .unwrap();
+    assert_eq!(
+        res.get_rows(),
+        &vec![Row::new(vec![
+            TableValue::String("payload1".to_string()),
+            TableValue::Null,
+            TableValue::Int(0),
+            TableValue::String("1".to_string()),
+            TableValue::String("1".to_string()),
+        ]),]
+    );
+}
+
+async fn queue_dead_letter_add_again(service: Box<dyn SqlClient>) {
+    service
+        .exec_query(r#"QUEUE ADD PRIORITY 1 MAX_ATTEMPTS 1 "STANDALONE#queue:1" "payload1";"#)
+        .await
+        .unwrap();
+
+    service
+        .exec_query(r#"QUEUE RETRIEVE CONCURRENCY 1 "STANDALONE#queue:1""#)
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::new(2, 0)).await;
+
+    service
+        .exec_query(r#"QUEUE STALLED 1000 "STANDALONE#queue""#)
+        .await
+        .unwrap();
+
+    // Adding the same key replaces the dead-lettered item
+    let add_response = service
+        .exec_query(r#"QUEUE ADD PRIORITY 1 "STANDALONE#queue:1" "payload2";"#)
+        .await
+        .unwrap();
+    assert_eq!(
+        add_response.get_rows(),
+        &vec![Row::new(vec![
+            TableValue::String("2".to_string()),
+            TableValue::Boolean(true),
+            TableValue::Int(1)
+        ])]
+    );
+
+    let res = service
+        .exec_query(r#"QUEUE DLQ LIST "STANDALONE#queue""#)
+        .await
+        .unwrap();
+    assert_eq!(res.get_rows().len(), 0);
+
+    let res = service
+        .exec_query(r#"QUEUE RETRIEVE CONCURRENCY 1 "STANDALONE#queue:1""#)
+        .await
+        .unwrap();
+    assert_eq!(
+        res.get_rows()[0].values()[0],
+        TableValue::String("payload2".to_string())
+    );
+}
+
 async fn sys_cachestore_info(service: Box<dyn SqlClient>) {
     service.exec_query("SYS CACHESTORE INFO").await.unwrap();
 }
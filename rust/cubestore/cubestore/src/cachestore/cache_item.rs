@@ -6,6 +6,7 @@ use chrono::serde::ts_seconds_option;
 use chrono::{DateTime, Duration, Utc};
 use cuberockstore::rocksdb::WriteBatch;
 use serde::{Deserialize, Deserializer, Serialize};
+use std::cmp::Ordering;
 
 #[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
 pub struct CacheItem {
@@ -24,6 +25,54 @@ pub const CACHE_ITEM_SIZE_WITHOUT_VALUE: u32 = (15 * 3) + 58 + (8 + 18) + (13 +
 
 impl RocksEntity for CacheItem {}
 
+/// Kind of the collection stored at a cache key. The key holds a header item, entries of the
+/// collection (hash fields or sorted set members) are separate items under the prefix which
+/// is derived from the header id, so an update touches only one entry. A re-created header
+/// gets a new id, which hides entries of the removed collection.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CacheCollectionKind {
+    Hash,
+    SortedSet,
+}
+
+impl CacheCollectionKind {
+    fn marker(&self) -> &'static str {
+        match self {
+            CacheCollectionKind::Hash => "\u{1}hash",
+            CacheCollectionKind::SortedSet => "\u{1}zset",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            CacheCollectionKind::Hash => "hash",
+            CacheCollectionKind::SortedSet => "sorted set",
+        }
+    }
+}
+
+// Published messages are kept under the channel prefix until they expire
+const CACHE_CHANNEL_MARKER: &str = "\u{1}channel";
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct CacheSortedSetEntry {
+    pub member: String,
+    pub score: f64,
+}
+
+impl CacheSortedSetEntry {
+    pub fn new(member: String, score: f64) -> Self {
+        Self { member, score }
+    }
+
+    pub fn cmp_by_score(&self, other: &Self) -> Ordering {
+        self.score
+            .partial_cmp(&other.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| self.member.cmp(&other.member))
+    }
+}
+
 impl CacheItem {
     pub fn parse_path_to_prefix(mut path: String) -> String {
         if path.ends_with(":*") {
@@ -81,6 +130,103 @@ impl CacheItem {
     pub fn get_value(&self) -> &String {
         &self.value
     }
+
+    pub fn new_collection_header(
+        path: String,
+        expire: Option<DateTime<Utc>>,
+        kind: CacheCollectionKind,
+    ) -> CacheItem {
+        let mut item = CacheItem::new(path, None, kind.marker().to_string());
+        item.expire = expire;
+
+        item
+    }
+
+    pub fn is_collection_header(&self) -> bool {
+        self.value == CacheCollectionKind::Hash.marker()
+            || self.value == CacheCollectionKind::SortedSet.marker()
+    }
+
+    pub fn check_collection_kind(&self, kind: CacheCollectionKind) -> Result<(), CubeError> {
+        if self.value == kind.marker() {
+            Ok(())
+        } else {
+            Err(CubeError::user(format!(
+                "Value for '{}' key is not a {}",
+                self.get_path(),
+                kind.name()
+            )))
+        }
+    }
+
+    /// Prefix of the collection entries, `header_id` is the id of the collection header.
+    pub fn collection_prefix(header_id: u64) -> String {
+        format!("\u{1}{}", header_id)
+    }
+
+    pub fn new_collection_entry(
+        header_id: u64,
+        key: String,
+        value: String,
+        expire: Option<DateTime<Utc>>,
+    ) -> CacheItem {
+        CacheItem {
+            prefix: Some(Self::collection_prefix(header_id)),
+            key,
+            value,
+            expire,
+        }
+    }
+
+    pub fn parse_sorted_set_entry(&self) -> Result<CacheSortedSetEntry, CubeError> {
+        let score = self.value.parse::<f64>().map_err(|_| {
+            CubeError::internal(format!(
+                "Score of '{}' sorted set member is not a number: {}",
+                self.key, self.value
+            ))
+        })?;
+
+        Ok(CacheSortedSetEntry::new(self.key.clone(), score))
+    }
+
+    pub fn channel_prefix(channel: &str) -> String {
+        format!("{}:{}", CACHE_CHANNEL_MARKER, channel)
+    }
+
+    pub fn new_channel_message(channel: &str, cursor: u64, message: String, ttl: u32) -> CacheItem {
+        CacheItem {
+            prefix: Some(Self::channel_prefix(channel)),
+            // Zero padded, so keys are ordered the same way as cursors
+            key: format!("{:020}", cursor),
+            value: message,
+            expire: Some(Utc::now() + Duration::seconds(ttl as i64)),
+        }
+    }
+
+    pub fn parse_channel_cursor(&self) -> Result<u64, CubeError> {
+        self.key.parse::<u64>().map_err(|_| {
+            CubeError::internal(format!("Channel message key is not a cursor: {}", self.key))
+        })
+    }
+}
+
+/// Increments the value of hash field by `increment`, missing field is treated as 0.
+pub fn cache_hash_incr_by(
+    current: Option<&str>,
+    field: &str,
+    increment: i64,
+) -> Result<i64, CubeError> {
+    let current = if let Some(value) = current {
+        value
+            .parse::<i64>()
+            .map_err(|_| CubeError::user(format!("Hash field '{}' is not an integer", field)))?
+    } else {
+        0
+    };
+
+    current
+        .checked_add(increment)
+        .ok_or_else(|| CubeError::user(format!("Increment of hash field '{}' overflows", field)))
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -207,4 +353,54 @@ mod tests {
         assert_eq!(row.key, "1".to_string());
         assert_eq!(row.get_path(), "lock:1".to_string());
     }
+
+    #[test]
+    fn test_hash_incr_by() {
+        assert_eq!(cache_hash_incr_by(None, "f", 5).unwrap(), 5);
+        assert_eq!(cache_hash_incr_by(Some("5"), "f", -2).unwrap(), 3);
+        assert!(cache_hash_incr_by(Some("a"), "f", 1).is_err());
+        assert!(cache_hash_incr_by(Some(&i64::MAX.to_string()), "f", 1).is_err());
+    }
+
+    #[test]
+    fn test_collection_items() {
+        let header = CacheItem::new_collection_header(
+            "events:1".to_string(),
+            None,
+            CacheCollectionKind::SortedSet,
+        );
+        assert_eq!(header.get_path(), "events:1".to_string());
+        assert!(header.is_collection_header());
+        assert!(header
+            .check_collection_kind(CacheCollectionKind::SortedSet)
+            .is_ok());
+        assert!(header
+            .check_collection_kind(CacheCollectionKind::Hash)
+            .is_err());
+
+        // Member with ':' is kept as the key of the entry
+        let entry = CacheItem::new_collection_entry(5, "a:b".to_string(), "1.5".to_string(), None);
+        assert_eq!(entry.get_prefix(), &Some(CacheItem::collection_prefix(5)));
+        assert_eq!(
+            entry.parse_sorted_set_entry().unwrap(),
+            CacheSortedSetEntry::new("a:b".to_string(), 1.5)
+        );
+
+        let plain = CacheItem::new("events:2".to_string(), None, "value".to_string());
+        assert!(!plain.is_collection_header());
+        assert!(plain
+            .check_collection_kind(CacheCollectionKind::Hash)
+            .is_err());
+    }
+
+    #[test]
+    fn test_channel_message_cursor() {
+        let message = CacheItem::new_channel_message("events", 42, "1".to_string(), 60);
+        assert_eq!(message.parse_channel_cursor().unwrap(), 42);
+        assert!(message.get_expire().is_some());
+        assert!(
+            CacheItem::new_channel_message("events", 9, "1".to_string(), 60).key
+                < CacheItem::new_channel_message("events", 10, "1".to_string(), 60).key
+        );
+    }
 }
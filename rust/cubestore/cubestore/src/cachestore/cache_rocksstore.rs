@@ -1,6 +1,6 @@
 use crate::cachestore::cache_item::{
-    CacheItem, CacheItemIndexKey, CacheItemRocksIndex, CacheItemRocksTable,
-    CACHE_ITEM_SIZE_WITHOUT_VALUE,
+    cache_hash_incr_by, CacheCollectionKind, CacheItem, CacheItemIndexKey, CacheItemRocksIndex,
+    CacheItemRocksTable, CacheSortedSetEntry, CACHE_ITEM_SIZE_WITHOUT_VALUE,
 };
 use crate::cachestore::queue_item::{
     QueueItem, QueueItemIndexKey, QueueItemRocksIndex, QueueItemRocksTable, QueueItemStatus,
@@ -27,7 +27,7 @@ use futures_timer::Delay;
 
 use crate::cachestore::cache_eviction_manager::{CacheEvictionManager, EvictionResult};
 use crate::cachestore::compaction::CompactionPreloadedState;
-use crate::cachestore::listener::RocksCacheStoreListener;
+use crate::cachestore::listener::{CacheChannelMessage, RocksCacheStoreListener};
 use crate::cachestore::queue_item_payload::QueueItemPayloadRocksTable;
 use crate::table::{Row, TableValue};
 use chrono::{DateTime, Utc};
@@ -38,6 +38,7 @@ use itertools::Itertools;
 use log::{error, trace, warn};
 use serde_derive::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::sync::broadcast::Sender;
@@ -179,9 +180,13 @@ impl RocksStoreDetails for RocksCacheStoreDetails {
     }
 }
 
+/// Published messages are kept for subscribers which poll with a cursor, in seconds
+const CACHE_CHANNEL_MESSAGE_TTL: u32 = 60;
+
 pub struct RocksCacheStore {
     store: Arc<RocksStore>,
     cache_eviction_manager: CacheEvictionManager,
+    last_message_cursor: Arc<AtomicU64>,
     upload_loop: Arc<WorkerLoop>,
     metrics_loop: Arc<WorkerLoop>,
 }
@@ -220,6 +225,11 @@ impl RocksCacheStore {
         Ok(Arc::new(Self {
             store,
             cache_eviction_manager,
+            // Cursors start from the current time, so they keep growing after restart and
+            // subscribers with cursors from the previous process don't skip new messages
+            last_message_cursor: Arc::new(AtomicU64::new(
+                Utc::now().timestamp_micros().max(0) as u64
+            )),
             upload_loop: Arc::new(WorkerLoop::new("Cachestore upload")),
             metrics_loop: Arc::new(WorkerLoop::new("Cachestore metrics")),
         }))
@@ -582,7 +592,11 @@ impl RocksCacheStore {
                             return if orphaned < &now { true } else { false };
                         }
 
-                        let elapsed = now - item.get_row().get_created().clone();
+                        let elapsed = now
+                            - item
+                                .get_row()
+                                .get_visible_at()
+                                .unwrap_or(item.get_row().get_created().clone());
                         if elapsed.num_milliseconds() > orphaned_timeout as i64 {
                             true
                         } else {
@@ -610,6 +624,261 @@ impl RocksCacheStore {
             })
             .collect()
     }
+
+    /// Updates one entry of the collection stored at `path`, the collection is created when it's
+    /// missing. `f` receives the current value of the entry and returns a new one (None to keep
+    /// the entry untouched) with the result of operation. Only the header and the entry are
+    /// read and written, so the cost doesn't depend on the size of the collection.
+    async fn cache_update_collection_entry<F, R>(
+        &self,
+        path: String,
+        kind: CacheCollectionKind,
+        entry_key: String,
+        ttl: Option<u32>,
+        f: F,
+    ) -> Result<R, CubeError>
+    where
+        F: FnOnce(Option<&str>) -> Result<(Option<String>, R), CubeError> + Send + Sync + 'static,
+        R: Send + Sync + 'static,
+    {
+        let max_entry_size = self.store.config.cachestore_cache_max_entry_size();
+
+        let (inserted, updated, result) = self
+            .store
+            .write_operation(move |db_ref, batch_pipe| {
+                let cache_schema = CacheItemRocksTable::new(db_ref.clone());
+                let header_opt = cache_schema.get_single_opt_row_by_index(
+                    &CacheItemIndexKey::ByPath(path.clone()),
+                    &CacheItemRocksIndex::ByPath,
+                )?;
+                let entry_opt = if let Some(header) = &header_opt {
+                    header.get_row().check_collection_kind(kind)?;
+
+                    cache_schema.get_single_opt_row_by_index(
+                        &CacheItemIndexKey::ByPath(format!(
+                            "{}:{}",
+                            CacheItem::collection_prefix(header.get_id()),
+                            entry_key
+                        )),
+                        &CacheItemRocksIndex::ByPath,
+                    )?
+                } else {
+                    None
+                };
+
+                let (value, result) =
+                    f(entry_opt.as_ref().map(|entry| entry.get_row().get_value().as_str()))?;
+                let value = if let Some(value) = value {
+                    value
+                } else {
+                    return Ok((vec![], vec![], result));
+                };
+
+                if value.len() >= max_entry_size {
+                    return Err(CubeError::user(format!(
+                        "Unable to update cache with '{}' key, exceeds maximum allowed size for payload: {}, max allowed: {}",
+                        path,
+                        humansize::format_size(value.len(), humansize::DECIMAL),
+                        humansize::format_size(max_entry_size, humansize::DECIMAL),
+                    )));
+                }
+
+                let expire = ttl.map(|ttl| Utc::now() + chrono::Duration::seconds(ttl as i64));
+                let mut inserted = Vec::new();
+                let mut updated = Vec::new();
+
+                // Header outlives its entries: an entry without TTL inherits the expiration of
+                // the header and a longer TTL extends the header
+                let header = if let Some(header) = header_opt {
+                    let extend = match (header.get_row().get_expire(), &expire) {
+                        (Some(current), Some(new)) => new > current,
+                        _ => false,
+                    };
+
+                    if extend {
+                        let mut new_header = header.get_row().clone();
+                        new_header.expire = expire.clone();
+
+                        let header = cache_schema.update(
+                            header.get_id(),
+                            new_header,
+                            header.get_row(),
+                            batch_pipe,
+                        )?;
+                        updated.push(header.clone());
+
+                        header
+                    } else {
+                        header
+                    }
+                } else {
+                    let header = cache_schema.insert(
+                        CacheItem::new_collection_header(path, expire.clone(), kind),
+                        batch_pipe,
+                    )?;
+                    inserted.push(header.get_row().get_value().len());
+
+                    header
+                };
+                let entry_expire = expire.or_else(|| header.get_row().get_expire().clone());
+
+                if let Some(entry) = entry_opt {
+                    let mut new = entry.get_row().clone();
+                    new.value = value;
+                    new.expire = entry_expire;
+
+                    updated.push(cache_schema.update(
+                        entry.get_id(),
+                        new,
+                        entry.get_row(),
+                        batch_pipe,
+                    )?);
+                } else {
+                    inserted.push(value.len());
+                    cache_schema.insert(
+                        CacheItem::new_collection_entry(
+                            header.get_id(),
+                            entry_key,
+                            value,
+                            entry_expire,
+                        ),
+                        batch_pipe,
+                    )?;
+                }
+
+                Ok((inserted, updated, result))
+            })
+            .await?;
+
+        for raw_size in inserted {
+            self.cache_eviction_manager.notify_insert(raw_size as u64)?;
+        }
+
+        for item in &updated {
+            self.cache_eviction_manager.notify_lookup(item)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Reads entries of the collection stored at `path`, `entry_key` selects a single entry.
+    async fn cache_collection_entries(
+        &self,
+        path: String,
+        kind: CacheCollectionKind,
+        entry_key: Option<String>,
+    ) -> Result<Vec<IdRow<CacheItem>>, CubeError> {
+        self.store
+            .read_operation(move |db_ref| {
+                let cache_schema = CacheItemRocksTable::new(db_ref.clone());
+                let header = if let Some(header) = cache_schema.get_single_opt_row_by_index(
+                    &CacheItemIndexKey::ByPath(path),
+                    &CacheItemRocksIndex::ByPath,
+                )? {
+                    header
+                } else {
+                    return Ok(vec![]);
+                };
+                header.get_row().check_collection_kind(kind)?;
+
+                let prefix = CacheItem::collection_prefix(header.get_id());
+                if let Some(entry_key) = entry_key {
+                    Ok(cache_schema
+                        .get_single_opt_row_by_index(
+                            &CacheItemIndexKey::ByPath(format!("{}:{}", prefix, entry_key)),
+                            &CacheItemRocksIndex::ByPath,
+                        )?
+                        .into_iter()
+                        .collect())
+                } else {
+                    cache_schema.get_rows_by_index(
+                        &CacheItemIndexKey::ByPrefix(prefix),
+                        &CacheItemRocksIndex::ByPrefix,
+                    )
+                }
+            })
+            .await
+    }
+
+    /// Deletes entries of the collection, returns ids and sizes of the deleted rows.
+    fn delete_collection_entries(
+        cache_schema: &CacheItemRocksTable,
+        header_id: u64,
+        batch_pipe: &mut BatchPipe,
+    ) -> Result<Vec<(u64, usize)>, CubeError> {
+        let entries = cache_schema.get_rows_by_index(
+            &CacheItemIndexKey::ByPrefix(CacheItem::collection_prefix(header_id)),
+            &CacheItemRocksIndex::ByPrefix,
+        )?;
+
+        let mut deleted = Vec::with_capacity(entries.len());
+        for entry in entries {
+            deleted.push((entry.get_id(), entry.get_row().get_value().len()));
+            cache_schema.delete_row(entry, batch_pipe)?;
+        }
+
+        Ok(deleted)
+    }
+
+    /// Reads stored messages of the channel published after the cursor, ordered by cursor.
+    async fn cache_channel_messages(
+        &self,
+        channel: String,
+        after: u64,
+    ) -> Result<Vec<CacheChannelMessage>, CubeError> {
+        self.store
+            .read_operation(move |db_ref| {
+                let cache_schema = CacheItemRocksTable::new(db_ref.clone());
+                let rows = cache_schema.get_rows_by_index(
+                    &CacheItemIndexKey::ByPrefix(CacheItem::channel_prefix(&channel)),
+                    &CacheItemRocksIndex::ByPrefix,
+                )?;
+
+                let mut messages = Vec::new();
+                for row in rows {
+                    let cursor = row.get_row().parse_channel_cursor()?;
+                    if cursor > after {
+                        messages.push(CacheChannelMessage {
+                            cursor,
+                            channel: channel.clone(),
+                            message: row.into_row().value,
+                        });
+                    }
+                }
+                messages.sort_by_key(|m| m.cursor);
+
+                Ok(messages)
+            })
+            .await
+    }
+
+    /// Stores a new state of the queue item and aligns TTL of its payload with it.
+    fn queue_item_reschedule(
+        db_ref: DbTableRef,
+        id_row: IdRow<QueueItem>,
+        new: QueueItem,
+        batch_pipe: &mut BatchPipe,
+    ) -> Result<IdRow<QueueItem>, CubeError> {
+        let queue_schema = QueueItemRocksTable::new(db_ref.clone());
+        let queue_payload_schema = QueueItemPayloadRocksTable::new(db_ref);
+
+        if let Some(payload_row) = queue_payload_schema.get_row(id_row.get_id())? {
+            let new_payload = payload_row.get_row().with_expire(new.get_expire().clone());
+            queue_payload_schema.update(
+                payload_row.get_id(),
+                new_payload,
+                payload_row.get_row(),
+                batch_pipe,
+            )?;
+        } else {
+            error!(
+                "Unable to find payload for queue item, id = {}",
+                id_row.get_id()
+            );
+        }
+
+        queue_schema.update(id_row.get_id(), new, id_row.get_row(), batch_pipe)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, DeepSizeOf)]
@@ -645,6 +914,8 @@ pub struct QueueAddPayload {
     pub value: String,
     pub priority: i64,
     pub orphaned: Option<u32>,
+    pub delay: Option<u32>,
+    pub max_attempts: Option<u32>,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
@@ -764,6 +1035,59 @@ pub trait CacheStore: DIService + Send + Sync {
     async fn cache_get(&self, key: String) -> Result<Option<IdRow<CacheItem>>, CubeError>;
     async fn cache_keys(&self, prefix: String) -> Result<Vec<IdRow<CacheItem>>, CubeError>;
     async fn cache_incr(&self, key: String) -> Result<IdRow<CacheItem>, CubeError>;
+    // hash
+    /// Every field of the hash is stored as a separate item, HSET/HINCRBY touch only the field.
+    /// `ttl` sets expiration of the written field and extends expiration of the key, a field
+    /// written without it expires with the key.
+    async fn cache_hset(
+        &self,
+        path: String,
+        field: String,
+        value: String,
+        ttl: Option<u32>,
+    ) -> Result<bool, CubeError>;
+    async fn cache_hget(&self, path: String, field: String) -> Result<Option<String>, CubeError>;
+    async fn cache_hincrby(
+        &self,
+        path: String,
+        field: String,
+        increment: i64,
+        ttl: Option<u32>,
+    ) -> Result<i64, CubeError>;
+    // sorted set
+    /// Members of the sorted set are stored as separate items the same way as hash fields, ZADD
+    /// touches only the member, range operations read all members of the set.
+    async fn cache_zadd(
+        &self,
+        path: String,
+        score: f64,
+        member: String,
+        ttl: Option<u32>,
+    ) -> Result<bool, CubeError>;
+    async fn cache_zrange_by_score(
+        &self,
+        path: String,
+        min: f64,
+        max: f64,
+        limit: Option<usize>,
+    ) -> Result<Vec<CacheSortedSetEntry>, CubeError>;
+    async fn cache_zrem_range_by_score(
+        &self,
+        path: String,
+        min: f64,
+        max: f64,
+    ) -> Result<u64, CubeError>;
+    // pub/sub
+    /// Message is kept for a minute, so subscribers which poll with a cursor don't lose it.
+    async fn cache_publish(&self, channel: String, message: String) -> Result<(), CubeError>;
+    /// Returns messages published after the `after` cursor, waits up to `timeout` ms for them.
+    /// Without cursor only messages published after the call are returned.
+    async fn cache_subscribe(
+        &self,
+        channel: String,
+        timeout: u64,
+        after: Option<u64>,
+    ) -> Result<Vec<CacheChannelMessage>, CubeError>;
 
     // queue
     async fn queue_all(&self, limit: Option<usize>) -> Result<Vec<QueueAllItem>, CubeError>;
@@ -807,6 +1131,7 @@ pub trait CacheStore: DIService + Send + Sync {
         timeout: u64,
     ) -> Result<Option<QueueResultResponse>, CubeError>;
     async fn queue_merge_extra(&self, key: QueueKey, payload: String) -> Result<(), CubeError>;
+    async fn queue_dead_letter_requeue(&self, key: QueueKey) -> Result<bool, CubeError>;
 
     // Force compaction for the whole RocksDB
     async fn compaction(&self) -> Result<(), CubeError>;
@@ -848,7 +1173,7 @@ impl CacheStore for RocksCacheStore {
             .before_insert(item.get_value().len() as u64)
             .await?;
 
-        let (result, inserted) = self
+        let (result, inserted, deleted) = self
             .store
             .write_operation(move |db_ref, batch_pipe| {
                 let cache_schema = CacheItemRocksTable::new(db_ref.clone());
@@ -858,16 +1183,22 @@ impl CacheStore for RocksCacheStore {
 
                 if let Some(id_row) = id_row_opt {
                     if update_if_not_exists {
-                        return Ok((false, None));
+                        return Ok((false, None, vec![]));
+                    };
+
+                    let deleted = if id_row.get_row().is_collection_header() {
+                        Self::delete_collection_entries(&cache_schema, id_row.id, batch_pipe)?
+                    } else {
+                        vec![]
                     };
 
                     cache_schema.update(id_row.id, item, &id_row.row, batch_pipe)?;
-                    Ok((true, None))
+                    Ok((true, None, deleted))
                 } else {
                     let raw_size = item.get_value().len();
 
                     cache_schema.insert(item, batch_pipe)?;
-                    Ok((true, Some(raw_size)))
+                    Ok((true, Some(raw_size), vec![]))
                 }
             })
             .await?;
@@ -876,6 +1207,11 @@ impl CacheStore for RocksCacheStore {
             self.cache_eviction_manager.notify_insert(raw_size as u64)?;
         }
 
+        for (row_id, raw_size) in deleted {
+            self.cache_eviction_manager
+                .notify_delete(row_id, raw_size as u64)?;
+        }
+
         Ok(result)
     }
 
@@ -899,7 +1235,7 @@ impl CacheStore for RocksCacheStore {
     }
 
     async fn cache_delete(&self, key: String) -> Result<(), CubeError> {
-        let result = self
+        let deleted = self
             .store
             .write_operation(move |db_ref, batch_pipe| {
                 let cache_schema = CacheItemRocksTable::new(db_ref.clone());
@@ -911,16 +1247,23 @@ impl CacheStore for RocksCacheStore {
                     let row_id = row.id;
                     let raw_size = row.get_row().get_value().len();
 
+                    let mut deleted = if row.get_row().is_collection_header() {
+                        Self::delete_collection_entries(&cache_schema, row_id, batch_pipe)?
+                    } else {
+                        vec![]
+                    };
+
                     cache_schema.delete_row(row, batch_pipe)?;
+                    deleted.push((row_id, raw_size));
 
-                    Ok(Some((row_id, raw_size)))
+                    Ok(deleted)
                 } else {
-                    Ok(None)
+                    Ok(vec![])
                 }
             })
             .await?;
 
-        if let Some((row_id, raw_size)) = result {
+        for (row_id, raw_size) in deleted {
             self.cache_eviction_manager
                 .notify_delete(row_id, raw_size as u64)?;
         }
@@ -991,6 +1334,234 @@ impl CacheStore for RocksCacheStore {
         Ok(item)
     }
 
+    async fn cache_hset(
+        &self,
+        path: String,
+        field: String,
+        value: String,
+        ttl: Option<u32>,
+    ) -> Result<bool, CubeError> {
+        self.cache_update_collection_entry(
+            path,
+            CacheCollectionKind::Hash,
+            field,
+            ttl,
+            move |current| Ok((Some(value), current.is_none())),
+        )
+        .await
+    }
+
+    async fn cache_hget(&self, path: String, field: String) -> Result<Option<String>, CubeError> {
+        let entry = self
+            .cache_collection_entries(path, CacheCollectionKind::Hash, Some(field))
+            .await?
+            .pop();
+
+        if let Some(entry) = entry {
+            self.cache_eviction_manager.notify_lookup(&entry)?;
+
+            Ok(Some(entry.into_row().value))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn cache_hincrby(
+        &self,
+        path: String,
+        field: String,
+        increment: i64,
+        ttl: Option<u32>,
+    ) -> Result<i64, CubeError> {
+        let field_name = field.clone();
+
+        self.cache_update_collection_entry(
+            path,
+            CacheCollectionKind::Hash,
+            field,
+            ttl,
+            move |current| {
+                let value = cache_hash_incr_by(current, &field_name, increment)?;
+
+                Ok((Some(value.to_string()), value))
+            },
+        )
+        .await
+    }
+
+    async fn cache_zadd(
+        &self,
+        path: String,
+        score: f64,
+        member: String,
+        ttl: Option<u32>,
+    ) -> Result<bool, CubeError> {
+        if !score.is_finite() {
+            return Err(CubeError::user(format!(
+                "Score for '{}' key must be a finite number, actual: {}",
+                path, score
+            )));
+        }
+
+        self.cache_update_collection_entry(
+            path,
+            CacheCollectionKind::SortedSet,
+            member,
+            ttl,
+            move |current| Ok((Some(score.to_string()), current.is_none())),
+        )
+        .await
+    }
+
+    async fn cache_zrange_by_score(
+        &self,
+        path: String,
+        min: f64,
+        max: f64,
+        limit: Option<usize>,
+    ) -> Result<Vec<CacheSortedSetEntry>, CubeError> {
+        let mut set = self
+            .cache_collection_entries(path, CacheCollectionKind::SortedSet, None)
+            .await?
+            .iter()
+            .map(|entry| entry.get_row().parse_sorted_set_entry())
+            .collect::<Result<Vec<_>, _>>()?;
+        set.retain(|entry| entry.score >= min && entry.score <= max);
+        set.sort_by(|a, b| a.cmp_by_score(b));
+        set.truncate(limit.unwrap_or(usize::MAX));
+
+        Ok(set)
+    }
+
+    async fn cache_zrem_range_by_score(
+        &self,
+        path: String,
+        min: f64,
+        max: f64,
+    ) -> Result<u64, CubeError> {
+        let deleted = self
+            .store
+            .write_operation(move |db_ref, batch_pipe| {
+                let cache_schema = CacheItemRocksTable::new(db_ref.clone());
+                let header = if let Some(header) = cache_schema.get_single_opt_row_by_index(
+                    &CacheItemIndexKey::ByPath(path),
+                    &CacheItemRocksIndex::ByPath,
+                )? {
+                    header
+                } else {
+                    return Ok(vec![]);
+                };
+                header
+                    .get_row()
+                    .check_collection_kind(CacheCollectionKind::SortedSet)?;
+
+                let entries = cache_schema.get_rows_by_index(
+                    &CacheItemIndexKey::ByPrefix(CacheItem::collection_prefix(header.get_id())),
+                    &CacheItemRocksIndex::ByPrefix,
+                )?;
+
+                let mut deleted = Vec::new();
+                for entry in entries {
+                    let score = entry.get_row().parse_sorted_set_entry()?.score;
+                    if score >= min && score <= max {
+                        deleted.push((entry.get_id(), entry.get_row().get_value().len()));
+                        cache_schema.delete_row(entry, batch_pipe)?;
+                    }
+                }
+
+                Ok(deleted)
+            })
+            .await?;
+
+        let removed = deleted.len() as u64;
+        for (row_id, raw_size) in deleted {
+            self.cache_eviction_manager
+                .notify_delete(row_id, raw_size as u64)?;
+        }
+
+        Ok(removed)
+    }
+
+    async fn cache_publish(&self, channel: String, message: String) -> Result<(), CubeError> {
+        let max_entry_size = self.store.config.cachestore_cache_max_entry_size();
+        if message.len() >= max_entry_size {
+            return Err(CubeError::user(format!(
+                "Unable to PUBLISH to '{}' channel, exceeds maximum allowed size for payload: {}, max allowed: {}",
+                channel,
+                humansize::format_size(message.len(), humansize::DECIMAL),
+                humansize::format_size(max_entry_size, humansize::DECIMAL),
+            )));
+        }
+
+        let raw_size = message.len();
+        self.cache_eviction_manager
+            .before_insert(raw_size as u64)
+            .await?;
+
+        let last_message_cursor = self.last_message_cursor.clone();
+        self.store
+            .write_operation(move |db_ref, batch_pipe| {
+                // Write operations are applied one by one, so cursors grow in the order in which
+                // messages are committed and a subscriber with a cursor doesn't skip any
+                let cursor = last_message_cursor.fetch_add(1, Ordering::SeqCst) + 1;
+
+                let cache_schema = CacheItemRocksTable::new(db_ref.clone());
+                cache_schema.insert(
+                    CacheItem::new_channel_message(
+                        &channel,
+                        cursor,
+                        message.clone(),
+                        CACHE_CHANNEL_MESSAGE_TTL,
+                    ),
+                    batch_pipe,
+                )?;
+
+                batch_pipe.add_event(MetaStoreEvent::CachePublish(CacheChannelMessage {
+                    cursor,
+                    channel,
+                    message,
+                }));
+
+                Ok(())
+            })
+            .await?;
+
+        self.cache_eviction_manager.notify_insert(raw_size as u64)?;
+
+        Ok(())
+    }
+
+    async fn cache_subscribe(
+        &self,
+        channel: String,
+        timeout: u64,
+        after: Option<u64>,
+    ) -> Result<Vec<CacheChannelMessage>, CubeError> {
+        // Listener is subscribed before the stored messages are checked, so a message published
+        // in between is received as an event
+        let listener = self.get_listener().await;
+        let after = after.unwrap_or_else(|| self.last_message_cursor.load(Ordering::SeqCst));
+
+        let messages = self.cache_channel_messages(channel.clone(), after).await?;
+        if !messages.is_empty() {
+            return Ok(messages);
+        }
+
+        let wait_result = tokio::time::timeout(
+            Duration::from_millis(timeout),
+            listener.wait_for_channel_message(&channel, after),
+        )
+        .await;
+
+        match wait_result {
+            // Event is sent after the message is committed, stored messages include it together
+            // with the ones published right after it
+            Ok(Ok(_)) => self.cache_channel_messages(channel, after).await,
+            Ok(Err(e)) => Err(e),
+            Err(_) => Ok(vec![]),
+        }
+    }
+
     async fn queue_all(&self, limit: Option<usize>) -> Result<Vec<QueueAllItem>, CubeError> {
         self.store
             .read_operation(move |db_ref| {
@@ -1058,6 +1629,18 @@ impl CacheStore for RocksCacheStore {
                 let index_key = QueueItemIndexKey::ByPath(payload.path.clone());
                 let id_row_opt = queue_schema
                     .get_single_opt_row_by_index(&index_key, &QueueItemRocksIndex::ByPath)?;
+                // Dead-lettered items are only kept for inspection, adding the same key again
+                // replaces them with a new item.
+                let id_row_opt = match id_row_opt {
+                    Some(row) if row.get_row().get_status() == &QueueItemStatus::DeadLetter => {
+                        let row_id = row.get_id();
+                        queue_schema.delete_row(row, batch_pipe)?;
+                        QueueItemPayloadRocksTable::new(db_ref.clone())
+                            .try_delete(row_id, batch_pipe)?;
+                        None
+                    }
+                    id_row_opt => id_row_opt,
+                };
 
                 let (id, added) = if let Some(row) = id_row_opt {
                     (row.id, false)
@@ -1068,7 +1651,9 @@ impl CacheStore for RocksCacheStore {
                             QueueItem::status_default(),
                             payload.priority,
                             payload.orphaned.clone(),
-                        ),
+                        )
+                        .with_delay(payload.delay)
+                        .with_max_attempts(payload.max_attempts),
                         batch_pipe,
                     )?;
 
@@ -1120,19 +1705,70 @@ impl CacheStore for RocksCacheStore {
         orphaned_timeout: Option<u32>,
         heartbeat_timeout: Option<u32>,
     ) -> Result<Vec<IdRow<QueueItem>>, CubeError> {
+        let retry_backoff = self.store.config.cachestore_queue_retry_backoff();
+        let dead_letter_expire = chrono::Duration::seconds(
+            self.store.config.cachestore_queue_dead_letter_expire() as i64,
+        );
+
+        // Items without retry policy are returned to the client for cancellation, others are
+        // rescheduled or moved to the dead-letter list here.
+        let is_retried = |item: &IdRow<QueueItem>| {
+            item.get_row().get_status() == &QueueItemStatus::Active
+                && item.get_row().get_max_attempts().is_some()
+        };
+
+        let index_key = QueueItemIndexKey::ByPrefix(prefix);
+        let to_cancel = {
+            let index_key = index_key.clone();
+            self.store
+                .read_operation(move |db_ref| {
+                    let queue_schema = QueueItemRocksTable::new(db_ref.clone());
+                    let items = queue_schema
+                        .get_rows_by_index(&index_key, &QueueItemRocksIndex::ByPrefix)?;
+
+                    Ok(Self::filter_to_cancel(
+                        db_ref.start_time.clone(),
+                        items,
+                        orphaned_timeout,
+                        heartbeat_timeout,
+                    ))
+                })
+                .await?
+        };
+        // Most polls find nothing to retry, so the write lock is only taken when needed.
+        if !to_cancel.iter().any(is_retried) {
+            return Ok(to_cancel);
+        }
+
         self.store
-            .read_operation(move |db_ref| {
+            .write_operation(move |db_ref, batch_pipe| {
                 let queue_schema = QueueItemRocksTable::new(db_ref.clone());
-                let index_key = QueueItemIndexKey::ByPrefix(prefix);
                 let items =
                     queue_schema.get_rows_by_index(&index_key, &QueueItemRocksIndex::ByPrefix)?;
 
-                Ok(Self::filter_to_cancel(
-                    db_ref.start_time.clone(),
-                    items,
-                    orphaned_timeout,
-                    heartbeat_timeout,
-                ))
+                let now = db_ref.start_time.clone();
+                let mut to_cancel = Vec::new();
+
+                for item in
+                    Self::filter_to_cancel(now.clone(), items, orphaned_timeout, heartbeat_timeout)
+                {
+                    if !is_retried(&item) {
+                        to_cancel.push(item);
+                        continue;
+                    }
+
+                    let mut new = item.get_row().clone();
+                    if new.has_attempts_left() {
+                        let backoff = new.retry_backoff(retry_backoff);
+                        new.schedule_retry(now.clone(), backoff);
+                    } else {
+                        new.move_to_dead_letter(now.clone(), dead_letter_expire);
+                    }
+
+                    Self::queue_item_reschedule(db_ref.clone(), item, new, batch_pipe)?;
+                }
+
+                Ok(to_cancel)
             })
             .await
     }
@@ -1154,7 +1790,11 @@ impl CacheStore for RocksCacheStore {
                         .get_rows_by_index(&index_key, &QueueItemRocksIndex::ByPrefixAndStatus)?
                 } else {
                     let index_key = QueueItemIndexKey::ByPrefix(prefix);
-                    queue_schema.get_rows_by_index(&index_key, &QueueItemRocksIndex::ByPrefix)?
+                    queue_schema
+                        .get_rows_by_index(&index_key, &QueueItemRocksIndex::ByPrefix)?
+                        .into_iter()
+                        .filter(|item| item.get_row().get_status() != &QueueItemStatus::DeadLetter)
+                        .collect()
                 };
 
                 let items = if priority_sort {
@@ -1310,8 +1950,13 @@ impl CacheStore for RocksCacheStore {
                 };
 
                 if id_row.get_row().get_status() == &QueueItemStatus::Pending {
+                    if !id_row.get_row().is_visible(&db_ref.start_time) {
+                        return Ok(QueueRetrieveResponse::NotFound { pending, active });
+                    }
+
                     let mut new = id_row.get_row().clone();
                     new.status = QueueItemStatus::Active;
+                    new.increment_attempts();
                     // It's an important to insert heartbeat, because
                     // without that created datetime will be used for orphaned filtering
                     new.update_heartbeat();
@@ -1471,6 +2116,35 @@ impl CacheStore for RocksCacheStore {
             .await
     }
 
+    async fn queue_dead_letter_requeue(&self, key: QueueKey) -> Result<bool, CubeError> {
+        self.store
+            .write_operation(move |db_ref, batch_pipe| {
+                let queue_schema = QueueItemRocksTable::new(db_ref.clone());
+                let id_row = if let Some(id_row) = queue_schema.get_row_by_key(key.clone())? {
+                    id_row
+                } else {
+                    warn!(
+                        "Unable to requeue from dead-letter list, unknown key: {:?}",
+                        key
+                    );
+
+                    return Ok(false);
+                };
+
+                if id_row.get_row().get_status() != &QueueItemStatus::DeadLetter {
+                    return Ok(false);
+                }
+
+                let mut new = id_row.get_row().clone();
+                new.requeue_from_dead_letter(db_ref.start_time.clone());
+
+                Self::queue_item_reschedule(db_ref, id_row, new, batch_pipe)?;
+
+                Ok(true)
+            })
+            .await
+    }
+
     async fn compaction(&self) -> Result<(), CubeError> {
         self.store
             .read_operation_out_of_queue(move |db_ref| {
@@ -1573,6 +2247,72 @@ impl CacheStore for ClusterCacheStoreClient {
         panic!("CacheStore cannot be used on the worker node! cache_incr was used.")
     }
 
+    async fn cache_hset(
+        &self,
+        _path: String,
+        _field: String,
+        _value: String,
+        _ttl: Option<u32>,
+    ) -> Result<bool, CubeError> {
+        panic!("CacheStore cannot be used on the worker node! cache_hset was used.")
+    }
+
+    async fn cache_hget(&self, _path: String, _field: String) -> Result<Option<String>, CubeError> {
+        panic!("CacheStore cannot be used on the worker node! cache_hget was used.")
+    }
+
+    async fn cache_hincrby(
+        &self,
+        _path: String,
+        _field: String,
+        _increment: i64,
+        _ttl: Option<u32>,
+    ) -> Result<i64, CubeError> {
+        panic!("CacheStore cannot be used on the worker node! cache_hincrby was used.")
+    }
+
+    async fn cache_zadd(
+        &self,
+        _path: String,
+        _score: f64,
+        _member: String,
+        _ttl: Option<u32>,
+    ) -> Result<bool, CubeError> {
+        panic!("CacheStore cannot be used on the worker node! cache_zadd was used.")
+    }
+
+    async fn cache_zrange_by_score(
+        &self,
+        _path: String,
+        _min: f64,
+        _max: f64,
+        _limit: Option<usize>,
+    ) -> Result<Vec<CacheSortedSetEntry>, CubeError> {
+        panic!("CacheStore cannot be used on the worker node! cache_zrange_by_score was used.")
+    }
+
+    async fn cache_zrem_range_by_score(
+        &self,
+        _path: String,
+        _min: f64,
+        _max: f64,
+    ) -> Result<u64, CubeError> {
+        panic!("CacheStore cannot be used on the worker node! cache_zrem_range_by_score was used.")
+    }
+
+    async fn cache_publish(&self, _channel: String, _message: String) -> Result<(), CubeError> {
+        panic!("CacheStore cannot be used on the worker node! cache_publish was used.")
+    }
+
+    async fn cache_subscribe(
+        &self,
+        _channel: String,
+        _timeout: u64,
+        _after: Option<u64>,
+    ) -> Result<Vec<CacheChannelMessage>, CubeError> {
+        panic!("CacheStore cannot be used on the worker node! cache_subscribe was used.")
+    }
+
     async fn queue_all(&self, _limit: Option<usize>) -> Result<Vec<QueueAllItem>, CubeError> {
         panic!("CacheStore cannot be used on the worker node! queue_all was used.")
     }
@@ -1658,6 +2398,10 @@ impl CacheStore for ClusterCacheStoreClient {
         panic!("CacheStore cannot be used on the worker node! queue_merge_extra was used.")
     }
 
+    async fn queue_dead_letter_requeue(&self, _key: QueueKey) -> Result<bool, CubeError> {
+        panic!("CacheStore cannot be used on the worker node! queue_dead_letter_requeue was used.")
+    }
+
     async fn compaction(&self) -> Result<(), CubeError> {
         panic!("CacheStore cannot be used on the worker node! compaction was used.")
     }
@@ -1771,6 +2515,149 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_cache_collection_entries() -> Result<(), CubeError> {
+        init_test_logger().await;
+
+        let (_, cachestore) = RocksCacheStore::prepare_test_cachestore(
+            "cache_collection_entries",
+            Config::test("cachestore_collection_entries"),
+        );
+
+        let path = "prefix:hash".to_string();
+        assert!(
+            cachestore
+                .cache_hset(path.clone(), "a".to_string(), "1".to_string(), None)
+                .await?
+        );
+        assert!(
+            !cachestore
+                .cache_hset(path.clone(), "a".to_string(), "2".to_string(), None)
+                .await?
+        );
+        assert_eq!(
+            cachestore
+                .cache_hincrby(path.clone(), "b".to_string(), 3, None)
+                .await?,
+            3
+        );
+
+        // Header and every field are separate items, an update doesn't rewrite other fields
+        let header = cachestore.cache_get(path.clone()).await?.unwrap();
+        let fields = cachestore
+            .cache_keys(CacheItem::collection_prefix(header.get_id()))
+            .await?;
+        assert_eq!(
+            fields
+                .iter()
+                .map(|f| (
+                    f.get_row().get_key().as_str(),
+                    f.get_row().get_value().as_str()
+                ))
+                .sorted()
+                .collect::<Vec<_>>(),
+            vec![("a", "2"), ("b", "3")]
+        );
+
+        // Removal of the key removes its fields, a new hash doesn't see them
+        cachestore.cache_delete(path.clone()).await?;
+        assert_eq!(
+            cachestore
+                .cache_keys(CacheItem::collection_prefix(header.get_id()))
+                .await?
+                .len(),
+            0
+        );
+        assert_eq!(
+            cachestore.cache_hget(path.clone(), "a".to_string()).await?,
+            None
+        );
+
+        assert!(cachestore
+            .cache_zadd(path.clone(), 1.0, "m".to_string(), None)
+            .await
+            .is_ok());
+        assert!(cachestore
+            .cache_hget(path.clone(), "m".to_string())
+            .await
+            .is_err());
+
+        RocksCacheStore::cleanup_test_cachestore("cache_collection_entries");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cache_publish_subscribe() -> Result<(), CubeError> {
+        init_test_logger().await;
+
+        let (_, cachestore) = RocksCacheStore::prepare_test_cachestore(
+            "cache_publish_subscribe",
+            Config::test("cachestore_publish_subscribe"),
+        );
+
+        let start = cachestore.last_message_cursor.load(Ordering::SeqCst);
+        cachestore
+            .cache_publish("events".to_string(), "1".to_string())
+            .await?;
+        cachestore
+            .cache_publish("other".to_string(), "skipped".to_string())
+            .await?;
+        cachestore
+            .cache_publish("events".to_string(), "2".to_string())
+            .await?;
+
+        // Messages published between polls are returned to the subscriber with a cursor
+        let messages = cachestore
+            .cache_subscribe("events".to_string(), 10, Some(start))
+            .await?;
+        assert_eq!(
+            messages
+                .iter()
+                .map(|m| m.message.as_str())
+                .collect::<Vec<_>>(),
+            vec!["1", "2"]
+        );
+        assert!(messages[0].cursor < messages[1].cursor);
+
+        assert!(cachestore
+            .cache_subscribe("events".to_string(), 10, Some(messages[1].cursor))
+            .await?
+            .is_empty());
+        // Without cursor only new messages are received
+        assert!(cachestore
+            .cache_subscribe("events".to_string(), 10, None)
+            .await?
+            .is_empty());
+
+        // Waiting subscriber is woken up by the listener event of the publish
+        let subscriber = {
+            let cachestore = cachestore.clone();
+            tokio::spawn(async move {
+                cachestore
+                    .cache_subscribe("events".to_string(), 5000, None)
+                    .await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        cachestore
+            .cache_publish("events".to_string(), "3".to_string())
+            .await?;
+
+        let messages = subscriber.await.unwrap()?;
+        assert_eq!(
+            messages
+                .iter()
+                .map(|m| m.message.as_str())
+                .collect::<Vec<_>>(),
+            vec!["3"]
+        );
+
+        RocksCacheStore::cleanup_test_cachestore("cache_publish_subscribe");
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_cache_set_max_entry_size() -> Result<(), CubeError> {
         init_test_logger().await;
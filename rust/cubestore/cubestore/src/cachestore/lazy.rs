@@ -5,8 +5,8 @@ use crate::cachestore::cache_rocksstore::{
 };
 use crate::cachestore::queue_item::QueueRetrieveResponse;
 use crate::cachestore::{
-    CacheItem, CacheStore, QueueCancelResponse, QueueItem, QueueItemStatus, QueueKey, QueueResult,
-    QueueResultResponse, RocksCacheStore,
+    CacheChannelMessage, CacheItem, CacheSortedSetEntry, CacheStore, QueueCancelResponse,
+    QueueItem, QueueItemStatus, QueueKey, QueueResult, QueueResultResponse, RocksCacheStore,
 };
 use crate::config::ConfigObj;
 use crate::metastore::{IdRow, MetaStoreEvent, MetaStoreFs, RocksPropertyRow};
@@ -207,6 +207,87 @@ impl CacheStore for LazyRocksCacheStore {
         self.init().await?.cache_incr(path).await
     }
 
+    async fn cache_hset(
+        &self,
+        path: String,
+        field: String,
+        value: String,
+        ttl: Option<u32>,
+    ) -> Result<bool, CubeError> {
+        self.init().await?.cache_hset(path, field, value, ttl).await
+    }
+
+    async fn cache_hget(&self, path: String, field: String) -> Result<Option<String>, CubeError> {
+        self.init().await?.cache_hget(path, field).await
+    }
+
+    async fn cache_hincrby(
+        &self,
+        path: String,
+        field: String,
+        increment: i64,
+        ttl: Option<u32>,
+    ) -> Result<i64, CubeError> {
+        self.init()
+            .await?
+            .cache_hincrby(path, field, increment, ttl)
+            .await
+    }
+
+    async fn cache_zadd(
+        &self,
+        path: String,
+        score: f64,
+        member: String,
+        ttl: Option<u32>,
+    ) -> Result<bool, CubeError> {
+        self.init()
+            .await?
+            .cache_zadd(path, score, member, ttl)
+            .await
+    }
+
+    async fn cache_zrange_by_score(
+        &self,
+        path: String,
+        min: f64,
+        max: f64,
+        limit: Option<usize>,
+    ) -> Result<Vec<CacheSortedSetEntry>, CubeError> {
+        self.init()
+            .await?
+            .cache_zrange_by_score(path, min, max, limit)
+            .await
+    }
+
+    async fn cache_zrem_range_by_score(
+        &self,
+        path: String,
+        min: f64,
+        max: f64,
+    ) -> Result<u64, CubeError> {
+        self.init()
+            .await?
+            .cache_zrem_range_by_score(path, min, max)
+            .await
+    }
+
+    async fn cache_publish(&self, channel: String, message: String) -> Result<(), CubeError> {
+        self.init().await?.cache_publish(channel, message).await
+    }
+
+    async fn cache_subscribe(
+        &self,
+        channel: String,
+        timeout: u64,
+        after: Option<u64>,
+    ) -> Result<Vec<CacheChannelMessage>, CubeError> {
+        self.init()
+            .await?
+            .cache_subscribe(channel, timeout, after)
+            .await
+    }
+
     async fn queue_all(&self, limit: Option<usize>) -> Result<Vec<QueueAllItem>, CubeError> {
         self.init().await?.queue_all(limit).await
     }
@@ -301,6 +382,10 @@ impl CacheStore for LazyRocksCacheStore {
         self.init().await?.queue_merge_extra(key, payload).await
     }
 
+    async fn queue_dead_letter_requeue(&self, key: QueueKey) -> Result<bool, CubeError> {
+        self.init().await?.queue_dead_letter_requeue(key).await
+    }
+
     async fn compaction(&self) -> Result<(), CubeError> {
         self.init().await?.compaction().await
     }
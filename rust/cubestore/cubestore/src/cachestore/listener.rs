@@ -1,6 +1,8 @@
 use crate::cachestore::{QueueKey, QueueResultAckEvent};
 use crate::metastore::MetaStoreEvent;
 use crate::CubeError;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::broadcast::Receiver;
 
 pub struct RocksCacheStoreListener {
@@ -49,4 +51,34 @@ impl RocksCacheStoreListener {
             }
         }
     }
+
+    /// Waits for a message published to the channel after the cursor. Returns None when the
+    /// listener lagged behind and events were dropped, stored messages must be checked instead.
+    pub async fn wait_for_channel_message(
+        mut self,
+        channel: &str,
+        after: u64,
+    ) -> Result<Option<CacheChannelMessage>, CubeError> {
+        loop {
+            let event = match self.receiver.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(_)) => return Ok(None),
+                Err(e) => return Err(e.into()),
+            };
+            if let MetaStoreEvent::CachePublish(message) = event {
+                if message.channel == channel && message.cursor > after {
+                    return Ok(Some(message));
+                }
+            }
+        }
+    }
+}
+
+/// Message of the pub/sub channel. Subscriber passes the cursor of the last received message
+/// to the next SUBSCRIBE, so messages published between polls are not lost.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct CacheChannelMessage {
+    pub cursor: u64,
+    pub channel: String,
+    pub message: String,
 }
@@ -12,13 +12,14 @@ mod scheduler;
 pub use cache_eviction_manager::{
     CacheEvictionManager, CacheEvictionPolicy, EvictionFinishedResult, EvictionResult,
 };
-pub use cache_item::CacheItem;
+pub use cache_item::{CacheItem, CacheSortedSetEntry};
 pub use cache_rocksstore::{
     CacheStore, CacheStoreRpcClient, CachestoreInfo, ClusterCacheStoreClient, QueueAddPayload,
     QueueAddResponse, QueueAllItem, QueueCancelResponse, QueueGetResponse, QueueKey, QueueListItem,
     QueueResultResponse, RocksCacheStore,
 };
 pub use lazy::LazyRocksCacheStore;
+pub use listener::CacheChannelMessage;
 pub use queue_item::{QueueItem, QueueItemStatus, QueueResultAckEvent, QueueRetrieveResponse};
 pub use queue_item_payload::QueueItemPayload;
 pub use queue_result::QueueResult;
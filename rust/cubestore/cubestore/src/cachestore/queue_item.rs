@@ -57,6 +57,8 @@ pub enum QueueItemStatus {
     Pending = 0,
     Active = 1,
     Finished = 2,
+    // Item exhausted its attempts and waits in dead-letter list
+    DeadLetter = 3,
 }
 
 impl ToString for QueueItemStatus {
@@ -65,6 +67,7 @@ impl ToString for QueueItemStatus {
             QueueItemStatus::Pending => "pending".to_string(),
             QueueItemStatus::Active => "active".to_string(),
             QueueItemStatus::Finished => "finished".to_string(),
+            QueueItemStatus::DeadLetter => "dead_letter".to_string(),
         }
     }
 }
@@ -86,6 +89,14 @@ pub struct QueueItem {
     orphaned: Option<DateTime<Utc>>,
     #[serde(with = "ts_seconds")]
     expire: DateTime<Utc>,
+    // Number of times the item was retrieved
+    #[serde(default)]
+    attempts: u32,
+    #[serde(default)]
+    max_attempts: Option<u32>,
+    // Item cannot be retrieved before this moment (delayed delivery or retry backoff)
+    #[serde(default, with = "ts_seconds_option")]
+    visible_at: Option<DateTime<Utc>>,
 }
 
 impl RocksEntity for QueueItem {
@@ -156,6 +167,79 @@ impl QueueItem {
                 created.clone() + Duration::hours(4)
             },
             created,
+            attempts: 0,
+            max_attempts: None,
+            visible_at: None,
+        }
+    }
+
+    pub fn with_delay(mut self, delay: Option<u32>) -> Self {
+        if let Some(delay) = delay {
+            let visible_at = self.created + Duration::seconds(delay as i64);
+            self.reschedule(visible_at);
+        }
+
+        self
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: Option<u32>) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Moves the moment when the item becomes visible, keeping the orphaned timeout
+    /// relative to it.
+    fn reschedule(&mut self, visible_at: DateTime<Utc>) {
+        let orphaned_timeout = self
+            .orphaned
+            .map(|orphaned| orphaned - self.visible_at.unwrap_or(self.created));
+
+        self.visible_at = Some(visible_at);
+        self.orphaned = orphaned_timeout.map(|timeout| visible_at + timeout);
+        self.expire = if let Some(orphaned) = self.orphaned {
+            orphaned + Duration::hours(2)
+        } else {
+            visible_at + Duration::hours(4)
+        };
+    }
+
+    /// Exponential backoff for the next attempt: `base * 2^(attempts - 1)`, capped to 1 hour.
+    pub fn retry_backoff(&self, base: u64) -> Duration {
+        let exp = self.attempts.saturating_sub(1).min(16);
+        let secs = base.saturating_mul(1 << exp).min(60 * 60);
+
+        Duration::seconds(secs as i64)
+    }
+
+    pub fn has_attempts_left(&self) -> bool {
+        match self.max_attempts {
+            Some(max_attempts) => self.attempts < max_attempts,
+            None => true,
+        }
+    }
+
+    pub fn schedule_retry(&mut self, now: DateTime<Utc>, backoff: Duration) {
+        self.status = QueueItemStatus::Pending;
+        self.heartbeat = None;
+        self.reschedule(now + backoff);
+    }
+
+    pub fn move_to_dead_letter(&mut self, now: DateTime<Utc>, expire: Duration) {
+        self.status = QueueItemStatus::DeadLetter;
+        self.heartbeat = None;
+        self.expire = now + expire;
+    }
+
+    pub fn requeue_from_dead_letter(&mut self, now: DateTime<Utc>) {
+        self.status = QueueItemStatus::Pending;
+        self.attempts = 0;
+        self.reschedule(now);
+    }
+
+    pub fn is_visible(&self, now: &DateTime<Utc>) -> bool {
+        match &self.visible_at {
+            Some(visible_at) => visible_at <= now,
+            None => true,
         }
     }
 
@@ -213,6 +297,18 @@ impl QueueItem {
         &self.expire
     }
 
+    pub fn get_attempts(&self) -> &u32 {
+        &self.attempts
+    }
+
+    pub fn get_max_attempts(&self) -> &Option<u32> {
+        &self.max_attempts
+    }
+
+    pub fn get_visible_at(&self) -> &Option<DateTime<Utc>> {
+        &self.visible_at
+    }
+
     pub fn status_default() -> QueueItemStatus {
         QueueItemStatus::Pending
     }
@@ -221,6 +317,10 @@ impl QueueItem {
         self.heartbeat = Some(Utc::now());
     }
 
+    pub fn increment_attempts(&mut self) {
+        self.attempts += 1;
+    }
+
     pub fn merge_extra(&self, payload: String) -> Result<Self, CubeError> {
         let mut new = self.clone();
 
@@ -397,6 +497,7 @@ impl RocksSecondaryIndex<QueueItem, QueueItemIndexKey> for QueueItemRocksIndex {
                     QueueItemStatus::Pending => r.push(0_u8),
                     QueueItemStatus::Active => r.push(1_u8),
                     QueueItemStatus::Finished => r.push(2_u8),
+                    QueueItemStatus::DeadLetter => r.push(3_u8),
                 }
 
                 r
@@ -496,4 +597,42 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_queue_item_retry_policy() -> Result<(), CubeError> {
+        let mut item = QueueItem::new("q:1".to_string(), QueueItemStatus::Pending, 0, Some(10))
+            .with_delay(Some(30))
+            .with_max_attempts(Some(3));
+
+        assert_eq!(item.is_visible(item.get_created()), false);
+        assert_eq!(
+            item.get_orphaned().unwrap() - item.get_visible_at().unwrap(),
+            Duration::seconds(10)
+        );
+
+        let mut backoffs = vec![];
+        while item.has_attempts_left() {
+            item.increment_attempts();
+            backoffs.push(item.retry_backoff(5).num_seconds());
+        }
+
+        assert_eq!(backoffs, vec![5, 10, 20]);
+
+        let now = Utc::now();
+        item.schedule_retry(now.clone(), Duration::seconds(20));
+        assert_eq!(item.get_status(), &QueueItemStatus::Pending);
+        assert_eq!(item.is_visible(&now), false);
+        assert_eq!(item.get_orphaned(), &Some(now + Duration::seconds(30)));
+
+        item.move_to_dead_letter(now.clone(), Duration::days(1));
+        assert_eq!(item.get_status(), &QueueItemStatus::DeadLetter);
+        assert_eq!(item.get_expire(), &(now + Duration::days(1)));
+
+        item.requeue_from_dead_letter(now.clone());
+        assert_eq!(item.get_status(), &QueueItemStatus::Pending);
+        assert_eq!(item.get_attempts(), &0);
+        assert_eq!(item.is_visible(&now), true);
+
+        Ok(())
+    }
 }
@@ -39,6 +39,14 @@ impl QueueItemPayload {
     pub fn get_created(&self) -> &DateTime<Utc> {
         &self.created
     }
+
+    pub fn with_expire(&self, expire: DateTime<Utc>) -> Self {
+        Self {
+            value: self.value.clone(),
+            created: self.created.clone(),
+            expire,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
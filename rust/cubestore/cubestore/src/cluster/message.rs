@@ -1,6 +1,8 @@
 use crate::metastore::{MetaStoreRpcMethodCall, MetaStoreRpcMethodResult};
 use crate::queryplanner::query_executor::SerializedRecordBatchStream;
+use crate::queryplanner::running_queries::RunningQueryStatus;
 use crate::queryplanner::serialized_plan::SerializedPlan;
+use crate::queryplanner::unload::{UnloadTarget, UnloadedFile};
 use crate::CubeError;
 use datafusion::arrow::datatypes::SchemaRef;
 use serde::{Deserialize, Serialize};
@@ -51,6 +53,20 @@ pub enum NetworkMessage {
 
     NotifyJobListeners,
     NotifyJobListenersSuccess,
+
+    /// Kill local parts of the query, the result is true if the query was running on the node.
+    KillQuery(u64),
+    KillQueryResult(Result<bool, CubeError>),
+
+    /// Route unload to a worker, which will act as the main node for the query.
+    RouterUnload(SerializedPlan, UnloadTarget),
+    /// Write partial results of the worker into a file with the specified name.
+    Unload(SerializedPlan, UnloadTarget, String),
+    UnloadResult(Result<Vec<UnloadedFile>, CubeError>),
+
+    /// List queries and query parts running on the node.
+    ListQueries,
+    ListQueriesResult(Result<Vec<RunningQueryStatus>, CubeError>),
 }
 
 const MAGIC: u32 = 94107;
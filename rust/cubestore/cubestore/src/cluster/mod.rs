@@ -38,7 +38,9 @@ use crate::metastore::{
     MetaStoreRpcServer,
 };
 use crate::queryplanner::query_executor::{QueryExecutor, SerializedRecordBatchStream};
+use crate::queryplanner::running_queries::{RunningQueries, RunningQueryInfo, RunningQueryStatus};
 use crate::queryplanner::serialized_plan::SerializedPlan;
+use crate::queryplanner::unload::{UnloadTarget, UnloadedFile};
 use crate::remotefs::RemoteFs;
 use crate::store::ChunkDataStore;
 use crate::telemetry::tracing::{TraceIdAndSpanId, TracingHelper};
@@ -94,6 +96,23 @@ pub trait Cluster: DIService + Send + Sync {
         plan: SerializedPlan,
     ) -> Result<(SchemaRef, Vec<SerializedRecordBatchStream>), CubeError>;
 
+    /// Send full unload to a worker, which will act as the main node for the query.
+    async fn route_unload(
+        &self,
+        node_name: &str,
+        plan: SerializedPlan,
+        target: UnloadTarget,
+    ) -> Result<Vec<UnloadedFile>, CubeError>;
+
+    /// Runs select on a single worker node and writes its partial results into `target`.
+    async fn run_unload(
+        &self,
+        node_name: &str,
+        plan: SerializedPlan,
+        target: UnloadTarget,
+        file_name: String,
+    ) -> Result<Option<UnloadedFile>, CubeError>;
+
     /// Runs select on a single worker node to get partial results from that worker.
     async fn run_select(
         &self,
@@ -164,6 +183,12 @@ pub trait Cluster: DIService + Send + Sync {
         location: &str,
     ) -> Result<String, CubeError>;
 
+    /// Kills parts of the query running on the node, returns true if there were any.
+    async fn kill_query(&self, node_name: &str, query_id: u64) -> Result<bool, CubeError>;
+
+    /// Lists queries and query parts running on the node.
+    async fn list_queries(&self, node_name: &str) -> Result<Vec<RunningQueryStatus>, CubeError>;
+
     async fn process_message_on_worker(&self, m: NetworkMessage) -> NetworkMessage;
 
     async fn process_metastore_message(&self, m: NetworkMessage) -> NetworkMessage;
@@ -463,6 +488,37 @@ impl Cluster for ClusterImpl {
         }
     }
 
+    async fn route_unload(
+        &self,
+        node_name: &str,
+        plan: SerializedPlan,
+        target: UnloadTarget,
+    ) -> Result<Vec<UnloadedFile>, CubeError> {
+        let response = self
+            .send_or_process_locally(node_name, NetworkMessage::RouterUnload(plan, target))
+            .await?;
+        match response {
+            NetworkMessage::UnloadResult(r) => r,
+            x => panic!("Unexpected result for route unload: {:?}", x),
+        }
+    }
+
+    async fn run_unload(
+        &self,
+        node_name: &str,
+        plan: SerializedPlan,
+        target: UnloadTarget,
+        file_name: String,
+    ) -> Result<Option<UnloadedFile>, CubeError> {
+        let response = self
+            .send_or_process_locally(node_name, NetworkMessage::Unload(plan, target, file_name))
+            .await?;
+        match response {
+            NetworkMessage::UnloadResult(r) => r.map(|files| files.into_iter().next()),
+            x => panic!("Unexpected result for unload: {:?}", x),
+        }
+    }
+
     #[instrument(level = "trace", skip(self, plan_node))]
     async fn run_select(
         &self,
@@ -555,6 +611,26 @@ impl Cluster for ClusterImpl {
         }
     }
 
+    async fn kill_query(&self, node_name: &str, query_id: u64) -> Result<bool, CubeError> {
+        let response = self
+            .send_or_process_locally(node_name, NetworkMessage::KillQuery(query_id))
+            .await?;
+        match response {
+            NetworkMessage::KillQueryResult(r) => r,
+            x => panic!("Unexpected result for kill query: {:?}", x),
+        }
+    }
+
+    async fn list_queries(&self, node_name: &str) -> Result<Vec<RunningQueryStatus>, CubeError> {
+        let response = self
+            .send_or_process_locally(node_name, NetworkMessage::ListQueries)
+            .await?;
+        match response {
+            NetworkMessage::ListQueriesResult(r) => r,
+            x => panic!("Unexpected result for list queries: {:?}", x),
+        }
+    }
+
     async fn free_memory_chunk(
         &self,
         node_name: &str,
@@ -666,9 +742,13 @@ impl Cluster for ClusterImpl {
     async fn process_message_on_worker(&self, m: NetworkMessage) -> NetworkMessage {
         match m {
             NetworkMessage::RouterSelect(plan) => {
+                let running_query = plan.running_query().cloned();
                 let res = self
-                    .query_executor
-                    .execute_router_plan(plan, self.this.upgrade().unwrap())
+                    .run_query_part(
+                        running_query,
+                        self.query_executor
+                            .execute_router_plan(plan, self.this.upgrade().unwrap()),
+                    )
                     .await
                     .and_then(|(schema, records)| {
                         let records = SerializedRecordBatchStream::write(&schema, records)?;
@@ -677,7 +757,10 @@ impl Cluster for ClusterImpl {
                 NetworkMessage::SelectResult(res)
             }
             NetworkMessage::Select(plan) => {
-                let res = self.run_local_select_worker(plan).await;
+                let running_query = plan.running_query().cloned();
+                let res = self
+                    .run_query_part(running_query, self.run_local_select_worker(plan))
+                    .await;
                 NetworkMessage::SelectResult(res)
             }
             NetworkMessage::ExplainAnalyze(plan) => {
@@ -752,6 +835,52 @@ impl Cluster for ClusterImpl {
             NetworkMessage::NotifyJobListenersSuccess => {
                 panic!("NotifyJobListenersSuccess sent to worker")
             }
+            NetworkMessage::KillQuery(query_id) => {
+                let killed = self.running_queries().await.kill(query_id);
+                NetworkMessage::KillQueryResult(Ok(killed))
+            }
+            NetworkMessage::KillQueryResult(_) => {
+                panic!("KillQueryResult sent to worker")
+            }
+            NetworkMessage::ListQueries => {
+                NetworkMessage::ListQueriesResult(Ok(self.running_queries().await.statuses()))
+            }
+            NetworkMessage::ListQueriesResult(_) => {
+                panic!("ListQueriesResult sent to worker")
+            }
+            NetworkMessage::RouterUnload(plan, target) => {
+                let running_query = plan.running_query().cloned();
+                let res = match self.remote_fs.uploads_dir().await {
+                    Ok(uploads_dir) => {
+                        self.run_query_part(
+                            running_query,
+                            self.query_executor.execute_router_unload(
+                                plan,
+                                self.this.upgrade().unwrap(),
+                                target,
+                                uploads_dir,
+                            ),
+                        )
+                        .await
+                    }
+                    Err(e) => Err(e),
+                };
+                NetworkMessage::UnloadResult(res)
+            }
+            NetworkMessage::Unload(plan, target, file_name) => {
+                let running_query = plan.running_query().cloned();
+                let res = self
+                    .run_query_part(
+                        running_query,
+                        self.run_local_unload_worker(plan, target, file_name),
+                    )
+                    .await
+                    .map(|f| f.into_iter().collect());
+                NetworkMessage::UnloadResult(res)
+            }
+            NetworkMessage::UnloadResult(_) => {
+                panic!("UnloadResult sent to worker")
+            }
             NetworkMessage::SelectStart(..)
             | NetworkMessage::SelectResultSchema(..)
             | NetworkMessage::SelectResultBatch(..) => {
@@ -1210,6 +1339,29 @@ impl ClusterImpl {
         .await
     }
 
+    async fn running_queries(&self) -> Arc<RunningQueries> {
+        self.injector
+            .upgrade()
+            .unwrap()
+            .get_service_typed::<RunningQueries>()
+            .await
+    }
+
+    /// Registers the query part on this node, so it's visible in `system.queries`
+    /// and can be interrupted by `KILL QUERY`.
+    async fn run_query_part<T>(
+        &self,
+        running_query: Option<RunningQueryInfo>,
+        f: impl Future<Output = Result<T, CubeError>>,
+    ) -> Result<T, CubeError> {
+        if let Some(running_query) = running_query {
+            let guard = self.running_queries().await.start_part(&running_query);
+            guard.run(f).await
+        } else {
+            f.await
+        }
+    }
+
     async fn run_local_select_worker(
         &self,
         plan_node: SerializedPlan,
@@ -1246,14 +1398,69 @@ impl ClusterImpl {
             }
         }
     }
-    #[instrument(level = "trace", skip(self, plan_node))]
-    async fn run_local_select_worker_impl(
+
+    async fn run_local_unload_worker(
         &self,
         plan_node: SerializedPlan,
-    ) -> Result<(SchemaRef, Vec<SerializedRecordBatchStream>, usize), CubeError> {
+        target: UnloadTarget,
+        file_name: String,
+    ) -> Result<Option<UnloadedFile>, CubeError> {
+        let wait_ms = self
+            .process_rate_limiter
+            .wait_for_allow(
+                TaskType::Select,
+                Some(Duration::from_secs(self.config_obj.query_timeout())),
+            )
+            .await?;
+        let trace_index = TraceIndex {
+            table_id: None,
+            trace_obj: plan_node.trace_obj(),
+        };
+        let res = self
+            .run_local_unload_worker_impl(plan_node, target, file_name)
+            .await;
+        let data_loaded_size = res.as_ref().map(|(_, size)| *size).unwrap_or(0);
+        self.process_rate_limiter
+            .commit_task_usage(
+                TaskType::Select,
+                data_loaded_size as i64,
+                wait_ms,
+                trace_index,
+            )
+            .await;
+        res.map(|(file, _)| file)
+    }
+
+    /// Unlike selects, unloads run outside of the select process pool, so results are written
+    /// into the file as they are computed instead of being sent back in one piece.
+    async fn run_local_unload_worker_impl(
+        &self,
+        plan_node: SerializedPlan,
+        target: UnloadTarget,
+        file_name: String,
+    ) -> Result<(Option<UnloadedFile>, usize), CubeError> {
+        let (remote_to_local_names, chunk_id_to_record_batches) =
+            self.load_select_worker_inputs(&plan_node).await?;
+        let uploads_dir = self.remote_fs.uploads_dir().await?;
+        self.query_executor
+            .execute_worker_unload(
+                plan_node,
+                remote_to_local_names,
+                chunk_id_to_record_batches,
+                target,
+                uploads_dir,
+                file_name,
+            )
+            .await
+    }
+
+    /// Downloads files and loads in-memory chunks the worker part of the query reads.
+    async fn load_select_worker_inputs(
+        &self,
+        plan_node: &SerializedPlan,
+    ) -> Result<(HashMap<String, String>, HashMap<u64, Vec<RecordBatch>>), CubeError> {
         let start = SystemTime::now();
-        debug!("Running select");
-        let remote_to_local_names = self.warmup_select_worker_files(&plan_node).await?;
+        let remote_to_local_names = self.warmup_select_worker_files(plan_node).await?;
         let warmup = start.elapsed()?;
         if warmup.as_millis() > 200 {
             warn!("Warmup download for select ({:?})", warmup);
@@ -1292,6 +1499,18 @@ impl ClusterImpl {
                     .into_iter(),
             )
             .collect::<HashMap<_, _>>();
+        Ok((remote_to_local_names, chunk_id_to_record_batches))
+    }
+
+    #[instrument(level = "trace", skip(self, plan_node))]
+    async fn run_local_select_worker_impl(
+        &self,
+        plan_node: SerializedPlan,
+    ) -> Result<(SchemaRef, Vec<SerializedRecordBatchStream>, usize), CubeError> {
+        let start = SystemTime::now();
+        debug!("Running select");
+        let (remote_to_local_names, chunk_id_to_record_batches) =
+            self.load_select_worker_inputs(&plan_node).await?;
 
         let mut res = None;
         #[cfg(not(target_os = "windows"))]
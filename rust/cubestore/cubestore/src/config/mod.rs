@@ -22,6 +22,8 @@ use crate::metastore::{
 };
 use crate::mysql::{MySqlServer, SqlAuthDefaultImpl, SqlAuthService};
 use crate::queryplanner::query_executor::{QueryExecutor, QueryExecutorImpl};
+use crate::queryplanner::running_queries::RunningQueries;
+use crate::queryplanner::unload::UnloadStorage;
 use crate::queryplanner::{QueryPlanner, QueryPlannerImpl};
 use crate::remotefs::cleanup::RemoteFsCleanup;
 use crate::remotefs::gcs::GCSRemoteFs;
@@ -354,6 +356,51 @@ pub enum FileStoreProvider {
     },
 }
 
+/// Settings to access `s3://` import locations with. Import specific env vars take precedence
+/// over the ones of the remote storage, so S3 compatible storages (e.g. MinIO) work for imports
+/// too.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportS3Config {
+    pub region: Option<String>,
+    /// Custom endpoint of an S3 compatible storage.
+    pub endpoint: Option<String>,
+    pub access_key_id: Option<String>,
+    pub secret_access_key: Option<String>,
+}
+
+impl ImportS3Config {
+    pub fn from_env(store_provider: &FileStoreProvider) -> ImportS3Config {
+        let (region, endpoint, access_key_id, secret_access_key) = match store_provider {
+            FileStoreProvider::MINIO { .. } => (
+                "CUBESTORE_MINIO_REGION",
+                Some("CUBESTORE_MINIO_SERVER_ENDPOINT"),
+                "CUBESTORE_MINIO_ACCESS_KEY_ID",
+                "CUBESTORE_MINIO_SECRET_ACCESS_KEY",
+            ),
+            _ => (
+                "CUBESTORE_S3_REGION",
+                None,
+                "CUBESTORE_AWS_ACCESS_KEY_ID",
+                "CUBESTORE_AWS_SECRET_ACCESS_KEY",
+            ),
+        };
+        let var = |import_name: &str, name: Option<&str>| {
+            env::var(import_name)
+                .ok()
+                .or_else(|| name.and_then(|n| env::var(n).ok()))
+        };
+        ImportS3Config {
+            region: var("CUBESTORE_IMPORT_S3_REGION", Some(region)),
+            endpoint: var("CUBESTORE_IMPORT_S3_ENDPOINT", endpoint),
+            access_key_id: var("CUBESTORE_IMPORT_AWS_ACCESS_KEY_ID", Some(access_key_id)),
+            secret_access_key: var(
+                "CUBESTORE_IMPORT_AWS_SECRET_ACCESS_KEY",
+                Some(secret_access_key),
+            ),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Config {
     config_obj: Arc<ConfigObjImpl>,
@@ -472,6 +519,10 @@ pub trait ConfigObj: DIService {
 
     fn cachestore_queue_results_expire(&self) -> u64;
 
+    fn cachestore_queue_retry_backoff(&self) -> u64;
+
+    fn cachestore_queue_dead_letter_expire(&self) -> u64;
+
     fn cachestore_metrics_interval(&self) -> u64;
 
     fn download_concurrency(&self) -> u64;
@@ -500,6 +551,8 @@ pub trait ConfigObj: DIService {
 
     fn query_queue_cache_max_capacity(&self) -> u64;
 
+    fn query_max_memory(&self) -> u64;
+
     fn query_cache_time_to_idle_secs(&self) -> Option<u64>;
 
     fn metadata_cache_max_capacity_bytes(&self) -> u64;
@@ -516,8 +569,16 @@ pub trait ConfigObj: DIService {
 
     fn skip_kafka_parsing_errors(&self) -> bool;
 
+    fn native_column_types(&self) -> bool;
+
+    fn retention_purge_interval_secs(&self) -> u64;
+
     fn dump_dir(&self) -> &Option<PathBuf>;
 
+    fn unload_local_dir(&self) -> &Option<PathBuf>;
+
+    fn import_s3_config(&self) -> &ImportS3Config;
+
     fn minimum_metastore_snapshots_count(&self) -> u64;
 
     fn metastore_snapshots_lifetime(&self) -> u64;
@@ -567,7 +628,9 @@ pub struct ConfigObjImpl {
     pub wal_split_threshold: u64,
     pub data_dir: PathBuf,
     pub dump_dir: Option<PathBuf>,
+    pub unload_local_dir: Option<PathBuf>,
     pub store_provider: FileStoreProvider,
+    pub import_s3_config: ImportS3Config,
     pub select_worker_pool_size: usize,
     pub select_worker_idle_timeout: u64,
     pub job_runners_count: usize,
@@ -601,6 +664,8 @@ pub struct ConfigObjImpl {
     pub cachestore_cache_compaction_trigger_size: u64,
     pub cachestore_cache_threshold_to_force_eviction: u8,
     pub cachestore_queue_results_expire: u64,
+    pub cachestore_queue_retry_backoff: u64,
+    pub cachestore_queue_dead_letter_expire: u64,
     pub cachestore_metrics_interval: u64,
     pub cachestore_cache_max_keys: u32,
     pub cachestore_cache_policy: CacheEvictionPolicy,
@@ -622,6 +687,8 @@ pub struct ConfigObjImpl {
     pub malloc_trim_every_secs: u64,
     pub query_cache_max_capacity_bytes: u64,
     pub query_queue_cache_max_capacity: u64,
+    /// Limit for the memory held by a single query on a node, 0 means unlimited.
+    pub query_max_memory: u64,
     pub query_cache_time_to_idle_secs: Option<u64>,
     pub metadata_cache_max_capacity_bytes: u64,
     pub metadata_cache_time_to_idle_secs: u64,
@@ -630,6 +697,8 @@ pub struct ConfigObjImpl {
     pub drop_ws_processing_messages_after_secs: u64,
     pub drop_ws_complete_messages_after_secs: u64,
     pub skip_kafka_parsing_errors: bool,
+    pub native_column_types: bool,
+    pub retention_purge_interval_secs: u64,
     pub minimum_metastore_snapshots_count: u64,
     pub metastore_snapshots_lifetime: u64,
     pub minimum_cachestore_snapshots_count: u64,
@@ -868,6 +937,14 @@ impl ConfigObj for ConfigObjImpl {
         self.cachestore_queue_results_expire
     }
 
+    fn cachestore_queue_retry_backoff(&self) -> u64 {
+        self.cachestore_queue_retry_backoff
+    }
+
+    fn cachestore_queue_dead_letter_expire(&self) -> u64 {
+        self.cachestore_queue_dead_letter_expire
+    }
+
     fn cachestore_metrics_interval(&self) -> u64 {
         self.cachestore_metrics_interval
     }
@@ -917,6 +994,9 @@ impl ConfigObj for ConfigObjImpl {
     fn query_queue_cache_max_capacity(&self) -> u64 {
         self.query_queue_cache_max_capacity
     }
+    fn query_max_memory(&self) -> u64 {
+        self.query_max_memory
+    }
     fn query_cache_time_to_idle_secs(&self) -> Option<u64> {
         self.query_cache_time_to_idle_secs
     }
@@ -949,10 +1029,26 @@ impl ConfigObj for ConfigObjImpl {
         self.skip_kafka_parsing_errors
     }
 
+    fn native_column_types(&self) -> bool {
+        self.native_column_types
+    }
+
+    fn retention_purge_interval_secs(&self) -> u64 {
+        self.retention_purge_interval_secs
+    }
+
     fn dump_dir(&self) -> &Option<PathBuf> {
         &self.dump_dir
     }
 
+    fn unload_local_dir(&self) -> &Option<PathBuf> {
+        &self.unload_local_dir
+    }
+
+    fn import_s3_config(&self) -> &ImportS3Config {
+        &self.import_s3_config
+    }
+
     fn minimum_metastore_snapshots_count(&self) -> u64 {
         self.minimum_metastore_snapshots_count
     }
@@ -1177,6 +1273,30 @@ impl Config {
 
     pub fn default() -> Config {
         let query_timeout = env_parse("CUBESTORE_QUERY_TIMEOUT", 120);
+        let store_provider = if let Ok(bucket_name) = env::var("CUBESTORE_S3_BUCKET") {
+            FileStoreProvider::S3 {
+                bucket_name,
+                region: env::var("CUBESTORE_S3_REGION")
+                    .expect("CUBESTORE_S3_REGION required when CUBESTORE_S3_BUCKET is set"),
+                sub_path: env::var("CUBESTORE_S3_SUB_PATH").ok(),
+            }
+        } else if let Ok(bucket_name) = env::var("CUBESTORE_MINIO_BUCKET") {
+            FileStoreProvider::MINIO {
+                bucket_name,
+                sub_path: env::var("CUBESTORE_MINIO_SUB_PATH").ok(),
+            }
+        } else if let Ok(bucket_name) = env::var("CUBESTORE_GCS_BUCKET") {
+            FileStoreProvider::GCS {
+                bucket_name,
+                sub_path: env::var("CUBESTORE_GCS_SUB_PATH").ok(),
+            }
+        } else if let Ok(remote_dir) = env::var("CUBESTORE_REMOTE_DIR") {
+            FileStoreProvider::Filesystem {
+                remote_dir: Some(PathBuf::from(remote_dir)),
+            }
+        } else {
+            FileStoreProvider::Filesystem { remote_dir: None }
+        };
         let query_cache_time_to_idle_secs = env_parse(
             "CUBESTORE_QUERY_CACHE_TIME_TO_IDLE",
             // 1 hour
@@ -1224,6 +1344,9 @@ impl Config {
                 dump_dir: env::var("CUBESTORE_DUMP_DIR")
                     .ok()
                     .map(|v| PathBuf::from(v)),
+                unload_local_dir: env::var("CUBESTORE_UNLOAD_LOCAL_DIR")
+                    .ok()
+                    .map(|v| PathBuf::from(v)),
                 partition_split_threshold: env_parse(
                     "CUBESTORE_PARTITION_SPLIT_THRESHOLD",
                     1048576 * 2,
@@ -1281,33 +1404,8 @@ impl Config {
                     "CUBESTORE_IN_MEMORY_CHUNKS_SCHEDULE_PERIOD_SECS",
                     5,
                 ),
-                store_provider: {
-                    if let Ok(bucket_name) = env::var("CUBESTORE_S3_BUCKET") {
-                        FileStoreProvider::S3 {
-                            bucket_name,
-                            region: env::var("CUBESTORE_S3_REGION").expect(
-                                "CUBESTORE_S3_REGION required when CUBESTORE_S3_BUCKET is set",
-                            ),
-                            sub_path: env::var("CUBESTORE_S3_SUB_PATH").ok(),
-                        }
-                    } else if let Ok(bucket_name) = env::var("CUBESTORE_MINIO_BUCKET") {
-                        FileStoreProvider::MINIO {
-                            bucket_name,
-                            sub_path: env::var("CUBESTORE_MINIO_SUB_PATH").ok(),
-                        }
-                    } else if let Ok(bucket_name) = env::var("CUBESTORE_GCS_BUCKET") {
-                        FileStoreProvider::GCS {
-                            bucket_name,
-                            sub_path: env::var("CUBESTORE_GCS_SUB_PATH").ok(),
-                        }
-                    } else if let Ok(remote_dir) = env::var("CUBESTORE_REMOTE_DIR") {
-                        FileStoreProvider::Filesystem {
-                            remote_dir: Some(PathBuf::from(remote_dir)),
-                        }
-                    } else {
-                        FileStoreProvider::Filesystem { remote_dir: None }
-                    }
-                },
+                import_s3_config: ImportS3Config::from_env(&store_provider),
+                store_provider,
                 select_worker_pool_size: env_parse("CUBESTORE_SELECT_WORKERS", 4),
                 select_worker_idle_timeout: env_parse_duration(
                     "CUBESTORE_SELECT_WORKERS_IDLE_TIMEOUT",
@@ -1394,6 +1492,19 @@ impl Config {
                     Some(60 * 5),
                     Some(1),
                 ),
+                cachestore_queue_retry_backoff: env_parse_duration(
+                    "CUBESTORE_QUEUE_RETRY_BACKOFF",
+                    5,
+                    Some(60 * 60),
+                    Some(1),
+                ),
+                cachestore_queue_dead_letter_expire: env_parse_duration(
+                    "CUBESTORE_QUEUE_DEAD_LETTER_EXPIRE",
+                    // 1 day
+                    60 * 60 * 24,
+                    Some(60 * 60 * 24 * 30),
+                    Some(60),
+                ),
                 cachestore_metrics_interval: env_parse_duration(
                     "CUBESTORE_CACHESTORE_METRICS_LOOP",
                     15,
@@ -1456,6 +1567,8 @@ impl Config {
                     "CUBESTORE_QUEUE_CACHE_MAX_CAPACITY",
                     10000,
                 ),
+                query_max_memory: env_parse_size("CUBESTORE_QUERY_MAX_MEMORY", 0, None, Some(0))
+                    as u64,
                 query_cache_time_to_idle_secs: if query_cache_time_to_idle_secs == 0 {
                     None
                 } else {
@@ -1486,6 +1599,11 @@ impl Config {
                     10 * 60,
                 ),
                 skip_kafka_parsing_errors: env_parse("CUBESTORE_SKIP_KAFKA_PARSING_ERRORS", false),
+                native_column_types: env_parse("CUBESTORE_NATIVE_COLUMN_TYPES", false),
+                retention_purge_interval_secs: env_parse(
+                    "CUBESTORE_RETENTION_PURGE_INTERVAL",
+                    10 * 60,
+                ),
                 minimum_metastore_snapshots_count: env_parse(
                     "CUBESTORE_MINIMUM_METASTORE_SNAPSHOTS_COUNT",
                     5,
@@ -1569,6 +1687,7 @@ impl Config {
                     .unwrap()
                     .join(format!("{}-local-store", name)),
                 dump_dir: None,
+                unload_local_dir: None,
                 partition_split_threshold: 20,
                 partition_size_split_threshold_bytes: 2 * 1024,
                 max_partition_split_threshold: 20,
@@ -1590,6 +1709,7 @@ impl Config {
                             .join(format!("{}-upstream", name)),
                     ),
                 },
+                import_s3_config: ImportS3Config::default(),
                 select_worker_pool_size: 0,
                 select_worker_idle_timeout: 600,
                 job_runners_count: 4,
@@ -1618,6 +1738,8 @@ impl Config {
                 cachestore_cache_compaction_trigger_size: 4096 * 2 << 20,
                 cachestore_cache_threshold_to_force_eviction: 25,
                 cachestore_queue_results_expire: 90,
+                cachestore_queue_retry_backoff: 5,
+                cachestore_queue_dead_letter_expire: 60 * 60 * 24,
                 cachestore_metrics_interval: 15,
                 cachestore_cache_max_keys: 100_000,
                 cachestore_cache_policy: CacheEvictionPolicy::SampledLru,
@@ -1640,6 +1762,7 @@ impl Config {
                 malloc_trim_every_secs: 0,
                 query_cache_max_capacity_bytes: 512 << 20,
                 query_queue_cache_max_capacity: 10000,
+                query_max_memory: 0,
                 query_cache_time_to_idle_secs: Some(600),
                 metadata_cache_max_capacity_bytes: 0,
                 metadata_cache_time_to_idle_secs: 1_000,
@@ -1652,6 +1775,8 @@ impl Config {
                 drop_ws_processing_messages_after_secs: 60,
                 drop_ws_complete_messages_after_secs: 10,
                 skip_kafka_parsing_errors: false,
+                native_column_types: false,
+                retention_purge_interval_secs: 10 * 60,
                 minimum_metastore_snapshots_count: 3,
                 metastore_snapshots_lifetime: 24 * 3600,
                 minimum_cachestore_snapshots_count: 3,
@@ -2085,6 +2210,12 @@ impl Config {
                     i.get_service_typed().await,
                     i.get_service_typed().await,
                     i.get_service_typed().await,
+                    UnloadStorage::new(
+                        i.get_service_typed::<dyn ConfigObj>()
+                            .await
+                            .import_s3_config()
+                            .clone(),
+                    ),
                 )
             })
             .await;
@@ -2098,6 +2229,7 @@ impl Config {
         self.injector
             .register_typed::<dyn StreamingService, _, _, _>(async move |i| {
                 StreamingServiceImpl::new(
+                    Arc::downgrade(&i),
                     i.get_service_typed().await,
                     i.get_service_typed().await,
                     i.get_service_typed().await,
@@ -2216,6 +2348,8 @@ impl Config {
                     i.get_service_typed().await,
                     query_cache_to_move,
                     metadata_cache_factory,
+                    i.get_service_typed().await,
+                    i.get_service_typed().await,
                 )
             })
             .await;
@@ -2229,6 +2363,7 @@ impl Config {
                         .clone(),
                     i.get_service_typed().await,
                     i.get_service_typed().await,
+                    i.get_service_typed().await,
                 )
             })
             .await;
@@ -2253,6 +2388,12 @@ impl Config {
             .register_typed::<dyn MemoryHandler, _, _, _>(async move |_| MemoryHandlerImpl::new())
             .await;
 
+        self.injector
+            .register_typed::<RunningQueries, _, _, _>(async move |i| {
+                RunningQueries::new(i.get_service_typed::<dyn ConfigObj>().await.as_ref())
+            })
+            .await;
+
         let query_cache_to_move = query_cache.clone();
         self.injector
             .register_typed::<dyn SqlService, _, _, _>(async move |i| {
@@ -2274,6 +2415,7 @@ impl Config {
                     Duration::from_secs(c.import_job_timeout() * 2),
                     query_cache_to_move,
                     i.get_service_typed().await,
+                    i.get_service_typed().await,
                 )
             })
             .await;
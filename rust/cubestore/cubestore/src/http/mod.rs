@@ -744,6 +744,19 @@ impl HttpMessage {
                         let string_value = Some(builder.create_string(&v.to_string()));
                         HttpColumnValue::create(builder, &HttpColumnValueArgs { string_value })
                     }
+                    TableValue::Date(v) => {
+                        let string_value = Some(builder.create_string(&v.to_string()));
+                        HttpColumnValue::create(builder, &HttpColumnValueArgs { string_value })
+                    }
+                    TableValue::Uuid(v) => {
+                        let string_value = Some(builder.create_string(&v.to_string()));
+                        HttpColumnValue::create(builder, &HttpColumnValueArgs { string_value })
+                    }
+                    TableValue::Array(v) => {
+                        let string_value =
+                            Some(builder.create_string(&TableValue::array_to_json_string(v)));
+                        HttpColumnValue::create(builder, &HttpColumnValueArgs { string_value })
+                    }
                 };
                 value_offsets.push(value);
             }
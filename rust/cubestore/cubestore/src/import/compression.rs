@@ -0,0 +1,248 @@
+use crate::CubeError;
+use async_compression::tokio::bufread::{BzDecoder, GzipDecoder, ZstdDecoder};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::pin::Pin;
+use tokio::fs::File;
+use tokio::io::{AsyncBufRead, AsyncReadExt, AsyncSeekExt, BufReader};
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+const LZ4_FRAME_MAGIC: &[u8] = &[0x04, 0x22, 0x4d, 0x18];
+const SNAPPY_FRAMED_MAGIC: &[u8] = b"\xff\x06\x00\x00sNaPpY";
+// "BZh", block size digit and the compressed block magic.
+const BZIP2_MAGIC_LEN: usize = 10;
+const BZIP2_BLOCK_MAGIC: &[u8] = &[0x31, 0x41, 0x59, 0x26, 0x53, 0x59];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportCompression {
+    None,
+    Gzip,
+    Zstd,
+    Lz4,
+    Bzip2,
+    Snappy,
+}
+
+impl ImportCompression {
+    /// Detects compression by the file extension. Query string is ignored so presigned urls work.
+    pub fn from_location(location: &str) -> Option<Self> {
+        let path = location.split('?').next().unwrap_or(location);
+        if path.ends_with(".zst") || path.ends_with(".zstd") {
+            Some(ImportCompression::Zstd)
+        } else if path.ends_with(".lz4") {
+            Some(ImportCompression::Lz4)
+        } else if path.ends_with(".bz2") {
+            Some(ImportCompression::Bzip2)
+        } else if path.ends_with(".sz") || path.ends_with(".snappy") {
+            Some(ImportCompression::Snappy)
+        } else if path.ends_with(".gz") {
+            Some(ImportCompression::Gzip)
+        } else {
+            None
+        }
+    }
+
+    pub fn from_magic_bytes(header: &[u8]) -> Self {
+        if header.starts_with(GZIP_MAGIC) {
+            ImportCompression::Gzip
+        } else if header.starts_with(ZSTD_MAGIC) {
+            ImportCompression::Zstd
+        } else if header.starts_with(LZ4_FRAME_MAGIC) {
+            ImportCompression::Lz4
+        } else if header.starts_with(SNAPPY_FRAMED_MAGIC) {
+            ImportCompression::Snappy
+        } else if header.len() >= BZIP2_MAGIC_LEN
+            && header.starts_with(b"BZh")
+            && (b'1'..=b'9').contains(&header[3])
+            && &header[4..BZIP2_MAGIC_LEN] == BZIP2_BLOCK_MAGIC
+        {
+            ImportCompression::Bzip2
+        } else {
+            ImportCompression::None
+        }
+    }
+
+    /// Uses the file extension if it's known and falls back to the magic bytes of the file.
+    /// File position is reset to the start.
+    pub async fn detect(location: &str, file: &mut File) -> Result<Self, CubeError> {
+        if let Some(compression) = Self::from_location(location) {
+            return Ok(compression);
+        }
+        let mut header = [0u8; 10];
+        let mut read = 0;
+        while read < header.len() {
+            let n = file.read(&mut header[read..]).await?;
+            if n == 0 {
+                break;
+            }
+            read += n;
+        }
+        file.seek(SeekFrom::Start(0)).await?;
+        Ok(Self::from_magic_bytes(&header[..read]))
+    }
+
+    /// Size of the uncompressed data which is used to estimate row count of the location.
+    pub fn estimate_uncompressed_size(&self, size: u64) -> u64 {
+        let ratio = match self {
+            ImportCompression::None => 1,
+            ImportCompression::Gzip => 5,
+            ImportCompression::Zstd => 6,
+            ImportCompression::Lz4 => 3,
+            ImportCompression::Bzip2 => 7,
+            ImportCompression::Snappy => 3,
+        };
+        size * ratio
+    }
+
+    /// There're no async decoders for lz4 and snappy so such files are decompressed
+    /// into a temporary file inside of `temp_dir` first.
+    pub async fn decoder(
+        &self,
+        file: File,
+        temp_dir: &Path,
+    ) -> Result<Pin<Box<dyn AsyncBufRead + Send>>, CubeError> {
+        Ok(match self {
+            ImportCompression::None => Box::pin(BufReader::new(file)),
+            ImportCompression::Gzip => {
+                Box::pin(BufReader::new(GzipDecoder::new(BufReader::new(file))))
+            }
+            ImportCompression::Zstd => {
+                Box::pin(BufReader::new(ZstdDecoder::new(BufReader::new(file))))
+            }
+            ImportCompression::Bzip2 => {
+                Box::pin(BufReader::new(BzDecoder::new(BufReader::new(file))))
+            }
+            ImportCompression::Lz4 | ImportCompression::Snappy => {
+                let compression = *self;
+                let input = file.into_std().await;
+                let temp_dir = temp_dir.to_path_buf();
+                let output = tokio::task::spawn_blocking(move || -> Result<_, CubeError> {
+                    let mut output = tempfile::tempfile_in(temp_dir)?;
+                    let input = std::io::BufReader::new(input);
+                    let mut decoder: Box<dyn Read> = match compression {
+                        ImportCompression::Lz4 => Box::new(lz4::Decoder::new(input)?),
+                        _ => Box::new(snap::read::FrameDecoder::new(input)),
+                    };
+                    let mut writer = std::io::BufWriter::new(&mut output);
+                    std::io::copy(&mut decoder, &mut writer)?;
+                    writer.flush()?;
+                    drop(writer);
+                    output.seek(SeekFrom::Start(0))?;
+                    Ok(output)
+                })
+                .await??;
+                Box::pin(BufReader::new(File::from_std(output)))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compression_from_location() {
+        assert_eq!(
+            ImportCompression::from_location("temp://foo.csv.gz"),
+            Some(ImportCompression::Gzip)
+        );
+        assert_eq!(
+            ImportCompression::from_location("https://bucket/foo.csv.zst?X-Amz-Expires=60"),
+            Some(ImportCompression::Zstd)
+        );
+        assert_eq!(
+            ImportCompression::from_location("s3://bucket/foo.csv.lz4"),
+            Some(ImportCompression::Lz4)
+        );
+        assert_eq!(
+            ImportCompression::from_location("foo.csv.bz2"),
+            Some(ImportCompression::Bzip2)
+        );
+        assert_eq!(
+            ImportCompression::from_location("foo.csv.snappy"),
+            Some(ImportCompression::Snappy)
+        );
+        assert_eq!(ImportCompression::from_location("foo.csv"), None);
+        assert_eq!(ImportCompression::from_location("foo.size.csv"), None);
+        assert_eq!(
+            ImportCompression::from_location("https://bucket/foo.csv.gz?X-Amz-Expires=60"),
+            Some(ImportCompression::Gzip)
+        );
+        assert_eq!(ImportCompression::from_location("foo.gz.csv"), None);
+        assert_eq!(
+            ImportCompression::from_location("/data/x.gzip/foo.csv"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_compression_from_magic_bytes() {
+        assert_eq!(
+            ImportCompression::from_magic_bytes(&[0x1f, 0x8b, 0x08, 0x00]),
+            ImportCompression::Gzip
+        );
+        assert_eq!(
+            ImportCompression::from_magic_bytes(&[0x28, 0xb5, 0x2f, 0xfd, 0x00]),
+            ImportCompression::Zstd
+        );
+        assert_eq!(
+            ImportCompression::from_magic_bytes(&[0x04, 0x22, 0x4d, 0x18, 0x64]),
+            ImportCompression::Lz4
+        );
+        assert_eq!(
+            ImportCompression::from_magic_bytes(b"\xff\x06\x00\x00sNaPpY"),
+            ImportCompression::Snappy
+        );
+        assert_eq!(
+            ImportCompression::from_magic_bytes(b"BZh91AY&SY"),
+            ImportCompression::Bzip2
+        );
+        assert_eq!(
+            ImportCompression::from_magic_bytes(b"BZhours,id"),
+            ImportCompression::None
+        );
+        assert_eq!(
+            ImportCompression::from_magic_bytes(b"id,name"),
+            ImportCompression::None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_decode_by_magic_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let content = "id,name\n1,foo\n2,bar\n".repeat(100);
+
+        let mut encoded = Vec::new();
+        {
+            let mut encoder = lz4::EncoderBuilder::new().build(&mut encoded).unwrap();
+            encoder.write_all(content.as_bytes()).unwrap();
+            let (_, res) = encoder.finish();
+            res.unwrap();
+        }
+        let mut snappy = Vec::new();
+        {
+            let mut encoder = snap::write::FrameEncoder::new(&mut snappy);
+            encoder.write_all(content.as_bytes()).unwrap();
+            encoder.flush().unwrap();
+        }
+
+        for (name, encoded, expected) in [
+            ("data-lz4", encoded, ImportCompression::Lz4),
+            ("data-snappy", snappy, ImportCompression::Snappy),
+        ] {
+            let path = dir.path().join(name);
+            std::fs::write(&path, encoded).unwrap();
+
+            let mut file = File::open(&path).await.unwrap();
+            let compression = ImportCompression::detect(name, &mut file).await.unwrap();
+            assert_eq!(compression, expected);
+
+            let mut reader = compression.decoder(file, dir.path()).await.unwrap();
+            let mut decoded = String::new();
+            reader.read_to_string(&mut decoded).await.unwrap();
+            assert_eq!(decoded, content);
+        }
+    }
+}
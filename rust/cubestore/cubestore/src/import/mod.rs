@@ -5,7 +5,6 @@ use std::path::Path;
 use std::pin::Pin;
 use std::sync::Arc;
 
-use async_compression::tokio::bufread::GzipDecoder;
 use async_std::io::SeekFrom;
 use async_std::task::{Context, Poll};
 use async_trait::async_trait;
@@ -26,14 +25,16 @@ use tokio::task::JoinHandle;
 use cubehll::HllSketch;
 
 use crate::config::injection::DIService;
-use crate::config::ConfigObj;
+use crate::config::{ConfigObj, ImportS3Config};
+use crate::import::compression::ImportCompression;
 use crate::import::limits::ConcurrencyLimits;
+use crate::import::object_store::{expand_locations, ObjectStoreLocation};
 use crate::metastore::table::Table;
 use crate::metastore::{is_valid_plain_binary_hll, HllFlavour, IdRow};
 use crate::metastore::{Column, ColumnType, ImportFormat, MetaStore};
 use crate::queryplanner::trace_data_loaded::DataLoadedSize;
 use crate::remotefs::RemoteFs;
-use crate::sql::timestamp_from_string;
+use crate::sql::{date_from_string, timestamp_from_string};
 use crate::store::ChunkDataStore;
 use crate::streaming::StreamingService;
 use crate::table::data::{append_row, create_array_builders};
@@ -47,20 +48,20 @@ use cubedatasketches::HLLDataSketch;
 use datafusion::cube_ext::ordfloat::OrdF64;
 use tokio::time::{sleep, Duration};
 
+pub mod compression;
 pub mod limits;
+pub mod object_store;
 
 impl ImportFormat {
     async fn row_stream(
         &self,
-        file: File,
+        mut file: File,
         location: String,
         columns: Vec<Column>,
+        temp_dir: &Path,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<Option<Row>, CubeError>> + Send>>, CubeError> {
-        let reader: Pin<Box<dyn AsyncBufRead + Send>> = if location.contains(".gz") {
-            Box::pin(BufReader::new(GzipDecoder::new(BufReader::new(file))))
-        } else {
-            Box::pin(BufReader::new(file))
-        };
+        let compression = ImportCompression::detect(&location, &mut file).await?;
+        let reader = compression.decoder(file, temp_dir).await?;
         self.row_stream_from_reader(reader, columns)
     }
 
@@ -221,10 +222,69 @@ impl ImportFormat {
             ColumnType::Boolean => {
                 TableValue::Boolean(value.to_lowercase() == "true" || value.to_lowercase() == "t")
             }
+            ColumnType::Date => TableValue::Date(date_from_string(value)?),
+            ColumnType::Uuid => TableValue::Uuid(parse_uuid(value)?),
+            ColumnType::Json => {
+                if let Err(e) = serde_json::from_str::<serde_json::Value>(value) {
+                    return Err(CubeError::user(format!("Can't parse json: {}", e)));
+                }
+                TableValue::String(value.to_string())
+            }
+            ColumnType::Array(element_type) => {
+                TableValue::Array(parse_array(element_type.as_ref(), value)?)
+            }
         })
     }
 }
 
+pub(crate) fn parse_uuid(value: &str) -> Result<uuid::Uuid, CubeError> {
+    uuid::Uuid::parse_str(value)
+        .map_err(|e| CubeError::user(format!("Can't parse uuid '{}': {}", value, e)))
+}
+
+/// Arrays are expected as JSON arrays, e.g. `["a", "b"]` or `[1, 2, null]`.
+pub(crate) fn parse_array(
+    element_type: &ColumnType,
+    value: &str,
+) -> Result<Vec<TableValue>, CubeError> {
+    let items = match serde_json::from_str::<serde_json::Value>(value) {
+        Ok(serde_json::Value::Array(items)) => items,
+        _ => {
+            return Err(CubeError::user(format!(
+                "Can't parse array of {} from '{}'",
+                element_type, value
+            )))
+        }
+    };
+    items
+        .iter()
+        .map(|item| {
+            let parsed = match (element_type, item) {
+                (_, serde_json::Value::Null) => Some(TableValue::Null),
+                (ColumnType::String, serde_json::Value::String(s)) => {
+                    Some(TableValue::String(s.to_string()))
+                }
+                (ColumnType::Int, v) => v.as_i64().map(TableValue::Int),
+                (ColumnType::Float, v) => v.as_f64().map(|v| TableValue::Float(OrdF64(v))),
+                (ColumnType::Boolean, v) => v.as_bool().map(TableValue::Boolean),
+                (ColumnType::Timestamp, serde_json::Value::String(s)) => {
+                    Some(TableValue::Timestamp(timestamp_from_string(s)?))
+                }
+                (ColumnType::Date, serde_json::Value::String(s)) => {
+                    Some(TableValue::Date(date_from_string(s)?))
+                }
+                _ => None,
+            };
+            parsed.ok_or_else(|| {
+                CubeError::user(format!(
+                    "Can't parse array element {} as {} in '{}'",
+                    item, element_type, value
+                ))
+            })
+        })
+        .collect()
+}
+
 pub(crate) fn parse_decimal(value: &str, scale: u8) -> Result<Decimal, CubeError> {
     // TODO: parse into Decimal directly.
     let bd = BigDecimal::from_str_radix(value, 10)?;
@@ -511,6 +571,9 @@ pub trait ImportService: DIService + Send + Sync {
         -> Result<(), CubeError>;
     async fn estimate_location_row_count(&self, location: &str) -> Result<u64, CubeError>;
     async fn validate_locations_size(&self, locations: &Vec<String>) -> Result<(), CubeError>;
+    /// Replaces object store locations with wildcards by the list of matching files,
+    /// so every file is imported by its own job.
+    async fn expand_locations(&self, locations: Vec<String>) -> Result<Vec<String>, CubeError>;
 }
 
 crate::di_service!(MockImportService, [ImportService]);
@@ -554,9 +617,9 @@ impl ImportServiceImpl {
         table_id: u64,
         temp_dir: &Path,
     ) -> Result<(File, Option<TempPath>), CubeError> {
-        if location.starts_with("http") {
+        if location.starts_with("http") || ObjectStoreLocation::is_object_store_location(location) {
             let (file, size, path) = self
-                .download_http_location(location, table_id, temp_dir)
+                .download_remote_location(location, table_id, temp_dir)
                 .await?;
             log::info!("Import downloaded {} ({} bytes)", location, size);
             self.meta_store
@@ -581,7 +644,7 @@ impl ImportServiceImpl {
         }
     }
 
-    async fn download_http_location(
+    async fn download_remote_location(
         &self,
         location: &str,
         table_id: u64,
@@ -594,7 +657,7 @@ impl ImportServiceImpl {
         loop {
             retry_attempts -= 1;
             let result = self
-                .try_download_http_location(location, table_id, temp_dir)
+                .try_download_remote_location(location, table_id, temp_dir)
                 .await;
 
             if retry_attempts <= 0 {
@@ -619,7 +682,7 @@ impl ImportServiceImpl {
         }
     }
 
-    async fn try_download_http_location(
+    async fn try_download_remote_location(
         &self,
         location: &str,
         table_id: u64,
@@ -638,6 +701,20 @@ impl ImportServiceImpl {
             .into_parts();
         let mut file = File::from_std(file);
 
+        let size = if ObjectStoreLocation::is_object_store_location(location) {
+            ObjectStoreLocation::parse(location)?
+                .download(&mut file, self.config_obj.import_s3_config())
+                .await?
+        } else {
+            Self::download_http_file(location, &mut file).await?
+        };
+
+        file.seek(SeekFrom::Start(0)).await?;
+
+        Ok((file, size, path))
+    }
+
+    async fn download_http_file(location: &str, file: &mut File) -> Result<usize, CubeError> {
         let res = reqwest::get(location).await?;
         if !res.status().is_success() {
             return Err(CubeError::user(format!(
@@ -654,10 +731,7 @@ impl ImportServiceImpl {
             size += slice.len();
             file.write_all(slice).await?;
         }
-
-        file.seek(SeekFrom::Start(0)).await?;
-
-        Ok((file, size, path))
+        Ok(size)
     }
 
     async fn download_temp_file(&self, location: &str) -> Result<File, CubeError> {
@@ -705,6 +779,7 @@ impl ImportServiceImpl {
                 file,
                 location.to_string(),
                 table.get_row().get_columns().clone(),
+                &temp_dir,
             )
             .await?;
 
@@ -751,11 +826,9 @@ impl ImportServiceImpl {
 
     fn estimate_rows(location: &str, size: Option<u64>) -> u64 {
         if let Some(size) = size {
-            let uncompressed_size = if location.contains(".gz") {
-                size * 5
-            } else {
-                size
-            };
+            let uncompressed_size = ImportCompression::from_location(location)
+                .unwrap_or(ImportCompression::None)
+                .estimate_uncompressed_size(size);
             let average_row_length = 256;
             uncompressed_size / average_row_length
         } else {
@@ -849,14 +922,22 @@ impl ImportService for ImportServiceImpl {
     }
 
     async fn estimate_location_row_count(&self, location: &str) -> Result<u64, CubeError> {
-        let file_size =
-            LocationHelper::location_file_size(location, self.remote_fs.clone()).await?;
+        let file_size = LocationHelper::location_file_size(
+            location,
+            self.remote_fs.clone(),
+            self.config_obj.import_s3_config(),
+        )
+        .await?;
         Ok(ImportServiceImpl::estimate_rows(location, file_size))
     }
 
     async fn validate_locations_size(&self, locations: &Vec<String>) -> Result<(), CubeError> {
         self.validator.validate(locations).await
     }
+
+    async fn expand_locations(&self, locations: Vec<String>) -> Result<Vec<String>, CubeError> {
+        expand_locations(locations, self.config_obj.import_s3_config()).await
+    }
 }
 
 pub struct LocationHelper;
@@ -865,6 +946,7 @@ impl LocationHelper {
     pub async fn location_file_size(
         location: &str,
         remote_fs: Arc<dyn RemoteFs>,
+        s3_config: &ImportS3Config,
     ) -> Result<Option<u64>, CubeError> {
         let res = if location.starts_with("http") {
             let client = reqwest::Client::new();
@@ -906,6 +988,10 @@ impl LocationHelper {
             }?
         } else if location.starts_with("stream://") {
             None
+        } else if ObjectStoreLocation::is_object_store_location(location) {
+            ObjectStoreLocation::parse(location)?
+                .file_size(s3_config)
+                .await?
         } else {
             Some(tokio::fs::metadata(location).await?.len())
         };
@@ -990,7 +1076,7 @@ mod tests {
 
     use crate::import::parse_decimal;
     use crate::metastore::{Column, ColumnType, ImportFormat};
-    use crate::table::{Row, TableValue};
+    use crate::table::{DateValue, Row, TableValue};
     use indoc::indoc;
     use tokio::io::BufReader;
     use tokio_stream::StreamExt;
@@ -1015,6 +1101,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_date_uuid_json_array_test() {
+        let parse = |t: ColumnType, v: &str| {
+            ImportFormat::parse_column_value_str(&Column::new("c".to_string(), t, 0), v)
+        };
+        assert_eq!(
+            parse(ColumnType::Date, "2022-02-28").unwrap(),
+            TableValue::Date(DateValue::new(19051))
+        );
+        assert!(parse(ColumnType::Date, "2022-02-30").is_err());
+        assert_eq!(
+            parse(ColumnType::Uuid, "6ba7b810-9dad-11d1-80b4-00c04fd430c8").unwrap(),
+            TableValue::Uuid(uuid::Uuid::from_u128(0x6ba7b8109dad11d180b400c04fd430c8))
+        );
+        assert!(parse(ColumnType::Uuid, "6ba7b810").is_err());
+        assert_eq!(
+            parse(ColumnType::Json, r#"{"a": [1, 2]}"#).unwrap(),
+            TableValue::String(r#"{"a": [1, 2]}"#.to_string())
+        );
+        assert!(parse(ColumnType::Json, r#"{"a": "#).is_err());
+        assert_eq!(
+            parse(ColumnType::Array(Box::new(ColumnType::Int)), "[1, null, 3]").unwrap(),
+            TableValue::Array(vec![
+                TableValue::Int(1),
+                TableValue::Null,
+                TableValue::Int(3)
+            ])
+        );
+        assert_eq!(
+            parse(
+                ColumnType::Array(Box::new(ColumnType::Date)),
+                r#"["2022-02-28"]"#
+            )
+            .unwrap(),
+            TableValue::Array(vec![TableValue::Date(DateValue::new(19051))])
+        );
+        assert!(parse(ColumnType::Array(Box::new(ColumnType::Int)), r#"["a"]"#).is_err());
+        assert!(parse(ColumnType::Array(Box::new(ColumnType::String)), "a,b").is_err());
+    }
+
     #[tokio::test]
     async fn read_nulls() {
         let data = indoc! {"
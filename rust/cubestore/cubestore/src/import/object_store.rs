@@ -0,0 +1,354 @@
+use crate::config::ImportS3Config;
+use crate::remotefs::gcs::ensure_credentials_init;
+use crate::CubeError;
+use cloud_storage::Object;
+use futures::StreamExt;
+use regex::Regex;
+use s3::creds::Credentials;
+use s3::{Bucket, Region};
+use tokio::fs::File;
+use tokio::io::{AsyncWriteExt, BufWriter};
+
+/// Import location which points directly to an object store, e.g. `s3://bucket/path/file.csv.gz`.
+/// The key may contain `*` and `?` wildcards to import all matching files,
+/// which is how warehouses usually unload data.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObjectStoreLocation {
+    S3 { bucket: String, key: String },
+    GCS { bucket: String, key: String },
+}
+
+impl ObjectStoreLocation {
+    pub fn is_object_store_location(location: &str) -> bool {
+        location.starts_with("s3://") || location.starts_with("gs://")
+    }
+
+    pub fn parse(location: &str) -> Result<Self, CubeError> {
+        let (scheme, path) = location.split_once("://").ok_or_else(|| {
+            CubeError::user(format!("Invalid object store location: {}", location))
+        })?;
+        let (bucket, key) = match path.split_once('/') {
+            Some((bucket, key)) if !bucket.is_empty() && !key.is_empty() => {
+                (bucket.to_string(), key.to_string())
+            }
+            _ => {
+                return Err(CubeError::user(format!(
+                    "Object store location should contain bucket and path: {}",
+                    location
+                )))
+            }
+        };
+        match scheme {
+            "s3" => Ok(ObjectStoreLocation::S3 { bucket, key }),
+            "gs" => Ok(ObjectStoreLocation::GCS { bucket, key }),
+            _ => Err(CubeError::user(format!(
+                "Unsupported object store location: {}",
+                location
+            ))),
+        }
+    }
+
+    pub fn has_wildcards(&self) -> bool {
+        self.key().contains(|c| c == '*' || c == '?')
+    }
+
+    /// Lists files matching the location. Returns the location itself if it has no wildcards.
+    pub async fn expand(&self, s3_config: &ImportS3Config) -> Result<Vec<String>, CubeError> {
+        if !self.has_wildcards() {
+            return Ok(vec![self.location(self.key())]);
+        }
+
+        let key = self.key();
+        let prefix = &key[..key.find(|c| c == '*' || c == '?').unwrap()];
+        let pattern = wildcard_to_regex(key)?;
+        let mut keys = self
+            .list(prefix, s3_config)
+            .await?
+            .into_iter()
+            .filter(|(k, _)| pattern.is_match(k))
+            .map(|(k, _)| self.location(&k))
+            .collect::<Vec<_>>();
+        keys.sort();
+
+        if keys.is_empty() {
+            return Err(CubeError::user(format!(
+                "No files match import location {}",
+                self.location(key)
+            )));
+        }
+        Ok(keys)
+    }
+
+    pub async fn file_size(&self, s3_config: &ImportS3Config) -> Result<Option<u64>, CubeError> {
+        let key = self.key();
+        Ok(self
+            .list(key, s3_config)
+            .await?
+            .into_iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, size)| size))
+    }
+
+    /// Downloads the file into `file` and returns its size.
+    pub async fn download(
+        &self,
+        file: &mut File,
+        s3_config: &ImportS3Config,
+    ) -> Result<usize, CubeError> {
+        match self {
+            ObjectStoreLocation::S3 { bucket, key } => {
+                let bucket = s3_bucket(bucket, s3_config)?;
+                let status_code = bucket.get_object_stream(key.as_str(), file).await?;
+                if status_code != 200 {
+                    return Err(CubeError::user(format!(
+                        "Unable to import from {}, S3 download returned non OK status: {}",
+                        self.location(key),
+                        status_code
+                    )));
+                }
+                file.flush().await?;
+                Ok(file.metadata().await?.len() as usize)
+            }
+            ObjectStoreLocation::GCS { bucket, key } => {
+                ensure_credentials_init();
+                let mut writer = BufWriter::new(file);
+                let mut stream = Object::download_streamed(bucket.as_str(), key.as_str()).await?;
+                let mut size = 0;
+                while let Some(byte) = stream.next().await {
+                    writer.write_all(&[byte?]).await?;
+                    size += 1;
+                }
+                writer.flush().await?;
+                Ok(size)
+            }
+        }
+    }
+
+    fn key(&self) -> &str {
+        match self {
+            ObjectStoreLocation::S3 { key, .. } | ObjectStoreLocation::GCS { key, .. } => key,
+        }
+    }
+
+    fn location(&self, key: &str) -> String {
+        match self {
+            ObjectStoreLocation::S3 { bucket, .. } => format!("s3://{}/{}", bucket, key),
+            ObjectStoreLocation::GCS { bucket, .. } => format!("gs://{}/{}", bucket, key),
+        }
+    }
+
+    /// Returns keys and sizes of the objects under `prefix`.
+    async fn list(
+        &self,
+        prefix: &str,
+        s3_config: &ImportS3Config,
+    ) -> Result<Vec<(String, u64)>, CubeError> {
+        match self {
+            ObjectStoreLocation::S3 { bucket, .. } => {
+                let bucket = s3_bucket(bucket, s3_config)?;
+                let list = bucket.list(prefix.to_string(), None).await?;
+                Ok(list
+                    .into_iter()
+                    .flat_map(|res| res.contents.into_iter().map(|o| (o.key, o.size)))
+                    .collect())
+            }
+            ObjectStoreLocation::GCS { bucket, .. } => {
+                ensure_credentials_init();
+                let list = Object::list_prefix(bucket.as_str(), prefix).await?;
+                let pages = list.collect::<Vec<_>>().await;
+                let mut result = Vec::new();
+                for objects in pages {
+                    result.extend(objects?.into_iter().map(|o| (o.name, o.size)));
+                }
+                Ok(result)
+            }
+        }
+    }
+}
+
+/// Expands object store locations with wildcards into the matching files, other locations are
+/// kept as is.
+pub async fn expand_locations(
+    locations: Vec<String>,
+    s3_config: &ImportS3Config,
+) -> Result<Vec<String>, CubeError> {
+    let mut res = Vec::with_capacity(locations.len());
+    for location in locations {
+        if ObjectStoreLocation::is_object_store_location(&location) {
+            res.extend(
+                ObjectStoreLocation::parse(&location)?
+                    .expand(s3_config)
+                    .await?,
+            );
+        } else {
+            res.push(location);
+        }
+    }
+    Ok(res)
+}
+
+pub fn s3_bucket(bucket_name: &str, config: &ImportS3Config) -> Result<Bucket, CubeError> {
+    let credentials = Credentials::new(
+        config.access_key_id.as_deref(),
+        config.secret_access_key.as_deref(),
+        None,
+        None,
+        None,
+    )
+    .map_err(|err| {
+        CubeError::internal(format!(
+            "Failed to create S3 credentials for import: {}",
+            err.to_string()
+        ))
+    })?;
+    let region = s3_region(config)?;
+    // S3 compatible storages are usually addressed by path rather than by subdomain.
+    let path_style = matches!(region, Region::Custom { .. });
+    let bucket = Bucket::new(bucket_name, region, credentials)?;
+    Ok(if path_style {
+        bucket.with_path_style()
+    } else {
+        bucket
+    })
+}
+
+fn s3_region(config: &ImportS3Config) -> Result<Region, CubeError> {
+    if let Some(endpoint) = &config.endpoint {
+        return Ok(Region::Custom {
+            region: config.region.clone().unwrap_or_default(),
+            endpoint: endpoint.clone(),
+        });
+    }
+    let region = config.region.as_ref().ok_or_else(|| {
+        CubeError::user(
+            "CUBESTORE_IMPORT_S3_REGION or CUBESTORE_S3_REGION is required to import from s3:// locations"
+                .to_string(),
+        )
+    })?;
+    region.parse::<Region>().map_err(|err| {
+        CubeError::internal(format!(
+            "Failed to parse Region '{}': {}",
+            region,
+            err.to_string()
+        ))
+    })
+}
+
+/// `*` matches any sequence of characters except `/`, `**` matches any sequence and `?` matches
+/// a single character.
+fn wildcard_to_regex(pattern: &str) -> Result<Regex, CubeError> {
+    let mut res = "^".to_string();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                res.push_str(".*");
+            }
+            '*' => res.push_str("[^/]*"),
+            '?' => res.push_str("[^/]"),
+            c => res.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    res.push('$');
+    Regex::new(&res).map_err(|e| {
+        CubeError::user(format!(
+            "Invalid import location pattern '{}': {}",
+            pattern, e
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_object_store_location() {
+        assert_eq!(
+            ObjectStoreLocation::parse("s3://bucket/unload/part_*.csv.gz").unwrap(),
+            ObjectStoreLocation::S3 {
+                bucket: "bucket".to_string(),
+                key: "unload/part_*.csv.gz".to_string()
+            }
+        );
+        assert_eq!(
+            ObjectStoreLocation::parse("gs://bucket/file.csv").unwrap(),
+            ObjectStoreLocation::GCS {
+                bucket: "bucket".to_string(),
+                key: "file.csv".to_string()
+            }
+        );
+        assert!(ObjectStoreLocation::parse("s3://bucket").is_err());
+        assert!(ObjectStoreLocation::parse("s3:///file.csv").is_err());
+        assert!(ObjectStoreLocation::parse("gs://bucket/unload/*.csv")
+            .unwrap()
+            .has_wildcards());
+    }
+
+    #[test]
+    fn test_wildcard_to_regex() {
+        let r = wildcard_to_regex("unload/part_*.csv.gz").unwrap();
+        assert!(r.is_match("unload/part_0001.csv.gz"));
+        assert!(!r.is_match("unload/part_0001.csv"));
+        assert!(!r.is_match("unload/nested/part_0001.csv.gz"));
+        assert!(!r.is_match("unload/part_0001xcsv.gz"));
+
+        let r = wildcard_to_regex("unload/**/?.csv").unwrap();
+        assert!(r.is_match("unload/a/b/1.csv"));
+        assert!(!r.is_match("unload/a/12.csv"));
+    }
+
+    #[test]
+    fn test_s3_region() {
+        assert!(s3_region(&ImportS3Config::default()).is_err());
+
+        let region = s3_region(&ImportS3Config {
+            region: Some("us-west-2".to_string()),
+            ..ImportS3Config::default()
+        })
+        .unwrap();
+        assert_eq!(region, Region::UsWest2);
+
+        let region = s3_region(&ImportS3Config {
+            endpoint: Some("http://localhost:9000".to_string()),
+            ..ImportS3Config::default()
+        })
+        .unwrap();
+        assert_eq!(
+            region,
+            Region::Custom {
+                region: "".to_string(),
+                endpoint: "http://localhost:9000".to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expand_locations() {
+        let config = ImportS3Config::default();
+        let locations = vec![
+            "/tmp/file.csv".to_string(),
+            "https://example.com/file.csv.gz".to_string(),
+            "s3://bucket/unload/file.csv".to_string(),
+            "temp://file.csv".to_string(),
+        ];
+        assert_eq!(
+            expand_locations(locations.clone(), &config).await.unwrap(),
+            locations
+        );
+
+        assert!(expand_locations(vec!["s3://bucket".to_string()], &config)
+            .await
+            .is_err());
+        // Listing files requires the bucket settings.
+        let err = expand_locations(vec!["s3://bucket/unload/*.csv".to_string()], &config)
+            .await
+            .unwrap_err();
+        assert!(
+            err.message.contains("CUBESTORE_IMPORT_S3_REGION"),
+            "{}",
+            err.message
+        );
+    }
+}
@@ -472,6 +472,12 @@ impl From<tempfile::PathPersistError> for CubeError {
     }
 }
 
+impl From<csv::Error> for CubeError {
+    fn from(v: csv::Error) -> Self {
+        return CubeError::from_error(v);
+    }
+}
+
 impl From<tokio::sync::AcquireError> for CubeError {
     fn from(v: tokio::sync::AcquireError) -> Self {
         return CubeError::from_error(v);
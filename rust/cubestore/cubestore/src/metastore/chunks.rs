@@ -1,6 +1,7 @@
 use super::{Chunk, IndexId, RocksSecondaryIndex, TableId};
 
 use crate::rocks_table_impl;
+use crate::table::statistics::ColumnStatistics;
 use crate::table::Row;
 use crate::{base_rocks_secondary_index, CubeError};
 use byteorder::{BigEndian, WriteBytesExt};
@@ -38,6 +39,7 @@ impl Chunk {
             replay_handle_id: None,
             min,
             max,
+            column_statistics: None,
         }
     }
 
@@ -113,6 +115,16 @@ impl Chunk {
         &self.max
     }
 
+    pub fn column_statistics(&self) -> &Option<ColumnStatistics> {
+        &self.column_statistics
+    }
+
+    pub fn set_column_statistics(&self, column_statistics: Option<ColumnStatistics>) -> Chunk {
+        let mut to_update = self.clone();
+        to_update.column_statistics = column_statistics;
+        to_update
+    }
+
     pub fn uploaded(&self) -> bool {
         self.uploaded
     }
@@ -44,12 +44,17 @@ use crate::metastore::replay_handle::{
 use crate::metastore::source::{
     Source, SourceCredentials, SourceIndexKey, SourceRocksIndex, SourceRocksTable,
 };
-use crate::metastore::table::{AggregateColumnIndex, StreamOffset, TableIndexKey, TablePath};
+use crate::metastore::table::{
+    AggregateColumnIndex, MaterializedView, ParquetOptions, RetentionPolicy, StreamOffset,
+    TableIndexKey, TablePath,
+};
 use crate::metastore::trace_object::{
     TraceObject, TraceObjectIndexKey, TraceObjectRocksIndex, TraceObjectRocksTable,
 };
 use crate::metastore::wal::{WALIndexKey, WALRocksIndex};
 
+use crate::table::bloom_filter::bloom_filter_file_name;
+use crate::table::statistics::ColumnStatistics;
 use crate::table::{Row, TableValue};
 
 use crate::util::WorkerLoop;
@@ -85,7 +90,8 @@ use std::path::Path;
 use std::str::FromStr;
 
 use crate::cachestore::{
-    CacheItem, QueueItem, QueueItemPayload, QueueItemStatus, QueueResult, QueueResultAckEvent,
+    CacheChannelMessage, CacheItem, QueueItem, QueueItemPayload, QueueItemStatus, QueueResult,
+    QueueResultAckEvent,
 };
 use crate::remotefs::LocalDirRemoteFs;
 use cubedatasketches::HLLDataSketch;
@@ -254,6 +260,21 @@ impl DataFrameValue<String> for DateTime<Utc> {
     }
 }
 
+impl DataFrameValue<String> for Option<ColumnStatistics> {
+    fn value(v: &Self) -> String {
+        v.as_ref()
+            .map(|v| {
+                v.min
+                    .iter()
+                    .zip(v.max.iter())
+                    .zip(v.null_counts.iter())
+                    .map(|((min, max), nulls)| format!("[{:?}, {:?}] nulls: {}", min, max, nulls))
+                    .join(", ")
+            })
+            .unwrap_or("NULL".to_string())
+    }
+}
+
 impl DataFrameValue<String> for Option<ImportFormat> {
     fn value(v: &Self) -> String {
         v.as_ref()
@@ -325,6 +346,9 @@ impl DataFrameValue<String> for Option<Row> {
                             TableValue::Decimal(v) => format!("{}", v.raw_value()),
                             TableValue::Decimal96(v) => format!("{}", v.raw_value()),
                             TableValue::Float(v) => format!("{}", v),
+                            TableValue::Date(v) => format!("{:?}", v),
+                            TableValue::Uuid(v) => format!("{}", v),
+                            TableValue::Array(v) => format!("{:?}", v),
                         })
                         .join(", ")
                 )
@@ -381,6 +405,10 @@ pub enum ColumnType {
     Decimal96 { scale: i32, precision: i32 },
     Float,
     Boolean,
+    Date,
+    Uuid,
+    Json,                   // Stored as text, validated on ingestion.
+    Array(Box<ColumnType>), // One-dimensional arrays of scalar elements.
 }
 
 impl Display for ColumnType {
@@ -388,6 +416,7 @@ impl Display for ColumnType {
         let s = match self {
             ColumnType::Decimal { scale, .. } => return write!(f, "decimal({})", scale),
             ColumnType::Decimal96 { scale, .. } => return write!(f, "decimal96({})", scale),
+            ColumnType::Array(element_type) => return write!(f, "{}[]", element_type),
             ColumnType::String => "text",
             ColumnType::Int => "int",
             ColumnType::Int96 => "int96",
@@ -400,18 +429,39 @@ impl Display for ColumnType {
             ColumnType::Timestamp => "timestamp",
             ColumnType::Float => "float",
             ColumnType::Boolean => "boolean",
+            ColumnType::Date => "date",
+            ColumnType::Uuid => "uuid",
+            ColumnType::Json => "json",
         };
         f.write_str(s)
     }
 }
 
 impl ColumnType {
+    /// Arrays are one-dimensional and only hold scalar elements that the array builders support.
+    pub fn array_of(element_type: ColumnType) -> Result<ColumnType, CubeError> {
+        match element_type {
+            ColumnType::String
+            | ColumnType::Int
+            | ColumnType::Float
+            | ColumnType::Boolean
+            | ColumnType::Timestamp
+            | ColumnType::Date => Ok(ColumnType::Array(Box::new(element_type))),
+            t => Err(CubeError::user(format!(
+                "Arrays of type '{}' are not supported",
+                t
+            ))),
+        }
+    }
+
     pub fn from_string(s: &str) -> Result<ColumnType, CubeError> {
         lazy_static! {
             static ref DECIMAL_RE: Regex = Regex::new(r"decimal\((?P<scale>\d+)\)").unwrap();
             static ref DECIMAL_96_RE: Regex = Regex::new(r"decimal96\((?P<scale>\d+)\)").unwrap();
         }
-        if let Some(captures) = DECIMAL_96_RE.captures(s) {
+        if let Some(element_type) = s.strip_suffix("[]") {
+            ColumnType::array_of(ColumnType::from_string(element_type)?)
+        } else if let Some(captures) = DECIMAL_96_RE.captures(s) {
             let scale = captures
                 .name("scale")
                 .ok_or(CubeError::internal("missing scale capture".to_string()))?
@@ -446,6 +496,9 @@ impl ColumnType {
                 "timestamp" => Ok(ColumnType::Timestamp),
                 "float" => Ok(ColumnType::Float),
                 "boolean" => Ok(ColumnType::Boolean),
+                "date" => Ok(ColumnType::Date),
+                "uuid" => Ok(ColumnType::Uuid),
+                "json" => Ok(ColumnType::Json),
                 _ => {
                     return Err(CubeError::user(format!(
                         "Column type '{}' is not supported",
@@ -543,6 +596,41 @@ impl From<&Column> for types::Type {
                     .build()
                     .unwrap()
             }
+            ColumnType::Date => {
+                types::Type::primitive_type_builder(&column.get_name(), Type::INT32)
+                    .with_converted_type(ConvertedType::DATE)
+                    .with_repetition(Repetition::OPTIONAL)
+                    .build()
+                    .unwrap()
+            }
+            ColumnType::Uuid => {
+                types::Type::primitive_type_builder(&column.get_name(), Type::FIXED_LEN_BYTE_ARRAY)
+                    .with_length(16)
+                    .with_repetition(Repetition::OPTIONAL)
+                    .build()
+                    .unwrap()
+            }
+            ColumnType::Json => {
+                types::Type::primitive_type_builder(&column.get_name(), Type::BYTE_ARRAY)
+                    .with_converted_type(ConvertedType::JSON)
+                    .with_repetition(Repetition::OPTIONAL)
+                    .build()
+                    .unwrap()
+            }
+            ColumnType::Array(element_type) => {
+                let element = Column::new("element".to_string(), *element_type.clone(), 0);
+                let list = types::Type::group_type_builder("list")
+                    .with_repetition(Repetition::REPEATED)
+                    .with_fields(&mut vec![Arc::new(types::Type::from(&element))])
+                    .build()
+                    .unwrap();
+                types::Type::group_type_builder(&column.get_name())
+                    .with_converted_type(ConvertedType::LIST)
+                    .with_repetition(Repetition::OPTIONAL)
+                    .with_fields(&mut vec![Arc::new(list)])
+                    .build()
+                    .unwrap()
+            }
         }
     }
 }
@@ -564,7 +652,7 @@ impl<'a> Into<Field> for &'a Column {
     fn into(self) -> Field {
         Field::new(
             self.name.as_str(),
-            match self.column_type {
+            match &self.column_type {
                 ColumnType::String => DataType::Utf8,
                 ColumnType::Int => DataType::Int64,
                 ColumnType::Int96 => DataType::Int96,
@@ -579,6 +667,12 @@ impl<'a> Into<Field> for &'a Column {
                 ColumnType::Bytes => DataType::Binary,
                 ColumnType::HyperLogLog(_) => DataType::Binary,
                 ColumnType::Float => DataType::Float64,
+                ColumnType::Date => DataType::Date32,
+                ColumnType::Uuid => DataType::FixedSizeBinary(16),
+                ColumnType::Json => DataType::Utf8,
+                ColumnType::Array(element_type) => DataType::List(Box::new(
+                    Column::new("item".to_string(), *element_type.clone(), 0).into(),
+                )),
             },
             true,
         )
@@ -587,27 +681,38 @@ impl<'a> Into<Field> for &'a Column {
 
 impl fmt::Display for Column {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let column_type = match &self.column_type {
-            ColumnType::String => "STRING".to_string(),
-            ColumnType::Int => "INT".to_string(),
-            ColumnType::Int96 => "INT96".to_string(),
-            ColumnType::Timestamp => "TIMESTAMP".to_string(),
-            ColumnType::Boolean => "BOOLEAN".to_string(),
-            ColumnType::Decimal { scale, precision } => {
-                format!("DECIMAL({}, {})", precision, scale)
-            }
-            ColumnType::Decimal96 { scale, precision } => {
-                format!("DECIMAL96({}, {})", precision, scale)
-            }
-            ColumnType::Bytes => "BYTES".to_string(),
-            ColumnType::HyperLogLog(HllFlavour::Airlift) => "HYPERLOGLOG".to_string(),
-            ColumnType::HyperLogLog(HllFlavour::ZetaSketch) => "HYPERLOGLOGPP".to_string(),
-            ColumnType::HyperLogLog(HllFlavour::Postgres) => "HLL_POSTGRES".to_string(),
-            ColumnType::HyperLogLog(HllFlavour::Snowflake) => "HLL_SNOWFLAKE".to_string(),
-            ColumnType::HyperLogLog(HllFlavour::DataSketches) => "HLL_DATASKETCHES".to_string(),
-            ColumnType::Float => "FLOAT".to_string(),
-        };
-        f.write_fmt(format_args!("{} {}", self.name, column_type))
+        f.write_fmt(format_args!(
+            "{} {}",
+            self.name,
+            column_type_sql(&self.column_type)
+        ))
+    }
+}
+
+fn column_type_sql(column_type: &ColumnType) -> String {
+    match column_type {
+        ColumnType::String => "STRING".to_string(),
+        ColumnType::Int => "INT".to_string(),
+        ColumnType::Int96 => "INT96".to_string(),
+        ColumnType::Timestamp => "TIMESTAMP".to_string(),
+        ColumnType::Boolean => "BOOLEAN".to_string(),
+        ColumnType::Decimal { scale, precision } => {
+            format!("DECIMAL({}, {})", precision, scale)
+        }
+        ColumnType::Decimal96 { scale, precision } => {
+            format!("DECIMAL96({}, {})", precision, scale)
+        }
+        ColumnType::Bytes => "BYTES".to_string(),
+        ColumnType::HyperLogLog(HllFlavour::Airlift) => "HYPERLOGLOG".to_string(),
+        ColumnType::HyperLogLog(HllFlavour::ZetaSketch) => "HYPERLOGLOGPP".to_string(),
+        ColumnType::HyperLogLog(HllFlavour::Postgres) => "HLL_POSTGRES".to_string(),
+        ColumnType::HyperLogLog(HllFlavour::Snowflake) => "HLL_SNOWFLAKE".to_string(),
+        ColumnType::HyperLogLog(HllFlavour::DataSketches) => "HLL_DATASKETCHES".to_string(),
+        ColumnType::Float => "FLOAT".to_string(),
+        ColumnType::Date => "DATE".to_string(),
+        ColumnType::Uuid => "UUID".to_string(),
+        ColumnType::Json => "JSON".to_string(),
+        ColumnType::Array(element_type) => format!("{}[]", column_type_sql(element_type)),
     }
 }
 
@@ -699,6 +804,7 @@ impl AggregateFunction {
         match self {
             Self::MAX | Self::MIN => match col_type {
                 ColumnType::HyperLogLog(_) => false,
+                ColumnType::Array(_) => false,
                 _ => true,
             },
             Self::SUM => match col_type {
@@ -748,7 +854,12 @@ pub struct Partition {
     #[serde(default)]
     min: Option<Row>,
     #[serde(default)]
-    max: Option<Row>
+    max: Option<Row>,
+    /// Bloom filters of the main table file are kept in a side file, see `bloom_filter_file_name`.
+    #[serde(default)]
+    has_bloom_filters: bool,
+    #[serde(default)]
+    column_statistics: Option<ColumnStatistics>
 }
 }
 
@@ -780,7 +891,9 @@ pub struct Chunk {
     replay_handle_id: Option<u64>,
     min: Option<Row>,
     #[serde(default)]
-    max: Option<Row>
+    max: Option<Row>,
+    #[serde(default)]
+    column_statistics: Option<ColumnStatistics>
 }
 }
 
@@ -857,9 +970,17 @@ pub trait MetaStore: DIService + Send + Sync {
         trace_obj: Option<String>,
         drop_if_exists: bool,
         extension: Option<String>,
+        parquet_options: Option<ParquetOptions>,
+        retention: Option<RetentionPolicy>,
+        materialized_view: Option<MaterializedView>,
     ) -> Result<IdRow<Table>, CubeError>;
     async fn table_ready(&self, id: u64, is_ready: bool) -> Result<IdRow<Table>, CubeError>;
     async fn seal_table(&self, id: u64) -> Result<IdRow<Table>, CubeError>;
+    async fn update_table_last_purge_at(
+        &self,
+        id: u64,
+        last_purge_at: DateTime<Utc>,
+    ) -> Result<IdRow<Table>, CubeError>;
     async fn get_trace_obj_by_table_id(&self, table_id: u64) -> Result<Option<String>, CubeError>;
     async fn update_location_download_size(
         &self,
@@ -878,6 +999,12 @@ pub trait MetaStore: DIService + Send + Sync {
         &self,
         include_non_ready: bool,
     ) -> Result<Arc<Vec<TablePath>>, CubeError>;
+    /// Materialized views built over `source_table_id`. Views which aren't ready are being
+    /// backfilled.
+    async fn get_materialized_views(
+        &self,
+        source_table_id: u64,
+    ) -> Result<Vec<IdRow<Table>>, CubeError>;
     async fn not_ready_tables(
         &self,
         created_seconds_ago: i64,
@@ -919,6 +1046,8 @@ pub trait MetaStore: DIService + Send + Sync {
         current_active: Vec<(IdRow<Partition>, Vec<IdRow<Chunk>>)>,
         new_active: Vec<(IdRow<Partition>, u64)>,
         new_active_min_max: Vec<(u64, (Option<Row>, Option<Row>), (Option<Row>, Option<Row>))>,
+        new_active_has_bloom_filters: Vec<bool>,
+        new_active_column_statistics: Vec<Option<ColumnStatistics>>,
     ) -> Result<(), CubeError>;
     async fn delete_partition(&self, partition_id: u64) -> Result<IdRow<Partition>, CubeError>;
     async fn mark_partition_warmed_up(&self, partition_id: u64) -> Result<(), CubeError>;
@@ -1047,6 +1176,7 @@ pub trait MetaStore: DIService + Send + Sync {
         min: Option<Row>,
         max: Option<Row>,
         in_memory: bool,
+        column_statistics: Option<ColumnStatistics>,
     ) -> Result<IdRow<Chunk>, CubeError>;
     async fn insert_chunks(&self, chunks: Vec<Chunk>) -> Result<Vec<IdRow<Chunk>>, CubeError>;
     async fn get_chunk(&self, chunk_id: u64) -> Result<IdRow<Chunk>, CubeError>;
@@ -1235,6 +1365,7 @@ pub enum MetaStoreEvent {
     // TODO: Split to CacheStoreEvent
     UpdateCacheItem(IdRow<CacheItem>, IdRow<CacheItem>),
     DeleteCacheItem(IdRow<CacheItem>),
+    CachePublish(CacheChannelMessage),
 
     UpdateQueueItem(IdRow<QueueItem>, IdRow<QueueItem>),
     DeleteQueueItem(IdRow<QueueItem>),
@@ -1523,6 +1654,25 @@ impl RocksMetaStore {
         let indexes_table = IndexRocksTable::new(db_ref.clone());
         let replay_handles_table = ReplayHandleRocksTable::new(db_ref.clone());
         let trace_objects_table = TraceObjectRocksTable::new(db_ref.clone());
+        let views = tables_table
+            .scan_all_rows()?
+            .filter_map(|t| match t {
+                Ok(t) => t
+                    .get_row()
+                    .materialized_view()
+                    .as_ref()
+                    .filter(|v| v.source_table_id == table_id)
+                    .map(|_| Ok(t.get_row().get_table_name().clone())),
+                Err(e) => Some(Err(e)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !views.is_empty() {
+            return Err(CubeError::user(format!(
+                "Can't drop table with id {} as it's used by materialized views: {}",
+                table_id,
+                views.join(", ")
+            )));
+        }
         let indexes = indexes_table
             .get_row_ids_by_index(&IndexIndexKey::TableId(table_id), &IndexRocksIndex::TableID)?;
         let trace_objects = trace_objects_table.get_rows_by_index(
@@ -1601,6 +1751,15 @@ impl RocksMetaStore {
                 table_id.get_row().get_table_name()
             )));
         }
+        if let Some(unsortable) = table_cols.iter().find(|c| {
+            index_def.columns.contains(&c.name)
+                && matches!(c.column_type, ColumnType::Json | ColumnType::Array(_))
+        }) {
+            return Err(CubeError::user(format!(
+                "Column '{}' of type {} can't be used in index '{}'",
+                unsortable.name, unsortable.column_type, index_def.name
+            )));
+        }
         let unique_key_columns = table_id.get_row().unique_key_columns();
         if let Some(unique_key) = &unique_key_columns {
             if let Some(not_found) = index_def
@@ -2089,6 +2248,9 @@ impl MetaStore for RocksMetaStore {
         trace_obj: Option<String>,
         drop_if_exists: bool,
         extension: Option<String>,
+        parquet_options: Option<ParquetOptions>,
+        retention: Option<RetentionPolicy>,
+        materialized_view: Option<MaterializedView>,
     ) -> Result<IdRow<Table>, CubeError> {
         self.write_operation(move |db_ref, batch_pipe| {
             batch_pipe.invalidate_tables_cache();
@@ -2192,7 +2354,10 @@ impl MetaStore for RocksMetaStore {
                 seq_column_index,
                 partition_split_threshold,
                 extension,
-            );
+            )
+            .with_parquet_options(parquet_options)
+            .with_retention(retention)
+            .with_materialized_view(materialized_view);
             let table_id = rocks_table.insert(table, batch_pipe)?;
 
             if let Some(trace_obj) = trace_obj {
@@ -2249,6 +2414,7 @@ impl MetaStore for RocksMetaStore {
                 .filter_map(|c| match c.get_column_type() {
                     ColumnType::Bytes => None,
                     ColumnType::HyperLogLog(_) => None,
+                    ColumnType::Uuid | ColumnType::Json | ColumnType::Array(_) => None,
                     _ => {
                         if !aggr_column_names.contains(&c.get_name())
                             && seq_column_index.is_none()
@@ -2303,6 +2469,23 @@ impl MetaStore for RocksMetaStore {
         .await
     }
 
+    async fn update_table_last_purge_at(
+        &self,
+        id: u64,
+        last_purge_at: DateTime<Utc>,
+    ) -> Result<IdRow<Table>, CubeError> {
+        self.write_operation(move |db_ref, batch_pipe| {
+            batch_pipe.invalidate_tables_cache();
+            let rocks_table = TableRocksTable::new(db_ref.clone());
+            Ok(rocks_table.update_with_fn(
+                id,
+                |r| r.update_last_purge_at(last_purge_at),
+                batch_pipe,
+            )?)
+        })
+        .await
+    }
+
     async fn get_trace_obj_by_table_id(&self, table_id: u64) -> Result<Option<String>, CubeError> {
         self.read_operation(move |db_ref| {
             let table = TraceObjectRocksTable::new(db_ref);
@@ -2415,6 +2598,39 @@ impl MetaStore for RocksMetaStore {
         }
     }
 
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn get_materialized_views(
+        &self,
+        source_table_id: u64,
+    ) -> Result<Vec<IdRow<Table>>, CubeError> {
+        let cache = self.store.cached_materialized_views.clone();
+        if let Some(v) = cube_ext::spawn_blocking(move || cache.lock().unwrap().clone()).await? {
+            return Ok(v.get(&source_table_id).cloned().unwrap_or_default());
+        }
+
+        let cache = self.store.cached_materialized_views.clone();
+        // Filled on the write loop thread so an invalidation can't be overwritten by a stale map.
+        self.read_operation(move |db_ref| {
+            let cached_views = { cache.lock().unwrap().clone() };
+            let views = if let Some(v) = cached_views {
+                v
+            } else {
+                let mut views = HashMap::<u64, Vec<IdRow<Table>>>::new();
+                for t in TableRocksTable::new(db_ref).scan_all_rows()? {
+                    let t = t?;
+                    if let Some(view) = t.get_row().materialized_view() {
+                        views.entry(view.source_table_id).or_default().push(t);
+                    }
+                }
+                let views = Arc::new(views);
+                *cache.lock().unwrap() = Some(views.clone());
+                views
+            };
+            Ok(views.get(&source_table_id).cloned().unwrap_or_default())
+        })
+        .await
+    }
+
     #[tracing::instrument(level = "trace", skip(self))]
     async fn not_ready_tables(
         &self,
@@ -2654,6 +2870,8 @@ impl MetaStore for RocksMetaStore {
         current_active: Vec<(IdRow<Partition>, Vec<IdRow<Chunk>>)>,
         new_active: Vec<(IdRow<Partition>, u64)>,
         mut new_active_min_max: Vec<(u64, (Option<Row>, Option<Row>), (Option<Row>, Option<Row>))>,
+        new_active_has_bloom_filters: Vec<bool>,
+        mut new_active_column_statistics: Vec<Option<ColumnStatistics>>,
     ) -> Result<(), CubeError> {
         trace!(
             "Swapping partitions: deactivating ({}), deactivating chunks ({}), activating ({})",
@@ -2673,7 +2891,11 @@ impl MetaStore for RocksMetaStore {
                 &new_active,
                 move |i, p| {
                     let (rows, (min_val, max_val), (min, max)) = take(&mut new_active_min_max[i]);
+                    let has_bloom_filters = new_active_has_bloom_filters.get(i) == Some(&true);
+                    let column_statistics = new_active_column_statistics.get_mut(i).and_then(take);
                     p.update_min_max_and_row_count(min_val, max_val, rows, min, max)
+                        .update_has_bloom_filters(has_bloom_filters)
+                        .update_column_statistics(column_statistics)
                 },
                 |current_i| {
                     Err(CubeError::internal(format!(
@@ -3467,6 +3689,12 @@ impl MetaStore for RocksMetaStore {
                 if p.row.active || p.row.main_table_row_count == 0 {
                     //maint_table_row_count == 0 means that partition is just created
                     filenames.push(partition_file_name(p.id, p.row.suffix()));
+                    if p.row.has_bloom_filters() {
+                        filenames.push(bloom_filter_file_name(&partition_file_name(
+                            p.id,
+                            p.row.suffix(),
+                        )));
+                    }
                 }
             }
             Ok(filenames)
@@ -3482,11 +3710,13 @@ impl MetaStore for RocksMetaStore {
         min: Option<Row>,
         max: Option<Row>,
         in_memory: bool,
+        column_statistics: Option<ColumnStatistics>,
     ) -> Result<IdRow<Chunk>, CubeError> {
         self.write_operation(move |db_ref, batch_pipe| {
             let rocks_chunk = ChunkRocksTable::new(db_ref.clone());
 
-            let chunk = Chunk::new(partition_id, row_count, min, max, in_memory);
+            let chunk = Chunk::new(partition_id, row_count, min, max, in_memory)
+                .set_column_statistics(column_statistics);
             let id_row = rocks_chunk.insert(chunk, batch_pipe)?;
 
             Ok(id_row)
@@ -4760,7 +4990,8 @@ fn swap_active_partitions_impl(
     let table = PartitionRocksTable::new(db_ref.clone());
     let chunk_table = ChunkRocksTable::new(db_ref.clone());
 
-    // Rows are compacted using unique key columns or aggregating index and totals don't match
+    // Rows are compacted using unique key columns or aggregating index, or purged by retention
+    // policy and totals don't match
     let skip_row_count_sanity_check = if let Some(current) = current_active.first() {
         let current_partition = table
             .get_row(current.0.get_id())?
@@ -4772,6 +5003,7 @@ fn swap_active_partitions_impl(
         let table = table_table.get_row_or_not_found(index.get_row().table_id())?;
         index.get_row().get_type() == IndexType::Aggregate
             || table.get_row().unique_key_columns().is_some()
+            || table.get_row().retention().is_some()
     } else {
         false
     };
@@ -4845,9 +5077,13 @@ fn swap_active_partitions_impl(
                 new_partition.get_row()
             )));
         }
-        let updated = update_new_partition_stats(i, new_partition.get_row())
-            .to_active(true)
-            .set_file_size(*new_file_size)?;
+        let updated = update_new_partition_stats(i, new_partition.get_row()).to_active(true);
+        // Partitions emptied by retention policy don't have a main table file.
+        let updated = if updated.main_table_row_count > 0 {
+            updated.set_file_size(*new_file_size)?
+        } else {
+            updated
+        };
         activated_row_count += updated.main_table_row_count;
         table.update(
             new_partition.get_id(),
@@ -5156,6 +5392,9 @@ mod tests {
                 None,
                 false,
                 None,
+                None,
+                None,
+                None,
             )
             .await
             .unwrap();
@@ -5180,6 +5419,9 @@ mod tests {
                 None,
                 false,
                 None,
+                None,
+                None,
+                None,
             )
             .await
             .unwrap();
@@ -5305,6 +5547,9 @@ mod tests {
                     None,
                     false,
                     None,
+                    None,
+                    None,
+                    None,
                 )
                 .await
                 .unwrap();
@@ -5331,6 +5576,9 @@ mod tests {
                     None,
                     false,
                     None,
+                    None,
+                    None,
+                    None,
                 )
                 .await
                 .is_err());
@@ -5423,6 +5671,9 @@ mod tests {
                     None,
                     false,
                     None,
+                    None,
+                    None,
+                    None,
                 )
                 .await
                 .unwrap();
@@ -5516,6 +5767,9 @@ mod tests {
                     None,
                     false,
                     None,
+                    None,
+                    None,
+                    None,
                 )
                 .await
                 .unwrap();
@@ -5590,6 +5844,9 @@ mod tests {
                     None,
                     false,
                     None,
+                    None,
+                    None,
+                    None,
                 )
                 .await
                 .is_err());
@@ -5614,6 +5871,9 @@ mod tests {
                     None,
                     false,
                     None,
+                    None,
+                    None,
+                    None,
                 )
                 .await
                 .is_err());
@@ -5641,6 +5901,9 @@ mod tests {
                     None,
                     false,
                     None,
+                    None,
+                    None,
+                    None,
                 )
                 .await
                 .is_err());
@@ -5707,6 +5970,9 @@ mod tests {
                     None,
                     false,
                     None,
+                    None,
+                    None,
+                    None,
                 )
                 .await
                 .unwrap();
@@ -6224,6 +6490,9 @@ mod tests {
                         None,
                         false,
                         None,
+                        None,
+                        None,
+                        None,
                     )
                     .await
                     .unwrap();
@@ -6447,6 +6716,9 @@ mod tests {
                     None,
                     false,
                     None,
+                    None,
+                    None,
+                    None,
                 )
                 .await
                 .unwrap();
@@ -6456,27 +6728,27 @@ mod tests {
 
             let mut source_ids: Vec<u64> = Vec::new();
             let ch = meta_store
-                .create_chunk(partition.get_id(), 10, None, None, true)
+                .create_chunk(partition.get_id(), 10, None, None, true, None)
                 .await
                 .unwrap();
             source_ids.push(ch.get_id());
             meta_store.chunk_uploaded(ch.get_id()).await.unwrap();
 
             let ch = meta_store
-                .create_chunk(partition.get_id(), 16, None, None, true)
+                .create_chunk(partition.get_id(), 16, None, None, true, None)
                 .await
                 .unwrap();
             source_ids.push(ch.get_id());
             meta_store.chunk_uploaded(ch.get_id()).await.unwrap();
 
             let dest_chunk = meta_store
-                .create_chunk(partition.get_id(), 26, None, None, true)
+                .create_chunk(partition.get_id(), 26, None, None, true, None)
                 .await
                 .unwrap();
             assert_eq!(dest_chunk.get_row().active(), false);
 
             let dest_chunk2 = meta_store
-                .create_chunk(partition.get_id(), 26, None, None, true)
+                .create_chunk(partition.get_id(), 26, None, None, true, None)
                 .await
                 .unwrap();
             assert_eq!(dest_chunk2.get_row().active(), false);
@@ -6510,14 +6782,14 @@ mod tests {
             //============= trying to use already active chunk as destination of swap ==============
             let mut source_ids: Vec<u64> = Vec::new();
             let ch = meta_store
-                .create_chunk(partition.get_id(), 10, None, None, true)
+                .create_chunk(partition.get_id(), 10, None, None, true, None)
                 .await
                 .unwrap();
             source_ids.push(ch.get_id());
             meta_store.chunk_uploaded(ch.get_id()).await.unwrap();
 
             let ch = meta_store
-                .create_chunk(partition.get_id(), 16, None, None, true)
+                .create_chunk(partition.get_id(), 16, None, None, true, None)
                 .await
                 .unwrap();
             source_ids.push(ch.get_id());
@@ -6589,6 +6861,9 @@ mod tests {
                     None,
                     false,
                     None,
+                    None,
+                    None,
+                    None,
                 )
                 .await
                 .unwrap();
@@ -6596,14 +6871,14 @@ mod tests {
 
             let mut source_chunks: Vec<IdRow<Chunk>> = Vec::new();
             let ch = meta_store
-                .create_chunk(partition.get_id(), 10, None, None, true)
+                .create_chunk(partition.get_id(), 10, None, None, true, None)
                 .await
                 .unwrap();
             meta_store.chunk_uploaded(ch.get_id()).await.unwrap();
             source_chunks.push(ch);
 
             let ch = meta_store
-                .create_chunk(partition.get_id(), 16, None, None, true)
+                .create_chunk(partition.get_id(), 16, None, None, true, None)
                 .await
                 .unwrap();
             meta_store.chunk_uploaded(ch.get_id()).await.unwrap();
@@ -6619,6 +6894,8 @@ mod tests {
                     vec![(partition.clone(), source_chunks.clone())],
                     vec![(dest_partition.clone(), 10)],
                     vec![(26, (None, None), (None, None))],
+                    vec![false],
+                    vec![None],
                 )
                 .await
                 .unwrap();
@@ -6656,14 +6933,14 @@ mod tests {
 
             let mut source_chunks: Vec<IdRow<Chunk>> = Vec::new();
             let ch = meta_store
-                .create_chunk(partition.clone().get_id(), 10, None, None, true)
+                .create_chunk(partition.clone().get_id(), 10, None, None, true, None)
                 .await
                 .unwrap();
             meta_store.chunk_uploaded(ch.get_id()).await.unwrap();
             source_chunks.push(ch);
 
             let ch = meta_store
-                .create_chunk(partition.get_id(), 16, None, None, true)
+                .create_chunk(partition.get_id(), 16, None, None, true, None)
                 .await
                 .unwrap();
             meta_store.chunk_uploaded(ch.get_id()).await.unwrap();
@@ -6679,6 +6956,8 @@ mod tests {
                     vec![(partition, source_chunks.clone())],
                     vec![(dest_partition.clone(), 10)],
                     vec![(26, (None, None), (None, None))],
+                    vec![false],
+                    vec![None],
                 )
                 .await
             {
@@ -6700,13 +6979,13 @@ mod tests {
                 .unwrap()
                 .to_owned();
             let ch = meta_store
-                .create_chunk(partition.clone().get_id(), 10, None, None, true)
+                .create_chunk(partition.clone().get_id(), 10, None, None, true, None)
                 .await
                 .unwrap();
             source_chunks.push(ch);
 
             let ch = meta_store
-                .create_chunk(partition.get_id(), 16, None, None, true)
+                .create_chunk(partition.get_id(), 16, None, None, true, None)
                 .await
                 .unwrap();
             source_chunks.push(ch);
@@ -6723,6 +7002,8 @@ mod tests {
                     vec![(partition, source_chunks.clone())],
                     vec![(dest_partition.clone(), 10)],
                     vec![(dest_row_count, (None, None), (None, None))],
+                    vec![false],
+                    vec![None],
                 )
                 .await
             {
@@ -6743,14 +7024,14 @@ mod tests {
                 .unwrap()
                 .to_owned();
             let ch = meta_store
-                .create_chunk(partition.clone().get_id(), 10, None, None, true)
+                .create_chunk(partition.clone().get_id(), 10, None, None, true, None)
                 .await
                 .unwrap();
             meta_store.chunk_uploaded(ch.get_id()).await.unwrap();
             source_chunks.push(ch);
 
             let ch = meta_store
-                .create_chunk(partition.get_id(), 16, None, None, true)
+                .create_chunk(partition.get_id(), 16, None, None, true, None)
                 .await
                 .unwrap();
             meta_store.chunk_uploaded(ch.get_id()).await.unwrap();
@@ -6763,6 +7044,8 @@ mod tests {
                     vec![(partition.clone(), source_chunks.clone())],
                     vec![(partition.clone(), 10)],
                     vec![(dest_row_count, (None, None), (None, None))],
+                    vec![false],
+                    vec![None],
                 )
                 .await
             {
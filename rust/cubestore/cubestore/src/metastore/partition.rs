@@ -1,6 +1,8 @@
 use super::{IndexId, Partition, RocksSecondaryIndex, TableId};
 use crate::metastore::IdRow;
 use crate::rocks_table_impl;
+use crate::table::bloom_filter::bloom_filter_file_name;
+use crate::table::statistics::ColumnStatistics;
 use crate::table::Row;
 use crate::{base_rocks_secondary_index, CubeError};
 use byteorder::{BigEndian, WriteBytesExt};
@@ -35,6 +37,8 @@ impl Partition {
             file_size: None,
             min: None,
             max: None,
+            has_bloom_filters: false,
+            column_statistics: None,
         }
     }
 
@@ -57,6 +61,8 @@ impl Partition {
             file_size: None,
             min: None,
             max: None,
+            has_bloom_filters: false,
+            column_statistics: None,
         }
     }
     pub fn get_min_val(&self) -> &Option<Row> {
@@ -129,6 +135,39 @@ impl Partition {
         p
     }
 
+    pub fn has_bloom_filters(&self) -> bool {
+        self.has_bloom_filters
+    }
+
+    pub fn update_has_bloom_filters(&self, has_bloom_filters: bool) -> Partition {
+        let mut p = self.clone();
+        p.has_bloom_filters = has_bloom_filters;
+        p
+    }
+
+    /// Side file with bloom filters of the main table file.
+    pub fn bloom_filter_file_name(&self, partition_id: u64) -> Option<String> {
+        if self.has_bloom_filters {
+            self.get_full_name(partition_id)
+                .map(|f| bloom_filter_file_name(&f))
+        } else {
+            None
+        }
+    }
+
+    pub fn get_column_statistics(&self) -> &Option<ColumnStatistics> {
+        &self.column_statistics
+    }
+
+    pub fn update_column_statistics(
+        &self,
+        column_statistics: Option<ColumnStatistics>,
+    ) -> Partition {
+        let mut p = self.clone();
+        p.column_statistics = column_statistics;
+        p
+    }
+
     pub fn file_size(&self) -> Option<u64> {
         self.file_size
     }
@@ -1,5 +1,5 @@
 use crate::config::ConfigObj;
-use crate::metastore::table::TablePath;
+use crate::metastore::table::{Table, TablePath};
 use crate::metastore::{MetaStoreEvent, MetaStoreFs};
 use crate::util::aborting_join_handle::AbortingJoinHandle;
 use crate::util::time_span::warn_long;
@@ -819,6 +819,8 @@ pub struct RocksStore {
     snapshot_uploaded: Arc<RwLock<bool>>,
     snapshots_upload_stopped: Arc<AsyncMutex<bool>>,
     pub(crate) cached_tables: Arc<Mutex<Option<Arc<Vec<TablePath>>>>>,
+    /// Ready materialized views by source table id. Invalidated along with `cached_tables`.
+    pub(crate) cached_materialized_views: Arc<Mutex<Option<Arc<HashMap<u64, Vec<IdRow<Table>>>>>>>,
     rw_loop_tx: tokio::sync::mpsc::Sender<
         Box<dyn FnOnce() -> Result<(), CubeError> + Send + Sync + 'static>,
     >,
@@ -898,6 +900,7 @@ impl RocksStore {
             snapshots_upload_stopped: Arc::new(AsyncMutex::new(false)),
             config,
             cached_tables: Arc::new(Mutex::new(None)),
+            cached_materialized_views: Arc::new(Mutex::new(None)),
             rw_loop_tx,
             _rw_loop_join_handle: Arc::new(AbortingJoinHandle::new(join_handle)),
             details,
@@ -990,6 +993,7 @@ impl RocksStore {
         let mem_seq = MemorySequence::new(self.seq_store.clone());
         let db_to_send = db.clone();
         let cached_tables = self.cached_tables.clone();
+        let cached_materialized_views = self.cached_materialized_views.clone();
         let store_name = self.details.get_name();
 
         let rw_loop_sender = self.rw_loop_tx.clone();
@@ -1013,6 +1017,7 @@ impl RocksStore {
                 Ok(res) => {
                     if batch.invalidate_tables_cache {
                         *cached_tables.lock().unwrap() = None;
+                        *cached_materialized_views.lock().unwrap() = None;
                     }
                     let write_result = batch.batch_write_rows()?;
                     tx.send(Ok((res, write_result))).map_err(|_| {
@@ -6,6 +6,8 @@ use crate::metastore::{IdRow, ImportFormat, RocksEntity, Schema};
 use crate::queryplanner::udfs::aggregate_udf_by_kind;
 use crate::queryplanner::udfs::CubeAggregateUDFKind;
 use crate::rocks_table_impl;
+use crate::table::statistics::ColumnStatistics;
+use crate::table::{Row, TableValue};
 use crate::{base_rocks_secondary_index, CubeError};
 use byteorder::{BigEndian, WriteBytesExt};
 use chrono::DateTime;
@@ -19,6 +21,7 @@ use itertools::Itertools;
 
 use serde::{Deserialize, Deserializer, Serialize};
 use std::io::Write;
+use std::str::FromStr;
 use std::sync::Arc;
 
 #[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq, Hash)]
@@ -125,6 +128,148 @@ impl DataFrameValue<String> for Option<StreamOffset> {
     }
 }
 
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Eq, PartialEq, Hash)]
+pub enum ParquetCompression {
+    Uncompressed,
+    Snappy,
+    Gzip,
+    Lz4,
+    Zstd,
+    Brotli,
+}
+
+impl FromStr for ParquetCompression {
+    type Err = CubeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" | "uncompressed" => Ok(ParquetCompression::Uncompressed),
+            "snappy" => Ok(ParquetCompression::Snappy),
+            "gzip" => Ok(ParquetCompression::Gzip),
+            "lz4" => Ok(ParquetCompression::Lz4),
+            "zstd" => Ok(ParquetCompression::Zstd),
+            "brotli" => Ok(ParquetCompression::Brotli),
+            _ => Err(CubeError::user(format!(
+                "Unsupported compression '{}'. Supported values: uncompressed, snappy, gzip, lz4, zstd, brotli",
+                s
+            ))),
+        }
+    }
+}
+
+/// Per-table overrides of the parquet writer settings.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, Eq, PartialEq, Hash)]
+pub struct ParquetOptions {
+    #[serde(default)]
+    pub compression: Option<ParquetCompression>,
+    #[serde(default)]
+    pub dictionary: Option<bool>,
+    /// Bloom filters of these columns are kept for each row group of partition files to prune
+    /// `=` and `IN` filters.
+    #[serde(default)]
+    pub bloom_filter_columns: Vec<String>,
+}
+
+impl DataFrameValue<String> for Option<ParquetOptions> {
+    fn value(v: &Self) -> String {
+        v.as_ref()
+            .map(|v| serde_json::to_string(v).unwrap())
+            .unwrap_or("NULL".to_string())
+    }
+}
+
+/// Rows with `column` older than `period_secs` are purged from the table.
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq, Hash)]
+pub struct RetentionPolicy {
+    pub column: String,
+    pub period_secs: u64,
+}
+
+impl RetentionPolicy {
+    /// Keeps cutoffs within the range of nanosecond timestamps.
+    pub const MAX_PERIOD_SECS: u64 = 100 * 366 * 24 * 60 * 60;
+
+    /// Rows with the retention column value less than the cutoff are expired. Returns `None` if
+    /// nothing can be expired, i.e. the period is out of the supported range.
+    pub fn cutoff(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if self.period_secs > Self::MAX_PERIOD_SECS {
+            return None;
+        }
+        now.checked_sub_signed(chrono::Duration::seconds(self.period_secs as i64))
+    }
+
+    /// Checks that all values of the timestamp `column` of a partition or chunk are known to be
+    /// less than `cutoff` nanos. Nulls never expire.
+    pub fn all_expired(
+        statistics: &Option<ColumnStatistics>,
+        min: &Option<Row>,
+        max: &Option<Row>,
+        column: usize,
+        cutoff: i64,
+    ) -> bool {
+        match statistics {
+            Some(s) => s.null_counts[column] == 0 && Self::before_cutoff(&s.max[column], cutoff),
+            // Min and max rows are ordered by the sort key, so only the first column can be
+            // checked. Nulls go first, so a non-null min value means there are no nulls.
+            None => match (min, max) {
+                (Some(min), Some(max)) if column == 0 => {
+                    Self::before_cutoff(&min.values()[0], cutoff)
+                        && Self::before_cutoff(&max.values()[0], cutoff)
+                }
+                _ => false,
+            },
+        }
+    }
+
+    /// Checks that some values of the timestamp `column` of a partition or chunk are known to be
+    /// less than `cutoff` nanos. Data without column statistics is only checked by the first
+    /// column of the sort key.
+    pub fn some_expired(
+        statistics: &Option<ColumnStatistics>,
+        min: &Option<Row>,
+        column: usize,
+        cutoff: i64,
+    ) -> bool {
+        match statistics {
+            Some(s) => Self::before_cutoff(&s.min[column], cutoff),
+            None => match min {
+                Some(min) if column == 0 => Self::before_cutoff(&min.values()[0], cutoff),
+                _ => false,
+            },
+        }
+    }
+
+    fn before_cutoff(v: &TableValue, cutoff: i64) -> bool {
+        match v {
+            TableValue::Timestamp(t) => t.get_time_stamp() < cutoff,
+            _ => false,
+        }
+    }
+}
+
+impl DataFrameValue<String> for Option<RetentionPolicy> {
+    fn value(v: &Self) -> String {
+        v.as_ref()
+            .map(|v| serde_json::to_string(v).unwrap())
+            .unwrap_or("NULL".to_string())
+    }
+}
+
+/// Aggregation over a streaming table, maintained incrementally as new rows of the source arrive.
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq, Hash)]
+pub struct MaterializedView {
+    pub source_table_id: u64,
+    pub select_statement: String,
+}
+
+impl DataFrameValue<String> for Option<MaterializedView> {
+    fn value(v: &Self) -> String {
+        v.as_ref()
+            .map(|v| serde_json::to_string(v).unwrap())
+            .unwrap_or("NULL".to_string())
+    }
+}
+
 data_frame_from! {
 #[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq, Hash)]
 pub struct Table {
@@ -163,6 +308,14 @@ pub struct Table {
     #[serde(default)]
     partition_split_threshold: Option<u64>,
     #[serde(default)]
+    parquet_options: Option<ParquetOptions>,
+    #[serde(default)]
+    retention: Option<RetentionPolicy>,
+    #[serde(default)]
+    last_purge_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    materialized_view: Option<MaterializedView>,
+    #[serde(default)]
     extension: Option<String>  // TODO: Make this an Option<serde_json::Value> or Option<json::JsonValue>?  We have some problems implementing Hash.
 }
 }
@@ -223,9 +376,29 @@ impl Table {
             seq_column_index,
             location_download_sizes,
             partition_split_threshold,
+            parquet_options: None,
+            retention: None,
+            last_purge_at: None,
+            materialized_view: None,
             extension,
         }
     }
+
+    pub fn with_parquet_options(mut self, parquet_options: Option<ParquetOptions>) -> Self {
+        self.parquet_options = parquet_options;
+        self
+    }
+
+    pub fn with_retention(mut self, retention: Option<RetentionPolicy>) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    pub fn with_materialized_view(mut self, materialized_view: Option<MaterializedView>) -> Self {
+        self.materialized_view = materialized_view;
+        self
+    }
+
     pub fn get_columns(&self) -> &Vec<Column> {
         &self.columns
     }
@@ -328,6 +501,28 @@ impl Table {
         &self.extension
     }
 
+    pub fn parquet_options(&self) -> &Option<ParquetOptions> {
+        &self.parquet_options
+    }
+
+    pub fn retention(&self) -> &Option<RetentionPolicy> {
+        &self.retention
+    }
+
+    pub fn last_purge_at(&self) -> &Option<DateTime<Utc>> {
+        &self.last_purge_at
+    }
+
+    pub fn update_last_purge_at(&self, last_purge_at: DateTime<Utc>) -> Self {
+        let mut table = self.clone();
+        table.last_purge_at = Some(last_purge_at);
+        table
+    }
+
+    pub fn materialized_view(&self) -> &Option<MaterializedView> {
+        &self.materialized_view
+    }
+
     pub fn source_columns(&self) -> &Option<Vec<Column>> {
         &self.source_columns
     }
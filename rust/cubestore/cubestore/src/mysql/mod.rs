@@ -95,6 +95,10 @@ impl<W: io::Write + Send> AsyncMysqlShim<W> for Backend {
                     metastore::ColumnType::Bytes => ColumnType::MYSQL_TYPE_STRING,
                     metastore::ColumnType::HyperLogLog(_) => ColumnType::MYSQL_TYPE_STRING,
                     metastore::ColumnType::Float => ColumnType::MYSQL_TYPE_STRING,
+                    metastore::ColumnType::Date => ColumnType::MYSQL_TYPE_STRING,
+                    metastore::ColumnType::Uuid => ColumnType::MYSQL_TYPE_STRING,
+                    metastore::ColumnType::Json => ColumnType::MYSQL_TYPE_STRING,
+                    metastore::ColumnType::Array(_) => ColumnType::MYSQL_TYPE_STRING,
                 },
                 colflags: ColumnFlags::empty(),
             })
@@ -127,6 +131,9 @@ impl<W: io::Write + Send> AsyncMysqlShim<W> for Backend {
                     TableValue::Bytes(b) => {
                         rw.write_col(format!("0x{}", b.encode_hex_upper::<String>()))?
                     }
+                    TableValue::Date(v) => rw.write_col(v.to_string())?,
+                    TableValue::Uuid(v) => rw.write_col(v.to_string())?,
+                    TableValue::Array(v) => rw.write_col(TableValue::array_to_json_string(v))?,
                     TableValue::Null => rw.write_col(Option::<String>::None)?,
                 }
             }
@@ -1,4 +1,6 @@
+use crate::queryplanner::running_queries::RunningQuery;
 use crate::util::memory::MemoryHandler;
+use crate::CubeError;
 use async_trait::async_trait;
 use datafusion::arrow::datatypes::SchemaRef;
 use datafusion::arrow::error::Result as ArrowResult;
@@ -18,13 +20,24 @@ use std::task::{Context, Poll};
 pub struct CheckMemoryExec {
     pub input: Arc<dyn ExecutionPlan>,
     pub memory_handler: Arc<dyn MemoryHandler>,
+    /// Query limits are checked and memory of returned batches is reserved for this query.
+    pub running_query: Option<Arc<RunningQuery>>,
+    /// True if the input reads data, i.e. it's not an exchange with other nodes.
+    pub is_scan: bool,
 }
 
 impl CheckMemoryExec {
-    pub fn new(input: Arc<dyn ExecutionPlan>, memory_handler: Arc<dyn MemoryHandler>) -> Self {
+    pub fn new(
+        input: Arc<dyn ExecutionPlan>,
+        memory_handler: Arc<dyn MemoryHandler>,
+        running_query: Option<Arc<RunningQuery>>,
+        is_scan: bool,
+    ) -> Self {
         Self {
             input,
             memory_handler,
+            running_query,
+            is_scan,
         }
     }
 }
@@ -55,6 +68,8 @@ impl ExecutionPlan for CheckMemoryExec {
         Ok(Arc::new(CheckMemoryExec {
             input: children.into_iter().next().unwrap(),
             memory_handler: self.memory_handler.clone(),
+            running_query: self.running_query.clone(),
+            is_scan: self.is_scan,
         }))
     }
 
@@ -77,6 +92,8 @@ impl ExecutionPlan for CheckMemoryExec {
         Ok(Box::pin(CheckMemoryStream {
             schema: self.schema(),
             memory_handler: self.memory_handler.clone(),
+            running_query: self.running_query.clone(),
+            is_scan: self.is_scan,
             input,
         }))
     }
@@ -85,20 +102,32 @@ impl ExecutionPlan for CheckMemoryExec {
 struct CheckMemoryStream {
     schema: SchemaRef,
     memory_handler: Arc<dyn MemoryHandler>,
+    running_query: Option<Arc<RunningQuery>>,
+    is_scan: bool,
     input: SendableRecordBatchStream,
 }
 
+impl CheckMemoryStream {
+    fn check(&self, batch: &RecordBatch) -> Result<(), CubeError> {
+        self.memory_handler.check_memory()?;
+        if let Some(running_query) = &self.running_query {
+            running_query.reserve(batch);
+            if self.is_scan {
+                running_query.record_scan(batch);
+            }
+            running_query.check_limits()?;
+        }
+        Ok(())
+    }
+}
+
 impl Stream for CheckMemoryStream {
     type Item = ArrowResult<RecordBatch>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         self.input.poll_next_unpin(cx).map(|x| match x {
             Some(Ok(batch)) => {
-                let r = self
-                    .memory_handler
-                    .check_memory()
-                    .map(|_| batch)
-                    .map_err(|e| e.into());
+                let r = self.check(&batch).map(|_| batch).map_err(|e| e.into());
                 Some(r)
             }
             other => other,
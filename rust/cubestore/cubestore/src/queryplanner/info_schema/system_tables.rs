@@ -55,6 +55,14 @@ impl InfoSchemaTableDef for SystemTablesTableDef {
             Field::new("sealed", DataType::Boolean, false),
             Field::new("select_statement", DataType::Utf8, false),
             Field::new("extension", DataType::Utf8, true),
+            Field::new("retention_column", DataType::Utf8, true),
+            Field::new("retention_period_secs", DataType::UInt64, true),
+            Field::new(
+                "last_purge_at",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                true,
+            ),
+            Field::new("materialized_view_source_table_id", DataType::UInt64, true),
         ]
     }
 
@@ -256,6 +264,62 @@ impl InfoSchemaTableDef for SystemTablesTableDef {
                         .collect::<Vec<_>>(),
                 ))
             }),
+            Box::new(|tables| {
+                Arc::new(StringArray::from(
+                    tables
+                        .iter()
+                        .map(|row| {
+                            row.table
+                                .get_row()
+                                .retention()
+                                .as_ref()
+                                .map(|r| r.column.as_str())
+                        })
+                        .collect::<Vec<_>>(),
+                ))
+            }),
+            Box::new(|tables| {
+                Arc::new(UInt64Array::from(
+                    tables
+                        .iter()
+                        .map(|row| {
+                            row.table
+                                .get_row()
+                                .retention()
+                                .as_ref()
+                                .map(|r| r.period_secs)
+                        })
+                        .collect::<Vec<_>>(),
+                ))
+            }),
+            Box::new(|tables| {
+                Arc::new(TimestampNanosecondArray::from(
+                    tables
+                        .iter()
+                        .map(|row| {
+                            row.table
+                                .get_row()
+                                .last_purge_at()
+                                .as_ref()
+                                .map(|t| t.timestamp_nanos())
+                        })
+                        .collect::<Vec<_>>(),
+                ))
+            }),
+            Box::new(|tables| {
+                Arc::new(UInt64Array::from(
+                    tables
+                        .iter()
+                        .map(|row| {
+                            row.table
+                                .get_row()
+                                .materialized_view()
+                                .as_ref()
+                                .map(|v| v.source_table_id)
+                        })
+                        .collect::<Vec<_>>(),
+                ))
+            }),
         ]
     }
 }
@@ -10,6 +10,7 @@ pub mod physical_plan_flags;
 pub mod pretty_printers;
 mod projection_above_limit;
 pub mod query_executor;
+pub mod running_queries;
 pub mod serialized_plan;
 mod tail_limit;
 mod topk;
@@ -24,8 +25,10 @@ pub mod providers;
 #[cfg(test)]
 mod test_utils;
 pub mod udfs;
+pub mod unload;
 
 use crate::cachestore::CacheStore;
+use crate::cluster::Cluster;
 use crate::config::injection::DIService;
 use crate::config::ConfigObj;
 use crate::metastore::multi_index::MultiPartition;
@@ -45,6 +48,7 @@ use crate::queryplanner::projection_above_limit::ProjectionAboveLimit;
 use crate::queryplanner::query_executor::{
     batches_to_dataframe, ClusterSendExec, InlineTableProvider,
 };
+use crate::queryplanner::running_queries::RunningQueries;
 use crate::queryplanner::serialized_plan::SerializedPlan;
 use crate::queryplanner::topk::ClusterAggregateTopK;
 use crate::queryplanner::udfs::aggregate_udf_by_kind;
@@ -102,6 +106,8 @@ pub struct QueryPlannerImpl {
     config: Arc<dyn ConfigObj>,
     cache: Arc<SqlResultCache>,
     metadata_cache_factory: Arc<dyn MetadataCacheFactory>,
+    running_queries: Arc<RunningQueries>,
+    cluster: Arc<dyn Cluster>,
 }
 
 crate::di_service!(QueryPlannerImpl, [QueryPlanner]);
@@ -127,6 +133,9 @@ impl QueryPlanner for QueryPlannerImpl {
             self.cache_store.clone(),
             inline_tables,
             self.cache.clone(),
+            self.running_queries.clone(),
+            self.cluster.clone(),
+            self.config.select_workers().clone(),
         );
 
         let query_planner = SqlToRel::new(&schema_provider);
@@ -184,6 +193,8 @@ impl QueryPlannerImpl {
         config: Arc<dyn ConfigObj>,
         cache: Arc<SqlResultCache>,
         metadata_cache_factory: Arc<dyn MetadataCacheFactory>,
+        running_queries: Arc<RunningQueries>,
+        cluster: Arc<dyn Cluster>,
     ) -> Arc<QueryPlannerImpl> {
         Arc::new(QueryPlannerImpl {
             meta_store,
@@ -191,6 +202,8 @@ impl QueryPlannerImpl {
             config,
             cache,
             metadata_cache_factory,
+            running_queries,
+            cluster,
         })
     }
 }
@@ -215,6 +228,9 @@ struct MetaStoreSchemaProvider {
     cache_store: Arc<dyn CacheStore>,
     inline_tables: InlineTables,
     cache: Arc<SqlResultCache>,
+    running_queries: Arc<RunningQueries>,
+    cluster: Arc<dyn Cluster>,
+    workers: Vec<String>,
 }
 
 /// Points into [MetaStoreSchemaProvider::data], never null.
@@ -251,6 +267,9 @@ impl MetaStoreSchemaProvider {
         cache_store: Arc<dyn CacheStore>,
         inline_tables: &InlineTables,
         cache: Arc<SqlResultCache>,
+        running_queries: Arc<RunningQueries>,
+        cluster: Arc<dyn Cluster>,
+        workers: Vec<String>,
     ) -> Self {
         let by_name = tables.iter().map(|t| TableKey(t)).collect();
         Self {
@@ -259,6 +278,9 @@ impl MetaStoreSchemaProvider {
             meta_store,
             cache_store,
             cache,
+            running_queries,
+            cluster,
+            workers,
             inline_tables: (*inline_tables).clone(),
         }
     }
@@ -346,6 +368,13 @@ impl ContextProvider for MetaStoreSchemaProvider {
             ("system", "query_cache") => Some(Arc::new(
                 providers::InfoSchemaQueryCacheTableProvider::new(self.cache.clone()),
             )),
+            ("system", "queries") => {
+                Some(Arc::new(providers::InfoSchemaQueriesTableProvider::new(
+                    self.running_queries.clone(),
+                    self.cluster.clone(),
+                    self.workers.clone(),
+                )))
+            }
             ("system", "cache") => Some(Arc::new(InfoSchemaTableProvider::new(
                 self.meta_store.clone(),
                 self.cache_store.clone(),
@@ -419,6 +448,10 @@ impl ContextProvider for MetaStoreSchemaProvider {
             "date_add" | "DATE_ADD" => CubeScalarUDFKind::DateAdd,
             "date_sub" | "DATE_SUB" => CubeScalarUDFKind::DateSub,
             "date_bin" | "DATE_BIN" => CubeScalarUDFKind::DateBin,
+            "json_extract_path" | "JSON_EXTRACT_PATH" => CubeScalarUDFKind::JsonExtractPath,
+            "json_extract_path_text" | "JSON_EXTRACT_PATH_TEXT" => {
+                CubeScalarUDFKind::JsonExtractPathText
+            }
             _ => return None,
         };
         return Some(Arc::new(scalar_udf_by_kind(kind).descriptor()));
@@ -775,6 +808,8 @@ fn compute_workers(
 pub mod tests {
     use super::*;
 
+    use crate::cluster::MockCluster;
+    use crate::config::Config;
     use crate::queryplanner::serialized_plan::SerializedPlan;
     use crate::sql::parser::{CubeStoreParser, Statement};
 
@@ -803,6 +838,9 @@ pub mod tests {
             Arc::new(test_utils::CacheStoreMock {}),
             &vec![],
             Arc::new(SqlResultCache::new(1 << 20, None, 10000)),
+            RunningQueries::new(Config::test("test").config_obj().as_ref()),
+            Arc::new(MockCluster::new()),
+            vec![],
         )
     }
 
@@ -826,6 +864,9 @@ pub mod tests {
         let plan = initial_plan("SELECT * FROM system.cache", get_test_execution_ctx());
         assert_eq!(SerializedPlan::is_data_select_query(&plan), false);
 
+        let plan = initial_plan("SELECT * FROM system.queries", get_test_execution_ctx());
+        assert_eq!(SerializedPlan::is_data_select_query(&plan), false);
+
         let plan = initial_plan("SELECT NOW()", get_test_execution_ctx());
         assert_eq!(SerializedPlan::is_data_select_query(&plan), false);
     }
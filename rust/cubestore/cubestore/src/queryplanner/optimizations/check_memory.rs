@@ -1,5 +1,6 @@
 use crate::queryplanner::check_memory::CheckMemoryExec;
 use crate::queryplanner::query_executor::ClusterSendExec;
+use crate::queryplanner::running_queries::RunningQuery;
 use crate::util::memory::MemoryHandler;
 use datafusion::error::DataFusionError;
 use datafusion::physical_plan::memory::MemoryExec;
@@ -11,10 +12,17 @@ use std::sync::Arc;
 pub fn add_check_memory_exec(
     p: Arc<dyn ExecutionPlan>,
     mem_handler: Arc<dyn MemoryHandler>,
+    running_query: Option<Arc<RunningQuery>>,
 ) -> Result<Arc<dyn ExecutionPlan>, DataFusionError> {
     let p_any = p.as_any();
     if p_any.is::<ParquetExec>() || p_any.is::<MemoryExec>() || p_any.is::<ClusterSendExec>() {
-        let memory_check = Arc::new(CheckMemoryExec::new(p, mem_handler.clone()));
+        let is_scan = !p_any.is::<ClusterSendExec>();
+        let memory_check = Arc::new(CheckMemoryExec::new(
+            p,
+            mem_handler.clone(),
+            running_query,
+            is_scan,
+        ));
         Ok(memory_check)
     } else {
         Ok(p)
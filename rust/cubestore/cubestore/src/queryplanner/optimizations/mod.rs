@@ -10,6 +10,7 @@ use crate::queryplanner::optimizations::distributed_partial_aggregate::{
 };
 use crate::queryplanner::optimizations::prefer_inplace_aggregates::try_switch_to_inplace_aggregates;
 use crate::queryplanner::planning::CubeExtensionPlanner;
+use crate::queryplanner::running_queries::RunningQuery;
 use crate::queryplanner::serialized_plan::SerializedPlan;
 use crate::queryplanner::trace_data_loaded::DataLoadedSize;
 use crate::util::memory::MemoryHandler;
@@ -28,6 +29,7 @@ pub struct CubeQueryPlanner {
     serialized_plan: Arc<SerializedPlan>,
     memory_handler: Arc<dyn MemoryHandler>,
     data_loaded_size: Option<Arc<DataLoadedSize>>,
+    running_query: Option<Arc<RunningQuery>>,
 }
 
 impl CubeQueryPlanner {
@@ -35,12 +37,14 @@ impl CubeQueryPlanner {
         cluster: Arc<dyn Cluster>,
         serialized_plan: Arc<SerializedPlan>,
         memory_handler: Arc<dyn MemoryHandler>,
+        running_query: Option<Arc<RunningQuery>>,
     ) -> CubeQueryPlanner {
         CubeQueryPlanner {
             cluster: Some(cluster),
             serialized_plan,
             memory_handler,
             data_loaded_size: None,
+            running_query,
         }
     }
 
@@ -48,12 +52,14 @@ impl CubeQueryPlanner {
         serialized_plan: Arc<SerializedPlan>,
         memory_handler: Arc<dyn MemoryHandler>,
         data_loaded_size: Option<Arc<DataLoadedSize>>,
+        running_query: Option<Arc<RunningQuery>>,
     ) -> CubeQueryPlanner {
         CubeQueryPlanner {
             serialized_plan,
             cluster: None,
             memory_handler,
             data_loaded_size,
+            running_query,
         }
     }
 }
@@ -75,6 +81,7 @@ impl QueryPlanner for CubeQueryPlanner {
             p,
             self.memory_handler.clone(),
             self.data_loaded_size.clone(),
+            self.running_query.clone(),
         )
     }
 }
@@ -83,11 +90,12 @@ fn finalize_physical_plan(
     p: Arc<dyn ExecutionPlan>,
     memory_handler: Arc<dyn MemoryHandler>,
     data_loaded_size: Option<Arc<DataLoadedSize>>,
+    running_query: Option<Arc<RunningQuery>>,
 ) -> Result<Arc<dyn ExecutionPlan>, DataFusionError> {
     let p = rewrite_physical_plan(p.as_ref(), &mut |p| try_switch_to_inplace_aggregates(p))?;
     let p = rewrite_physical_plan(p.as_ref(), &mut |p| push_aggregate_to_workers(p))?;
     let p = rewrite_physical_plan(p.as_ref(), &mut |p| {
-        add_check_memory_exec(p, memory_handler.clone())
+        add_check_memory_exec(p, memory_handler.clone(), running_query.clone())
     })?;
     let p = if let Some(data_loaded_size) = data_loaded_size {
         rewrite_physical_plan(p.as_ref(), &mut |p| {
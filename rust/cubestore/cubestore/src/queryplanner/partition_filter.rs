@@ -1,4 +1,7 @@
-use crate::table::{cmp_same_types, TableValue};
+use crate::sql::date_from_string;
+use crate::table::bloom_filter::{hash_scalar, ColumnBloomFilter};
+use crate::table::statistics::ColumnStatistics;
+use crate::table::{cmp_same_types, DateValue, TableValue, TimestampValue};
 use crate::util::decimal::Decimal;
 use datafusion::arrow::datatypes::{DataType, Schema};
 use datafusion::logical_plan::{Column, Expr, Operator};
@@ -46,6 +49,75 @@ impl PartitionFilter {
     }
 }
 
+/// Filter on per-column statistics of partition and chunk files. Unlike [PartitionFilter], it
+/// uses conditions on any index column, not only on the sort key prefix.
+#[derive(Debug)]
+pub struct ColumnStatisticsFilter {
+    num_columns: usize,
+    /// Same as in [PartitionFilter], but each column is checked separately.
+    min_max: Vec<MinMaxCondition>,
+    /// Column indices with `IS NULL` (true) or `IS NOT NULL` (false) conditions.
+    nulls: Vec<(usize, bool)>,
+}
+
+impl ColumnStatisticsFilter {
+    pub fn extract(s: &Schema, filters: &[Expr]) -> ColumnStatisticsFilter {
+        let builder = Builder { schema: s };
+
+        let mut min_max = vec![];
+        let mut nulls = vec![];
+        for f in filters {
+            min_max = builder.extract_filter(f, min_max);
+            builder.extract_nulls(f, &mut nulls);
+        }
+
+        ColumnStatisticsFilter {
+            num_columns: s.fields().len(),
+            min_max,
+            nulls,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min_max.is_empty() && self.nulls.is_empty()
+    }
+
+    /// Drops conditions on columns for which `keep` returns false. The filter can only become
+    /// less selective, so it's safe to use after that.
+    pub fn retain_columns(&mut self, keep: impl Fn(usize) -> bool) {
+        for mm in self.min_max.iter_mut() {
+            for i in 0..self.num_columns {
+                if !keep(i) {
+                    mm.min[i] = None;
+                    mm.max[i] = None;
+                }
+            }
+        }
+        self.nulls.retain(|(i, _)| keep(*i));
+    }
+
+    /// Returns false only when statistics prove that no rows of the file match the filter.
+    pub fn can_match(&self, stats: &ColumnStatistics) -> bool {
+        if stats.min.len() != self.num_columns
+            || stats.max.len() != self.num_columns
+            || stats.null_counts.len() != self.num_columns
+        {
+            return true;
+        }
+        for (i, is_null) in &self.nulls {
+            let null_count = stats.null_counts[*i];
+            if *is_null && null_count == 0 || !*is_null && null_count == stats.row_count {
+                return false;
+            }
+        }
+        self.min_max.is_empty()
+            || self
+                .min_max
+                .iter()
+                .any(|mm| mm.can_match_columns(&stats.min, &stats.max))
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 struct MinMaxCondition {
     min: Vec<Option<TableValue>>, // 'None' means no limit.
@@ -93,6 +165,36 @@ impl MinMaxCondition {
         return true;
     }
 
+    /// Checks every column separately. Values of `min` and `max` must not be nulls unless the
+    /// column contains only nulls.
+    pub fn can_match_columns(&self, min: &[TableValue], max: &[TableValue]) -> bool {
+        let n = self.min.len();
+        assert_eq!(n, min.len());
+        assert_eq!(n, max.len());
+        for i in 0..n {
+            let lower = self.min[i].as_ref().filter(|v| **v != TableValue::Null);
+            let upper = self.max[i].as_ref().filter(|v| **v != TableValue::Null);
+            if lower.is_none() && upper.is_none() {
+                continue;
+            }
+            // Comparisons never match nulls.
+            if min[i] == TableValue::Null {
+                return false;
+            }
+            if let Some(lower) = lower {
+                if cmp_same_types(&max[i], lower) < Ordering::Equal {
+                    return false;
+                }
+            }
+            if let Some(upper) = upper {
+                if cmp_same_types(upper, &min[i]) < Ordering::Equal {
+                    return false;
+                }
+            }
+        }
+        return true;
+    }
+
     pub fn can_match(&self, min_row: &[TableValue], max_row: &[TableValue]) -> bool {
         let n = self.min.len();
         assert_eq!(n, min_row.len());
@@ -145,6 +247,85 @@ impl MinMaxCondition {
     }
 }
 
+/// Equality conditions on columns with bloom filters, e.g. `user_id = 5` or `order_id IN (1, 2)`.
+#[derive(Debug)]
+pub struct BloomFilterPredicate {
+    /// All conditions must match. A condition matches if any of the value hashes is in the filter.
+    conditions: Vec<(String, Vec<u64>)>,
+}
+
+impl BloomFilterPredicate {
+    pub fn extract(bloom_filter_columns: &[String], filters: &[Expr]) -> BloomFilterPredicate {
+        let mut conditions = Vec::new();
+        for f in filters {
+            Self::extract_filter(bloom_filter_columns, f, &mut conditions);
+        }
+        BloomFilterPredicate { conditions }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
+    /// Drops conditions on columns for which `keep` returns false.
+    pub fn retain_columns(&mut self, keep: impl Fn(&str) -> bool) {
+        self.conditions.retain(|(column, _)| keep(column));
+    }
+
+    /// Returns false only when bloom filters prove that no rows match the predicate.
+    pub fn can_match(&self, bloom_filters: &[ColumnBloomFilter]) -> bool {
+        self.conditions.iter().all(|(column, hashes)| {
+            match bloom_filters.iter().find(|f| &f.column == column) {
+                Some(f) => hashes.iter().any(|h| f.filter.check_hash(*h)),
+                None => true,
+            }
+        })
+    }
+
+    fn extract_filter(columns: &[String], e: &Expr, conditions: &mut Vec<(String, Vec<u64>)>) {
+        let (column, values) = match e {
+            Expr::BinaryExpr {
+                left,
+                op: Operator::And,
+                right,
+            } => {
+                Self::extract_filter(columns, left, conditions);
+                Self::extract_filter(columns, right, conditions);
+                return;
+            }
+            Expr::BinaryExpr {
+                left: box Expr::Column(c),
+                op: Operator::Eq,
+                right: box v,
+            }
+            | Expr::BinaryExpr {
+                left: box v,
+                op: Operator::Eq,
+                right: box Expr::Column(c),
+            } => (c, vec![v]),
+            Expr::InList {
+                expr: box Expr::Column(c),
+                list,
+                negated: false,
+            } => (c, list.iter().collect()),
+            _ => return,
+        };
+        if !columns.contains(&column.name) {
+            return;
+        }
+        let hashes = values
+            .into_iter()
+            .map(|v| match v {
+                Expr::Literal(v) => hash_scalar(v),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>();
+        if let Some(hashes) = hashes {
+            conditions.push((column.name.clone(), hashes));
+        }
+    }
+}
+
 struct Builder<'a> {
     schema: &'a Schema,
 }
@@ -247,6 +428,27 @@ impl Builder<'_> {
         }
     }
 
+    /// Collects `IS NULL` and `IS NOT NULL` conditions on columns joined with `AND`.
+    fn extract_nulls(&self, e: &Expr, r: &mut Vec<(usize, bool)>) {
+        let (c, is_null) = match e {
+            Expr::BinaryExpr {
+                left,
+                op: Operator::And,
+                right,
+            } => {
+                self.extract_nulls(left, r);
+                self.extract_nulls(right, r);
+                return;
+            }
+            Expr::IsNull(box Expr::Column(c)) => (c, true),
+            Expr::IsNotNull(box Expr::Column(c)) => (c, false),
+            _ => return,
+        };
+        if let Some((i, _)) = self.schema.column_with_name(&c.name) {
+            r.push((i, is_null));
+        }
+    }
+
     /// <e_1> OR <e_2> OR ... OR <e_n>
     fn handle_or<Iter: Iterator<Item = Vec<MinMaxCondition>>>(
         &self,
@@ -409,11 +611,35 @@ impl Builder<'_> {
             DataType::Int64Decimal(scale) => Self::extract_decimal(v, *scale),
             DataType::Boolean => Self::extract_bool(v),
             DataType::Utf8 => Self::extract_string(v),
+            DataType::Timestamp(_, _) => Self::extract_timestamp(v),
+            DataType::Date32 => Self::extract_date(v),
             _ => None,
             // TODO: more data types
         }
     }
 
+    fn extract_timestamp(v: &ScalarValue) -> Option<TableValue> {
+        let nanos = match v {
+            ScalarValue::TimestampSecond(v) => v.as_ref()?.checked_mul(1_000_000_000)?,
+            ScalarValue::TimestampMillisecond(v) => v.as_ref()?.checked_mul(1_000_000)?,
+            ScalarValue::TimestampMicrosecond(v) => v.as_ref()?.checked_mul(1_000)?,
+            ScalarValue::TimestampNanosecond(v) => *v.as_ref()?,
+            _ => return None, // TODO: casts.
+        };
+        // Precision loss only widens the range, values are truncated the same way on write.
+        Some(TableValue::Timestamp(TimestampValue::new(nanos)))
+    }
+
+    fn extract_date(v: &ScalarValue) -> Option<TableValue> {
+        match v {
+            ScalarValue::Date32(v) => Some(TableValue::Date(DateValue::new(*v.as_ref()?))),
+            ScalarValue::Utf8(s) | ScalarValue::LargeUtf8(s) => {
+                Some(TableValue::Date(date_from_string(s.as_ref()?).ok()?))
+            }
+            _ => None,
+        }
+    }
+
     fn extract_bool(v: &ScalarValue) -> Option<TableValue> {
         match v {
             ScalarValue::Boolean(v) => v.as_ref().map(|v| TableValue::Boolean(*v)),
@@ -1431,6 +1657,123 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_bloom_filter_predicate() {
+        use crate::table::bloom_filter::{BloomFilter, ColumnBloomFilter};
+
+        let s = schema(&[("a", DataType::Int64), ("b", DataType::Utf8)]);
+        let columns = vec!["a".to_string(), "b".to_string()];
+        let extract = |sql| BloomFilterPredicate::extract(&columns, &[parse(sql, &s)]);
+        let filters = vec![
+            ColumnBloomFilter {
+                column: "a".to_string(),
+                filter: BloomFilter::from_hashes(
+                    (1..=3)
+                        .map(|i| hash_scalar(&ScalarValue::Int64(Some(i))).unwrap())
+                        .collect(),
+                ),
+            },
+            ColumnBloomFilter {
+                column: "b".to_string(),
+                filter: BloomFilter::from_hashes(vec![hash_scalar(&ScalarValue::Utf8(Some(
+                    "foo".to_string(),
+                )))
+                .unwrap()]),
+            },
+        ];
+
+        assert!(extract("a > 1").is_empty());
+        assert!(extract("a = 1 OR a = 100").is_empty());
+
+        assert!(extract("a = 1").can_match(&filters));
+        assert!(!extract("a = 100").can_match(&filters));
+        assert!(!extract("100 = a").can_match(&filters));
+        assert!(extract("a IN (100, 2)").can_match(&filters));
+        assert!(!extract("a IN (100, 200)").can_match(&filters));
+        assert!(extract("b = 'foo' AND a = 3").can_match(&filters));
+        assert!(!extract("b = 'bar' AND a = 3").can_match(&filters));
+        // Files written before the filters were enabled.
+        assert!(extract("b = 'bar'").can_match(&[]));
+
+        // Conditions on dropped columns are ignored.
+        let mut p = extract("b = 'bar' AND a = 3");
+        p.retain_columns(|c| c == "a");
+        assert!(p.can_match(&filters));
+    }
+
+    #[test]
+    fn test_column_statistics_filter() {
+        use datafusion::arrow::datatypes::TimeUnit;
+        use datafusion::logical_plan::col;
+
+        let s = schema(&[
+            ("a", DataType::Int64),
+            ("b", DataType::Utf8),
+            ("t", DataType::Timestamp(TimeUnit::Microsecond, None)),
+        ]);
+        let extract = |sql| ColumnStatisticsFilter::extract(&s, &[parse(sql, &s)]);
+        let stats = ColumnStatistics {
+            row_count: 10,
+            min: vec![
+                TableValue::Int(10),
+                TableValue::Null,
+                TableValue::Timestamp(TimestampValue::new(1_000_000_000)),
+            ],
+            max: vec![
+                TableValue::Int(20),
+                TableValue::Null,
+                TableValue::Timestamp(TimestampValue::new(2_000_000_000)),
+            ],
+            null_counts: vec![0, 10, 2],
+        };
+
+        assert!(extract("a + 1 > 100").is_empty());
+        assert!(extract("a = 15").can_match(&stats));
+        assert!(extract("a >= 20").can_match(&stats));
+        assert!(!extract("a > 20").can_match(&stats));
+        assert!(!extract("a < 10").can_match(&stats));
+        assert!(!extract("a IN (1, 2, 30)").can_match(&stats));
+        assert!(extract("a IN (1, 2, 15)").can_match(&stats));
+        assert!(extract("a < 5 OR a > 15").can_match(&stats));
+        assert!(!extract("a < 5 OR a > 25").can_match(&stats));
+
+        // Column with nulls only.
+        assert!(!extract("b = 'foo'").can_match(&stats));
+        assert!(extract("b IS NULL").can_match(&stats));
+        assert!(!extract("b IS NOT NULL").can_match(&stats));
+        assert!(!extract("a IS NULL").can_match(&stats));
+        assert!(extract("t IS NULL AND a IS NOT NULL").can_match(&stats));
+
+        let extract_expr = |e| ColumnStatisticsFilter::extract(&s, &[e]);
+        let ts = |seconds: i64| {
+            Expr::Literal(ScalarValue::TimestampNanosecond(Some(
+                seconds * 1_000_000_000,
+            )))
+        };
+        assert!(extract_expr(col("t").gt_eq(ts(1))).can_match(&stats));
+        assert!(!extract_expr(col("t").gt(ts(2))).can_match(&stats));
+        assert!(!extract_expr(col("t").lt(ts(1))).can_match(&stats));
+        assert!(extract_expr(col("t").lt_eq(ts(1))).can_match(&stats));
+
+        // Conditions on dropped columns are ignored.
+        let mut f = extract("a > 20 AND b IS NOT NULL");
+        f.retain_columns(|c| c == 1);
+        assert!(!f.can_match(&stats));
+        f.retain_columns(|c| c == 2);
+        assert!(f.can_match(&stats));
+        let mut f = extract("a < 5 OR (a > 25 AND t IS NULL)");
+        f.retain_columns(|c| c == 2);
+        assert!(f.can_match(&stats));
+
+        // Statistics of a different index are ignored.
+        assert!(extract("a > 20").can_match(&ColumnStatistics {
+            row_count: 1,
+            min: vec![TableValue::Int(1)],
+            max: vec![TableValue::Int(1)],
+            null_counts: vec![0],
+        }));
+    }
+
     fn schema(s: &[(&str, DataType)]) -> Schema {
         Schema::new(
             s.iter()
@@ -43,7 +43,9 @@ use crate::metastore::{
 };
 use crate::queryplanner::optimizations::rewrite_plan::{rewrite_plan, PlanRewriter};
 use crate::queryplanner::panic::{plan_panic_worker, PanicWorkerNode};
-use crate::queryplanner::partition_filter::PartitionFilter;
+use crate::queryplanner::partition_filter::{
+    BloomFilterPredicate, ColumnStatisticsFilter, PartitionFilter,
+};
 use crate::queryplanner::providers::InfoSchemaQueryCacheTableProvider;
 use crate::queryplanner::query_executor::{ClusterSendExec, CubeTable, InlineTableProvider};
 use crate::queryplanner::serialized_plan::{
@@ -1272,6 +1274,24 @@ fn optimal_index_by_score<'a, T: Iterator<Item = &'a IdRow<Index>>>(
         .map(|(index, _)| index)
 }
 
+/// Conditions of `filters` which bloom filters of partition files in `i` can be checked against.
+pub(crate) fn bloom_filter_predicate(i: &IndexSnapshot, filters: &[Expr]) -> BloomFilterPredicate {
+    let bloom_filter_columns = i
+        .table_path
+        .table
+        .get_row()
+        .parquet_options()
+        .as_ref()
+        .map(|o| o.bloom_filter_columns.as_slice())
+        .unwrap_or(&[]);
+    let mut predicate = BloomFilterPredicate::extract(bloom_filter_columns, filters);
+    // See `pick_partitions` on why only key columns can be used for merged rows.
+    if let Some(key_columns) = merged_rows_key_columns(i) {
+        predicate.retain_columns(|c| key_columns.iter().any(|k| k == c));
+    }
+    predicate
+}
+
 fn pick_partitions(
     i: &IndexSnapshot,
     c: &IndexConstraints,
@@ -1279,8 +1299,23 @@ fn pick_partitions(
 ) -> Result<Vec<PartitionSnapshot>, DataFusionError> {
     let partition_filter = PartitionFilter::extract(&partition_filter_schema(&i.index), &c.filters);
     log::trace!("Extracted partition filter is {:?}", partition_filter);
+    let bloom_filter_predicate = bloom_filter_predicate(i, &c.filters);
+    let mut statistics_filter =
+        ColumnStatisticsFilter::extract(&index_schema(&i.index), &c.filters);
+    // Newer rows of a unique key replace older ones and aggregated rows are merged across files.
+    // A file can't be dropped by conditions on other columns then, because its rows can hide
+    // or change rows of other files which match the filter.
+    if let Some(key_columns) = merged_rows_key_columns(i) {
+        let index_columns = i.index.get_row().get_columns();
+        statistics_filter.retain_columns(|c| key_columns.contains(index_columns[c].get_name()));
+    }
+    log::trace!(
+        "Extracted column statistics filter is {:?}",
+        statistics_filter
+    );
     let candidate_partitions = partitions.len();
     let mut pruned_partitions = 0;
+    let mut pruned_files = 0;
 
     let mut partition_snapshots = Vec::new();
     for (partition, chunks) in partitions.into_iter() {
@@ -1300,17 +1335,86 @@ fn pick_partitions(
             continue;
         }
 
-        partition_snapshots.push(PartitionSnapshot { chunks, partition });
+        let had_files = partition.get_row().has_main_table_file() || !chunks.is_empty();
+        let chunks = chunks
+            .into_iter()
+            .filter(|c| match c.get_row().column_statistics() {
+                Some(stats) if !statistics_filter.can_match(stats) => {
+                    pruned_files += 1;
+                    false
+                }
+                _ => true,
+            })
+            .map(|c| match c.get_row().column_statistics() {
+                // Workers don't need statistics, so we don't send them with the plan.
+                Some(_) => IdRow::new(c.get_id(), c.get_row().set_column_statistics(None)),
+                None => c,
+            })
+            .collect_vec();
+
+        let row = partition.get_row();
+        let main_table_file_can_match = row
+            .get_column_statistics()
+            .as_ref()
+            .map_or(true, |stats| statistics_filter.can_match(stats));
+        let skip_main_table_file = row.has_main_table_file() && !main_table_file_can_match;
+        if skip_main_table_file {
+            pruned_files += 1;
+        }
+        if had_files && (!row.has_main_table_file() || skip_main_table_file) && chunks.is_empty() {
+            pruned_partitions += 1;
+            continue;
+        }
+
+        // Workers skip row groups by bloom filters, they only download them when there is
+        // a predicate to check.
+        let has_bloom_filters = row.has_bloom_filters() && !bloom_filter_predicate.is_empty();
+        let row = row
+            .update_has_bloom_filters(has_bloom_filters)
+            .update_column_statistics(None);
+        let partition = IdRow::new(partition.get_id(), row);
+        partition_snapshots.push(PartitionSnapshot {
+            chunks,
+            partition,
+            skip_main_table_file,
+        });
     }
     log::trace!(
-        "Pruned {} of {} partitions",
+        "Pruned {} of {} partitions, {} files by statistics",
         pruned_partitions,
-        candidate_partitions
+        candidate_partitions,
+        pruned_files
     );
 
     Ok(partition_snapshots)
 }
 
+/// Columns identifying rows which are deduplicated or aggregated across files of a partition,
+/// `None` when rows of every file are independent.
+fn merged_rows_key_columns(i: &IndexSnapshot) -> Option<Vec<String>> {
+    let table = i.table_path.table.get_row();
+    if let Some(unique_key) = table.unique_key_columns() {
+        return Some(unique_key.iter().map(|c| c.get_name().clone()).collect());
+    }
+    let index = i.index.get_row();
+    if matches!(index.get_type(), IndexType::Aggregate) || !table.aggregate_columns().is_empty() {
+        let sort_key = &index.get_columns()[..index.sort_key_size() as usize];
+        return Some(sort_key.iter().map(|c| c.get_name().clone()).collect());
+    }
+    None
+}
+
+fn index_schema(index: &IdRow<Index>) -> datafusion::arrow::datatypes::Schema {
+    datafusion::arrow::datatypes::Schema::new(
+        index
+            .get_row()
+            .columns()
+            .iter()
+            .map(|c| c.clone().into())
+            .collect(),
+    )
+}
+
 fn partition_filter_schema(index: &IdRow<Index>) -> datafusion::arrow::datatypes::Schema {
     let schema_fields: Vec<Field>;
     schema_fields = index
@@ -1,3 +1,5 @@
+mod queries;
 mod query_cache;
 
+pub use queries::InfoSchemaQueriesTableProvider;
 pub use query_cache::InfoSchemaQueryCacheTableProvider;
@@ -0,0 +1,209 @@
+use crate::cluster::Cluster;
+use crate::queryplanner::project_schema;
+use crate::queryplanner::running_queries::{RunningQueries, RunningQueryStatus};
+use async_trait::async_trait;
+use datafusion::arrow::array::{Array, Int64Builder, StringBuilder, TimestampNanosecondBuilder};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::datasource::Statistics;
+use datafusion::datasource::TableProvider;
+use datafusion::error::DataFusionError;
+use datafusion::logical_plan::Expr;
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::Partitioning;
+use datafusion::physical_plan::{ExecutionPlan, SendableRecordBatchStream};
+use std::any::Any;
+use std::fmt;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+/// Shows queries of the router along with query parts running on the workers.
+pub struct InfoSchemaQueriesTableProvider {
+    running_queries: Arc<RunningQueries>,
+    cluster: Arc<dyn Cluster>,
+    workers: Vec<String>,
+}
+
+impl InfoSchemaQueriesTableProvider {
+    pub fn new(
+        running_queries: Arc<RunningQueries>,
+        cluster: Arc<dyn Cluster>,
+        workers: Vec<String>,
+    ) -> Self {
+        Self {
+            running_queries,
+            cluster,
+            workers,
+        }
+    }
+}
+
+fn get_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int64, false),
+        Field::new("sql", DataType::Utf8, false),
+        Field::new(
+            "started_at",
+            DataType::Timestamp(TimeUnit::Nanosecond, None),
+            false,
+        ),
+        Field::new("node", DataType::Utf8, false),
+        Field::new("memory_usage", DataType::Int64, false),
+        Field::new("rows_scanned", DataType::Int64, false),
+    ]))
+}
+
+impl TableProvider for InfoSchemaQueriesTableProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        get_schema()
+    }
+
+    fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _batch_size: usize,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>, DataFusionError> {
+        let exec = InfoSchemaQueriesTableExec {
+            running_queries: self.running_queries.clone(),
+            cluster: self.cluster.clone(),
+            workers: self.workers.clone(),
+            projection: projection.clone(),
+            projected_schema: project_schema(&self.schema(), projection.as_deref()),
+        };
+
+        Ok(Arc::new(exec))
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics {
+            num_rows: None,
+            total_byte_size: None,
+            column_statistics: None,
+        }
+    }
+}
+
+struct InfoSchemaQueriesBuilder {
+    id: Int64Builder,
+    sql: StringBuilder,
+    started_at: TimestampNanosecondBuilder,
+    node: StringBuilder,
+    memory_usage: Int64Builder,
+    rows_scanned: Int64Builder,
+}
+
+impl InfoSchemaQueriesBuilder {
+    fn new(capacity: usize) -> Self {
+        Self {
+            id: Int64Builder::new(capacity),
+            sql: StringBuilder::new(capacity),
+            started_at: TimestampNanosecondBuilder::new(capacity),
+            node: StringBuilder::new(capacity),
+            memory_usage: Int64Builder::new(capacity),
+            rows_scanned: Int64Builder::new(capacity),
+        }
+    }
+
+    fn add_row(&mut self, query: &RunningQueryStatus) {
+        self.id.append_value(query.id as i64).unwrap();
+        self.sql.append_value(&query.sql).unwrap();
+        self.started_at
+            .append_value(query.started_at.timestamp_nanos())
+            .unwrap();
+        self.node.append_value(&query.node).unwrap();
+        self.memory_usage
+            .append_value(query.memory_usage.try_into().unwrap_or(i64::MAX))
+            .unwrap();
+        self.rows_scanned
+            .append_value(query.rows_scanned.try_into().unwrap_or(i64::MAX))
+            .unwrap();
+    }
+
+    fn finish(mut self) -> Vec<Arc<dyn Array>> {
+        let mut columns: Vec<Arc<dyn Array>> = vec![];
+        columns.push(Arc::new(self.id.finish()));
+        columns.push(Arc::new(self.sql.finish()));
+        columns.push(Arc::new(self.started_at.finish()));
+        columns.push(Arc::new(self.node.finish()));
+        columns.push(Arc::new(self.memory_usage.finish()));
+        columns.push(Arc::new(self.rows_scanned.finish()));
+
+        columns
+    }
+}
+
+#[derive(Clone)]
+pub struct InfoSchemaQueriesTableExec {
+    running_queries: Arc<RunningQueries>,
+    cluster: Arc<dyn Cluster>,
+    workers: Vec<String>,
+    projection: Option<Vec<usize>>,
+    projected_schema: SchemaRef,
+}
+
+impl std::fmt::Debug for InfoSchemaQueriesTableExec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(&format!(
+            "MetaTabular(running_queries: hidden, projected_schema: {:?})",
+            self.projected_schema
+        ))
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for InfoSchemaQueriesTableExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.projected_schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        &self,
+        _children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>, DataFusionError> {
+        Ok(Arc::new(self.clone()))
+    }
+
+    async fn execute(
+        &self,
+        partition: usize,
+    ) -> Result<SendableRecordBatchStream, DataFusionError> {
+        let mut queries = self.running_queries.statuses();
+        for worker in self.workers.iter() {
+            if worker.as_str() != self.cluster.server_name() {
+                queries.extend(self.cluster.list_queries(worker).await?);
+            }
+        }
+        queries.sort_by(|a, b| a.id.cmp(&b.id).then_with(|| a.node.cmp(&b.node)));
+        let mut builder = InfoSchemaQueriesBuilder::new(queries.len());
+
+        for query in queries.iter() {
+            builder.add_row(query);
+        }
+
+        let data = builder.finish();
+        let batch = RecordBatch::try_new(get_schema(), data.to_vec())?;
+
+        // TODO: Please migrate to real streaming, if we are going to expose query results
+        let mem_exec =
+            MemoryExec::try_new(&vec![vec![batch]], self.schema(), self.projection.clone())?;
+        mem_exec.execute(partition).await
+    }
+}
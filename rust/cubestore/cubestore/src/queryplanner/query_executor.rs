@@ -6,14 +6,20 @@ use crate::metastore::table::Table;
 use crate::metastore::{Column, ColumnType, IdRow, Index, Partition};
 use crate::queryplanner::filter_by_key_range::FilterByKeyRangeExec;
 use crate::queryplanner::optimizations::CubeQueryPlanner;
+use crate::queryplanner::partition_filter::BloomFilterPredicate;
 use crate::queryplanner::physical_plan_flags::PhysicalPlanFlags;
-use crate::queryplanner::planning::{get_worker_plan, Snapshot, Snapshots};
+use crate::queryplanner::planning::{bloom_filter_predicate, get_worker_plan, Snapshot, Snapshots};
 use crate::queryplanner::pretty_printers::{pp_phys_plan, pp_plan};
-use crate::queryplanner::serialized_plan::{IndexSnapshot, RowFilter, RowRange, SerializedPlan};
+use crate::queryplanner::running_queries::RunningQueries;
+use crate::queryplanner::serialized_plan::{
+    IndexSnapshot, PartitionSnapshot, RowFilter, RowRange, SerializedPlan,
+};
 use crate::queryplanner::trace_data_loaded::DataLoadedSize;
+use crate::queryplanner::unload::{UnloadStorage, UnloadTarget, UnloadedFile};
 use crate::store::DataFrame;
+use crate::table::bloom_filter::RowGroupBloomFilters;
 use crate::table::data::rows_to_columns;
-use crate::table::parquet::CubestoreParquetMetadataCache;
+use crate::table::parquet::{CubestoreParquetMetadataCache, SkipRowGroupsMetadataCache};
 use crate::table::{Row, TableValue, TimestampValue};
 use crate::telemetry::suboptimal_query_plan_event;
 use crate::util::memory::MemoryHandler;
@@ -50,6 +56,7 @@ use datafusion::physical_plan::projection::ProjectionExec;
 use datafusion::physical_plan::{
     collect, ExecutionPlan, OptimizerHints, Partitioning, PhysicalExpr, SendableRecordBatchStream,
 };
+use futures::future::join_all;
 use itertools::Itertools;
 use log::{debug, error, trace, warn};
 use mockall::automock;
@@ -73,6 +80,17 @@ pub trait QueryExecutor: DIService + Send + Sync {
         cluster: Arc<dyn Cluster>,
     ) -> Result<(SchemaRef, Vec<RecordBatch>), CubeError>;
 
+    /// Writes results of the query into `target`. When the results do not need a final merge
+    /// on the router, every worker writes its part of the results in parallel. Otherwise every
+    /// partition of the router plan is streamed into its own file.
+    async fn execute_router_unload(
+        &self,
+        plan: SerializedPlan,
+        cluster: Arc<dyn Cluster>,
+        target: UnloadTarget,
+        uploads_dir: String,
+    ) -> Result<Vec<UnloadedFile>, CubeError>;
+
     async fn execute_worker_plan(
         &self,
         plan: SerializedPlan,
@@ -80,6 +98,18 @@ pub trait QueryExecutor: DIService + Send + Sync {
         chunk_id_to_record_batches: HashMap<u64, Vec<RecordBatch>>,
     ) -> Result<(SchemaRef, Vec<RecordBatch>, usize), CubeError>;
 
+    /// Writes results of the worker part of the query into `target` as they are computed.
+    /// Returns the written file along with the size of loaded data.
+    async fn execute_worker_unload(
+        &self,
+        plan: SerializedPlan,
+        remote_to_local_names: HashMap<String, String>,
+        chunk_id_to_record_batches: HashMap<u64, Vec<RecordBatch>>,
+        target: UnloadTarget,
+        uploads_dir: String,
+        file_name: String,
+    ) -> Result<(Option<UnloadedFile>, usize), CubeError>;
+
     async fn router_plan(
         &self,
         plan: SerializedPlan,
@@ -109,6 +139,8 @@ pub struct QueryExecutorImpl {
     metadata_cache_factory: Arc<dyn MetadataCacheFactory>,
     parquet_metadata_cache: Arc<dyn CubestoreParquetMetadataCache>,
     memory_handler: Arc<dyn MemoryHandler>,
+    running_queries: Arc<RunningQueries>,
+    unload_storage: UnloadStorage,
 }
 
 crate::di_service!(QueryExecutorImpl, [QueryExecutor]);
@@ -123,6 +155,9 @@ impl QueryExecutor for QueryExecutorImpl {
     ) -> Result<(SchemaRef, Vec<RecordBatch>), CubeError> {
         let collect_span = tracing::span!(tracing::Level::TRACE, "collect_physical_plan");
         let trace_obj = plan.trace_obj();
+        let _running_query = plan
+            .running_query()
+            .map(|q| self.running_queries.start_part(q));
         let (physical_plan, logical_plan) = self.router_plan(plan, cluster).await?;
         let split_plan = physical_plan;
 
@@ -171,6 +206,87 @@ impl QueryExecutor for QueryExecutorImpl {
         Ok((split_plan.schema(), results?))
     }
 
+    #[instrument(level = "trace", skip(self, plan, cluster))]
+    async fn execute_router_unload(
+        &self,
+        plan: SerializedPlan,
+        cluster: Arc<dyn Cluster>,
+        target: UnloadTarget,
+        uploads_dir: String,
+    ) -> Result<Vec<UnloadedFile>, CubeError> {
+        let _running_query = plan
+            .running_query()
+            .map(|q| self.running_queries.start_part(q));
+        let (physical_plan, logical_plan) = self.router_plan(plan, cluster.clone()).await?;
+        trace!(
+            "Router Unload Physical Plan: {}",
+            pp_phys_plan(physical_plan.as_ref())
+        );
+
+        let execution_time = SystemTime::now();
+        let files = if let Some(send) = physical_plan.as_any().downcast_ref::<ClusterSendExec>() {
+            // Nothing is left to do on the router, so workers write their results directly.
+            let futures = send
+                .worker_plans()
+                .into_iter()
+                .enumerate()
+                .map(|(i, (node_name, plan))| {
+                    let cluster = cluster.clone();
+                    let target = target.clone();
+                    async move {
+                        cluster
+                            .run_unload(&node_name, plan, target, format!("part-{:05}", i))
+                            .await
+                    }
+                })
+                .collect::<Vec<_>>();
+            join_all(futures)
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+        } else {
+            // Every partition of the router plan is written into its own file.
+            let futures = (0..physical_plan.output_partitioning().partition_count())
+                .map(|i| {
+                    let physical_plan = physical_plan.clone();
+                    let target = &target;
+                    let uploads_dir = &uploads_dir;
+                    async move {
+                        let stream = physical_plan.execute(i).await?;
+                        target
+                            .write_stream(
+                                &self.unload_storage,
+                                uploads_dir,
+                                &format!("part-{:05}", i),
+                                stream,
+                            )
+                            .await
+                    }
+                })
+                .collect::<Vec<_>>();
+            join_all(futures)
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+        };
+        let execution_time = execution_time.elapsed()?;
+        debug!("Unload data processing time: {:?}", execution_time);
+        if execution_time.as_millis() > 200 {
+            warn!(
+                "Slow Unload ({:?}):\n{}",
+                execution_time,
+                pp_plan(&logical_plan)
+            );
+        }
+        Ok(files)
+    }
+
     #[instrument(level = "trace", skip(self, plan, remote_to_local_names))]
     async fn execute_worker_plan(
         &self,
@@ -179,6 +295,9 @@ impl QueryExecutor for QueryExecutorImpl {
         chunk_id_to_record_batches: HashMap<u64, Vec<RecordBatch>>,
     ) -> Result<(SchemaRef, Vec<RecordBatch>, usize), CubeError> {
         let data_loaded_size = DataLoadedSize::new();
+        let _running_query = plan
+            .running_query()
+            .map(|q| self.running_queries.start_part(q));
         let (physical_plan, logical_plan) = self
             .worker_plan(
                 plan,
@@ -244,6 +363,68 @@ impl QueryExecutor for QueryExecutorImpl {
         Ok((worker_plan.schema(), results, data_loaded_size.get()))
     }
 
+    #[instrument(level = "trace", skip(self, plan, remote_to_local_names))]
+    async fn execute_worker_unload(
+        &self,
+        plan: SerializedPlan,
+        remote_to_local_names: HashMap<String, String>,
+        chunk_id_to_record_batches: HashMap<u64, Vec<RecordBatch>>,
+        target: UnloadTarget,
+        uploads_dir: String,
+        file_name: String,
+    ) -> Result<(Option<UnloadedFile>, usize), CubeError> {
+        let data_loaded_size = DataLoadedSize::new();
+        let _running_query = plan
+            .running_query()
+            .map(|q| self.running_queries.start_part(q));
+        let (physical_plan, logical_plan) = self
+            .worker_plan(
+                plan,
+                remote_to_local_names,
+                chunk_id_to_record_batches,
+                Some(data_loaded_size.clone()),
+            )
+            .await?;
+        let worker_plan = match get_worker_plan(&physical_plan) {
+            Some((p, _)) => p,
+            None => {
+                error!("No worker marker in physical plan: {:?}", physical_plan);
+                return Err(CubeError::internal(
+                    "Invalid physical plan on worker".to_string(),
+                ));
+            }
+        };
+        trace!(
+            "Partition Unload Physical Plan: {}",
+            pp_phys_plan(worker_plan.as_ref())
+        );
+
+        let execution_time = SystemTime::now();
+        let worker_plan: Arc<dyn ExecutionPlan> =
+            if worker_plan.output_partitioning().partition_count() == 1 {
+                worker_plan
+            } else {
+                Arc::new(MergeExec::new(worker_plan))
+            };
+        let stream = worker_plan.execute(0).await?;
+        let file = target
+            .write_stream(&self.unload_storage, &uploads_dir, &file_name, stream)
+            .await?;
+        let execution_time = execution_time.elapsed()?;
+        debug!(
+            "Partition Unload data processing time: {:?}",
+            execution_time
+        );
+        if execution_time.as_millis() > 200 {
+            warn!(
+                "Slow Partition Unload ({:?}):\n{}",
+                execution_time,
+                pp_plan(&logical_plan)
+            );
+        }
+        Ok((file, data_loaded_size.get()))
+    }
+
     async fn router_plan(
         &self,
         plan: SerializedPlan,
@@ -317,11 +498,15 @@ impl QueryExecutorImpl {
         metadata_cache_factory: Arc<dyn MetadataCacheFactory>,
         parquet_metadata_cache: Arc<dyn CubestoreParquetMetadataCache>,
         memory_handler: Arc<dyn MemoryHandler>,
+        running_queries: Arc<RunningQueries>,
+        unload_storage: UnloadStorage,
     ) -> Arc<Self> {
         Arc::new(QueryExecutorImpl {
             metadata_cache_factory,
             parquet_metadata_cache,
             memory_handler,
+            running_queries,
+            unload_storage,
         })
     }
 
@@ -330,6 +515,9 @@ impl QueryExecutorImpl {
         cluster: Arc<dyn Cluster>,
         serialized_plan: Arc<SerializedPlan>,
     ) -> Result<Arc<ExecutionContext>, CubeError> {
+        let running_query = serialized_plan
+            .running_query()
+            .and_then(|q| self.running_queries.get(q.id));
         Ok(Arc::new(ExecutionContext::with_config(
             ExecutionConfig::new()
                 .with_metadata_cache_factory(self.metadata_cache_factory.clone())
@@ -339,6 +527,7 @@ impl QueryExecutorImpl {
                     cluster,
                     serialized_plan,
                     self.memory_handler.clone(),
+                    running_query,
                 ))),
         )))
     }
@@ -348,6 +537,9 @@ impl QueryExecutorImpl {
         serialized_plan: Arc<SerializedPlan>,
         data_loaded_size: Option<Arc<DataLoadedSize>>,
     ) -> Result<Arc<ExecutionContext>, CubeError> {
+        let running_query = serialized_plan
+            .running_query()
+            .and_then(|q| self.running_queries.get(q.id));
         Ok(Arc::new(ExecutionContext::with_config(
             ExecutionConfig::new()
                 .with_metadata_cache_factory(self.metadata_cache_factory.clone())
@@ -357,6 +549,7 @@ impl QueryExecutorImpl {
                     serialized_plan,
                     self.memory_handler.clone(),
                     data_loaded_size,
+                    running_query,
                 ))),
         )))
     }
@@ -543,6 +736,7 @@ impl CubeTable {
         };
 
         let predicate = combine_filters(filters);
+        let bloom_filter_predicate = bloom_filter_predicate(&self.index_snapshot, filters);
         for partition_snapshot in partition_snapshots {
             let partition = partition_snapshot.partition();
             let filter = self
@@ -555,22 +749,27 @@ impl CubeTable {
 
             let key_len = self.index_snapshot.index.get_row().sort_key_size() as usize;
 
-            if let Some(remote_path) = partition.get_row().get_full_name(partition.get_id()) {
+            if let Some(remote_path) = partition_snapshot.main_table_file() {
                 let local_path = self
                     .remote_to_local_names
                     .get(remote_path.as_str())
                     .expect(format!("Missing remote path {}", remote_path).as_str());
-                let arc: Arc<dyn ExecutionPlan> = Arc::new(ParquetExec::try_from_path_with_cache(
-                    &local_path,
-                    index_projection_or_none_on_schema_match.clone(),
-                    predicate.clone(),
-                    batch_size,
-                    1,
-                    None, // TODO: propagate limit
-                    self.parquet_metadata_cache.clone(),
-                )?);
-                let arc = FilterByKeyRangeExec::issue_filters(arc, filter.clone(), key_len);
-                partition_execs.push(arc);
+                if let Some(metadata_cache) = self
+                    .main_table_file_metadata_cache(partition_snapshot, &bloom_filter_predicate)?
+                {
+                    let arc: Arc<dyn ExecutionPlan> =
+                        Arc::new(ParquetExec::try_from_path_with_cache(
+                            &local_path,
+                            index_projection_or_none_on_schema_match.clone(),
+                            predicate.clone(),
+                            batch_size,
+                            1,
+                            None, // TODO: propagate limit
+                            metadata_cache,
+                        )?);
+                    let arc = FilterByKeyRangeExec::issue_filters(arc, filter.clone(), key_len);
+                    partition_execs.push(arc);
+                }
             }
 
             let chunks = partition_snapshot.chunks();
@@ -764,6 +963,39 @@ impl CubeTable {
         Ok(plan)
     }
 
+    /// Metadata cache to read the main table file of the partition with. Row groups which bloom
+    /// filters prove to have no matching rows are hidden from the reader. Returns [None] if no
+    /// row groups have to be read at all.
+    fn main_table_file_metadata_cache(
+        &self,
+        partition_snapshot: &PartitionSnapshot,
+        bloom_filter_predicate: &BloomFilterPredicate,
+    ) -> Result<Option<Arc<dyn ParquetMetadataCache>>, CubeError> {
+        let remote_path = match partition_snapshot.bloom_filter_file() {
+            Some(path) if !bloom_filter_predicate.is_empty() => path,
+            _ => return Ok(Some(self.parquet_metadata_cache.clone())),
+        };
+        let local_path = self
+            .remote_to_local_names
+            .get(remote_path.as_str())
+            .expect(format!("Missing remote path {}", remote_path).as_str());
+        let read_row_groups = RowGroupBloomFilters::read(local_path)?
+            .row_groups
+            .iter()
+            .map(|filters| bloom_filter_predicate.can_match(filters))
+            .collect_vec();
+        if read_row_groups.iter().all(|read| !*read) {
+            return Ok(None);
+        }
+        if read_row_groups.iter().all(|read| *read) {
+            return Ok(Some(self.parquet_metadata_cache.clone()));
+        }
+        Ok(Some(SkipRowGroupsMetadataCache::new(
+            self.parquet_metadata_cache.clone(),
+            read_row_groups,
+        )))
+    }
+
     pub fn project_to_index_positions(
         projection_columns: &Vec<String>,
         i: &IdRow<Index>,
@@ -1625,6 +1857,11 @@ pub fn batches_to_dataframe(batches: Vec<RecordBatch>) -> Result<DataFrame, Cube
                         });
                     }
                 }
+                DataType::Date32 | DataType::FixedSizeBinary(16) | DataType::List(_) => {
+                    for i in 0..num_rows {
+                        rows[i].push(TableValue::from_array(array.as_ref(), i));
+                    }
+                }
                 x => panic!("Unsupported data type: {:?}", x),
             }
         }
@@ -1648,6 +1885,11 @@ pub fn arrow_to_column_type(arrow_type: DataType) -> Result<ColumnType, CubeErro
             precision: 27,
         }),
         DataType::Boolean => Ok(ColumnType::Boolean),
+        DataType::Date32 => Ok(ColumnType::Date),
+        DataType::FixedSizeBinary(16) => Ok(ColumnType::Uuid),
+        DataType::List(field) => Ok(ColumnType::Array(Box::new(arrow_to_column_type(
+            field.data_type().clone(),
+        )?))),
         DataType::Int8
         | DataType::Int16
         | DataType::Int32
@@ -0,0 +1,429 @@
+use crate::config::ConfigObj;
+use crate::CubeError;
+use chrono::{DateTime, Utc};
+use datafusion::arrow::array::Array;
+use datafusion::arrow::record_batch::RecordBatch;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+/// Identifies a query across the router and all workers executing its parts.
+/// Travels inside [crate::queryplanner::serialized_plan::SerializedPlan].
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct RunningQueryInfo {
+    pub id: u64,
+    pub sql: String,
+}
+
+/// Snapshot of a running query, it's sent to the router to show queries of all nodes in `system.queries`.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct RunningQueryStatus {
+    pub id: u64,
+    pub sql: String,
+    pub node: String,
+    pub started_at: DateTime<Utc>,
+    pub memory_usage: u64,
+    pub rows_scanned: u64,
+}
+
+/// Memory held by the record batches of a query. Arrays are tracked by weak references, so they
+/// stay reserved while any operator of the query buffers them and are released once dropped.
+#[derive(Debug, Default)]
+struct QueryMemoryReservation {
+    arrays: HashMap<usize, (Weak<dyn Array>, u64)>,
+}
+
+impl QueryMemoryReservation {
+    fn reserve(&mut self, batch: &RecordBatch) {
+        for c in batch.columns() {
+            // Operators pass the same arrays along, count each of them once.
+            let key = Arc::as_ptr(c) as *const u8 as usize;
+            self.arrays
+                .entry(key)
+                .or_insert_with(|| (Arc::downgrade(c), c.get_array_memory_size() as u64));
+        }
+    }
+
+    fn size(&mut self) -> u64 {
+        self.arrays.retain(|_, (array, _)| array.strong_count() > 0);
+        self.arrays.values().map(|(_, size)| *size).sum()
+    }
+}
+
+#[derive(Debug)]
+pub struct RunningQuery {
+    info: RunningQueryInfo,
+    node: String,
+    started_at: DateTime<Utc>,
+    deadline: Instant,
+    max_memory: Option<u64>,
+    memory: Mutex<QueryMemoryReservation>,
+    rows_scanned: AtomicU64,
+    killed: AtomicBool,
+    cancel_token: CancellationToken,
+}
+
+impl RunningQuery {
+    pub fn id(&self) -> u64 {
+        self.info.id
+    }
+
+    pub fn info(&self) -> &RunningQueryInfo {
+        &self.info
+    }
+
+    pub fn sql(&self) -> &str {
+        &self.info.sql
+    }
+
+    pub fn node(&self) -> &str {
+        &self.node
+    }
+
+    pub fn started_at(&self) -> &DateTime<Utc> {
+        &self.started_at
+    }
+
+    /// Memory currently held by record batches of this query on the current node.
+    pub fn memory_usage(&self) -> u64 {
+        self.memory.lock().unwrap().size()
+    }
+
+    pub fn rows_scanned(&self) -> u64 {
+        self.rows_scanned.load(Ordering::Relaxed)
+    }
+
+    pub fn status(&self) -> RunningQueryStatus {
+        RunningQueryStatus {
+            id: self.info.id,
+            sql: self.info.sql.clone(),
+            node: self.node.clone(),
+            started_at: self.started_at,
+            memory_usage: self.memory_usage(),
+            rows_scanned: self.rows_scanned(),
+        }
+    }
+
+    pub fn is_killed(&self) -> bool {
+        self.killed.load(Ordering::Relaxed)
+    }
+
+    /// Reserves memory for the batch until all of its arrays are dropped.
+    pub fn reserve(&self, batch: &RecordBatch) {
+        self.memory.lock().unwrap().reserve(batch);
+    }
+
+    pub fn record_scan(&self, batch: &RecordBatch) {
+        self.rows_scanned
+            .fetch_add(batch.num_rows() as u64, Ordering::Relaxed);
+    }
+
+    /// Fails if the query was killed or exceeded its time or memory limit.
+    pub fn check_limits(&self) -> Result<(), CubeError> {
+        if self.is_killed() {
+            return Err(CubeError::user(format!(
+                "Query {} was killed",
+                self.info.id
+            )));
+        }
+        if Instant::now() > self.deadline {
+            return Err(CubeError::user(format!(
+                "Query {} exceeded the time limit",
+                self.info.id
+            )));
+        }
+        if let Some(max_memory) = self.max_memory {
+            let memory_usage = self.memory_usage();
+            if memory_usage > max_memory {
+                return Err(CubeError::user(format!(
+                    "Query {} exceeded the memory limit: {} bytes used, limit is {} bytes",
+                    self.info.id, memory_usage, max_memory
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolves when the query is killed.
+    pub async fn killed(&self) {
+        self.cancel_token.cancelled().await
+    }
+
+    fn kill(&self) {
+        self.killed.store(true, Ordering::Relaxed);
+        self.cancel_token.cancel();
+    }
+}
+
+/// Queries running on the current node: full queries on the router and query parts on workers.
+pub struct RunningQueries {
+    node: String,
+    timeout: Duration,
+    max_memory: Option<u64>,
+    next_id: AtomicU64,
+    // Entries are shared by the router and local worker parts of the same query,
+    // so we keep the number of active registrations along with the query.
+    queries: Mutex<HashMap<u64, (Arc<RunningQuery>, usize)>>,
+}
+
+crate::di_service!(RunningQueries, []);
+
+impl RunningQueries {
+    pub fn new(config: &dyn ConfigObj) -> Arc<Self> {
+        let max_memory = config.query_max_memory();
+        Arc::new(Self {
+            node: config.server_name().to_string(),
+            timeout: Duration::from_secs(config.query_timeout()),
+            max_memory: if max_memory == 0 {
+                None
+            } else {
+                Some(max_memory)
+            },
+            next_id: AtomicU64::new(1),
+            queries: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Registers a new query on the router, it's removed when the returned guard is dropped.
+    pub fn start(self: &Arc<Self>, sql: String) -> RunningQueryGuard {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.start_part(&RunningQueryInfo { id, sql })
+    }
+
+    /// Registers a part of the query which is already known to the cluster or joins the
+    /// existing registration of this query on the current node.
+    pub fn start_part(self: &Arc<Self>, info: &RunningQueryInfo) -> RunningQueryGuard {
+        let mut queries = self.queries.lock().unwrap();
+        let (query, refs) = queries.entry(info.id).or_insert_with(|| {
+            (
+                Arc::new(RunningQuery {
+                    info: info.clone(),
+                    node: self.node.clone(),
+                    started_at: Utc::now(),
+                    deadline: Instant::now() + self.timeout,
+                    max_memory: self.max_memory,
+                    memory: Mutex::new(QueryMemoryReservation::default()),
+                    rows_scanned: AtomicU64::new(0),
+                    killed: AtomicBool::new(false),
+                    cancel_token: CancellationToken::new(),
+                }),
+                0,
+            )
+        });
+        *refs += 1;
+        RunningQueryGuard {
+            registry: self.clone(),
+            query: query.clone(),
+        }
+    }
+
+    pub fn get(&self, id: u64) -> Option<Arc<RunningQuery>> {
+        self.queries
+            .lock()
+            .unwrap()
+            .get(&id)
+            .map(|(query, _)| query.clone())
+    }
+
+    pub fn list(&self) -> Vec<Arc<RunningQuery>> {
+        let mut queries = self
+            .queries
+            .lock()
+            .unwrap()
+            .values()
+            .map(|(query, _)| query.clone())
+            .collect::<Vec<_>>();
+        queries.sort_by_key(|q| q.id());
+        queries
+    }
+
+    pub fn statuses(&self) -> Vec<RunningQueryStatus> {
+        self.list().iter().map(|q| q.status()).collect()
+    }
+
+    /// Returns true if the query was running on the current node.
+    pub fn kill(&self, id: u64) -> bool {
+        if let Some(query) = self.get(id) {
+            query.kill();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn finish(&self, id: u64) {
+        let mut queries = self.queries.lock().unwrap();
+        if let Some((_, refs)) = queries.get_mut(&id) {
+            *refs -= 1;
+            if *refs == 0 {
+                queries.remove(&id);
+            }
+        }
+    }
+}
+
+pub struct RunningQueryGuard {
+    registry: Arc<RunningQueries>,
+    query: Arc<RunningQuery>,
+}
+
+impl RunningQueryGuard {
+    pub fn query(&self) -> &Arc<RunningQuery> {
+        &self.query
+    }
+
+    /// Runs `f` until it completes or the query gets killed.
+    pub async fn run<T>(
+        &self,
+        f: impl std::future::Future<Output = Result<T, CubeError>>,
+    ) -> Result<T, CubeError> {
+        tokio::select! {
+            res = f => res,
+            _ = self.query.killed() => Err(CubeError::user(format!(
+                "Query {} was killed",
+                self.query.id()
+            ))),
+        }
+    }
+}
+
+impl Drop for RunningQueryGuard {
+    fn drop(&mut self) {
+        self.registry.finish(self.query.id());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::queryplanner::check_memory::CheckMemoryExec;
+    use crate::util::memory::MemoryHandlerImpl;
+    use datafusion::arrow::array::Int64Array;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::physical_plan::memory::MemoryExec;
+    use datafusion::physical_plan::ExecutionPlan;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_running_queries_parts() {
+        let registry = RunningQueries::new(Config::test("running_queries").config_obj().as_ref());
+
+        let router = registry.start("SELECT 1".to_string());
+        let id = router.query().id();
+        let worker = registry.start_part(router.query().info());
+        assert_eq!(registry.list().len(), 1);
+
+        drop(router);
+        assert!(registry.get(id).is_some());
+        drop(worker);
+        assert!(registry.get(id).is_none());
+        assert!(!registry.kill(id));
+    }
+
+    #[tokio::test]
+    async fn test_kill_running_query() {
+        let registry = RunningQueries::new(Config::test("kill_query").config_obj().as_ref());
+
+        let guard = registry.start("SELECT 1".to_string());
+        assert!(guard.query().check_limits().is_ok());
+        assert!(registry.kill(guard.query().id()));
+
+        let res = guard
+            .run(futures::future::pending::<Result<(), CubeError>>())
+            .await;
+        assert_eq!(
+            res,
+            Err(CubeError::user(format!(
+                "Query {} was killed",
+                guard.query().id()
+            )))
+        );
+        assert!(guard.query().check_limits().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_kill_stops_scan() {
+        let registry = RunningQueries::new(Config::test("kill_scan").config_obj().as_ref());
+        let guard = registry.start("SELECT * FROM s.t".to_string());
+
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let batches = (0..3)
+            .map(|i| {
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(Int64Array::from(vec![i, i + 1]))],
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let exec = CheckMemoryExec::new(
+            Arc::new(MemoryExec::try_new(&[batches], schema, None).unwrap()),
+            MemoryHandlerImpl::new(),
+            Some(guard.query().clone()),
+            true,
+        );
+        let mut stream = exec.execute(0).await.unwrap();
+
+        let batch = stream.next().await.unwrap().unwrap();
+        assert_eq!(guard.query().rows_scanned(), 2);
+        assert!(guard.query().memory_usage() > 0);
+        assert_eq!(registry.statuses()[0].rows_scanned, 2);
+        drop(batch);
+
+        // KILL QUERY on the node interrupts the scan before the next batch is returned
+        assert!(registry.kill(guard.query().id()));
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("was killed"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_query_memory_limit() {
+        let config = Config::test("query_memory_limit").update_config(|mut c| {
+            c.query_max_memory = 1024;
+            c
+        });
+        let registry = RunningQueries::new(config.config_obj().as_ref());
+        let guard = registry.start("SELECT * FROM s.t".to_string());
+
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let batch = |from: i64| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(Int64Array::from(
+                    (from..from + 100).collect::<Vec<_>>(),
+                ))],
+            )
+            .unwrap()
+        };
+
+        // Memory is released when the batch is dropped.
+        let first = batch(0);
+        guard.query().reserve(&first);
+        let used = guard.query().memory_usage();
+        assert!(used >= 800, "{}", used);
+        guard.query().reserve(&first);
+        assert_eq!(guard.query().memory_usage(), used);
+        assert!(guard.query().check_limits().is_ok());
+        drop(first);
+        assert_eq!(guard.query().memory_usage(), 0);
+
+        // Batches held at the same time are reserved together.
+        let held = (0..3).map(|i| batch(i * 100)).collect::<Vec<_>>();
+        for b in held.iter() {
+            guard.query().reserve(b);
+        }
+        assert_eq!(guard.query().memory_usage(), 3 * used);
+        let err = guard.query().check_limits().unwrap_err();
+        assert!(
+            err.to_string().contains("exceeded the memory limit"),
+            "{}",
+            err
+        );
+        drop(held);
+        assert!(guard.query().check_limits().is_ok());
+    }
+}
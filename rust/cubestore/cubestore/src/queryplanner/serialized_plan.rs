@@ -2,8 +2,11 @@ use crate::metastore::table::{Table, TablePath};
 use crate::metastore::{Chunk, IdRow, Index, Partition};
 use crate::queryplanner::panic::PanicWorkerNode;
 use crate::queryplanner::planning::{ClusterSendNode, PlanningMeta, Snapshots};
-use crate::queryplanner::providers::InfoSchemaQueryCacheTableProvider;
+use crate::queryplanner::providers::{
+    InfoSchemaQueriesTableProvider, InfoSchemaQueryCacheTableProvider,
+};
 use crate::queryplanner::query_executor::{CubeTable, InlineTableId, InlineTableProvider};
+use crate::queryplanner::running_queries::RunningQueryInfo;
 use crate::queryplanner::topk::{ClusterAggregateTopK, SortColumn};
 use crate::queryplanner::udfs::aggregate_udf_by_kind;
 use crate::queryplanner::udfs::{
@@ -77,6 +80,7 @@ pub struct SerializedPlan {
     partition_ids_to_execute: Vec<(u64, RowFilter)>,
     inline_table_ids_to_execute: Vec<InlineTableId>,
     trace_obj: Option<String>,
+    running_query: Option<RunningQueryInfo>,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -118,6 +122,9 @@ impl IndexSnapshot {
 pub struct PartitionSnapshot {
     pub partition: IdRow<Partition>,
     pub chunks: Vec<IdRow<Chunk>>,
+    /// Set when the planner proved that no rows of the main table file match the query, only
+    /// chunks of the partition are read then.
+    pub skip_main_table_file: bool,
 }
 
 impl PartitionSnapshot {
@@ -125,6 +132,27 @@ impl PartitionSnapshot {
         &self.partition
     }
 
+    /// Name of the main table file to read, if any.
+    pub fn main_table_file(&self) -> Option<String> {
+        if self.skip_main_table_file {
+            return None;
+        }
+        self.partition
+            .get_row()
+            .get_full_name(self.partition.get_id())
+    }
+
+    /// Bloom filters of the main table file to skip its row groups with, if any. The planner
+    /// only keeps them for queries with bloom filter predicates.
+    pub fn bloom_filter_file(&self) -> Option<String> {
+        if self.skip_main_table_file {
+            return None;
+        }
+        self.partition
+            .get_row()
+            .bloom_filter_file_name(self.partition.get_id())
+    }
+
     pub fn chunks(&self) -> &Vec<IdRow<Chunk>> {
         &self.chunks
     }
@@ -1052,9 +1080,16 @@ impl SerializedPlan {
             partition_ids_to_execute: Vec::new(),
             inline_table_ids_to_execute: Vec::new(),
             trace_obj,
+            running_query: None,
         })
     }
 
+    /// Attaches the query registered on the router, workers use it to track and kill query parts.
+    pub fn with_running_query(mut self, running_query: RunningQueryInfo) -> Self {
+        self.running_query = Some(running_query);
+        self
+    }
+
     pub fn with_partition_id_to_execute(
         &self,
         partition_ids_to_execute: Vec<(u64, RowFilter)>,
@@ -1069,6 +1104,7 @@ impl SerializedPlan {
             partition_ids_to_execute,
             inline_table_ids_to_execute,
             trace_obj: self.trace_obj.clone(),
+            running_query: self.running_query.clone(),
         }
     }
 
@@ -1091,6 +1127,10 @@ impl SerializedPlan {
         self.trace_obj.clone()
     }
 
+    pub fn running_query(&self) -> Option<&RunningQueryInfo> {
+        self.running_query.as_ref()
+    }
+
     pub fn index_snapshots(&self) -> &Vec<IndexSnapshot> {
         &self.schema_snapshot.index_snapshots.indices
     }
@@ -1130,11 +1170,7 @@ impl SerializedPlan {
                 if !include_partition(partition.partition.get_id()) {
                     continue;
                 }
-                if let Some(file) = partition
-                    .partition
-                    .get_row()
-                    .get_full_name(partition.partition.get_id())
-                {
+                if let Some(file) = partition.main_table_file() {
                     files.push((
                         partition.partition.clone(),
                         file,
@@ -1142,6 +1178,9 @@ impl SerializedPlan {
                         None,
                     ));
                 }
+                if let Some(file) = partition.bloom_filter_file() {
+                    files.push((partition.partition.clone(), file, None, None));
+                }
 
                 for chunk in partition.chunks() {
                     if !chunk.get_row().in_memory() {
@@ -1213,6 +1252,10 @@ impl SerializedPlan {
                             .as_any()
                             .downcast_ref::<InfoSchemaQueryCacheTableProvider>()
                             .is_none()
+                        && source
+                            .as_any()
+                            .downcast_ref::<InfoSchemaQueriesTableProvider>()
+                            .is_none()
                     {
                         self.seen_data_scans = true;
                         return Ok(false);
@@ -1,19 +1,22 @@
 use crate::cachestore::{
-    CacheItem, CacheStore, QueueAddPayload, QueueAllItem, QueueCancelResponse, QueueGetResponse,
-    QueueItem, QueueItemStatus, QueueKey, QueueListItem, QueueResult, QueueResultResponse,
-    QueueRetrieveResponse,
+    CacheChannelMessage, CacheItem, CacheSortedSetEntry, CacheStore, QueueAddPayload, QueueAllItem,
+    QueueCancelResponse, QueueGetResponse, QueueItem, QueueItemStatus, QueueKey, QueueListItem,
+    QueueResult, QueueResultResponse, QueueRetrieveResponse,
 };
 use crate::metastore::job::{Job, JobStatus, JobType};
 use crate::metastore::multi_index::{MultiIndex, MultiPartition};
 use crate::metastore::replay_handle::{ReplayHandle, SeqPointer};
 use crate::metastore::snapshot_info::SnapshotInfo;
 use crate::metastore::source::{Source, SourceCredentials};
-use crate::metastore::table::{StreamOffset, Table, TablePath};
+use crate::metastore::table::{
+    MaterializedView, ParquetOptions, RetentionPolicy, StreamOffset, Table, TablePath,
+};
 use crate::metastore::{
     Chunk, ChunkMetaStoreTable, Column, IdRow, ImportFormat, Index, IndexDef, IndexMetaStoreTable,
     MetaStore, Partition, PartitionData, PartitionMetaStoreTable, RocksPropertyRow, RowKey, Schema,
     SchemaMetaStoreTable, TableMetaStoreTable, WAL,
 };
+use crate::table::statistics::ColumnStatistics;
 use crate::table::Row;
 use crate::CubeError;
 use async_trait::async_trait;
@@ -106,6 +109,9 @@ impl MetaStore for MetaStoreMock {
         _trace_obj: Option<String>,
         _drop_if_exists: bool,
         _extension: Option<String>,
+        _parquet_options: Option<ParquetOptions>,
+        _retention: Option<RetentionPolicy>,
+        _materialized_view: Option<MaterializedView>,
     ) -> Result<IdRow<Table>, CubeError> {
         panic!("MetaStore mock!")
     }
@@ -118,6 +124,14 @@ impl MetaStore for MetaStoreMock {
         panic!("MetaStore mock!")
     }
 
+    async fn update_table_last_purge_at(
+        &self,
+        _id: u64,
+        _last_purge_at: DateTime<Utc>,
+    ) -> Result<IdRow<Table>, CubeError> {
+        panic!("MetaStore mock!")
+    }
+
     async fn get_trace_obj_by_table_id(&self, _table_id: u64) -> Result<Option<String>, CubeError> {
         panic!("MetaStore mock!")
     }
@@ -154,6 +168,13 @@ impl MetaStore for MetaStoreMock {
         panic!("MetaStore mock!")
     }
 
+    async fn get_materialized_views(
+        &self,
+        _source_table_id: u64,
+    ) -> Result<Vec<IdRow<Table>>, CubeError> {
+        panic!("MetaStore mock!")
+    }
+
     async fn not_ready_tables(
         &self,
         _created_seconds_ago: i64,
@@ -218,6 +239,8 @@ impl MetaStore for MetaStoreMock {
         _current_active: Vec<(IdRow<Partition>, Vec<IdRow<Chunk>>)>,
         _new_active: Vec<(IdRow<Partition>, u64)>,
         _new_active_min_max: Vec<(u64, (Option<Row>, Option<Row>), (Option<Row>, Option<Row>))>,
+        _new_active_has_bloom_filters: Vec<bool>,
+        _new_active_column_statistics: Vec<Option<ColumnStatistics>>,
     ) -> Result<(), CubeError> {
         panic!("MetaStore mock!")
     }
@@ -447,6 +470,7 @@ impl MetaStore for MetaStoreMock {
         _min: Option<Row>,
         _max: Option<Row>,
         _in_memory: bool,
+        _column_statistics: Option<ColumnStatistics>,
     ) -> Result<IdRow<Chunk>, CubeError> {
         panic!("MetaStore mock!")
     }
@@ -787,6 +811,72 @@ impl CacheStore for CacheStoreMock {
         panic!("CacheStore mock!")
     }
 
+    async fn cache_hset(
+        &self,
+        _path: String,
+        _field: String,
+        _value: String,
+        _ttl: Option<u32>,
+    ) -> Result<bool, CubeError> {
+        panic!("CacheStore mock!")
+    }
+
+    async fn cache_hget(&self, _path: String, _field: String) -> Result<Option<String>, CubeError> {
+        panic!("CacheStore mock!")
+    }
+
+    async fn cache_hincrby(
+        &self,
+        _path: String,
+        _field: String,
+        _increment: i64,
+        _ttl: Option<u32>,
+    ) -> Result<i64, CubeError> {
+        panic!("CacheStore mock!")
+    }
+
+    async fn cache_zadd(
+        &self,
+        _path: String,
+        _score: f64,
+        _member: String,
+        _ttl: Option<u32>,
+    ) -> Result<bool, CubeError> {
+        panic!("CacheStore mock!")
+    }
+
+    async fn cache_zrange_by_score(
+        &self,
+        _path: String,
+        _min: f64,
+        _max: f64,
+        _limit: Option<usize>,
+    ) -> Result<Vec<CacheSortedSetEntry>, CubeError> {
+        panic!("CacheStore mock!")
+    }
+
+    async fn cache_zrem_range_by_score(
+        &self,
+        _path: String,
+        _min: f64,
+        _max: f64,
+    ) -> Result<u64, CubeError> {
+        panic!("CacheStore mock!")
+    }
+
+    async fn cache_publish(&self, _channel: String, _message: String) -> Result<(), CubeError> {
+        panic!("CacheStore mock!")
+    }
+
+    async fn cache_subscribe(
+        &self,
+        _channel: String,
+        _timeout: u64,
+        _after: Option<u64>,
+    ) -> Result<Vec<CacheChannelMessage>, CubeError> {
+        panic!("CacheStore mock!")
+    }
+
     async fn queue_all(&self, _limit: Option<usize>) -> Result<Vec<QueueAllItem>, CubeError> {
         panic!("CacheStore mock!")
     }
@@ -875,6 +965,10 @@ impl CacheStore for CacheStoreMock {
         panic!("CacheStore mock!")
     }
 
+    async fn queue_dead_letter_requeue(&self, _key: QueueKey) -> Result<bool, CubeError> {
+        panic!("CacheStore mock!")
+    }
+
     async fn compaction(&self) -> Result<(), CubeError> {
         panic!("CacheStore mock!")
     }
@@ -3,7 +3,8 @@ use crate::queryplanner::hll::{Hll, HllUnion};
 use crate::CubeError;
 use chrono::{Datelike, Duration, Months, NaiveDateTime, TimeZone, Utc};
 use datafusion::arrow::array::{
-    Array, ArrayRef, BinaryArray, TimestampNanosecondArray, UInt64Builder,
+    Array, ArrayRef, BinaryArray, StringArray, StringBuilder, TimestampNanosecondArray,
+    UInt64Builder,
 };
 use datafusion::arrow::datatypes::{DataType, IntervalUnit, TimeUnit};
 use datafusion::cube_ext::datetime::{date_addsub_array, date_addsub_scalar};
@@ -27,6 +28,8 @@ pub enum CubeScalarUDFKind {
     DateAdd,
     DateSub,
     DateBin,
+    JsonExtractPath,     // json_extract_path(), same as `->` in Postgres.
+    JsonExtractPathText, // json_extract_path_text(), same as `->>` in Postgres.
 }
 
 pub trait CubeScalarUDF {
@@ -44,6 +47,8 @@ pub fn scalar_udf_by_kind(k: CubeScalarUDFKind) -> Box<dyn CubeScalarUDF> {
         CubeScalarUDFKind::DateAdd => Box::new(DateAddSub { is_add: true }),
         CubeScalarUDFKind::DateSub => Box::new(DateAddSub { is_add: false }),
         CubeScalarUDFKind::DateBin => Box::new(DateBin {}),
+        CubeScalarUDFKind::JsonExtractPath => Box::new(JsonExtractPath { as_text: false }),
+        CubeScalarUDFKind::JsonExtractPathText => Box::new(JsonExtractPath { as_text: true }),
     }
 }
 
@@ -70,6 +75,12 @@ pub fn scalar_kind_by_name(n: &str) -> Option<CubeScalarUDFKind> {
     if n == "DATE_BIN" {
         return Some(CubeScalarUDFKind::DateBin);
     }
+    if n == "JSON_EXTRACT_PATH" {
+        return Some(CubeScalarUDFKind::JsonExtractPath);
+    }
+    if n == "JSON_EXTRACT_PATH_TEXT" {
+        return Some(CubeScalarUDFKind::JsonExtractPathText);
+    }
     return None;
 }
 
@@ -519,6 +530,108 @@ impl CubeScalarUDF for DateAddSub {
     }
 }
 
+/// Extracts a value from a JSON document by a path of object keys and array indices.
+/// Returns the value as JSON text, or as plain text for strings when `as_text` is set.
+struct JsonExtractPath {
+    as_text: bool,
+}
+
+impl JsonExtractPath {
+    fn signature() -> Signature {
+        Signature::Variadic(vec![DataType::Utf8])
+    }
+
+    fn name_static(&self) -> &'static str {
+        match self.as_text {
+            false => "JSON_EXTRACT_PATH",
+            true => "JSON_EXTRACT_PATH_TEXT",
+        }
+    }
+}
+
+impl CubeScalarUDF for JsonExtractPath {
+    fn kind(&self) -> CubeScalarUDFKind {
+        match self.as_text {
+            false => CubeScalarUDFKind::JsonExtractPath,
+            true => CubeScalarUDFKind::JsonExtractPathText,
+        }
+    }
+
+    fn name(&self) -> &str {
+        self.name_static()
+    }
+
+    fn descriptor(&self) -> ScalarUDF {
+        let name = self.name_static();
+        let as_text = self.as_text;
+        return ScalarUDF {
+            name: self.name().to_string(),
+            signature: Self::signature(),
+            return_type: Arc::new(move |inputs| {
+                if inputs.len() < 2 {
+                    return Err(DataFusionError::Plan(format!(
+                        "{} requires a json argument and at least one path element",
+                        name
+                    )));
+                }
+                Ok(Arc::new(DataType::Utf8))
+            }),
+            fun: Arc::new(move |inputs| {
+                assert!(inputs.len() >= 2);
+                let len = inputs
+                    .iter()
+                    .find_map(|i| match i {
+                        ColumnarValue::Array(a) => Some(a.len()),
+                        ColumnarValue::Scalar(_) => None,
+                    })
+                    .unwrap_or(1);
+                let arrays = inputs
+                    .iter()
+                    .map(|i| i.clone().into_array(len))
+                    .collect::<Vec<_>>();
+                let strings = arrays
+                    .iter()
+                    .map(|a| {
+                        a.as_any()
+                            .downcast_ref::<StringArray>()
+                            .expect("expected string data")
+                    })
+                    .collect::<Vec<_>>();
+
+                let mut r = StringBuilder::new(len);
+                for i in 0..len {
+                    if strings.iter().any(|a| a.is_null(i)) {
+                        r.append_null()?;
+                        continue;
+                    }
+                    let document = serde_json::from_str::<serde_json::Value>(strings[0].value(i))
+                        .map_err(|e| {
+                        DataFusionError::Execution(format!("Invalid json in `{}`: {}", name, e))
+                    })?;
+                    let mut value = Some(&document);
+                    for path in &strings[1..] {
+                        let key = path.value(i);
+                        value = value.and_then(|v| match v {
+                            serde_json::Value::Object(o) => o.get(key),
+                            serde_json::Value::Array(a) => {
+                                key.parse::<usize>().ok().and_then(|i| a.get(i))
+                            }
+                            _ => None,
+                        });
+                    }
+                    match value {
+                        None => r.append_null()?,
+                        Some(serde_json::Value::Null) if as_text => r.append_null()?,
+                        Some(serde_json::Value::String(s)) if as_text => r.append_value(s)?,
+                        Some(v) => r.append_value(v.to_string())?,
+                    }
+                }
+                return Ok(ColumnarValue::Array(Arc::new(r.finish())));
+            }),
+        };
+    }
+}
+
 struct HllCardinality {}
 impl CubeScalarUDF for HllCardinality {
     fn kind(&self) -> CubeScalarUDFKind {
@@ -0,0 +1,560 @@
+use crate::config::ImportS3Config;
+use crate::import::object_store::s3_bucket;
+use crate::queryplanner::query_executor::batches_to_dataframe;
+use crate::remotefs::gcs::GCSRemoteFs;
+use crate::remotefs::{LocalDirRemoteFs, RemoteFs};
+use crate::table::TableValue;
+use crate::CubeError;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::cube_ext;
+use datafusion::parquet::arrow::ArrowWriter;
+use datafusion::parquet::file::properties::WriterProperties;
+use datafusion::physical_plan::SendableRecordBatchStream;
+use futures::StreamExt;
+use hex::ToHex;
+use s3::Bucket;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnloadFormat {
+    Parquet,
+    CSV,
+}
+
+impl UnloadFormat {
+    pub fn from_string(s: &str) -> Result<Self, CubeError> {
+        match s.to_lowercase().as_str() {
+            "parquet" => Ok(UnloadFormat::Parquet),
+            "csv" => Ok(UnloadFormat::CSV),
+            _ => Err(CubeError::user(format!(
+                "Unsupported unload format '{}', expected 'parquet' or 'csv'",
+                s
+            ))),
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            UnloadFormat::Parquet => "parquet",
+            UnloadFormat::CSV => "csv",
+        }
+    }
+}
+
+/// Destination of `UNLOAD` results, e.g. `s3://bucket/path`, `gs://bucket/path` or
+/// `file:///path`. Every node writes its part of the results into a separate file there.
+/// Local files are allowed only inside of `CUBESTORE_UNLOAD_LOCAL_DIR`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UnloadTarget {
+    location: String,
+    format: UnloadFormat,
+    /// Unique for every unload, so repeated or concurrent unloads into the same location
+    /// don't overwrite files of each other.
+    prefix: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UnloadedFile {
+    pub path: String,
+    pub row_count: u64,
+}
+
+impl UnloadTarget {
+    pub fn try_new(
+        location: String,
+        format: UnloadFormat,
+        local_dir: Option<&Path>,
+    ) -> Result<Self, CubeError> {
+        let mut target = UnloadTarget {
+            location,
+            format,
+            prefix: Uuid::new_v4().to_string(),
+        };
+        // Validate location before starting the query.
+        if let (_, _, Some(path)) = target.parse_location()? {
+            if target.location.starts_with("file://") {
+                let path = resolve_local_path(&path, local_dir)?;
+                target.location = format!("file://{}", path.to_string_lossy());
+            }
+        }
+        Ok(target)
+    }
+
+    fn parse_location(&self) -> Result<(&str, &str, Option<String>), CubeError> {
+        let (scheme, rest) = self.location.split_once("://").ok_or_else(|| {
+            CubeError::user(format!(
+                "Unload location '{}' should start with 's3://', 'gs://' or 'file://'",
+                self.location
+            ))
+        })?;
+        match scheme {
+            "s3" | "gs" => {
+                let (bucket, path) = rest.split_once('/').unwrap_or((rest, ""));
+                if bucket.is_empty() {
+                    return Err(CubeError::user(format!(
+                        "Bucket name is missing in unload location '{}'",
+                        self.location
+                    )));
+                }
+                let path = path.trim_matches('/');
+                let path = if path.is_empty() {
+                    None
+                } else {
+                    Some(path.to_string())
+                };
+                Ok((scheme, bucket, path))
+            }
+            "file" => {
+                if rest.is_empty() {
+                    return Err(CubeError::user(format!(
+                        "Path is missing in unload location '{}'",
+                        self.location
+                    )));
+                }
+                Ok((scheme, "", Some(rest.to_string())))
+            }
+            _ => Err(CubeError::user(format!(
+                "Unsupported unload location '{}', expected 's3://', 'gs://' or 'file://'",
+                self.location
+            ))),
+        }
+    }
+
+    /// Writes batches of `stream` into a file called `name` in the target location as they
+    /// arrive. Returns [None] and writes nothing if there are no rows.
+    pub async fn write_stream(
+        &self,
+        storage: &UnloadStorage,
+        uploads_dir: &str,
+        name: &str,
+        mut stream: SendableRecordBatchStream,
+    ) -> Result<Option<UnloadedFile>, CubeError> {
+        let local_dir = TempDir::new_in(uploads_dir)?;
+        let destination = storage.destination(self, local_dir.path())?;
+        let file_name = format!("{}-{}.{}", self.prefix, name, self.format.extension());
+        let temp_path = destination.temp_upload_path(&file_name).await?;
+
+        let schema = stream.schema();
+        let mut writer = None;
+        let mut row_count = 0;
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            if batch.num_rows() == 0 {
+                continue;
+            }
+            row_count += batch.num_rows() as u64;
+            let format = self.format;
+            let dest = temp_path.clone();
+            let schema = schema.clone();
+            let w = writer.take();
+            writer = Some(
+                cube_ext::spawn_blocking(move || -> Result<UnloadFileWriter, CubeError> {
+                    let mut w = match w {
+                        Some(w) => w,
+                        None => UnloadFileWriter::try_new(format, &dest, schema)?,
+                    };
+                    w.write(batch)?;
+                    Ok(w)
+                })
+                .await??,
+            );
+        }
+        let writer = match writer {
+            Some(w) => w,
+            None => return Ok(None),
+        };
+        cube_ext::spawn_blocking(move || writer.close()).await??;
+
+        destination.upload_file(temp_path, &file_name).await?;
+
+        Ok(Some(UnloadedFile {
+            path: format!("{}/{}", self.location.trim_end_matches('/'), file_name),
+            row_count,
+        }))
+    }
+}
+
+/// Uploads unloaded files into their targets. S3 clients are built from [ImportS3Config], so
+/// unloads work with the same S3 compatible storages as imports, and reused across unloads.
+pub struct UnloadStorage {
+    s3_config: ImportS3Config,
+    s3_buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl UnloadStorage {
+    pub fn new(s3_config: ImportS3Config) -> Self {
+        Self {
+            s3_config,
+            s3_buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn s3_bucket(&self, bucket_name: &str) -> Result<Bucket, CubeError> {
+        let mut buckets = self.s3_buckets.lock().unwrap();
+        if let Some(bucket) = buckets.get(bucket_name) {
+            return Ok(bucket.clone());
+        }
+        let bucket = s3_bucket(bucket_name, &self.s3_config)?;
+        buckets.insert(bucket_name.to_string(), bucket.clone());
+        Ok(bucket)
+    }
+
+    fn destination(
+        &self,
+        target: &UnloadTarget,
+        local_dir: &Path,
+    ) -> Result<UnloadDestination, CubeError> {
+        let (scheme, bucket, path) = target.parse_location()?;
+        Ok(match scheme {
+            "s3" => UnloadDestination::S3 {
+                bucket: self.s3_bucket(bucket)?,
+                path,
+                local_dir: local_dir.to_path_buf(),
+            },
+            "gs" => UnloadDestination::RemoteFs(GCSRemoteFs::new(
+                local_dir.to_path_buf(),
+                bucket.to_string(),
+                path,
+            )?),
+            _ => UnloadDestination::RemoteFs(LocalDirRemoteFs::new(
+                path.map(PathBuf::from),
+                local_dir.to_path_buf(),
+            )),
+        })
+    }
+}
+
+enum UnloadDestination {
+    S3 {
+        bucket: Bucket,
+        path: Option<String>,
+        local_dir: PathBuf,
+    },
+    RemoteFs(Arc<dyn RemoteFs>),
+}
+
+impl UnloadDestination {
+    async fn temp_upload_path(&self, file_name: &str) -> Result<String, CubeError> {
+        match self {
+            UnloadDestination::S3 { local_dir, .. } => {
+                Ok(local_dir.join(file_name).to_string_lossy().to_string())
+            }
+            UnloadDestination::RemoteFs(remote_fs) => {
+                remote_fs.temp_upload_path(file_name.to_string()).await
+            }
+        }
+    }
+
+    async fn upload_file(&self, temp_path: String, file_name: &str) -> Result<(), CubeError> {
+        match self {
+            UnloadDestination::S3 { bucket, path, .. } => {
+                let key = match path {
+                    Some(path) => format!("{}/{}", path, file_name),
+                    None => file_name.to_string(),
+                };
+                let mut file = tokio::fs::File::open(&temp_path).await?;
+                let status_code = bucket.put_object_stream(&mut file, key).await?;
+                if status_code != 200 {
+                    return Err(CubeError::user(format!(
+                        "S3 upload returned non OK status: {}",
+                        status_code
+                    )));
+                }
+            }
+            UnloadDestination::RemoteFs(remote_fs) => {
+                remote_fs
+                    .upload_file(temp_path, file_name.to_string())
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+enum UnloadFileWriter {
+    Parquet(ArrowWriter<File>),
+    CSV(csv::Writer<File>),
+}
+
+impl UnloadFileWriter {
+    fn try_new(format: UnloadFormat, dest: &str, schema: SchemaRef) -> Result<Self, CubeError> {
+        let file = File::create(dest)?;
+        Ok(match format {
+            UnloadFormat::Parquet => {
+                let props = WriterProperties::builder().build();
+                UnloadFileWriter::Parquet(ArrowWriter::try_new(file, schema, Some(props))?)
+            }
+            UnloadFormat::CSV => {
+                let mut w = csv::Writer::from_writer(file);
+                w.write_record(schema.fields().iter().map(|f| f.name()))?;
+                UnloadFileWriter::CSV(w)
+            }
+        })
+    }
+
+    fn write(&mut self, batch: RecordBatch) -> Result<(), CubeError> {
+        match self {
+            UnloadFileWriter::Parquet(w) => w.write(&batch)?,
+            UnloadFileWriter::CSV(w) => write_csv(w, batch)?,
+        }
+        Ok(())
+    }
+
+    fn close(self) -> Result<(), CubeError> {
+        match self {
+            UnloadFileWriter::Parquet(mut w) => {
+                w.close()?;
+            }
+            UnloadFileWriter::CSV(mut w) => w.flush()?,
+        }
+        Ok(())
+    }
+}
+
+/// Resolves `path` against `local_dir`, the result should stay inside of it.
+fn resolve_local_path(path: &str, local_dir: Option<&Path>) -> Result<PathBuf, CubeError> {
+    let local_dir = local_dir.ok_or_else(|| {
+        CubeError::user(
+            "Unload into local files is disabled, CUBESTORE_UNLOAD_LOCAL_DIR is not set"
+                .to_string(),
+        )
+    })?;
+    let path = Path::new(path);
+    if path
+        .components()
+        .any(|c| matches!(c, Component::ParentDir | Component::Prefix(_)))
+    {
+        return Err(CubeError::user(format!(
+            "Unload path '{}' should not contain '..'",
+            path.display()
+        )));
+    }
+    // `components()` normalizes repeated separators and `.`, so `starts_with` compares the
+    // paths component by component.
+    let resolved = local_dir.join(path).components().collect::<PathBuf>();
+    if !resolved.starts_with(local_dir.components().collect::<PathBuf>()) {
+        return Err(CubeError::user(format!(
+            "Unload path '{}' is outside of CUBESTORE_UNLOAD_LOCAL_DIR",
+            path.display()
+        )));
+    }
+    Ok(resolved)
+}
+
+fn write_csv(w: &mut csv::Writer<File>, batch: RecordBatch) -> Result<(), CubeError> {
+    let data = batches_to_dataframe(vec![batch])?;
+    for row in data.get_rows().iter() {
+        let mut record = Vec::with_capacity(row.values().len());
+        for (i, value) in row.values().iter().enumerate() {
+            let v = match value {
+                TableValue::Null => String::new(),
+                TableValue::String(v) => v.clone(),
+                TableValue::Int(v) => v.to_string(),
+                TableValue::Int96(v) => v.to_string(),
+                TableValue::Decimal(v) => {
+                    let scale =
+                        u8::try_from(data.get_columns()[i].get_column_type().target_scale())
+                            .unwrap();
+                    v.to_string(scale)
+                }
+                TableValue::Decimal96(v) => {
+                    let scale =
+                        u8::try_from(data.get_columns()[i].get_column_type().target_scale())
+                            .unwrap();
+                    v.to_string(scale)
+                }
+                TableValue::Float(v) => v.to_string(),
+                TableValue::Bytes(v) => format!("0x{}", v.encode_hex_upper::<String>()),
+                TableValue::Timestamp(v) => v.to_string(),
+                TableValue::Boolean(v) => v.to_string(),
+                TableValue::Date(v) => v.to_string(),
+                TableValue::Uuid(v) => v.to_string(),
+                TableValue::Array(v) => TableValue::array_to_json_string(v),
+            };
+            record.push(v);
+        }
+        w.write_record(&record)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::arrow::array::{Int64Array, StringArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::physical_plan::memory::MemoryExec;
+    use datafusion::physical_plan::ExecutionPlan;
+
+    #[test]
+    fn parse_unload_location() {
+        let t =
+            UnloadTarget::try_new("s3://bucket/a/b/".to_string(), UnloadFormat::CSV, None).unwrap();
+        assert_eq!(
+            t.parse_location().unwrap(),
+            ("s3", "bucket", Some("a/b".to_string()))
+        );
+
+        let t =
+            UnloadTarget::try_new("gs://bucket".to_string(), UnloadFormat::Parquet, None).unwrap();
+        assert_eq!(t.parse_location().unwrap(), ("gs", "bucket", None));
+
+        let local_dir = Path::new("/tmp/unload");
+        let t = UnloadTarget::try_new(
+            "file:///tmp/unload/a".to_string(),
+            UnloadFormat::CSV,
+            Some(local_dir),
+        )
+        .unwrap();
+        assert_eq!(
+            t.parse_location().unwrap(),
+            ("file", "", Some("/tmp/unload/a".to_string()))
+        );
+        // Relative paths are resolved against the local dir.
+        let t = UnloadTarget::try_new(
+            "file://./a//b/".to_string(),
+            UnloadFormat::CSV,
+            Some(local_dir),
+        )
+        .unwrap();
+        assert_eq!(t.location, "file:///tmp/unload/a/b");
+
+        assert!(UnloadTarget::try_new("s3://".to_string(), UnloadFormat::CSV, None).is_err());
+        assert!(UnloadTarget::try_new("/tmp/unload".to_string(), UnloadFormat::CSV, None).is_err());
+        assert!(UnloadTarget::try_new("ftp://host/".to_string(), UnloadFormat::CSV, None).is_err());
+        assert!(UnloadFormat::from_string("json").is_err());
+        assert_eq!(
+            UnloadFormat::from_string("Parquet").unwrap(),
+            UnloadFormat::Parquet
+        );
+    }
+
+    #[test]
+    fn local_unload_location_is_restricted() {
+        let local_dir = Path::new("/tmp/unload");
+        for location in [
+            "file:///tmp/unload/../etc",
+            "file://../etc",
+            "file:///etc/cron.d",
+            "file:///tmp/unload-other",
+            "file:///tmp",
+        ] {
+            assert!(
+                UnloadTarget::try_new(location.to_string(), UnloadFormat::CSV, Some(local_dir))
+                    .is_err(),
+                "{} is accepted",
+                location
+            );
+        }
+        // Local files are disabled without the local dir.
+        assert!(
+            UnloadTarget::try_new("file:///tmp/unload".to_string(), UnloadFormat::CSV, None)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn unload_file_names_are_unique() {
+        let t1 =
+            UnloadTarget::try_new("s3://bucket/a".to_string(), UnloadFormat::CSV, None).unwrap();
+        let t2 =
+            UnloadTarget::try_new("s3://bucket/a".to_string(), UnloadFormat::CSV, None).unwrap();
+        assert_ne!(t1.prefix, t2.prefix);
+        // Targets are sent to workers, which should use the same prefix.
+        let t = bincode::deserialize::<UnloadTarget>(&bincode::serialize(&t1).unwrap()).unwrap();
+        assert_eq!(t, t1);
+    }
+
+    #[tokio::test]
+    async fn write_stream_into_local_dir() {
+        let uploads_dir = TempDir::new().unwrap();
+        let local_dir = TempDir::new().unwrap();
+        let storage = UnloadStorage::new(ImportS3Config::default());
+        let target = UnloadTarget::try_new(
+            "file://out".to_string(),
+            UnloadFormat::CSV,
+            Some(local_dir.path()),
+        )
+        .unwrap();
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let batches = (0..3)
+            .map(|i| {
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![
+                        Arc::new(Int64Array::from(vec![i])),
+                        Arc::new(StringArray::from(vec![Some(format!("n{}", i))])),
+                    ],
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let stream = MemoryExec::try_new(&[batches], schema.clone(), None)
+            .unwrap()
+            .execute(0)
+            .await
+            .unwrap();
+        let uploads = uploads_dir.path().to_string_lossy().to_string();
+        let file = target
+            .write_stream(&storage, &uploads, "part-00000", stream)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(file.row_count, 3);
+        let path = file.path.strip_prefix("file://").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            "id,name\n0,n0\n1,n1\n2,n2\n"
+        );
+
+        // Nothing is written without rows.
+        let stream = MemoryExec::try_new(&[vec![]], schema, None)
+            .unwrap()
+            .execute(0)
+            .await
+            .unwrap();
+        assert_eq!(
+            target
+                .write_stream(&storage, &uploads, "part-00001", stream)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn s3_clients_are_reused() {
+        let local_dir = Path::new("/tmp/unload");
+        let storage = UnloadStorage::new(ImportS3Config {
+            endpoint: Some("http://localhost:9000".to_string()),
+            ..ImportS3Config::default()
+        });
+        for location in ["s3://bucket/a", "s3://bucket/b", "s3://other"] {
+            let target =
+                UnloadTarget::try_new(location.to_string(), UnloadFormat::CSV, None).unwrap();
+            assert!(matches!(
+                storage.destination(&target, local_dir).unwrap(),
+                UnloadDestination::S3 { .. }
+            ));
+        }
+        assert_eq!(storage.s3_buckets.lock().unwrap().len(), 2);
+
+        // The region is taken from the config rather than the environment.
+        let storage = UnloadStorage::new(ImportS3Config::default());
+        let target =
+            UnloadTarget::try_new("s3://bucket".to_string(), UnloadFormat::CSV, None).unwrap();
+        assert!(storage.destination(&target, local_dir).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::config::ConfigObj;
 use crate::metastore::MetaStore;
 use crate::remotefs::RemoteFs;
+use crate::table::bloom_filter::BLOOM_FILTER_FILE_SUFFIX;
 use crate::{app_metrics, CubeError};
 use chrono::Utc;
 use datafusion::cube_ext;
@@ -111,7 +112,9 @@ impl RemoteFsCleanup {
 
             for f in remote_files {
                 let file_name = f.remote_path();
-                if !file_name.ends_with(".parquet") {
+                if !file_name.ends_with(".parquet")
+                    && !file_name.ends_with(BLOOM_FILTER_FILE_SUFFIX)
+                {
                     continue;
                 }
                 if files_from_metastore.get(file_name).is_some() {
@@ -216,7 +219,9 @@ impl RemoteFsCleanup {
                             Ok(name) => name,
                         };
 
-                        if !file_name.ends_with(".parquet") {
+                        if !file_name.ends_with(".parquet")
+                            && !file_name.ends_with(BLOOM_FILTER_FILE_SUFFIX)
+                        {
                             continue;
                         }
 
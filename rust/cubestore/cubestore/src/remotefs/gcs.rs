@@ -20,7 +20,7 @@ use tokio::sync::Mutex;
 use tokio_util::codec::{BytesCodec, FramedRead};
 
 static INIT_CREDENTIALS: Once = Once::new();
-fn ensure_credentials_init() {
+pub fn ensure_credentials_init() {
     // The cloud storage library uses env vars to get access tokens.
     // We decided CubeStore needs its own alias for it, so rewrite and hope no one read it before.
     // TODO: switch to something that allows to configure without env vars.
@@ -8,7 +8,7 @@ use crate::metastore::replay_handle::{
     subtract_from_right_seq_pointer_by_location, subtract_if_covers_seq_pointer_by_location,
     union_seq_pointer_by_location, SeqPointerForLocation,
 };
-use crate::metastore::table::Table;
+use crate::metastore::table::{RetentionPolicy, Table};
 use crate::metastore::{
     deactivate_table_due_to_corrupt_data, deactivate_table_on_corrupt_data, Chunk, IdRow,
     MetaStore, MetaStoreEvent, Partition, RowKey, TableId,
@@ -16,6 +16,7 @@ use crate::metastore::{
 use crate::remotefs::RemoteFs;
 use crate::shared::deadline_queue::DeadlineQueue;
 use crate::store::{ChunkStore, WALStore};
+use crate::table::bloom_filter::bloom_filter_file_name;
 use crate::util::time_span::warn_long_fut;
 use crate::util::WorkerLoop;
 use crate::CubeError;
@@ -47,6 +48,7 @@ pub struct SchedulerImpl {
     chunk_events_queue: Mutex<Vec<(SystemTime, u64)>>,
     in_memory_chunks_to_delete: Mutex<Vec<(String, String)>>, //(node, chunk_is)
     node_last_actions: Mutex<HashMap<String, LastNodeActionTimes>>,
+    last_purge_expired_data: Mutex<Option<Instant>>,
 }
 
 crate::di_service!(SchedulerImpl, []);
@@ -101,6 +103,7 @@ impl SchedulerImpl {
             chunk_events_queue: Mutex::new(Vec::with_capacity(1000)),
             in_memory_chunks_to_delete: Mutex::new(Vec::with_capacity(1000)),
             node_last_actions: Mutex::new(workers),
+            last_purge_expired_data: Mutex::new(None),
             chunk_processing_loop: WorkerLoop::new("ChunkProcessing"),
         }
     }
@@ -338,6 +341,16 @@ impl SchedulerImpl {
             );
         }
 
+        if let Err(e) = warn_long_fut(
+            "Purge expired data",
+            Duration::from_millis(5000),
+            self.purge_expired_data_if_needed(),
+        )
+        .await
+        {
+            error!("Error purging expired data: {}", e);
+        }
+
         if let Err(e) = warn_long_fut(
             "Scheduling compactions",
             Duration::from_millis(5000),
@@ -569,6 +582,162 @@ impl SchedulerImpl {
         Ok(())
     }
 
+    /// Purges expired data at most once per `retention_purge_interval_secs` as it has to scan all
+    /// partitions of the tables with a retention policy.
+    async fn purge_expired_data_if_needed(&self) -> Result<(), CubeError> {
+        {
+            let mut last_purge = self.last_purge_expired_data.lock().await;
+            let interval = Duration::from_secs(self.config.retention_purge_interval_secs());
+            if last_purge.map(|t| t.elapsed() < interval).unwrap_or(false) {
+                return Ok(());
+            }
+            *last_purge = Some(Instant::now());
+        }
+        self.purge_expired_data().await
+    }
+
+    /// Drops partitions and chunks of tables with a retention policy that have only expired rows.
+    /// Compaction is scheduled for partitions that are expired only partially, it rewrites them
+    /// without the expired rows.
+    pub async fn purge_expired_data(&self) -> Result<(), CubeError> {
+        let tables = self.meta_store.get_tables_with_path(false).await?;
+        let now = Utc::now();
+        for table in tables.iter() {
+            let retention = match table.table.get_row().retention() {
+                Some(retention) => retention,
+                None => continue,
+            };
+            let cutoff = match retention.cutoff(now) {
+                Some(cutoff) => cutoff.timestamp_nanos(),
+                None => continue,
+            };
+            let mut purged = false;
+            let indexes = self
+                .meta_store
+                .get_table_indexes(table.table.get_id())
+                .await?;
+            for index in indexes {
+                let column = match index
+                    .get_row()
+                    .columns()
+                    .iter()
+                    .position(|c| c.get_name() == &retention.column)
+                {
+                    Some(column) => column,
+                    None => continue,
+                };
+                let partitions = self
+                    .meta_store
+                    .get_active_partitions_and_chunks_by_index_id_for_select(vec![index.get_id()])
+                    .await?;
+                for (partition, chunks) in partitions.into_iter().flatten() {
+                    if partition.get_row().multi_partition_id().is_some() {
+                        continue;
+                    }
+                    let (expired_chunks, chunks): (Vec<_>, Vec<_>) =
+                        chunks.into_iter().partition(|c| {
+                            let c = c.get_row();
+                            RetentionPolicy::all_expired(
+                                c.column_statistics(),
+                                c.min(),
+                                c.max(),
+                                column,
+                                cutoff,
+                            )
+                        });
+                    let p = partition.get_row();
+                    let has_main_table = p.main_table_row_count() > 0;
+                    let main_table_expired = has_main_table
+                        && RetentionPolicy::all_expired(
+                            p.get_column_statistics(),
+                            p.get_min(),
+                            p.get_max(),
+                            column,
+                            cutoff,
+                        );
+                    // Expired rows are filtered out when the partition is compacted.
+                    let needs_compaction = (has_main_table
+                        && RetentionPolicy::some_expired(
+                            p.get_column_statistics(),
+                            p.get_min(),
+                            column,
+                            cutoff,
+                        ))
+                        || chunks.iter().any(|c| {
+                            RetentionPolicy::some_expired(
+                                c.get_row().column_statistics(),
+                                c.get_row().min(),
+                                column,
+                                cutoff,
+                            )
+                        });
+                    if main_table_expired {
+                        log::info!(
+                            "Purging expired partition {} of {}",
+                            partition.get_id(),
+                            table.table_name()
+                        );
+                        let min_val = p.get_min_val().clone();
+                        let max_val = p.get_max_val().clone();
+                        let child = self
+                            .meta_store
+                            .create_partition(
+                                Partition::new_child(&partition, None)
+                                    .update_min_max_and_row_count(
+                                        min_val.clone(),
+                                        max_val.clone(),
+                                        0,
+                                        None,
+                                        None,
+                                    ),
+                            )
+                            .await?;
+                        // Not expired chunks are moved to the child as it covers the same range.
+                        self.meta_store
+                            .swap_active_partitions(
+                                vec![(partition, expired_chunks)],
+                                vec![(child, 0)],
+                                vec![(0, (min_val, max_val), (None, None))],
+                                vec![false],
+                                vec![None],
+                            )
+                            .await?;
+                        purged = true;
+                    } else {
+                        if !expired_chunks.is_empty() {
+                            log::info!(
+                                "Purging {} expired chunks of partition {} of {}",
+                                expired_chunks.len(),
+                                partition.get_id(),
+                                table.table_name()
+                            );
+                            self.meta_store
+                                .deactivate_chunks(
+                                    expired_chunks.iter().map(|c| c.get_id()).collect(),
+                                )
+                                .await?;
+                            purged = true;
+                        }
+                        if needs_compaction {
+                            log::debug!(
+                                "Scheduling compaction of partially expired partition {} of {}",
+                                partition.get_id(),
+                                table.table_name()
+                            );
+                            self.schedule_partition_to_compact(&partition).await?;
+                        }
+                    }
+                }
+            }
+            if purged {
+                self.meta_store
+                    .update_table_last_purge_at(table.table.get_id(), now)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
     async fn remove_inactive_not_uploaded_chunks(&self) -> Result<(), CubeError> {
         let all_inactive_not_uploaded_chunks =
             self.meta_store.all_inactive_not_uploaded_chunks().await?;
@@ -778,7 +947,16 @@ impl SchedulerImpl {
         if let MetaStoreEvent::DeletePartition(partition) = &event {
             // remove file only if partition is active otherwise it should be removed when it's deactivated
             if partition.get_row().is_active() {
-                if let Some(file_name) = partition.get_row().get_full_name(partition.get_id()) {
+                let file_names = partition
+                    .get_row()
+                    .get_full_name(partition.get_id())
+                    .into_iter()
+                    .chain(
+                        partition
+                            .get_row()
+                            .bloom_filter_file_name(partition.get_id()),
+                    );
+                for file_name in file_names {
                     let deadline = Instant::now()
                         + Duration::from_secs(self.config.meta_store_snapshot_interval() * 2);
                     self.gc_queue
@@ -796,6 +974,14 @@ impl SchedulerImpl {
                         partition_file_name(partition.get_id(), partition.get_row().suffix());
                     let deadline =
                         Instant::now() + Duration::from_secs(self.config.not_used_timeout());
+                    if partition.get_row().has_bloom_filters() {
+                        self.gc_queue
+                            .send(
+                                GCTask::RemoveRemoteFile(bloom_filter_file_name(&file_name)),
+                                deadline,
+                            )
+                            .await?;
+                    }
                     self.gc_queue
                         .send(GCTask::RemoveRemoteFile(file_name), deadline)
                         .await?;
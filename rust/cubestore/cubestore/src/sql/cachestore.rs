@@ -272,6 +272,175 @@ impl CacheStoreSqlService {
                     true,
                 )
             }
+            CacheCommand::HSet {
+                key,
+                field,
+                value,
+                ttl,
+            } => {
+                let value_size =
+                    key.value.deep_size_of() + field.value.deep_size_of() + value.deep_size_of();
+                let added = self
+                    .cachestore
+                    .cache_hset(key.value, field.value, value, ttl)
+                    .await?;
+
+                (
+                    Arc::new(DataFrame::new(
+                        vec![Column::new("added".to_string(), ColumnType::Boolean, 0)],
+                        vec![Row::new(vec![TableValue::Boolean(added)])],
+                    )),
+                    Some(value_size),
+                    true,
+                )
+            }
+            CacheCommand::HGet { key, field } => {
+                let value = self.cachestore.cache_hget(key.value, field.value).await?;
+
+                (
+                    Arc::new(DataFrame::new(
+                        vec![Column::new("value".to_string(), ColumnType::String, 0)],
+                        vec![Row::new(vec![if let Some(value) = value {
+                            TableValue::String(value)
+                        } else {
+                            TableValue::Null
+                        }])],
+                    )),
+                    None,
+                    true,
+                )
+            }
+            CacheCommand::HIncrBy {
+                key,
+                field,
+                increment,
+                ttl,
+            } => {
+                let value = self
+                    .cachestore
+                    .cache_hincrby(key.value, field.value, increment, ttl)
+                    .await?;
+
+                (
+                    Arc::new(DataFrame::new(
+                        vec![Column::new("value".to_string(), ColumnType::String, 0)],
+                        vec![Row::new(vec![TableValue::String(value.to_string())])],
+                    )),
+                    None,
+                    true,
+                )
+            }
+            CacheCommand::ZAdd {
+                key,
+                score,
+                member,
+                ttl,
+            } => {
+                let value_size = key.value.deep_size_of() + member.deep_size_of();
+                let added = self
+                    .cachestore
+                    .cache_zadd(key.value, score, member, ttl)
+                    .await?;
+
+                (
+                    Arc::new(DataFrame::new(
+                        vec![Column::new("added".to_string(), ColumnType::Boolean, 0)],
+                        vec![Row::new(vec![TableValue::Boolean(added)])],
+                    )),
+                    Some(value_size),
+                    true,
+                )
+            }
+            CacheCommand::ZRangeByScore {
+                key,
+                min,
+                max,
+                limit,
+            } => {
+                let entries = self
+                    .cachestore
+                    .cache_zrange_by_score(key.value, min, max, limit)
+                    .await?;
+
+                (
+                    Arc::new(DataFrame::new(
+                        vec![
+                            Column::new("member".to_string(), ColumnType::String, 0),
+                            Column::new("score".to_string(), ColumnType::Float, 1),
+                        ],
+                        entries
+                            .into_iter()
+                            .map(|entry| {
+                                Row::new(vec![
+                                    TableValue::String(entry.member),
+                                    TableValue::Float(entry.score.into()),
+                                ])
+                            })
+                            .collect(),
+                    )),
+                    None,
+                    true,
+                )
+            }
+            CacheCommand::ZRemRangeByScore { key, min, max } => {
+                let removed = self
+                    .cachestore
+                    .cache_zrem_range_by_score(key.value, min, max)
+                    .await?;
+
+                (
+                    Arc::new(DataFrame::new(
+                        vec![Column::new("removed".to_string(), ColumnType::Int, 0)],
+                        vec![Row::new(vec![TableValue::Int(removed as i64)])],
+                    )),
+                    None,
+                    true,
+                )
+            }
+            CacheCommand::Publish { channel, message } => {
+                let message_size = message.deep_size_of();
+                self.cachestore
+                    .cache_publish(channel.value, message)
+                    .await?;
+
+                (
+                    Arc::new(DataFrame::new(vec![], vec![])),
+                    Some(message_size),
+                    true,
+                )
+            }
+            CacheCommand::Subscribe {
+                channel,
+                timeout,
+                after,
+            } => {
+                let rows = self
+                    .cachestore
+                    .cache_subscribe(channel.value, timeout, after)
+                    .await?
+                    .into_iter()
+                    .map(|message| {
+                        Row::new(vec![
+                            TableValue::String(message.channel),
+                            TableValue::String(message.message),
+                            TableValue::Int(message.cursor as i64),
+                        ])
+                    })
+                    .collect();
+
+                (
+                    Arc::new(DataFrame::new(
+                        vec![
+                            Column::new("channel".to_string(), ColumnType::String, 0),
+                            Column::new("message".to_string(), ColumnType::String, 1),
+                            Column::new("cursor".to_string(), ColumnType::Int, 2),
+                        ],
+                        rows,
+                    )),
+                    None,
+                    false,
+                )
+            }
         };
 
         let trace_index = TraceIndex {
@@ -325,6 +494,8 @@ impl CacheStoreSqlService {
                 key,
                 priority,
                 orphaned,
+                delay,
+                max_attempts,
                 value,
             } => {
                 let value_size = key.value.deep_size_of() + value.deep_size_of();
@@ -335,6 +506,8 @@ impl CacheStoreSqlService {
                         value,
                         priority,
                         orphaned,
+                        delay,
+                        max_attempts,
                     })
                     .await?;
 
@@ -375,6 +548,18 @@ impl CacheStoreSqlService {
 
                 (Arc::new(DataFrame::new(columns, rows)), None, true)
             }
+            QueueCommand::DeadLetterRequeue { key } => {
+                let success = self.cachestore.queue_dead_letter_requeue(key).await?;
+
+                (
+                    Arc::new(DataFrame::new(
+                        vec![Column::new("success".to_string(), ColumnType::Boolean, 0)],
+                        vec![Row::new(vec![TableValue::Boolean(success)])],
+                    )),
+                    None,
+                    true,
+                )
+            }
             QueueCommand::Heartbeat { key } => {
                 self.cachestore.queue_heartbeat(key).await?;
 
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use async_trait::async_trait;
 use chrono::format::Fixed::Nanosecond3;
@@ -10,7 +10,7 @@ use chrono::format::Item::{Fixed, Literal, Numeric, Space};
 use chrono::format::Numeric::{Day, Hour, Minute, Month, Second, Year};
 use chrono::format::Pad::Zero;
 use chrono::format::Parsed;
-use chrono::{ParseResult, TimeZone, Utc};
+use chrono::{NaiveDate, ParseResult, TimeZone, Utc};
 use datafusion::arrow::array::*;
 use datafusion::arrow::compute::kernels::cast_utils::string_to_timestamp_nanos;
 use datafusion::cube_ext;
@@ -41,22 +41,30 @@ use crate::config::injection::DIService;
 use crate::config::ConfigObj;
 use crate::import::limits::ConcurrencyLimits;
 use crate::import::{parse_space_separated_binstring, ImportService, Ingestion};
+use crate::metastore::job::{JobStatus, JobType};
 use crate::metastore::multi_index::MultiIndex;
+use crate::metastore::replay_handle::{
+    subtract_from_right_seq_pointer_by_location, union_seq_pointer_by_location,
+};
 use crate::metastore::source::SourceCredentials;
+use crate::metastore::table::{MaterializedView, ParquetOptions, RetentionPolicy, Table};
 use crate::metastore::{
     is_valid_plain_binary_hll, HllFlavour, IdRow, ImportFormat, Index, IndexDef, IndexType,
-    MetaStoreTable, Schema,
+    MetaStoreTable, RowKey, Schema, TableId,
 };
 use crate::queryplanner::panic::PanicWorkerNode;
 use crate::queryplanner::pretty_printers::{pp_phys_plan, pp_plan};
 use crate::queryplanner::query_executor::{batches_to_dataframe, ClusterSendExec, QueryExecutor};
+use crate::queryplanner::running_queries::RunningQueries;
 use crate::queryplanner::serialized_plan::{RowFilter, SerializedPlan};
+use crate::queryplanner::unload::{UnloadFormat, UnloadTarget};
 use crate::queryplanner::{PlanningMeta, QueryPlan, QueryPlanner};
 use crate::remotefs::RemoteFs;
 use crate::sql::cache::SqlResultCache;
 use crate::sql::parser::{CubeStoreParser, DropCommand, MetaStoreCommand, SystemCommand};
 use crate::store::ChunkDataStore;
-use crate::table::{data, Row, TableValue, TimestampValue};
+use crate::streaming::materialized_view::{is_supported_key_column_type, MaterializedViewPlanner};
+use crate::table::{data, DateValue, Row, TableValue, TimestampValue};
 use crate::util::decimal::{Decimal, Decimal96};
 use crate::util::strings::path_to_string;
 use crate::CubeError;
@@ -173,6 +181,7 @@ pub struct SqlServiceImpl {
     query_timeout: Duration,
     cache: Arc<SqlResultCache>,
     table_creator: Arc<TableCreator>,
+    running_queries: Arc<RunningQueries>,
 }
 
 crate::di_service!(SqlServiceImpl, [SqlService]);
@@ -196,6 +205,7 @@ impl SqlServiceImpl {
         create_table_timeout: Duration,
         cache: Arc<SqlResultCache>,
         process_rate_limiter: Arc<dyn ProcessRateLimiter>,
+        running_queries: Arc<RunningQueries>,
     ) -> Arc<SqlServiceImpl> {
         Arc::new(SqlServiceImpl {
             cachestore: CacheStoreSqlService::new(
@@ -223,6 +233,7 @@ impl SqlServiceImpl {
             query_timeout,
             remote_fs,
             cache,
+            running_queries,
         })
     }
 
@@ -241,7 +252,7 @@ impl SqlServiceImpl {
         columns: Vec<ColumnDef>,
         if_not_exists: bool,
     ) -> Result<IdRow<MultiIndex>, CubeError> {
-        let columns = convert_columns_type(&columns)?;
+        let columns = convert_columns_type(&columns, self.config_obj.native_column_types())?;
         self.db
             .create_partitioned_index(schema, name, columns, if_not_exists)
             .await
@@ -269,6 +280,244 @@ impl SqlServiceImpl {
             .await?)
     }
 
+    async fn create_materialized_view(
+        &self,
+        name: ObjectName,
+        query: Box<Query>,
+    ) -> Result<IdRow<Table>, CubeError> {
+        if name.0.len() != 2 {
+            return Err(CubeError::user(format!(
+                "Schema's name should be present in materialized view name but found: {}",
+                name
+            )));
+        }
+        let source_name = match &query.body {
+            SetExpr::Select(select) => match select.from.as_slice() {
+                [TableWithJoins {
+                    relation: TableFactor::Table { name, .. },
+                    joins,
+                }] if joins.is_empty() && name.0.len() == 2 => Ok(name),
+                _ => Err(CubeError::user(
+                    "Materialized view should select from a single streaming table referenced by its full name".to_string(),
+                )),
+            },
+            _ => Err(CubeError::user(format!(
+                "Unsupported materialized view query: {}",
+                query
+            ))),
+        }?;
+        let source = self
+            .db
+            .get_table(
+                source_name.0[0].value.clone(),
+                source_name.0[1].value.clone(),
+            )
+            .await?;
+        if source.get_row().seq_column().is_none() {
+            return Err(CubeError::user(format!(
+                "Materialized views can only be created over streaming tables but '{}' is not streaming",
+                source_name
+            )));
+        }
+
+        if let Some(key_column) = source.get_row().unique_key_columns().and_then(|c| {
+            c.into_iter()
+                .find(|c| !is_supported_key_column_type(c.get_column_type()))
+        }) {
+            return Err(CubeError::user(format!(
+                "Materialized views can't be created over '{}' as its unique key column '{}' has type {}",
+                source_name,
+                key_column.get_name(),
+                key_column.get_column_type()
+            )));
+        }
+
+        let select_statement = query.to_string();
+        let planner = MaterializedViewPlanner::new(
+            source_name.0[0].value.clone(),
+            source_name.0[1].value.clone(),
+            source.get_row().get_columns().clone(),
+        );
+        let (columns, aggregates, dimensions) = planner.view_columns(&select_statement)?;
+
+        let view = self
+            .db
+            .create_table(
+                name.0[0].value.clone(),
+                name.0[1].value.clone(),
+                columns,
+                None,
+                None,
+                vec![IndexDef {
+                    name: "aggregate".to_string(),
+                    multi_index: None,
+                    columns: dimensions,
+                    index_type: IndexType::Aggregate,
+                }],
+                false,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(
+                    aggregates
+                        .into_iter()
+                        .map(|(f, c)| (f.to_string(), c))
+                        .collect(),
+                ),
+                None,
+                None,
+                false,
+                None,
+                None,
+                None,
+                Some(MaterializedView {
+                    source_table_id: source.get_id(),
+                    select_statement: select_statement.clone(),
+                }),
+            )
+            .await?;
+
+        match self
+            .backfill_materialized_view(&source, &view, &select_statement)
+            .await
+        {
+            Ok(view) => Ok(view),
+            Err(e) => {
+                self.db.drop_table(view.get_id()).await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Aggregates rows which are already in the source into a materialized view and makes the
+    /// view ready. Streams of the source don't write while the view isn't ready: each of them
+    /// either records the last source position it has written in a replay handle of the view or
+    /// isn't running, so rows up to these positions are aggregated here and the rest by streams.
+    async fn backfill_materialized_view(
+        &self,
+        source: &IdRow<Table>,
+        view: &IdRow<Table>,
+        select_statement: &str,
+    ) -> Result<IdRow<Table>, CubeError> {
+        let locations = source
+            .get_row()
+            .locations()
+            .map_or(Vec::new(), |l| l.into_iter().cloned().collect());
+        let started_at = SystemTime::now();
+        let recorded = loop {
+            let mut recorded = vec![false; locations.len()];
+            for handle in self
+                .db
+                .get_replay_handles_by_table(view.get_id())
+                .await?
+                .iter()
+            {
+                if let Some(seq_pointers) = handle.get_row().seq_pointers_by_location() {
+                    for (r, p) in recorded.iter_mut().zip(seq_pointers.iter()) {
+                        *r |= p.is_some();
+                    }
+                }
+            }
+            let mut paused = true;
+            for (location, _) in locations.iter().zip(recorded.iter()).filter(|(_, r)| !**r) {
+                let job = self
+                    .db
+                    .get_job_by_ref(
+                        RowKey::Table(TableId::Tables, source.get_id()),
+                        JobType::TableImportCSV(location.clone()),
+                    )
+                    .await?;
+                if let Some(JobStatus::ProcessingBy(_)) = job.as_ref().map(|j| j.get_row().status())
+                {
+                    paused = false;
+                }
+            }
+            if paused {
+                break recorded;
+            }
+            if started_at.elapsed()?.as_secs() > self.config_obj.import_job_timeout() {
+                return Err(CubeError::user(format!(
+                    "Timed out waiting for streams of '{}' to pause for materialized view '{}'",
+                    source.get_row().get_table_name(),
+                    view.get_row().get_table_name()
+                )));
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        };
+
+        let rows = self.exec_query(select_statement).await?;
+        let columns = view.get_row().get_columns();
+        let mut new_chunk_ids = Ok(Vec::new());
+        if !rows.get_rows().is_empty() {
+            let mut builders = data::create_array_builders(columns);
+            for row in rows.get_rows() {
+                data::append_row(&mut builders, columns, row);
+            }
+            let new_chunks = self
+                .chunk_store
+                .partition_data(
+                    view.get_id(),
+                    builders.into_iter().map(|mut b| b.finish()).collect(),
+                    columns.as_slice(),
+                    false,
+                )
+                .await?;
+            new_chunk_ids = join_all(new_chunks)
+                .await
+                .into_iter()
+                .map(|c| {
+                    let (c, file_size) = c??;
+                    Ok((c.get_id(), file_size))
+                })
+                .collect::<Result<Vec<_>, CubeError>>();
+        }
+
+        // Streams which aren't running continue from the positions of the source replay handles.
+        let source_handles = self.db.get_replay_handles_by_table(source.get_id()).await?;
+        let mut seq_pointers_by_location = None;
+        let mut failed_seq_pointers_by_location = None;
+        for handle in source_handles.iter() {
+            union_seq_pointer_by_location(
+                if handle.get_row().has_failed_to_persist_chunks() {
+                    &mut failed_seq_pointers_by_location
+                } else {
+                    &mut seq_pointers_by_location
+                },
+                handle.get_row().seq_pointers_by_location(),
+            )?;
+        }
+        subtract_from_right_seq_pointer_by_location(
+            &mut seq_pointers_by_location,
+            &failed_seq_pointers_by_location,
+        )?;
+        if let Some(seq_pointers) = seq_pointers_by_location.as_mut() {
+            for (p, r) in seq_pointers.iter_mut().zip(recorded.iter()) {
+                if *r {
+                    *p = None;
+                }
+            }
+        }
+        let replay_handle = match seq_pointers_by_location {
+            Some(seq_pointers) if seq_pointers.iter().any(|p| p.is_some()) => Some(
+                self.db
+                    .create_replay_handle_from_seq_pointers(view.get_id(), Some(seq_pointers))
+                    .await?,
+            ),
+            _ => None,
+        };
+        self.db
+            .activate_chunks(
+                view.get_id(),
+                new_chunk_ids?,
+                replay_handle.map(|h| h.get_id()),
+            )
+            .await?;
+        self.db.table_ready(view.get_id(), true).await
+    }
+
     async fn insert_data<'a>(
         &'a self,
         schema_name: String,
@@ -370,6 +619,76 @@ impl SqlServiceImpl {
         )))
     }
 
+    async fn unload(
+        &self,
+        context: SqlQueryContext,
+        query: &str,
+        q: Box<Query>,
+        location: String,
+        options: Vec<SqlOption>,
+    ) -> Result<Arc<DataFrame>, CubeError> {
+        let target = UnloadTarget::try_new(
+            location,
+            unload_format_from_options(&options)?,
+            self.config_obj.unload_local_dir().as_deref(),
+        )?;
+        let logical_plan = self
+            .query_planner
+            .logical_plan(
+                DFStatement::Statement(Statement::Query(q)),
+                &context.inline_tables,
+                context.trace_obj.clone(),
+            )
+            .await?;
+        let (plan, workers) = match logical_plan {
+            QueryPlan::Select(plan, workers) => (plan, workers),
+            QueryPlan::Meta(_) => {
+                return Err(CubeError::user(
+                    "Unload is not supported for selects from system tables".to_string(),
+                ))
+            }
+        };
+        app_metrics::DATA_QUERIES
+            .add_with_tags(1, Some(&vec![metrics::format_tag("command", "unload")]));
+
+        let cluster = self.cluster.clone();
+        let executor = self.query_executor.clone();
+        let remote_fs = self.remote_fs.clone();
+        let running_query = self.running_queries.start(query.to_string());
+        let plan = plan.with_running_query(running_query.query().info().clone());
+        let files = timeout(
+            self.query_timeout,
+            running_query.run(async move {
+                if workers.len() == 0 {
+                    let uploads_dir = remote_fs.uploads_dir().await?;
+                    executor
+                        .execute_router_unload(plan, cluster, target, uploads_dir)
+                        .await
+                } else {
+                    // Pick one of the workers to run as main for the request.
+                    let i = thread_rng().sample(Uniform::new(0, workers.len()));
+                    cluster.route_unload(&workers[i], plan, target).await
+                }
+            }),
+        )
+        .await??;
+
+        let columns = vec![
+            Column::new("path".to_string(), ColumnType::String, 0),
+            Column::new("row_count".to_string(), ColumnType::Int, 1),
+        ];
+        let rows = files
+            .into_iter()
+            .map(|f| {
+                Row::new(vec![
+                    TableValue::String(f.path),
+                    TableValue::Int(f.row_count as i64),
+                ])
+            })
+            .collect();
+        Ok(Arc::new(DataFrame::new(columns, rows)))
+    }
+
     async fn explain(
         &self,
         statement: Statement,
@@ -495,6 +814,118 @@ pub fn boolean_prop(credentials: &Vec<SqlOption>, prop_name: &str) -> Option<boo
         })
 }
 
+fn parquet_options_from_with_options(
+    with_options: &Vec<SqlOption>,
+) -> Result<Option<ParquetOptions>, CubeError> {
+    let mut options = ParquetOptions::default();
+    let mut has_options = false;
+    for option in with_options {
+        match (option.name.value.as_str(), &option.value) {
+            ("compression", Value::SingleQuotedString(v)) => {
+                options.compression = Some(v.parse()?);
+            }
+            ("dictionary", Value::Boolean(v)) => {
+                options.dictionary = Some(*v);
+            }
+            ("bloom_filter_columns", Value::SingleQuotedString(v)) => {
+                options.bloom_filter_columns = v
+                    .split(',')
+                    .map(|c| c.trim().to_string())
+                    .filter(|c| !c.is_empty())
+                    .collect();
+            }
+            ("compression" | "dictionary" | "bloom_filter_columns", _) => {
+                return Err(CubeError::user(format!(
+                    "Bad {} {}",
+                    option.name.value, option.value
+                )));
+            }
+            _ => continue,
+        }
+        has_options = true;
+    }
+    Ok(if has_options { Some(options) } else { None })
+}
+
+fn unload_format_from_options(options: &Vec<SqlOption>) -> Result<UnloadFormat, CubeError> {
+    let mut format = UnloadFormat::Parquet;
+    for option in options {
+        match (option.name.value.to_lowercase().as_str(), &option.value) {
+            ("format", Value::SingleQuotedString(v)) => {
+                format = UnloadFormat::from_string(v)?;
+            }
+            ("format", v) => {
+                return Err(CubeError::user(format!(
+                    "Bad format {}. Expected string.",
+                    v
+                )))
+            }
+            (name, _) => {
+                return Err(CubeError::user(format!(
+                    "Unsupported unload option '{}'",
+                    name
+                )))
+            }
+        }
+    }
+    Ok(format)
+}
+
+fn retention_from_with_options(
+    with_options: &Vec<SqlOption>,
+) -> Result<Option<RetentionPolicy>, CubeError> {
+    let option = |name: &str| -> Result<Option<&String>, CubeError> {
+        with_options
+            .iter()
+            .find(|o| o.name.value == name)
+            .map_or(Ok(None), |o| match &o.value {
+                Value::SingleQuotedString(v) => Ok(Some(v)),
+                v => Err(CubeError::user(format!(
+                    "Bad {} {}. Expected string.",
+                    name, v
+                ))),
+            })
+    };
+    match (option("retention_column")?, option("retention")?) {
+        (Some(column), Some(period)) => Ok(Some(RetentionPolicy {
+            column: column.clone(),
+            period_secs: parse_retention_period(period)?,
+        })),
+        (None, None) => Ok(None),
+        _ => Err(CubeError::user(
+            "Both retention_column and retention should be specified".to_string(),
+        )),
+    }
+}
+
+/// Parses periods like `90 days` or `12 hours` into seconds.
+fn parse_retention_period(period: &str) -> Result<u64, CubeError> {
+    let bad_period = || {
+        CubeError::user(format!(
+            "Bad retention '{}'. Expected a period like '90 days'",
+            period
+        ))
+    };
+    let mut parts = period.split_whitespace();
+    let (amount, unit) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(amount), Some(unit), None) => (amount, unit.to_lowercase()),
+        _ => return Err(bad_period()),
+    };
+    let amount = amount.parse::<u64>().map_err(|_| bad_period())?;
+    let unit_secs = match unit.strip_suffix('s').unwrap_or(&unit) {
+        "second" => 1,
+        "minute" => 60,
+        "hour" => 60 * 60,
+        "day" => 24 * 60 * 60,
+        "week" => 7 * 24 * 60 * 60,
+        _ => return Err(bad_period()),
+    };
+    match amount.checked_mul(unit_secs) {
+        Some(secs) if secs > 0 && secs <= RetentionPolicy::MAX_PERIOD_SECS => Ok(secs),
+        _ => Err(bad_period()),
+    }
+}
+
 #[derive(Debug)]
 pub struct MySqlDialectWithBackTicks {}
 
@@ -813,6 +1244,8 @@ impl SqlService for SqlServiceImpl {
                             option.value
                         ))),
                     })?;
+                let parquet_options = parquet_options_from_with_options(&with_options)?;
+                let retention = retention_from_with_options(&with_options)?;
 
                 let res = self
                     .table_creator
@@ -834,6 +1267,8 @@ impl SqlService for SqlServiceImpl {
                         unique_key,
                         aggregates,
                         partitioned_index,
+                        parquet_options,
+                        retention,
                         &context.trace_obj,
                     )
                     .await?;
@@ -932,6 +1367,18 @@ impl SqlService for SqlServiceImpl {
                     ))
                 }
             }
+            CubeStoreStatement::CreateMaterializedView { name, query: q } => {
+                app_metrics::DATA_QUERIES.add_with_tags(
+                    1,
+                    Some(&vec![metrics::format_tag(
+                        "command",
+                        "create_materialized_view",
+                    )]),
+                );
+
+                let view = self.create_materialized_view(name, q).await?;
+                Ok(Arc::new(DataFrame::from(vec![view])))
+            }
             CubeStoreStatement::Statement(Statement::CreatePartitionedIndex {
                 name,
                 columns,
@@ -1056,23 +1503,36 @@ impl SqlService for SqlServiceImpl {
 
                         let cluster = self.cluster.clone();
                         let executor = self.query_executor.clone();
+                        let running_queries = self.running_queries.clone();
+                        let sql = query.to_string();
                         timeout(
                             self.query_timeout,
                             self.cache
                                 .get(query, context, serialized, async move |plan| {
-                                    let records;
-                                    if workers.len() == 0 {
-                                        records =
-                                            executor.execute_router_plan(plan, cluster).await?.1;
-                                    } else {
-                                        // Pick one of the workers to run as main for the request.
-                                        let i = thread_rng().sample(Uniform::new(0, workers.len()));
-                                        let rs = cluster.route_select(&workers[i], plan).await?.1;
-                                        records = rs
-                                            .into_iter()
-                                            .map(|r| r.read())
-                                            .collect::<Result<Vec<_>, _>>()?;
-                                    }
+                                    let running_query = running_queries.start(sql);
+                                    let plan = plan
+                                        .with_running_query(running_query.query().info().clone());
+                                    let records = running_query
+                                        .run(async move {
+                                            if workers.len() == 0 {
+                                                Ok(executor
+                                                    .execute_router_plan(plan, cluster)
+                                                    .await?
+                                                    .1)
+                                            } else {
+                                                // Pick one of the workers to run as main for the request.
+                                                let i = thread_rng()
+                                                    .sample(Uniform::new(0, workers.len()));
+                                                let rs = cluster
+                                                    .route_select(&workers[i], plan)
+                                                    .await?
+                                                    .1;
+                                                rs.into_iter()
+                                                    .map(|r| r.read())
+                                                    .collect::<Result<Vec<_>, _>>()
+                                            }
+                                        })
+                                        .await?;
                                     Ok(cube_ext::spawn_blocking(
                                         move || -> Result<DataFrame, CubeError> {
                                             let df = batches_to_dataframe(records)?;
@@ -1102,6 +1562,28 @@ impl SqlService for SqlServiceImpl {
 
             CubeStoreStatement::Dump(q) => self.dump_select_inputs(query, q).await,
 
+            CubeStoreStatement::Unload {
+                query: q,
+                location,
+                options,
+            } => self.unload(context, query, q, location, options).await,
+
+            CubeStoreStatement::KillQuery { query_id } => {
+                let mut killed = self.running_queries.kill(query_id);
+                for worker in self.config_obj.select_workers().iter() {
+                    if worker.as_str() != self.cluster.server_name() {
+                        killed |= self.cluster.kill_query(worker, query_id).await?;
+                    }
+                }
+                if !killed {
+                    return Err(CubeError::user(format!(
+                        "Query {} is not running",
+                        query_id
+                    )));
+                }
+                Ok(Arc::new(DataFrame::new(vec![], vec![])))
+            }
+
             _ => Err(CubeError::user(format!("Unsupported SQL: '{}'", query))),
         }
     }
@@ -1575,6 +2057,21 @@ fn extract_data<'a>(
             let v = parse_float(cell)?;
             builder.append_value(v)?;
         }
+        ColumnType::Date | ColumnType::Uuid | ColumnType::Json | ColumnType::Array(_) => {
+            let value = match cell {
+                Expr::Value(Value::Null) => TableValue::Null,
+                Expr::Value(Value::SingleQuotedString(v)) => {
+                    ImportFormat::parse_column_value_str(column, v)?
+                }
+                x => {
+                    return Err(CubeError::user(format!(
+                        "Single quoted string is expected but {:?} found",
+                        x
+                    )))
+                }
+            };
+            data::append_value(builder, column.get_column_type(), &value);
+        }
     }
     Ok(())
 }
@@ -1598,6 +2095,22 @@ pub fn timestamp_from_string(v: &str) -> Result<TimestampValue, CubeError> {
     Ok(TimestampValue::new(nanos))
 }
 
+pub fn date_from_string(v: &str) -> Result<DateValue, CubeError> {
+    if let Ok(d) = NaiveDate::parse_from_str(v, "%Y-%m-%d") {
+        return Ok(DateValue::from_date(d));
+    }
+    // Drivers often export dates as midnight timestamps, take the UTC date of those.
+    match timestamp_from_string(v) {
+        Ok(ts) => {
+            const NANOS_PER_DAY: i64 = 86_400 * 1_000_000_000;
+            Ok(DateValue::new(
+                ts.get_time_stamp().div_euclid(NANOS_PER_DAY) as i32,
+            ))
+        }
+        Err(_) => Err(CubeError::user(format!("Can't parse date: {}", v))),
+    }
+}
+
 fn parse_time(s: &str, format: &[chrono::format::Item]) -> ParseResult<Parsed> {
     let mut p = Parsed::new();
     chrono::format::parse(&mut p, s, format.into_iter())?;
@@ -1661,7 +2174,7 @@ mod tests {
     use crate::metastore::job::JobType;
     use crate::store::compaction::CompactionService;
     use crate::table::parquet::CubestoreMetadataCacheFactoryImpl;
-    use async_compression::tokio::write::GzipEncoder;
+    use async_compression::tokio::write::{BzEncoder, GzipEncoder, ZstdEncoder};
     use cuberockstore::rocksdb::{Options, DB};
     use datafusion::physical_plan::parquet::BasicMetadataCacheFactory;
     use futures_timer::Delay;
@@ -1688,6 +2201,7 @@ mod tests {
     use crate::queryplanner::pretty_printers::{pp_phys_plan, pp_phys_plan_ext, PPOptions};
     use crate::remotefs::queue::QueueRemoteFs;
     use crate::scheduler::SchedulerImpl;
+    use crate::table::bloom_filter::RowGroupBloomFilters;
     use crate::table::data::{cmp_min_rows, cmp_row_key_heap};
     use crate::table::TableValue;
     use crate::util::int96::Int96;
@@ -1754,6 +2268,7 @@ mod tests {
                     1000,
                 )),
                 BasicProcessRateLimiter::new(),
+                RunningQueries::new(config.config_obj().as_ref()),
             );
             let i = service.exec_query("CREATE SCHEMA foo").await.unwrap();
             assert_eq!(
@@ -1833,6 +2348,7 @@ mod tests {
                     1000,
                 )),
                 BasicProcessRateLimiter::new(),
+                RunningQueries::new(config.config_obj().as_ref()),
             );
             let i = service.exec_query("CREATE SCHEMA Foo").await.unwrap();
             assert_eq!(
@@ -1943,6 +2459,7 @@ mod tests {
                     1000,
                 )),
                 BasicProcessRateLimiter::new(),
+                RunningQueries::new(config.config_obj().as_ref()),
             );
             let i = service.exec_query("CREATE SCHEMA Foo").await.unwrap();
             assert_eq!(
@@ -2413,79 +2930,473 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn over_2k_booleans() {
-        Config::test("over_2k_booleans").update_config(|mut c| {
-            c.partition_split_threshold = 1000000;
-            c.compaction_chunks_count_threshold = 0;
+    async fn date_uuid_json_array() {
+        Config::test("date_uuid_json_array").update_config(|mut c| {
+            c.native_column_types = true;
             c
         }).start_test(async move |services| {
             let service = services.sql_service;
 
             let _ = service.exec_query("CREATE SCHEMA foo").await.unwrap();
 
-            let _ = service.exec_query("CREATE TABLE foo.bool_group (bool_value boolean)").await.unwrap();
+            let _ = service
+                .exec_query("CREATE TABLE foo.events (id int, day date, uid uuid, payload json, tags text[])")
+                .await
+                .unwrap();
 
-            for batch in 0..25 {
-                let mut bools = Vec::new();
-                for i in 0..1000 {
-                    bools.push(i % (batch + 1) == 0);
-                }
+            service
+                .exec_query("INSERT INTO foo.events (id, day, uid, payload, tags) VALUES \
+                    (1, '2022-02-28', '6ba7b810-9dad-11d1-80b4-00c04fd430c8', '{\"user\": {\"name\": \"foo\"}, \"n\": [1, 2]}', '[\"a\", \"b\"]'), \
+                    (2, '1969-12-31', NULL, NULL, '[]')")
+                .await
+                .unwrap();
 
-                let values = bools.into_iter().map(|b| format!("({})", b)).join(", ");
-                service.exec_query(
-                    &format!("INSERT INTO foo.bool_group (bool_value) VALUES {}", values)
-                ).await.unwrap();
-            }
+            let result = service
+                .exec_query("SELECT id, day, uid, tags from foo.events order by id")
+                .await
+                .unwrap();
+            assert_eq!(
+                result.get_rows(),
+                &vec![
+                    Row::new(vec![
+                        TableValue::Int(1),
+                        TableValue::Date(DateValue::new(19051)),
+                        TableValue::Uuid(Uuid::from_u128(0x6ba7b8109dad11d180b400c04fd430c8)),
+                        TableValue::Array(vec![TableValue::String("a".to_string()), TableValue::String("b".to_string())]),
+                    ]),
+                    Row::new(vec![
+                        TableValue::Int(2),
+                        TableValue::Date(DateValue::new(-1)),
+                        TableValue::Null,
+                        TableValue::Array(vec![]),
+                    ]),
+                ]
+            );
 
-            let result = service.exec_query("SELECT count(*) from foo.bool_group").await.unwrap();
-            assert_eq!(result.get_rows()[0], Row::new(vec![TableValue::Int(25000)]));
+            let result = service
+                .exec_query("SELECT json_extract_path_text(payload, 'user', 'name'), json_extract_path(payload, 'n', '1') from foo.events where id = 1")
+                .await
+                .unwrap();
+            assert_eq!(
+                result.get_rows(),
+                &vec![Row::new(vec![TableValue::String("foo".to_string()), TableValue::String("2".to_string())])]
+            );
 
-            let result = service.exec_query("SELECT count(*) from foo.bool_group where bool_value = true").await.unwrap();
-            assert_eq!(result.get_rows()[0], Row::new(vec![TableValue::Int(3823)]));
+            let result = service
+                .exec_query("SELECT payload->'user'->>'name', payload -> 'n' ->> 1 from foo.events where id = 1")
+                .await
+                .unwrap();
+            assert_eq!(
+                result.get_rows(),
+                &vec![Row::new(vec![TableValue::String("foo".to_string()), TableValue::String("2".to_string())])]
+            );
 
-            let result = service.exec_query("SELECT g.bool_value, count(*) from foo.bool_group g GROUP BY 1 ORDER BY 2 DESC").await.unwrap();
+            service
+                .exec_query("INSERT INTO foo.events (id, day) VALUES (3, '2022-03-01T00:00:00.000Z')")
+                .await
+                .unwrap();
+            let result = service
+                .exec_query("SELECT day from foo.events where id = 3")
+                .await
+                .unwrap();
+            assert_eq!(result.get_rows(), &vec![Row::new(vec![TableValue::Date(DateValue::new(19052))])]);
 
-            assert_eq!(result.get_rows()[0], Row::new(vec![TableValue::Boolean(false), TableValue::Int(21177)]));
-            assert_eq!(result.get_rows()[1], Row::new(vec![TableValue::Boolean(true), TableValue::Int(3823)]));
-        }).await;
+            for column_type in ["decimal(2)[]", "int[][]", "uuid[]"] {
+                assert!(ColumnType::from_string(column_type).is_err(), "{}", column_type);
+            }
+            let err = service
+                .exec_query("CREATE TABLE foo.nested (tags int[][])")
+                .await;
+            assert!(err.is_err(), "nested array accepted: {:?}", err);
+
+            let err = service
+                .exec_query("INSERT INTO foo.events (id, payload) VALUES (3, '{\"user\": ')")
+                .await;
+            assert!(err.is_err(), "invalid json accepted: {:?}", err);
+
+            let err = service
+                .exec_query("CREATE INDEX by_tags ON foo.events (tags)")
+                .await;
+            assert!(err.is_err(), "index on array column accepted: {:?}", err);
+        })
+            .await;
     }
 
     #[tokio::test]
-    async fn flatten_union() {
-        Config::test("flatten_union").start_test(async move |services| {
+    async fn date_uuid_array_legacy_types() {
+        Config::test("date_uuid_array_legacy_types").start_test(async move |services| {
             let service = services.sql_service;
 
             let _ = service.exec_query("CREATE SCHEMA foo").await.unwrap();
 
-            let _ = service.exec_query("CREATE TABLE foo.a (a int, b int, c int)").await.unwrap();
-            let _ = service.exec_query("CREATE TABLE foo.b (a int, b int, c int)").await.unwrap();
+            let _ = service
+                .exec_query("CREATE TABLE foo.events (id int, day date, uid uuid, payload json)")
+                .await
+                .unwrap();
 
-            let _ = service.exec_query("CREATE TABLE foo.a1 (a int, b int, c int)").await.unwrap();
-            let _ = service.exec_query("CREATE TABLE foo.b1 (a int, b int, c int)").await.unwrap();
+            service
+                .exec_query("INSERT INTO foo.events (id, day, payload) VALUES (1, '2022-02-28', '{\"n\": 1}')")
+                .await
+                .unwrap();
 
-            service.exec_query(
-                "INSERT INTO foo.a (a, b, c) VALUES (1, 1, 1)"
-            ).await.unwrap();
-            service.exec_query(
-                "INSERT INTO foo.b (a, b, c) VALUES (2, 2, 1)"
-            ).await.unwrap();
-            service.exec_query(
-                "INSERT INTO foo.a1 (a, b, c) VALUES (1, 1, 2)"
-            ).await.unwrap();
-            service.exec_query(
-                "INSERT INTO foo.b1 (a, b, c) VALUES (2, 2, 2)"
-            ).await.unwrap();
+            let result = service
+                .exec_query("SELECT day, payload->>'n' from foo.events")
+                .await
+                .unwrap();
+            assert_eq!(
+                result.get_rows(),
+                &vec![Row::new(vec![TableValue::String("2022-02-28".to_string()), TableValue::String("1".to_string())])]
+            );
 
-            let result = service.exec_query("EXPLAIN SELECT a `sel__a`, b `sel__b`, sum(c) `sel__c` from ( \
-                         select * from ( \
-                                        select * from foo.a \
-                                        union all \
-                                        select * from foo.b \
-                                        ) \
-                             union all
-                             select * from
-                                ( \
-                                        select * from foo.a1 \
+            let result = service
+                .exec_query("SELECT column_name, data_type FROM information_schema.columns WHERE table_name = 'events' ORDER BY column_name")
+                .await
+                .unwrap();
+            assert_eq!(
+                result.get_rows(),
+                &vec![
+                    Row::new(vec![TableValue::String("day".to_string()), TableValue::String("text".to_string())]),
+                    Row::new(vec![TableValue::String("id".to_string()), TableValue::String("int".to_string())]),
+                    Row::new(vec![TableValue::String("payload".to_string()), TableValue::String("json".to_string())]),
+                    Row::new(vec![TableValue::String("uid".to_string()), TableValue::String("bytes".to_string())]),
+                ]
+            );
+        })
+            .await;
+    }
+
+    /// Paths and row counts of unloaded files.
+    fn unloaded_files(result: &DataFrame) -> Vec<(String, i64)> {
+        result
+            .get_rows()
+            .iter()
+            .map(|r| match (&r.values()[0], &r.values()[1]) {
+                (TableValue::String(p), TableValue::Int(n)) => (p.clone(), *n),
+                v => panic!("Unexpected unload result: {:?}", v),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn unload() {
+        let dir = TempDir::new().unwrap();
+        let local_dir = dir.path().to_path_buf();
+        Config::test("unload").update_config(|mut c| {
+            c.unload_local_dir = Some(local_dir);
+            c
+        }).start_test(async move |services| {
+            let service = services.sql_service;
+
+            let _ = service.exec_query("CREATE SCHEMA foo").await.unwrap();
+            let _ = service
+                .exec_query("CREATE TABLE foo.orders (id int, city text, amount decimal)")
+                .await
+                .unwrap();
+            service
+                .exec_query("INSERT INTO foo.orders (id, city, amount) VALUES (1, 'NY', 1.5), (2, 'SF', 2), (3, 'NY', 3.25)")
+                .await
+                .unwrap();
+
+            let location = format!("file://{}", dir.path().to_str().unwrap());
+
+            let query = format!("UNLOAD (SELECT city, SUM(amount) total FROM foo.orders GROUP BY 1 ORDER BY 1) TO '{}' WITH (format = 'csv')", location);
+            let files = unloaded_files(&service.exec_query(&query).await.unwrap());
+            assert_eq!(files.len(), 1);
+            let (path, row_count) = &files[0];
+            assert_eq!(*row_count, 2);
+            assert!(path.starts_with(&format!("{}/", location)), "{}", path);
+            assert!(path.ends_with("-part-00000.csv"), "{}", path);
+            let csv = std::fs::read_to_string(path.strip_prefix("file://").unwrap()).unwrap();
+            assert_eq!(csv, "city,total\nNY,4.75\nSF,2\n");
+
+            // Repeated unload into the same location doesn't overwrite files.
+            let repeated = unloaded_files(&service.exec_query(&query).await.unwrap());
+            assert_eq!(repeated.len(), 1);
+            assert_ne!(repeated[0].0, *path);
+            assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+
+            let files = unloaded_files(
+                &service
+                    .exec_query(&format!("UNLOAD (SELECT id FROM foo.orders) TO '{}'", location))
+                    .await
+                    .unwrap(),
+            );
+            assert_eq!(files.iter().map(|(_, n)| n).sum::<i64>(), 3);
+            for (p, _) in files {
+                assert!(p.ends_with(".parquet"), "{}", p);
+            }
+
+            let err = service
+                .exec_query(&format!("UNLOAD (SELECT id FROM foo.orders) TO '{}' WITH (format = 'json')", location))
+                .await;
+            assert!(err.is_err(), "unsupported format accepted: {:?}", err);
+
+            let err = service
+                .exec_query(&format!("UNLOAD (SELECT * FROM system.tables) TO '{}'", location))
+                .await;
+            assert!(err.is_err(), "unload of system tables accepted: {:?}", err);
+
+            for location in ["file:///etc", &format!("{}/../other", location)] {
+                let err = service
+                    .exec_query(&format!("UNLOAD (SELECT id FROM foo.orders) TO '{}'", location))
+                    .await;
+                assert!(err.is_err(), "unload outside of local dir accepted: {:?}", err);
+            }
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn unload_without_local_dir() {
+        Config::test("unload_without_local_dir")
+            .start_test(async move |services| {
+                let service = services.sql_service;
+
+                let err = service
+                    .exec_query("UNLOAD (SELECT 1) TO 'file:///tmp/unload'")
+                    .await;
+                assert!(err.is_err(), "unload into local files accepted: {:?}", err);
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn unload_cluster() {
+        let dir = TempDir::new().unwrap();
+        let location = format!("file://{}", dir.path().to_str().unwrap());
+        let local_dir = dir.path().to_path_buf();
+        let worker_local_dir_1 = local_dir.clone();
+        let worker_local_dir_2 = local_dir.clone();
+        Config::test("unload_cluster_router").update_config(|mut config| {
+            config.select_workers = vec!["127.0.0.1:14506".to_string(), "127.0.0.1:14507".to_string()];
+            config.metastore_bind_address = Some("127.0.0.1:15506".to_string());
+            config.compaction_chunks_count_threshold = 0;
+            config.unload_local_dir = Some(local_dir);
+            config
+        }).start_test(async move |services| {
+            let service = services.sql_service;
+
+            Config::test("unload_cluster_worker_1").update_config(|mut config| {
+                config.worker_bind_address = Some("127.0.0.1:14506".to_string());
+                config.server_name = "127.0.0.1:14506".to_string();
+                config.metastore_remote_address = Some("127.0.0.1:15506".to_string());
+                config.store_provider = FileStoreProvider::Filesystem {
+                    remote_dir: Some(env::current_dir()
+                        .unwrap()
+                        .join("unload_cluster_router-upstream".to_string())),
+                };
+                config.compaction_chunks_count_threshold = 0;
+                config.unload_local_dir = Some(worker_local_dir_1);
+                config
+            }).start_test_worker(async move |_| {
+                Config::test("unload_cluster_worker_2").update_config(|mut config| {
+                    config.worker_bind_address = Some("127.0.0.1:14507".to_string());
+                    config.server_name = "127.0.0.1:14507".to_string();
+                    config.metastore_remote_address = Some("127.0.0.1:15506".to_string());
+                    config.store_provider = FileStoreProvider::Filesystem {
+                        remote_dir: Some(env::current_dir()
+                            .unwrap()
+                            .join("unload_cluster_router-upstream".to_string())),
+                    };
+                    config.compaction_chunks_count_threshold = 0;
+                    config.unload_local_dir = Some(worker_local_dir_2);
+                    config
+                }).start_test_worker(async move |_| {
+                    service.exec_query("CREATE SCHEMA foo").await.unwrap();
+                    service.exec_query("CREATE TABLE foo.orders_1 (id int, amount int)").await.unwrap();
+                    service.exec_query("CREATE TABLE foo.orders_2 (id int, amount int)").await.unwrap();
+                    service.exec_query("INSERT INTO foo.orders_1 (id, amount) VALUES (1, 10), (2, 20), (3, 30)").await.unwrap();
+                    service.exec_query("INSERT INTO foo.orders_2 (id, amount) VALUES (4, 40), (5, 50)").await.unwrap();
+
+                    // Without the final merge every worker writes its part of results.
+                    let query = format!(
+                        "UNLOAD (SELECT * FROM (SELECT * FROM foo.orders_1 UNION ALL SELECT * FROM foo.orders_2) o) TO '{}' WITH (format = 'csv')",
+                        location
+                    );
+                    let (first, second) = futures::future::join(
+                        service.exec_query(&query),
+                        service.exec_query(&query),
+                    )
+                    .await;
+                    let files = unloaded_files(&first.unwrap())
+                        .into_iter()
+                        .chain(unloaded_files(&second.unwrap()))
+                        .collect::<Vec<_>>();
+                    assert_eq!(files.iter().map(|(_, n)| n).sum::<i64>(), 10);
+
+                    let mut rows = Vec::new();
+                    for (path, row_count) in files.iter() {
+                        let csv = std::fs::read_to_string(path.strip_prefix("file://").unwrap()).unwrap();
+                        let lines = csv.lines().skip(1).map(|l| l.to_string()).collect::<Vec<_>>();
+                        assert_eq!(lines.len() as i64, *row_count, "{}", path);
+                        rows.extend(lines);
+                    }
+                    rows.sort();
+                    assert_eq!(
+                        rows,
+                        vec!["1,10", "1,10", "2,20", "2,20", "3,30", "3,30", "4,40", "4,40", "5,50", "5,50"]
+                    );
+                    // Concurrent unloads into the same location don't overwrite files.
+                    assert_eq!(files.iter().map(|(p, _)| p).unique().count(), files.len());
+                    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), files.len());
+                }).await;
+            }).await;
+        }).await;
+    }
+
+    #[tokio::test]
+    async fn unload_s3() {
+        if env::var("CUBESTORE_AWS_ACCESS_KEY_ID").is_err()
+            || env::var("CUBESTORE_S3_REGION").is_err()
+        {
+            return;
+        }
+        Config::test("unload_s3")
+            .start_test(async move |services| {
+                let service = services.sql_service;
+
+                service.exec_query("CREATE SCHEMA foo").await.unwrap();
+                service
+                    .exec_query("CREATE TABLE foo.orders (id int, amount int)")
+                    .await
+                    .unwrap();
+                service
+                    .exec_query(
+                        "INSERT INTO foo.orders (id, amount) VALUES (1, 10), (2, 20), (3, 30)",
+                    )
+                    .await
+                    .unwrap();
+
+                let location = "s3://cube-store-ci-test/unload_s3";
+                let query = format!("UNLOAD (SELECT * FROM foo.orders) TO '{}'", location);
+                let files = unloaded_files(&service.exec_query(&query).await.unwrap());
+                assert_eq!(files.iter().map(|(_, n)| n).sum::<i64>(), 3);
+                for (path, _) in files.iter() {
+                    assert!(path.starts_with(&format!("{}/", location)), "{}", path);
+                    assert!(path.ends_with(".parquet"), "{}", path);
+                }
+
+                let repeated = unloaded_files(&service.exec_query(&query).await.unwrap());
+                assert!(repeated.iter().all(|f| !files.contains(f)));
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn unload_gcs() {
+        if env::var("SERVICE_ACCOUNT_JSON").is_err()
+            && env::var("CUBESTORE_GCP_SERVICE_ACCOUNT_JSON").is_err()
+        {
+            return;
+        }
+        Config::test("unload_gcs")
+            .start_test(async move |services| {
+                let service = services.sql_service;
+
+                service.exec_query("CREATE SCHEMA foo").await.unwrap();
+                service
+                    .exec_query("CREATE TABLE foo.orders (id int, amount int)")
+                    .await
+                    .unwrap();
+                service
+                    .exec_query(
+                        "INSERT INTO foo.orders (id, amount) VALUES (1, 10), (2, 20), (3, 30)",
+                    )
+                    .await
+                    .unwrap();
+
+                let location = "gs://cube-store-ci-test/unload_gcs";
+                let query = format!(
+                    "UNLOAD (SELECT * FROM foo.orders) TO '{}' WITH (format = 'csv')",
+                    location
+                );
+                let files = unloaded_files(&service.exec_query(&query).await.unwrap());
+                assert_eq!(files.iter().map(|(_, n)| n).sum::<i64>(), 3);
+                for (path, _) in files.iter() {
+                    assert!(path.starts_with(&format!("{}/", location)), "{}", path);
+                    assert!(path.ends_with(".csv"), "{}", path);
+                }
+
+                let repeated = unloaded_files(&service.exec_query(&query).await.unwrap());
+                assert!(repeated.iter().all(|f| !files.contains(f)));
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn over_2k_booleans() {
+        Config::test("over_2k_booleans").update_config(|mut c| {
+            c.partition_split_threshold = 1000000;
+            c.compaction_chunks_count_threshold = 0;
+            c
+        }).start_test(async move |services| {
+            let service = services.sql_service;
+
+            let _ = service.exec_query("CREATE SCHEMA foo").await.unwrap();
+
+            let _ = service.exec_query("CREATE TABLE foo.bool_group (bool_value boolean)").await.unwrap();
+
+            for batch in 0..25 {
+                let mut bools = Vec::new();
+                for i in 0..1000 {
+                    bools.push(i % (batch + 1) == 0);
+                }
+
+                let values = bools.into_iter().map(|b| format!("({})", b)).join(", ");
+                service.exec_query(
+                    &format!("INSERT INTO foo.bool_group (bool_value) VALUES {}", values)
+                ).await.unwrap();
+            }
+
+            let result = service.exec_query("SELECT count(*) from foo.bool_group").await.unwrap();
+            assert_eq!(result.get_rows()[0], Row::new(vec![TableValue::Int(25000)]));
+
+            let result = service.exec_query("SELECT count(*) from foo.bool_group where bool_value = true").await.unwrap();
+            assert_eq!(result.get_rows()[0], Row::new(vec![TableValue::Int(3823)]));
+
+            let result = service.exec_query("SELECT g.bool_value, count(*) from foo.bool_group g GROUP BY 1 ORDER BY 2 DESC").await.unwrap();
+
+            assert_eq!(result.get_rows()[0], Row::new(vec![TableValue::Boolean(false), TableValue::Int(21177)]));
+            assert_eq!(result.get_rows()[1], Row::new(vec![TableValue::Boolean(true), TableValue::Int(3823)]));
+        }).await;
+    }
+
+    #[tokio::test]
+    async fn flatten_union() {
+        Config::test("flatten_union").start_test(async move |services| {
+            let service = services.sql_service;
+
+            let _ = service.exec_query("CREATE SCHEMA foo").await.unwrap();
+
+            let _ = service.exec_query("CREATE TABLE foo.a (a int, b int, c int)").await.unwrap();
+            let _ = service.exec_query("CREATE TABLE foo.b (a int, b int, c int)").await.unwrap();
+
+            let _ = service.exec_query("CREATE TABLE foo.a1 (a int, b int, c int)").await.unwrap();
+            let _ = service.exec_query("CREATE TABLE foo.b1 (a int, b int, c int)").await.unwrap();
+
+            service.exec_query(
+                "INSERT INTO foo.a (a, b, c) VALUES (1, 1, 1)"
+            ).await.unwrap();
+            service.exec_query(
+                "INSERT INTO foo.b (a, b, c) VALUES (2, 2, 1)"
+            ).await.unwrap();
+            service.exec_query(
+                "INSERT INTO foo.a1 (a, b, c) VALUES (1, 1, 2)"
+            ).await.unwrap();
+            service.exec_query(
+                "INSERT INTO foo.b1 (a, b, c) VALUES (2, 2, 2)"
+            ).await.unwrap();
+
+            let result = service.exec_query("EXPLAIN SELECT a `sel__a`, b `sel__b`, sum(c) `sel__c` from ( \
+                         select * from ( \
+                                        select * from foo.a \
+                                        union all \
+                                        select * from foo.b \
+                                        ) \
+                             union all
+                             select * from
+                                ( \
+                                        select * from foo.a1 \
                                         union all \
                                         select * from foo.b1 \
                                         union all \
@@ -2831,6 +3742,273 @@ mod tests {
             .await;
     }
 
+    #[tokio::test]
+    async fn column_statistics_pruning() {
+        Config::test("column_statistics_pruning")
+            .update_config(|mut c| {
+                c.compaction_chunks_count_threshold = 100;
+                c
+            })
+            .start_test(async move |services| {
+                let service = services.sql_service;
+
+                service.exec_query("CREATE SCHEMA foo").await.unwrap();
+
+                service
+                    .exec_query("CREATE TABLE foo.events (id int, day int)")
+                    .await
+                    .unwrap();
+
+                for day in 1..=3 {
+                    service
+                        .exec_query(&format!(
+                            "INSERT INTO foo.events (id, day) VALUES (1, {0}), (2, {0}), (3, {0})",
+                            day
+                        ))
+                        .await
+                        .unwrap();
+                }
+
+                let result = service
+                    .exec_query("SELECT count(*) from foo.events where day = 2")
+                    .await
+                    .unwrap();
+                assert_eq!(result.get_rows()[0], Row::new(vec![TableValue::Int(3)]));
+
+                let result = service
+                    .exec_query("SELECT count(*) from foo.events where day IS NULL")
+                    .await
+                    .unwrap();
+                assert_eq!(result.get_rows()[0], Row::new(vec![TableValue::Int(0)]));
+
+                // `day` is not a prefix of the sort key, chunks are pruned by their statistics.
+                let plans = service
+                    .plan_query("SELECT count(*) from foo.events where day = 2")
+                    .await
+                    .unwrap();
+
+                let worker_plan = pp_phys_plan(plans.worker.as_ref());
+                let chunk_regex = Regex::new(r"\d+-[a-z0-9]+.chunk.parquet").unwrap();
+                assert_eq!(
+                    chunk_regex.captures_iter(&worker_plan).count(),
+                    1,
+                    "{}",
+                    worker_plan
+                );
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn column_statistics_pruning_unique_key() {
+        Config::test("column_statistics_pruning_unique_key")
+            .update_config(|mut c| {
+                c.compaction_chunks_count_threshold = 100;
+                c
+            })
+            .start_test(async move |services| {
+                let service = services.sql_service;
+
+                service.exec_query("CREATE SCHEMA foo").await.unwrap();
+
+                service
+                    .exec_query("CREATE TABLE foo.events (id int, day int) UNIQUE KEY (id)")
+                    .await
+                    .unwrap();
+
+                service
+                    .exec_query(
+                        "INSERT INTO foo.events (id, day, __seq) VALUES (1, 1, 1), (2, 1, 2)",
+                    )
+                    .await
+                    .unwrap();
+                // Newer version of the key is written into a separate chunk.
+                service
+                    .exec_query("INSERT INTO foo.events (id, day, __seq) VALUES (1, 2, 3)")
+                    .await
+                    .unwrap();
+
+                // The chunk with the newest version of `id = 1` doesn't match the filter, but it
+                // still hides the older version.
+                let result = service
+                    .exec_query("SELECT id, day from foo.events where day = 1 ORDER BY id")
+                    .await
+                    .unwrap();
+                assert_eq!(
+                    result.get_rows(),
+                    &vec![Row::new(vec![TableValue::Int(2), TableValue::Int(1)])]
+                );
+
+                let chunk_regex = Regex::new(r"\d+-[a-z0-9]+.chunk.parquet").unwrap();
+                let plans = service
+                    .plan_query("SELECT id, day from foo.events where day = 1")
+                    .await
+                    .unwrap();
+                let worker_plan = pp_phys_plan(plans.worker.as_ref());
+                assert_eq!(
+                    chunk_regex.captures_iter(&worker_plan).count(),
+                    2,
+                    "{}",
+                    worker_plan
+                );
+
+                // Conditions on the unique key can still prune chunks.
+                let result = service
+                    .exec_query("SELECT id, day from foo.events where id = 2")
+                    .await
+                    .unwrap();
+                assert_eq!(
+                    result.get_rows(),
+                    &vec![Row::new(vec![TableValue::Int(2), TableValue::Int(1)])]
+                );
+                let plans = service
+                    .plan_query("SELECT id, day from foo.events where id = 2")
+                    .await
+                    .unwrap();
+                let worker_plan = pp_phys_plan(plans.worker.as_ref());
+                assert_eq!(
+                    chunk_regex.captures_iter(&worker_plan).count(),
+                    1,
+                    "{}",
+                    worker_plan
+                );
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn table_retention() {
+        Config::test("table_retention")
+            .update_config(|mut c| {
+                c.compaction_chunks_count_threshold = 100;
+                c
+            })
+            .start_test(async move |services| {
+                let service = services.sql_service;
+
+                service.exec_query("CREATE SCHEMA foo").await.unwrap();
+
+                let res = service
+                    .exec_query("CREATE TABLE foo.bad (id int, ts timestamp) WITH (retention = '30 days')")
+                    .await;
+                assert!(res.is_err(), "{:?}", res);
+                let res = service
+                    .exec_query("CREATE TABLE foo.bad (id int, ts timestamp) WITH (retention_column = 'id', retention = '30 days')")
+                    .await;
+                assert!(res.is_err(), "{:?}", res);
+
+                service
+                    .exec_query("CREATE TABLE foo.events (id int, ts timestamp) WITH (retention_column = 'ts', retention = '30 days')")
+                    .await
+                    .unwrap();
+
+                let recent = (Utc::now() - chrono::Duration::days(1))
+                    .format("%Y-%m-%dT%H:%M:%S%.3fZ")
+                    .to_string();
+                for values in [
+                    "(1, '2020-01-01T00:00:00.000Z'), (2, '2020-01-02T00:00:00.000Z')".to_string(),
+                    format!("(3, '2020-01-01T00:00:00.000Z'), (4, '{}')", recent),
+                    "(5, NULL)".to_string(),
+                ] {
+                    service
+                        .exec_query(&format!("INSERT INTO foo.events (id, ts) VALUES {}", values))
+                        .await
+                        .unwrap();
+                }
+
+                services
+                    .injector
+                    .get_service_typed::<SchedulerImpl>()
+                    .await
+                    .purge_expired_data()
+                    .await
+                    .unwrap();
+
+                // Only the chunk with all rows expired is dropped.
+                let result = service
+                    .exec_query("SELECT id from foo.events ORDER BY id")
+                    .await
+                    .unwrap();
+                assert_eq!(
+                    result.get_rows(),
+                    &vec![
+                        Row::new(vec![TableValue::Int(3)]),
+                        Row::new(vec![TableValue::Int(4)]),
+                        Row::new(vec![TableValue::Int(5)]),
+                    ]
+                );
+
+                // Compaction scheduled for the partially expired chunk drops the expired row.
+                let mut ids = Vec::new();
+                for _ in 0..40 {
+                    let result = service
+                        .exec_query("SELECT id from foo.events ORDER BY id")
+                        .await
+                        .unwrap();
+                    ids = result.get_rows().clone();
+                    if ids.len() == 2 {
+                        break;
+                    }
+                    Delay::new(Duration::from_millis(250)).await;
+                }
+                assert_eq!(
+                    ids,
+                    vec![
+                        Row::new(vec![TableValue::Int(4)]),
+                        Row::new(vec![TableValue::Int(5)]),
+                    ]
+                );
+
+                let result = service
+                    .exec_query("SELECT retention_column, retention_period_secs, last_purge_at IS NOT NULL FROM system.tables")
+                    .await
+                    .unwrap();
+                assert_eq!(
+                    result.get_rows(),
+                    &vec![Row::new(vec![
+                        TableValue::String("ts".to_string()),
+                        TableValue::Int(30 * 24 * 60 * 60),
+                        TableValue::Boolean(true),
+                    ])]
+                );
+            })
+            .await;
+    }
+
+    #[test]
+    fn retention_period_parsing() {
+        assert_eq!(
+            parse_retention_period("90 days").unwrap(),
+            90 * 24 * 60 * 60
+        );
+        assert_eq!(parse_retention_period("1 day").unwrap(), 24 * 60 * 60);
+        assert_eq!(parse_retention_period("12 Hours").unwrap(), 12 * 60 * 60);
+        assert_eq!(
+            parse_retention_period("2 weeks").unwrap(),
+            14 * 24 * 60 * 60
+        );
+        assert!(parse_retention_period("0 days").is_err());
+        assert!(parse_retention_period("90").is_err());
+        assert!(parse_retention_period("90 fortnights").is_err());
+        assert!(parse_retention_period("-1 days").is_err());
+        assert!(parse_retention_period("6000 weeks").is_err());
+        assert!(parse_retention_period("18446744073709551615 seconds").is_err());
+
+        let retention = |period_secs| RetentionPolicy {
+            column: "ts".to_string(),
+            period_secs,
+        };
+        let now = Utc::now();
+        assert_eq!(
+            retention(60).cutoff(now),
+            Some(now - chrono::Duration::seconds(60))
+        );
+        assert!(retention(RetentionPolicy::MAX_PERIOD_SECS)
+            .cutoff(now)
+            .is_some());
+        assert_eq!(retention(u64::MAX).cutoff(now), None);
+    }
+
     #[tokio::test]
     async fn check_memory_test() {
         Config::test("check_memory_test")
@@ -3776,6 +4954,57 @@ mod tests {
         }).await;
     }
 
+    #[tokio::test]
+    async fn table_parquet_options() {
+        Config::test("table_parquet_options").update_config(|mut config| {
+            config.compaction_chunks_count_threshold = 0;
+            config
+        }).start_test(async move |services| {
+            let service = services.sql_service;
+
+            service.exec_query("CREATE SCHEMA foo").await.unwrap();
+
+            let res = service.exec_query(
+                "CREATE TABLE foo.bad (id int, amount float) WITH (bloom_filter_columns = 'amount')"
+            ).await;
+            assert!(res.is_err(), "{:?}", res);
+
+            service.exec_query(
+                "CREATE TABLE foo.orders (id int, city text) WITH (compression = 'zstd', dictionary = false, bloom_filter_columns = 'id, city')"
+            ).await.unwrap();
+
+            let listener = services.cluster.job_result_listener();
+
+            service.exec_query(
+                "INSERT INTO foo.orders (id, city) VALUES (1, 'San Francisco'), (2, 'New York'), (3, 'New York')"
+            ).await.unwrap();
+
+            let wait = listener.wait_for_job_results(vec![
+                (RowKey::Table(TableId::Partitions, 1), JobType::PartitionCompaction),
+            ]);
+            timeout(Duration::from_secs(10), wait).await.unwrap().unwrap();
+
+            let partitions = services.meta_store.get_active_partitions_by_index_id(1).await.unwrap();
+            assert_eq!(partitions.len(), 1);
+            assert!(partitions[0].get_row().has_bloom_filters());
+            let remote_fs = services.injector.get_service_typed::<dyn RemoteFs>().await;
+            let bloom_filter_file = partitions[0].get_row().bloom_filter_file_name(partitions[0].get_id()).unwrap();
+            let local_file = remote_fs.download_file(bloom_filter_file, None).await.unwrap();
+            let bloom_filters = RowGroupBloomFilters::read(&local_file).unwrap();
+            assert_eq!(bloom_filters.row_groups.len(), 1);
+            assert_eq!(
+                bloom_filters.row_groups[0].iter().map(|f| f.column.as_str()).collect::<Vec<_>>(),
+                vec!["id", "city"]
+            );
+
+            let result = service.exec_query("SELECT city FROM foo.orders WHERE id = 2").await.unwrap();
+            assert_eq!(result.get_rows(), &vec![Row::new(vec![TableValue::String("New York".to_string())])]);
+
+            let result = service.exec_query("SELECT count(*) FROM foo.orders WHERE city IN ('Chicago', 'Boston')").await.unwrap();
+            assert_eq!(result.get_rows(), &vec![Row::new(vec![TableValue::Int(0)])]);
+        }).await;
+    }
+
     #[test]
     fn create_table_with_temp_file() {
         tokio::runtime::Builder::new_multi_thread()
@@ -3828,6 +5057,44 @@ mod tests {
             )
     }
 
+    #[tokio::test]
+    async fn create_table_with_compressed_temp_files() {
+        Config::run_test("create_table_with_compressed_temp_files", async move |services| {
+            let service = services.sql_service;
+            let remote_fs = services.injector.get_service_typed::<dyn RemoteFs>().await;
+            let dir = env::temp_dir();
+
+            let content = "id,city\n1,San Francisco\n2,New York\n3,New York\n";
+
+            let zstd_path = dir.clone().join("foo-compressed.csv.zst");
+            let mut file = ZstdEncoder::new(BufWriter::new(tokio::fs::File::create(zstd_path.clone()).await.unwrap()));
+            file.write_all(content.as_bytes()).await.unwrap();
+            file.shutdown().await.unwrap();
+            remote_fs.upload_file(zstd_path.to_str().unwrap().to_string(), "temp-uploads/foo-compressed.csv.zst".to_string()).await.unwrap();
+
+            // No extension, compression is detected by magic bytes.
+            let bzip2_path = dir.clone().join("foo-compressed-bz");
+            let mut file = BzEncoder::new(BufWriter::new(tokio::fs::File::create(bzip2_path.clone()).await.unwrap()));
+            file.write_all(content.as_bytes()).await.unwrap();
+            file.shutdown().await.unwrap();
+            remote_fs.upload_file(bzip2_path.to_str().unwrap().to_string(), "temp-uploads/foo-compressed-bz".to_string()).await.unwrap();
+
+            let _ = service.exec_query("CREATE SCHEMA IF NOT EXISTS Foo").await.unwrap();
+            let _ = service.exec_query(
+                "CREATE TABLE Foo.Persons (id int, city text) LOCATION 'temp://foo-compressed.csv.zst', 'temp://foo-compressed-bz'"
+            ).await.unwrap();
+
+            let result = service.exec_query("SELECT city, count(*) as cnt from Foo.Persons GROUP BY 1 ORDER BY 1").await.unwrap();
+            assert_eq!(
+                result.get_rows(),
+                &vec![
+                    Row::new(vec![TableValue::String("New York".to_string()), TableValue::Int(4)]),
+                    Row::new(vec![TableValue::String("San Francisco".to_string()), TableValue::Int(2)]),
+                ]
+            );
+        }).await;
+    }
+
     #[tokio::test]
     async fn explain_logical_plan() {
         Config::run_test("explain_logical_plan", async move |services| {
@@ -29,6 +29,110 @@ impl Dialect for MySqlDialectWithBackTicks {
     }
 }
 
+/// Rewrites Postgres `expr -> 'key'` and `expr ->> 'key'` into `json_extract_path(expr, 'key')`
+/// and `json_extract_path_text(expr, 'key')`, which the SQL parser understands. The left operand
+/// must be a (possibly qualified) column, a function call or a parenthesized expression.
+fn rewrite_json_operators(tokens: Vec<Token>) -> Vec<Token> {
+    let mut out: Vec<Token> = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+    while i < tokens.len() {
+        let (function, operator_len) = match json_operator(&tokens[i..]) {
+            Some(op) => op,
+            None => {
+                out.push(tokens[i].clone());
+                i += 1;
+                continue;
+            }
+        };
+        let mut key = i + operator_len;
+        while let Some(Token::Whitespace(_)) = tokens.get(key) {
+            key += 1;
+        }
+        // json_extract_path only accepts text keys, array indexes are passed as strings too.
+        let key_token = match tokens.get(key) {
+            Some(t @ Token::SingleQuotedString(_)) => Some(t.clone()),
+            Some(Token::Number(n, _)) => Some(Token::SingleQuotedString(n.clone())),
+            _ => None,
+        };
+        match (key_token, json_operand_start(&out)) {
+            (Some(key_token), Some(start)) => {
+                let mut operand = out.split_off(start);
+                while let Some(Token::Whitespace(_)) = operand.last() {
+                    operand.pop();
+                }
+                out.push(Token::make_word(function, None));
+                out.push(Token::LParen);
+                out.extend(operand);
+                out.push(Token::Comma);
+                out.push(key_token);
+                out.push(Token::RParen);
+                i = key + 1;
+            }
+            _ => {
+                out.push(tokens[i].clone());
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Function for the `->` or `->>` operator at the start of `tokens` and the number of tokens it
+/// spans. The tokenizer doesn't know these operators and splits them into `-` and `>`s.
+fn json_operator(tokens: &[Token]) -> Option<(&'static str, usize)> {
+    let text = |t: &Token| t.to_string();
+    match tokens {
+        [t, ..] if text(t) == "->>" => Some(("json_extract_path_text", 1)),
+        [t, ..] if text(t) == "->" => Some(("json_extract_path", 1)),
+        [Token::Minus, t, ..] if text(t) == ">>" => Some(("json_extract_path_text", 2)),
+        [Token::Minus, a, b, ..] if text(a) == ">" && text(b) == ">" => {
+            Some(("json_extract_path_text", 3))
+        }
+        [Token::Minus, t, ..] if text(t) == ">" => Some(("json_extract_path", 2)),
+        _ => None,
+    }
+}
+
+/// Index of the first token of the operand that ends `tokens`, ignoring trailing whitespace.
+fn json_operand_start(tokens: &[Token]) -> Option<usize> {
+    let mut end = tokens.len();
+    while end > 0 && matches!(tokens[end - 1], Token::Whitespace(_)) {
+        end -= 1;
+    }
+    let mut start = end.checked_sub(1)?;
+    match &tokens[start] {
+        Token::RParen => {
+            let mut depth = 0;
+            loop {
+                match tokens[start] {
+                    Token::RParen => depth += 1,
+                    Token::LParen => depth -= 1,
+                    _ => {}
+                }
+                if depth == 0 {
+                    break;
+                }
+                start = start.checked_sub(1)?;
+            }
+            if let Some(Token::Word(w)) = start.checked_sub(1).map(|p| &tokens[p]) {
+                if w.keyword == Keyword::NoKeyword {
+                    start -= 1;
+                }
+            }
+        }
+        Token::Word(_) => {
+            while start >= 2
+                && tokens[start - 1] == Token::Period
+                && matches!(tokens[start - 2], Token::Word(_))
+            {
+                start -= 2;
+            }
+        }
+        _ => return None,
+    }
+    Some(start)
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct PartitionedIndexRef {
     pub name: ObjectName,
@@ -60,6 +164,18 @@ pub enum Statement {
     Queue(QueueCommand),
     System(SystemCommand),
     Dump(Box<Query>),
+    KillQuery {
+        query_id: u64,
+    },
+    Unload {
+        query: Box<Query>,
+        location: String,
+        options: Vec<SqlOption>,
+    },
+    CreateMaterializedView {
+        name: ObjectName,
+        query: Box<Query>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -89,6 +205,49 @@ pub enum CacheCommand {
     Incr {
         path: Ident,
     },
+    HSet {
+        key: Ident,
+        field: Ident,
+        value: String,
+        ttl: Option<u32>,
+    },
+    HGet {
+        key: Ident,
+        field: Ident,
+    },
+    HIncrBy {
+        key: Ident,
+        field: Ident,
+        increment: i64,
+        ttl: Option<u32>,
+    },
+    ZAdd {
+        key: Ident,
+        score: f64,
+        member: String,
+        ttl: Option<u32>,
+    },
+    ZRangeByScore {
+        key: Ident,
+        min: f64,
+        max: f64,
+        limit: Option<usize>,
+    },
+    ZRemRangeByScore {
+        key: Ident,
+        min: f64,
+        max: f64,
+    },
+    Publish {
+        channel: Ident,
+        message: String,
+    },
+    Subscribe {
+        channel: Ident,
+        timeout: u64,
+        /// Cursor of the last received message, messages published after it are returned
+        after: Option<u64>,
+    },
 }
 
 impl CacheCommand {
@@ -100,6 +259,14 @@ impl CacheCommand {
             CacheCommand::Remove { .. } => "remove",
             CacheCommand::Truncate { .. } => "truncate",
             CacheCommand::Incr { .. } => "incr",
+            CacheCommand::HSet { .. } => "hset",
+            CacheCommand::HGet { .. } => "hget",
+            CacheCommand::HIncrBy { .. } => "hincrby",
+            CacheCommand::ZAdd { .. } => "zadd",
+            CacheCommand::ZRangeByScore { .. } => "zrangebyscore",
+            CacheCommand::ZRemRangeByScore { .. } => "zremrangebyscore",
+            CacheCommand::Publish { .. } => "publish",
+            CacheCommand::Subscribe { .. } => "subscribe",
         }
     }
 }
@@ -109,6 +276,8 @@ pub enum QueueCommand {
     Add {
         priority: i64,
         orphaned: Option<u32>,
+        delay: Option<u32>,
+        max_attempts: Option<u32>,
         key: Ident,
         value: String,
     },
@@ -152,6 +321,9 @@ pub enum QueueCommand {
         key: QueueKey,
         timeout: u64,
     },
+    DeadLetterRequeue {
+        key: QueueKey,
+    },
     Truncate {},
 }
 
@@ -164,6 +336,7 @@ impl QueueCommand {
             QueueCommand::List { status_filter, .. } => match status_filter {
                 Some(QueueItemStatus::Active) => "active",
                 Some(QueueItemStatus::Pending) => "pending",
+                Some(QueueItemStatus::DeadLetter) => "dlq_list",
                 _ => "list",
             },
             QueueCommand::Cancel { .. } => "cancel",
@@ -173,6 +346,7 @@ impl QueueCommand {
             QueueCommand::Retrieve { .. } => "retrieve",
             QueueCommand::Result { .. } => "result",
             QueueCommand::ResultBlocking { .. } => "result_blocking",
+            QueueCommand::DeadLetterRequeue { .. } => "dlq_requeue",
             QueueCommand::Truncate { .. } => "truncate",
         }
     }
@@ -218,7 +392,7 @@ impl<'a> CubeStoreParser<'a> {
     pub fn new(sql: &str) -> Result<Self, ParserError> {
         let dialect = &MySqlDialectWithBackTicks {};
         let mut tokenizer = Tokenizer::new(dialect, sql);
-        let tokens = tokenizer.tokenize()?;
+        let tokens = rewrite_json_operators(tokenizer.tokenize()?);
         Ok(CubeStoreParser {
             parser: Parser::new(tokens, dialect),
         })
@@ -256,6 +430,21 @@ impl<'a> CubeStoreParser<'a> {
                     };
                     Ok(Statement::Dump(q))
                 }
+                _ if w.value.eq_ignore_ascii_case("kill") => {
+                    self.parser.next_token();
+                    if !self.parse_custom_token("query") {
+                        return Err(ParserError::ParserError(
+                            "Expected QUERY after 'kill'".to_string(),
+                        ));
+                    }
+                    Ok(Statement::KillQuery {
+                        query_id: self.parse_integer("query id", false)?,
+                    })
+                }
+                _ if w.value.eq_ignore_ascii_case("unload") => {
+                    self.parser.next_token();
+                    self.parse_unload()
+                }
                 _ => Ok(Statement::Statement(self.parser.parse_statement()?)),
             },
             _ => Ok(Statement::Statement(self.parser.parse_statement()?)),
@@ -283,6 +472,11 @@ impl<'a> CubeStoreParser<'a> {
             self.parse_create_schema()
         } else if self.parser.parse_keyword(Keyword::TABLE) {
             self.parse_create_table()
+        } else if self
+            .parser
+            .parse_keywords(&[Keyword::MATERIALIZED, Keyword::VIEW])
+        {
+            self.parse_create_materialized_view()
         } else if self.parser.consume_token(&Token::make_keyword("SOURCE"))
             || self.parser.consume_token(&Token::make_keyword("source"))
         {
@@ -323,11 +517,7 @@ impl<'a> CubeStoreParser<'a> {
         let command = match method.as_str() {
             "set" => {
                 let nx = self.parse_custom_token(&"nx");
-                let ttl = if self.parse_custom_token(&"ttl") {
-                    Some(self.parse_integer("ttl", false)?)
-                } else {
-                    None
-                };
+                let ttl = self.parse_cache_ttl()?;
 
                 CacheCommand::Set {
                     key: self.parser.parse_identifier()?,
@@ -349,9 +539,90 @@ impl<'a> CubeStoreParser<'a> {
                 key: self.parser.parse_identifier()?,
             },
             "truncate" => CacheCommand::Truncate {},
+            "hset" => {
+                let ttl = self.parse_cache_ttl()?;
+
+                CacheCommand::HSet {
+                    key: self.parser.parse_identifier()?,
+                    field: self.parser.parse_identifier()?,
+                    value: self.parser.parse_literal_string()?,
+                    ttl,
+                }
+            }
+            "hget" => CacheCommand::HGet {
+                key: self.parser.parse_identifier()?,
+                field: self.parser.parse_identifier()?,
+            },
+            "hincrby" => {
+                let ttl = self.parse_cache_ttl()?;
+
+                CacheCommand::HIncrBy {
+                    key: self.parser.parse_identifier()?,
+                    field: self.parser.parse_identifier()?,
+                    increment: self.parse_integer("increment", true)?,
+                    ttl,
+                }
+            }
+            "zadd" => {
+                let ttl = self.parse_cache_ttl()?;
+
+                CacheCommand::ZAdd {
+                    key: self.parser.parse_identifier()?,
+                    score: self.parse_score("score")?,
+                    member: self.parser.parse_literal_string()?,
+                    ttl,
+                }
+            }
+            "zrangebyscore" => {
+                let key = self.parser.parse_identifier()?;
+                let min = self.parse_score("min")?;
+                let max = self.parse_score("max")?;
+                let limit = if self.parser.parse_keyword(Keyword::LIMIT) {
+                    Some(self.parse_integer("limit", false)?)
+                } else {
+                    None
+                };
+
+                CacheCommand::ZRangeByScore {
+                    key,
+                    min,
+                    max,
+                    limit,
+                }
+            }
+            "zremrangebyscore" => CacheCommand::ZRemRangeByScore {
+                key: self.parser.parse_identifier()?,
+                min: self.parse_score("min")?,
+                max: self.parse_score("max")?,
+            },
+            "publish" => CacheCommand::Publish {
+                channel: self.parser.parse_identifier()?,
+                message: self.parser.parse_literal_string()?,
+            },
+            "subscribe" => {
+                let timeout = if self.parse_custom_token(&"timeout") {
+                    self.parse_integer(&"timeout", false)?
+                } else {
+                    return Err(ParserError::ParserError(
+                        "TIMEOUT is required for SUBSCRIBE".to_string(),
+                    ));
+                };
+
+                let after = if self.parse_custom_token(&"after") {
+                    Some(self.parse_integer(&"after", false)?)
+                } else {
+                    None
+                };
+
+                CacheCommand::Subscribe {
+                    channel: self.parser.parse_identifier()?,
+                    timeout,
+                    after,
+                }
+            }
             other => {
                 return Err(ParserError::ParserError(format!(
-                    "Unknown cache command: {}, available: SET|GET|KEYS|INC|REMOVE|TRUNCATE",
+                    "Unknown cache command: {}, available: SET|GET|KEYS|INC|REMOVE|TRUNCATE|HSET|HGET|HINCRBY|ZADD|ZRANGEBYSCORE|ZREMRANGEBYSCORE|PUBLISH|SUBSCRIBE",
                     other
                 )))
             }
@@ -407,6 +678,50 @@ impl<'a> CubeStoreParser<'a> {
         }
     }
 
+    /// Parses a score of sorted set: number with an optional sign or `inf` (`-inf`, `+inf`).
+    fn parse_cache_ttl(&mut self) -> Result<Option<u32>, ParserError> {
+        if self.parse_custom_token(&"ttl") {
+            Ok(Some(self.parse_integer("ttl", false)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn parse_score(&mut self, var_name: &str) -> Result<f64, ParserError> {
+        let is_negative = match self.parser.peek_token() {
+            Token::Minus => {
+                self.parser.next_token();
+                true
+            }
+            Token::Plus => {
+                self.parser.next_token();
+                false
+            }
+            _ => false,
+        };
+
+        let value = if self.parse_custom_token(&"inf") {
+            f64::INFINITY
+        } else {
+            match self.parser.parse_number_value()? {
+                Value::Number(var, false) => var.parse::<f64>().map_err(|err| {
+                    ParserError::ParserError(format!(
+                        "{} must be a valid number, error: {}",
+                        var_name, err
+                    ))
+                })?,
+                x => {
+                    return Err(ParserError::ParserError(format!(
+                        "{} must be a valid number, actual: {:?}",
+                        var_name, x
+                    )))
+                }
+            }
+        };
+
+        Ok(if is_negative { -value } else { value })
+    }
+
     pub fn parse_drop(&mut self) -> Result<Statement, ParserError> {
         if self.parse_custom_token("query") && self.parse_custom_token("cache") {
             Ok(Statement::System(SystemCommand::Drop(
@@ -484,9 +799,23 @@ impl<'a> CubeStoreParser<'a> {
                     None
                 };
 
+                let delay = if self.parse_custom_token(&"delay") {
+                    Some(self.parse_integer("delay", false)?)
+                } else {
+                    None
+                };
+
+                let max_attempts = if self.parse_custom_token(&"max_attempts") {
+                    Some(self.parse_integer("max_attempts", false)?)
+                } else {
+                    None
+                };
+
                 QueueCommand::Add {
                     priority,
                     orphaned,
+                    delay,
+                    max_attempts,
                     key: self.parser.parse_identifier()?,
                     value: self.parser.parse_literal_string()?,
                 }
@@ -598,6 +927,26 @@ impl<'a> CubeStoreParser<'a> {
                     key: self.parse_queue_key()?,
                 }
             }
+            "dlq" => {
+                if self.parse_custom_token(&"list") {
+                    let with_payload = self.parse_custom_token(&"with_payload");
+
+                    QueueCommand::List {
+                        prefix: self.parser.parse_identifier()?,
+                        with_payload,
+                        status_filter: Some(QueueItemStatus::DeadLetter),
+                        sort_by_priority: false,
+                    }
+                } else if self.parse_custom_token(&"requeue") {
+                    QueueCommand::DeadLetterRequeue {
+                        key: self.parse_queue_key()?,
+                    }
+                } else {
+                    return Err(ParserError::ParserError(
+                        "Unknown queue dlq command, expected LIST or REQUEUE".to_string(),
+                    ));
+                }
+            }
             "truncate" => QueueCommand::Truncate {},
             other => {
                 return Err(ParserError::ParserError(format!(
@@ -783,6 +1132,27 @@ impl<'a> CubeStoreParser<'a> {
         })
     }
 
+    fn parse_unload(&mut self) -> Result<Statement, ParserError> {
+        self.parser.expect_token(&Token::LParen)?;
+        let query = Box::new(self.parser.parse_query()?);
+        self.parser.expect_token(&Token::RParen)?;
+        self.parser.expect_keyword(Keyword::TO)?;
+        let location = self.parser.parse_literal_string()?;
+        let options = self.parser.parse_options(Keyword::WITH)?;
+        Ok(Statement::Unload {
+            query,
+            location,
+            options,
+        })
+    }
+
+    fn parse_create_materialized_view(&mut self) -> Result<Statement, ParserError> {
+        let name = self.parser.parse_object_name()?;
+        self.parser.expect_keyword(Keyword::AS)?;
+        let query = Box::new(self.parser.parse_query()?);
+        Ok(Statement::CreateMaterializedView { name, query })
+    }
+
     fn parse_create_schema(&mut self) -> Result<Statement, ParserError> {
         let if_not_exists =
             self.parser
@@ -889,4 +1259,157 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn parse_kill_query() {
+        let query = "KILL QUERY 42";
+        let mut parser = CubeStoreParser::new(&query).unwrap();
+        let res = parser.parse_statement().unwrap();
+        assert_eq!(res, Statement::KillQuery { query_id: 42 });
+
+        let query = "KILL 42";
+        let mut parser = CubeStoreParser::new(&query).unwrap();
+        assert!(parser.parse_statement().is_err());
+    }
+
+    #[test]
+    fn parse_unload() {
+        let query = "UNLOAD (SELECT a, SUM(b) FROM foo.bar GROUP BY 1) TO 's3://bucket/export' WITH (format = 'csv')";
+        let mut parser = CubeStoreParser::new(&query).unwrap();
+        let res = parser.parse_statement().unwrap();
+        match res {
+            Statement::Unload {
+                location, options, ..
+            } => {
+                assert_eq!(location, "s3://bucket/export");
+                assert_eq!(options.len(), 1);
+                assert_eq!(options[0].name.value, "format");
+            }
+            _ => panic!("Unexpected statement: {:?}", res),
+        }
+
+        let query = "UNLOAD (SELECT 1) TO 'file:///tmp/export'";
+        let mut parser = CubeStoreParser::new(&query).unwrap();
+        match parser.parse_statement().unwrap() {
+            Statement::Unload { options, .. } => assert!(options.is_empty()),
+            res => panic!("Unexpected statement: {:?}", res),
+        }
+
+        let query = "UNLOAD SELECT 1 TO 'file:///tmp/export'";
+        let mut parser = CubeStoreParser::new(&query).unwrap();
+        assert!(parser.parse_statement().is_err());
+    }
+
+    #[test]
+    fn parse_create_materialized_view() {
+        let query = "CREATE MATERIALIZED VIEW test.events_by_user AS SELECT user_id, COUNT(*) cnt FROM test.events GROUP BY 1";
+        let mut parser = CubeStoreParser::new(&query).unwrap();
+        let res = parser.parse_statement().unwrap();
+        match res {
+            Statement::CreateMaterializedView { name, query } => {
+                assert_eq!(name.to_string(), "test.events_by_user");
+                assert_eq!(
+                    query.to_string(),
+                    "SELECT user_id, COUNT(*) AS cnt FROM test.events GROUP BY 1"
+                );
+            }
+            _ => panic!("Unexpected statement: {:?}", res),
+        }
+
+        let query = "CREATE MATERIALIZED VIEW test.events_by_user SELECT 1";
+        let mut parser = CubeStoreParser::new(&query).unwrap();
+        assert!(parser.parse_statement().is_err());
+    }
+
+    #[test]
+    fn parse_cache_sorted_set() {
+        let query = r#"CACHE ZRANGEBYSCORE "tenant:window" -inf 1.5 LIMIT 10"#;
+        let mut parser = CubeStoreParser::new(&query).unwrap();
+        let res = parser.parse_statement().unwrap();
+        match res {
+            Statement::Cache(CacheCommand::ZRangeByScore {
+                key,
+                min,
+                max,
+                limit,
+            }) => {
+                assert_eq!(key.value, "tenant:window");
+                assert_eq!(min, f64::NEG_INFINITY);
+                assert_eq!(max, 1.5);
+                assert_eq!(limit, Some(10));
+            }
+            _ => panic!("Unexpected statement: {:?}", res),
+        }
+
+        let query = r#"CACHE ZADD "tenant:window" -2 'member'"#;
+        let mut parser = CubeStoreParser::new(&query).unwrap();
+        match parser.parse_statement().unwrap() {
+            Statement::Cache(CacheCommand::ZAdd { score, member, .. }) => {
+                assert_eq!(score, -2.0);
+                assert_eq!(member, "member");
+            }
+            res => panic!("Unexpected statement: {:?}", res),
+        }
+
+        let query = r#"CACHE HSET TTL 60 "tenant:hash" "field" 'value'"#;
+        let mut parser = CubeStoreParser::new(&query).unwrap();
+        match parser.parse_statement().unwrap() {
+            Statement::Cache(CacheCommand::HSet { key, ttl, .. }) => {
+                assert_eq!(key.value, "tenant:hash");
+                assert_eq!(ttl, Some(60));
+            }
+            res => panic!("Unexpected statement: {:?}", res),
+        }
+
+        let query = r#"CACHE SUBSCRIBE TIMEOUT 100 AFTER 42 "channel""#;
+        let mut parser = CubeStoreParser::new(&query).unwrap();
+        match parser.parse_statement().unwrap() {
+            Statement::Cache(CacheCommand::Subscribe {
+                channel,
+                timeout,
+                after,
+            }) => {
+                assert_eq!(channel.value, "channel");
+                assert_eq!(timeout, 100);
+                assert_eq!(after, Some(42));
+            }
+            res => panic!("Unexpected statement: {:?}", res),
+        }
+
+        let query = r#"CACHE SUBSCRIBE "channel""#;
+        let mut parser = CubeStoreParser::new(&query).unwrap();
+        assert!(parser.parse_statement().is_err());
+    }
+
+    #[test]
+    fn parse_json_operators() {
+        let parse = |query: &str| {
+            CubeStoreParser::new(query)
+                .unwrap()
+                .parse_statement()
+                .unwrap()
+        };
+        let cases = vec![
+            (
+                "SELECT payload->'user'->>'name' FROM foo.events",
+                "SELECT json_extract_path_text(json_extract_path(payload, 'user'), 'name') FROM foo.events",
+            ),
+            (
+                "SELECT e.payload -> 'n' ->> 1 FROM foo.events e",
+                "SELECT json_extract_path_text(json_extract_path(e.payload, 'n'), '1') FROM foo.events e",
+            ),
+            (
+                "SELECT (coalesce(a, b))->>'x', lower(c)->'y' FROM t WHERE d->>'z' = 'w'",
+                "SELECT json_extract_path_text((coalesce(a, b)), 'x'), json_extract_path(lower(c), 'y') FROM t WHERE json_extract_path_text(d, 'z') = 'w'",
+            ),
+        ];
+        for (query, expected) in cases {
+            assert_eq!(parse(query), parse(expected), "{}", query);
+        }
+
+        assert_eq!(
+            parse("SELECT a - 1, b -2 FROM t"),
+            parse("SELECT a - 1, b - 2 FROM t")
+        );
+    }
 }
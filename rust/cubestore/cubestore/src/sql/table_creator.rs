@@ -6,7 +6,7 @@ use crate::config::injection::DIService;
 use crate::config::ConfigObj;
 use crate::import::ImportService;
 use crate::metastore::job::JobType;
-use crate::metastore::table::StreamOffset;
+use crate::metastore::table::{ParquetOptions, RetentionPolicy, StreamOffset};
 use crate::metastore::{
     table::Table, HllFlavour, IdRow, ImportFormat, IndexDef, IndexType, RowKey, TableId,
 };
@@ -96,6 +96,8 @@ impl TableCreator {
         unique_key: Option<Vec<Ident>>,
         aggregates: Option<Vec<(Ident, Ident)>>,
         partitioned_index: Option<PartitionedIndexRef>,
+        parquet_options: Option<ParquetOptions>,
+        retention: Option<RetentionPolicy>,
         trace_obj: &Option<String>,
     ) -> Result<IdRow<Table>, CubeError> {
         let extension: Option<serde_json::Value> =
@@ -119,6 +121,8 @@ impl TableCreator {
                     unique_key,
                     aggregates,
                     partitioned_index,
+                    parquet_options,
+                    retention,
                     &trace_obj,
                     &extension,
                 )
@@ -156,6 +160,8 @@ impl TableCreator {
                     unique_key,
                     aggregates,
                     partitioned_index,
+                    parquet_options,
+                    retention,
                     &trace_obj,
                     &extension,
                 )
@@ -182,6 +188,8 @@ impl TableCreator {
         unique_key: Option<Vec<Ident>>,
         aggregates: Option<Vec<(Ident, Ident)>>,
         partitioned_index: Option<PartitionedIndexRef>,
+        parquet_options: Option<ParquetOptions>,
+        retention: Option<RetentionPolicy>,
         trace_obj: &Option<String>,
         extension: &Option<serde_json::Value>,
     ) -> Result<IdRow<Table>, CubeError> {
@@ -211,6 +219,8 @@ impl TableCreator {
                     unique_key.clone(),
                     aggregates.clone(),
                     partitioned_index.clone(),
+                    parquet_options.clone(),
+                    retention.clone(),
                     trace_obj,
                     extension,
                 )
@@ -284,10 +294,18 @@ impl TableCreator {
         unique_key: Option<Vec<Ident>>,
         aggregates: Option<Vec<(Ident, Ident)>>,
         partitioned_index: Option<PartitionedIndexRef>,
+        parquet_options: Option<ParquetOptions>,
+        retention: Option<RetentionPolicy>,
         trace_obj: &Option<String>,
         extension: &Option<serde_json::Value>,
     ) -> Result<IdRow<Table>, CubeError> {
-        let columns_to_set = convert_columns_type(columns)?;
+        let columns_to_set = convert_columns_type(columns, self.config_obj.native_column_types())?;
+        if let Some(parquet_options) = parquet_options.as_ref() {
+            validate_bloom_filter_columns(&parquet_options.bloom_filter_columns, &columns_to_set)?;
+        }
+        if let Some(retention) = retention.as_ref() {
+            validate_retention_column(&retention.column, &columns_to_set)?;
+        }
         let mut indexes_to_create = Vec::new();
         if let Some(mut p) = partitioned_index {
             let part_index_name = match p.name.0.as_mut_slice() {
@@ -405,10 +423,18 @@ impl TableCreator {
                     None,
                     false,
                     extension.as_ref().map(|json_value| json_value.to_string()),
+                    parquet_options,
+                    retention,
+                    None,
                 )
                 .await;
         }
 
+        let locations = match locations {
+            Some(locations) => Some(self.import_service.expand_locations(locations).await?),
+            None => None,
+        };
+
         if let Some(locations) = locations.as_ref() {
             self.import_service
                 .validate_locations_size(locations)
@@ -454,7 +480,7 @@ impl TableCreator {
             let cols = parser
                 .parse_streaming_source_table()
                 .map_err(|e| CubeError::user(format!("Unexpected source_table param: {}", e)))?;
-            let res = convert_columns_type(&cols)
+            let res = convert_columns_type(&cols, self.config_obj.native_column_types())
                 .map_err(|e| CubeError::user(format!("Unexpected source_table param: {}", e)))?;
             Some(res)
         } else {
@@ -486,6 +512,9 @@ impl TableCreator {
                 trace_obj_to_save,
                 if_not_exists,
                 extension.as_ref().map(|json_value| json_value.to_string()),
+                parquet_options,
+                retention,
+                None,
             )
             .await?;
 
@@ -558,82 +587,142 @@ impl TableCreator {
     }
 }
 
-pub fn convert_columns_type(columns: &Vec<ColumnDef>) -> Result<Vec<Column>, CubeError> {
+/// Without `native_column_types` date, uuid and array columns keep their legacy text and bytes
+/// representation so existing pre-aggregation schemas don't change their storage on upgrade.
+pub fn convert_columns_type(
+    columns: &Vec<ColumnDef>,
+    native_column_types: bool,
+) -> Result<Vec<Column>, CubeError> {
     let mut rolupdb_columns = Vec::new();
 
     for (i, col) in columns.iter().enumerate() {
         let cube_col = Column::new(
             col.name.value.clone(),
-            match &col.data_type {
-                DataType::Date
-                | DataType::Time
-                | DataType::Char(_)
-                | DataType::Varchar(_)
-                | DataType::Clob(_)
-                | DataType::Text
-                | DataType::String => ColumnType::String,
-                DataType::Uuid
-                | DataType::Binary(_)
-                | DataType::Varbinary(_)
-                | DataType::Blob(_)
-                | DataType::Bytea
-                | DataType::Array(_) => ColumnType::Bytes,
-                DataType::Decimal(precision, scale) => {
-                    let (precision, scale) = proper_decimal_args(precision, scale);
-                    if precision > 18 {
-                        ColumnType::Decimal96 {
-                            precision: precision as i32,
-                            scale: scale as i32,
-                        }
-                    } else {
-                        ColumnType::Decimal {
-                            precision: precision as i32,
-                            scale: scale as i32,
-                        }
-                    }
-                }
-                DataType::SmallInt | DataType::Int | DataType::BigInt | DataType::Interval => {
-                    ColumnType::Int
-                }
-                DataType::Boolean => ColumnType::Boolean,
-                DataType::Float(_) | DataType::Real | DataType::Double => ColumnType::Float,
-                DataType::Timestamp => ColumnType::Timestamp,
-                DataType::Custom(custom) => {
-                    let custom_type_name = custom.to_string().to_lowercase();
-                    match custom_type_name.as_str() {
-                        "tinyint" | "mediumint" => ColumnType::Int,
-                        "decimal96" => ColumnType::Decimal96 {
-                            scale: 5,
-                            precision: 27,
-                        },
-                        "int96" => ColumnType::Int96,
-                        "bytes" => ColumnType::Bytes,
-                        "varbinary" => ColumnType::Bytes,
-                        "hyperloglog" => ColumnType::HyperLogLog(HllFlavour::Airlift),
-                        "hyperloglogpp" => ColumnType::HyperLogLog(HllFlavour::ZetaSketch),
-                        "hll_snowflake" => ColumnType::HyperLogLog(HllFlavour::Snowflake),
-                        "hll_postgres" => ColumnType::HyperLogLog(HllFlavour::Postgres),
-                        "hll_datasketches" => ColumnType::HyperLogLog(HllFlavour::DataSketches),
-                        _ => {
-                            return Err(CubeError::user(format!(
-                                "Custom type '{}' is not supported",
-                                custom
-                            )))
-                        }
-                    }
-                }
-                DataType::Regclass => {
-                    return Err(CubeError::user(
-                        "Type 'RegClass' is not suppored.".to_string(),
-                    ));
-                }
-            },
+            convert_data_type(&col.data_type, native_column_types)?,
             i,
         );
         rolupdb_columns.push(cube_col);
     }
     Ok(rolupdb_columns)
 }
+
+fn convert_data_type(data_type: &DataType, native_types: bool) -> Result<ColumnType, CubeError> {
+    Ok(match data_type {
+        DataType::Date if native_types => ColumnType::Date,
+        DataType::Uuid if native_types => ColumnType::Uuid,
+        DataType::Array(element_type) if native_types => {
+            ColumnType::array_of(convert_data_type(element_type, native_types)?)?
+        }
+        DataType::Date => ColumnType::String,
+        DataType::Uuid | DataType::Array(_) => ColumnType::Bytes,
+        DataType::Time
+        | DataType::Char(_)
+        | DataType::Varchar(_)
+        | DataType::Clob(_)
+        | DataType::Text
+        | DataType::String => ColumnType::String,
+        DataType::Binary(_) | DataType::Varbinary(_) | DataType::Blob(_) | DataType::Bytea => {
+            ColumnType::Bytes
+        }
+        DataType::Decimal(precision, scale) => {
+            let (precision, scale) = proper_decimal_args(precision, scale);
+            if precision > 18 {
+                ColumnType::Decimal96 {
+                    precision: precision as i32,
+                    scale: scale as i32,
+                }
+            } else {
+                ColumnType::Decimal {
+                    precision: precision as i32,
+                    scale: scale as i32,
+                }
+            }
+        }
+        DataType::SmallInt | DataType::Int | DataType::BigInt | DataType::Interval => {
+            ColumnType::Int
+        }
+        DataType::Boolean => ColumnType::Boolean,
+        DataType::Float(_) | DataType::Real | DataType::Double => ColumnType::Float,
+        DataType::Timestamp => ColumnType::Timestamp,
+        DataType::Custom(custom) => {
+            let custom_type_name = custom.to_string().to_lowercase();
+            match custom_type_name.as_str() {
+                "tinyint" | "mediumint" => ColumnType::Int,
+                "decimal96" => ColumnType::Decimal96 {
+                    scale: 5,
+                    precision: 27,
+                },
+                "int96" => ColumnType::Int96,
+                "bytes" => ColumnType::Bytes,
+                "varbinary" => ColumnType::Bytes,
+                "hyperloglog" => ColumnType::HyperLogLog(HllFlavour::Airlift),
+                "hyperloglogpp" => ColumnType::HyperLogLog(HllFlavour::ZetaSketch),
+                "hll_snowflake" => ColumnType::HyperLogLog(HllFlavour::Snowflake),
+                "hll_postgres" => ColumnType::HyperLogLog(HllFlavour::Postgres),
+                "hll_datasketches" => ColumnType::HyperLogLog(HllFlavour::DataSketches),
+                "json" | "jsonb" => ColumnType::Json,
+                _ => {
+                    return Err(CubeError::user(format!(
+                        "Custom type '{}' is not supported",
+                        custom
+                    )))
+                }
+            }
+        }
+        DataType::Regclass => {
+            return Err(CubeError::user(
+                "Type 'RegClass' is not suppored.".to_string(),
+            ));
+        }
+    })
+}
+
+fn validate_bloom_filter_columns(
+    bloom_filter_columns: &Vec<String>,
+    columns: &Vec<Column>,
+) -> Result<(), CubeError> {
+    for name in bloom_filter_columns {
+        let column = columns
+            .iter()
+            .find(|c| c.get_name() == name)
+            .ok_or_else(|| {
+                CubeError::user(format!(
+                    "Bloom filter column {} not found among column definitions",
+                    name
+                ))
+            })?;
+        match column.get_column_type() {
+            ColumnType::Int | ColumnType::String => {}
+            t => {
+                return Err(CubeError::user(format!(
+                    "Bloom filters are supported only for int and text columns, but {} is {}",
+                    name, t
+                )))
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_retention_column(name: &String, columns: &Vec<Column>) -> Result<(), CubeError> {
+    let column = columns
+        .iter()
+        .find(|c| c.get_name() == name)
+        .ok_or_else(|| {
+            CubeError::user(format!(
+                "Retention column {} not found among column definitions",
+                name
+            ))
+        })?;
+    match column.get_column_type() {
+        ColumnType::Timestamp => Ok(()),
+        t => Err(CubeError::user(format!(
+            "Retention column must be a timestamp, but {} is {}",
+            name, t
+        ))),
+    }
+}
+
 fn proper_decimal_args(precision: &Option<u64>, scale: &Option<u64>) -> (i32, i32) {
     let mut precision = precision.unwrap_or(18);
     let mut scale = scale.unwrap_or(5);
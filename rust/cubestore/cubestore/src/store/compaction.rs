@@ -4,7 +4,7 @@ use crate::metastore::chunks::chunk_file_name;
 use crate::metastore::multi_index::MultiPartition;
 use crate::metastore::partition::partition_file_name;
 use crate::metastore::replay_handle::{union_seq_pointer_by_location, SeqPointerForLocation};
-use crate::metastore::table::AggregateColumn;
+use crate::metastore::table::{AggregateColumn, RetentionPolicy};
 use crate::metastore::{
     deactivate_table_on_corrupt_data, table::Table, Chunk, IdRow, Index, IndexType, MetaStore,
     Partition, PartitionData,
@@ -12,23 +12,29 @@ use crate::metastore::{
 use crate::queryplanner::trace_data_loaded::{DataLoadedSize, TraceDataLoadedExec};
 use crate::remotefs::{ensure_temp_file_is_dropped, RemoteFs};
 use crate::store::{min_max_values_from_data, ChunkDataStore, ChunkStore, ROW_GROUP_SIZE};
+use crate::table::bloom_filter::{bloom_filter_file_name, BloomFilterBuilder};
 use crate::table::data::{cmp_min_rows, cmp_partition_key};
-use crate::table::parquet::{arrow_schema, CubestoreMetadataCacheFactory, ParquetTableStore};
+use crate::table::parquet::{
+    arrow_schema, row_group_sizes, CubestoreMetadataCacheFactory, ParquetTableStore,
+};
 use crate::table::redistribute::redistribute;
+use crate::table::statistics::{ColumnStatistics, ColumnStatisticsBuilder};
 use crate::table::{Row, TableValue};
 use crate::util::batch_memory::record_batch_buffer_size;
 use crate::CubeError;
 use async_trait::async_trait;
 use chrono::Utc;
-use datafusion::arrow::array::{ArrayRef, UInt64Array};
+use datafusion::arrow::array::{new_empty_array, ArrayRef, UInt64Array};
 use datafusion::arrow::compute::{lexsort_to_indices, SortColumn, SortOptions};
-use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::datatypes::{DataType, Schema as ArrowSchema};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::cube_ext;
+use datafusion::logical_plan::Operator;
 use datafusion::parquet::arrow::ArrowWriter;
 use datafusion::physical_plan::common::collect;
 use datafusion::physical_plan::empty::EmptyExec;
-use datafusion::physical_plan::expressions::{Column, Count, Literal};
+use datafusion::physical_plan::expressions::{BinaryExpr, Column, Count, IsNullExpr, Literal};
+use datafusion::physical_plan::filter::FilterExec;
 use datafusion::physical_plan::hash_aggregate::{
     AggregateMode, AggregateStrategy, HashAggregateExec,
 };
@@ -46,7 +52,7 @@ use itertools::{EitherOrBoth, Itertools};
 use num::integer::div_ceil;
 use std::cmp::Ordering;
 use std::fs::File;
-use std::mem::take;
+use std::mem::{replace, take};
 use std::ops::DerefMut;
 use std::sync::{Arc, Mutex};
 use tokio::task::JoinHandle;
@@ -281,6 +287,7 @@ impl CompactionServiceImpl {
                 in_memory_columns,
                 unique_key.clone(),
                 aggregate_columns.clone(),
+                None,
             )
             .await?;
             let batches = collect(batches_stream).await?;
@@ -299,9 +306,17 @@ impl CompactionServiceImpl {
                 .min();
 
             let (min, max) = min_max_values_from_data(batch.columns(), key_size);
+            let column_statistics = ColumnStatistics::from_data(batch.columns());
             let chunk = self
                 .meta_store
-                .create_chunk(partition.get_id(), batch.num_rows(), min, max, true)
+                .create_chunk(
+                    partition.get_id(),
+                    batch.num_rows(),
+                    min,
+                    max,
+                    true,
+                    column_statistics,
+                )
                 .await?;
 
             self.meta_store
@@ -372,6 +387,7 @@ impl CompactionServiceImpl {
             in_memory_columns,
             unique_key.clone(),
             aggregate_columns.clone(),
+            None,
         )
         .await?;
 
@@ -470,7 +486,14 @@ impl CompactionService for CompactionServiceImpl {
             .map(|c| c.clone())
             .collect::<Vec<_>>();
 
-        if chunks.is_empty() {
+        if chunks.is_empty()
+            && (multi_part.is_some()
+                || !main_table_has_expired_rows(
+                    table.get_row(),
+                    index.get_row(),
+                    partition.get_row(),
+                ))
+        {
             return Ok(());
         }
 
@@ -528,7 +551,14 @@ impl CompactionService for CompactionServiceImpl {
                 //We don't track min/max chunk values for multi-parititons
                 Some(
                     self.meta_store
-                        .create_chunk(partition_id, chunks_row_count as usize, None, None, false)
+                        .create_chunk(
+                            partition_id,
+                            chunks_row_count as usize,
+                            None,
+                            None,
+                            false,
+                            None,
+                        )
                         .await?,
                 )
             }
@@ -609,18 +639,25 @@ impl CompactionService for CompactionServiceImpl {
 
         let new_local_files = scopeguard::guard(new_local_files, |files| {
             for f in files {
+                ensure_temp_file_is_dropped(bloom_filter_file_name(&f));
                 ensure_temp_file_is_dropped(f);
             }
         });
 
         let key_size = index.get_row().sort_key_size() as usize;
+        let schema = Arc::new(arrow_schema(index.get_row()));
+        let data_schema = schema.clone();
         let (store, new) = cube_ext::spawn_blocking(move || -> Result<_, CubeError> {
-            // Concat rows from all chunks.
+            // Concat rows from all chunks. There are none if only expired rows are purged.
             let mut columns = Vec::with_capacity(num_columns);
             for i in 0..num_columns {
-                let v = datafusion::arrow::compute::concat(
-                    &data.iter().map(|a| a.column(i).as_ref()).collect_vec(),
-                )?;
+                let v = if data.is_empty() {
+                    new_empty_array(data_schema.field(i).data_type())
+                } else {
+                    datafusion::arrow::compute::concat(
+                        &data.iter().map(|a| a.column(i).as_ref()).collect_vec(),
+                    )?
+                };
                 columns.push(v);
             }
             // Sort rows from all chunks.
@@ -648,7 +685,6 @@ impl CompactionService for CompactionServiceImpl {
         .await??;
 
         // Merge and write rows.
-        let schema = Arc::new(arrow_schema(index.get_row()));
         let main_table: Arc<dyn ExecutionPlan> = match old_partition_local {
             Some(file) => {
                 let parquet_exec = Arc::new(ParquetExec::try_from_path_with_cache(
@@ -680,9 +716,17 @@ impl CompactionService for CompactionServiceImpl {
             IndexType::Regular => None,
             IndexType::Aggregate => Some(table.get_row().aggregate_columns()),
         };
-        let records =
-            merge_chunks(key_size, main_table, new, unique_key, aggregate_columns).await?;
-        let count_and_min = write_to_files(
+        let filter = retention_filter(table.get_row(), &schema);
+        let records = merge_chunks(
+            key_size,
+            main_table,
+            new,
+            unique_key,
+            aggregate_columns,
+            filter,
+        )
+        .await?;
+        let (count_and_min, has_bloom_filters, column_statistics) = write_to_files(
             records,
             total_rows as usize,
             store,
@@ -721,8 +765,23 @@ impl CompactionService for CompactionServiceImpl {
             .enumerate()
         {
             match p {
+                // All rows were purged by the retention policy. The first partition stays active
+                // without a main table file to keep the key range covered.
+                EitherOrBoth::Left(p) if i == 0 && count_and_min.is_empty() => {
+                    filtered_partitions.push((p, 0));
+                    let _ = tokio::fs::remove_file(&new_local_files[i]).await;
+                }
                 EitherOrBoth::Both(p, _) => {
                     let new_remote_path = partition_file_name(p.get_id(), p.get_row().suffix());
+                    // The side file goes first so it's there once the partition is activated.
+                    if has_bloom_filters[i] {
+                        self.remote_fs
+                            .upload_file(
+                                bloom_filter_file_name(&new_local_files[i]),
+                                bloom_filter_file_name(&new_remote_path),
+                            )
+                            .await?;
+                    }
                     let file_size = self
                         .remote_fs
                         .upload_file(new_local_files[i].clone(), new_remote_path.to_string())
@@ -747,6 +806,18 @@ impl CompactionService for CompactionServiceImpl {
 
         let partition_min = partition.get_row().get_min_val().clone();
         let partition_max = partition.get_row().get_max_val().clone();
+        if count_and_min.is_empty() {
+            self.meta_store
+                .swap_active_partitions(
+                    vec![(partition, chunks)],
+                    filtered_partitions,
+                    vec![(0, (partition_min, partition_max), (None, None))],
+                    vec![false],
+                    vec![None],
+                )
+                .await?;
+            return Ok(());
+        }
         self.meta_store
             .swap_active_partitions(
                 vec![(partition, chunks)],
@@ -807,6 +878,8 @@ impl CompactionService for CompactionServiceImpl {
                         }
                     })
                     .collect::<Result<Vec<_>, CubeError>>()?,
+                has_bloom_filters,
+                column_statistics,
             )
             .await?;
 
@@ -1123,7 +1196,14 @@ pub(crate) async fn write_to_files(
     store: ParquetTableStore,
     table: &IdRow<Table>,
     files: Vec<String>,
-) -> Result<Vec<(usize, Vec<TableValue>, Vec<TableValue>)>, CubeError> {
+) -> Result<
+    (
+        Vec<(usize, Vec<TableValue>, Vec<TableValue>)>,
+        Vec<bool>,
+        Vec<Option<ColumnStatistics>>,
+    ),
+    CubeError,
+> {
     let rows_per_file = div_ceil(num_rows as usize, files.len());
     let key_size = store.key_size() as usize;
     let partition_split_key_size = store.partition_split_key_size() as usize;
@@ -1179,13 +1259,24 @@ pub(crate) async fn write_to_files(
         };
     };
 
-    write_to_files_impl(records, store, files, table, pick_writer).await?;
+    let bloom_filter_builder = store.bloom_filter_builder(table);
+    let (mut has_bloom_filters, mut column_statistics) = write_to_files_impl(
+        records,
+        store,
+        files,
+        table,
+        bloom_filter_builder,
+        pick_writer,
+    )
+    .await?;
 
     let mut stats = take(stats.lock().unwrap().deref_mut());
     if stats.last().unwrap().0 == 0 {
         stats.pop();
     }
-    Ok(stats)
+    has_bloom_filters.truncate(stats.len());
+    column_statistics.truncate(stats.len());
+    Ok((stats, has_bloom_filters, column_statistics))
 }
 
 enum WriteBatchTo {
@@ -1200,10 +1291,24 @@ async fn write_to_files_impl(
     store: ParquetTableStore,
     files: Vec<String>,
     table: &IdRow<Table>,
+    bloom_filter_builder: BloomFilterBuilder,
     mut pick_writer: impl FnMut(&RecordBatch) -> WriteBatchTo,
-) -> Result<(), CubeError> {
+) -> Result<(Vec<bool>, Vec<Option<ColumnStatistics>>), CubeError> {
     let schema = Arc::new(store.arrow_schema());
+    let num_columns = schema.fields().len();
     let writer_props = store.writer_props(table).await?;
+    // Bloom filters of each file are written into a side file next to it once it's closed, as
+    // only then row groups of the file are known.
+    let finish_bloom_filters = |file: &str, builder: BloomFilterBuilder| -> Result<_, CubeError> {
+        if builder.is_empty() {
+            return Ok(false);
+        }
+        builder
+            .finish(&row_group_sizes(file)?)?
+            .write(&bloom_filter_file_name(file))?;
+        Ok(true)
+    };
+    let file_names = files.clone();
     let mut writers = files.into_iter().map(move |f| -> Result<_, CubeError> {
         Ok(ArrowWriter::try_new(
             File::create(f)?,
@@ -1215,21 +1320,43 @@ async fn write_to_files_impl(
     let (write_tx, mut write_rx) = tokio::sync::mpsc::channel(1);
     let io_job = cube_ext::spawn_blocking(move || -> Result<_, CubeError> {
         let mut writer = writers.next().transpose()?.unwrap();
+        let mut builder = bloom_filter_builder.clone();
+        let mut has_bloom_filters = Vec::new();
+        let mut statistics_builder = ColumnStatisticsBuilder::new(num_columns);
+        let mut column_statistics = Vec::new();
         let mut current_writer_i = 0;
         while let Some((writer_i, batch)) = write_rx.blocking_recv() {
             debug_assert!(current_writer_i <= writer_i);
             if current_writer_i != writer_i {
                 writer.close()?;
+                has_bloom_filters.push(finish_bloom_filters(
+                    &file_names[current_writer_i],
+                    replace(&mut builder, bloom_filter_builder.clone()),
+                )?);
+                column_statistics.push(
+                    replace(
+                        &mut statistics_builder,
+                        ColumnStatisticsBuilder::new(num_columns),
+                    )
+                    .finish(),
+                );
 
                 writer = writers.next().transpose()?.unwrap();
                 current_writer_i = writer_i;
             }
 
             writer.write(&batch)?;
+            builder.add_batch(batch.columns())?;
+            statistics_builder.add_batch(batch.columns());
         }
 
         writer.close()?;
-        Ok(())
+        has_bloom_filters.push(finish_bloom_filters(
+            &file_names[current_writer_i],
+            builder,
+        )?);
+        column_statistics.push(statistics_builder.finish());
+        Ok((has_bloom_filters, column_statistics))
     });
 
     let mut writer_i = 0;
@@ -1260,10 +1387,10 @@ async fn write_to_files_impl(
     .await;
 
     // We want to report IO errors first, `err` will be unhelpful ("channel closed") when IO fails.
-    io_job.await??;
+    let file_statistics = io_job.await??;
     err?;
 
-    Ok(())
+    Ok(file_statistics)
 }
 
 async fn write_to_files_by_keys(
@@ -1313,7 +1440,16 @@ async fn write_to_files_by_keys(
         panic!("impossible")
     };
     let num_files = files.len();
-    write_to_files_impl(records, store, files, table, pick_writer).await?;
+    // Partitions written by multi-splits get bloom filters once they are compacted.
+    write_to_files_impl(
+        records,
+        store,
+        files,
+        table,
+        BloomFilterBuilder::new(Vec::new()),
+        pick_writer,
+    )
+    .await?;
 
     let mut row_counts: Vec<usize> = take(row_counts.lock().unwrap().as_mut());
     assert!(
@@ -1333,6 +1469,7 @@ pub async fn merge_chunks(
     r: Vec<ArrayRef>,
     unique_key_columns: Option<Vec<&crate::metastore::Column>>,
     aggregate_columns: Option<Vec<AggregateColumn>>,
+    filter: Option<Arc<dyn PhysicalExpr>>,
 ) -> Result<SendableRecordBatchStream, CubeError> {
     let schema = l.schema();
     let r = RecordBatch::try_new(schema.clone(), r)?;
@@ -1388,9 +1525,61 @@ pub async fn merge_chunks(
         )?);
     }
 
+    if let Some(filter) = filter {
+        res = Arc::new(FilterExec::try_new(filter, res)?);
+    }
+
     Ok(res.execute(0).await?)
 }
 
+/// Partitions with expired rows are rewritten by compaction even if they don't have new chunks.
+fn main_table_has_expired_rows(table: &Table, index: &Index, partition: &Partition) -> bool {
+    let retention = match table.retention() {
+        Some(retention) => retention,
+        None => return false,
+    };
+    let cutoff = match retention.cutoff(Utc::now()) {
+        Some(cutoff) => cutoff.timestamp_nanos(),
+        None => return false,
+    };
+    match index
+        .columns()
+        .iter()
+        .position(|c| c.get_name() == &retention.column)
+    {
+        Some(column) => {
+            partition.main_table_row_count() > 0
+                && RetentionPolicy::some_expired(
+                    partition.get_column_statistics(),
+                    partition.get_min(),
+                    column,
+                    cutoff,
+                )
+        }
+        None => false,
+    }
+}
+
+/// Keeps rows that are not expired by the retention policy of the table. Null values of the
+/// retention column never expire.
+fn retention_filter(table: &Table, schema: &ArrowSchema) -> Option<Arc<dyn PhysicalExpr>> {
+    let retention = table.retention().as_ref()?;
+    let column_index = schema.index_of(&retention.column).ok()?;
+    let column: Arc<dyn PhysicalExpr> = Arc::new(Column::new(&retention.column, column_index));
+    let cutoff = retention.cutoff(Utc::now())?.timestamp_nanos() / 1000;
+    Some(Arc::new(BinaryExpr::new(
+        Arc::new(IsNullExpr::new(column.clone())),
+        Operator::Or,
+        Arc::new(BinaryExpr::new(
+            column,
+            Operator::GtEq,
+            Arc::new(Literal::new(ScalarValue::TimestampMicrosecond(Some(
+                cutoff,
+            )))),
+        )),
+    )))
+}
+
 pub async fn merge_replay_handles(
     meta_store: Arc<dyn MetaStore>,
     chunks: &Vec<IdRow<Chunk>>,
@@ -1476,23 +1665,26 @@ mod tests {
                 None,
                 false,
                 None,
+                None,
+                None,
+                None,
             )
             .await
             .unwrap();
         metastore.get_default_index(1).await.unwrap();
         let partition = metastore.get_partition(1).await.unwrap();
         metastore
-            .create_chunk(partition.get_id(), 10, None, None, false)
+            .create_chunk(partition.get_id(), 10, None, None, false, None)
             .await
             .unwrap();
         metastore.chunk_uploaded(1).await.unwrap();
         metastore
-            .create_chunk(partition.get_id(), 16, None, None, false)
+            .create_chunk(partition.get_id(), 16, None, None, false, None)
             .await
             .unwrap();
         metastore.chunk_uploaded(2).await.unwrap();
         metastore
-            .create_chunk(partition.get_id(), 20, None, None, false)
+            .create_chunk(partition.get_id(), 20, None, None, false, None)
             .await
             .unwrap();
         metastore.chunk_uploaded(3).await.unwrap();
@@ -1627,7 +1819,7 @@ mod tests {
             .unwrap()
             .get_id();
         metastore
-            .create_chunk(next_partition_id, 2, None, None, false)
+            .create_chunk(next_partition_id, 2, None, None, false, None)
             .await
             .unwrap();
         metastore.chunk_uploaded(4).await.unwrap();
@@ -1725,6 +1917,9 @@ mod tests {
                 None,
                 false,
                 None,
+                None,
+                None,
+                None,
             )
             .await
             .unwrap();
@@ -1747,11 +1942,11 @@ mod tests {
         let batch = RecordBatch::try_new(schema.clone(), data).unwrap();
         let batch2 = RecordBatch::try_new(schema.clone(), data2).unwrap();
         let chunk_first = metastore
-            .create_chunk(partition.get_id(), 5, None, None, true)
+            .create_chunk(partition.get_id(), 5, None, None, true, None)
             .await
             .unwrap();
         let chunk_second = metastore
-            .create_chunk(partition.get_id(), 4, None, None, true)
+            .create_chunk(partition.get_id(), 4, None, None, true, None)
             .await
             .unwrap();
 
@@ -1913,6 +2108,9 @@ mod tests {
                 None,
                 false,
                 None,
+                None,
+                None,
+                None,
             )
             .await
             .unwrap();
@@ -2202,6 +2400,108 @@ mod tests {
             })
             .await;
     }
+
+    #[tokio::test]
+    async fn partition_compaction_retention() {
+        Config::test("partition_compaction_retention")
+            .start_test(async move |services| {
+                let service = services.sql_service;
+                let _ = service.exec_query("CREATE SCHEMA test").await.unwrap();
+                let compaction_service = services
+                    .injector
+                    .get_service_typed::<dyn CompactionService>()
+                    .await;
+                service
+                    .exec_query("CREATE TABLE test.events (id int, ts timestamp) WITH (retention_column = 'ts', retention = '2 seconds')")
+                    .await
+                    .unwrap();
+                let soon = (Utc::now() + chrono::Duration::seconds(1))
+                    .format("%Y-%m-%dT%H:%M:%S%.3fZ")
+                    .to_string();
+                service
+                    .exec_query(&format!(
+                        "INSERT INTO test.events (id, ts) VALUES (1, '2020-01-01T00:00:00.000Z'), (2, '{}'), (3, NULL)",
+                        soon
+                    ))
+                    .await
+                    .unwrap();
+
+                // Expired rows of chunks are filtered out.
+                compaction_service
+                    .compact(1, DataLoadedSize::new())
+                    .await
+                    .unwrap();
+                let partitions = services
+                    .meta_store
+                    .get_active_partitions_by_index_id(1)
+                    .await
+                    .unwrap();
+                assert_eq!(partitions.len(), 1);
+                assert_eq!(partitions[0].get_row().main_table_row_count(), 2);
+
+                // The main table is rewritten without new chunks once its rows expire.
+                tokio::time::sleep(std::time::Duration::from_secs(4)).await;
+                compaction_service
+                    .compact(partitions[0].get_id(), DataLoadedSize::new())
+                    .await
+                    .unwrap();
+                let partitions = services
+                    .meta_store
+                    .get_active_partitions_by_index_id(1)
+                    .await
+                    .unwrap();
+                assert_eq!(partitions.len(), 1);
+                assert_eq!(partitions[0].get_row().main_table_row_count(), 1);
+                let result = service
+                    .exec_query("SELECT id FROM test.events")
+                    .await
+                    .unwrap();
+                assert_eq!(result.get_rows(), &vec![Row::new(vec![TableValue::Int(3)])]);
+
+                // The partition stays active without a file if all rows expire.
+                service
+                    .exec_query("CREATE TABLE test.old (id int, ts timestamp) WITH (retention_column = 'ts', retention = '1 day')")
+                    .await
+                    .unwrap();
+                service
+                    .exec_query("INSERT INTO test.old (id, ts) VALUES (1, '2020-01-01T00:00:00.000Z'), (2, '2020-01-02T00:00:00.000Z')")
+                    .await
+                    .unwrap();
+                let table = services
+                    .meta_store
+                    .get_table("test".to_string(), "old".to_string())
+                    .await
+                    .unwrap();
+                let index = services
+                    .meta_store
+                    .get_default_index(table.get_id())
+                    .await
+                    .unwrap();
+                let partitions = services
+                    .meta_store
+                    .get_active_partitions_by_index_id(index.get_id())
+                    .await
+                    .unwrap();
+                compaction_service
+                    .compact(partitions[0].get_id(), DataLoadedSize::new())
+                    .await
+                    .unwrap();
+                let partitions = services
+                    .meta_store
+                    .get_active_partitions_by_index_id(index.get_id())
+                    .await
+                    .unwrap();
+                assert_eq!(partitions.len(), 1);
+                assert_eq!(partitions[0].get_row().main_table_row_count(), 0);
+                assert_eq!(partitions[0].get_row().get_full_name(partitions[0].get_id()), None);
+                let result = service
+                    .exec_query("SELECT count(*) FROM test.old")
+                    .await
+                    .unwrap();
+                assert_eq!(result.get_rows(), &vec![Row::new(vec![TableValue::Int(0)])]);
+            })
+            .await;
+    }
 }
 
 struct MultiSplit {
@@ -39,6 +39,7 @@ use crate::metastore::chunks::chunk_file_name;
 use crate::queryplanner::trace_data_loaded::DataLoadedSize;
 use crate::table::data::cmp_partition_key;
 use crate::table::parquet::{arrow_schema, CubestoreMetadataCacheFactory, ParquetTableStore};
+use crate::table::statistics::ColumnStatistics;
 use compaction::{merge_chunks, merge_replay_handles};
 use datafusion::arrow::array::{Array, ArrayRef, Int64Builder, StringBuilder, UInt64Array};
 use datafusion::arrow::record_batch::RecordBatch;
@@ -439,6 +440,7 @@ impl ChunkDataStore for ChunkStore {
             in_memory_columns,
             unique_key.clone(),
             aggregate_columns.clone(),
+            None,
         )
         .await?;
         let batches = common_collect(batches_stream).await?;
@@ -896,6 +898,9 @@ mod tests {
                     None,
                     false,
                     None,
+                    None,
+                    None,
+                    None,
                 )
                 .await
                 .unwrap();
@@ -994,6 +999,9 @@ mod tests {
                     None,
                     false,
                     None,
+                    None,
+                    None,
+                    None,
                 )
                 .await
                 .unwrap();
@@ -1106,6 +1114,9 @@ mod tests {
                     None,
                     false,
                     None,
+                    None,
+                    None,
+                    None,
                 )
                 .await
                 .unwrap();
@@ -1357,9 +1368,19 @@ impl ChunkStore {
     ) -> Result<ChunkUploadJob, CubeError> {
         let key_size = index.get_row().sort_key_size() as usize;
         let (min, max) = min_max_values_from_data(&data, key_size);
+        let data_copy = data.clone();
+        let column_statistics =
+            cube_ext::spawn_blocking(move || ColumnStatistics::from_data(&data_copy)).await?;
         let chunk = self
             .meta_store
-            .create_chunk(partition.get_id(), data[0].len(), min, max, in_memory)
+            .create_chunk(
+                partition.get_id(),
+                data[0].len(),
+                min,
+                max,
+                in_memory,
+                column_statistics,
+            )
             .await?;
         if in_memory {
             trace!(
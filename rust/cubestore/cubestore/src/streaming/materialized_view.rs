@@ -0,0 +1,853 @@
+use crate::metastore::replay_handle::SeqPointer;
+use crate::metastore::{AggregateFunction, Column, ColumnType};
+use crate::queryplanner::query_executor::arrow_to_column_type;
+use crate::queryplanner::udfs::{aggregate_udf_by_kind, CubeAggregateUDFKind};
+use crate::sql::MySqlDialectWithBackTicks;
+use crate::streaming::topic_table_provider::TopicTableProvider;
+use crate::table::data::{append_row, create_array_builders};
+use crate::table::{Row, TableValue};
+use crate::util::int96::Int96;
+use crate::CubeError;
+use datafusion::arrow::array::{Array, ArrayRef, BooleanArray, Int64Array};
+use datafusion::arrow::compute::{cast, filter_record_batch};
+use datafusion::arrow::datatypes::{DataType, Schema, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::catalog::TableReference;
+use datafusion::cube_ext::ordfloat::OrdF64;
+use datafusion::datasource::TableProvider;
+use datafusion::logical_plan::{Expr, LogicalPlan};
+use datafusion::physical_plan::aggregates::AggregateFunction as FusionAggregateFunction;
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::parquet::MetadataCacheFactory;
+use datafusion::physical_plan::udaf::AggregateUDF;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::{collect, ExecutionPlan};
+use datafusion::prelude::{ExecutionConfig, ExecutionContext};
+use datafusion::sql::parser::Statement as DFStatement;
+use datafusion::sql::planner::{ContextProvider, SqlToRel};
+use itertools::Itertools;
+use sqlparser::ast::{Query, SetExpr, Statement};
+use sqlparser::parser::{Parser, ParserError};
+use sqlparser::tokenizer::Tokenizer;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+/// Columns of a materialized view table: all columns in select order, aggregate columns with
+/// the functions used to merge them and the `GROUP BY` columns.
+pub type MaterializedViewColumns = (Vec<Column>, Vec<(AggregateFunction, String)>, Vec<String>);
+
+/// Aggregates new rows of a streaming table into rows of a materialized view.
+#[derive(Clone)]
+pub struct MaterializedViewPlan {
+    plan: Arc<dyn ExecutionPlan>,
+    source_schema: SchemaRef,
+    target_columns: Vec<Column>,
+    target_schema: SchemaRef,
+    target_aggregate_column_indices: Vec<usize>,
+    source_seq_column_index: usize,
+    source_unique_key_column_indices: Option<Vec<usize>>,
+}
+
+impl MaterializedViewPlan {
+    pub fn source_unique_key_column_indices(&self) -> &Option<Vec<usize>> {
+        &self.source_unique_key_column_indices
+    }
+
+    /// Aggregates rows of `data` with sequence numbers greater than `after_seq`. Returns
+    /// aggregated rows in view column order along with the sequence range they were built from
+    /// or [None] if there is nothing to aggregate.
+    ///
+    /// If the source has a unique key only the row with the greatest sequence number is
+    /// aggregated for every key of `data`, the same row the source keeps after merge.
+    /// `previous` holds rows the source has for keys of `data` before `data` is written. A
+    /// previous row is retracted by aggregating it with negated aggregates when a newer row
+    /// replaces it. Rows older than the previous row of their key are skipped.
+    pub async fn apply(
+        &self,
+        data: Vec<ArrayRef>,
+        after_seq: Option<i64>,
+        previous: Option<Vec<ArrayRef>>,
+    ) -> Result<Option<(Vec<ArrayRef>, SeqPointer)>, CubeError> {
+        let batch = RecordBatch::try_new(self.source_schema.clone(), data)?;
+        let seq = seq_column(&batch, self.source_seq_column_index)?;
+
+        let mut seq_pointer = SeqPointer::empty();
+        let mut predicate = Vec::with_capacity(seq.len());
+        for i in 0..seq.len() {
+            let keep = !seq.is_null(i) && after_seq.map_or(true, |s| seq.value(i) > s);
+            if keep {
+                seq_pointer.union(&SeqPointer::new(Some(seq.value(i)), Some(seq.value(i))));
+            }
+            predicate.push(keep);
+        }
+        let mut retracted = None;
+        if let Some(key_indices) = &self.source_unique_key_column_indices {
+            keep_last_by_key(&batch, key_indices, seq, &mut predicate);
+            if let Some(previous) = previous {
+                let previous = RecordBatch::try_new(self.source_schema.clone(), previous)?;
+                let previous_predicate = replace_previous_by_key(
+                    &batch,
+                    &previous,
+                    key_indices,
+                    self.source_seq_column_index,
+                    &mut predicate,
+                )?;
+                retracted = Some(filter_record_batch(
+                    &previous,
+                    &BooleanArray::from(previous_predicate),
+                )?);
+            }
+        }
+        if seq_pointer.is_empty() {
+            return Ok(None);
+        }
+
+        let batch = filter_record_batch(&batch, &BooleanArray::from(predicate))?;
+        let added = self.aggregate(batch).await?;
+        let retracted = match retracted {
+            Some(retracted) if retracted.num_rows() > 0 => self.aggregate(retracted).await?,
+            _ => None,
+        };
+
+        let columns = match (added, retracted) {
+            (None, None) => return Ok(None),
+            (Some(added), None) => added,
+            (added, Some(retracted)) => {
+                let mut builders = create_array_builders(&self.target_columns);
+                if let Some(added) = added {
+                    for i in 0..added[0].len() {
+                        let row = Row::new(TableValue::from_columns(&added, i));
+                        append_row(&mut builders, &self.target_columns, &row);
+                    }
+                }
+                for i in 0..retracted[0].len() {
+                    let mut values = TableValue::from_columns(&retracted, i);
+                    for a in self.target_aggregate_column_indices.iter() {
+                        values[*a] = negate(&values[*a])?;
+                    }
+                    append_row(&mut builders, &self.target_columns, &Row::new(values));
+                }
+                builders.into_iter().map(|mut b| b.finish()).collect()
+            }
+        };
+        Ok(Some((columns, seq_pointer)))
+    }
+
+    /// Runs the view query over `batch`. Returns [None] if it produces no rows.
+    async fn aggregate(&self, batch: RecordBatch) -> Result<Option<Vec<ArrayRef>>, CubeError> {
+        let input = Arc::new(MemoryExec::try_new(
+            &[vec![batch]],
+            self.source_schema.clone(),
+            None,
+        )?);
+        let plan = with_input(self.plan.clone(), input)?;
+        let out_batches = collect(plan.clone()).await?;
+        let res = RecordBatch::concat(&plan.schema(), &out_batches)?;
+        if res.num_rows() == 0 {
+            return Ok(None);
+        }
+
+        let columns = res
+            .columns()
+            .iter()
+            .zip(self.target_schema.fields().iter())
+            .map(|(c, f)| {
+                if c.data_type() == f.data_type() {
+                    Ok(c.clone())
+                } else {
+                    Ok(cast(c, f.data_type())?)
+                }
+            })
+            .collect::<Result<Vec<_>, CubeError>>()?;
+        Ok(Some(columns))
+    }
+}
+
+fn seq_column(batch: &RecordBatch, index: usize) -> Result<&Int64Array, CubeError> {
+    batch
+        .column(index)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .ok_or_else(|| CubeError::internal("Seq column is expected to be Int64".to_string()))
+}
+
+fn negate(value: &TableValue) -> Result<TableValue, CubeError> {
+    Ok(match value {
+        TableValue::Null => TableValue::Null,
+        TableValue::Int(v) => TableValue::Int(-v),
+        TableValue::Int96(v) => TableValue::Int96(Int96::new(-v.raw_value())),
+        TableValue::Decimal(v) => TableValue::Decimal(v.negate()),
+        TableValue::Decimal96(v) => TableValue::Decimal96(v.negate()),
+        TableValue::Float(v) => TableValue::Float(OrdF64(-v.0)),
+        x => {
+            return Err(CubeError::internal(format!(
+                "Can't retract aggregated value: {:?}",
+                x
+            )))
+        }
+    })
+}
+
+/// Unsets `predicate` for rows of `batch` which are older than the row `previous` has for their
+/// key. Returns the predicate selecting rows of `previous` replaced by rows of `batch`.
+fn replace_previous_by_key(
+    batch: &RecordBatch,
+    previous: &RecordBatch,
+    key_indices: &[usize],
+    seq_index: usize,
+    predicate: &mut Vec<bool>,
+) -> Result<Vec<bool>, CubeError> {
+    let key_of = |b: &RecordBatch, i: usize| {
+        key_indices
+            .iter()
+            .map(|k| TableValue::from_array(b.column(*k).as_ref(), i))
+            .collect::<Vec<_>>()
+    };
+    let previous_seq = seq_column(previous, seq_index)?;
+    let mut previous_by_key = HashMap::<Vec<TableValue>, usize>::new();
+    for i in 0..previous.num_rows() {
+        match previous_by_key.entry(key_of(previous, i)) {
+            Entry::Occupied(mut e) => {
+                if previous_seq.value(*e.get()) < previous_seq.value(i) {
+                    e.insert(i);
+                }
+            }
+            Entry::Vacant(e) => {
+                e.insert(i);
+            }
+        }
+    }
+
+    let seq = seq_column(batch, seq_index)?;
+    let mut previous_predicate = vec![false; previous.num_rows()];
+    for i in 0..predicate.len() {
+        if !predicate[i] {
+            continue;
+        }
+        if let Some(p) = previous_by_key.get(&key_of(batch, i)) {
+            if previous_seq.value(*p) < seq.value(i) {
+                previous_predicate[*p] = true;
+            } else {
+                predicate[i] = false;
+            }
+        }
+    }
+    Ok(previous_predicate)
+}
+
+/// Builds a query which selects rows of the source with unique keys of `data`. It may select
+/// some rows with other keys as well. Returns [None] if `data` is empty.
+pub fn previous_rows_query(
+    source_name: &str,
+    columns: &[Column],
+    key_columns: &[&Column],
+    data: &[ArrayRef],
+) -> Result<Option<String>, CubeError> {
+    if data.first().map_or(true, |a| a.len() == 0) {
+        return Ok(None);
+    }
+    let mut conditions = Vec::with_capacity(key_columns.len());
+    for column in key_columns {
+        let array = data[column.get_index()].as_ref();
+        let mut values = BTreeSet::new();
+        let mut has_nulls = false;
+        for i in 0..array.len() {
+            match TableValue::from_array(array, i) {
+                TableValue::Null => has_nulls = true,
+                value => {
+                    values.insert(key_literal(&value)?);
+                }
+            }
+        }
+        let name = format!("`{}`", column.get_name());
+        let mut condition = Vec::new();
+        if !values.is_empty() {
+            condition.push(format!("{} IN ({})", name, values.into_iter().join(", ")));
+        }
+        if has_nulls {
+            condition.push(format!("{} IS NULL", name));
+        }
+        conditions.push(format!("({})", condition.join(" OR ")));
+    }
+    Ok(Some(format!(
+        "SELECT {} FROM {} WHERE {}",
+        columns
+            .iter()
+            .map(|c| format!("`{}`", c.get_name()))
+            .join(", "),
+        source_name,
+        conditions.join(" AND ")
+    )))
+}
+
+/// Unique key columns of sources of materialized views should have one of these types so rows
+/// replaced by new ones can be looked up.
+pub fn is_supported_key_column_type(column_type: &ColumnType) -> bool {
+    matches!(
+        column_type,
+        ColumnType::String | ColumnType::Int | ColumnType::Boolean
+    )
+}
+
+fn key_literal(value: &TableValue) -> Result<String, CubeError> {
+    match value {
+        TableValue::String(v) => Ok(format!("'{}'", v.replace('\'', "''"))),
+        TableValue::Int(v) => Ok(v.to_string()),
+        TableValue::Boolean(v) => Ok(v.to_string()),
+        x => Err(CubeError::internal(format!(
+            "Unsupported unique key value for materialized view: {:?}",
+            x
+        ))),
+    }
+}
+
+/// Unsets `predicate` for rows superseded by a row with the same key and a greater sequence
+/// number.
+fn keep_last_by_key(
+    batch: &RecordBatch,
+    key_indices: &[usize],
+    seq: &Int64Array,
+    predicate: &mut Vec<bool>,
+) {
+    let mut last_by_key = HashMap::<Vec<TableValue>, usize>::new();
+    for i in 0..predicate.len() {
+        if !predicate[i] {
+            continue;
+        }
+        let key = key_indices
+            .iter()
+            .map(|k| TableValue::from_array(batch.column(*k).as_ref(), i))
+            .collect::<Vec<_>>();
+        match last_by_key.get_mut(&key) {
+            Some(last) if seq.value(*last) > seq.value(i) => predicate[i] = false,
+            Some(last) => {
+                predicate[*last] = false;
+                *last = i;
+            }
+            None => {
+                last_by_key.insert(key, i);
+            }
+        }
+    }
+}
+
+/// Replaces leaves of `plan` with `input`.
+fn with_input(
+    plan: Arc<dyn ExecutionPlan>,
+    input: Arc<dyn ExecutionPlan>,
+) -> Result<Arc<dyn ExecutionPlan>, CubeError> {
+    let children = plan.children();
+    if children.is_empty() {
+        return Ok(input);
+    }
+    let children = children
+        .into_iter()
+        .map(|c| with_input(c, input.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(plan.with_new_children(children)?)
+}
+
+/// Resolves the source table of a materialized view by its full or bare name.
+struct MaterializedViewContextProvider {
+    schema: String,
+    table: String,
+    topic_provider: TopicTableProvider,
+}
+
+impl ContextProvider for MaterializedViewContextProvider {
+    fn get_table_provider(&self, name: TableReference) -> Option<Arc<dyn TableProvider>> {
+        match name {
+            TableReference::Partial { schema, table }
+                if schema == self.schema && table == self.table =>
+            {
+                Some(Arc::new(self.topic_provider.clone()))
+            }
+            TableReference::Bare { table } if table == self.table => {
+                Some(Arc::new(self.topic_provider.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    fn get_function_meta(&self, name: &str) -> Option<Arc<ScalarUDF>> {
+        self.topic_provider.get_function_meta(name)
+    }
+
+    fn get_aggregate_meta(&self, name: &str) -> Option<Arc<AggregateUDF>> {
+        match name {
+            "merge" | "MERGE" => Some(Arc::new(
+                aggregate_udf_by_kind(CubeAggregateUDFKind::MergeHll).descriptor(),
+            )),
+            _ => None,
+        }
+    }
+}
+
+pub struct MaterializedViewPlanner {
+    source_schema: String,
+    source_table: String,
+    source_columns: Vec<Column>,
+}
+
+impl MaterializedViewPlanner {
+    pub fn new(source_schema: String, source_table: String, source_columns: Vec<Column>) -> Self {
+        Self {
+            source_schema,
+            source_table,
+            source_columns,
+        }
+    }
+
+    /// Only Projection > Aggregate > [Filter] > TableScan plans are allowed. Every selected
+    /// expression should be either a `GROUP BY` expression or an aggregate which can be merged
+    /// and retracted incrementally: `SUM` or `COUNT`. Rows replaced by a newer row with the same
+    /// unique key are retracted, so `MIN`, `MAX` and `MERGE` can't be maintained.
+    pub fn view_columns(
+        &self,
+        select_statement: &str,
+    ) -> Result<MaterializedViewColumns, CubeError> {
+        let plan = self.make_logical_plan(select_statement)?;
+        let (expr, schema, group_expr, aggr_expr, aggr_schema) = match &plan {
+            LogicalPlan::Projection {
+                expr,
+                input,
+                schema,
+            } => match input.as_ref() {
+                LogicalPlan::Aggregate {
+                    input,
+                    group_expr,
+                    aggr_expr,
+                    schema: aggr_schema,
+                } if !group_expr.is_empty() => {
+                    match input.as_ref() {
+                        LogicalPlan::TableScan { .. } => {}
+                        LogicalPlan::Filter { input, .. }
+                            if matches!(input.as_ref(), LogicalPlan::TableScan { .. }) => {}
+                        _ => return Err(unsupported_plan()),
+                    }
+                    (expr, schema, group_expr, aggr_expr, aggr_schema)
+                }
+                _ => return Err(unsupported_plan()),
+            },
+            _ => return Err(unsupported_plan()),
+        };
+
+        let mut columns = Vec::with_capacity(expr.len());
+        let mut aggregates = Vec::new();
+        let mut dimensions = Vec::new();
+        for (i, e) in expr.iter().enumerate() {
+            let (inner, name, has_alias) = match e {
+                Expr::Alias(inner, name) => (inner.as_ref(), name.clone(), true),
+                Expr::Column(c) => (e, c.name.clone(), false),
+                _ => return Err(unsupported_select_item(e)),
+            };
+            let input_column = match inner {
+                Expr::Column(c) => c,
+                _ => return Err(unsupported_select_item(e)),
+            };
+            let position = aggr_schema
+                .fields()
+                .iter()
+                .position(|f| f.name() == &input_column.name)
+                .ok_or_else(|| unsupported_select_item(e))?;
+            let data_type = schema.field(i).data_type().clone();
+
+            let column_type = if position < group_expr.len() {
+                dimensions.push(name.clone());
+                match &group_expr[position] {
+                    Expr::Column(c) => self.source_column_type(&c.name)?,
+                    _ => arrow_to_column_type(data_type)?,
+                }
+            } else {
+                if !has_alias {
+                    return Err(CubeError::user(format!(
+                        "All aggregates must have aliases in materialized views, expression: {:?}",
+                        e
+                    )));
+                }
+                let (function, column_type) =
+                    self.merge_function(&aggr_expr[position - group_expr.len()], data_type)?;
+                aggregates.push((function, name.clone()));
+                column_type
+            };
+            columns.push(Column::new(name, column_type, i));
+        }
+
+        if dimensions.len() != group_expr.len() {
+            return Err(CubeError::user(
+                "All GROUP BY expressions should be selected in materialized views".to_string(),
+            ));
+        }
+
+        Ok((columns, aggregates, dimensions))
+    }
+
+    pub fn build(
+        &self,
+        select_statement: &str,
+        columns: &Vec<Column>,
+        source_unique_key_columns: Option<Vec<&Column>>,
+        metadata_cache_factory: Arc<dyn MetadataCacheFactory>,
+    ) -> Result<MaterializedViewPlan, CubeError> {
+        let source_seq_column_index = self
+            .source_columns
+            .iter()
+            .find(|c| c.get_name() == "__seq")
+            .ok_or_else(|| {
+                CubeError::internal(format!(
+                    "Seq column is not defined for streaming table '{}.{}'",
+                    self.source_schema, self.source_table
+                ))
+            })?
+            .get_index();
+        let (_, aggregates, _) = self.view_columns(select_statement)?;
+        let target_aggregate_column_indices = columns
+            .iter()
+            .filter(|c| aggregates.iter().any(|(_, name)| name == c.get_name()))
+            .map(|c| c.get_index())
+            .collect();
+        let logical_plan = self.make_logical_plan(select_statement)?;
+        let plan_ctx = Arc::new(ExecutionContext::with_config(
+            ExecutionConfig::new().with_metadata_cache_factory(metadata_cache_factory),
+        ));
+        let plan = plan_ctx.create_physical_plan(&logical_plan)?;
+        Ok(MaterializedViewPlan {
+            plan,
+            source_schema: schema_of(&self.source_columns),
+            target_columns: columns.clone(),
+            target_schema: schema_of(columns),
+            target_aggregate_column_indices,
+            source_seq_column_index,
+            source_unique_key_column_indices: source_unique_key_columns
+                .map(|c| c.iter().map(|c| c.get_index()).collect()),
+        })
+    }
+
+    fn make_logical_plan(&self, select_statement: &str) -> Result<LogicalPlan, CubeError> {
+        let dialect = &MySqlDialectWithBackTicks {};
+        let mut tokenizer = Tokenizer::new(dialect, &select_statement);
+        let tokens = tokenizer.tokenize().map_err(ParserError::from)?;
+        let statement = Parser::new(tokens, dialect).parse_statement()?;
+
+        match &statement {
+            Statement::Query(box Query {
+                body: SetExpr::Select(_),
+                ..
+            }) => {
+                let provider = MaterializedViewContextProvider {
+                    schema: self.source_schema.clone(),
+                    table: self.source_table.clone(),
+                    topic_provider: TopicTableProvider::new(
+                        self.source_table.clone(),
+                        &self.source_columns,
+                    ),
+                };
+                let query_planner = SqlToRel::new(&provider);
+                Ok(query_planner.statement_to_plan(&DFStatement::Statement(statement.clone()))?)
+            }
+            _ => Err(CubeError::user(format!(
+                "{} is not valid select query",
+                select_statement
+            ))),
+        }
+    }
+
+    fn merge_function(
+        &self,
+        expr: &Expr,
+        data_type: DataType,
+    ) -> Result<(AggregateFunction, ColumnType), CubeError> {
+        let (function, args) = match expr {
+            Expr::AggregateFunction {
+                distinct: false,
+                fun,
+                args,
+            } => match fun {
+                FusionAggregateFunction::Count => {
+                    return Ok((AggregateFunction::SUM, ColumnType::Int))
+                }
+                FusionAggregateFunction::Sum => (AggregateFunction::SUM, args),
+                _ => return Err(unsupported_aggregate(expr)),
+            },
+            _ => return Err(unsupported_aggregate(expr)),
+        };
+        let column_type = match args.as_slice() {
+            [Expr::Column(c)] => self.source_column_type(&c.name)?,
+            _ => arrow_to_column_type(data_type)?,
+        };
+        Ok((function, column_type))
+    }
+
+    fn source_column_type(&self, name: &str) -> Result<ColumnType, CubeError> {
+        self.source_columns
+            .iter()
+            .find(|c| c.get_name() == name)
+            .map(|c| c.get_column_type().clone())
+            .ok_or_else(|| CubeError::user(format!("Column {} not found in source table", name)))
+    }
+}
+
+fn schema_of(columns: &Vec<Column>) -> SchemaRef {
+    Arc::new(Schema::new(
+        columns.iter().map(|c| c.clone().into()).collect::<Vec<_>>(),
+    ))
+}
+
+fn unsupported_plan() -> CubeError {
+    CubeError::user(
+        "Only SELECT ... FROM <streaming table> [WHERE ...] GROUP BY ... queries are allowed for materialized views"
+            .to_string(),
+    )
+}
+
+fn unsupported_select_item(expr: &Expr) -> CubeError {
+    CubeError::user(format!(
+        "Materialized views can only select GROUP BY expressions and aggregates, expression: {:?}",
+        expr
+    ))
+}
+
+fn unsupported_aggregate(expr: &Expr) -> CubeError {
+    CubeError::user(format!(
+        "Only SUM and COUNT aggregates can be maintained incrementally, expression: {:?}",
+        expr
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::arrow::array::StringArray;
+    use datafusion::physical_plan::parquet::BasicMetadataCacheFactory;
+
+    fn planner() -> MaterializedViewPlanner {
+        MaterializedViewPlanner::new(
+            "test".to_string(),
+            "events".to_string(),
+            vec![
+                Column::new("user_id".to_string(), ColumnType::String, 0),
+                Column::new("amount".to_string(), ColumnType::Int, 1),
+                Column::new("__seq".to_string(), ColumnType::Int, 2),
+            ],
+        )
+    }
+
+    #[test]
+    fn view_columns() {
+        let (columns, aggregates, dimensions) = planner()
+            .view_columns("SELECT user_id, COUNT(*) cnt, SUM(amount) total FROM test.events WHERE amount > 0 GROUP BY 1")
+            .unwrap();
+        assert_eq!(
+            columns,
+            vec![
+                Column::new("user_id".to_string(), ColumnType::String, 0),
+                Column::new("cnt".to_string(), ColumnType::Int, 1),
+                Column::new("total".to_string(), ColumnType::Int, 2),
+            ]
+        );
+        assert_eq!(
+            aggregates,
+            vec![
+                (AggregateFunction::SUM, "cnt".to_string()),
+                (AggregateFunction::SUM, "total".to_string()),
+            ]
+        );
+        assert_eq!(dimensions, vec!["user_id".to_string()]);
+
+        let planner = planner();
+        for query in [
+            "SELECT user_id, amount FROM test.events",
+            "SELECT user_id, SUM(amount) FROM test.events GROUP BY 1",
+            "SELECT user_id, AVG(amount) a FROM test.events GROUP BY 1",
+            "SELECT user_id, MAX(amount) m FROM test.events GROUP BY 1",
+            "SELECT user_id, MIN(amount) m FROM test.events GROUP BY 1",
+            "SELECT user_id, COUNT(DISTINCT amount) c FROM test.events GROUP BY 1",
+            "SELECT user_id, SUM(amount) + 1 s FROM test.events GROUP BY 1",
+            "SELECT SUM(amount) s FROM test.events GROUP BY user_id",
+            "SELECT user_id, SUM(amount) s FROM test.events GROUP BY 1 HAVING SUM(amount) > 1",
+            "SELECT user_id, SUM(amount) s FROM test.other GROUP BY 1",
+        ] {
+            assert!(planner.view_columns(query).is_err(), "{}", query);
+        }
+    }
+
+    #[tokio::test]
+    async fn apply_after_seq() {
+        let planner = planner();
+        let select = "SELECT user_id, COUNT(*) cnt, SUM(amount) total FROM test.events GROUP BY 1";
+        let (columns, _, _) = planner.view_columns(select).unwrap();
+        let plan = planner
+            .build(
+                select,
+                &columns,
+                None,
+                Arc::new(BasicMetadataCacheFactory::new()),
+            )
+            .unwrap();
+
+        let data: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(vec!["a", "b", "a", "a"])),
+            Arc::new(Int64Array::from(vec![1, 2, 3, 4])),
+            Arc::new(Int64Array::from(vec![10, 11, 12, 13])),
+        ];
+        let (res, seq_pointer) = plan
+            .apply(data.clone(), Some(10), None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(seq_pointer, SeqPointer::new(Some(11), Some(13)));
+
+        let batch = RecordBatch::try_new(schema_of(&columns), res).unwrap();
+        let mut rows = (0..batch.num_rows())
+            .map(|i| {
+                (
+                    batch
+                        .column(0)
+                        .as_any()
+                        .downcast_ref::<StringArray>()
+                        .unwrap()
+                        .value(i)
+                        .to_string(),
+                    batch
+                        .column(1)
+                        .as_any()
+                        .downcast_ref::<Int64Array>()
+                        .unwrap()
+                        .value(i),
+                    batch
+                        .column(2)
+                        .as_any()
+                        .downcast_ref::<Int64Array>()
+                        .unwrap()
+                        .value(i),
+                )
+            })
+            .collect::<Vec<_>>();
+        rows.sort();
+        assert_eq!(rows, vec![("a".to_string(), 2, 7), ("b".to_string(), 1, 2)]);
+
+        assert!(plan.apply(data, Some(13), None).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn apply_keeps_last_row_by_unique_key() {
+        let planner = planner();
+        let select = "SELECT user_id, COUNT(*) cnt, SUM(amount) total FROM test.events GROUP BY 1";
+        let (columns, _, _) = planner.view_columns(select).unwrap();
+        let user_id = Column::new("user_id".to_string(), ColumnType::String, 0);
+        let plan = planner
+            .build(
+                select,
+                &columns,
+                Some(vec![&user_id]),
+                Arc::new(BasicMetadataCacheFactory::new()),
+            )
+            .unwrap();
+
+        let data: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(vec!["a", "b", "a", "a"])),
+            Arc::new(Int64Array::from(vec![1, 2, 3, 4])),
+            Arc::new(Int64Array::from(vec![10, 13, 12, 11])),
+        ];
+        let (res, seq_pointer) = plan.apply(data, None, None).await.unwrap().unwrap();
+        assert_eq!(seq_pointer, SeqPointer::new(Some(10), Some(13)));
+
+        let batch = RecordBatch::try_new(schema_of(&columns), res).unwrap();
+        let mut rows = (0..batch.num_rows())
+            .map(|i| {
+                TableValue::from_columns(batch.columns(), i)
+                    .into_iter()
+                    .map(|v| format!("{:?}", v))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .collect::<Vec<_>>();
+        rows.sort();
+        assert_eq!(
+            rows,
+            vec!["String(\"a\"),Int(1),Int(3)", "String(\"b\"),Int(1),Int(2)"]
+        );
+    }
+
+    #[tokio::test]
+    async fn apply_retracts_previous_rows() {
+        let planner = planner();
+        let select = "SELECT user_id, COUNT(*) cnt, SUM(amount) total FROM test.events GROUP BY 1";
+        let (columns, _, _) = planner.view_columns(select).unwrap();
+        let user_id = Column::new("user_id".to_string(), ColumnType::String, 0);
+        let plan = planner
+            .build(
+                select,
+                &columns,
+                Some(vec![&user_id]),
+                Arc::new(BasicMetadataCacheFactory::new()),
+            )
+            .unwrap();
+
+        let data: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(vec!["a", "b", "c"])),
+            Arc::new(Int64Array::from(vec![5, 6, 7])),
+            Arc::new(Int64Array::from(vec![20, 21, 22])),
+        ];
+        // "a" is replaced, the row for "b" is older than the one the source has.
+        let previous: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(vec!["a", "b"])),
+            Arc::new(Int64Array::from(vec![1, 2])),
+            Arc::new(Int64Array::from(vec![10, 30])),
+        ];
+        let (res, seq_pointer) = plan
+            .apply(data, None, Some(previous))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(seq_pointer, SeqPointer::new(Some(20), Some(22)));
+
+        let batch = RecordBatch::try_new(schema_of(&columns), res).unwrap();
+        let mut rows = (0..batch.num_rows())
+            .map(|i| {
+                TableValue::from_columns(batch.columns(), i)
+                    .into_iter()
+                    .map(|v| format!("{:?}", v))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .collect::<Vec<_>>();
+        rows.sort();
+        assert_eq!(
+            rows,
+            vec![
+                "String(\"a\"),Int(-1),Int(-1)",
+                "String(\"a\"),Int(1),Int(5)",
+                "String(\"c\"),Int(1),Int(7)"
+            ]
+        );
+    }
+
+    #[test]
+    fn previous_rows_query_selects_keys() {
+        let columns = vec![
+            Column::new("user_id".to_string(), ColumnType::String, 0),
+            Column::new("amount".to_string(), ColumnType::Int, 1),
+            Column::new("__seq".to_string(), ColumnType::Int, 2),
+        ];
+        let data: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(vec![
+                Some("b"),
+                Some("it's"),
+                None,
+                Some("b"),
+            ])),
+            Arc::new(Int64Array::from(vec![1, 2, 3, 4])),
+            Arc::new(Int64Array::from(vec![10, 11, 12, 13])),
+        ];
+        assert_eq!(
+            previous_rows_query("`test`.`events`", &columns, &[&columns[0]], &data).unwrap(),
+            Some("SELECT `user_id`, `amount`, `__seq` FROM `test`.`events` WHERE (`user_id` IN ('b', 'it''s') OR `user_id` IS NULL)".to_string())
+        );
+        assert_eq!(
+            previous_rows_query("`test`.`events`", &columns, &[&columns[0]], &[]).unwrap(),
+            None
+        );
+    }
+}
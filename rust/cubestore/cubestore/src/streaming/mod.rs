@@ -1,18 +1,24 @@
 pub mod kafka;
 mod kafka_post_processing;
+pub mod materialized_view;
 mod topic_table_provider;
 mod traffic_sender;
 
 mod buffered_stream;
-use crate::config::injection::DIService;
+use crate::config::injection::{DIService, Injector};
 use crate::config::ConfigObj;
+use crate::import::parse_uuid;
 use crate::metastore::replay_handle::{ReplayHandle, SeqPointer, SeqPointerForLocation};
 use crate::metastore::source::SourceCredentials;
 use crate::metastore::table::{StreamOffset, Table};
 use crate::metastore::{Column, ColumnType, IdRow, MetaStore};
-use crate::sql::timestamp_from_string;
+use crate::sql::SqlService;
+use crate::sql::{date_from_string, timestamp_from_string};
 use crate::store::ChunkDataStore;
 use crate::streaming::kafka::{KafkaClientService, KafkaStreamingSource};
+use crate::streaming::materialized_view::{
+    previous_rows_query, MaterializedViewPlan, MaterializedViewPlanner,
+};
 use crate::table::data::{append_row, create_array_builders};
 use crate::table::{Row, TableValue, TimestampValue};
 use crate::util::decimal::Decimal;
@@ -26,16 +32,18 @@ use datafusion::cube_ext::ordfloat::OrdF64;
 use datafusion::physical_plan::parquet::MetadataCacheFactory;
 use futures::future::join_all;
 use futures::stream::StreamExt;
-use futures::Stream;
+use futures::{Future, Stream};
 use futures_util::stream;
 use itertools::Itertools;
 use json::JsonValue;
 use log::debug;
 use reqwest::{Response, Url};
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
 use std::io::{Cursor, Write};
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 use std::time::{Duration, SystemTime};
 use tracing::Instrument;
 use traffic_sender::TrafficSender;
@@ -52,7 +60,11 @@ pub trait StreamingService: DIService + Send + Sync {
     ) -> Result<(), CubeError>;
 }
 
+/// How often a stream which waits for new rows checks for materialized views being backfilled.
+const MATERIALIZED_VIEW_BACKFILL_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
 pub struct StreamingServiceImpl {
+    injector: Weak<Injector>,
     config_obj: Arc<dyn ConfigObj>,
     meta_store: Arc<dyn MetaStore>,
     chunk_store: Arc<dyn ChunkDataStore>,
@@ -65,6 +77,7 @@ crate::di_service!(StreamingServiceImpl, [StreamingService]);
 
 impl StreamingServiceImpl {
     pub fn new(
+        injector: Weak<Injector>,
         config_obj: Arc<dyn ConfigObj>,
         meta_store: Arc<dyn MetaStore>,
         chunk_store: Arc<dyn ChunkDataStore>,
@@ -73,6 +86,7 @@ impl StreamingServiceImpl {
         metadata_cache_factory: Arc<dyn MetadataCacheFactory>,
     ) -> Arc<Self> {
         Arc::new(Self {
+            injector,
             config_obj,
             meta_store,
             chunk_store,
@@ -224,6 +238,195 @@ impl StreamingServiceImpl {
 
         Ok(initial_seq_pointer.end_seq().clone())
     }
+
+    async fn materialized_view_plan(
+        &self,
+        table: &IdRow<Table>,
+        view: &IdRow<Table>,
+    ) -> Result<MaterializedViewPlan, CubeError> {
+        let definition = view.get_row().materialized_view().as_ref().ok_or_else(|| {
+            CubeError::internal(format!("Table is not a materialized view: {:?}", view))
+        })?;
+        let schema = self
+            .meta_store
+            .get_schema_by_id(table.get_row().get_schema_id())
+            .await?;
+        MaterializedViewPlanner::new(
+            schema.get_row().get_name().clone(),
+            table.get_row().get_table_name().clone(),
+            table.get_row().get_columns().clone(),
+        )
+        .build(
+            &definition.select_statement,
+            view.get_row().get_columns(),
+            table.get_row().unique_key_columns(),
+            self.metadata_cache_factory.clone(),
+        )
+    }
+
+    /// Aggregates rows of `data` which weren't aggregated into `view` yet. Positions of the source
+    /// covered by the view are tracked by replay handles of the view so replayed rows are skipped.
+    async fn update_materialized_view(
+        &self,
+        table: &IdRow<Table>,
+        view: &IdRow<Table>,
+        plan: &MaterializedViewPlan,
+        location_index: usize,
+        data: Vec<ArrayRef>,
+        previous: Option<Vec<ArrayRef>>,
+    ) -> Result<(), CubeError> {
+        let mut position = SeqPointer::empty();
+        for handle in self
+            .meta_store
+            .get_replay_handles_by_table(view.get_id())
+            .await?
+            .iter()
+        {
+            if let Some(Some(seq_pointer)) = handle
+                .get_row()
+                .seq_pointers_by_location()
+                .as_ref()
+                .and_then(|p| p.get(location_index))
+            {
+                position.union(seq_pointer);
+            }
+        }
+
+        let (data, seq_pointer) = match plan.apply(data, *position.end_seq(), previous).await? {
+            Some(res) => res,
+            None => return Ok(()),
+        };
+        let new_chunks = self
+            .chunk_store
+            .partition_data(
+                view.get_id(),
+                data,
+                view.get_row().get_columns().as_slice(),
+                false,
+            )
+            .await?;
+        let new_chunk_ids: Result<Vec<(u64, Option<u64>)>, CubeError> = join_all(new_chunks)
+            .await
+            .into_iter()
+            .map(|c| {
+                let (c, file_size) = c??;
+                Ok((c.get_id(), file_size))
+            })
+            .collect();
+
+        let mut seq_pointers = vec![None; table.get_row().locations().map_or(0, |l| l.len())];
+        seq_pointers[location_index] = Some(seq_pointer);
+        let replay_handle = self
+            .meta_store
+            .create_replay_handle_from_seq_pointers(view.get_id(), Some(seq_pointers))
+            .await?;
+        self.meta_store
+            .activate_chunks(view.get_id(), new_chunk_ids?, Some(replay_handle.get_id()))
+            .await?;
+        Ok(())
+    }
+
+    /// Reads rows the source has for unique keys of `data` so materialized views can retract
+    /// rows replaced by `data`. Returns [None] if the source has no unique key.
+    async fn previous_rows(
+        &self,
+        table: &IdRow<Table>,
+        source_name: &str,
+        data: &[ArrayRef],
+    ) -> Result<Option<Vec<ArrayRef>>, CubeError> {
+        let key_columns = match table.get_row().unique_key_columns() {
+            Some(key_columns) => key_columns,
+            None => return Ok(None),
+        };
+        let columns = table.get_row().get_columns();
+        let query = match previous_rows_query(source_name, columns, &key_columns, data)? {
+            Some(query) => query,
+            None => return Ok(None),
+        };
+        let sql_service = self
+            .injector
+            .upgrade()
+            .ok_or_else(|| CubeError::internal("Injector has been dropped".to_string()))?
+            .get_service_typed::<dyn SqlService>()
+            .await;
+        let rows = sql_service.exec_query(&query).await?;
+        let mut builders = create_array_builders(columns);
+        for row in rows.get_rows() {
+            append_row(&mut builders, columns, row);
+        }
+        Ok(Some(
+            builders.into_iter().map(|mut b| b.finish()).collect_vec(),
+        ))
+    }
+
+    /// Returns ready materialized views of `table`. While a view is backfilled the stream
+    /// records `position`, the last sequence number it has written to the source, in a replay
+    /// handle of the view and waits until the backfill is done: rows up to recorded positions
+    /// are aggregated by the backfill and the stream aggregates the rest.
+    async fn wait_for_materialized_views_backfill(
+        &self,
+        table: &IdRow<Table>,
+        location_index: usize,
+        position: Option<i64>,
+    ) -> Result<Vec<IdRow<Table>>, CubeError> {
+        loop {
+            let views = self
+                .meta_store
+                .get_materialized_views(table.get_id())
+                .await?;
+            let mut backfilled = true;
+            for view in views.iter().filter(|v| !v.get_row().is_ready()) {
+                backfilled = false;
+                let recorded = self
+                    .meta_store
+                    .get_replay_handles_by_table(view.get_id())
+                    .await?
+                    .iter()
+                    .any(|h| {
+                        matches!(
+                            h.get_row()
+                                .seq_pointers_by_location()
+                                .as_ref()
+                                .and_then(|p| p.get(location_index)),
+                            Some(Some(_))
+                        )
+                    });
+                if !recorded {
+                    let mut seq_pointers =
+                        vec![None; table.get_row().locations().map_or(0, |l| l.len())];
+                    seq_pointers[location_index] = Some(SeqPointer::new(position, position));
+                    self.meta_store
+                        .create_replay_handle_from_seq_pointers(view.get_id(), Some(seq_pointers))
+                        .await?;
+                }
+            }
+            if backfilled {
+                return Ok(views);
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    /// Awaits `future` pausing for materialized views being backfilled meanwhile, so streams
+    /// waiting for new rows don't hold backfills back.
+    async fn with_materialized_views_backfill<T>(
+        &self,
+        table: &IdRow<Table>,
+        location_index: usize,
+        position: Option<i64>,
+        future: impl Future<Output = T>,
+    ) -> Result<T, CubeError> {
+        tokio::pin!(future);
+        loop {
+            tokio::select! {
+                res = &mut future => return Ok(res),
+                _ = tokio::time::sleep(MATERIALIZED_VIEW_BACKFILL_CHECK_INTERVAL) => {
+                    self.wait_for_materialized_views_backfill(table, location_index, position)
+                        .await?;
+                }
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -247,13 +450,18 @@ impl StreamingService for StreamingServiceImpl {
         })?;
         let location_index = table.get_row().location_index(location)?;
         let initial_seq_value = self.initial_seq_for(&table, location).await?;
-        let stream = source
-            .row_stream(
-                table.get_row().get_columns().clone(),
-                seq_column.clone(),
-                initial_seq_value.clone(),
+        let stream = self
+            .with_materialized_views_backfill(
+                &table,
+                location_index,
+                initial_seq_value,
+                source.row_stream(
+                    table.get_row().get_columns().clone(),
+                    seq_column.clone(),
+                    initial_seq_value.clone(),
+                ),
             )
-            .await?;
+            .await??;
         let mut stream = BufferedStream::new(
             stream,
             self.config_obj.wal_split_threshold() as usize,
@@ -265,6 +473,17 @@ impl StreamingService for StreamingServiceImpl {
         };
 
         let mut sealed = false;
+        let mut view_plans = HashMap::new();
+        let mut position = initial_seq_value;
+        let schema = self
+            .meta_store
+            .get_schema_by_id(table.get_row().get_schema_id())
+            .await?;
+        let source_name = format!(
+            "`{}`.`{}`",
+            schema.get_row().get_name(),
+            table.get_row().get_table_name()
+        );
 
         let seq_column_index = source.source_seq_column_index();
 
@@ -273,11 +492,17 @@ impl StreamingService for StreamingServiceImpl {
         let tags = vec![format!("location:{}", location)];
 
         while !sealed {
-            let new_rows = match tokio::time::timeout(
-                Duration::from_secs(self.config_obj.stale_stream_timeout()),
-                stream.next(),
-            )
-            .await
+            let new_rows = match self
+                .with_materialized_views_backfill(
+                    &table,
+                    location_index,
+                    position,
+                    tokio::time::timeout(
+                        Duration::from_secs(self.config_obj.stale_stream_timeout()),
+                        stream.next(),
+                    ),
+                )
+                .await?
             {
                 Ok(Some(rows)) => rows,
                 Ok(None) => {
@@ -314,6 +539,10 @@ impl StreamingService for StreamingServiceImpl {
                 last_init_seq_check = SystemTime::now();
             }
 
+            let views = self
+                .wait_for_materialized_views_backfill(&table, location_index, position)
+                .await?;
+
             let rows = new_rows;
             debug!("Received {} rows for {}", rows.len(), location);
             let table_cols = source.source_columns().as_slice();
@@ -352,6 +581,30 @@ impl StreamingService for StreamingServiceImpl {
             let data = finish(builders);
             let data = source.apply_post_processing(data).await?;
 
+            // Views are updated before the source so rows replayed after a failure are skipped
+            // by views instead of being aggregated twice and rows replaced by `data` can still
+            // be read from the source to retract them.
+            if !views.is_empty() {
+                let previous = self.previous_rows(&table, &source_name, &data).await?;
+                for view in views {
+                    let plan = match view_plans.entry(view.get_id()) {
+                        Entry::Occupied(e) => e.into_mut(),
+                        Entry::Vacant(e) => {
+                            e.insert(self.materialized_view_plan(&table, &view).await?)
+                        }
+                    };
+                    self.update_materialized_view(
+                        &table,
+                        &view,
+                        plan,
+                        location_index,
+                        data.clone(),
+                        previous.clone(),
+                    )
+                    .await?;
+                }
+            }
+
             let partition_started_at = SystemTime::now();
             let new_chunks = self
                 .chunk_store
@@ -400,6 +653,7 @@ impl StreamingService for StreamingServiceImpl {
             self.meta_store
                 .activate_chunks(table.get_id(), new_chunk_ids, Some(replay_handle.get_id()))
                 .await?;
+            position = position.max(end_seq);
 
             if let Ok(process_time) = process_started.elapsed() {
                 app_metrics::STREAMING_IMPORT_TIME
@@ -622,6 +876,48 @@ pub fn parse_json_value(column: &Column, value: &JsonValue) -> Result<TableValue
                 x
             ))),
         },
+        ColumnType::Date => match value {
+            JsonValue::Short(v) => Ok(TableValue::Date(date_from_string(v.as_str())?)),
+            JsonValue::String(v) => Ok(TableValue::Date(date_from_string(v.as_str())?)),
+            JsonValue::Null => Ok(TableValue::Null),
+            x => Err(CubeError::internal(format!(
+                "ksql source returned {:?} as row value but only string values are supported",
+                x
+            ))),
+        },
+        ColumnType::Uuid => match value {
+            JsonValue::Short(v) => Ok(TableValue::Uuid(parse_uuid(v.as_str())?)),
+            JsonValue::String(v) => Ok(TableValue::Uuid(parse_uuid(v.as_str())?)),
+            JsonValue::Null => Ok(TableValue::Null),
+            x => Err(CubeError::internal(format!(
+                "ksql source returned {:?} as row value but only string values are supported",
+                x
+            ))),
+        },
+        ColumnType::Json => match value {
+            JsonValue::Null => Ok(TableValue::Null),
+            x => Ok(TableValue::String(x.dump())),
+        },
+        ColumnType::Array(element_type) => match value {
+            JsonValue::Array(items) => {
+                let element_column = Column::new(
+                    column.get_name().clone(),
+                    element_type.as_ref().clone(),
+                    column.get_index(),
+                );
+                Ok(TableValue::Array(
+                    items
+                        .iter()
+                        .map(|item| parse_json_value(&element_column, item))
+                        .collect::<Result<Vec<_>, _>>()?,
+                ))
+            }
+            JsonValue::Null => Ok(TableValue::Null),
+            x => Err(CubeError::internal(format!(
+                "ksql source returned {:?} as row value but array expected",
+                x
+            ))),
+        },
     }
 }
 
@@ -951,6 +1247,7 @@ mod tests {
     use sqlparser::ast::{BinaryOperator, Expr, SetExpr, Statement, Value};
     use sqlparser::parser::Parser;
     use sqlparser::tokenizer::Tokenizer;
+    use std::sync::atomic::{AtomicBool, Ordering};
     use tokio::time::timeout;
 
     pub struct MockKsqlClient;
@@ -1297,6 +1594,272 @@ mod tests {
             .await;
     }
 
+    static MATERIALIZED_VIEW_STREAM_STARTED: AtomicBool = AtomicBool::new(false);
+
+    static MATERIALIZED_VIEW_UPSERT_STREAM_STARTED: AtomicBool = AtomicBool::new(false);
+
+    /// Holds streams back until the flag is set once materialized views are created.
+    pub struct GatedKsqlClient(&'static AtomicBool);
+
+    crate::di_service!(GatedKsqlClient, [KsqlClient]);
+
+    #[async_trait::async_trait]
+    impl KsqlClient for GatedKsqlClient {
+        async fn post_req(
+            &self,
+            url: &str,
+            json: serde_json::Value,
+            endpoint_url: &String,
+            user: &Option<String>,
+            password: &Option<String>,
+        ) -> Result<KsqlResponse, CubeError> {
+            while !self.0.load(Ordering::SeqCst) {
+                Delay::new(Duration::from_millis(100)).await;
+            }
+            MockKsqlClient
+                .post_req(url, json, endpoint_url, user, password)
+                .await
+        }
+    }
+
+    #[tokio::test]
+    async fn streaming_materialized_view() {
+        Config::test("streaming_materialized_view").update_config(|mut c| {
+            c.stream_replay_check_interval_secs = 1;
+            c.stale_stream_timeout = 1;
+            c.wal_split_threshold = 1638;
+            c
+        }).start_with_injector_override(async move |injector| {
+            injector.register_typed::<dyn KsqlClient, _, _, _>(async move |_| {
+                Arc::new(GatedKsqlClient(&MATERIALIZED_VIEW_STREAM_STARTED))
+            })
+                .await
+        }, async move |services| {
+            let scheduler = services.injector.get_service_typed::<SchedulerImpl>().await;
+            let service = services.sql_service;
+
+            let _ = service.exec_query("CREATE SCHEMA test").await.unwrap();
+
+            service
+                .exec_query("CREATE SOURCE OR UPDATE ksql AS 'ksql' VALUES (user = 'foo', password = 'bar', url = 'http://foo.com')")
+                .await
+                .unwrap();
+
+            let listener = services.cluster.job_result_listener();
+
+            let _ = service
+                .exec_query("CREATE TABLE test.events (`ANONYMOUSID` text, `MESSAGEID` text) WITH (stream_offset = 'earliest') unique key (`ANONYMOUSID`, `MESSAGEID`) location 'stream://ksql/EVENTS_BY_TYPE/0', 'stream://ksql/EVENTS_BY_TYPE/1'")
+                .await
+                .unwrap();
+
+            let _ = service
+                .exec_query("CREATE TABLE test.plain (`ANONYMOUSID` text)")
+                .await
+                .unwrap();
+            let err = service
+                .exec_query("CREATE MATERIALIZED VIEW test.plain_by_anonymous AS SELECT `ANONYMOUSID`, COUNT(*) cnt FROM test.plain GROUP BY 1")
+                .await
+                .unwrap_err();
+            assert!(err.message.contains("only be created over streaming tables"), "{}", err);
+
+            service
+                .exec_query("CREATE MATERIALIZED VIEW test.events_by_anonymous AS SELECT `ANONYMOUSID`, COUNT(*) cnt, SUM(`__seq`) seq_sum FROM test.events GROUP BY 1")
+                .await
+                .unwrap();
+
+            MATERIALIZED_VIEW_STREAM_STARTED.store(true, Ordering::SeqCst);
+
+            let wait = listener.wait_for_job_results(vec![
+                (RowKey::Table(TableId::Tables, 1), JobType::TableImportCSV("stream://ksql/EVENTS_BY_TYPE/0".to_string())),
+                (RowKey::Table(TableId::Tables, 1), JobType::TableImportCSV("stream://ksql/EVENTS_BY_TYPE/1".to_string())),
+            ]);
+            timeout(Duration::from_secs(15), wait).await.unwrap().unwrap();
+
+            let expected = vec![
+                Row::new(vec![TableValue::String("0".to_string()), TableValue::Int(5000), TableValue::Int(12497500)]),
+                Row::new(vec![TableValue::String("1".to_string()), TableValue::Int(5000), TableValue::Int(12497500)]),
+            ];
+            let result = service
+                .exec_query("SELECT `ANONYMOUSID`, SUM(cnt), SUM(seq_sum) FROM test.events_by_anonymous GROUP BY 1 ORDER BY 1")
+                .await
+                .unwrap();
+            assert_eq!(result.get_rows(), &expected);
+
+            // Restarted streams replay rows from the last seq which should be skipped by the view.
+            let listener = services.cluster.job_result_listener();
+            scheduler.reconcile_table_imports().await.unwrap();
+            let wait = listener.wait_for_job_results(vec![
+                (RowKey::Table(TableId::Tables, 1), JobType::TableImportCSV("stream://ksql/EVENTS_BY_TYPE/0".to_string())),
+                (RowKey::Table(TableId::Tables, 1), JobType::TableImportCSV("stream://ksql/EVENTS_BY_TYPE/1".to_string())),
+            ]);
+            timeout(Duration::from_secs(15), wait).await.unwrap().unwrap();
+
+            let result = service
+                .exec_query("SELECT `ANONYMOUSID`, SUM(cnt), SUM(seq_sum) FROM test.events_by_anonymous GROUP BY 1 ORDER BY 1")
+                .await
+                .unwrap();
+            assert_eq!(result.get_rows(), &expected);
+
+            let result = service
+                .exec_query("SELECT COUNT(*) FROM test.events")
+                .await
+                .unwrap();
+            assert_eq!(result.get_rows(), &vec![Row::new(vec![TableValue::Int(10000)])]);
+
+            let err = service.exec_query("DROP TABLE test.events").await.unwrap_err();
+            assert!(err.message.contains("used by materialized views: events_by_anonymous"), "{}", err);
+            service.exec_query("DROP TABLE test.events_by_anonymous").await.unwrap();
+            service.exec_query("DROP TABLE test.events").await.unwrap();
+        })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn streaming_materialized_view_backfill() {
+        Config::test("streaming_materialized_view_backfill").update_config(|mut c| {
+            c.stream_replay_check_interval_secs = 1;
+            c.stale_stream_timeout = 1;
+            c.wal_split_threshold = 1638;
+            c
+        }).start_with_injector_override(async move |injector| {
+            injector.register_typed::<dyn KsqlClient, _, _, _>(async move |_| {
+                Arc::new(MockKsqlClient)
+            })
+                .await
+        }, async move |services| {
+            let scheduler = services.injector.get_service_typed::<SchedulerImpl>().await;
+            let service = services.sql_service;
+
+            let _ = service.exec_query("CREATE SCHEMA test").await.unwrap();
+
+            service
+                .exec_query("CREATE SOURCE OR UPDATE ksql AS 'ksql' VALUES (user = 'foo', password = 'bar', url = 'http://foo.com')")
+                .await
+                .unwrap();
+
+            let listener = services.cluster.job_result_listener();
+
+            let _ = service
+                .exec_query("CREATE TABLE test.events (`ANONYMOUSID` text, `MESSAGEID` text) WITH (stream_offset = 'earliest') unique key (`ANONYMOUSID`, `MESSAGEID`) location 'stream://ksql/EVENTS_BY_TYPE/0', 'stream://ksql/EVENTS_BY_TYPE/1'")
+                .await
+                .unwrap();
+
+            let wait = listener.wait_for_job_results(vec![
+                (RowKey::Table(TableId::Tables, 1), JobType::TableImportCSV("stream://ksql/EVENTS_BY_TYPE/0".to_string())),
+                (RowKey::Table(TableId::Tables, 1), JobType::TableImportCSV("stream://ksql/EVENTS_BY_TYPE/1".to_string())),
+            ]);
+            timeout(Duration::from_secs(15), wait).await.unwrap().unwrap();
+
+            // Rows already in the source are aggregated when the view is created.
+            service
+                .exec_query("CREATE MATERIALIZED VIEW test.events_by_anonymous AS SELECT `ANONYMOUSID`, COUNT(*) cnt, SUM(`__seq`) seq_sum FROM test.events GROUP BY 1")
+                .await
+                .unwrap();
+
+            let expected = vec![
+                Row::new(vec![TableValue::String("0".to_string()), TableValue::Int(5000), TableValue::Int(12497500)]),
+                Row::new(vec![TableValue::String("1".to_string()), TableValue::Int(5000), TableValue::Int(12497500)]),
+            ];
+            let result = service
+                .exec_query("SELECT `ANONYMOUSID`, SUM(cnt), SUM(seq_sum) FROM test.events_by_anonymous GROUP BY 1 ORDER BY 1")
+                .await
+                .unwrap();
+            assert_eq!(result.get_rows(), &expected);
+
+            // Replayed rows were backfilled already.
+            let listener = services.cluster.job_result_listener();
+            scheduler.reconcile_table_imports().await.unwrap();
+            let wait = listener.wait_for_job_results(vec![
+                (RowKey::Table(TableId::Tables, 1), JobType::TableImportCSV("stream://ksql/EVENTS_BY_TYPE/0".to_string())),
+                (RowKey::Table(TableId::Tables, 1), JobType::TableImportCSV("stream://ksql/EVENTS_BY_TYPE/1".to_string())),
+            ]);
+            timeout(Duration::from_secs(15), wait).await.unwrap().unwrap();
+
+            let result = service
+                .exec_query("SELECT `ANONYMOUSID`, SUM(cnt), SUM(seq_sum) FROM test.events_by_anonymous GROUP BY 1 ORDER BY 1")
+                .await
+                .unwrap();
+            assert_eq!(result.get_rows(), &expected);
+        })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn streaming_materialized_view_upsert() {
+        Config::test("streaming_materialized_view_upsert").update_config(|mut c| {
+            c.stream_replay_check_interval_secs = 1;
+            c.stale_stream_timeout = 1;
+            c.wal_split_threshold = 1638;
+            c
+        }).start_with_injector_override(async move |injector| {
+            injector.register_typed::<dyn KsqlClient, _, _, _>(async move |_| {
+                Arc::new(GatedKsqlClient(&MATERIALIZED_VIEW_UPSERT_STREAM_STARTED))
+            })
+                .await
+        }, async move |services| {
+            let service = services.sql_service;
+
+            let _ = service.exec_query("CREATE SCHEMA test").await.unwrap();
+
+            service
+                .exec_query("CREATE SOURCE OR UPDATE ksql AS 'ksql' VALUES (user = 'foo', password = 'bar', url = 'http://foo.com')")
+                .await
+                .unwrap();
+
+            let listener = services.cluster.job_result_listener();
+
+            // Every batch of a location replaces the row of its only key.
+            let _ = service
+                .exec_query("CREATE TABLE test.events (`ANONYMOUSID` text, `MESSAGEID` text) WITH (stream_offset = 'earliest') unique key (`ANONYMOUSID`) location 'stream://ksql/EVENTS_BY_TYPE/0', 'stream://ksql/EVENTS_BY_TYPE/1'")
+                .await
+                .unwrap();
+
+            let err = service
+                .exec_query("CREATE MATERIALIZED VIEW test.events_max AS SELECT `ANONYMOUSID`, MAX(`__seq`) max_seq FROM test.events GROUP BY 1")
+                .await
+                .unwrap_err();
+            assert!(err.message.contains("Only SUM and COUNT aggregates"), "{}", err);
+
+            service
+                .exec_query("CREATE MATERIALIZED VIEW test.events_by_anonymous AS SELECT `ANONYMOUSID`, COUNT(*) cnt, SUM(`__seq`) seq_sum FROM test.events GROUP BY 1")
+                .await
+                .unwrap();
+
+            MATERIALIZED_VIEW_UPSERT_STREAM_STARTED.store(true, Ordering::SeqCst);
+
+            let wait = listener.wait_for_job_results(vec![
+                (RowKey::Table(TableId::Tables, 1), JobType::TableImportCSV("stream://ksql/EVENTS_BY_TYPE/0".to_string())),
+                (RowKey::Table(TableId::Tables, 1), JobType::TableImportCSV("stream://ksql/EVENTS_BY_TYPE/1".to_string())),
+            ]);
+            timeout(Duration::from_secs(15), wait).await.unwrap().unwrap();
+
+            let result = service
+                .exec_query("SELECT `ANONYMOUSID`, `__seq` FROM test.events ORDER BY 1")
+                .await
+                .unwrap();
+            assert_eq!(
+                result.get_rows(),
+                &vec![
+                    Row::new(vec![TableValue::String("0".to_string()), TableValue::Int(4999)]),
+                    Row::new(vec![TableValue::String("1".to_string()), TableValue::Int(4999)]),
+                ]
+            );
+
+            let result = service
+                .exec_query("SELECT `ANONYMOUSID`, SUM(cnt), SUM(seq_sum) FROM test.events_by_anonymous GROUP BY 1 ORDER BY 1")
+                .await
+                .unwrap();
+            assert_eq!(
+                result.get_rows(),
+                &vec![
+                    Row::new(vec![TableValue::String("0".to_string()), TableValue::Int(1), TableValue::Int(4999)]),
+                    Row::new(vec![TableValue::String("1".to_string()), TableValue::Int(1), TableValue::Int(4999)]),
+                ]
+            );
+        })
+            .await;
+    }
+
     #[tokio::test]
     async fn streaming_replay_kafka() {
         Config::test("streaming_replay_kafka").update_config(|mut c| {
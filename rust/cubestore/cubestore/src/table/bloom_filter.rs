@@ -0,0 +1,275 @@
+use crate::CubeError;
+use datafusion::arrow::array::{Array, ArrayRef, Int64Array, StringArray};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::scalar::ScalarValue;
+use serde::{Deserialize, Serialize};
+use std::hash::Hasher;
+use twox_hash::XxHash64;
+
+/// Split block bloom filter as described in the Parquet format specification.
+/// Values are hashed with xxHash64 over their plain encoding, so filters are compatible with
+/// the ones Parquet writers produce.
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq, Hash)]
+pub struct BloomFilter {
+    #[serde(with = "serde_bytes")]
+    bitset: Vec<u8>,
+}
+
+const SALT: [u32; 8] = [
+    0x47b6137b, 0x44974d91, 0x8824ad5b, 0xa2b7289d, 0x705495c7, 0x2df1424b, 0x9efc4947, 0x5c6bfb31,
+];
+const BLOCK_BYTES: usize = 32;
+const FALSE_POSITIVE_PROBABILITY: f64 = 0.01;
+/// Filters of all row groups are read on every scan of a file with a bloom filter predicate, so
+/// we trade accuracy for size on big row groups.
+pub const MAX_BLOOM_FILTER_BYTES: usize = 128 * 1024;
+/// Bloom filters of a parquet file are kept in a side file with the same name and this suffix.
+pub const BLOOM_FILTER_FILE_SUFFIX: &str = ".bloom";
+
+impl BloomFilter {
+    /// Builds a filter sized for the number of distinct hashes.
+    pub fn from_hashes(mut hashes: Vec<u64>) -> BloomFilter {
+        hashes.sort_unstable();
+        hashes.dedup();
+        let mut filter = BloomFilter::with_ndv(hashes.len());
+        for h in hashes {
+            filter.insert_hash(h);
+        }
+        filter
+    }
+
+    fn with_ndv(ndv: usize) -> BloomFilter {
+        let bits = -8.0 * ndv as f64 / (1.0 - FALSE_POSITIVE_PROBABILITY.powf(1.0 / 8.0)).ln();
+        let bytes = ((bits / 8.0).ceil() as usize)
+            .next_power_of_two()
+            .max(BLOCK_BYTES)
+            .min(MAX_BLOOM_FILTER_BYTES);
+        BloomFilter {
+            bitset: vec![0; bytes],
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.bitset.len()
+    }
+
+    pub fn insert_hash(&mut self, hash: u64) {
+        let block = self.block_offset(hash);
+        let mask = Self::mask(hash as u32);
+        for i in 0..8 {
+            let word = self.word(block, i) | mask[i];
+            self.bitset[block + i * 4..block + i * 4 + 4].copy_from_slice(&word.to_le_bytes());
+        }
+    }
+
+    pub fn check_hash(&self, hash: u64) -> bool {
+        let block = self.block_offset(hash);
+        let mask = Self::mask(hash as u32);
+        (0..8).all(|i| self.word(block, i) & mask[i] != 0)
+    }
+
+    fn block_offset(&self, hash: u64) -> usize {
+        let num_blocks = (self.bitset.len() / BLOCK_BYTES) as u64;
+        (((hash >> 32) * num_blocks) >> 32) as usize * BLOCK_BYTES
+    }
+
+    fn word(&self, block: usize, i: usize) -> u32 {
+        let offset = block + i * 4;
+        u32::from_le_bytes(self.bitset[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn mask(x: u32) -> [u32; 8] {
+        let mut res = [0; 8];
+        for i in 0..8 {
+            res[i] = 1 << (x.wrapping_mul(SALT[i]) >> 27);
+        }
+        res
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq, Hash)]
+pub struct ColumnBloomFilter {
+    pub column: String,
+    pub filter: BloomFilter,
+}
+
+/// Bloom filters of every row group of a parquet file in row group order.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, Eq, PartialEq)]
+pub struct RowGroupBloomFilters {
+    pub row_groups: Vec<Vec<ColumnBloomFilter>>,
+}
+
+impl RowGroupBloomFilters {
+    pub fn read(path: &str) -> Result<Self, CubeError> {
+        Ok(bincode::deserialize(&std::fs::read(path)?)?)
+    }
+
+    pub fn write(&self, path: &str) -> Result<(), CubeError> {
+        Ok(std::fs::write(path, bincode::serialize(self)?)?)
+    }
+}
+
+pub fn bloom_filter_file_name(data_file: &str) -> String {
+    format!("{}{}", data_file, BLOOM_FILTER_FILE_SUFFIX)
+}
+
+/// Collects value hashes of columns while the data is written and builds filters of the row
+/// groups once the file is closed.
+#[derive(Clone)]
+pub struct BloomFilterBuilder {
+    /// Column names with their indices in the written batches.
+    columns: Vec<(String, usize)>,
+    /// Hashes of each row by column, [None] for nulls.
+    hashes: Vec<Vec<Option<u64>>>,
+}
+
+impl BloomFilterBuilder {
+    pub fn new(columns: Vec<(String, usize)>) -> Self {
+        let hashes = vec![Vec::new(); columns.len()];
+        Self { columns, hashes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    pub fn add_batch(&mut self, columns: &[ArrayRef]) -> Result<(), CubeError> {
+        for ((_, i), hashes) in self.columns.iter().zip(self.hashes.iter_mut()) {
+            hashes.extend(hash_array(columns[*i].as_ref())?);
+        }
+        Ok(())
+    }
+
+    /// `row_group_sizes` are numbers of rows in row groups of the written file.
+    pub fn finish(self, row_group_sizes: &[usize]) -> Result<RowGroupBloomFilters, CubeError> {
+        let num_rows = row_group_sizes.iter().sum::<usize>();
+        if let Some(hashes) = self.hashes.iter().find(|h| h.len() != num_rows) {
+            return Err(CubeError::internal(format!(
+                "Bloom filters collected {} rows, but {} rows were written",
+                hashes.len(),
+                num_rows
+            )));
+        }
+        let mut row_groups = Vec::with_capacity(row_group_sizes.len());
+        let mut start = 0;
+        for size in row_group_sizes {
+            row_groups.push(
+                self.columns
+                    .iter()
+                    .zip(self.hashes.iter())
+                    .map(|((name, _), hashes)| ColumnBloomFilter {
+                        column: name.clone(),
+                        filter: BloomFilter::from_hashes(
+                            hashes[start..start + size]
+                                .iter()
+                                .flatten()
+                                .cloned()
+                                .collect(),
+                        ),
+                    })
+                    .collect(),
+            );
+            start += size;
+        }
+        Ok(RowGroupBloomFilters { row_groups })
+    }
+}
+
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hasher = XxHash64::with_seed(0);
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+/// Hashes of values in row order, [None] for nulls.
+pub fn hash_array(array: &dyn Array) -> Result<Vec<Option<u64>>, CubeError> {
+    let mut res = Vec::with_capacity(array.len());
+    match array.data_type() {
+        DataType::Int64 => {
+            let a = array.as_any().downcast_ref::<Int64Array>().unwrap();
+            for i in 0..a.len() {
+                res.push(if a.is_null(i) {
+                    None
+                } else {
+                    Some(hash_bytes(&a.value(i).to_le_bytes()))
+                });
+            }
+        }
+        DataType::Utf8 => {
+            let a = array.as_any().downcast_ref::<StringArray>().unwrap();
+            for i in 0..a.len() {
+                res.push(if a.is_null(i) {
+                    None
+                } else {
+                    Some(hash_bytes(a.value(i).as_bytes()))
+                });
+            }
+        }
+        t => {
+            return Err(CubeError::internal(format!(
+                "Bloom filters are not supported for {:?}",
+                t
+            )))
+        }
+    }
+    Ok(res)
+}
+
+/// Returns None if the value can't be checked against the filter.
+pub fn hash_scalar(value: &ScalarValue) -> Option<u64> {
+    let int = match value {
+        ScalarValue::Int64(Some(v)) => *v,
+        ScalarValue::Int32(Some(v)) => *v as i64,
+        ScalarValue::Int16(Some(v)) => *v as i64,
+        ScalarValue::Int8(Some(v)) => *v as i64,
+        ScalarValue::UInt32(Some(v)) => *v as i64,
+        ScalarValue::UInt16(Some(v)) => *v as i64,
+        ScalarValue::UInt8(Some(v)) => *v as i64,
+        ScalarValue::Utf8(Some(v)) | ScalarValue::LargeUtf8(Some(v)) => {
+            return Some(hash_bytes(v.as_bytes()))
+        }
+        _ => return None,
+    };
+    Some(hash_bytes(&int.to_le_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_bloom_filter() {
+        let ids = Int64Array::from((0..10_000).collect::<Vec<i64>>());
+        let filter =
+            BloomFilter::from_hashes(hash_array(&ids).unwrap().into_iter().flatten().collect());
+        assert!(filter.size() <= MAX_BLOOM_FILTER_BYTES);
+
+        for i in 0..10_000 {
+            assert!(filter.check_hash(hash_scalar(&ScalarValue::Int64(Some(i))).unwrap()));
+        }
+        let false_positives = (10_000..20_000)
+            .filter(|i| filter.check_hash(hash_scalar(&ScalarValue::Int64(Some(*i))).unwrap()))
+            .count();
+        assert!(false_positives < 300, "{}", false_positives);
+
+        let mut builder = BloomFilterBuilder::new(vec![("name".to_string(), 0)]);
+        let names: ArrayRef = Arc::new(StringArray::from(vec![Some("foo"), None]));
+        builder.add_batch(&[names]).unwrap();
+        let names: ArrayRef = Arc::new(StringArray::from(vec![Some("bar"), Some("baz")]));
+        builder.add_batch(&[names]).unwrap();
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let path = file.path().to_str().unwrap();
+        builder.finish(&[3, 1]).unwrap().write(path).unwrap();
+        let filters = RowGroupBloomFilters::read(path).unwrap();
+        assert_eq!(filters.row_groups.len(), 2);
+        let foo = hash_scalar(&ScalarValue::Utf8(Some("foo".to_string()))).unwrap();
+        let baz = hash_scalar(&ScalarValue::Utf8(Some("baz".to_string()))).unwrap();
+        assert!(filters.row_groups[0][0].filter.check_hash(foo));
+        assert!(!filters.row_groups[0][0].filter.check_hash(baz));
+        assert!(filters.row_groups[1][0].filter.check_hash(baz));
+        assert!(!filters.row_groups[1][0].filter.check_hash(foo));
+        assert_eq!(hash_scalar(&ScalarValue::Utf8(None)), None);
+    }
+}
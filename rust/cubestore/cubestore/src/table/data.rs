@@ -1,5 +1,5 @@
 use crate::metastore::{Column, ColumnType};
-use crate::table::{Row, TableValue, TimestampValue};
+use crate::table::{DateValue, Row, TableValue, TimestampValue};
 use crate::util::decimal::{Decimal, Decimal96};
 use crate::util::int96::Int96;
 use itertools::Itertools;
@@ -11,6 +11,7 @@ use datafusion::cube_ext::ordfloat::OrdF64;
 use datafusion::physical_plan::memory::MemoryExec;
 use datafusion::physical_plan::{ExecutionPlan, SendableRecordBatchStream};
 use std::fmt;
+use uuid::Uuid;
 
 #[derive(Clone, Copy, Eq, PartialEq, Debug)]
 pub enum TableValueR<'a> {
@@ -24,6 +25,9 @@ pub enum TableValueR<'a> {
     Bytes(&'a [u8]),
     Timestamp(TimestampValue),
     Boolean(bool),
+    Date(DateValue),
+    Uuid(Uuid),
+    Array(&'a [TableValue]),
 }
 
 impl TableValueR<'_> {
@@ -39,6 +43,9 @@ impl TableValueR<'_> {
             TableValue::Bytes(b) => TableValueR::Bytes(&b),
             TableValue::Timestamp(v) => TableValueR::Timestamp(v.clone()),
             TableValue::Boolean(v) => TableValueR::Boolean(*v),
+            TableValue::Date(v) => TableValueR::Date(*v),
+            TableValue::Uuid(v) => TableValueR::Uuid(*v),
+            TableValue::Array(v) => TableValueR::Array(&v),
         }
     }
 }
@@ -129,6 +136,20 @@ pub fn cmp_same_types(l: &TableValueR, r: &TableValueR) -> Ordering {
         (TableValueR::Bytes(a), TableValueR::Bytes(b)) => a.cmp(b),
         (TableValueR::Timestamp(a), TableValueR::Timestamp(b)) => a.cmp(b),
         (TableValueR::Boolean(a), TableValueR::Boolean(b)) => a.cmp(b),
+        (TableValueR::Date(a), TableValueR::Date(b)) => a.cmp(b),
+        (TableValueR::Uuid(a), TableValueR::Uuid(b)) => a.cmp(b),
+        (TableValueR::Array(a), TableValueR::Array(b)) => {
+            for (a, b) in a.iter().zip(b.iter()) {
+                let o = cmp_same_types(
+                    &TableValueR::from_heap_allocated(a),
+                    &TableValueR::from_heap_allocated(b),
+                );
+                if o != Ordering::Equal {
+                    return o;
+                }
+            }
+            a.len().cmp(&b.len())
+        }
         (a, b) => panic!("Can't compare {:?} to {:?}", a, b),
     }
 }
@@ -167,12 +188,43 @@ macro_rules! match_column_type {
                 n => panic!("unhandled target scale: {}", n),
             },
             ColumnType::Float => $matcher!(Float, Float64Builder, Float),
+            ColumnType::Date => $matcher!(Date, Date32Builder, Date),
+            ColumnType::Uuid => $matcher!(Uuid, FixedSizeBinaryBuilder, Uuid),
+            ColumnType::Json => $matcher!(Json, StringBuilder, String),
+            ColumnType::Array(_) => panic!("array columns should be handled separately"),
+        }
+    }};
+}
+
+/// Arrays are stored as lists of the element type and need a typed [ListBuilder].
+macro_rules! match_array_element_type {
+    ($t: expr, $matcher: ident) => {{
+        use datafusion::arrow::array::*;
+        match $t {
+            ColumnType::String => $matcher!(StringBuilder),
+            ColumnType::Int => $matcher!(Int64Builder),
+            ColumnType::Float => $matcher!(Float64Builder),
+            ColumnType::Boolean => $matcher!(BooleanBuilder),
+            ColumnType::Timestamp => $matcher!(TimestampMicrosecondBuilder),
+            ColumnType::Date => $matcher!(Date32Builder),
+            other => panic!("unsupported array element type: {:?}", other),
         }
     }};
 }
 
 pub fn create_array_builder(t: &ColumnType) -> Box<dyn ArrayBuilder> {
+    if let ColumnType::Array(element_type) = t {
+        macro_rules! create_list_builder {
+            ($builder: tt) => {
+                Box::new(ListBuilder::new($builder::new(0)))
+            };
+        }
+        return match_array_element_type!(element_type.as_ref(), create_list_builder);
+    }
     macro_rules! create_builder {
+        (Uuid, $builder: tt $(,$arg: tt)*) => {
+            Box::new($builder::new(0, 16))
+        };
         ($type: tt, $builder: tt $(,$arg: tt)*) => {
             Box::new($builder::new(0))
         };
@@ -195,6 +247,9 @@ pub fn append_row(bs: &mut [Box<dyn ArrayBuilder>], cs: &[Column], r: &Row) {
 }
 
 pub fn append_value(b: &mut dyn ArrayBuilder, c: &ColumnType, v: &TableValue) {
+    if let ColumnType::Array(element_type) = c {
+        return append_array_value(b, element_type, v);
+    }
     let is_null = matches!(v, TableValue::Null);
     macro_rules! convert_value {
         (Decimal, $v: expr) => {{
@@ -212,6 +267,12 @@ pub fn append_value(b: &mut dyn ArrayBuilder, c: &ColumnType, v: &TableValue) {
         (Timestamp, $v: expr) => {{
             $v.get_time_stamp() / 1000
         }}; // Nanoseconds to microseconds.
+        (Date, $v: expr) => {{
+            $v.get_days_since_epoch()
+        }};
+        (Uuid, $v: expr) => {{
+            &$v.as_bytes()[..]
+        }};
         (String, $v: expr) => {{
             $v.as_str()
         }};
@@ -239,6 +300,31 @@ pub fn append_value(b: &mut dyn ArrayBuilder, c: &ColumnType, v: &TableValue) {
     match_column_type!(c, append)
 }
 
+fn append_array_value(b: &mut dyn ArrayBuilder, element_type: &ColumnType, v: &TableValue) {
+    macro_rules! append_list {
+        ($builder: tt) => {{
+            let b = b
+                .as_any_mut()
+                .downcast_mut::<ListBuilder<$builder>>()
+                .unwrap();
+            match v {
+                TableValue::Null => b.append(false).unwrap(),
+                TableValue::Array(items) => {
+                    for item in items {
+                        append_value(b.values(), element_type, item);
+                    }
+                    b.append(true).unwrap();
+                }
+                other => panic!(
+                    "unexpected value {:?} for array of {:?}",
+                    other, element_type
+                ),
+            }
+        }};
+    }
+    match_array_element_type!(element_type, append_list)
+}
+
 pub fn rows_to_columns(cols: &[Column], rows: &[Row]) -> Vec<ArrayRef> {
     let mut builders = create_array_builders(&cols);
     for r in rows {
@@ -2,15 +2,15 @@ use crate::util::decimal::{Decimal, Decimal96};
 use crate::util::int96::Int96;
 
 use datafusion::arrow::array::{
-    Array, ArrayRef, BinaryArray, BooleanArray, Float64Array, Int64Array, Int64Decimal0Array,
-    Int64Decimal10Array, Int64Decimal1Array, Int64Decimal2Array, Int64Decimal3Array,
-    Int64Decimal4Array, Int64Decimal5Array, Int96Array, Int96Decimal0Array, Int96Decimal10Array,
-    Int96Decimal1Array, Int96Decimal2Array, Int96Decimal3Array, Int96Decimal4Array,
-    Int96Decimal5Array, StringArray, TimestampMicrosecondArray,
+    Array, ArrayRef, BinaryArray, BooleanArray, Date32Array, FixedSizeBinaryArray, Float64Array,
+    Int64Array, Int64Decimal0Array, Int64Decimal10Array, Int64Decimal1Array, Int64Decimal2Array,
+    Int64Decimal3Array, Int64Decimal4Array, Int64Decimal5Array, Int96Array, Int96Decimal0Array,
+    Int96Decimal10Array, Int96Decimal1Array, Int96Decimal2Array, Int96Decimal3Array,
+    Int96Decimal4Array, Int96Decimal5Array, ListArray, StringArray, TimestampMicrosecondArray,
 };
 use datafusion::arrow::datatypes::{DataType, TimeUnit};
 
-use chrono::{SecondsFormat, TimeZone, Utc};
+use chrono::{Duration, NaiveDate, SecondsFormat, TimeZone, Utc};
 use datafusion::cube_ext::ordfloat::OrdF64;
 use deepsize::{Context, DeepSizeOf};
 use itertools::Itertools;
@@ -18,10 +18,13 @@ use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::fmt;
 use std::fmt::{Debug, Formatter};
+use uuid::Uuid;
 
+pub mod bloom_filter;
 pub mod data;
 pub mod parquet;
 pub mod redistribute;
+pub mod statistics;
 
 #[derive(Clone, Serialize, Deserialize, Eq, PartialEq, Debug, Hash)]
 pub enum TableValue {
@@ -35,6 +38,9 @@ pub enum TableValue {
     Bytes(Vec<u8>),
     Timestamp(TimestampValue),
     Boolean(bool),
+    Date(DateValue),
+    Uuid(Uuid),
+    Array(Vec<TableValue>),
 }
 
 impl DeepSizeOf for TableValue {
@@ -50,11 +56,35 @@ impl DeepSizeOf for TableValue {
             TableValue::Bytes(v) => v.deep_size_of_children(context),
             TableValue::Timestamp(_) => 0,
             TableValue::Boolean(_) => 0,
+            TableValue::Date(_) => 0,
+            TableValue::Uuid(_) => 0,
+            TableValue::Array(v) => v.deep_size_of_children(context),
         }
     }
 }
 
 impl TableValue {
+    /// Renders array elements as a JSON array, the same format arrays are imported from.
+    pub fn array_to_json_string(items: &[TableValue]) -> String {
+        fn to_json(v: &TableValue) -> serde_json::Value {
+            match v {
+                TableValue::Null => serde_json::Value::Null,
+                TableValue::String(s) => serde_json::Value::String(s.clone()),
+                TableValue::Int(i) => serde_json::Value::from(*i),
+                TableValue::Float(f) => serde_json::Value::from(f.0),
+                TableValue::Boolean(b) => serde_json::Value::Bool(*b),
+                TableValue::Timestamp(t) => serde_json::Value::String(t.to_string()),
+                TableValue::Date(d) => serde_json::Value::String(d.to_string()),
+                TableValue::Uuid(u) => serde_json::Value::String(u.to_string()),
+                TableValue::Array(items) => {
+                    serde_json::Value::Array(items.iter().map(to_json).collect())
+                }
+                other => panic!("unexpected array element {:?}", other),
+            }
+        }
+        serde_json::Value::Array(items.iter().map(to_json).collect()).to_string()
+    }
+
     pub fn from_columns(a: &[ArrayRef], row: usize) -> Vec<TableValue> {
         a.iter()
             .map(|c| TableValue::from_array(c.as_ref(), row))
@@ -192,6 +222,26 @@ impl TableValue {
                     .unwrap()
                     .value(row),
             ),
+            DataType::Date32 => TableValue::Date(DateValue::new(
+                a.as_any().downcast_ref::<Date32Array>().unwrap().value(row),
+            )),
+            DataType::FixedSizeBinary(16) => TableValue::Uuid(
+                Uuid::from_slice(
+                    a.as_any()
+                        .downcast_ref::<FixedSizeBinaryArray>()
+                        .unwrap()
+                        .value(row),
+                )
+                .unwrap(),
+            ),
+            DataType::List(_) => {
+                let items = a.as_any().downcast_ref::<ListArray>().unwrap().value(row);
+                TableValue::Array(
+                    (0..items.len())
+                        .map(|i| TableValue::from_array(items.as_ref(), i))
+                        .collect(),
+                )
+            }
             other => panic!(
                 "unexpected array type when converting to TableValue: {:?}",
                 other
@@ -234,6 +284,44 @@ impl ToString for TimestampValue {
     }
 }
 
+#[derive(Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct DateValue {
+    days_since_epoch: i32,
+}
+
+impl DateValue {
+    pub fn new(days_since_epoch: i32) -> DateValue {
+        DateValue { days_since_epoch }
+    }
+
+    pub fn from_date(date: NaiveDate) -> DateValue {
+        DateValue::new((date - NaiveDate::from_ymd(1970, 1, 1)).num_days() as i32)
+    }
+
+    pub fn get_days_since_epoch(&self) -> i32 {
+        self.days_since_epoch
+    }
+
+    pub fn to_date(&self) -> NaiveDate {
+        NaiveDate::from_ymd(1970, 1, 1) + Duration::days(self.days_since_epoch as i64)
+    }
+}
+
+impl Debug for DateValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DateValue")
+            .field("days_since_epoch", &self.days_since_epoch)
+            .field("str", &self.to_string())
+            .finish()
+    }
+}
+
+impl ToString for DateValue {
+    fn to_string(&self) -> String {
+        self.to_date().format("%Y-%m-%d").to_string()
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq, Hash, DeepSizeOf)]
 pub struct Row {
     values: Vec<TableValue>,
@@ -269,13 +357,21 @@ pub fn cmp_same_types(l: &TableValue, r: &TableValue) -> Ordering {
         (TableValue::Bytes(a), TableValue::Bytes(b)) => a.cmp(b),
         (TableValue::Timestamp(a), TableValue::Timestamp(b)) => a.cmp(b),
         (TableValue::Boolean(a), TableValue::Boolean(b)) => a.cmp(b),
+        (TableValue::Date(a), TableValue::Date(b)) => a.cmp(b),
+        (TableValue::Uuid(a), TableValue::Uuid(b)) => a.cmp(b),
+        (TableValue::Array(a), TableValue::Array(b)) => a
+            .iter()
+            .zip(b.iter())
+            .map(|(a, b)| cmp_same_types(a, b))
+            .find(|o| *o != Ordering::Equal)
+            .unwrap_or_else(|| a.len().cmp(&b.len())),
         (a, b) => panic!("Can't compare {:?} to {:?}", a, b),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::table::{TableValue, TimestampValue};
+    use crate::table::{DateValue, TableValue, TimestampValue};
     use crate::util::decimal::Decimal;
     use deepsize::DeepSizeOf;
     use serde::{Deserialize, Serialize};
@@ -291,6 +387,9 @@ mod tests {
             TableValue::Bytes(vec![1, 2, 3]),
             TableValue::Timestamp(TimestampValue::new(123)),
             TableValue::Boolean(false),
+            TableValue::Date(DateValue::new(19000)),
+            TableValue::Uuid(uuid::Uuid::from_u128(0x6ba7b8109dad11d180b400c04fd430c8)),
+            TableValue::Array(vec![TableValue::Int(1), TableValue::Null]),
         ] {
             let b = bincode::serialize(v).expect(&format!("could not serialize {:?}", v));
             let v2: TableValue =
@@ -307,6 +406,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn date_value() {
+        assert_eq!(DateValue::new(0).to_string(), "1970-01-01");
+        assert_eq!(DateValue::new(-1).to_string(), "1969-12-31");
+        let d = chrono::NaiveDate::from_ymd(2022, 2, 28);
+        assert_eq!(DateValue::from_date(d).get_days_since_epoch(), 19051);
+        assert_eq!(DateValue::from_date(d).to_date(), d);
+    }
+
     #[test]
     fn table_value_deep_size_of() {
         for (v, expected_size) in [
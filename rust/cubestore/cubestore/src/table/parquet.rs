@@ -1,15 +1,20 @@
 use crate::config::injection::DIService;
-use crate::metastore::table::Table;
+use crate::metastore::table::{ParquetCompression, Table};
 use crate::metastore::{IdRow, Index};
+use crate::table::bloom_filter::BloomFilterBuilder;
 use crate::CubeError;
 use async_trait::async_trait;
 use datafusion::arrow::array::ArrayRef;
 use datafusion::arrow::datatypes::Schema;
 use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::DataFusionError;
 use datafusion::parquet::arrow::{ArrowReader, ArrowWriter, ParquetFileArrowReader};
+use datafusion::parquet::basic::Compression;
+use datafusion::parquet::file::metadata::ParquetMetaData;
 use datafusion::parquet::file::properties::{
     WriterProperties, WriterPropertiesBuilder, WriterVersion,
 };
+use datafusion::parquet::file::reader::{FileReader, SerializedFileReader};
 use datafusion::physical_plan::parquet::{MetadataCacheFactory, ParquetMetadataCache};
 use std::fs::File;
 use std::sync::Arc;
@@ -130,17 +135,47 @@ impl ParquetTableStore {
     }
 
     pub async fn writer_props(&self, table: &IdRow<Table>) -> Result<WriterProperties, CubeError> {
+        let mut builder = WriterProperties::builder()
+            .set_max_row_group_size(self.row_group_size)
+            .set_writer_version(WriterVersion::PARQUET_2_0);
+        if let Some(options) = table.get_row().parquet_options() {
+            if let Some(compression) = options.compression {
+                builder = builder.set_compression(match compression {
+                    ParquetCompression::Uncompressed => Compression::UNCOMPRESSED,
+                    ParquetCompression::Snappy => Compression::SNAPPY,
+                    ParquetCompression::Gzip => Compression::GZIP,
+                    ParquetCompression::Lz4 => Compression::LZ4,
+                    ParquetCompression::Zstd => Compression::ZSTD,
+                    ParquetCompression::Brotli => Compression::BROTLI,
+                });
+            }
+            if let Some(dictionary) = options.dictionary {
+                builder = builder.set_dictionary_enabled(dictionary);
+            }
+        }
         self.metadata_cache_factory
-            .build_writer_props(
-                table,
-                WriterProperties::builder()
-                    .set_max_row_group_size(self.row_group_size)
-                    .set_writer_version(WriterVersion::PARQUET_2_0),
-            )
+            .build_writer_props(table, builder)
             .await
             .map_err(CubeError::from)
     }
 
+    /// Bloom filters for the table columns which are present in this index.
+    pub fn bloom_filter_builder(&self, table: &IdRow<Table>) -> BloomFilterBuilder {
+        let columns = match table.get_row().parquet_options() {
+            Some(options) => &options.bloom_filter_columns,
+            None => return BloomFilterBuilder::new(Vec::new()),
+        };
+        BloomFilterBuilder::new(
+            self.table
+                .columns()
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| columns.contains(c.get_name()))
+                .map(|(i, c)| (c.get_name().clone(), i))
+                .collect(),
+        )
+    }
+
     pub async fn write_data(
         &self,
         dest_file: &str,
@@ -171,17 +206,76 @@ pub fn arrow_schema(i: &Index) -> Schema {
     Schema::new(i.columns().iter().map(|c| c.into()).collect())
 }
 
+/// Numbers of rows in row groups of a written parquet file.
+pub fn row_group_sizes(path: &str) -> Result<Vec<usize>, CubeError> {
+    let reader = SerializedFileReader::new(File::open(path)?)?;
+    Ok(reader
+        .metadata()
+        .row_groups()
+        .iter()
+        .map(|r| r.num_rows() as usize)
+        .collect())
+}
+
+/// Hides row groups which are known to have no matching rows from the parquet reader.
+#[derive(Debug)]
+pub struct SkipRowGroupsMetadataCache {
+    cache: Arc<dyn ParquetMetadataCache>,
+    /// Whether each row group of the file should be read.
+    read_row_groups: Vec<bool>,
+}
+
+impl SkipRowGroupsMetadataCache {
+    pub fn new(
+        cache: Arc<dyn ParquetMetadataCache>,
+        read_row_groups: Vec<bool>,
+    ) -> Arc<SkipRowGroupsMetadataCache> {
+        Arc::new(SkipRowGroupsMetadataCache {
+            cache,
+            read_row_groups,
+        })
+    }
+}
+
+impl ParquetMetadataCache for SkipRowGroupsMetadataCache {
+    fn metadata(&self, key: &str, file: File) -> datafusion::error::Result<Arc<ParquetMetaData>> {
+        let metadata = self.cache.metadata(key, file)?;
+        if metadata.num_row_groups() != self.read_row_groups.len() {
+            return Err(DataFusionError::Internal(format!(
+                "File {} has {} row groups, but {} were expected",
+                key,
+                metadata.num_row_groups(),
+                self.read_row_groups.len()
+            )));
+        }
+        let row_groups = metadata
+            .row_groups()
+            .iter()
+            .zip(self.read_row_groups.iter())
+            .filter(|(_, read)| **read)
+            .map(|(r, _)| r.clone())
+            .collect();
+        Ok(Arc::new(ParquetMetaData::new(
+            metadata.file_metadata().clone(),
+            row_groups,
+        )))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     extern crate test;
 
     use crate::assert_eq_columns;
-    use crate::metastore::table::Table;
+    use crate::metastore::table::{ParquetOptions, Table};
     use crate::metastore::{Column, ColumnType, IdRow, Index};
+    use crate::queryplanner::partition_filter::BloomFilterPredicate;
     use crate::store::{compaction, ROW_GROUP_SIZE};
+    use crate::table::bloom_filter::{bloom_filter_file_name, RowGroupBloomFilters};
     use crate::table::data::{cmp_row_key_heap, concat_record_batches, rows_to_columns, to_stream};
     use crate::table::parquet::{
-        arrow_schema, CubestoreMetadataCacheFactoryImpl, ParquetTableStore,
+        arrow_schema, row_group_sizes, CubestoreMetadataCacheFactoryImpl, ParquetTableStore,
+        SkipRowGroupsMetadataCache,
     };
     use crate::table::{Row, TableValue};
     use crate::util::decimal::Decimal;
@@ -190,11 +284,15 @@ mod tests {
         TimestampMicrosecondArray,
     };
     use datafusion::arrow::record_batch::RecordBatch;
+    use datafusion::logical_plan::{col, lit};
     use datafusion::parquet::data_type::DataType;
     use datafusion::parquet::file::reader::FileReader;
     use datafusion::parquet::file::reader::SerializedFileReader;
     use datafusion::parquet::file::statistics::{Statistics, TypedStatistics};
-    use datafusion::physical_plan::parquet::BasicMetadataCacheFactory;
+    use datafusion::physical_plan::collect;
+    use datafusion::physical_plan::parquet::{
+        BasicMetadataCacheFactory, NoopParquetMetadataCache, ParquetExec,
+    };
     use itertools::Itertools;
     use pretty_assertions::assert_eq;
     use std::sync::Arc;
@@ -411,7 +509,7 @@ mod tests {
         let to_split_cols = rows_to_columns(&store.table.columns(), &to_split);
         let schema = Arc::new(arrow_schema(&store.table));
         let to_split_batch = RecordBatch::try_new(schema.clone(), to_split_cols.clone()).unwrap();
-        let count_min = compaction::write_to_files(
+        let (count_min, _, _) = compaction::write_to_files(
             to_stream(to_split_batch).await,
             to_split.len(),
             ParquetTableStore::new(
@@ -465,6 +563,95 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn bloom_filters_skip_row_groups() {
+        let index = Index::try_new(
+            "foo".to_string(),
+            1,
+            vec![
+                Column::new("id".to_string(), ColumnType::Int, 0),
+                Column::new("name".to_string(), ColumnType::String, 1),
+            ],
+            1,
+            None,
+            None,
+            Index::index_type_default(),
+        )
+        .unwrap();
+        let table = dummy_table_row(index.table_id(), index.get_name());
+        let table = IdRow::new(
+            table.get_id(),
+            table
+                .get_row()
+                .clone()
+                .with_parquet_options(Some(ParquetOptions {
+                    bloom_filter_columns: vec!["id".to_string()],
+                    ..ParquetOptions::default()
+                })),
+        );
+
+        let rows = (0..40)
+            .map(|i| {
+                Row::new(vec![
+                    TableValue::Int(i),
+                    TableValue::String(format!("Foo {}", i)),
+                ])
+            })
+            .collect_vec();
+        let columns = rows_to_columns(&index.columns(), &rows);
+        let batch = RecordBatch::try_new(Arc::new(arrow_schema(&index)), columns).unwrap();
+
+        let file = NamedTempFile::new().unwrap();
+        let file_name = file.path().to_str().unwrap();
+        let store = ParquetTableStore::new(
+            index,
+            10,
+            CubestoreMetadataCacheFactoryImpl::new(Arc::new(BasicMetadataCacheFactory::new())),
+        );
+        let (_, has_bloom_filters, _) = compaction::write_to_files(
+            to_stream(batch).await,
+            rows.len(),
+            store,
+            &table,
+            vec![file_name.to_string()],
+        )
+        .await
+        .unwrap();
+        assert_eq!(has_bloom_filters, vec![true]);
+        assert_eq!(row_group_sizes(file_name).unwrap(), vec![10, 10, 10, 10]);
+
+        let bloom_filter_file = bloom_filter_file_name(file_name);
+        let bloom_filters = RowGroupBloomFilters::read(&bloom_filter_file).unwrap();
+        std::fs::remove_file(&bloom_filter_file).unwrap();
+        let predicate =
+            BloomFilterPredicate::extract(&["id".to_string()], &[col("id").eq(lit(25i64))]);
+        let read_row_groups = bloom_filters
+            .row_groups
+            .iter()
+            .map(|filters| predicate.can_match(filters))
+            .collect_vec();
+        assert_eq!(read_row_groups, vec![false, false, true, false]);
+
+        let exec = ParquetExec::try_from_path_with_cache(
+            file_name,
+            None,
+            None,
+            ROW_GROUP_SIZE,
+            1,
+            None,
+            SkipRowGroupsMetadataCache::new(NoopParquetMetadataCache::new(), read_row_groups),
+        )
+        .unwrap();
+        let read = concat_record_batches(&collect(Arc::new(exec)).await.unwrap());
+        assert_eq!(read.num_rows(), 10);
+        let ids = read
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!(ids.values(), (20..30).collect_vec().as_slice());
+    }
+
     #[tokio::test]
     async fn failed_rle_run_bools() {
         const NUM_ROWS: usize = ROW_GROUP_SIZE;
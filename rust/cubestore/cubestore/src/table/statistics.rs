@@ -0,0 +1,120 @@
+use crate::table::data::cmp_partition_column_same_type;
+use crate::table::TableValue;
+use datafusion::arrow::array::{Array, ArrayRef};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Min/max values and null counts of every index column of a partition or chunk file.
+/// Unlike partition min/max rows, these are tracked for each column separately.
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq, Hash)]
+pub struct ColumnStatistics {
+    pub row_count: u64,
+    /// Smallest non-null value of each column, [TableValue::Null] if a column has only nulls.
+    pub min: Vec<TableValue>,
+    /// Largest non-null value of each column, [TableValue::Null] if a column has only nulls.
+    pub max: Vec<TableValue>,
+    pub null_counts: Vec<u64>,
+}
+
+impl ColumnStatistics {
+    pub fn from_data(columns: &[ArrayRef]) -> Option<ColumnStatistics> {
+        let mut builder = ColumnStatisticsBuilder::new(columns.len());
+        builder.add_batch(columns);
+        builder.finish()
+    }
+}
+
+/// Collects column statistics while the data is written.
+#[derive(Clone)]
+pub struct ColumnStatisticsBuilder {
+    row_count: u64,
+    min: Vec<TableValue>,
+    max: Vec<TableValue>,
+    null_counts: Vec<u64>,
+}
+
+impl ColumnStatisticsBuilder {
+    pub fn new(num_columns: usize) -> Self {
+        Self {
+            row_count: 0,
+            min: vec![TableValue::Null; num_columns],
+            max: vec![TableValue::Null; num_columns],
+            null_counts: vec![0; num_columns],
+        }
+    }
+
+    pub fn add_batch(&mut self, columns: &[ArrayRef]) {
+        assert_eq!(columns.len(), self.min.len());
+        if columns.is_empty() {
+            return;
+        }
+        self.row_count += columns[0].len() as u64;
+        for (i, c) in columns.iter().enumerate() {
+            let c = c.as_ref();
+            self.null_counts[i] += c.null_count() as u64;
+            for r in 0..c.len() {
+                if !c.is_valid(r) {
+                    continue;
+                }
+                // Null compares less than any value, so the first valid value always replaces it.
+                if self.min[i] == TableValue::Null
+                    || cmp_partition_column_same_type(&self.min[i], c, r) == Ordering::Greater
+                {
+                    self.min[i] = TableValue::from_array(c, r);
+                }
+                if cmp_partition_column_same_type(&self.max[i], c, r) == Ordering::Less {
+                    self.max[i] = TableValue::from_array(c, r);
+                }
+            }
+        }
+    }
+
+    pub fn finish(self) -> Option<ColumnStatistics> {
+        if self.row_count == 0 {
+            return None;
+        }
+        Some(ColumnStatistics {
+            row_count: self.row_count,
+            min: self.min,
+            max: self.max,
+            null_counts: self.null_counts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::arrow::array::{Int64Array, StringArray};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_column_statistics() {
+        let mut builder = ColumnStatisticsBuilder::new(2);
+        assert_eq!(builder.clone().finish(), None);
+
+        builder.add_batch(&[
+            Arc::new(Int64Array::from(vec![Some(5), None, Some(3)])),
+            Arc::new(StringArray::from(vec![None, None, None])),
+        ]);
+        builder.add_batch(&[
+            Arc::new(Int64Array::from(vec![Some(10), Some(-1)])),
+            Arc::new(StringArray::from(vec![Some("foo"), None])),
+        ]);
+        assert_eq!(
+            builder.finish(),
+            Some(ColumnStatistics {
+                row_count: 5,
+                min: vec![TableValue::Int(-1), TableValue::String("foo".to_string())],
+                max: vec![TableValue::Int(10), TableValue::String("foo".to_string())],
+                null_counts: vec![1, 4],
+            })
+        );
+
+        let stats =
+            ColumnStatistics::from_data(&[Arc::new(StringArray::from(vec![None, None]))]).unwrap();
+        assert_eq!(stats.min, vec![TableValue::Null]);
+        assert_eq!(stats.max, vec![TableValue::Null]);
+        assert_eq!(stats.null_counts, vec![2]);
+    }
+}